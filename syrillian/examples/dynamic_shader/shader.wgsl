@@ -1,6 +1,6 @@
 fn tri(x_og: f32, y_og: f32) -> bool {
-  let x = x_og * (cos(system.time) + 2) * 0.75;
-  let y = y_og * (cos(system.time) + 2) * 0.75;
+  let x = x_og * (cos(globals.time) + 2) * 0.75;
+  let y = y_og * (cos(globals.time) + 2) * 0.75;
 
   if x > 0.25 || x < -0.25 || y > 0.25 || y < -0.25 {
     return false;
@@ -11,7 +11,7 @@ fn tri(x_og: f32, y_og: f32) -> bool {
 
 @fragment
 fn fs_main(in: FInput) -> @location(0) vec4<f32> {
-  let time = system.time;
+  let time = globals.time;
   let slices = 20.;
   let uv_x = in.uv.x;
   let uv_y = in.uv.y;
@@ -7,8 +7,8 @@ fn circle(ranged: f32) -> vec4<f32> {
 }
 
 fn tri(x_og: f32, y_og: f32) -> bool {
-  let x = x_og * cos(system.time);
-  let y = y_og * cos(system.time);
+  let x = x_og * cos(globals.time);
+  let y = y_og * cos(globals.time);
 
   if x > 0.25 || x < -0.25 || y > 0.25 || y < -0.25 {
     return false;
@@ -27,8 +27,8 @@ fn fs_main(in: FInput) -> @location(0) vec4<f32> {
   let dist = length(in.position);
   var color = circle(dist);
 
-  let tx = sin(system.time) + y;
-  let ty = cos(system.time) + x;
+  let tx = sin(globals.time) + y;
+  let ty = cos(globals.time) + x;
   let meow = (x * tx + y * ty) % 0.3;
   let meow2 = (x * ty + y * tx) % 0.3;
 
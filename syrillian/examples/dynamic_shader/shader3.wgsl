@@ -1,6 +1,6 @@
 @fragment
 fn fs_main(in: FInput) -> @location(0) vec4<f32> {
-  let time = system.time;
+  let time = globals.time;
 
   let uv = in.uv - 0.5;
 
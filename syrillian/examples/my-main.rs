@@ -7,7 +7,7 @@
 use gilrs::Button;
 use kira::effect::reverb::ReverbBuilder;
 use kira::track::SpatialTrackBuilder;
-use nalgebra::UnitQuaternion;
+use nalgebra::{UnitQuaternion, Vector3};
 use rapier3d::parry::query::Ray;
 use rapier3d::prelude::QueryFilter;
 use std::error::Error;
@@ -53,6 +53,7 @@ struct MyMain {
     player: GameObjectRef,
     player_rb: CRef<RigidBodyComponent>,
     picked_up: Option<GameObjectRef>,
+    picked_up_velocity: Vector3<f32>,
     text3d: GameObjectRef,
     light1: CRef<SpotLightComponent>,
     light2: CRef<SpotLightComponent>,
@@ -70,6 +71,7 @@ impl Default for MyMain {
                 player: GameObjectRef::null(),
                 player_rb: CRef::null(),
                 picked_up: None,
+                picked_up_velocity: Vector3::zeros(),
                 text3d: GameObjectRef::null(),
                 light1: CRef::null(),
                 light2: CRef::null(),
@@ -418,6 +420,7 @@ impl MyMain {
                     if let Some(obj_ref) = world.get_object_ref(obj) {
                         info!("Intersection after {dt}s, against: {}", obj_ref.name);
                         self.picked_up = Some(obj_ref);
+                        self.picked_up_velocity = Vector3::zeros();
                     }
                 }
             }
@@ -435,17 +438,13 @@ impl MyMain {
             let scale = obj.transform.scale();
             let target_position = camera_obj.transform.position()
                 + camera_obj.transform.forward() * scale.magnitude().max(1.) * 2.;
-            let position = obj.transform.position();
             let target_rotation = UnitQuaternion::face_towards(
                 &camera_obj.transform.up(),
                 &camera_obj.transform.forward(),
             );
-            let rotation = obj.transform.rotation();
-            let unit_quat =
-                UnitQuaternion::from_quaternion(rotation.lerp(&target_rotation, 1.03 * delta));
             obj.transform
-                .set_position_vec(position.lerp(&target_position, 10.03 * delta));
-            obj.transform.set_rotation(unit_quat);
+                .smooth_damp(target_position, &mut self.picked_up_velocity, 0.1, delta);
+            obj.transform.rotate_toward(target_rotation, 2.0 * delta);
             if let Some(mut rb) = obj.get_component::<RigidBodyComponent>() {
                 rb.set_kinematic(true);
             }
@@ -460,14 +459,14 @@ impl MyMain {
             self.sound_cube2_emitter.toggle_looping();
         }
         if world.input.is_key_down(KeyCode::KeyP) {
-            if world.input.is_key_pressed(KeyCode::ShiftLeft) {
+            if world.input.shift() {
                 self.sound_cube_emitter.stop();
             } else {
                 self.sound_cube_emitter.play();
             }
         }
         if world.input.is_key_down(KeyCode::KeyO) {
-            if world.input.is_key_pressed(KeyCode::ShiftLeft) {
+            if world.input.shift() {
                 self.sound_cube2_emitter.stop();
             } else {
                 self.sound_cube2_emitter.play();
@@ -494,9 +493,7 @@ impl MyMain {
 
         let pos = camera_obj.transform.position() + camera_obj.transform.forward() * 3.;
         world
-            .spawn(&CubePrefab {
-                material: HMaterial::DEFAULT,
-            })
+            .spawn(&CubePrefab::new(HMaterial::DEFAULT))
             .at_vec(pos)
             .build_component::<Collider3D>()
             .build_component::<RigidBodyComponent>();
@@ -18,6 +18,9 @@ pub struct AssetStore {
     pub bgls: Arc<Store<BGL>>,
     pub fonts: Arc<Store<Font>>,
     pub sounds: Arc<Store<Sound>>,
+    pub compute: Arc<Store<ComputeShader>>,
+    /// Snippets that the shaders of this store can `#include`
+    pub shader_includes: Arc<ShaderIncludes>,
 }
 
 impl AssetStore {
@@ -30,6 +33,8 @@ impl AssetStore {
             bgls: Arc::new(Store::populated()),
             fonts: Arc::new(Store::populated()),
             sounds: Arc::new(Store::empty()),
+            compute: Arc::new(Store::empty()),
+            shader_includes: Arc::new(ShaderIncludes::new()),
         })
     }
 }
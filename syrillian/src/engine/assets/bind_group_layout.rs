@@ -20,8 +20,9 @@ impl H<BGL> {
     pub(super) const SHADOW_ID: u32 = 4;
     pub(super) const POST_PROCESS_ID: u32 = 5;
     pub(super) const EMPTY_ID: u32 = 6;
+    pub(super) const DEPTH_ID: u32 = 7;
 
-    const MAX_BUILTIN_ID: u32 = 6;
+    const MAX_BUILTIN_ID: u32 = 7;
 
     pub const RENDER: HBGL = HBGL::new(Self::RENDER_ID);
     pub const MODEL: HBGL = HBGL::new(Self::MODEL_ID);
@@ -30,6 +31,7 @@ impl H<BGL> {
     pub const SHADOW: HBGL = HBGL::new(Self::SHADOW_ID);
     pub const POST_PROCESS: HBGL = HBGL::new(Self::POST_PROCESS_ID);
     pub const EMPTY: HBGL = HBGL::new(Self::EMPTY_ID);
+    pub const DEPTH: HBGL = HBGL::new(Self::DEPTH_ID);
 }
 
 impl StoreType for BGL {
@@ -45,6 +47,7 @@ impl StoreType for BGL {
             HBGL::LIGHT_ID => HandleName::Static("Light Bind Group Layout"),
             HBGL::SHADOW_ID => HandleName::Static("Shadow Bind Group Layout"),
             HBGL::POST_PROCESS_ID => HandleName::Static("Post Process Bind Group Layout"),
+            HBGL::DEPTH_ID => HandleName::Static("Depth Bind Group Layout"),
             _ => HandleName::Id(handle),
         }
     }
@@ -54,7 +57,8 @@ impl StoreType for BGL {
     }
 }
 
-const TWO_DEFAULT_ENTRIES: [BindGroupLayoutEntry; 2] = [
+/// Model transform and the bone palette, which is only read while skinning vertices
+const MODEL_ENTRIES: [BindGroupLayoutEntry; 2] = [
     BindGroupLayoutEntry {
         binding: 0,
         visibility: ShaderStages::VERTEX_FRAGMENT,
@@ -67,6 +71,40 @@ const TWO_DEFAULT_ENTRIES: [BindGroupLayoutEntry; 2] = [
     },
     BindGroupLayoutEntry {
         binding: 1,
+        visibility: ShaderStages::VERTEX,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Storage { read_only: true },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+];
+
+/// Camera, system data and shader globals
+const RENDER_ENTRIES: [BindGroupLayoutEntry; 3] = [
+    BindGroupLayoutEntry {
+        binding: 0,
+        visibility: ShaderStages::VERTEX_FRAGMENT,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 1,
+        visibility: ShaderStages::VERTEX_FRAGMENT,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 2,
         visibility: ShaderStages::VERTEX_FRAGMENT,
         ty: BindingType::Buffer {
             ty: BufferBindingType::Uniform,
@@ -77,7 +115,7 @@ const TWO_DEFAULT_ENTRIES: [BindGroupLayoutEntry; 2] = [
     },
 ];
 
-const MATERIAL_ENTRIES: [BindGroupLayoutEntry; 7] = [
+const MATERIAL_ENTRIES: [BindGroupLayoutEntry; 8] = [
     BindGroupLayoutEntry {
         binding: 0,
         visibility: ShaderStages::FRAGMENT,
@@ -136,9 +174,19 @@ const MATERIAL_ENTRIES: [BindGroupLayoutEntry; 7] = [
         ty: BindingType::Sampler(SamplerBindingType::Filtering),
         count: None,
     },
+    BindGroupLayoutEntry {
+        binding: 7,
+        visibility: ShaderStages::VERTEX_FRAGMENT,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
 ];
 
-const LIGHT_ENTRIES: [BindGroupLayoutEntry; 2] = [
+const LIGHT_ENTRIES: [BindGroupLayoutEntry; 5] = [
     BindGroupLayoutEntry {
         binding: 0,
         visibility: ShaderStages::VERTEX_FRAGMENT,
@@ -159,9 +207,39 @@ const LIGHT_ENTRIES: [BindGroupLayoutEntry; 2] = [
         },
         count: None,
     },
+    BindGroupLayoutEntry {
+        binding: 2,
+        visibility: ShaderStages::FRAGMENT,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 3,
+        visibility: ShaderStages::FRAGMENT,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Storage { read_only: true },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 4,
+        visibility: ShaderStages::FRAGMENT,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Storage { read_only: true },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
 ];
 
-const SHADOW_ENTRIES: [BindGroupLayoutEntry; 2] = [
+const SHADOW_ENTRIES: [BindGroupLayoutEntry; 3] = [
     BindGroupLayoutEntry {
         binding: 0,
         visibility: ShaderStages::FRAGMENT,
@@ -178,9 +256,19 @@ const SHADOW_ENTRIES: [BindGroupLayoutEntry; 2] = [
         ty: BindingType::Sampler(SamplerBindingType::Comparison),
         count: None,
     },
+    BindGroupLayoutEntry {
+        binding: 2,
+        visibility: ShaderStages::FRAGMENT,
+        ty: BindingType::Texture {
+            sample_type: TextureSampleType::Float { filterable: true },
+            view_dimension: TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    },
 ];
 
-const PP_ENTRIES: [BindGroupLayoutEntry; 3] = [
+const PP_ENTRIES: [BindGroupLayoutEntry; 7] = [
     BindGroupLayoutEntry {
         binding: 0,
         visibility: ShaderStages::FRAGMENT,
@@ -207,8 +295,59 @@ const PP_ENTRIES: [BindGroupLayoutEntry; 3] = [
         },
         count: None,
     },
+    BindGroupLayoutEntry {
+        binding: 3,
+        visibility: ShaderStages::FRAGMENT,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 4,
+        visibility: ShaderStages::FRAGMENT,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 5,
+        visibility: ShaderStages::FRAGMENT,
+        ty: BindingType::Texture {
+            sample_type: TextureSampleType::Float { filterable: true },
+            view_dimension: TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 6,
+        visibility: ShaderStages::FRAGMENT,
+        ty: BindingType::Texture {
+            sample_type: TextureSampleType::Float { filterable: true },
+            view_dimension: TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    },
 ];
 
+const DEPTH_ENTRIES: [BindGroupLayoutEntry; 1] = [BindGroupLayoutEntry {
+    binding: 0,
+    visibility: ShaderStages::FRAGMENT,
+    ty: BindingType::Texture {
+        sample_type: TextureSampleType::Depth,
+        view_dimension: TextureViewDimension::D2,
+        multisampled: false,
+    },
+    count: None,
+}];
+
 impl StoreDefaults for BGL {
     fn populate(store: &mut Store<Self>) {
         store_add_checked!(
@@ -216,7 +355,7 @@ impl StoreDefaults for BGL {
             HBGL::RENDER_ID,
             BGL {
                 label: HBGL::RENDER.ident(),
-                entries: TWO_DEFAULT_ENTRIES.to_vec()
+                entries: RENDER_ENTRIES.to_vec()
             }
         );
 
@@ -225,7 +364,7 @@ impl StoreDefaults for BGL {
             HBGL::MODEL_ID,
             BGL {
                 label: HBGL::MODEL.ident(),
-                entries: TWO_DEFAULT_ENTRIES.to_vec()
+                entries: MODEL_ENTRIES.to_vec()
             }
         );
 
@@ -273,5 +412,14 @@ impl StoreDefaults for BGL {
                 entries: [].to_vec()
             }
         );
+
+        store_add_checked!(
+            store,
+            HBGL::DEPTH_ID,
+            BGL {
+                label: HBGL::DEPTH.ident(),
+                entries: DEPTH_ENTRIES.to_vec()
+            }
+        );
     }
 }
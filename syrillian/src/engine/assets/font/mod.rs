@@ -1,14 +1,18 @@
 use crate::assets::{H, HFont, HandleName, Store, StoreDefaults, StoreType, StoreTypeFallback};
 use crate::store_add_checked;
+use nalgebra::Vector2;
 use std::convert::Into;
 use std::sync::Arc;
 use tracing::trace;
+use ttf_parser::Face;
 
 #[derive(Debug, Clone)]
 pub struct Font {
     pub(crate) family_name: String,
     pub(crate) font_bytes: Arc<Vec<u8>>,
     pub(crate) atlas_em_px: u32,
+    pub(crate) fallbacks: Vec<HFont>,
+    pub(crate) msdf_range: f32,
 }
 
 impl StoreType for Font {
@@ -53,6 +57,7 @@ impl<T: StoreTypeFallback> Default for H<T> {
 }
 
 pub const DEFAULT_ATLAS_SIZE: u32 = 1024;
+pub const DEFAULT_MSDF_RANGE: f32 = 4.0;
 
 impl Font {
     /// The default atlas glyph size is 100 pixels
@@ -64,8 +69,66 @@ impl Font {
             family_name,
             font_bytes: bytes,
             atlas_em_px,
+            fallbacks: Vec::new(),
+            msdf_range: DEFAULT_MSDF_RANGE,
         })
     }
+
+    pub fn msdf_range(&self) -> f32 {
+        self.msdf_range
+    }
+
+    /// Sets the width of the distance field around each glyph in atlas pixels, at least 1.
+    ///
+    /// Glyphs are rasterized once and scaled to any text size, so this isn't needed for large
+    /// text. A larger range keeps sharp corners cleaner and allows thicker effects, but each
+    /// glyph takes more space in the atlas.
+    pub fn set_msdf_range(&mut self, range_px: f32) {
+        self.msdf_range = range_px.max(1.0);
+    }
+
+    /// Measures the size of `text` at `size_em` without rasterizing it. Characters that are
+    /// missing in this font don't take up any space.
+    pub fn measure_text(&self, text: &str, size_em: f32, letter_spacing_em: f32) -> Vector2<f32> {
+        let Ok(face) = Face::parse(&self.font_bytes, 0) else {
+            return Vector2::zeros();
+        };
+
+        let units_per_em = face.units_per_em() as f32;
+        let advance = |ch: char| {
+            face.glyph_index(ch)
+                .and_then(|glyph| face.glyph_hor_advance(glyph))
+                .map_or(0.0, |advance| advance as f32 / units_per_em)
+        };
+
+        let mut width = 0.0f32;
+        let mut lines = 0;
+        for line in text.split('\n') {
+            let spacing = line.chars().count().saturating_sub(1) as f32 * letter_spacing_em;
+            width = width.max(line.chars().map(advance).sum::<f32>() + spacing);
+            lines += 1;
+        }
+
+        let ascent = face.ascender() as f32 / units_per_em;
+        let descent = -face.descender() as f32 / units_per_em;
+        let line_gap = face.line_gap() as f32 / units_per_em;
+        let height = ascent + descent + (lines - 1) as f32 * (ascent + descent + line_gap);
+
+        Vector2::new(width, height) * size_em
+    }
+
+    /// The fonts that glyphs missing in this font are taken from, in order
+    pub fn fallbacks(&self) -> &[HFont] {
+        &self.fallbacks
+    }
+
+    /// Sets fonts to look in when a character isn't part of this font, e.g. an emoji or CJK font.
+    ///
+    /// They're tried in order, and only their own glyphs are used, not the ones of their
+    /// fallbacks. Characters that aren't in any of the fonts are shown as a space.
+    pub fn set_fallbacks(&mut self, fallbacks: Vec<HFont>) {
+        self.fallbacks = fallbacks;
+    }
 }
 
 impl Store<Font> {
@@ -175,10 +175,14 @@ impl<T: StoreType> Display for HandleName<T> {
 }
 
 impl<T: StoreType> Store<T> {
+    /// Hands out a fresh id. Ids are never reused, which keeps handles to removed assets from
+    /// resolving to whatever gets added later.
     fn next_id(&self) -> H<T> {
         let mut id_lock = self.next_id.write().unwrap();
         let id = H::new(*id_lock);
-        *id_lock += 1;
+        *id_lock = id_lock
+            .checked_add(1)
+            .unwrap_or_else(|| panic!("[{} Store] Ran out of handle ids", T::name()));
         id
     }
 
@@ -191,6 +195,20 @@ impl<T: StoreType> Store<T> {
         id
     }
 
+    /// Returns true if the handle refers to an asset in this store.
+    ///
+    /// Handles to removed assets stay invalid, even after new assets were added. Built-in handles
+    /// are always valid, as they can't be removed.
+    pub fn contains(&self, h: H<T>) -> bool {
+        self.data.contains_key(&h.into())
+    }
+
+    /// Returns the asset if the handle is valid. Unlike [`try_get`](Self::try_get), an invalid
+    /// handle isn't logged, so this can be used to check handles that are expected to dangle.
+    pub fn get_checked(&self, h: H<T>) -> Option<Ref<'_, T>> {
+        self.data.get(&h.into()).map(Into::into)
+    }
+
     pub fn try_get(&self, h: H<T>) -> Option<Ref<'_, T>> {
         self.data
             .get(&h.into())
@@ -222,6 +240,12 @@ impl<T: StoreType> Store<T> {
         reference.map(|i| i.into())
     }
 
+    /// Like [`try_get_mut`](Self::try_get_mut), but doesn't mark the asset dirty. Only for
+    /// engine-side state that the GPU copy doesn't depend on.
+    pub(crate) fn get_mut_untracked(&self, h: H<T>) -> Option<RefMut<'_, T>> {
+        self.data.get_mut(&h.into()).map(Into::into)
+    }
+
     fn set_dirty(&self, h: AssetKey) {
         let mut dirty_store = self.dirty.write().expect("Deadlock in Asset Store");
         if !dirty_store.contains(&h) {
@@ -4,6 +4,10 @@ use std::fmt::{Debug, Display, Formatter};
 use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
 
+/// A handle to an asset in one of the stores of the [`AssetStore`](crate::assets::AssetStore).
+///
+/// Ids are never reused within a store, so a handle to a removed asset can't alias a newer one.
+/// Check if a handle is still valid with `contains`, e.g. `assets.meshes.contains(handle)`.
 #[derive(Debug)]
 pub struct H<T: StoreType>(u32, PhantomData<T>);
 
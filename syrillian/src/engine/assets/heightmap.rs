@@ -0,0 +1,194 @@
+use crate::assets::Mesh;
+use crate::core::Vertex3D;
+use image::DynamicImage;
+use nalgebra::{DMatrix, Vector2, Vector3};
+use snafu::{ResultExt, Snafu, ensure};
+use std::fs;
+
+#[derive(Debug, Snafu)]
+#[snafu(context(suffix(Err)))]
+pub enum HeightmapError {
+    #[snafu(display("A heightmap needs at least 2x2 samples, got {width}x{depth}"))]
+    TooSmall { width: usize, depth: usize },
+
+    #[snafu(display("Expected {expected} heights for the heightmap, got {actual}"))]
+    SizeMismatch { expected: usize, actual: usize },
+
+    #[snafu(display("Failed to read heightmap: {source}"))]
+    Io { source: std::io::Error },
+
+    #[snafu(display("Failed to decode heightmap image: {source}"))]
+    Image { source: image::ImageError },
+}
+
+/// A regular grid of heights in `[0, 1]`, e.g. loaded from a grayscale image.
+///
+/// [`Mesh::from_heightmap`] and
+/// [`Collider3D::set_heightfield`](crate::components::Collider3D::set_heightfield) sample the
+/// grid the same way, so terrain visuals and physics line up when both are given the same size.
+/// The grid is centered on the origin, with columns along X and rows along Z.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heightmap {
+    width: usize,
+    depth: usize,
+    heights: Vec<f32>,
+}
+
+impl Heightmap {
+    /// Creates a heightmap from row-major heights, `width` samples along X per row
+    pub fn new(width: usize, depth: usize, heights: Vec<f32>) -> Result<Self, HeightmapError> {
+        ensure!(width >= 2 && depth >= 2, TooSmallErr { width, depth });
+        ensure!(
+            heights.len() == width * depth,
+            SizeMismatchErr {
+                expected: width * depth,
+                actual: heights.len(),
+            }
+        );
+
+        Ok(Heightmap {
+            width,
+            depth,
+            heights,
+        })
+    }
+
+    /// Uses the brightness of every pixel as its height
+    pub fn from_image(image: &DynamicImage) -> Result<Self, HeightmapError> {
+        let luma = image.to_luma16();
+        let heights = luma
+            .pixels()
+            .map(|p| p[0] as f32 / u16::MAX as f32)
+            .collect();
+
+        Self::new(luma.width() as usize, luma.height() as usize, heights)
+    }
+
+    pub fn load_image(path: &str) -> Result<Self, HeightmapError> {
+        let bytes = fs::read(path).context(IoErr)?;
+        Self::load_image_from_memory(&bytes)
+    }
+
+    pub fn load_image_from_memory(bytes: &[u8]) -> Result<Self, HeightmapError> {
+        let image = image::load_from_memory(bytes).context(ImageErr)?;
+        Self::from_image(&image)
+    }
+
+    /// Number of samples along X
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Number of samples along Z
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn heights(&self) -> &[f32] {
+        &self.heights
+    }
+
+    /// Returns the height at column `x` and row `z`
+    pub fn height(&self, x: usize, z: usize) -> f32 {
+        self.heights[z * self.width + x]
+    }
+
+    /// Bilinearly samples the height at fractional grid coordinates
+    pub fn sample(&self, x: f32, z: f32) -> f32 {
+        let x = x.clamp(0.0, (self.width - 1) as f32);
+        let z = z.clamp(0.0, (self.depth - 1) as f32);
+
+        let x0 = (x.floor() as usize).min(self.width - 2);
+        let z0 = (z.floor() as usize).min(self.depth - 2);
+        let tx = x - x0 as f32;
+        let tz = z - z0 as f32;
+
+        let top = self.height(x0, z0) * (1.0 - tx) + self.height(x0 + 1, z0) * tx;
+        let bottom = self.height(x0, z0 + 1) * (1.0 - tx) + self.height(x0 + 1, z0 + 1) * tx;
+        top * (1.0 - tz) + bottom * tz
+    }
+
+    /// Returns a coarser heightmap with roughly every `stride`-th sample, e.g. for a cheaper
+    /// collider. The edges are kept, so it still covers the same area.
+    pub fn downsample(&self, stride: usize) -> Heightmap {
+        let stride = stride.max(1);
+        let width = (self.width - 1) / stride + 1;
+        let depth = (self.depth - 1) / stride + 1;
+        let width = width.max(2);
+        let depth = depth.max(2);
+
+        let step_x = (self.width - 1) as f32 / (width - 1) as f32;
+        let step_z = (self.depth - 1) as f32 / (depth - 1) as f32;
+
+        let heights = (0..depth)
+            .flat_map(|z| (0..width).map(move |x| (x, z)))
+            .map(|(x, z)| self.sample(x as f32 * step_x, z as f32 * step_z))
+            .collect();
+
+        Heightmap {
+            width,
+            depth,
+            heights,
+        }
+    }
+
+    /// The heights as a matrix with one row per Z sample, as Rapier's heightfields expect
+    pub(crate) fn to_matrix(&self) -> DMatrix<f32> {
+        DMatrix::from_fn(self.depth, self.width, |z, x| self.height(x, z))
+    }
+}
+
+impl Mesh {
+    /// Generates a terrain mesh from a heightmap.
+    ///
+    /// The mesh spans `size` along X and Z, centered on the origin, and the heights are
+    /// multiplied by `height_scale`. UVs stretch once over the whole terrain.
+    pub fn from_heightmap(heightmap: &Heightmap, size: Vector2<f32>, height_scale: f32) -> Mesh {
+        let (width, depth) = (heightmap.width(), heightmap.depth());
+        let cell_x = size.x / (width - 1) as f32;
+        let cell_z = size.y / (depth - 1) as f32;
+
+        let mut vertices = Vec::with_capacity(width * depth);
+        for z in 0..depth {
+            for x in 0..width {
+                let u = x as f32 / (width - 1) as f32;
+                let v = z as f32 / (depth - 1) as f32;
+                let position = Vector3::new(
+                    (u - 0.5) * size.x,
+                    heightmap.height(x, z) * height_scale,
+                    (v - 0.5) * size.y,
+                );
+
+                let left = heightmap.height(x.saturating_sub(1), z);
+                let right = heightmap.height((x + 1).min(width - 1), z);
+                let back = heightmap.height(x, z.saturating_sub(1));
+                let front = heightmap.height(x, (z + 1).min(depth - 1));
+                let span_x = ((x + 1).min(width - 1) - x.saturating_sub(1)) as f32 * cell_x;
+                let span_z = ((z + 1).min(depth - 1) - z.saturating_sub(1)) as f32 * cell_z;
+                let slope_x = (right - left) * height_scale / span_x;
+                let slope_z = (front - back) * height_scale / span_z;
+
+                let normal = Vector3::new(-slope_x, 1.0, -slope_z).normalize();
+                let tangent = Vector3::new(1.0, slope_x, 0.0).normalize();
+
+                let mut vertex = Vertex3D::basic(position, Vector2::new(u, v), normal);
+                vertex.tangent = tangent;
+                vertices.push(vertex);
+            }
+        }
+
+        // same triangulation as Rapier's heightfield, so the collider matches exactly
+        let mut indices = Vec::with_capacity((width - 1) * (depth - 1) * 6);
+        for z in 0..depth - 1 {
+            for x in 0..width - 1 {
+                let i00 = (z * width + x) as u32;
+                let i01 = i00 + 1;
+                let i10 = i00 + width as u32;
+                let i11 = i10 + 1;
+                indices.extend_from_slice(&[i00, i10, i01, i10, i11, i01]);
+            }
+        }
+
+        Mesh::builder(vertices).with_indices(indices).build()
+    }
+}
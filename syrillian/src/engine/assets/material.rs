@@ -3,8 +3,41 @@ use crate::engine::assets::*;
 use crate::store_add_checked;
 use bon::Builder;
 use nalgebra::Vector3;
+use snafu::{ResultExt, Snafu, ensure};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How many floats [`Material::set_custom_params`] can hand to a shader
+pub const MAX_CUSTOM_PARAMS: usize = 16;
+
+#[derive(Debug, Snafu)]
+#[snafu(context(suffix(Err)))]
+pub enum CustomParamsError {
+    #[snafu(display(
+        "got {count} custom params, but shaders can only receive {MAX_CUSTOM_PARAMS}"
+    ))]
+    TooMany { count: usize },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(context(suffix(Err)))]
+pub enum MaterialTextureError {
+    #[snafu(display("Failed to read texture {}: {source}", path.display()))]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[snafu(display("Failed to decode texture {}: {source}", path.display()))]
+    Decode {
+        path: PathBuf,
+        source: image::ImageError,
+    },
+}
 
 #[derive(Debug, Clone, Builder)]
+// public, so the builder states can be named in `TexturedMaterialBuilder`
+#[builder(state_mod(vis = "pub"))]
 pub struct Material {
     #[builder(into)]
     pub name: String,
@@ -12,9 +45,18 @@ pub struct Material {
     pub color: Vector3<f32>,
     pub diffuse_texture: Option<HTexture>,
     pub normal_texture: Option<HTexture>,
-    pub roughness_texture: Option<HTexture>,
+    /// Packed like glTF: roughness in the green channel, metallic in the blue channel.
+    ///
+    /// The texture values are multiplied with [`roughness`](Self::roughness) and
+    /// [`metallic`](Self::metallic), so keep those at 1 to use the texture as is.
+    ///
+    /// This replaces `roughness_texture`, which only read roughness from the green channel and
+    /// ignored the roughness factor.
+    pub metallic_roughness_texture: Option<HTexture>,
+    /// Microfacet roughness, from mirror-like at 0 to fully rough at 1
     #[builder(default = 0.5)]
     pub roughness: f32,
+    /// 0 for dielectrics like plastic or wood, 1 for bare metals
     #[builder(default = 0.0)]
     pub metallic: f32,
     #[builder(default = 1.0)]
@@ -25,14 +67,89 @@ pub struct Material {
     pub cast_shadows: bool,
     #[builder(default = false)]
     pub has_transparency: bool,
+    /// Anisotropic filtering level for this material's samplers. `None` uses the renderer default.
+    pub anisotropy: Option<u16>,
     #[builder(default = HShader::DIM3)]
     pub shader: HShader,
+    /// Parameters shaders can read with `custom_param(i)`, see
+    /// [`set_custom_params`](Self::set_custom_params)
+    #[builder(default = [0.0; MAX_CUSTOM_PARAMS])]
+    pub custom_params: [f32; MAX_CUSTOM_PARAMS],
 }
 
 impl Material {
     pub fn is_transparent(&self) -> bool {
         self.alpha < 1.0
     }
+
+    /// Returns the parameters shaders can read with `custom_param(i)`
+    pub fn custom_params(&self) -> &[f32; MAX_CUSTOM_PARAMS] {
+        &self.custom_params
+    }
+
+    /// Hands up to [`MAX_CUSTOM_PARAMS`] floats to the material's shader, like a color or a
+    /// speed. The rest is set to 0.
+    ///
+    /// Default shaders can read them right away, custom shaders need `#use custom`:
+    ///
+    /// ```wgsl
+    /// let tint = vec3(custom_param(0u), custom_param(1u), custom_param(2u));
+    /// let pulse = sin(globals.time * custom_param(3u));
+    /// ```
+    pub fn set_custom_params(&mut self, params: &[f32]) -> Result<(), CustomParamsError> {
+        ensure!(
+            params.len() <= MAX_CUSTOM_PARAMS,
+            TooManyErr {
+                count: params.len()
+            }
+        );
+
+        self.custom_params = [0.0; MAX_CUSTOM_PARAMS];
+        self.custom_params[..params.len()].copy_from_slice(params);
+        Ok(())
+    }
+}
+
+impl<S: material_builder::State> MaterialBuilder<S> {
+    /// Loads an image file as the diffuse texture, replacing
+    /// [`diffuse_texture`](Self::diffuse_texture). See [`TexturedMaterialBuilder`].
+    pub fn diffuse_texture_path(self, path: impl AsRef<Path>) -> TexturedMaterialBuilder<S> {
+        TexturedMaterialBuilder::new(self).diffuse_texture_path(path)
+    }
+
+    /// Loads an image file as the normal map, with linear colors, replacing
+    /// [`normal_texture`](Self::normal_texture). See [`TexturedMaterialBuilder`].
+    pub fn normal_texture_path(self, path: impl AsRef<Path>) -> TexturedMaterialBuilder<S> {
+        TexturedMaterialBuilder::new(self).normal_texture_path(path)
+    }
+
+    /// Old name of [`metallic_roughness_texture`](Self::metallic_roughness_texture)
+    #[deprecated(
+        note = "use `metallic_roughness_texture`, which also reads metallic from the blue channel"
+    )]
+    pub fn roughness_texture(
+        self,
+        texture: HTexture,
+    ) -> MaterialBuilder<material_builder::SetMetallicRoughnessTexture<S>>
+    where
+        S::MetallicRoughnessTexture: material_builder::IsUnset,
+    {
+        self.metallic_roughness_texture(texture)
+    }
+
+    /// Old name of [`maybe_metallic_roughness_texture`](Self::maybe_metallic_roughness_texture)
+    #[deprecated(
+        note = "use `maybe_metallic_roughness_texture`, which also reads metallic from the blue channel"
+    )]
+    pub fn maybe_roughness_texture(
+        self,
+        texture: Option<HTexture>,
+    ) -> MaterialBuilder<material_builder::SetMetallicRoughnessTexture<S>>
+    where
+        S::MetallicRoughnessTexture: material_builder::IsUnset,
+    {
+        self.maybe_metallic_roughness_texture(texture)
+    }
 }
 
 impl<S: material_builder::State> MaterialBuilder<S>
@@ -44,6 +161,94 @@ where
     }
 }
 
+/// A [`MaterialBuilder`] with textures to load from image files.
+///
+/// The files are only read when the material is built, which can fail, so this can only be
+/// finished with [`build_in`](Self::build_in) or [`try_store`](Self::try_store). Everything
+/// else has to be set on the builder before the first path.
+///
+/// ```rust,no_run
+/// use syrillian::World;
+/// use syrillian::assets::Material;
+///
+/// fn textured(world: &World) {
+///     let material = Material::builder()
+///         .name("Bricks")
+///         .diffuse_texture_path("textures/bricks.png")
+///         .normal_texture_path("textures/bricks_normal.png")
+///         .try_store(world)
+///         .expect("bricks textures are missing");
+/// }
+/// ```
+pub struct TexturedMaterialBuilder<S: material_builder::State> {
+    builder: MaterialBuilder<S>,
+    diffuse: Option<PathBuf>,
+    normal: Option<PathBuf>,
+}
+
+impl<S: material_builder::State> TexturedMaterialBuilder<S> {
+    fn new(builder: MaterialBuilder<S>) -> Self {
+        TexturedMaterialBuilder {
+            builder,
+            diffuse: None,
+            normal: None,
+        }
+    }
+
+    /// See [`MaterialBuilder::diffuse_texture_path`]
+    pub fn diffuse_texture_path(mut self, path: impl AsRef<Path>) -> Self {
+        self.diffuse = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// See [`MaterialBuilder::normal_texture_path`]
+    pub fn normal_texture_path(mut self, path: impl AsRef<Path>) -> Self {
+        self.normal = Some(path.as_ref().to_path_buf());
+        self
+    }
+}
+
+impl<S: material_builder::State> TexturedMaterialBuilder<S>
+where
+    S: material_builder::IsComplete,
+{
+    /// Builds the material after loading the textures into the texture store
+    pub fn build_in<A: AsRef<Store<Texture>>>(
+        self,
+        store: &A,
+    ) -> Result<Material, MaterialTextureError> {
+        let mut material = self.builder.build();
+
+        if let Some(path) = self.diffuse {
+            material.diffuse_texture = Some(load_texture(store.as_ref(), &path, true)?);
+        }
+        if let Some(path) = self.normal {
+            material.normal_texture = Some(load_texture(store.as_ref(), &path, false)?);
+        }
+
+        Ok(material)
+    }
+
+    /// Like [`MaterialBuilder::store`], but loads the textures first
+    pub fn try_store<A>(self, store: &A) -> Result<HMaterial, MaterialTextureError>
+    where
+        A: AsRef<Store<Material>> + AsRef<Store<Texture>>,
+    {
+        let material = self.build_in(store)?;
+        Ok(AsRef::<Store<Material>>::as_ref(store).add(material))
+    }
+}
+
+fn load_texture(
+    store: &Store<Texture>,
+    path: &Path,
+    srgb: bool,
+) -> Result<HTexture, MaterialTextureError> {
+    let bytes = fs::read(path).context(ReadErr { path })?;
+    let image = image::load_from_memory(&bytes).context(DecodeErr { path })?;
+    Ok(store.add(Texture::load_decoded_image(image, srgb)))
+}
+
 impl StoreDefaults for Material {
     fn populate(store: &mut Store<Self>) {
         let fallback = Material {
@@ -51,7 +256,7 @@ impl StoreDefaults for Material {
             color: Vector3::new(1.0, 1.0, 1.0),
             diffuse_texture: None,
             normal_texture: None,
-            roughness_texture: None,
+            metallic_roughness_texture: None,
             roughness: 0.5,
             metallic: 0.0,
             shader: HShader::FALLBACK,
@@ -59,6 +264,8 @@ impl StoreDefaults for Material {
             lit: true,
             cast_shadows: true,
             has_transparency: false,
+            anisotropy: None,
+            custom_params: [0.0; MAX_CUSTOM_PARAMS],
         };
 
         store_add_checked!(store, HMaterial::FALLBACK_ID, fallback);
@@ -68,7 +275,7 @@ impl StoreDefaults for Material {
             color: Vector3::new(0.7, 0.7, 0.7),
             diffuse_texture: None,
             normal_texture: None,
-            roughness_texture: None,
+            metallic_roughness_texture: None,
             roughness: 0.5,
             metallic: 0.4,
             shader: HShader::DIM3,
@@ -76,6 +283,8 @@ impl StoreDefaults for Material {
             lit: true,
             cast_shadows: true,
             has_transparency: false,
+            anisotropy: None,
+            custom_params: [0.0; MAX_CUSTOM_PARAMS],
         };
 
         store_add_checked!(store, HMaterial::DEFAULT_ID, default);
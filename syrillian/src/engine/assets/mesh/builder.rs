@@ -1,6 +1,7 @@
-use crate::assets::mesh::bounding_sphere_from_vertices;
+use crate::assets::mesh::{aabb_from_vertices, bounding_sphere_from_vertices};
 use crate::assets::{Mesh, MeshVertexData};
 use crate::core::{Bones, Vertex3D};
+use nalgebra::Vector4;
 use std::ops::Range;
 use std::sync::Arc;
 
@@ -34,12 +35,14 @@ impl MeshBuilder {
             material_ranges.push(0u32..vert_count as u32);
         }
 
-        let bounding_sphere = bounding_sphere_from_vertices(&self.vertices);
+        let aabb = aabb_from_vertices(&self.vertices);
+        let bounding_sphere = bounding_sphere_from_vertices(&self.vertices, aabb);
 
         Mesh {
             data: Arc::new(MeshVertexData::new(self.vertices, self.indices)),
             material_ranges,
             bones: self.bones.unwrap_or_default(),
+            aabb,
             bounding_sphere,
         }
     }
@@ -54,6 +57,15 @@ impl MeshBuilder {
         self
     }
 
+    /// Sets the linear RGBA vertex colors, one per vertex in order.
+    /// Vertices without a matching color keep their current one.
+    pub fn with_colors(mut self, colors: &[Vector4<f32>]) -> Self {
+        for (vertex, color) in self.vertices.iter_mut().zip(colors) {
+            vertex.color = *color;
+        }
+        self
+    }
+
     pub fn with_indices(mut self, indices: Vec<u32>) -> Self {
         self.indices = Some(indices);
         self
@@ -10,6 +10,7 @@ use itertools::izip;
 use nalgebra::{Point, Vector2, Vector3};
 use obj::{IndexTuple, ObjError};
 use snafu::Snafu;
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::ops::Range;
 use std::sync::Arc;
@@ -18,6 +19,9 @@ const CUBE_OBJ: &[u8] = include_bytes!("preset_meshes/cube.obj");
 const DEBUG_ARROW: &[u8] = include_bytes!("preset_meshes/debug_arrow.obj");
 const SPHERE: &[u8] = include_bytes!("preset_meshes/small_sphere.obj");
 
+/// Default distance under which [`Mesh::optimize`] considers vertex attributes equal
+pub const DEFAULT_WELD_EPSILON: f32 = 1e-5;
+
 #[derive(Debug, Snafu)]
 pub enum MeshError {
     #[snafu(display("The loaded mesh did not have any normals"))]
@@ -35,6 +39,9 @@ pub struct Mesh {
     pub(crate) data: Arc<MeshVertexData<Vertex3D>>,
     pub material_ranges: Vec<Range<u32>>,
     pub bones: Bones,
+    pub(crate) aabb: (Vector3<f32>, Vector3<f32>),
+    /// Computed from the vertices when the mesh is built, see
+    /// [`bounding_sphere`](Self::bounding_sphere())
     pub bounding_sphere: BoundingSphere,
 }
 
@@ -83,6 +90,76 @@ impl Mesh {
         self.data.indices.is_some()
     }
 
+    /// Returns the local space axis-aligned bounding box as `(min, max)`.
+    /// Empty meshes have a zero-sized box at the origin.
+    #[inline]
+    pub fn aabb(&self) -> (Vector3<f32>, Vector3<f32>) {
+        self.aabb
+    }
+
+    /// Returns the local space sphere enclosing all vertices, centered on the bounding box.
+    #[inline]
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        self.bounding_sphere
+    }
+
+    /// Welds identical vertices and indexes the mesh, using [`DEFAULT_WELD_EPSILON`].
+    ///
+    /// See [`optimize_with_epsilon`](Self::optimize_with_epsilon).
+    pub fn optimize(&mut self) {
+        self.optimize_with_epsilon(DEFAULT_WELD_EPSILON);
+    }
+
+    /// Welds vertices that have the same attributes and rebuilds the index buffer to match.
+    ///
+    /// Float attributes are snapped to a grid of `epsilon` before comparing, so vertices closer
+    /// than that are usually merged. An `epsilon` of zero only merges exact copies. Bone indices
+    /// always have to match exactly. The order of the indices, and with it the material ranges,
+    /// stays the same.
+    pub fn optimize_with_epsilon(&mut self, epsilon: f32) {
+        let quantize = |v: f32| {
+            if epsilon > 0.0 {
+                (v / epsilon).round() as i64
+            } else {
+                v.to_bits() as i64
+            }
+        };
+
+        let mut vertices = Vec::new();
+        let mut welded = HashMap::new();
+        let remap: Vec<u32> = self
+            .data
+            .vertices
+            .iter()
+            .map(|v| {
+                let floats = v
+                    .position
+                    .iter()
+                    .chain(v.uv.iter())
+                    .chain(v.normal.iter())
+                    .chain(v.tangent.iter())
+                    .chain(v.color.iter())
+                    .chain(v.bone_weights.iter());
+                let mut key = [0i64; 19];
+                for (k, &f) in key.iter_mut().zip(floats) {
+                    *k = quantize(f);
+                }
+
+                *welded.entry((key, v.bone_indices)).or_insert_with(|| {
+                    vertices.push(*v);
+                    vertices.len() as u32 - 1
+                })
+            })
+            .collect();
+
+        let indices = match &self.data.indices {
+            Some(indices) => indices.iter().map(|&i| remap[i as usize]).collect(),
+            None => remap,
+        };
+
+        self.data = Arc::new(MeshVertexData::new(vertices, Some(indices)));
+    }
+
     pub fn load_from_obj_slice(data: &[u8]) -> Result<Mesh, MeshError> {
         let data = obj::ObjData::load_buf(data)?;
         let mut vertices: Vec<Vector3<f32>> = Vec::new();
@@ -122,12 +199,14 @@ impl Mesh {
         let vertices = izip!(vertices, uvs, normals)
             .map(|(v, u, n)| Vertex3D::basic(v, u, n))
             .collect::<Vec<_>>();
-        let bounding_sphere = bounding_sphere_from_vertices(&vertices);
+        let aabb = aabb_from_vertices(&vertices);
+        let bounding_sphere = bounding_sphere_from_vertices(&vertices, aabb);
 
         Ok(Mesh {
             data: Arc::new(MeshVertexData::new(vertices, None)),
             material_ranges,
             bones: Bones::none(),
+            aabb,
             bounding_sphere,
         })
     }
@@ -184,6 +263,17 @@ impl StoreDefaults for Mesh {
     }
 }
 
+impl Store<Mesh> {
+    /// Welds the vertices of the built-in meshes loaded from OBJ files, see [`Mesh::optimize`].
+    pub fn optimize_builtins(&self) {
+        for handle in [HMesh::UNIT_CUBE, HMesh::DEBUG_ARROW, HMesh::SPHERE] {
+            if let Some(mut mesh) = self.try_get_mut(handle) {
+                mesh.optimize();
+            }
+        }
+    }
+}
+
 impl StoreType for Mesh {
     fn name() -> &'static str {
         "Mesh"
@@ -204,22 +294,28 @@ impl StoreType for Mesh {
     }
 }
 
-pub(crate) fn bounding_sphere_from_vertices(vertices: &[Vertex3D]) -> BoundingSphere {
-    if vertices.is_empty() {
-        return BoundingSphere::empty();
-    }
+pub(crate) fn aabb_from_vertices(vertices: &[Vertex3D]) -> (Vector3<f32>, Vector3<f32>) {
+    let Some(first) = vertices.first() else {
+        return (Vector3::zeros(), Vector3::zeros());
+    };
 
-    let mut min = vertices[0].position;
-    let mut max = vertices[0].position;
+    let mut min = first.position;
+    let mut max = first.position;
 
     for v in vertices.iter().skip(1) {
-        let p = v.position;
-        min.x = min.x.min(p.x);
-        min.y = min.y.min(p.y);
-        min.z = min.z.min(p.z);
-        max.x = max.x.max(p.x);
-        max.y = max.y.max(p.y);
-        max.z = max.z.max(p.z);
+        min = min.inf(&v.position);
+        max = max.sup(&v.position);
+    }
+
+    (min, max)
+}
+
+pub(crate) fn bounding_sphere_from_vertices(
+    vertices: &[Vertex3D],
+    (min, max): (Vector3<f32>, Vector3<f32>),
+) -> BoundingSphere {
+    if vertices.is_empty() {
+        return BoundingSphere::empty();
     }
 
     let center = (min + max) * 0.5;
@@ -34,6 +34,7 @@ pub(crate) mod generic_store;
 
 mod bind_group_layout;
 mod font;
+mod heightmap;
 mod material;
 mod mesh;
 mod shader;
@@ -48,6 +49,7 @@ pub use self::handle::*;
 
 pub use self::bind_group_layout::*;
 pub use self::font::*;
+pub use self::heightmap::*;
 pub use self::material::*;
 pub use self::mesh::*;
 pub use self::shader::*;
@@ -63,6 +65,7 @@ pub type HBGL = H<BGL>;
 pub type HMaterial = H<Material>;
 pub type HMesh = H<Mesh>;
 pub type HShader = H<Shader>;
+pub type HComputeShader = H<ComputeShader>;
 pub type HTexture = H<Texture>;
 pub type HFont = H<Font>;
 pub type HSound = H<Sound>;
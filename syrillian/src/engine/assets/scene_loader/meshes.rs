@@ -5,7 +5,7 @@ use crate::core::{Bones, Vertex3D};
 use gltf::mesh;
 use gltf::{self, Node};
 use itertools::izip;
-use nalgebra::{Vector2, Vector3};
+use nalgebra::{Vector2, Vector3, Vector4};
 use std::collections::HashMap;
 use tracing::warn;
 
@@ -34,6 +34,7 @@ struct VertexSources<'a> {
     normals: Option<&'a Vec<[f32; 3]>>,
     tangents: Option<&'a Vec<[f32; 4]>>,
     tex_coords: Option<&'a Vec<[f32; 2]>>,
+    colors: Option<&'a Vec<[f32; 4]>>,
     skin: Option<SkinSlices<'a>>,
     joint_map: &'a HashMap<usize, usize>,
 }
@@ -135,6 +136,9 @@ fn extract_primitive_data(
     let normals = reader.read_normals().map(|it| it.collect::<Vec<_>>());
     let tangents = reader.read_tangents().map(|it| it.collect::<Vec<_>>());
     let tex_coords = reader.read_tex_coords(0).map(convert_tex_coords);
+    let colors = reader
+        .read_colors(0)
+        .map(|it| it.into_rgba_f32().collect::<Vec<_>>());
     let joints_raw = reader.read_joints(0);
     let weights_raw = reader.read_weights(0);
     let indices: Vec<u32> = if let Some(ind) = reader.read_indices() {
@@ -150,6 +154,7 @@ fn extract_primitive_data(
         normals: normals.as_ref(),
         tangents: tangents.as_ref(),
         tex_coords: tex_coords.as_ref(),
+        colors: colors.as_ref(),
         skin: skin_slices,
         joint_map: joint_node_index_of,
     };
@@ -260,6 +265,7 @@ struct PrimitiveBuffers {
     normals: Vec<Vector3<f32>>,
     tangents: Vec<Vector3<f32>>,
     bitangents: Vec<Vector3<f32>>,
+    colors: Vec<Vector4<f32>>,
     bone_indices: Vec<Vec<u32>>,
     bone_weights: Vec<Vec<f32>>,
     ranges: Vec<std::ops::Range<u32>>,
@@ -275,6 +281,7 @@ impl PrimitiveBuffers {
             normals,
             tangents,
             bitangents,
+            colors,
             bone_indices,
             bone_weights,
             material_index,
@@ -286,6 +293,7 @@ impl PrimitiveBuffers {
         self.normals.extend(normals);
         self.tangents.extend(tangents);
         self.bitangents.extend(bitangents);
+        self.colors.extend(colors);
         self.bone_indices.extend(bone_indices);
         self.bone_weights.extend(bone_weights);
 
@@ -320,6 +328,7 @@ impl PrimitiveBuffers {
             normals,
             tangents,
             bitangents,
+            colors,
             bone_indices,
             bone_weights,
             ranges,
@@ -335,7 +344,8 @@ impl PrimitiveBuffers {
             bone_indices,
             bone_weights
         )
-        .map(Vertex3D::from)
+        .zip(colors)
+        .map(|(attributes, color)| Vertex3D::from(attributes).with_color(color))
         .collect();
 
         let mesh = Mesh::builder(vertices)
@@ -352,6 +362,7 @@ struct PrimitiveResult {
     normals: Vec<Vector3<f32>>,
     tangents: Vec<Vector3<f32>>,
     bitangents: Vec<Vector3<f32>>,
+    colors: Vec<Vector4<f32>>,
     bone_indices: Vec<Vec<u32>>,
     bone_weights: Vec<Vec<f32>>,
     material_index: u32,
@@ -366,6 +377,7 @@ impl PrimitiveResult {
             normals: Vec::new(),
             tangents: Vec::new(),
             bitangents: Vec::new(),
+            colors: Vec::new(),
             bone_indices: Vec::new(),
             bone_weights: Vec::new(),
             material_index,
@@ -407,6 +419,12 @@ impl PrimitiveResult {
         });
         self.tex_coords.push(uv);
 
+        let color = sources.colors.map_or_else(
+            || Vector4::new(1.0, 1.0, 1.0, 1.0),
+            |list| list[index].into(),
+        );
+        self.colors.push(color);
+
         if let Some(skin) = sources.skin {
             let joint = skin.joints[index];
             let weight = skin.weights[index];
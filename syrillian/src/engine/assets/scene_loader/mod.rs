@@ -1,5 +1,5 @@
 use crate::World;
-use crate::assets::{HMaterial, Mesh};
+use crate::assets::{DEFAULT_WELD_EPSILON, HMaterial, Mesh};
 use crate::components::{
     AnimationComponent, MeshRenderer, PointLightComponent, SkeletalComponent, SpotLightComponent,
     SunLightComponent,
@@ -13,8 +13,10 @@ use gltf::{self, Document, Node};
 use nalgebra::{Quaternion, UnitQuaternion, Vector3};
 use snafu::{OptionExt, ResultExt, Snafu};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use syrillian_utils::debug_panic;
-use tracing::trace;
+use tracing::{trace, warn};
+use uuid::Uuid;
 
 mod bones;
 mod meshes;
@@ -31,27 +33,89 @@ pub enum Error {
     GltfImport { source: gltf::Error },
 }
 
+/// Options controlling how external files referenced by a scene are found.
+#[derive(Default)]
+pub struct SceneLoadOptions {
+    /// Directory relative buffer and texture paths are resolved from.
+    /// Defaults to the directory of the loaded file.
+    pub base_dir: Option<PathBuf>,
+    /// Rewrites texture URIs before they're resolved, e.g. to fix absolute paths from another machine
+    pub remap_path: Option<Box<dyn Fn(&str) -> PathBuf>>,
+    /// Welds identical vertices of every loaded mesh within this epsilon when set.
+    /// See [`Mesh::optimize_with_epsilon`].
+    pub weld_epsilon: Option<f32>,
+}
+
+impl SceneLoadOptions {
+    pub fn base_dir(mut self, base_dir: impl Into<PathBuf>) -> Self {
+        self.base_dir = Some(base_dir.into());
+        self
+    }
+
+    pub fn remap_path(mut self, remap: impl Fn(&str) -> PathBuf + 'static) -> Self {
+        self.remap_path = Some(Box::new(remap));
+        self
+    }
+
+    /// Welds identical vertices of every loaded mesh with [`DEFAULT_WELD_EPSILON`]
+    pub fn optimize_meshes(self) -> Self {
+        self.weld_epsilon(DEFAULT_WELD_EPSILON)
+    }
+
+    pub fn weld_epsilon(mut self, epsilon: f32) -> Self {
+        self.weld_epsilon = Some(epsilon);
+        self
+    }
+}
+
 /// Container for a glTF document and its binary attachments.
 pub struct GltfScene {
     pub doc: Document,
     pub buffers: Vec<gltf::buffer::Data>,
+    /// Decoded images, indexed like the document's images. Images that couldn't be loaded
+    /// are empty.
     pub images: Vec<gltf::image::Data>,
 }
 
 impl GltfScene {
     /// Imports a glTF scene from disk and gathers its buffers and images.
     pub fn import(path: &str) -> Result<Self, Error> {
-        let (doc, buffers, images) = gltf::import(path).context(GltfImportErr)?;
-        Ok(Self {
-            doc,
-            buffers,
-            images,
-        })
+        Self::import_with(path, &SceneLoadOptions::default())
+    }
+
+    /// Imports a glTF scene from disk, resolving external files as described by `options`.
+    pub fn import_with(path: &str, options: &SceneLoadOptions) -> Result<Self, Error> {
+        let path = Path::new(path);
+        let gltf::Gltf { document, blob } = gltf::Gltf::open(path).context(GltfImportErr)?;
+        let base_dir = options
+            .base_dir
+            .clone()
+            .or_else(|| path.parent().map(Path::to_path_buf))
+            .unwrap_or_else(|| PathBuf::from("./"));
+
+        Self::from_document(document, blob, Some(&base_dir), options)
     }
 
     /// Imports a glTF scene from an in-memory byte slice.
     pub fn from_slice(bytes: &[u8]) -> Result<Self, Error> {
-        let (doc, buffers, images) = gltf::import_slice(bytes).context(GltfImportErr)?;
+        Self::from_slice_with(bytes, &SceneLoadOptions::default())
+    }
+
+    /// Imports a glTF scene from an in-memory byte slice. External files can only be loaded if
+    /// a base directory or path remap is set in `options`.
+    pub fn from_slice_with(bytes: &[u8], options: &SceneLoadOptions) -> Result<Self, Error> {
+        let gltf::Gltf { document, blob } = gltf::Gltf::from_slice(bytes).context(GltfImportErr)?;
+        Self::from_document(document, blob, options.base_dir.as_deref(), options)
+    }
+
+    fn from_document(
+        doc: Document,
+        blob: Option<Vec<u8>>,
+        base_dir: Option<&Path>,
+        options: &SceneLoadOptions,
+    ) -> Result<Self, Error> {
+        let buffers = gltf::import_buffers(&doc, base_dir, blob).context(GltfImportErr)?;
+        let images = textures::import_images(&doc, base_dir, &buffers, options);
         Ok(Self {
             doc,
             buffers,
@@ -60,6 +124,10 @@ impl GltfScene {
     }
 }
 
+/// The custom property (glTF node extra) that's read as the [persistent id](crate::core::GameObject::set_uuid)
+/// of the spawned object
+pub const UUID_PROPERTY: &str = "uuid";
+
 /// Loader utilities for bringing glTF content into the engine.
 pub struct SceneLoader;
 
@@ -67,13 +135,37 @@ impl SceneLoader {
     /// Loads a glTF file from disk and spawns its root into the world.
     pub fn load(world: &mut World, path: &str) -> Result<GameObjectId, Error> {
         let scene = GltfScene::import(path)?;
-        Self::load_into_world(world, &scene)
+        Self::load_into_world(world, &scene, &SceneLoadOptions::default())
+    }
+
+    /// Loads a glTF file from disk like [`load`](Self::load), resolving external textures as
+    /// described by `options`.
+    ///
+    /// Embedded textures are always decoded from the file itself. Textures that can't be found
+    /// are logged with the attempted path and left out of their material.
+    pub fn load_with(
+        world: &mut World,
+        path: &str,
+        options: SceneLoadOptions,
+    ) -> Result<GameObjectId, Error> {
+        let scene = GltfScene::import_with(path, &options)?;
+        Self::load_into_world(world, &scene, &options)
     }
 
     /// Loads a glTF scene from memory and spawns its root into the world.
     pub fn load_buffer(world: &mut World, model: &[u8]) -> Result<GameObjectId, Error> {
         let scene = Self::load_scene_from_buffer(model)?;
-        Self::load_into_world(world, &scene)
+        Self::load_into_world(world, &scene, &SceneLoadOptions::default())
+    }
+
+    /// Loads a glTF scene from memory, resolving external files as described by `options`.
+    pub fn load_buffer_with(
+        world: &mut World,
+        model: &[u8],
+        options: SceneLoadOptions,
+    ) -> Result<GameObjectId, Error> {
+        let scene = GltfScene::from_slice_with(model, &options)?;
+        Self::load_into_world(world, &scene, &options)
     }
 
     /// Parses a glTF scene directly from an in-memory buffer.
@@ -99,7 +191,11 @@ impl SceneLoader {
     }
 
     /// Spawns the glTF scene graph into the world and returns the created root object.
-    fn load_into_world(world: &mut World, gltf_scene: &GltfScene) -> Result<GameObjectId, Error> {
+    fn load_into_world(
+        world: &mut World,
+        gltf_scene: &GltfScene,
+        options: &SceneLoadOptions,
+    ) -> Result<GameObjectId, Error> {
         let doc = &gltf_scene.doc;
         let root_scene = doc
             .default_scene()
@@ -111,7 +207,7 @@ impl SceneLoader {
 
         let mut root = world.new_object("glTF Scene");
         for node in root_scene.nodes() {
-            let child = Self::spawn_node(world, gltf_scene, node, Some(&materials));
+            let child = Self::spawn_node(world, gltf_scene, node, Some(&materials), options);
             root.add_child(child);
         }
 
@@ -136,6 +232,7 @@ impl SceneLoader {
         scene: &GltfScene,
         node: Node,
         materials: Option<&HashMap<u32, HMaterial>>,
+        options: &SceneLoadOptions,
     ) -> GameObjectId {
         let name = node.name().unwrap_or("Unnamed").to_string();
         trace!("Starting to build scene object {name:?}");
@@ -151,7 +248,20 @@ impl SceneLoader {
             }
         }
 
-        if let Some((mesh, mats)) = meshes::load_mesh(scene, node.clone()) {
+        let uuid = obj
+            .property(UUID_PROPERTY)
+            .and_then(|uuid| uuid.as_str())
+            .and_then(|uuid| Uuid::parse_str(uuid).ok());
+        if let Some(uuid) = uuid
+            && let Err(e) = obj.set_uuid(uuid)
+        {
+            warn!("Couldn't assign the uuid of scene node {:?}: {e}", obj.name);
+        }
+
+        if let Some((mut mesh, mats)) = meshes::load_mesh(scene, node.clone()) {
+            if let Some(epsilon) = options.weld_epsilon {
+                mesh.optimize_with_epsilon(epsilon);
+            }
             Self::attach_mesh(world, materials, &mut obj, mesh, mats);
         }
 
@@ -164,7 +274,7 @@ impl SceneLoader {
         load_node_light(node.clone(), obj);
 
         for child in node.children() {
-            let c = Self::spawn_node(world, scene, child, materials);
+            let c = Self::spawn_node(world, scene, child, materials, options);
             obj.add_child(c);
         }
 
@@ -1,12 +1,127 @@
-use super::GltfScene;
+use super::{GltfScene, SceneLoadOptions};
 use crate::World;
-use crate::assets::{HMaterial, HShader, HTexture, Material, StoreType, Texture};
-use gltf::image::Format;
+use crate::assets::{HMaterial, HTexture, Material, StoreType, Texture};
+use gltf::Document;
+use gltf::image::{Format, Source};
 use nalgebra::Vector3;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use syrillian_utils::debug_panic;
+use tracing::warn;
 use wgpu::TextureFormat;
 
+/// Decodes all images of the document.
+///
+/// Embedded images are decoded from their buffer or data URI. External images go through the
+/// path remap of `options` and are resolved against `base_dir`. If that file doesn't exist, the
+/// file name is looked up in `base_dir` directly, which covers absolute paths from other machines.
+/// Images that can't be loaded are logged and left empty, so the indices still match the
+/// document.
+pub(super) fn import_images(
+    doc: &Document,
+    base_dir: Option<&Path>,
+    buffers: &[gltf::buffer::Data],
+    options: &SceneLoadOptions,
+) -> Vec<gltf::image::Data> {
+    doc.images()
+        .map(|image| match image.source() {
+            Source::Uri { uri, .. } if !uri.starts_with("data:") => {
+                import_external_image(uri, base_dir, options)
+            }
+            source => {
+                // data URIs don't touch the file system, but are rejected without a base directory
+                let base_dir = base_dir.or(Some(Path::new("./")));
+                gltf::image::Data::from_source(source, base_dir, buffers)
+                    .inspect_err(|e| {
+                        warn!("Failed to decode embedded texture {}: {e}", image.index())
+                    })
+                    .ok()
+            }
+        })
+        .map(|image| image.unwrap_or_else(empty_image))
+        .collect()
+}
+
+/// Stands in for an image that couldn't be loaded
+fn empty_image() -> gltf::image::Data {
+    gltf::image::Data {
+        pixels: Vec::new(),
+        format: Format::R8G8B8A8,
+        width: 0,
+        height: 0,
+    }
+}
+
+fn import_external_image(
+    uri: &str,
+    base_dir: Option<&Path>,
+    options: &SceneLoadOptions,
+) -> Option<gltf::image::Data> {
+    let path = match &options.remap_path {
+        Some(remap) => remap(uri),
+        None => PathBuf::from(decode_uri(uri)),
+    };
+    let base_dir = base_dir.unwrap_or(Path::new("./"));
+
+    let mut candidates = vec![base_dir.join(&path)];
+    let file_name = uri.rsplit(['/', '\\']).next().map(decode_uri);
+    if let Some(file_name) = file_name.filter(|name| !name.is_empty()) {
+        candidates.push(base_dir.join(file_name));
+    }
+
+    for candidate in &candidates {
+        if !candidate.is_file() {
+            continue;
+        }
+        match read_image(candidate) {
+            Ok(image) => return Some(image),
+            Err(e) => warn!("Failed to load texture {}: {e}", candidate.display()),
+        }
+    }
+
+    warn!("Texture {uri:?} not found, tried {candidates:?}. Using no texture instead.");
+    None
+}
+
+fn read_image(path: &Path) -> Result<gltf::image::Data, image::ImageError> {
+    let image = image::open(path)?.to_rgba8();
+    Ok(gltf::image::Data {
+        width: image.width(),
+        height: image.height(),
+        format: Format::R8G8B8A8,
+        pixels: image.into_raw(),
+    })
+}
+
+/// Strips a `file:` scheme and decodes percent escapes in a glTF URI
+fn decode_uri(uri: &str) -> String {
+    let uri = uri
+        .strip_prefix("file://")
+        .or_else(|| uri.strip_prefix("file:"))
+        .unwrap_or(uri);
+
+    let mut bytes = Vec::with_capacity(uri.len());
+    let mut rest = uri.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let escaped = tail
+            .get(..2)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(decoded) if byte == b'%' => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            _ => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
 /// Loads all materials defined in the glTF scene and stores them in the asset store.
 pub(super) fn load_materials(scene: &GltfScene, world: &mut World) -> HashMap<u32, HMaterial> {
     let mut map = HashMap::new();
@@ -21,26 +136,24 @@ pub(super) fn load_materials(scene: &GltfScene, world: &mut World) -> HashMap<u3
         let metallic = pbr.metallic_factor();
         let roughness = pbr.roughness_factor();
 
-        let diffuse_texture = load_texture(scene, world, pbr.base_color_texture());
-        let normal_texture = load_texture(scene, world, mat.normal_texture());
-        let roughness_texture = load_texture(scene, world, pbr.metallic_roughness_texture());
+        let diffuse_texture = load_texture(scene, world, pbr.base_color_texture(), true);
+        let normal_texture = load_texture(scene, world, mat.normal_texture(), false);
+        let metallic_roughness_texture =
+            load_texture(scene, world, pbr.metallic_roughness_texture(), false);
 
         let lit = !mat.unlit();
 
-        let material = Material {
-            name,
-            color,
-            roughness,
-            metallic,
-            diffuse_texture,
-            normal_texture,
-            roughness_texture,
-            alpha,
-            lit,
-            cast_shadows: true,
-            shader: HShader::DIM3,
-            has_transparency: false,
-        };
+        let material = Material::builder()
+            .name(name)
+            .color(color)
+            .roughness(roughness)
+            .metallic(metallic)
+            .maybe_diffuse_texture(diffuse_texture)
+            .maybe_normal_texture(normal_texture)
+            .maybe_metallic_roughness_texture(metallic_roughness_texture)
+            .alpha(alpha)
+            .lit(lit)
+            .build();
         map.insert(i as u32, world.assets.materials.add(material));
     }
 
@@ -48,28 +161,34 @@ pub(super) fn load_materials(scene: &GltfScene, world: &mut World) -> HashMap<u3
 }
 
 /// Converts a glTF texture reference into an engine texture handle.
+///
+/// Only color textures are `srgb`. Data like normals or metallic/roughness must stay linear.
 pub(super) fn load_texture<'a, T>(
     scene: &'a GltfScene,
     world: &mut World,
     info: Option<T>,
+    srgb: bool,
 ) -> Option<HTexture>
 where
     T: AsRef<gltf::texture::Texture<'a>>,
 {
     let tex = info.as_ref()?.as_ref();
-    let image = tex.source();
-    let index = image.index();
+    let image = &scene.images[tex.source().index()];
+    if image.pixels.is_empty() {
+        // couldn't be loaded, which was already reported on import
+        return None;
+    }
 
-    let pixels = &scene.images[index].pixels;
+    let pixels = &image.pixels;
     let mut data = Vec::new();
-    let (width, height) = (scene.images[index].width, scene.images[index].height);
-    let original_format = scene.images[index].format;
+    let (width, height) = (image.width, image.height);
+    let original_format = image.format;
 
     let format = match original_format {
         Format::R8 => TextureFormat::R8Unorm,
         Format::R8G8 => TextureFormat::Rg8Unorm,
-        Format::R8G8B8 => TextureFormat::Rgba8UnormSrgb,
-        Format::R8G8B8A8 => TextureFormat::Rgba8UnormSrgb,
+        Format::R8G8B8 | Format::R8G8B8A8 if srgb => TextureFormat::Rgba8UnormSrgb,
+        Format::R8G8B8 | Format::R8G8B8A8 => TextureFormat::Rgba8Unorm,
         Format::R16 => TextureFormat::R16Unorm,
         Format::R16G16 => TextureFormat::Rg16Snorm,
         Format::R16G16B16 => {
@@ -0,0 +1,115 @@
+use super::shader_gen::expand_builtin_includes;
+use crate::assets::{ShaderIncludeError, ShaderIncludes, ShaderValidationError};
+use crate::engine::assets::generic_store::{HandleName, StoreType};
+use crate::engine::assets::{H, StoreTypeName};
+use crate::utils::validate_wgsl_source;
+use bon::Builder;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+/// A WGSL compute shader, dispatched through a [`ComputeJob`](crate::rendering::ComputeJob) or
+/// on the render thread with a [`RuntimeComputeShader`](crate::rendering::RuntimeComputeShader).
+///
+/// Unlike render shaders, the code is used as-is, only `#include` directives are expanded.
+/// Buffers are bound in group 0, in the order they're added to the job.
+#[derive(Debug, Clone, Builder)]
+pub struct ComputeShader {
+    #[builder(into)]
+    name: String,
+    #[builder(into)]
+    code: String,
+    #[builder(into, default = "main")]
+    entry_point: String,
+}
+
+impl ComputeShader {
+    pub fn new<S, S2>(name: S, code: S2) -> ComputeShader
+    where
+        S: Into<String>,
+        S2: Into<String>,
+    {
+        ComputeShader {
+            name: name.into(),
+            code: code.into(),
+            entry_point: "main".to_string(),
+        }
+    }
+
+    pub fn load<S, T>(name: S, path: T) -> Result<ComputeShader, Box<dyn Error>>
+    where
+        S: Into<String>,
+        T: AsRef<Path>,
+    {
+        let code = fs::read_to_string(path)?;
+        Ok(Self::new(name, code))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn entry_point(&self) -> &str {
+        &self.entry_point
+    }
+
+    pub fn set_code(&mut self, source: String) {
+        self.code = source;
+    }
+
+    /// Returns the code with the built-in includes expanded
+    pub fn try_gen_code(&self) -> Result<String, ShaderIncludeError> {
+        expand_builtin_includes(&self.name, &self.code).map(|code| code.into_owned())
+    }
+
+    /// Returns a copy of the shader with the `#include` lines replaced by the snippets from
+    /// `includes`
+    pub fn with_includes(
+        &self,
+        includes: &ShaderIncludes,
+    ) -> Result<ComputeShader, ShaderIncludeError> {
+        let code = includes.expand(&self.name, &self.code)?.into_owned();
+        Ok(ComputeShader {
+            code,
+            ..self.clone()
+        })
+    }
+
+    /// Expands includes and validates the code with naga, see [`Shader::validate`](crate::assets::Shader::validate)
+    pub fn validate(&self) -> Result<(), ShaderValidationError> {
+        let code = self
+            .try_gen_code()
+            .map_err(|source| ShaderValidationError::Include { source })?;
+        validate_wgsl_source(&code).map_err(|e| ShaderValidationError::Invalid {
+            name: self.name.clone(),
+            diagnostic: e.emit_to_string(&code),
+        })?;
+        Ok(())
+    }
+}
+
+impl StoreTypeName for ComputeShader {
+    #[inline]
+    fn name(&self) -> &str {
+        self.name()
+    }
+}
+
+impl StoreType for ComputeShader {
+    #[inline]
+    fn name() -> &'static str {
+        "Compute Shader"
+    }
+
+    fn ident_fmt(handle: H<Self>) -> HandleName<Self> {
+        HandleName::Id(handle)
+    }
+
+    fn is_builtin(_handle: H<Self>) -> bool {
+        false
+    }
+}
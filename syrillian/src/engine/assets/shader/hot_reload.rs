@@ -0,0 +1,130 @@
+use crate::assets::{HShader, Shader, ShaderCode, ShaderIncludes, ShaderValidationError, Store};
+use snafu::{ResultExt, Snafu};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tracing::{error, info, warn};
+
+/// The file a watched shader is reloaded from
+#[derive(Debug, Clone)]
+pub(crate) struct WatchedSource {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(context(suffix(Err)))]
+pub enum ShaderReloadError {
+    #[snafu(display("failed to read {}: {source}", path.display()))]
+    Read { path: PathBuf, source: io::Error },
+
+    #[snafu(display("{source}"))]
+    Invalid { source: ShaderValidationError },
+}
+
+/// Emitted on [`World::events`](crate::World::events) after a watched shader file changed.
+///
+/// If the reload failed, the shader keeps its last working code.
+#[derive(Debug)]
+pub struct ShaderReloaded {
+    pub shader: HShader,
+    pub path: PathBuf,
+    pub result: Result<(), ShaderReloadError>,
+}
+
+impl Store<Shader> {
+    /// Reloads the shader from `path` whenever the file changes. The new code is validated
+    /// first, and the pipeline is only swapped if it's valid.
+    ///
+    /// The file is expected to hold the same kind of code the shader already has, so a fragment
+    /// shader keeps getting the default vertex stage. Every reload sends a [`ShaderReloaded`]
+    /// event. Returns false if the handle is invalid.
+    pub fn watch(&self, h: HShader, path: impl Into<PathBuf>) -> bool {
+        let path = path.into();
+        let modified = modified_time(&path);
+        if modified.is_none() {
+            warn!(
+                "Can't watch shader file {}: it's not readable",
+                path.display()
+            );
+        }
+
+        let Some(mut shader) = self.get_mut_untracked(h) else {
+            return false;
+        };
+        shader.watched = Some(WatchedSource { path, modified });
+        true
+    }
+
+    /// Stops reloading the shader when its file changes
+    pub fn unwatch(&self, h: HShader) {
+        if let Some(mut shader) = self.get_mut_untracked(h) {
+            shader.watched = None;
+        }
+    }
+
+    /// Reloads all watched shaders whose file changed since the last call, validating them with
+    /// `includes`
+    pub(crate) fn reload_watched(&self, includes: &ShaderIncludes) -> Vec<ShaderReloaded> {
+        let mut changed = Vec::new();
+        for mut entry in self.items_mut() {
+            let shader = HShader::from(*entry.key());
+            let Some(watched) = &mut entry.value_mut().watched else {
+                continue;
+            };
+            // unreadable files are usually in the middle of being saved
+            let Some(modified) = modified_time(&watched.path) else {
+                continue;
+            };
+            if watched.modified != Some(modified) {
+                watched.modified = Some(modified);
+                changed.push((shader, watched.path.clone()));
+            }
+        }
+
+        changed
+            .into_iter()
+            .map(|(shader, path)| {
+                let result = self.reload(shader, &path, includes);
+                match &result {
+                    Ok(()) => info!("Reloaded shader {}", path.display()),
+                    Err(e) => error!("Failed to reload shader {}: {e}", path.display()),
+                }
+                ShaderReloaded {
+                    shader,
+                    path,
+                    result,
+                }
+            })
+            .collect()
+    }
+
+    fn reload(
+        &self,
+        h: HShader,
+        path: &Path,
+        includes: &ShaderIncludes,
+    ) -> Result<(), ShaderReloadError> {
+        let source = fs::read_to_string(path).context(ReadErr { path })?;
+
+        let Some(mut candidate) = self.get_checked(h).map(|shader| shader.clone()) else {
+            return Ok(());
+        };
+        candidate.code = match candidate.code {
+            ShaderCode::Full(_) => ShaderCode::Full(source),
+            ShaderCode::Fragment(_) => ShaderCode::Fragment(source),
+        };
+
+        candidate.validated_with(includes).context(InvalidErr)?;
+
+        if let Some(mut shader) = self.try_get_mut(h) {
+            shader.code = candidate.code;
+        }
+        Ok(())
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
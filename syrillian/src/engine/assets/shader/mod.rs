@@ -1,5 +1,12 @@
+mod compute;
+mod hot_reload;
 mod shader_gen;
+mod validation;
+pub use compute::ComputeShader;
+pub use hot_reload::{ShaderReloadError, ShaderReloaded};
 pub(crate) use shader_gen::ShaderGen;
+pub use shader_gen::{ShaderIncludeError, ShaderIncludes};
+pub use validation::ShaderValidationError;
 
 // this module only has tests for the built-in shaders and can be safely ignored
 #[cfg(test)]
@@ -8,17 +15,26 @@ mod shaders;
 use crate::assets::HBGL;
 use crate::engine::assets::generic_store::{HandleName, Store, StoreDefaults, StoreType};
 use crate::engine::assets::{H, HShader, StoreTypeFallback, StoreTypeName};
+use crate::rendering::proxies::mesh_proxy::OutlineImmediates;
+use crate::rendering::proxies::particle_proxy::ParticleInstance;
 use crate::rendering::proxies::text_proxy::TextImmediates;
-use crate::rendering::{AssetCache, DEFAULT_COLOR_TARGET, DEFAULT_VBL, PICKING_TEXTURE_FORMAT};
-use crate::utils::sizes::{VEC2_SIZE, VEC4_SIZE};
+use crate::rendering::strobe::NineSliceImmediates;
+use crate::rendering::{
+    AMBIENT_OCCLUSION_FORMAT, AssetCache, DEFAULT_COLOR_TARGET, DEFAULT_VBL, GBUFFER_FORMAT,
+    PICKING_TEXTURE_FORMAT,
+};
+use crate::utils::sizes::{VEC2_SIZE, VEC3_SIZE, VEC4_SIZE};
 use crate::{store_add_checked, store_add_checked_many};
 use bon::Builder;
+use nalgebra::Matrix4;
+use smallvec::SmallVec;
+use std::borrow::Cow;
 use std::error::Error;
 use std::fs;
 use std::path::Path;
 use std::sync::Arc;
 use wgpu::{
-    BindGroupLayout, ColorTargetState, ColorWrites, Device, PipelineLayout,
+    BindGroupLayout, ColorTargetState, ColorWrites, CompareFunction, Device, PipelineLayout,
     PipelineLayoutDescriptor, PolygonMode, PrimitiveTopology, VertexAttribute, VertexBufferLayout,
     VertexFormat, VertexStepMode,
 };
@@ -71,7 +87,13 @@ pub struct Shader {
     shadow_transparency: bool,
     #[builder(default = true)]
     depth_enabled: bool,
+    #[builder(default = CompareFunction::LessEqual)]
+    depth_compare: CompareFunction,
+    #[builder(default = true)]
+    depth_write: bool,
     shader_type: ShaderType,
+    #[builder(skip)]
+    watched: Option<hot_reload::WatchedSource>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -81,6 +103,7 @@ pub struct BindGroupMap {
     pub material: Option<u32>,
     pub light: Option<u32>,
     pub shadow: Option<u32>,
+    pub depth: Option<u32>,
     pub post_process: Option<u32>,
 }
 
@@ -104,23 +127,31 @@ impl H<Shader> {
     pub const TEXT_2D_PICKER_ID: u32 = 7;
     pub const TEXT_3D_ID: u32 = 8;
     pub const TEXT_3D_PICKER_ID: u32 = 9;
+    pub const DECAL_ID: u32 = 10;
+    pub const PARTICLE_ID: u32 = 11;
+    pub const GBUFFER_ID: u32 = 12;
+    pub const OUTLINE_ID: u32 = 13;
+    pub const OUTLINE_ON_TOP_ID: u32 = 14;
+    pub const NINE_SLICE_ID: u32 = 15;
+    pub const AMBIENT_OCCLUSION_ID: u32 = 16;
+    pub const VIEWPORT_CLEAR_ID: u32 = 17;
     #[cfg(not(debug_assertions))]
-    pub const MAX_BUILTIN_ID: u32 = 9;
+    pub const MAX_BUILTIN_ID: u32 = 17;
 
     #[cfg(debug_assertions)]
-    pub const DEBUG_EDGES_ID: u32 = 10;
+    pub const DEBUG_EDGES_ID: u32 = 18;
     #[cfg(debug_assertions)]
-    pub const DEBUG_VERTEX_NORMALS_ID: u32 = 11;
+    pub const DEBUG_VERTEX_NORMALS_ID: u32 = 19;
     #[cfg(debug_assertions)]
-    pub const DEBUG_LINES_ID: u32 = 12;
+    pub const DEBUG_LINES_ID: u32 = 20;
     #[cfg(debug_assertions)]
-    pub const DEBUG_TEXT2D_GEOMETRY_ID: u32 = 13;
+    pub const DEBUG_TEXT2D_GEOMETRY_ID: u32 = 21;
     #[cfg(debug_assertions)]
-    pub const DEBUG_TEXT3D_GEOMETRY_ID: u32 = 14;
+    pub const DEBUG_TEXT3D_GEOMETRY_ID: u32 = 22;
     #[cfg(debug_assertions)]
-    pub const DEBUG_LIGHT_ID: u32 = 15;
+    pub const DEBUG_LIGHT_ID: u32 = 23;
     #[cfg(debug_assertions)]
-    pub const MAX_BUILTIN_ID: u32 = 15;
+    pub const MAX_BUILTIN_ID: u32 = 23;
 
     // The fallback shader if a pipeline fails
     pub const FALLBACK: H<Shader> = H::new(Self::FALLBACK_ID);
@@ -152,6 +183,30 @@ impl H<Shader> {
     // Default 3D Text picking shader.
     pub const TEXT_3D_PICKING: H<Shader> = H::new(Self::TEXT_3D_PICKER_ID);
 
+    // Projects decal textures onto the scene depth.
+    pub const DECAL: H<Shader> = H::new(Self::DECAL_ID);
+
+    // Camera-facing, instanced particle quads.
+    pub const PARTICLE: H<Shader> = H::new(Self::PARTICLE_ID);
+
+    // Writes world space normals and positions of meshes into the G-buffer.
+    pub const GBUFFER: H<Shader> = H::new(Self::GBUFFER_ID);
+
+    // Draws the grown back faces of a mesh as its outline, hidden behind other objects.
+    pub const OUTLINE: H<Shader> = H::new(Self::OUTLINE_ID);
+
+    // Like the outline shader, but drawn on top of everything.
+    pub const OUTLINE_ON_TOP: H<Shader> = H::new(Self::OUTLINE_ON_TOP_ID);
+
+    // Draws 2D images with fixed corners and stretched or tiled edges and center.
+    pub const NINE_SLICE: H<Shader> = H::new(Self::NINE_SLICE_ID);
+
+    // Writes the screen-space ambient occlusion that the 3D shader darkens its ambient light with.
+    pub const AMBIENT_OCCLUSION: H<Shader> = H::new(Self::AMBIENT_OCCLUSION_ID);
+
+    // Clears the depth of a camera that only covers part of its render target.
+    pub const VIEWPORT_CLEAR: H<Shader> = H::new(Self::VIEWPORT_CLEAR_ID);
+
     // An addon shader ID that is used for drawing debug edges on meshes
     #[cfg(debug_assertions)]
     pub const DEBUG_EDGES: H<Shader> = H::new(Self::DEBUG_EDGES_ID);
@@ -181,6 +236,13 @@ const SHADER_TEXT2D_PICKER: &str = include_str!("shaders/picking_text2d.wgsl");
 const SHADER_TEXT3D: &str = include_str!("shaders/text3d.wgsl");
 const SHADER_TEXT3D_PICKER: &str = include_str!("shaders/picking_text3d.wgsl");
 const SHADER_FS_COPY: &str = include_str!("shaders/fullscreen_passthrough.wgsl");
+const SHADER_DECAL: &str = include_str!("shaders/decal.wgsl");
+const SHADER_PARTICLE: &str = include_str!("shaders/particle.wgsl");
+const SHADER_GBUFFER: &str = include_str!("shaders/gbuffer_mesh.wgsl");
+const SHADER_OUTLINE: &str = include_str!("shaders/outline.wgsl");
+const SHADER_NINE_SLICE: &str = include_str!("shaders/nine_slice.wgsl");
+const SHADER_AMBIENT_OCCLUSION: &str = include_str!("shaders/ambient_occlusion.wgsl");
+const SHADER_VIEWPORT_CLEAR: &str = include_str!("shaders/viewport_clear.wgsl");
 
 #[cfg(debug_assertions)]
 const DEBUG_EDGES_SHADER: &str = include_str!("shaders/debug/edges.wgsl");
@@ -195,6 +257,9 @@ const DEBUG_TEXT3D_GEOMETRY: &str = include_str!("shaders/debug/text3d_geometry.
 #[cfg(debug_assertions)]
 const DEBUG_LIGHT_SHADER: &str = include_str!("shaders/debug/light.wgsl");
 
+/// Render, model, material, light, shadow and depth, the most bind groups a shader can use
+const MAX_BIND_GROUPS: usize = 6;
+
 impl StoreDefaults for Shader {
     fn populate(store: &mut Store<Self>) {
         store_add_checked_many!(store,
@@ -206,7 +271,7 @@ impl StoreDefaults for Shader {
         );
 
         const TEXT_VBL: &[VertexBufferLayout] = &[VertexBufferLayout {
-            array_stride: VEC2_SIZE * 2,
+            array_stride: VEC2_SIZE * 2 + VEC4_SIZE,
             step_mode: VertexStepMode::Vertex,
             attributes: &[
                 VertexAttribute {
@@ -219,6 +284,11 @@ impl StoreDefaults for Shader {
                     offset: VEC2_SIZE,
                     shader_location: 1,
                 },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: VEC2_SIZE * 2,
+                    shader_location: 2,
+                },
             ],
         }];
 
@@ -307,9 +377,149 @@ impl StoreDefaults for Shader {
                 .build()
         );
 
+        store_add_checked!(
+            store,
+            HShader::DECAL_ID,
+            Shader::builder()
+                .shader_type(ShaderType::Custom)
+                .name("Decal Shader")
+                .code(ShaderCode::Full(SHADER_DECAL.to_string()))
+                .vertex_buffers(&[])
+                .immediate_size(size_of::<Matrix4<f32>>() as u32)
+                .depth_enabled(false)
+                .build()
+        );
+
+        const PARTICLE_VBL: &[VertexBufferLayout] = &[VertexBufferLayout {
+            array_stride: size_of::<ParticleInstance>() as u64,
+            step_mode: VertexStepMode::Instance,
+            attributes: &[
+                VertexAttribute {
+                    format: VertexFormat::Float32x3, // position
+                    offset: 0,
+                    shader_location: 0,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32, // size
+                    offset: VEC3_SIZE,
+                    shader_location: 1,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4, // color
+                    offset: VEC4_SIZE,
+                    shader_location: 2,
+                },
+            ],
+        }];
+
+        store_add_checked!(
+            store,
+            HShader::PARTICLE_ID,
+            Shader::builder()
+                .shader_type(ShaderType::Custom)
+                .name("Particle Shader")
+                .code(ShaderCode::Full(SHADER_PARTICLE.to_string()))
+                .vertex_buffers(PARTICLE_VBL)
+                .build()
+        );
+
+        const GBUFFER_COLOR_TARGET: &[Option<ColorTargetState>] = &[
+            Some(ColorTargetState {
+                format: GBUFFER_FORMAT,
+                blend: None,
+                write_mask: ColorWrites::all(),
+            }),
+            Some(ColorTargetState {
+                format: GBUFFER_FORMAT,
+                blend: None,
+                write_mask: ColorWrites::all(),
+            }),
+        ];
+
+        store_add_checked!(
+            store,
+            HShader::GBUFFER_ID,
+            Shader::builder()
+                .shader_type(ShaderType::Custom)
+                .name("G-Buffer Shader")
+                .code(ShaderCode::Fragment(SHADER_GBUFFER.to_string()))
+                .color_target(GBUFFER_COLOR_TARGET)
+                .build()
+        );
+
+        // the outline is drawn after the object, so it must not hide it by writing depth
+        store_add_checked!(
+            store,
+            HShader::OUTLINE_ID,
+            Shader::builder()
+                .shader_type(ShaderType::Custom)
+                .name("Outline Shader")
+                .code(ShaderCode::Full(SHADER_OUTLINE.to_string()))
+                .immediate_size(size_of::<OutlineImmediates>() as u32)
+                .depth_write(false)
+                .build()
+        );
+
+        store_add_checked!(
+            store,
+            HShader::OUTLINE_ON_TOP_ID,
+            Shader::builder()
+                .shader_type(ShaderType::Custom)
+                .name("Outline On Top Shader")
+                .code(ShaderCode::Full(SHADER_OUTLINE.to_string()))
+                .immediate_size(size_of::<OutlineImmediates>() as u32)
+                .depth_compare(CompareFunction::Always)
+                .depth_write(false)
+                .build()
+        );
+
+        store_add_checked!(
+            store,
+            HShader::NINE_SLICE_ID,
+            Shader::builder()
+                .shader_type(ShaderType::Custom)
+                .name("Nine Slice Shader")
+                .code(ShaderCode::Full(SHADER_NINE_SLICE.to_string()))
+                .immediate_size(size_of::<NineSliceImmediates>() as u32)
+                .depth_enabled(false)
+                .build()
+        );
+
+        const AMBIENT_OCCLUSION_COLOR_TARGET: &[Option<ColorTargetState>] =
+            &[Some(ColorTargetState {
+                format: AMBIENT_OCCLUSION_FORMAT,
+                blend: None,
+                write_mask: ColorWrites::all(),
+            })];
+
+        store_add_checked!(
+            store,
+            HShader::AMBIENT_OCCLUSION_ID,
+            Shader::builder()
+                .shader_type(ShaderType::PostProcessing)
+                .name("Ambient Occlusion Shader")
+                .code(ShaderCode::Full(SHADER_AMBIENT_OCCLUSION.to_string()))
+                .color_target(AMBIENT_OCCLUSION_COLOR_TARGET)
+                .depth_enabled(false)
+                .build()
+        );
+
+        store_add_checked!(
+            store,
+            HShader::VIEWPORT_CLEAR_ID,
+            Shader::builder()
+                .shader_type(ShaderType::Custom)
+                .name("Viewport Clear Shader")
+                .code(ShaderCode::Full(SHADER_VIEWPORT_CLEAR.to_string()))
+                .vertex_buffers(&[])
+                .color_target(&[])
+                .depth_compare(CompareFunction::Always)
+                .build()
+        );
+
         #[cfg(debug_assertions)]
         {
-            use crate::utils::sizes::{VEC3_SIZE, WGPU_VEC4_ALIGN};
+            use crate::utils::sizes::WGPU_VEC4_ALIGN;
             use wgpu::{VertexAttribute, VertexFormat, VertexStepMode};
 
             store_add_checked!(
@@ -449,6 +659,13 @@ impl StoreType for Shader {
             HShader::TEXT_2D_ID => "2D Text Shader",
             HShader::TEXT_3D_ID => "3D Text Shader",
             HShader::POST_PROCESS_ID => "Post Process Shader",
+            HShader::DECAL_ID => "Decal Shader",
+            HShader::GBUFFER_ID => "G-Buffer Shader",
+            HShader::OUTLINE_ID => "Outline Shader",
+            HShader::OUTLINE_ON_TOP_ID => "Outline On Top Shader",
+            HShader::NINE_SLICE_ID => "Nine Slice Shader",
+            HShader::AMBIENT_OCCLUSION_ID => "Ambient Occlusion Shader",
+            HShader::VIEWPORT_CLEAR_ID => "Viewport Clear Shader",
 
             #[cfg(debug_assertions)]
             HShader::DEBUG_EDGES_ID => "Debug Edges Shader",
@@ -508,7 +725,10 @@ impl Shader {
             immediate_size: 0,
             shadow_transparency: false,
             depth_enabled: false,
+            depth_compare: CompareFunction::LessEqual,
+            depth_write: true,
             shader_type: ShaderType::PostProcessing,
+            watched: None,
         }
     }
 
@@ -527,7 +747,10 @@ impl Shader {
             immediate_size: 0,
             shadow_transparency: false,
             depth_enabled: true,
+            depth_compare: CompareFunction::LessEqual,
+            depth_write: true,
             shader_type: ShaderType::Default,
+            watched: None,
         }
     }
 
@@ -546,7 +769,10 @@ impl Shader {
             immediate_size: 0,
             shadow_transparency: false,
             depth_enabled: true,
+            depth_compare: CompareFunction::LessEqual,
+            depth_write: true,
             shader_type: ShaderType::Default,
+            watched: None,
         }
     }
 
@@ -586,6 +812,16 @@ impl Shader {
         self.depth_enabled
     }
 
+    /// How fragments are tested against the depth buffer, if depth is enabled
+    pub fn depth_compare(&self) -> CompareFunction {
+        self.depth_compare
+    }
+
+    /// Returns false if the shader's fragments don't occlude what's drawn after them
+    pub fn has_depth_write(&self) -> bool {
+        self.depth_write
+    }
+
     pub fn color_target(&self) -> &'static [Option<ColorTargetState>] {
         self.color_target
     }
@@ -615,16 +851,43 @@ impl Shader {
         self.shadow_transparency
     }
 
+    /// Generates the final WGSL. Only built-in snippets are included, see
+    /// [`with_includes`](Self::with_includes) for the ones registered on an
+    /// [`AssetStore`](crate::assets::AssetStore).
     pub fn gen_code(&self) -> String {
         let map = self.bind_group_map();
         ShaderGen::new(self, &map).generate()
     }
 
+    /// Returns a copy of the shader with the `#include` lines replaced by the snippets from
+    /// `includes`
+    pub fn with_includes(&self, includes: &ShaderIncludes) -> Result<Shader, ShaderIncludeError> {
+        let code = match &self.code {
+            ShaderCode::Full(code) => ShaderCode::Full(includes.expand(&self.name, code)?.into()),
+            ShaderCode::Fragment(code) => {
+                ShaderCode::Fragment(includes.expand(&self.name, code)?.into())
+            }
+        };
+        Ok(Shader {
+            code,
+            ..self.clone()
+        })
+    }
+
+    /// Like [`gen_code`](Self::gen_code), but fails if an `#include` can't be resolved
+    pub fn try_gen_code(&self) -> Result<String, ShaderIncludeError> {
+        let map = self.bind_group_map();
+        ShaderGen::new(self, &map).try_generate()
+    }
+
     pub fn needs_bgl(&self, bgl: HBGL) -> bool {
         if !self.is_custom() {
             if bgl == HBGL::LIGHT || bgl == HBGL::SHADOW {
                 return self.is_depth_enabled();
             }
+            if bgl == HBGL::DEPTH {
+                return false;
+            }
 
             return true;
         }
@@ -634,18 +897,24 @@ impl Shader {
             HBGL::MATERIAL_ID => "material",
             HBGL::LIGHT_ID => "light",
             HBGL::SHADOW_ID => "shadow",
+            HBGL::DEPTH_ID => "depth",
 
             HBGL::RENDER_ID => return true,
             _ => return false,
         };
-        let source = self.code().code();
+        // included snippets can declare their own groups
+        let code = self.code().code();
+        let source =
+            shader_gen::expand_builtin_includes(self.name(), code).unwrap_or(Cow::Borrowed(code));
 
         for line in source.lines() {
             let Some(i) = line.find("#use ") else {
                 continue;
             };
 
-            if line[i + 5..].trim() == use_name {
+            let used = line[i + 5..].trim();
+            // custom parameters live in the material group
+            if used == use_name || (use_name == "material" && used == "custom") {
                 return true;
             }
         }
@@ -676,13 +945,17 @@ impl Shader {
         }
         if self.needs_bgl(HBGL::SHADOW) {
             map.shadow = Some(idx);
+            idx += 1;
+        }
+        if self.needs_bgl(HBGL::DEPTH) {
+            map.depth = Some(idx);
         }
 
         map
     }
 
-    fn required_bgls(&self) -> Vec<HBGL> {
-        let mut out = Vec::new();
+    fn required_bgls(&self) -> SmallVec<[HBGL; MAX_BIND_GROUPS]> {
+        let mut out = SmallVec::new();
         out.push(HBGL::RENDER);
 
         if self.is_post_process() {
@@ -702,22 +975,32 @@ impl Shader {
         if self.needs_bgl(HBGL::SHADOW) {
             out.push(HBGL::SHADOW);
         }
+        if self.needs_bgl(HBGL::DEPTH) {
+            out.push(HBGL::DEPTH);
+        }
 
         out
     }
 
-    pub(crate) fn solid_layout(&self, device: &Device, cache: &AssetCache) -> PipelineLayout {
-        let layout_name = format!("{} Pipeline Layout", self.name());
-        let bgls = self.required_bgls();
-        let layouts: Vec<Arc<BindGroupLayout>> = bgls
+    fn required_layouts(
+        &self,
+        cache: &AssetCache,
+    ) -> SmallVec<[Arc<BindGroupLayout>; MAX_BIND_GROUPS]> {
+        self.required_bgls()
             .iter()
             .map(|handle| {
                 cache
                     .bgl(*handle)
                     .expect("required bind group layout should exist")
             })
-            .collect();
-        let refs: Vec<&BindGroupLayout> = layouts.iter().map(|l| l.as_ref()).collect();
+            .collect()
+    }
+
+    pub(crate) fn solid_layout(&self, device: &Device, cache: &AssetCache) -> PipelineLayout {
+        let layout_name = format!("{} Pipeline Layout", self.name());
+        let layouts = self.required_layouts(cache);
+        let refs: SmallVec<[&BindGroupLayout; MAX_BIND_GROUPS]> =
+            layouts.iter().map(|l| l.as_ref()).collect();
 
         self.layout_with(device, &layout_name, &refs)
     }
@@ -732,16 +1015,9 @@ impl Shader {
         }
 
         let layout_name = format!("{} Shadow Pipeline Layout", self.name());
-        let bgls = self.required_bgls();
-        let layouts: Vec<Arc<BindGroupLayout>> = bgls
-            .iter()
-            .map(|handle| {
-                cache
-                    .bgl(*handle)
-                    .expect("required bind group layout should exist")
-            })
-            .collect();
-        let refs: Vec<&BindGroupLayout> = layouts.iter().map(|l| l.as_ref()).collect();
+        let layouts = self.required_layouts(cache);
+        let refs: SmallVec<[&BindGroupLayout; MAX_BIND_GROUPS]> =
+            layouts.iter().map(|l| l.as_ref()).collect();
 
         Some(self.layout_with(device, &layout_name, &refs))
     }
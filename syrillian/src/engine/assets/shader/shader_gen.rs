@@ -1,5 +1,9 @@
-use crate::assets::{BindGroupMap, Shader, ShaderCode, ShaderType};
-use tracing::warn;
+use crate::assets::{BindGroupMap, Shader, ShaderType};
+use snafu::{Snafu, ensure};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use tracing::{error, warn};
 
 const POST_PROCESS_HEADER: &str = include_str!("shaders/groups/post_process.wgsl");
 const DEFAULT_HEADER: &str = include_str!("shaders/groups/basic.wgsl");
@@ -7,9 +11,92 @@ const LIGHT_GROUP: &str = include_str!("shaders/groups/light.wgsl");
 const BASE_GROUP: &str = include_str!("shaders/groups/render.wgsl");
 const MODEL_GROUP: &str = include_str!("shaders/groups/model.wgsl");
 const MATERIAL_GROUP: &str = include_str!("shaders/groups/material.wgsl");
-const DEFAULT_VERTEX_3D: &str = include_str!("shaders/default_vertex3d.wgsl");
+const CUSTOM_GROUP: &str = include_str!("shaders/groups/custom.wgsl");
+const DEPTH_GROUP: &str = include_str!("shaders/groups/depth.wgsl");
+const SKINNING: &str = include_str!("shaders/skinning.wgsl");
+const MSDF: &str = include_str!("shaders/msdf.wgsl");
+const DEFAULT_VERTEX_3D: &str = concat!(
+    include_str!("shaders/skinning.wgsl"),
+    "\n",
+    include_str!("shaders/default_vertex3d.wgsl")
+);
 const POST_PROCESS_VERTEX: &str = include_str!("shaders/default_vertex_post.wgsl");
 
+/// Prefix of includes that are shipped with the engine, like `#include builtin:default_vertex3d`
+const BUILTIN_PREFIX: &str = "builtin:";
+const BUILTIN_INCLUDES: &[(&str, &str)] = &[
+    ("default_vertex3d", DEFAULT_VERTEX_3D),
+    ("skinning", SKINNING),
+    ("msdf", MSDF),
+    ("default_vertex_post", POST_PROCESS_VERTEX),
+];
+
+#[derive(Debug, Snafu)]
+#[snafu(context(suffix(Err)))]
+pub enum ShaderIncludeError {
+    #[snafu(display(
+        "{source_name}:{line}: expected `#include \"name\"` or `#include builtin:name`"
+    ))]
+    Malformed { source_name: String, line: usize },
+
+    #[snafu(display("{source_name}:{line}: unknown shader include {name:?}"))]
+    Unknown {
+        source_name: String,
+        line: usize,
+        name: String,
+    },
+
+    #[snafu(display("{source_name}:{line}: cyclic shader include: {}", chain.join(" -> ")))]
+    Cycle {
+        source_name: String,
+        line: usize,
+        chain: Vec<String>,
+    },
+}
+
+/// WGSL snippets that shaders can splice in with `#include "name"`.
+///
+/// Every [`AssetStore`](crate::assets::AssetStore) has its own set, used when its shaders are
+/// validated and uploaded. Built-in snippets, like `#include builtin:default_vertex3d`, are
+/// always available.
+#[derive(Debug, Default)]
+pub struct ShaderIncludes {
+    snippets: RwLock<HashMap<String, String>>,
+}
+
+impl ShaderIncludes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a snippet under `name`.
+    ///
+    /// Snippets can include other snippets and declare the groups they need with `#use`, just
+    /// like custom shaders. Registering a name again replaces the snippet, which
+    /// applies to shaders generated after that.
+    pub fn register(&self, name: impl Into<String>, source: impl Into<String>) {
+        self.snippets
+            .write()
+            .unwrap()
+            .insert(name.into(), source.into());
+    }
+
+    /// Removes a snippet registered with [`register`](Self::register)
+    pub fn unregister(&self, name: &str) -> bool {
+        self.snippets.write().unwrap().remove(name).is_some()
+    }
+
+    /// Splices the registered and built-in snippets into the `#include` lines of `code`,
+    /// recursively. `name` is only used for error messages.
+    pub fn expand<'s>(
+        &self,
+        name: &str,
+        code: &'s str,
+    ) -> Result<Cow<'s, str>, ShaderIncludeError> {
+        expand_includes(name, code, &self.snippets.read().unwrap())
+    }
+}
+
 pub struct ShaderGen<'a> {
     shader: &'a Shader,
     map: &'a BindGroupMap,
@@ -20,30 +107,146 @@ impl<'a> ShaderGen<'a> {
         Self { shader, map }
     }
 
+    /// Generates the final WGSL. Include errors are logged and the failing directive is left
+    /// out, see [`try_generate`](Self::try_generate) to handle them.
     pub fn generate(self) -> String {
+        let code = match expand_builtin_includes(self.shader.name(), self.shader.code().code()) {
+            Ok(code) => code,
+            Err(e) => {
+                error!("Failed to generate shader: {e}");
+                Cow::Owned(strip_includes(self.shader.code().code()))
+            }
+        };
+        self.generate_expanded(&code)
+    }
+
+    /// Generates the final WGSL, failing if an `#include` can't be resolved
+    pub fn try_generate(self) -> Result<String, ShaderIncludeError> {
+        let code = expand_builtin_includes(self.shader.name(), self.shader.code().code())?;
+        Ok(self.generate_expanded(&code))
+    }
+
+    fn generate_expanded(self, code: &str) -> String {
         let shader = self.shader;
-        let code = shader.code();
+        let fragment_only = shader.code().is_only_fragment_shader();
 
         match shader.stage() {
             ShaderType::Default | ShaderType::Custom => generate_default(
                 code,
+                fragment_only,
                 shader.is_custom(),
                 shader.is_depth_enabled(),
                 self.map,
             ),
-            ShaderType::PostProcessing => generate_post_process(code, self.map),
+            ShaderType::PostProcessing => generate_post_process(code, fragment_only, self.map),
         }
     }
 }
 
+/// Splices only the built-in snippets into `#include` lines, for shaders that aren't generated
+/// through an [`AssetStore`](crate::assets::AssetStore)
+pub(crate) fn expand_builtin_includes<'s>(
+    name: &str,
+    code: &'s str,
+) -> Result<Cow<'s, str>, ShaderIncludeError> {
+    expand_includes(name, code, &HashMap::new())
+}
+
+fn expand_includes<'s>(
+    name: &str,
+    code: &'s str,
+    includes: &HashMap<String, String>,
+) -> Result<Cow<'s, str>, ShaderIncludeError> {
+    if !code.contains("#include") {
+        return Ok(Cow::Borrowed(code));
+    }
+
+    let mut out = String::with_capacity(code.len());
+    expand_into(name, code, includes, &mut Vec::new(), &mut out)?;
+    Ok(Cow::Owned(out))
+}
+
+/// `stack` holds the includes that are currently being expanded, to detect cycles
+fn expand_into(
+    source_name: &str,
+    code: &str,
+    includes: &HashMap<String, String>,
+    stack: &mut Vec<String>,
+    out: &mut String,
+) -> Result<(), ShaderIncludeError> {
+    for (i, line) in code.lines().enumerate() {
+        let Some(directive) = line.trim_start().strip_prefix("#include") else {
+            out.push_str(line);
+            out.push('\n');
+            continue;
+        };
+
+        let source_name = source_name.to_string();
+        let line = i + 1;
+        let name = parse_include(directive).ok_or_else(|| ShaderIncludeError::Malformed {
+            source_name: source_name.clone(),
+            line,
+        })?;
+
+        ensure!(
+            !stack.iter().any(|open| open == name),
+            CycleErr {
+                source_name,
+                line,
+                chain: [stack.as_slice(), &[name.to_string()]].concat(),
+            }
+        );
+
+        let snippet = match name.strip_prefix(BUILTIN_PREFIX) {
+            Some(builtin) => BUILTIN_INCLUDES
+                .iter()
+                .find(|(n, _)| *n == builtin)
+                .map(|(_, code)| *code),
+            None => includes.get(name).map(String::as_str),
+        };
+        let snippet = snippet.ok_or_else(|| ShaderIncludeError::Unknown {
+            source_name,
+            line,
+            name: name.to_string(),
+        })?;
+
+        stack.push(name.to_string());
+        expand_into(name, snippet, includes, stack, out)?;
+        stack.pop();
+    }
+
+    Ok(())
+}
+
+/// Returns the included name from what follows `#include`, either `"name"` or `builtin:name`
+fn parse_include(directive: &str) -> Option<&str> {
+    let directive = directive.trim();
+    let name = match directive.strip_prefix('"') {
+        Some(quoted) => quoted.strip_suffix('"')?,
+        None if directive.starts_with(BUILTIN_PREFIX) => directive,
+        None => return None,
+    };
+    (!name.is_empty() && !name.contains('"')).then_some(name)
+}
+
+fn strip_includes(code: &str) -> String {
+    code.lines()
+        .filter(|line| !line.trim_start().starts_with("#include"))
+        .fold(String::with_capacity(code.len()), |mut out, line| {
+            out.push_str(line);
+            out.push('\n');
+            out
+        })
+}
+
 fn generate_default(
-    code: &ShaderCode,
+    code: &str,
+    fragment_only: bool,
     custom: bool,
     has_depth: bool,
     map: &BindGroupMap,
 ) -> String {
     let mut generated = format!("{BASE_GROUP}\n");
-    let fragment_only = code.is_only_fragment_shader();
 
     // if it's a fragment only, it doesn't matter if it's custom, because I can't have a clue what
     // the custom vertex shader should look like...
@@ -54,6 +257,8 @@ fn generate_default(
         generated.push('\n');
         generated.push_str(MATERIAL_GROUP);
         generated.push('\n');
+        generated.push_str(CUSTOM_GROUP);
+        generated.push('\n');
 
         if has_depth {
             generated.push_str(LIGHT_GROUP);
@@ -65,12 +270,12 @@ fn generate_default(
             generated.push('\n');
         }
 
-        generated.push_str(code.code());
+        generated.push_str(code);
         return rewrite_bind_groups(generated, map);
     }
 
     // for custom shaders, we add to the shader what it needs using the use statements at the top
-    for line in code.code().lines() {
+    for line in code.lines() {
         let Some(import) = line.find("#use ") else {
             generated.push_str(line);
             generated.push('\n');
@@ -81,7 +286,9 @@ fn generate_default(
         match group {
             "model" => generated.push_str(MODEL_GROUP),
             "material" => generated.push_str(MATERIAL_GROUP),
+            "custom" => generated.push_str(CUSTOM_GROUP),
             "light" => generated.push_str(LIGHT_GROUP),
+            "depth" => generated.push_str(DEPTH_GROUP),
             "default_vertex" => generated.push_str(DEFAULT_HEADER),
 
             _ => warn!("Shader use group {group} is invalid."),
@@ -97,16 +304,15 @@ fn generate_default(
     rewrite_bind_groups(generated, map)
 }
 
-fn generate_post_process(code: &ShaderCode, map: &BindGroupMap) -> String {
+fn generate_post_process(code: &str, fragment_only: bool, map: &BindGroupMap) -> String {
     let mut generated = format!("{BASE_GROUP}\n{POST_PROCESS_HEADER}\n");
-    let fragment_only = code.is_only_fragment_shader();
 
     if fragment_only {
         generated.push_str(POST_PROCESS_VERTEX);
         generated.push('\n');
     }
 
-    generated.push_str(code.code());
+    generated.push_str(code);
     rewrite_bind_groups(generated, map)
 }
 
@@ -132,6 +338,9 @@ fn rewrite_bind_groups(source: String, map: &BindGroupMap) -> String {
     if let Some(idx) = map.shadow {
         replace(4, idx);
     }
+    if let Some(idx) = map.depth {
+        replace(5, idx);
+    }
     if let Some(idx) = map.post_process {
         replace(1, idx);
     }
@@ -0,0 +1,31 @@
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> FInput {
+    let positions = array<vec2f,6>(
+        vec2f(-1.0, -1.0),
+        vec2f( 1.0, -1.0),
+        vec2f(-1.0,  1.0),
+        vec2f(-1.0,  1.0),
+        vec2f( 1.0, -1.0),
+        vec2f( 1.0,  1.0),
+    );
+    let uvs = array<vec2f,6>(
+        vec2f(0.0, 1.0),
+        vec2f(1.0, 1.0),
+        vec2f(0.0, 0.0),
+        vec2f(0.0, 0.0),
+        vec2f(1.0, 1.0),
+        vec2f(1.0, 0.0),
+    );
+
+    var output: FInput;
+    output.position = vec4f(positions[vertex_index], 0.0, 1.0);
+    output.uv = uvs[vertex_index];
+    return output;
+}
+
+// Writes the occlusion of the ambient light, which the lit shaders read in the color pass
+@fragment
+fn fs_main(in: FInput) -> @location(0) vec4f {
+    let occlusion = 1.0 - post_ambient_occlusion(in.uv, in.position.xy);
+    return vec4f(occlusion, 0.0, 0.0, 1.0);
+}
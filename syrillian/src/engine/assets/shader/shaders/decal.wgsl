@@ -0,0 +1,67 @@
+#use model
+#use material
+#use depth
+
+struct VOut {
+    @builtin(position) position: vec4<f32>,
+    @location(0) clip: vec4<f32>,
+}
+
+struct PushConstants {
+    world_to_decal: mat4x4<f32>,
+}
+
+var<immediate> pc: PushConstants;
+
+const CUBE_CORNERS = array<vec3<f32>, 8>(
+    vec3(-0.5, -0.5, -0.5),
+    vec3( 0.5, -0.5, -0.5),
+    vec3( 0.5,  0.5, -0.5),
+    vec3(-0.5,  0.5, -0.5),
+    vec3(-0.5, -0.5,  0.5),
+    vec3( 0.5, -0.5,  0.5),
+    vec3( 0.5,  0.5,  0.5),
+    vec3(-0.5,  0.5,  0.5),
+);
+
+const CUBE_INDICES = array<u32, 36>(
+    0, 2, 1, 0, 3, 2, // back
+    4, 5, 6, 4, 6, 7, // front
+    0, 1, 5, 0, 5, 4, // bottom
+    3, 7, 6, 3, 6, 2, // top
+    0, 4, 7, 0, 7, 3, // left
+    1, 2, 6, 1, 6, 5, // right
+);
+
+@vertex
+fn vs_main(@builtin(vertex_index) vid: u32) -> VOut {
+    let local = CUBE_CORNERS[CUBE_INDICES[vid]];
+    let clip = camera.view_proj_mat * model.transform * vec4(local, 1.0);
+
+    var out: VOut;
+    out.position = clip;
+    out.clip = clip;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VOut, @builtin(front_facing) front_facing: bool) -> @location(0) vec4<f32> {
+    // Only one side of the volume may shade a pixel, and back faces are visible from inside too
+    if front_facing {
+        discard;
+    }
+
+    let ndc = in.clip.xy / in.clip.w;
+    let world = scene_world_position(in.position.xy, ndc);
+    let local = (pc.world_to_decal * vec4(world, 1.0)).xyz;
+
+    if any(abs(local) > vec3(0.5)) {
+        discard;
+    }
+
+    // Projected along the local Y axis
+    let uv = vec2(local.x + 0.5, 0.5 - local.z);
+    let color = textureSampleLevel(t_diffuse, s_diffuse, uv, 0.0);
+
+    return vec4(color.rgb * material.diffuse, color.a * material.alpha);
+}
@@ -0,0 +1,57 @@
+// Draws egui meshes on top of the final frame. Vertex colors and textures are premultiplied
+// and in gamma space, like egui expects, and are blended there before writing to an sRGB target.
+
+struct Screen {
+    size_in_points: vec2<f32>,
+    _padding: vec2<f32>,
+}
+
+@group(0) @binding(0) var<uniform> screen: Screen;
+@group(1) @binding(0) var t_egui: texture_2d<f32>;
+@group(1) @binding(1) var s_egui: sampler;
+
+struct VOut {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+fn linear_from_gamma(srgb: vec3<f32>) -> vec3<f32> {
+    let cutoff = srgb < vec3<f32>(0.04045);
+    let lower = srgb / vec3<f32>(12.92);
+    let higher = pow((srgb + vec3<f32>(0.055)) / vec3<f32>(1.055), vec3<f32>(2.4));
+    return select(higher, lower, cutoff);
+}
+
+fn gamma_from_linear(rgb: vec3<f32>) -> vec3<f32> {
+    let cutoff = rgb < vec3<f32>(0.0031308);
+    let lower = rgb * vec3<f32>(12.92);
+    let higher = vec3<f32>(1.055) * pow(rgb, vec3<f32>(1.0 / 2.4)) - vec3<f32>(0.055);
+    return select(higher, lower, cutoff);
+}
+
+@vertex
+fn vs_main(
+    @location(0) position: vec2<f32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) color: vec4<f32>,
+) -> VOut {
+    var out: VOut;
+    out.position = vec4<f32>(
+        2.0 * position.x / screen.size_in_points.x - 1.0,
+        1.0 - 2.0 * position.y / screen.size_in_points.y,
+        0.0,
+        1.0,
+    );
+    out.uv = uv;
+    out.color = color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VOut) -> @location(0) vec4<f32> {
+    let tex_linear = textureSample(t_egui, s_egui, in.uv);
+    let tex_gamma = vec4<f32>(gamma_from_linear(tex_linear.rgb), tex_linear.a);
+    let color_gamma = in.color * tex_gamma;
+    return vec4<f32>(linear_from_gamma(color_gamma.rgb), color_gamma.a);
+}
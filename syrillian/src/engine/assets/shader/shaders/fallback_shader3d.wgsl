@@ -6,6 +6,7 @@ fn fallback_vs_main(in: VInput) -> FInput {
 
     out.clip = mvp_matrix * vec4<f32>(in.position, 1.0);
     out.uv = in.uv;
+    out.color = in.color;
 
     return out;
 }
@@ -20,5 +21,5 @@ fn fallback_fs_main(in: FInput) -> @location(0) vec4<f32> {
     } else if u32(tex.x * 10.0) % 2 != 0 && u32(tex.y * 10.0) % 2 == 0 {
         color = vec4<f32>(1.0, 0.0, 1.0, 1.0);
     }
-    return color;
+    return color * in.color;
 }
@@ -24,6 +24,7 @@ fn vs_main(@builtin(vertex_index) vertex_index: u32) -> FInput {
 }
 
 @fragment
-fn fs_main(@location(0) uv: vec2f) -> @location(0) vec4f {
-    return textureSample(postTexture, postSampler, uv);
+fn fs_main(in: FInput) -> @location(0) vec4f {
+    let color = textureSample(postTexture, postSampler, in.uv);
+    return post_apply_fog(color, in.uv);
 }
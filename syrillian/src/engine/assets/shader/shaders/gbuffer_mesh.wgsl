@@ -0,0 +1,15 @@
+#use default_vertex
+#use model
+
+struct GBufferOutput {
+    @location(0) normal: vec4<f32>,
+    @location(1) position: vec4<f32>,
+};
+
+@fragment
+fn fs_main(in: FInput) -> GBufferOutput {
+    var out: GBufferOutput;
+    out.normal = vec4(normalize(in.normal), 1.0);
+    out.position = vec4(in.position, 1.0);
+    return out;
+}
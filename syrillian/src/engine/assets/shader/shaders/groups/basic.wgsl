@@ -5,6 +5,7 @@ struct VInput {
     @location(3) tangent:  vec3<f32>,
     @location(4) bone_idx: vec4<u32>,
     @location(5) bone_w:   vec4<f32>,
+    @location(6) color:    vec4<f32>,
 }
 
 struct FInput {
@@ -16,4 +17,5 @@ struct FInput {
     @location(4) bitangent:  vec3<f32>,
     @location(5) bone_idx:   vec4<u32>,
     @location(6) bone_w:     vec4<f32>,
+    @location(7) color:      vec4<f32>,
 }
@@ -0,0 +1,10 @@
+// Parameters set with `Material::set_custom_params`, packed into vec4s.
+// Use `custom_param(i)` to read one. The elapsed time is `globals.time`.
+struct CustomParams {
+    values: array<vec4<f32>, 4>,
+}
+@group(2) @binding(7) var<uniform> custom: CustomParams;
+
+fn custom_param(index: u32) -> f32 {
+    return custom.values[index / 4u][index % 4u];
+}
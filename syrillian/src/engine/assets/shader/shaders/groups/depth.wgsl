@@ -0,0 +1,9 @@
+@group(5) @binding(0) var scene_depth: texture_depth_2d;
+
+// Reconstructs the world position of the scene behind a fragment.
+// `pixel` is the framebuffer position, `ndc` the fragment position in normalized device coordinates.
+fn scene_world_position(pixel: vec2<f32>, ndc: vec2<f32>) -> vec3<f32> {
+    let depth = textureLoad(scene_depth, vec2<i32>(pixel), 0);
+    let world = camera.inv_view_proj_mat * vec4<f32>(ndc, depth, 1.0);
+    return world.xyz / world.w;
+}
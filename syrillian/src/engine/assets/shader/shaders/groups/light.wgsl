@@ -14,11 +14,43 @@ struct Light {
     outer_angle: f32,
     type_id: u32,
     shadow_map_id: u32,
+    shadow_constant_bias: f32,
+    shadow_slope_bias: f32,
+    shadow_normal_offset: f32,
+    shadow_pcf_taps: u32,
     view_mat: mat4x4<f32>,
 }
 
+// Lights are sorted into screen tiles and depth slices. `enabled` is 0 when the lights aren't
+// sorted for the current view, then all lights should be iterated.
+struct LightClusters {
+    tiles_x: u32,
+    tiles_y: u32,
+    slices: u32,
+    enabled: u32,
+    near: f32,
+    far: f32,
+    global_count: u32,
+    _padding: u32,
+}
+
 @group(3) @binding(0) var<uniform> light_count: u32;
 @group(3) @binding(1) var<storage, read> lights: array<Light>;
+@group(3) @binding(2) var<uniform> light_clusters: LightClusters;
+// Offset into `light_indices` and light count for every cluster
+@group(3) @binding(3) var<storage, read> light_grid: array<vec2<u32>>;
+// Sun lights first, they reach every cluster. Then the lights of each cluster.
+@group(3) @binding(4) var<storage, read> light_indices: array<u32>;
 
 @group(4) @binding(0) var shadow_maps: texture_depth_2d_array;
 @group(4) @binding(1) var shadow_sampler: sampler_comparison;
+// How much ambient light is blocked at a framebuffer pixel, 0 where nothing occludes it.
+// A single empty pixel while ambient occlusion is disabled.
+@group(4) @binding(2) var ambient_occlusion: texture_2d<f32>;
+
+// Fraction of the ambient light reaching the fragment at framebuffer position `pixel`
+fn ambient_visibility(pixel: vec2<f32>) -> f32 {
+    let size = vec2<i32>(textureDimensions(ambient_occlusion));
+    let texel = clamp(vec2<i32>(pixel), vec2<i32>(0), size - 1);
+    return 1.0 - textureLoad(ambient_occlusion, texel, 0).r;
+}
@@ -1,6 +1,6 @@
 const MAT_PARAM_DIFFUSE_TEXTURE: u32 = 1;
 const MAT_PARAM_NORMAL_TEXTURE: u32 = 1 << 1;
-const MAT_PARAM_ROUGHNESS_TEXTURE: u32 = 1 << 2;
+const MAT_PARAM_METALLIC_ROUGHNESS_TEXTURE: u32 = 1 << 2;
 const MAT_PARAM_LIT: u32 = 1 << 3;
 const MAT_PARAM_CAST_SHADOWS: u32 = 1 << 4;
 const MAT_PARAM_GRAYSCALE_DIFFUSE: u32 = 1 << 5;
@@ -18,8 +18,9 @@ struct Material {
 @group(2) @binding(2) var s_diffuse: sampler;
 @group(2) @binding(3) var t_normal: texture_2d<f32>;
 @group(2) @binding(4) var s_normal: sampler;
-@group(2) @binding(5) var t_roughness: texture_2d<f32>;
-@group(2) @binding(6) var s_roughness: sampler;
+// roughness in the green channel, metallic in the blue channel, like glTF
+@group(2) @binding(5) var t_metallic_roughness: texture_2d<f32>;
+@group(2) @binding(6) var s_metallic_roughness: sampler;
 
 fn mat_has_texture_diffuse(material: Material) -> bool {
     return (material.params & MAT_PARAM_DIFFUSE_TEXTURE) != 0u;
@@ -29,8 +30,8 @@ fn mat_has_texture_normal(material: Material) -> bool {
     return (material.params & MAT_PARAM_NORMAL_TEXTURE) != 0u;
 }
 
-fn mat_has_texture_roughness(material: Material) -> bool {
-    return (material.params & MAT_PARAM_ROUGHNESS_TEXTURE) != 0u;
+fn mat_has_texture_metallic_roughness(material: Material) -> bool {
+    return (material.params & MAT_PARAM_METALLIC_ROUGHNESS_TEXTURE) != 0u;
 }
 
 fn mat_is_lit(material: Material) -> bool {
@@ -1,5 +1,3 @@
-const MAX_BONES : u32 = 256u;
-
 struct ModelData {
     transform: mat4x4<f32>,
     // For correct normal transformation with non-uniform scaling,
@@ -8,7 +6,5 @@ struct ModelData {
 }
 @group(1) @binding(0) var<uniform> model: ModelData;
 
-struct BoneData {
-    mats : array<mat4x4<f32>, MAX_BONES>,
-}
-@group(1) @binding(1) var<uniform> bones: BoneData;
+// The bone palette of skinned meshes. Meshes without skin data get a single identity bone.
+@group(1) @binding(1) var<storage, read> bones: array<mat4x4<f32>>;
@@ -3,9 +3,146 @@ struct FInput {
     @location(0) uv: vec2f,
 };
 
+struct PostFog {
+    color: vec3f,
+    enabled: u32,
+    start: f32,
+    end: f32,
+};
+
+struct PostSsao {
+    enabled: u32,
+    radius: f32,
+    intensity: f32,
+    bias: f32,
+};
+
 @group(1) @binding(0)
 var postTexture: texture_2d<f32>;
 @group(1) @binding(1)
 var postSampler: sampler;
 @group(1) @binding(2)
 var postDepth: texture_depth_2d;
+@group(1) @binding(3)
+var<uniform> postFog: PostFog;
+@group(1) @binding(4)
+var<uniform> postSsao: PostSsao;
+@group(1) @binding(5)
+var postNormal: texture_2d<f32>;
+@group(1) @binding(6)
+var postPosition: texture_2d<f32>;
+
+const SSAO_SAMPLES: u32 = 16u;
+const GOLDEN_ANGLE: f32 = 2.39996323;
+
+fn post_pixel(uv: vec2f, dimensions: vec2<u32>) -> vec2<i32> {
+    let size = vec2<i32>(dimensions);
+    return clamp(vec2<i32>(uv * vec2f(dimensions)), vec2<i32>(0), size - 1);
+}
+
+// Raw depth buffer value at `uv`. 1.0 means nothing was drawn there.
+fn post_depth(uv: vec2f) -> f32 {
+    return textureLoad(postDepth, post_pixel(uv, textureDimensions(postDepth)), 0);
+}
+
+// World space normal from the G-buffer at `uv`. `w` is 0 where no opaque mesh was drawn,
+// or if the G-buffer is disabled.
+fn post_gbuffer_normal(uv: vec2f) -> vec4f {
+    return textureLoad(postNormal, post_pixel(uv, textureDimensions(postNormal)), 0);
+}
+
+// World space position from the G-buffer at `uv`. `w` is 0 where no opaque mesh was drawn,
+// or if the G-buffer is disabled.
+fn post_gbuffer_position(uv: vec2f) -> vec4f {
+    return textureLoad(postPosition, post_pixel(uv, textureDimensions(postPosition)), 0);
+}
+
+// World position of the scene at `uv`
+fn post_world_position(uv: vec2f) -> vec3f {
+    let ndc = vec2f(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0);
+    let world = camera.inv_view_proj_mat * vec4f(ndc, post_depth(uv), 1.0);
+    return world.xyz / world.w;
+}
+
+// View space position of the scene at `uv`
+fn post_view_position(uv: vec2f) -> vec3f {
+    return (camera.view_mat * vec4f(post_world_position(uv), 1.0)).xyz;
+}
+
+// Linear distance of the scene at `uv` along the camera's view direction,
+// between the camera's near and far plane
+fn post_linear_depth(uv: vec2f) -> f32 {
+    return -post_view_position(uv).z;
+}
+
+// Screen uv of a view space position
+fn post_view_to_uv(view: vec3f) -> vec2f {
+    let clip = camera.projection_mat * vec4f(view, 1.0);
+    let ndc = clip.xy / clip.w;
+    return vec2f(ndc.x * 0.5 + 0.5, 0.5 - ndc.y * 0.5);
+}
+
+// View space normal at `uv`, from the G-buffer if available, or reconstructed from the depth
+// of neighbouring pixels otherwise
+fn post_view_normal(uv: vec2f) -> vec3f {
+    let gbuffer = post_gbuffer_normal(uv);
+    if gbuffer.w > 0.5 {
+        return normalize((camera.view_mat * vec4f(gbuffer.xyz, 0.0)).xyz);
+    }
+
+    let texel = 1.0 / vec2f(textureDimensions(postDepth));
+    let origin = post_view_position(uv);
+    let right = post_view_position(uv + vec2f(texel.x, 0.0)) - origin;
+    let down = post_view_position(uv + vec2f(0.0, texel.y)) - origin;
+    return normalize(cross(down, right));
+}
+
+// Fraction of ambient light reaching the scene at `uv`, from 0 (occluded) to 1 (open)
+fn post_ambient_occlusion(uv: vec2f, pixel: vec2f) -> f32 {
+    if post_depth(uv) >= 1.0 {
+        return 1.0;
+    }
+
+    let origin = post_view_position(uv);
+    let normal = post_view_normal(uv);
+
+    let helper = select(vec3f(1.0, 0.0, 0.0), vec3f(0.0, 1.0, 0.0), abs(normal.x) > 0.9);
+    let tangent = normalize(cross(helper, normal));
+    let bitangent = cross(normal, tangent);
+
+    // interleaved gradient noise rotates the kernel per pixel to hide banding
+    let noise = fract(52.9829189 * fract(dot(pixel, vec2f(0.06711056, 0.00583715))));
+
+    var occlusion = 0.0;
+    for (var i = 0u; i < SSAO_SAMPLES; i++) {
+        let t = (f32(i) + 0.5) / f32(SSAO_SAMPLES);
+        let cos_theta = 1.0 - t;
+        let sin_theta = sqrt(1.0 - cos_theta * cos_theta);
+        let phi = f32(i) * GOLDEN_ANGLE + noise * 6.28318531;
+        let dir = tangent * cos(phi) * sin_theta + bitangent * sin(phi) * sin_theta + normal * cos_theta;
+
+        // more samples close to the origin, where occluders matter most
+        let probe = origin + dir * postSsao.radius * mix(0.1, 1.0, t * t);
+        let sample_uv = post_view_to_uv(probe);
+        if any(sample_uv < vec2f(0.0)) || any(sample_uv > vec2f(1.0)) {
+            continue;
+        }
+
+        let scene_z = post_view_position(sample_uv).z;
+        let range = smoothstep(0.0, 1.0, postSsao.radius / max(abs(origin.z - scene_z), 0.0001));
+        occlusion += select(0.0, 1.0, scene_z >= probe.z + postSsao.bias) * range;
+    }
+
+    return clamp(1.0 - occlusion / f32(SSAO_SAMPLES) * postSsao.intensity, 0.0, 1.0);
+}
+
+// Blends `color` towards the configured fog color based on the scene depth at `uv`
+fn post_apply_fog(color: vec4f, uv: vec2f) -> vec4f {
+    if postFog.enabled == 0u || post_depth(uv) >= 1.0 {
+        return color;
+    }
+
+    let range = max(postFog.end - postFog.start, 0.0001);
+    let amount = clamp((post_linear_depth(uv) - postFog.start) / range, 0.0, 1.0);
+    return vec4f(mix(color.rgb, postFog.color, amount), color.a);
+}
@@ -12,5 +12,13 @@ struct SystemData {
     delta_time: f32,
 }
 
+// Available in every shader, for effects that animate or depend on the screen size
+struct Globals {
+    resolution: vec2<f32>,
+    time: f32,
+    delta_time: f32,
+}
+
 @group(0) @binding(0) var<uniform> camera: CameraData;
 @group(0) @binding(1) var<uniform> system: SystemData;
+@group(0) @binding(2) var<uniform> globals: Globals;
@@ -60,9 +60,14 @@ test_shader!(fallback_shader3d, "Fallback Shader 3D" => "fallback_shader3d.wgsl"
 test_custom_shader!(picking_text_2d, "Text 2D Picking Shader" => "picking_text2d.wgsl");
 test_custom_shader!(picking_text_3d, "Text 3D Picking Shader" => "picking_text3d.wgsl");
 test_custom_shader!(picking_mesh, "Mesh Picking Shader" => "picking_mesh.wgsl");
+test_custom_shader!(gbuffer_mesh, "G-Buffer Shader" => "gbuffer_mesh.wgsl");
 test_custom_shader!(picking_ui, "UI Picking Shader" => "picking_ui.wgsl");
 test_custom_shader!(text2d, "Text 2D Shader" => "text2d.wgsl");
 test_custom_shader!(text3d, "Text 3D Shader" => "text3d.wgsl");
+test_custom_shader!(decal, "Decal Shader" => "decal.wgsl");
+test_custom_shader!(particle, "Particle Shader" => "particle.wgsl");
+test_custom_shader!(outline, "Outline Shader" => "outline.wgsl");
+test_custom_shader!(nine_slice, "Nine Slice Shader" => "nine_slice.wgsl");
 
 // Debug shaders
 test_custom_shader!(debug_edges, "Debug Edges Shader" => "debug/edges.wgsl");
@@ -74,3 +79,54 @@ test_custom_shader!(debug_light, "Debug Light Geometry Shader" => "debug/light.w
 
 // Post-Processing Shaders
 test_post_shader!(fullscreen_passthrough, "Fullscreen Passthrough Shader" => "fullscreen_passthrough.wgsl");
+
+// Standalone shaders
+#[test]
+fn egui() {
+    use crate::utils::validate_wgsl_source;
+
+    let shader = include_str!("egui.wgsl");
+    validate_wgsl_source(shader)
+        .inspect_err(|e| e.emit_to_stderr_with_path(shader, "egui.wgsl"))
+        .unwrap();
+}
+
+#[test]
+fn invisible_fallbacks_are_valid() {
+    use crate::assets::Shader;
+    use crate::assets::shader::ShaderCode;
+    use wgpu::{ColorTargetState, ColorWrites, TextureFormat};
+
+    const TARGETS: &[Option<ColorTargetState>] = &[
+        Some(ColorTargetState {
+            format: TextureFormat::Rgba8Unorm,
+            blend: None,
+            write_mask: ColorWrites::all(),
+        }),
+        None,
+        Some(ColorTargetState {
+            format: TextureFormat::R32Uint,
+            blend: None,
+            write_mask: ColorWrites::all(),
+        }),
+    ];
+
+    for targets in [TARGETS, &[]] {
+        let broken = Shader::builder()
+            .shader_type(ShaderType::Custom)
+            .name("Broken")
+            .code(ShaderCode::Full("#use model\nthis isn't wgsl".to_string()))
+            .color_target(targets)
+            .build();
+        assert!(broken.validate().is_err());
+
+        let fallback = broken.invisible_fallback();
+        fallback.validate().unwrap();
+        assert_eq!(fallback.color_target().len(), targets.len());
+    }
+
+    Shader::new_default("Broken", "this isn't wgsl")
+        .invisible_fallback()
+        .validate()
+        .unwrap();
+}
@@ -0,0 +1,15 @@
+fn msdf_median(a: vec3<f32>) -> f32 {
+    return max(min(a.r, a.g), min(max(a.r, a.g), a.b));
+}
+
+// Coverage of a multi-channel signed distance field sample. The edge is always smoothed over
+// one screen pixel, by measuring how many screen pixels the atlas distance range covers, so
+// text stays crisp at any size.
+fn msdf_alpha(msdf: vec3<f32>, uv: vec2<f32>, range_px: f32) -> f32 {
+    let atlas_size = vec2<f32>(textureDimensions(t_diffuse));
+    let unit_range = vec2(range_px) / atlas_size;
+    let screen_tex_size = vec2(1.0) / max(fwidth(uv), vec2(1e-6));
+    let screen_px_range = max(0.5 * dot(unit_range, screen_tex_size), 1.0);
+    let screen_px_dist = screen_px_range * (msdf_median(msdf) - 0.5);
+    return clamp(screen_px_dist + 0.5, 0.0, 1.0);
+}
@@ -0,0 +1,84 @@
+#use default_vertex
+#use model
+#use material
+
+const SLICE_TILE_CENTER: u32 = 1u;
+const SLICE_TILE_EDGES: u32 = 1u << 1u;
+
+struct NineSlice {
+    // the size of the image on screen, in pixels
+    size: vec2<f32>,
+    tile: u32,
+    // left, right, top and bottom border in texture pixels
+    border: vec4<f32>,
+}
+
+var<immediate> slice: NineSlice;
+
+@vertex
+fn vs_main(in: VInput) -> FInput {
+    var out: FInput;
+
+    out.clip = model.transform * vec4<f32>(in.position, 1.0);
+    out.uv = vec2<f32>(in.uv.x, 1.0 - in.uv.y);
+
+    return out;
+}
+
+// Borders shrink evenly if the image is smaller than both of them together
+fn slice_shrink(size: f32, lo: f32, hi: f32) -> f32 {
+    return min(1.0, size / max(lo + hi, 1e-5));
+}
+
+fn slice_is_middle(px: f32, size: f32, lo: f32, hi: f32) -> bool {
+    let shrink = slice_shrink(size, lo, hi);
+    return px >= lo * shrink && px <= size - hi * shrink;
+}
+
+// Maps a pixel along one axis of the image to a texture coordinate along that axis
+fn slice_axis(px: f32, size: f32, lo: f32, hi: f32, tex_size: f32, tile: bool) -> f32 {
+    let shrink = slice_shrink(size, lo, hi);
+    if px < lo * shrink {
+        return px / shrink / tex_size;
+    }
+    if px > size - hi * shrink {
+        return 1.0 - (size - px) / shrink / tex_size;
+    }
+
+    let offset = px - lo * shrink;
+    let middle_tex = max(tex_size - lo - hi, 1e-5);
+    if tile {
+        return (lo + fract(offset / middle_tex) * middle_tex) / tex_size;
+    }
+
+    let middle_px = max(size - (lo + hi) * shrink, 1e-5);
+    return (lo + offset / middle_px * middle_tex) / tex_size;
+}
+
+@fragment
+fn fs_main(in: FInput) -> @location(0) vec4<f32> {
+    if !mat_has_texture_diffuse(material) {
+        return vec4<f32>(material.diffuse, 1.0);
+    }
+
+    let tex_size = vec2<f32>(textureDimensions(t_diffuse));
+    let px = in.uv * slice.size;
+    let b = slice.border;
+
+    let middle_x = slice_is_middle(px.x, slice.size.x, b.x, b.y);
+    let middle_y = slice_is_middle(px.y, slice.size.y, b.z, b.w);
+    let tile_center = (slice.tile & SLICE_TILE_CENTER) != 0u;
+    let tile_edges = (slice.tile & SLICE_TILE_EDGES) != 0u;
+
+    // the center tiles both ways, the top and bottom edges horizontally, the side edges vertically
+    let tile_x = select(tile_edges, tile_center, middle_y);
+    let tile_y = select(tile_edges, tile_center, middle_x);
+
+    let uv = vec2<f32>(
+        slice_axis(px.x, slice.size.x, b.x, b.y, tex_size.x, tile_x),
+        slice_axis(px.y, slice.size.y, b.z, b.w, tex_size.y, tile_y),
+    );
+
+    // tiling wraps the coordinates, which breaks the derivatives mip selection would need
+    return textureSampleLevel(t_diffuse, s_diffuse, uv, 0.0);
+}
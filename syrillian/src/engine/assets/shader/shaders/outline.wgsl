@@ -0,0 +1,39 @@
+#use default_vertex
+#use model
+#include builtin:skinning
+
+struct OutlineImmediates {
+    color: vec4<f32>,
+    // in pixels
+    width: f32,
+}
+
+var<immediate> outline: OutlineImmediates;
+
+// Grows the mesh along its normals in screen space, so the outline is equally thick at any distance
+@vertex
+fn vs_main(in: VInput) -> @builtin(position) vec4<f32> {
+    let p_sk = skin_pos(vec4(in.position, 1.0), in.bone_idx, in.bone_w);
+    let n_sk = skin_dir(in.normal, in.bone_idx, in.bone_w);
+
+    let ws_pos = model.transform * p_sk;
+    let ws_normal = (model.transform * vec4(n_sk, 0.0)).xyz;
+
+    var clip = camera.view_proj_mat * ws_pos;
+    let clip_normal = (camera.view_proj_mat * vec4(ws_normal, 0.0)).xy;
+    if length(clip_normal) > 1e-6 {
+        let ndc_offset = normalize(clip_normal) * outline.width * 2.0 / globals.resolution;
+        clip += vec4(ndc_offset * clip.w, 0.0, 0.0);
+    }
+
+    return clip;
+}
+
+// Only the back faces of the grown mesh are drawn, so they show around the silhouette
+@fragment
+fn fs_main(@builtin(front_facing) front_facing: bool) -> @location(0) vec4<f32> {
+    if front_facing {
+        discard;
+    }
+    return outline.color;
+}
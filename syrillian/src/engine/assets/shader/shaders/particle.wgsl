@@ -0,0 +1,57 @@
+#use material
+
+struct ParticleIn {
+    @location(0) position: vec3<f32>,
+    @location(1) size: f32,
+    @location(2) color: vec4<f32>,
+}
+
+struct VOut {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+const QUAD_CORNERS = array<vec2<f32>, 6>(
+    vec2(-0.5, -0.5),
+    vec2( 0.5, -0.5),
+    vec2( 0.5,  0.5),
+    vec2(-0.5, -0.5),
+    vec2( 0.5,  0.5),
+    vec2(-0.5,  0.5),
+);
+
+@vertex
+fn vs_main(@builtin(vertex_index) vid: u32, in: ParticleIn) -> VOut {
+    let corner = QUAD_CORNERS[vid];
+
+    // the rows of the view matrix are the camera axes in world space
+    let right = vec3(camera.view_mat[0][0], camera.view_mat[1][0], camera.view_mat[2][0]);
+    let up = vec3(camera.view_mat[0][1], camera.view_mat[1][1], camera.view_mat[2][1]);
+    let world = in.position + (right * corner.x + up * corner.y) * in.size;
+
+    var out: VOut;
+    out.position = camera.view_proj_mat * vec4(world, 1.0);
+    out.uv = vec2(corner.x + 0.5, 0.5 - corner.y);
+    out.color = in.color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VOut) -> @location(0) vec4<f32> {
+    var color = in.color * vec4(material.diffuse, material.alpha);
+
+    if mat_has_texture_diffuse(material) {
+        color *= textureSample(t_diffuse, s_diffuse, in.uv);
+    } else {
+        // soft round sprite without a texture
+        let dist = length(in.uv - vec2(0.5)) * 2.0;
+        color.a *= 1.0 - smoothstep(0.5, 1.0, dist);
+    }
+
+    if color.a <= 0.001 {
+        discard;
+    }
+
+    return color;
+}
@@ -1,5 +1,6 @@
 #use model
 #use material
+#include builtin:msdf
 
 struct GlyphIn {
     @location(0) pos_em: vec2<f32>,
@@ -30,17 +31,10 @@ fn vs_main(in: GlyphIn) -> VOut {
     return out;
 }
 
-fn median3(a: vec3<f32>) -> f32 {
-    return max(min(a.r, a.g), min(max(a.r, a.g), a.b));
-}
-
 @fragment
 fn fs_main(in: VOut) -> @location(0) vec4<f32> {
     let msdf = textureSample(t_diffuse, s_diffuse, in.uv).rgb;
-    let sig = median3(msdf);
-    let dist = (sig - 0.5) * pc.msdf_range_px;
-    let w = max(fwidth(dist), 1e-4);
-    let alpha = smoothstep(-w, w, dist);
+    let alpha = msdf_alpha(msdf, in.uv, pc.msdf_range_px);
 
     if (alpha <= 0.01) {
         discard;
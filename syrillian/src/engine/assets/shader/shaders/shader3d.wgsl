@@ -149,16 +149,16 @@ fn calculate_attenuation(distance: f32, radius: f32) -> f32 {
 fn shadow_visibility_spot(in_pos: vec3<f32>, N: vec3<f32>, L: vec3<f32>, light: Light) -> f32 {
     if (!mat_has_cast_shadows(material) || light.shadow_map_id == 0xffffffffu) { return 1.0; }
 
-    let world_pos_bias = in_pos + N * 0.002;
+    let world_pos_bias = in_pos + N * light.shadow_normal_offset;
     let uvz = spot_shadow_uvz(light, world_pos_bias);
     if !(all(uvz >= vec3<f32>(0.0)) && all(uvz <= vec3<f32>(1.0))) {
         return 1.0;
     }
 
     let slope = 1.0 - max(dot(N, L), 0.0);
-    let bias  = 0.0001 * slope;
+    let bias  = light.shadow_constant_bias + light.shadow_slope_bias * slope;
     let layer = i32(light.shadow_map_id);
-    return pcf_3x3(shadow_maps, shadow_sampler, uvz.xy, uvz.z - bias, layer);
+    return pcf(shadow_maps, shadow_sampler, uvz.xy, uvz.z - bias, layer, light.shadow_pcf_taps);
 }
 
 fn point_face_axes(dir: vec3<f32>) -> CubeFaceAxes {
@@ -259,7 +259,7 @@ fn sample_point_face(
     }
 
     let layer = i32(light.shadow_map_id) + i32(face);
-    let samp = pcf_3x3(shadow_maps, shadow_sampler, uvz.xy, uvz.z - bias, layer);
+    let samp = pcf(shadow_maps, shadow_sampler, uvz.xy, uvz.z - bias, layer, light.shadow_pcf_taps);
     return vec2<f32>(samp, 1.0);
 }
 
@@ -289,9 +289,10 @@ fn shadow_visibility_point(in_pos: vec3<f32>, N: vec3<f32>, L: vec3<f32>, light:
 
     let ndir = dir_unbiased * inverseSqrt(dist_sq);
     let abs_dir = abs(ndir);
-    let world_pos_bias = in_pos + N * 0.002;
+    let world_pos_bias = in_pos + N * light.shadow_normal_offset;
     let slope = 1.0 - max(dot(N, L), 0.0);
-    let bias  = 0.0003 * slope;
+    // the 90° cube faces spread their depth precision thinner than spot maps
+    let bias  = light.shadow_constant_bias + 3.0 * light.shadow_slope_bias * slope;
 
     let contrib_x = axis_shadow_contrib(0u, ndir.x, abs_dir.x, light, world_pos_bias, bias);
     let contrib_y = axis_shadow_contrib(1u, ndir.y, abs_dir.y, light, world_pos_bias, bias);
@@ -365,6 +366,37 @@ fn eval_sun(
     return brdf * radiance;
 }
 
+fn eval_light(
+    in_pos: vec3<f32>, N: vec3<f32>, V: vec3<f32>,
+    base: vec3<f32>, metallic: f32, roughness: f32, light: Light
+) -> vec3<f32> {
+    if (light.type_id == LIGHT_TYPE_POINT) {
+        return eval_point(in_pos, N, V, base, metallic, roughness, light);
+    } else if (light.type_id == LIGHT_TYPE_SUN) {
+        return eval_sun(in_pos, N, V, base, metallic, roughness, light);
+    } else if (light.type_id == LIGHT_TYPE_SPOT) {
+        return eval_spot(in_pos, N, V, base, metallic, roughness, light);
+    }
+    return vec3<f32>(0.0);
+}
+
+// Returns the offset into `light_indices` and the light count of the cluster containing the
+// position. Tiles and slices are picked like in `light_clusters.rs`.
+fn light_cluster(world_pos: vec3<f32>) -> vec2<u32> {
+    let clip = camera.view_proj_mat * vec4<f32>(world_pos, 1.0);
+    let ndc = clip.xy / max(clip.w, 1e-6);
+    let depth = -(camera.view_mat * vec4<f32>(world_pos, 1.0)).z;
+
+    let tiles = vec2<u32>(light_clusters.tiles_x, light_clusters.tiles_y);
+    let tile = min(vec2<u32>(max((ndc * 0.5 + 0.5) * vec2<f32>(tiles), vec2<f32>(0.0))), tiles - 1u);
+
+    let near = light_clusters.near;
+    let depth_ratio = log(max(depth, near) / near) / log(light_clusters.far / near);
+    let slice = min(u32(max(depth_ratio * f32(light_clusters.slices), 0.0)), light_clusters.slices - 1u);
+
+    return light_grid[(slice * tiles.y + tile.y) * tiles.x + tile.x];
+}
+
 @fragment
 fn fs_main_3d(in: FInput) -> @location(0) vec4<f32> {
     // Base color (linear)
@@ -377,20 +409,22 @@ fn fs_main_3d(in: FInput) -> @location(0) vec4<f32> {
     } else {
         base_rgba = vec4<f32>(material.diffuse, 1.0);
     }
+    base_rgba *= in.color;
 
     // Alpha test
     if (base_rgba.a < 0.01) { discard; }
 
     let base = saturate3(base_rgba.rgb);
 
-    let metallic = clamp(material.metallic, 0.0, 1.0);
-
-    var roughness: f32;
-    if mat_has_texture_roughness(material) {
-        roughness = textureSample(t_roughness, s_roughness, in.uv).g;
-    } else {
-        roughness = clamp(material.roughness, 0.045, 1.0);
+    var metallic = material.metallic;
+    var roughness = material.roughness;
+    if mat_has_texture_metallic_roughness(material) {
+        let packed = textureSample(t_metallic_roughness, s_metallic_roughness, in.uv);
+        roughness *= packed.g;
+        metallic *= packed.b;
     }
+    metallic = clamp(metallic, 0.0, 1.0);
+    roughness = clamp(roughness, 0.045, 1.0);
 
     var Lo = base;
 
@@ -404,20 +438,26 @@ fn fs_main_3d(in: FInput) -> @location(0) vec4<f32> {
     let V = safe_normalize(camera.position - in.position);   // to viewer
 
     if mat_is_lit(material) {
-        // start with a dim ambient term (energy‑aware)
-        Lo *= (AMBIENT_STRENGTH * (1.0 - 0.04)); // tiny spec energy loss
+        // start with a dim ambient term (energy‑aware), darkened by the ambient occlusion
+        Lo *= (AMBIENT_STRENGTH * (1.0 - 0.04)) * ambient_visibility(in.clip.xy); // tiny spec energy loss
     }
 
     // Lights
-    let count = light_count;
-    for (var i: u32 = 0u; i < count; i = i + 1u) {
-        let Ld = lights[i];
-        if (Ld.type_id == LIGHT_TYPE_POINT) {
-            Lo += eval_point(in.position, N, V, base, metallic, roughness, Ld);
-        } else if (Ld.type_id == LIGHT_TYPE_SUN) {
-            Lo += eval_sun(in.position, N, V, base, metallic, roughness, Ld);
-        } else if (Ld.type_id == LIGHT_TYPE_SPOT) {
-            Lo += eval_spot(in.position, N, V, base, metallic, roughness, Ld);
+    if (light_clusters.enabled == 0u) {
+        let count = light_count;
+        for (var i: u32 = 0u; i < count; i = i + 1u) {
+            Lo += eval_light(in.position, N, V, base, metallic, roughness, lights[i]);
+        }
+    } else {
+        for (var i: u32 = 0u; i < light_clusters.global_count; i = i + 1u) {
+            let Ld = lights[light_indices[i]];
+            Lo += eval_light(in.position, N, V, base, metallic, roughness, Ld);
+        }
+
+        let cluster = light_cluster(in.position);
+        for (var i: u32 = 0u; i < cluster.y; i = i + 1u) {
+            let Ld = lights[light_indices[cluster.x + i]];
+            Lo += eval_light(in.position, N, V, base, metallic, roughness, Ld);
         }
     }
 
@@ -477,22 +517,30 @@ fn spot_shadow_uvz(light: Light, world_pos: vec3<f32>) -> vec3<f32> {
     return vec3<f32>(uv, ndc.z);
 }
 
-fn pcf_3x3(depthTex: texture_depth_2d_array,
-           cmpSampler: sampler_comparison,
-           uv: vec2<f32>, depth_ref: f32, layer: i32) -> f32
+// Percentage-closer filtering over a taps x taps grid. 1 tap gives hard shadows.
+fn pcf(depthTex: texture_depth_2d_array,
+       cmpSampler: sampler_comparison,
+       uv: vec2<f32>, depth_ref: f32, layer: i32, taps: u32) -> f32
 {
     let dims  = vec2<f32>(textureDimensions(depthTex, 0));
     let texel = 1.0 / dims;
     let guard = texel * 0.5;
     let guard_max = vec2<f32>(1.0) - guard;
 
+    if (taps <= 1u) {
+        return textureSampleCompareLevel(depthTex, cmpSampler, clamp(uv, guard, guard_max), layer, depth_ref);
+    }
+
+    // centers the kernel, even tap counts sample between texels
+    let center = f32(taps - 1u) * 0.5;
+
     var sum = 0.0;
-    for (var dy = -1; dy <= 1; dy++) {
-        for (var dx = -1; dx <= 1; dx++) {
-            let ofs = vec2<f32>(f32(dx), f32(dy)) * texel;
+    for (var y = 0u; y < taps; y++) {
+        for (var x = 0u; x < taps; x++) {
+            let ofs = (vec2<f32>(f32(x), f32(y)) - center) * texel;
             let sample_uv = clamp(uv + ofs, guard, guard_max);
-            sum += textureSampleCompare(depthTex, cmpSampler, sample_uv, layer, depth_ref);
+            sum += textureSampleCompareLevel(depthTex, cmpSampler, sample_uv, layer, depth_ref);
         }
     }
-    return sum / 9.0;
+    return sum / f32(taps * taps);
 }
@@ -1,13 +1,17 @@
 #use material
+#include builtin:msdf
 
 struct GlyphIn {
     @location(0) pos_em: vec2<f32>,
     @location(1) uv: vec2<f32>,
+    // alpha is 1 if the glyph has its own color
+    @location(2) color: vec4<f32>,
 }
 
 struct VOut {
     @builtin(position) position: vec4<f32>,
     @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
 }
 
 struct PushConstants {
@@ -30,22 +34,16 @@ fn text_2d_vs_main(in: GlyphIn) -> VOut {
                     1.0 - (vpos.y / screen_size.y) * 2.0);
     out.position = vec4(ndc, 0.0, 1.0);
     out.uv = in.uv;
+    out.color = in.color;
     return out;
 }
 
-fn median3(a: vec3<f32>) -> f32 {
-    return max(min(a.r, a.g), min(max(a.r, a.g), a.b));
-}
-
 @fragment
 fn text_2d_fs_main(in: VOut) -> @location(0) vec4<f32> {
     let msdf = textureSample(t_diffuse, s_diffuse, in.uv).rgb;
-    let sig = median3(msdf);
-    var dist = (sig - 0.5) * pc.msdf_range_px;
-
-    let w = max(fwidth(dist), 1e-4);
-    let alpha = smoothstep(-w, w, dist);
+    let alpha = msdf_alpha(msdf, in.uv, pc.msdf_range_px);
 
     if (alpha <= 0.01) { discard; }
-    return vec4(pc.color, alpha);
+    let color = mix(pc.color, in.color.rgb, in.color.a);
+    return vec4(color, alpha);
 }
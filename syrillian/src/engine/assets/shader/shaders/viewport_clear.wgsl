@@ -0,0 +1,10 @@
+// Writes the far plane depth over the whole viewport. Load ops clear the whole target, so this
+// clears cameras that only cover part of it.
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4f {
+    let uv = vec2f(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    return vec4f(uv * 2.0 - 1.0, 1.0, 1.0);
+}
+
+@fragment
+fn fs_main() {}
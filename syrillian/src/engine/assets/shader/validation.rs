@@ -0,0 +1,123 @@
+use crate::assets::{AssetStore, HShader, Shader, ShaderCode, ShaderIncludeError, ShaderIncludes};
+use crate::utils::validate_wgsl_source;
+use snafu::{ResultExt, Snafu};
+use std::fmt::Write;
+use wgpu::TextureSampleType;
+
+#[derive(Debug, Snafu)]
+#[snafu(context(suffix(Err)))]
+pub enum ShaderValidationError {
+    #[snafu(display("shader {shader} doesn't exist"))]
+    NotFound { shader: HShader },
+
+    #[snafu(display("{source}"))]
+    Include { source: ShaderIncludeError },
+
+    /// Contains naga's diagnostic, pointing at the line and column in the generated code
+    #[snafu(display("shader {name:?} is invalid:\n{diagnostic}"))]
+    Invalid { name: String, diagnostic: String },
+}
+
+impl Shader {
+    /// Generates the final code and validates it with naga, like the GPU driver would.
+    ///
+    /// Invalid shaders can't be used for rendering, so this is the place to find out why. Only
+    /// built-in snippets can be included here, see [`AssetStore::validate_shader`] for shaders
+    /// that use the snippets of a store.
+    pub fn validate(&self) -> Result<(), ShaderValidationError> {
+        self.validated_code().map(drop)
+    }
+
+    /// Expands the includes with `includes` and validates the result. Returns the expanded
+    /// shader along with its generated code.
+    pub(crate) fn validated_with(
+        &self,
+        includes: &ShaderIncludes,
+    ) -> Result<(Shader, String), ShaderValidationError> {
+        let expanded = self.with_includes(includes).context(IncludeErr)?;
+        let code = expanded.validated_code()?;
+        Ok((expanded, code))
+    }
+
+    /// Generates the final code and returns it if it's valid, so it doesn't have to be generated
+    /// again for the GPU
+    fn validated_code(&self) -> Result<String, ShaderValidationError> {
+        let code = self.try_gen_code().context(IncludeErr)?;
+        validate_wgsl_source(&code).map_err(|e| ShaderValidationError::Invalid {
+            name: self.name.clone(),
+            diagnostic: e.emit_to_string(&code),
+        })?;
+        Ok(code)
+    }
+
+    /// Returns a copy of the shader that draws nothing, but keeps the vertex buffers, color
+    /// targets and immediates of the original. Used in place of invalid shaders that no built-in
+    /// shader can stand in for.
+    pub(crate) fn invisible_fallback(&self) -> Shader {
+        let mut outputs = String::new();
+        let mut values = Vec::new();
+        for (location, target) in self.color_target.iter().enumerate() {
+            let Some(target) = target else {
+                continue;
+            };
+            let ty = match target.format.sample_type(None, None) {
+                Some(TextureSampleType::Uint) => "vec4<u32>",
+                Some(TextureSampleType::Sint) => "vec4<i32>",
+                _ => "vec4<f32>",
+            };
+            let _ = writeln!(outputs, "    @location({location}) out{location}: {ty},");
+            values.push(format!("{ty}()"));
+        }
+
+        let fragment = if values.is_empty() {
+            "@fragment\nfn fs_main() {}\n".to_string()
+        } else {
+            format!(
+                "struct FallbackOutput {{\n{outputs}}}\n\n\
+                 @fragment\nfn fs_main() -> FallbackOutput {{\n    return FallbackOutput({});\n}}\n",
+                values.join(", ")
+            )
+        };
+
+        let mut fallback = self.clone();
+        // the vertex lies outside of the clip volume, so nothing is rasterized
+        fallback.code = ShaderCode::Full(format!(
+            "@vertex\nfn vs_main() -> @builtin(position) vec4<f32> {{\n    \
+             return vec4<f32>(2.0, 2.0, 2.0, 1.0);\n}}\n\n{fragment}"
+        ));
+        fallback.shadow_transparency = false;
+        fallback
+    }
+
+    /// Replaces the code like [`set_code`](Self::set_code) if the new code is valid with the
+    /// given snippets. Otherwise, the current code is kept.
+    pub fn try_set_code(
+        &mut self,
+        source: String,
+        includes: &ShaderIncludes,
+    ) -> Result<(), ShaderValidationError> {
+        let previous = std::mem::replace(&mut self.code, ShaderCode::Full(source));
+        self.validated_with(includes)
+            .map(drop)
+            .inspect_err(|_| self.code = previous)
+    }
+}
+
+impl AssetStore {
+    /// Validates a stored shader with the snippets registered in
+    /// [`shader_includes`](Self::shader_includes), see [`Shader::validate`]
+    pub fn validate_shader(&self, h: HShader) -> Result<(), ShaderValidationError> {
+        self.shaders
+            .get_checked(h)
+            .ok_or(ShaderValidationError::NotFound { shader: h })?
+            .validated_with(&self.shader_includes)
+            .map(drop)
+    }
+
+    /// Adds the shader if it's valid, so mistakes are reported right away instead of when the
+    /// shader is first rendered
+    pub fn add_validated_shader(&self, shader: Shader) -> Result<HShader, ShaderValidationError> {
+        shader.validated_with(&self.shader_includes)?;
+        Ok(self.shaders.add(shader))
+    }
+}
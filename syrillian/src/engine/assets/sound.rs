@@ -1,15 +1,60 @@
 use crate::assets::{H, HandleName, StoreType};
-use delegate::delegate;
-use kira::sound::static_sound::{StaticSoundData, StaticSoundSettings};
-use kira::sound::{IntoOptionalRegion, PlaybackPosition};
+use kira::sound::static_sound::{StaticSoundData, StaticSoundHandle, StaticSoundSettings};
+#[cfg(not(target_arch = "wasm32"))]
+use kira::sound::streaming::{StreamingSoundData, StreamingSoundHandle, StreamingSoundSettings};
+#[cfg(not(target_arch = "wasm32"))]
+use kira::sound::{FromFileError, Region};
+use kira::sound::{IntoOptionalRegion, PlaybackPosition, PlaybackState};
+use kira::track::SpatialTrackHandle;
 use kira::{Decibels, Frame, Panning, PlaybackRate, StartTime, Tween, Value};
+use snafu::Snafu;
 use std::error::Error;
 use std::io::Cursor;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+use tracing::warn;
 use web_time::Duration;
 
+#[derive(Debug, Snafu)]
+#[snafu(context(suffix(Err)))]
+pub enum SoundError {
+    #[snafu(display("failed to play sound: {reason}"))]
+    Play { reason: String },
+}
+
+/// A sound asset, either fully decoded in memory or streamed from disk while playing.
+///
+/// Streamed sounds have no decoded data to inspect, so [`sample_rate`](Self::sample_rate),
+/// [`frames`](Self::frames) and [`inner`](Self::inner) return an `Option`. They used to return
+/// the value directly, and for sounds in memory they're always `Some`.
 #[derive(Debug, Clone)]
 pub struct Sound {
-    inner: StaticSoundData,
+    inner: SoundData,
+}
+
+#[derive(Debug, Clone)]
+enum SoundData {
+    Static(StaticSoundData),
+    #[cfg(not(target_arch = "wasm32"))]
+    Streaming(StreamSource),
+}
+
+/// A streamed sound is opened again every time it's played, since kira consumes the decoder
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+struct StreamSource {
+    path: PathBuf,
+    settings: StreamingSoundSettings,
+    slice: Option<Region>,
+    duration: Duration,
+}
+
+/// Controls a playing [`Sound`]
+#[derive(Debug)]
+pub enum SoundHandle {
+    Static(StaticSoundHandle),
+    #[cfg(not(target_arch = "wasm32"))]
+    Streaming(StreamingSoundHandle<FromFileError>),
 }
 
 impl StoreType for Sound {
@@ -26,12 +71,29 @@ impl StoreType for Sound {
     }
 }
 
+/// Sets a field that static and streaming sound settings share
+macro_rules! set_setting {
+    ($self:ident, $field:ident, $value:expr) => {{
+        let value = $value;
+        match &mut $self.inner {
+            SoundData::Static(data) => data.settings.$field = value,
+            #[cfg(not(target_arch = "wasm32"))]
+            SoundData::Streaming(stream) => stream.settings.$field = value,
+        }
+        $self
+    }};
+}
+
 impl Sound {
+    /// Loads and decodes the whole file into memory. Best for short sound effects that are
+    /// played often, see [`stream_from_file`](Self::stream_from_file) for long music tracks.
     #[cfg(not(target_arch = "wasm32"))]
     pub fn load_sound(path: &str) -> Result<Sound, Box<dyn Error>> {
         let data = StaticSoundData::from_file(path)?;
 
-        let sound = Sound { inner: data };
+        let sound = Sound {
+            inner: SoundData::Static(data),
+        };
 
         Ok(sound)
     }
@@ -39,66 +101,232 @@ impl Sound {
     pub fn load_sound_data(sound: Vec<u8>) -> Result<Sound, Box<dyn Error>> {
         let data = StaticSoundData::from_cursor(Cursor::new(sound))?;
 
-        let sound = Sound { inner: data };
+        let sound = Sound {
+            inner: SoundData::Static(data),
+        };
 
         Ok(sound)
     }
 
-    delegate! {
-        to self.inner {
-            #[expr(self.inner.sample_rate)]
-            pub fn sample_rate(&self) -> u32;
+    /// Creates a sound that's decoded from disk bit by bit while it plays, so long music tracks
+    /// don't have to be resident in memory.
+    ///
+    /// Every playback opens the file again, which adds some latency on start, seeking and
+    /// looping. Prefer [`load_sound`](Self::load_sound) for short effects played in rapid
+    /// succession. Streamed sounds can't be reversed or inspected frame by frame.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn stream_from_file(path: impl Into<PathBuf>) -> Result<Sound, Box<dyn Error>> {
+        let path = path.into();
+        // opening it once up front reports broken files right away
+        let data = StreamingSoundData::from_file(&path)?;
+
+        let sound = Sound {
+            inner: SoundData::Streaming(StreamSource {
+                path,
+                settings: StreamingSoundSettings::default(),
+                slice: None,
+                duration: data.duration(),
+            }),
+        };
 
-            #[call(num_frames)]
-            pub fn frames(&self) -> usize;
+        Ok(sound)
+    }
 
-            pub fn duration(&self) -> Duration;
-            pub fn unsliced_duration(&self) -> Duration;
-            pub fn frame_at_index(&self, index: usize) -> Option<Frame>;
+    /// Returns true if the sound is streamed from disk instead of being kept in memory
+    pub fn is_streaming(&self) -> bool {
+        !matches!(self.inner, SoundData::Static(_))
+    }
 
-            #[expr(self.inner = $; self)]
-            #[call(start_time)]
-            pub fn set_start_time(&mut self, start_time: impl Into<StartTime>) -> &mut Self;
+    /// Returns the sample rate of a sound in memory. `None` for streamed sounds.
+    pub fn sample_rate(&self) -> Option<u32> {
+        self.static_data().map(|data| data.sample_rate)
+    }
 
-            #[expr(self.inner = $; self)]
-            #[call(start_position)]
-            pub fn set_start_position(&mut self, start_position: impl Into<PlaybackPosition>) -> &mut Self;
+    /// Returns the number of frames of a sound in memory. `None` for streamed sounds.
+    pub fn frames(&self) -> Option<usize> {
+        self.static_data().map(StaticSoundData::num_frames)
+    }
 
-            #[expr(self.inner = $; self)]
-            pub fn reverse(&mut self, reverse: bool) -> &mut Self;
+    /// Returns the frame at `index` of a sound in memory. `None` for streamed sounds.
+    pub fn frame_at_index(&self, index: usize) -> Option<Frame> {
+        self.static_data()?.frame_at_index(index)
+    }
 
-            #[expr(self.inner = $; self)]
-            #[call(volume)]
-            pub fn set_volume(&mut self, volume: impl Into<Value<Decibels>>) -> &mut Self;
+    /// Returns the playback duration. For streamed sounds, this is the length of the whole
+    /// file, even if it was sliced.
+    pub fn duration(&self) -> Duration {
+        match &self.inner {
+            SoundData::Static(data) => data.duration(),
+            #[cfg(not(target_arch = "wasm32"))]
+            SoundData::Streaming(stream) => stream.duration,
+        }
+    }
 
-            #[expr(self.inner = $; self)]
-            #[call(playback_rate)]
-            pub fn set_speed(&mut self, speed: impl Into<Value<PlaybackRate>>) -> &mut Self;
+    pub fn unsliced_duration(&self) -> Duration {
+        match &self.inner {
+            SoundData::Static(data) => data.unsliced_duration(),
+            #[cfg(not(target_arch = "wasm32"))]
+            SoundData::Streaming(stream) => stream.duration,
+        }
+    }
+
+    pub fn set_start_time(&mut self, start_time: impl Into<StartTime>) -> &mut Self {
+        set_setting!(self, start_time, start_time.into())
+    }
 
-            #[expr(self.inner = $; self)]
-            #[call(panning)]
-            pub fn set_panning(&mut self, panning: impl Into<Value<Panning>>) -> &mut Self;
+    pub fn set_start_position(&mut self, start_position: impl Into<PlaybackPosition>) -> &mut Self {
+        set_setting!(self, start_position, start_position.into())
+    }
 
-            #[expr(self.inner = $; self)]
-            #[call(fade_in_tween)]
-            pub fn set_fade_in(&mut self, tween: impl Into<Option<Tween>>) -> &mut Self;
+    /// Loops the given region of the sound without a gap. Works for streamed sounds too.
+    pub fn set_loop_region(&mut self, loop_region: impl IntoOptionalRegion) -> &mut Self {
+        set_setting!(self, loop_region, loop_region.into_optional_region())
+    }
 
-            #[expr(self.inner = $; self)]
-            pub fn slice(&mut self, slice: impl IntoOptionalRegion) -> &mut Self;
+    /// Plays the sound backwards. Not supported for streamed sounds.
+    pub fn reverse(&mut self, reverse: bool) -> &mut Self {
+        match &mut self.inner {
+            SoundData::Static(data) => data.settings.reverse = reverse,
+            #[cfg(not(target_arch = "wasm32"))]
+            SoundData::Streaming(_) => warn!("Streamed sounds can't be played in reverse"),
         }
+        self
+    }
+
+    pub fn set_volume(&mut self, volume: impl Into<Value<Decibels>>) -> &mut Self {
+        set_setting!(self, volume, volume.into())
+    }
+
+    pub fn set_speed(&mut self, speed: impl Into<Value<PlaybackRate>>) -> &mut Self {
+        set_setting!(self, playback_rate, speed.into())
+    }
+
+    pub fn set_panning(&mut self, panning: impl Into<Value<Panning>>) -> &mut Self {
+        set_setting!(self, panning, panning.into())
+    }
+
+    pub fn set_fade_in(&mut self, tween: impl Into<Option<Tween>>) -> &mut Self {
+        set_setting!(self, fade_in_tween, tween.into())
+    }
+
+    pub fn slice(&mut self, slice: impl IntoOptionalRegion) -> &mut Self {
+        match &mut self.inner {
+            SoundData::Static(data) => *data = data.slice(slice),
+            #[cfg(not(target_arch = "wasm32"))]
+            SoundData::Streaming(stream) => stream.slice = slice.into_optional_region(),
+        }
+        self
     }
 
     pub fn with_settings(&self, settings: StaticSoundSettings) -> Self {
+        let inner = match &self.inner {
+            SoundData::Static(data) => SoundData::Static(data.with_settings(settings)),
+            #[cfg(not(target_arch = "wasm32"))]
+            SoundData::Streaming(stream) => SoundData::Streaming(StreamSource {
+                settings: StreamingSoundSettings {
+                    start_time: settings.start_time,
+                    start_position: settings.start_position,
+                    loop_region: settings.loop_region,
+                    volume: settings.volume,
+                    playback_rate: settings.playback_rate,
+                    panning: settings.panning,
+                    fade_in_tween: settings.fade_in_tween,
+                },
+                ..stream.clone()
+            }),
+        };
+        Sound { inner }
+    }
+
+    /// Returns the decoded sound data. `None` for streamed sounds.
+    pub fn inner(&self) -> Option<StaticSoundData> {
+        self.static_data().cloned()
+    }
+
+    pub fn from_data(data: StaticSoundData) -> Self {
         Sound {
-            inner: self.inner.with_settings(settings),
+            inner: SoundData::Static(data),
         }
     }
 
-    pub fn inner(&self) -> StaticSoundData {
-        self.inner.clone()
+    /// Starts playing the sound on the track. Streamed sounds are opened from disk here.
+    pub fn play_on(&self, track: &mut SpatialTrackHandle) -> Result<SoundHandle, SoundError> {
+        match &self.inner {
+            SoundData::Static(data) => {
+                track
+                    .play(data.clone())
+                    .map(SoundHandle::Static)
+                    .map_err(|e| SoundError::Play {
+                        reason: e.to_string(),
+                    })
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            SoundData::Streaming(stream) => {
+                let data = StreamingSoundData::from_file(&stream.path)
+                    .map_err(|e| SoundError::Play {
+                        reason: e.to_string(),
+                    })?
+                    .with_settings(stream.settings)
+                    .slice(stream.slice);
+
+                track
+                    .play(data)
+                    .map(SoundHandle::Streaming)
+                    .map_err(|e| SoundError::Play {
+                        reason: e.to_string(),
+                    })
+            }
+        }
     }
 
-    pub fn from_data(data: StaticSoundData) -> Self {
-        Sound { inner: data }
+    fn static_data(&self) -> Option<&StaticSoundData> {
+        match &self.inner {
+            SoundData::Static(data) => Some(data),
+            #[cfg(not(target_arch = "wasm32"))]
+            SoundData::Streaming(_) => None,
+        }
+    }
+}
+
+impl SoundHandle {
+    pub fn state(&self) -> PlaybackState {
+        match self {
+            SoundHandle::Static(handle) => handle.state(),
+            #[cfg(not(target_arch = "wasm32"))]
+            SoundHandle::Streaming(handle) => handle.state(),
+        }
+    }
+
+    /// Returns the current playback position in seconds
+    pub fn position(&self) -> f64 {
+        match self {
+            SoundHandle::Static(handle) => handle.position(),
+            #[cfg(not(target_arch = "wasm32"))]
+            SoundHandle::Streaming(handle) => handle.position(),
+        }
+    }
+
+    pub fn seek_to(&mut self, position: f64) {
+        match self {
+            SoundHandle::Static(handle) => handle.seek_to(position),
+            #[cfg(not(target_arch = "wasm32"))]
+            SoundHandle::Streaming(handle) => handle.seek_to(position),
+        }
+    }
+
+    pub fn seek_by(&mut self, amount: f64) {
+        match self {
+            SoundHandle::Static(handle) => handle.seek_by(amount),
+            #[cfg(not(target_arch = "wasm32"))]
+            SoundHandle::Streaming(handle) => handle.seek_by(amount),
+        }
+    }
+
+    pub fn stop(&mut self, tween: Tween) {
+        match self {
+            SoundHandle::Static(handle) => handle.stop(tween),
+            #[cfg(not(target_arch = "wasm32"))]
+            SoundHandle::Streaming(handle) => handle.stop(tween),
+        }
     }
 }
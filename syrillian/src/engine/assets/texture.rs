@@ -2,6 +2,7 @@ use crate::engine::assets::generic_store::{HandleName, Store, StoreDefaults, Sto
 use crate::engine::assets::{H, HTexture, StoreTypeFallback};
 use crate::rendering::RenderMsg;
 use crate::{World, store_add_checked};
+use image::DynamicImage;
 use std::error::Error;
 use std::fs;
 use std::path::PathBuf;
@@ -44,6 +45,9 @@ impl H<Texture> {
 }
 
 impl Texture {
+    /// The color format of the main pipeline. Camera render targets need to use this format.
+    pub const RENDER_TARGET_FORMAT: TextureFormat = TextureFormat::Bgra8UnormSrgb;
+
     pub fn gen_fallback_diffuse(width: u32, height: u32) -> Vec<u8> {
         let mut diffuse = vec![];
         for x in 0..width as i32 {
@@ -73,6 +77,25 @@ impl Texture {
         }
     }
 
+    /// Creates an empty texture that a camera can render into.
+    ///
+    /// See [`CameraComponent::set_render_target`](crate::components::CameraComponent::set_render_target).
+    /// Change the size in the store to resize it.
+    pub fn new_render_target(width: u32, height: u32) -> Self {
+        Self {
+            width: width.max(1),
+            height: height.max(1),
+            format: Self::RENDER_TARGET_FORMAT,
+            data: None,
+            view_formats: [Self::RENDER_TARGET_FORMAT],
+            array_layers: 1,
+            repeat_mode: AddressMode::ClampToEdge,
+            filter_mode: FilterMode::Linear,
+            mip_filter_mode: MipmapFilterMode::Linear,
+            has_transparency: false,
+        }
+    }
+
     pub(crate) fn desc(&self) -> TextureDescriptor<'_> {
         let layers = self.array_layers.max(1);
         let usage = TextureUsages::TEXTURE_BINDING
@@ -117,7 +140,7 @@ impl Texture {
         }
     }
 
-    pub fn sampler_desc(&self) -> wgpu::SamplerDescriptor<'_> {
+    pub fn sampler_desc(&self) -> wgpu::SamplerDescriptor<'static> {
         wgpu::SamplerDescriptor {
             address_mode_u: self.repeat_mode,
             address_mode_v: self.repeat_mode,
@@ -136,6 +159,11 @@ impl Texture {
 
     pub fn load_image_from_memory(bytes: &[u8]) -> Result<Texture, Box<dyn Error>> {
         let image = image::load_from_memory(bytes)?;
+        Ok(Self::load_decoded_image(image, true))
+    }
+
+    /// Converts a decoded image to BGRA. Colors are `srgb`, data like normal maps is linear.
+    pub(crate) fn load_decoded_image(image: DynamicImage, srgb: bool) -> Texture {
         let rgba = image.into_rgba8();
 
         let mut data = Vec::with_capacity((rgba.width() * rgba.height() * 4) as usize);
@@ -146,12 +174,12 @@ impl Texture {
             data.push(pixel[3]); // A
         }
 
-        Ok(Self::load_pixels(
-            data,
-            rgba.width(),
-            rgba.height(),
-            TextureFormat::Bgra8UnormSrgb,
-        ))
+        let format = if srgb {
+            TextureFormat::Bgra8UnormSrgb
+        } else {
+            TextureFormat::Bgra8Unorm
+        };
+        Self::load_pixels(data, rgba.width(), rgba.height(), format)
     }
 
     pub fn load_pixels(pixels: Vec<u8>, width: u32, height: u32, format: TextureFormat) -> Texture {
@@ -1,8 +1,10 @@
+use crate::assets::{Sound, SoundHandle};
 use kira::listener::ListenerHandle;
+use kira::sound::PlaybackState;
 use kira::track::{SpatialTrackBuilder, SpatialTrackHandle};
 use kira::{AudioManager, AudioManagerSettings, DefaultBackend, Tween};
 use nalgebra::{Quaternion, Vector3};
-use tracing::error;
+use tracing::{error, warn};
 
 struct AudioSceneInner {
     manager: AudioManager<DefaultBackend>,
@@ -35,26 +37,42 @@ impl AudioSceneInner {
     }
 }
 
+/// A sound playing on its own track. The track has to stay alive until the sound finished.
+struct OneShot {
+    _track: SpatialTrackHandle,
+    sound: SoundHandle,
+}
+
 pub struct AudioScene {
     inner: Option<AudioSceneInner>,
+    one_shots: Vec<OneShot>,
+    receiver_position: Vector3<f32>,
 }
 
 impl Default for AudioScene {
     fn default() -> Self {
         Self {
             inner: AudioSceneInner::new(),
+            one_shots: Vec::new(),
+            receiver_position: Vector3::zeros(),
         }
     }
 }
 
 impl AudioScene {
     pub fn set_receiver_position(&mut self, receiver_position: Vector3<f32>) {
+        self.receiver_position = receiver_position;
         if let Some(this) = self.inner.as_mut() {
             this.listener
                 .set_position(receiver_position, Tween::default())
         }
     }
 
+    /// Returns the position sounds are heard from, as set by the [`AudioReceiver`](crate::components::audio::AudioReceiver)
+    pub fn receiver_position(&self) -> Vector3<f32> {
+        self.receiver_position
+    }
+
     pub fn set_receiver_orientation(&mut self, receiver_orientation: Quaternion<f32>) {
         if let Some(this) = self.inner.as_mut() {
             this.listener
@@ -74,4 +92,43 @@ impl AudioScene {
                 .ok()
         })
     }
+
+    /// Plays a sound once at a fixed position, on a track of its own. Overlapping sounds mix
+    /// instead of cutting each other off.
+    ///
+    /// The track is freed by [`cleanup_one_shots`](Self::cleanup_one_shots) once the sound
+    /// finished. Returns false if the sound couldn't be played.
+    pub fn play_one_shot(&mut self, sound: &Sound, position: Vector3<f32>) -> bool {
+        self.cleanup_one_shots();
+
+        let Some(mut track) = self.add_spatial_track(position, SpatialTrackBuilder::new()) else {
+            warn!("Spatial track limit reached");
+            return false;
+        };
+
+        match sound.play_on(&mut track) {
+            Ok(sound) => {
+                self.one_shots.push(OneShot {
+                    _track: track,
+                    sound,
+                });
+                true
+            }
+            Err(e) => {
+                warn!("{e}");
+                false
+            }
+        }
+    }
+
+    /// Frees the tracks of one-shot sounds that finished playing
+    pub fn cleanup_one_shots(&mut self) {
+        self.one_shots
+            .retain(|one_shot| one_shot.sound.state() != PlaybackState::Stopped);
+    }
+
+    /// Returns how many one-shot sounds are still playing
+    pub fn one_shot_count(&self) -> usize {
+        self.one_shots.len()
+    }
 }
@@ -66,6 +66,19 @@ impl Component for AnimationComponent {
 
         self.evaluate_and_apply(clip_index, time, weight);
     }
+
+    fn duplicate(&self, mut target: GameObjectId, world: &mut World) {
+        let mut copy = target.add_component::<AnimationComponent>();
+        copy.set_clips(self.clips.clone());
+        copy.current = self.current.clone();
+
+        // the animated children are duplicated after this, so they're bound once they exist
+        world.commands().push(move |_| {
+            if copy.parent.exists() {
+                copy.resolve_bindings();
+            }
+        });
+    }
 }
 
 impl AnimationComponent {
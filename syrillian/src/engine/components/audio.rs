@@ -1,13 +1,27 @@
 use crate::World;
-use crate::assets::HSound;
+use crate::assets::{HSound, SoundHandle};
 use crate::components::{Component, NewComponent};
 use crate::core::GameObjectId;
-use kira::Tween;
+use kira::effect::filter::{FilterBuilder, FilterHandle};
+use kira::effect::volume_control::{VolumeControlBuilder, VolumeControlHandle};
 use kira::sound::PlaybackState;
-use kira::sound::static_sound::StaticSoundHandle;
 use kira::track::{SpatialTrackBuilder, SpatialTrackHandle};
+use kira::{Decibels, Tween};
+use rapier3d::prelude::QueryFilter;
+use std::time::Duration;
 use tracing::{trace, warn};
 
+/// Frames between two occlusion raycasts of an emitter
+const OCCLUSION_CHECK_INTERVAL: u32 = 5;
+/// Occlusion added by every object between the emitter and the receiver
+const OCCLUSION_PER_OCCLUDER: f32 = 0.4;
+/// Occlusion added per meter of occluding material
+const OCCLUSION_PER_METER: f32 = 0.2;
+/// Low-pass cutoff in Hz while nothing is in the way
+const OPEN_CUTOFF: f64 = 20_000.0;
+/// Low-pass cutoff in Hz when fully occluded
+const OCCLUDED_CUTOFF: f64 = 400.0;
+
 pub struct AudioReceiver {
     parent: GameObjectId,
 }
@@ -15,10 +29,19 @@ pub struct AudioReceiver {
 pub struct AudioEmitter {
     parent: GameObjectId,
     asset_handle: Option<HSound>,
-    sound_handle: Option<StaticSoundHandle>,
+    sound_handle: Option<SoundHandle>,
+    start_offset: Option<f64>,
     track_handle: Option<SpatialTrackHandle>,
+    one_shots: Vec<SoundHandle>,
+    queued_one_shots: Vec<HSound>,
     looping: bool,
     play_triggered: bool,
+    occlusion_enabled: bool,
+    max_occlusion_attenuation: f32,
+    occlusion: f32,
+    occlusion_filter: Option<FilterHandle>,
+    occlusion_volume: Option<VolumeControlHandle>,
+    occlusion_check_in: u32,
 }
 
 impl NewComponent for AudioEmitter {
@@ -27,9 +50,18 @@ impl NewComponent for AudioEmitter {
             parent,
             asset_handle: None,
             sound_handle: None,
+            start_offset: None,
             track_handle: None,
+            one_shots: Vec::new(),
+            queued_one_shots: Vec::new(),
             looping: false,
             play_triggered: false,
+            occlusion_enabled: false,
+            max_occlusion_attenuation: 18.0,
+            occlusion: 0.0,
+            occlusion_filter: None,
+            occlusion_volume: None,
+            occlusion_check_in: 0,
         }
     }
 }
@@ -37,13 +69,12 @@ impl NewComponent for AudioEmitter {
 impl Component for AudioEmitter {
     fn init(&mut self, world: &mut World) {
         trace!("Initializing new Spatial Track");
-        self.track_handle = world
-            .audio
-            .add_spatial_track(self.parent.transform.position(), SpatialTrackBuilder::new());
+        self.add_track(world, SpatialTrackBuilder::new());
     }
 
     fn update(&mut self, world: &mut World) {
         let Some(track) = self.track_handle.as_mut() else {
+            self.queued_one_shots.clear();
             return;
         };
 
@@ -53,6 +84,18 @@ impl Component for AudioEmitter {
         if self.play_triggered || (self.looping && !self.is_playing()) {
             self._play(world);
         }
+
+        self.play_queued_one_shots(world);
+        self.update_occlusion(world);
+    }
+
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        // the copy gets a default track, the effects of a custom one can't be copied
+        let mut copy = target.add_component::<AudioEmitter>();
+        copy.asset_handle = self.asset_handle;
+        copy.looping = self.looping;
+        copy.play_triggered = self.play_triggered || self.is_playing();
+        copy.set_occlusion(self.occlusion_enabled, self.max_occlusion_attenuation);
     }
 }
 
@@ -91,10 +134,45 @@ impl AudioEmitter {
 
         self.play_triggered = false;
 
-        match track.play(sound.inner()) {
+        let result = match self.start_offset.take() {
+            Some(offset) => sound.clone().set_start_position(offset).play_on(track),
+            None => sound.play_on(track),
+        };
+
+        match result {
             Ok(handle) => self.sound_handle = Some(handle),
             Err(e) => {
-                warn!("Error when playing sound: {e}")
+                warn!("{e}")
+            }
+        }
+    }
+
+    /// Plays a sound once on this emitter's track, without stopping the main sound or earlier
+    /// one-shots. The sound follows the emitter and uses the effects of its track.
+    ///
+    /// Finished one-shots are cleaned up automatically. They stop early if the emitter is removed,
+    /// use [`World::play_sound_at`] for sounds that should outlive it.
+    pub fn play_one_shot(&mut self, sound: HSound) {
+        self.queued_one_shots.push(sound);
+    }
+
+    fn play_queued_one_shots(&mut self, world: &World) {
+        self.one_shots
+            .retain(|handle| handle.state() != PlaybackState::Stopped);
+
+        let Some(track) = self.track_handle.as_mut() else {
+            return;
+        };
+
+        for h in self.queued_one_shots.drain(..) {
+            let Some(sound) = world.assets.sounds.try_get(h) else {
+                warn!("AudioEmitter one-shot had no sound handle");
+                continue;
+            };
+
+            match sound.play_on(track) {
+                Ok(handle) => self.one_shots.push(handle),
+                Err(e) => warn!("{e}"),
             }
         }
     }
@@ -120,6 +198,40 @@ impl AudioEmitter {
         }
     }
 
+    /// Jumps to `seconds` into the sound. If nothing is playing, the next play starts there.
+    pub fn seek_to(&mut self, seconds: f64) {
+        match self.active_handle() {
+            Some(handle) => handle.seek_to(seconds),
+            None => self.start_offset = Some(seconds.max(0.0)),
+        }
+    }
+
+    /// Moves the playback position by `delta` seconds. If nothing is playing, this moves the
+    /// position the next play starts at.
+    pub fn seek_by(&mut self, delta: f64) {
+        match self.active_handle() {
+            Some(handle) => handle.seek_by(delta),
+            None => {
+                let offset = self.start_offset.unwrap_or(0.0) + delta;
+                self.start_offset = Some(offset.max(0.0));
+            }
+        }
+    }
+
+    /// Returns the playback position of the main sound, or `None` if it isn't playing
+    pub fn position(&self) -> Option<Duration> {
+        self.sound_handle
+            .as_ref()
+            .filter(|handle| handle.state() != PlaybackState::Stopped)
+            .map(|handle| Duration::from_secs_f64(handle.position().max(0.0)))
+    }
+
+    fn active_handle(&mut self) -> Option<&mut SoundHandle> {
+        self.sound_handle
+            .as_mut()
+            .filter(|handle| handle.state() != PlaybackState::Stopped)
+    }
+
     pub fn is_playing(&self) -> bool {
         self.sound_handle
             .as_ref()
@@ -133,13 +245,84 @@ impl AudioEmitter {
     }
 
     pub fn set_track(&mut self, world: &mut World, track: SpatialTrackBuilder) -> &mut Self {
-        let pos = self.parent.transform.position();
-        self.track_handle = world.audio.add_spatial_track(pos, track);
+        self.add_track(world, track);
         if self.track_handle.is_none() {
             warn!("Spatial track limit reached");
         }
         self
     }
+
+    fn add_track(&mut self, world: &mut World, mut track: SpatialTrackBuilder) {
+        // the occlusion effects are always part of the track, since effects can't be added later.
+        // attenuating with an effect leaves the track volume to the user
+        let filter = track.add_effect(FilterBuilder::new().cutoff(OPEN_CUTOFF));
+        let volume = track.add_effect(VolumeControlBuilder::new(Decibels::IDENTITY));
+        let pos = self.parent.transform.position();
+        self.track_handle = world.audio.add_spatial_track(pos, track);
+        self.occlusion_filter = Some(filter);
+        self.occlusion_volume = Some(volume);
+        self.occlusion = 0.0;
+    }
+
+    /// Muffles the emitter while objects with colliders are between it and the receiver.
+    ///
+    /// The more objects and the thicker they are, the lower the low-pass cutoff and the quieter
+    /// the sound, down to `max_attenuation` decibels. The check is a raycast every few frames.
+    pub fn set_occlusion(&mut self, enabled: bool, max_attenuation: f32) {
+        self.occlusion_enabled = enabled;
+        self.max_occlusion_attenuation = max_attenuation.max(0.0);
+        self.occlusion_check_in = 0;
+    }
+
+    pub fn is_occlusion_enabled(&self) -> bool {
+        self.occlusion_enabled
+    }
+
+    /// Returns how occluded the emitter currently is, from 0 (free) to 1 (fully occluded)
+    pub fn occlusion(&self) -> f32 {
+        self.occlusion
+    }
+
+    fn update_occlusion(&mut self, world: &World) {
+        let target = if self.occlusion_enabled {
+            if self.occlusion_check_in > 0 {
+                self.occlusion_check_in -= 1;
+                return;
+            }
+            self.occlusion_check_in = OCCLUSION_CHECK_INTERVAL;
+
+            let occluders = world.physics.segment_occluders(
+                self.parent.transform.position(),
+                world.audio.receiver_position(),
+                QueryFilter::default(),
+            );
+            occluders
+                .iter()
+                .map(|(_, thickness)| OCCLUSION_PER_OCCLUDER + thickness * OCCLUSION_PER_METER)
+                .sum::<f32>()
+                .min(1.0)
+        } else {
+            0.0
+        };
+
+        if (target - self.occlusion).abs() < f32::EPSILON {
+            return;
+        }
+        self.occlusion = target;
+
+        let tween = Tween {
+            duration: Duration::from_millis(100),
+            ..Tween::default()
+        };
+        // interpolate exponentially, since pitch is perceived logarithmically
+        let cutoff = OPEN_CUTOFF * (OCCLUDED_CUTOFF / OPEN_CUTOFF).powf(target as f64);
+        if let Some(filter) = self.occlusion_filter.as_mut() {
+            filter.set_cutoff(cutoff, tween);
+        }
+        if let Some(volume) = self.occlusion_volume.as_mut() {
+            volume.set_volume(Decibels(-self.max_occlusion_attenuation * target), tween);
+        }
+    }
 }
 
 impl NewComponent for AudioReceiver {
@@ -155,4 +338,70 @@ impl Component for AudioReceiver {
         world.audio.set_receiver_position(transform.position());
         world.audio.set_receiver_orientation(*transform.rotation());
     }
+
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        target.add_component::<AudioReceiver>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assets::Sound;
+    use crate::components::CRef;
+
+    fn emitter(world: &mut World) -> CRef<AudioEmitter> {
+        let mut obj = world.new_object("emitter");
+        world.add_child(obj);
+
+        let sound = Sound::load_sound("examples/assets/pop.wav").expect("Failed to load sound");
+        let sound = world.assets.sounds.add(sound);
+        let mut emitter = obj.add_component::<AudioEmitter>();
+        emitter.set_sound(sound);
+        emitter
+    }
+
+    #[test]
+    fn seeking_a_stopped_emitter_moves_the_start() {
+        let (mut world, ..) = World::fresh();
+        let mut emitter = emitter(&mut world);
+        assert_eq!(emitter.position(), None);
+
+        emitter.seek_to(2.0);
+        assert_eq!(emitter.start_offset, Some(2.0));
+        emitter.seek_by(-0.5);
+        assert_eq!(emitter.start_offset, Some(1.5));
+        emitter.seek_by(-5.0);
+        assert_eq!(emitter.start_offset, Some(0.0));
+        emitter.seek_to(-1.0);
+        assert_eq!(emitter.start_offset, Some(0.0));
+
+        emitter.seek_by(0.25);
+        assert_eq!(emitter.start_offset, Some(0.25));
+        assert_eq!(emitter.position(), None);
+    }
+
+    #[test]
+    fn playing_starts_at_the_seeked_position() {
+        let (mut world, ..) = World::fresh();
+        let mut emitter = emitter(&mut world);
+
+        emitter.seek_to(0.1);
+        emitter.play();
+        world.update();
+
+        if !emitter.is_playing() {
+            // without an audio device nothing plays, and the seek is kept for the next play
+            assert_eq!(emitter.position(), None);
+            assert_eq!(emitter.start_offset, Some(0.1));
+            return;
+        }
+
+        // the offset is used up by the play
+        assert_eq!(emitter.start_offset, None);
+        assert!(emitter.position().is_some());
+
+        emitter.stop();
+        assert_eq!(emitter.position(), None);
+    }
 }
@@ -0,0 +1,129 @@
+use crate::World;
+use crate::components::{Component, NewComponent};
+use crate::core::GameObjectId;
+use nalgebra::{Affine3, Matrix4, Translation3, UnitQuaternion, Vector3};
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum BillboardMode {
+    /// Turns fully towards the camera
+    #[default]
+    Full,
+    /// Only rotates around the world up axis, so the object stays upright
+    YAxisLocked,
+}
+
+/// Makes an object's visuals face the active camera, e.g. for sprites, health bars or impostors.
+///
+/// The object's local +Z axis is turned towards the camera. This only replaces the rotation
+/// that is sent to the renderer for the components on the same object, so the object's
+/// position and scale still apply and its [`Transform`](crate::core::Transform) is left untouched.
+///
+/// Children do **not** inherit the billboard rotation. They keep following the object's real
+/// transform, so add a `Billboard` to each child that should face the camera as well.
+#[derive(Debug)]
+pub struct Billboard {
+    mode: BillboardMode,
+    /// The facing transform last sent to the renderer
+    sent_facing: Option<Affine3<f32>>,
+}
+
+impl NewComponent for Billboard {
+    fn new(_parent: GameObjectId) -> Self {
+        Billboard {
+            mode: BillboardMode::default(),
+            sent_facing: None,
+        }
+    }
+}
+
+impl Component for Billboard {
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        target.add_component::<Billboard>().mode = self.mode;
+    }
+}
+
+impl Billboard {
+    pub fn mode(&self) -> BillboardMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: BillboardMode) {
+        self.mode = mode;
+    }
+
+    /// Returns `local_to_world` with its rotation replaced by one facing `camera_position`.
+    ///
+    /// `camera_up` keeps the full mode from rolling around the view direction.
+    pub fn facing_transform(
+        &self,
+        local_to_world: &Affine3<f32>,
+        camera_position: Vector3<f32>,
+        camera_up: Vector3<f32>,
+    ) -> Affine3<f32> {
+        self.mode
+            .facing_transform(local_to_world, camera_position, camera_up)
+    }
+
+    /// Like [`facing_transform`](Self::facing_transform), but returns `None` if the facing is
+    /// the same as the one returned last, so it only has to be sent when it changed.
+    pub(crate) fn changed_facing(
+        &mut self,
+        local_to_world: &Affine3<f32>,
+        camera_position: Vector3<f32>,
+        camera_up: Vector3<f32>,
+    ) -> Option<Affine3<f32>> {
+        let facing = self.facing_transform(local_to_world, camera_position, camera_up);
+        if self.sent_facing == Some(facing) {
+            return None;
+        }
+        self.sent_facing = Some(facing);
+        Some(facing)
+    }
+
+    /// Makes the next [`changed_facing`](Self::changed_facing) return the facing again, e.g.
+    /// after the renderer got the object's real transform
+    pub(crate) fn reset_facing(&mut self) {
+        self.sent_facing = None;
+    }
+}
+
+impl BillboardMode {
+    /// See [`Billboard::facing_transform`]
+    pub(crate) fn facing_transform(
+        self,
+        local_to_world: &Affine3<f32>,
+        camera_position: Vector3<f32>,
+        camera_up: Vector3<f32>,
+    ) -> Affine3<f32> {
+        let matrix = local_to_world.matrix();
+        let position = Vector3::new(matrix[(0, 3)], matrix[(1, 3)], matrix[(2, 3)]);
+        let scale = Vector3::new(
+            matrix.fixed_view::<3, 1>(0, 0).norm(),
+            matrix.fixed_view::<3, 1>(0, 1).norm(),
+            matrix.fixed_view::<3, 1>(0, 2).norm(),
+        );
+
+        let mut to_camera = camera_position - position;
+        let up = match self {
+            BillboardMode::Full => camera_up,
+            BillboardMode::YAxisLocked => {
+                to_camera.y = 0.0;
+                Vector3::y()
+            }
+        };
+
+        let rotation = if to_camera.norm_squared() > f32::EPSILON
+            && to_camera.cross(&up).norm_squared() > f32::EPSILON
+        {
+            UnitQuaternion::face_towards(&to_camera, &up)
+        } else {
+            UnitQuaternion::identity()
+        };
+
+        let facing = Translation3::from(position).to_homogeneous()
+            * rotation.to_homogeneous()
+            * Matrix4::new_nonuniform_scaling(&scale);
+
+        Affine3::from_matrix_unchecked(facing)
+    }
+}
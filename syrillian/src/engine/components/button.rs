@@ -28,6 +28,11 @@ impl Component for Button {
             handler(world);
         }
     }
+
+    // click handlers can't be cloned, so the copy starts without any
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        target.add_component::<Button>();
+    }
 }
 
 impl Button {
@@ -1,16 +1,129 @@
 use crate::World;
+use crate::assets::HTexture;
 use crate::components::{Component, NewComponent};
-use crate::core::GameObjectId;
+use crate::core::{BoundingSphere, Frustum, GameObjectId};
 use crate::utils::FloatMathExt;
 use crate::windowing::RenderTargetId;
-use nalgebra::{Matrix4, Perspective3, Point3, Vector2, Vector4};
+use bitflags::bitflags;
+use nalgebra::{Matrix4, Perspective3, Point3, Vector2, Vector3, Vector4};
 use rapier3d::geometry::Ray;
+use tracing::warn;
+
+bitflags! {
+    /// Selects what a camera clears before it starts drawing
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub struct ClearFlags: u8 {
+        const COLOR  = 1;
+        const DEPTH  = 1 << 1;
+    }
+}
+
+/// The clear configuration of a camera, as seen by the renderer
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CameraClear {
+    pub color: Option<Vector4<f32>>,
+    pub flags: ClearFlags,
+}
+
+impl CameraClear {
+    /// The color this camera clears to, or `None` if the previous color output should be kept
+    pub fn clear_color(&self) -> Option<Vector4<f32>> {
+        if self.flags.contains(ClearFlags::COLOR) {
+            self.color
+        } else {
+            None
+        }
+    }
+
+    pub fn clears_depth(&self) -> bool {
+        self.flags.contains(ClearFlags::DEPTH)
+    }
+}
+
+/// A region of a render target in normalized coordinates, with the origin at the top left
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ViewportRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl ViewportRect {
+    pub const FULL: ViewportRect = ViewportRect::new(0.0, 0.0, 1.0, 1.0);
+
+    pub const fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        ViewportRect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Clamps the rect so it lies fully inside the render target
+    pub fn clamped(self) -> Self {
+        let x = self.x.clamp(0.0, 1.0);
+        let y = self.y.clamp(0.0, 1.0);
+        ViewportRect {
+            x,
+            y,
+            width: self.width.clamp(0.0, 1.0 - x),
+            height: self.height.clamp(0.0, 1.0 - y),
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        *self == Self::FULL
+    }
+
+    /// Returns the rect as `(x, y, width, height)` in pixels of a target with the given size.
+    /// The resulting width and height are at least 1.
+    pub fn to_pixels(&self, width: u32, height: u32) -> (u32, u32, u32, u32) {
+        let w = width.max(1) as f32;
+        let h = height.max(1) as f32;
+        let px = ((self.x * w) as u32).min(width.saturating_sub(1));
+        let py = ((self.y * h) as u32).min(height.saturating_sub(1));
+        let pw = ((self.width * w).round() as u32).clamp(1, width.max(1) - px);
+        let ph = ((self.height * h).round() as u32).clamp(1, height.max(1) - py);
+        (px, py, pw, ph)
+    }
+
+    /// Checks if a point in pixels of a target with the given size lies inside the rect
+    pub fn contains_pixel(&self, x: f32, y: f32, width: f32, height: f32) -> bool {
+        let nx = x / width.max(1.0);
+        let ny = y / height.max(1.0);
+        nx >= self.x && nx < self.x + self.width && ny >= self.y && ny < self.y + self.height
+    }
+}
+
+impl Default for ViewportRect {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+impl Default for CameraClear {
+    fn default() -> Self {
+        CameraClear {
+            color: Some(Vector4::new(0.0, 0.0, 0.0, 1.0)),
+            flags: ClearFlags::COLOR | ClearFlags::DEPTH,
+        }
+    }
+}
+
+const MIN_FOV: f32 = 1.0;
+const MAX_FOV: f32 = 179.0;
+const MIN_NEAR: f32 = 1e-4;
+/// Smallest ratio the far plane has to be behind the near plane
+const MIN_DEPTH_RANGE: f32 = 1e-3;
 
 pub struct CameraComponent {
     pub projection: Perspective3<f32>,
     pub projection_inverse: Matrix4<f32>,
     fov_active: f32,
-    fov_target: f32,
+    fov_base: f32,
+    zoom: f32,
     near: f32,
     far: f32,
     width: f32,
@@ -19,32 +132,73 @@ pub struct CameraComponent {
     pub zoom_speed: f32,
     projection_dirty: bool,
     render_target: RenderTargetId,
+    clear: CameraClear,
+    clear_dirty: bool,
+    viewport: ViewportRect,
+    depth: i32,
+    view_dirty: bool,
+    render_texture: Option<HTexture>,
 }
 
 impl CameraComponent {
-    /// Returns the fov in degrees
+    /// Returns the current fov in degrees, including zoom
     pub fn fov(&self) -> f32 {
         self.fov_active
     }
 
-    /// Sets the fov in degrees. Only use this for camera switches/init etc.
+    /// Returns the fov in degrees without zoom
+    pub fn base_fov(&self) -> f32 {
+        self.fov_base
+    }
+
+    /// Sets the fov in degrees without zoom, clamped between 1° and 179°.
+    ///
+    /// Zoom stays applied on top of it, and the camera snaps to the result right away.
+    pub fn set_fov(&mut self, fov: f32) {
+        self.fov_base = clamp_checked(fov, MIN_FOV, MAX_FOV, "fov");
+        self.fov_active = self.zoomed_fov();
+        self.regenerate();
+    }
+
+    /// Sets the fov in degrees and removes any zoom. Only use this for camera switches/init etc.
     /// Prefer set_fov_target for a smooth zoom.
     pub fn set_fov_instant(&mut self, fov: f32) {
-        self.fov_active = fov;
-        self.regenerate();
+        self.zoom = 1.0;
+        self.set_fov(fov);
     }
 
-    /// Smoothly zoom to the fov using the speed specified with set_zoom_speed
+    /// Smoothly zoom to the fov using the speed specified with set_zoom_speed.
+    ///
+    /// The target is stored relative to the [base fov](Self::base_fov), so
+    /// [`set_zoom(1.0)`](Self::set_zoom) returns to it.
     pub fn set_fov_target(&mut self, fov: f32) {
-        self.fov_target = fov;
+        let fov = clamp_checked(fov, MIN_FOV, MAX_FOV, "fov");
+        self.zoom = half_tan(self.fov_base) / half_tan(fov);
+    }
+
+    /// Returns the zoom magnification the camera moves towards. 1 is no zoom.
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// Smoothly zooms by the magnification on top of the base fov, e.g. 2 shows half the width.
+    /// Values below 1 zoom out.
+    pub fn set_zoom(&mut self, zoom: f32) {
+        let min_zoom = half_tan(self.fov_base) / half_tan(MAX_FOV);
+        let max_zoom = half_tan(self.fov_base) / half_tan(MIN_FOV);
+        self.zoom = clamp_checked(zoom, min_zoom, max_zoom, "zoom");
     }
 
     pub fn near(&self) -> f32 {
         self.near
     }
 
+    /// Sets the distance of the near plane, kept above 0 and in front of the far plane.
+    ///
+    /// Keep it as large as possible, since the depth precision drops quickly with tiny values.
     pub fn set_near(&mut self, near: f32) {
-        self.near = near;
+        let max = self.far / (1.0 + MIN_DEPTH_RANGE);
+        self.near = clamp_checked(near, MIN_NEAR, max, "near plane");
         self.regenerate();
     }
 
@@ -53,28 +207,54 @@ impl CameraComponent {
         self.far
     }
 
+    /// Sets the distance of the far plane, kept behind the near plane
     pub fn set_far(&mut self, far: f32) {
-        self.far = far;
+        let min = self.near * (1.0 + MIN_DEPTH_RANGE);
+        self.far = clamp_checked(far, min, f32::MAX, "far plane");
         self.regenerate();
     }
 
+    fn zoomed_fov(&self) -> f32 {
+        (2.0 * (half_tan(self.fov_base) / self.zoom).atan()).to_degrees()
+    }
+
+    /// Returns the size of the whole render target this camera draws into
     #[inline]
     pub fn resolution(&self) -> (f32, f32) {
         (self.width, self.height)
     }
 
+    /// Returns the size of the region this camera draws into, in pixels
+    #[inline]
+    pub fn viewport_resolution(&self) -> (f32, f32) {
+        (
+            (self.width * self.viewport.width).max(1.0),
+            (self.height * self.viewport.height).max(1.0),
+        )
+    }
+
+    /// Converts a position on the render target to a position relative to this camera's viewport
     #[inline]
     pub fn mouse_viewport_position(&self, x: f32, y: f32) -> Vector2<f32> {
+        let x = x - self.viewport.x * self.width;
+        let y = y - self.viewport.y * self.height;
         Vector2::new(x.max(0.), y.max(0.))
     }
 
     #[inline]
     pub fn mouse_viewport_ndc(&self, x: f32, y: f32) -> Vector2<f32> {
-        let nx = (x / self.width).clamp(0.0, 1.0);
-        let ny = 1.0 - (y / self.height).clamp(0.0, 1.0);
+        let (width, height) = self.viewport_resolution();
+        let local = self.mouse_viewport_position(x, y);
+        let nx = (local.x / width).clamp(0.0, 1.0);
+        let ny = 1.0 - (local.y / height).clamp(0.0, 1.0);
         Vector2::new(nx * 2.0 - 1.0, ny * 2.0 - 1.0)
     }
 
+    /// Checks if a position on the render target lies inside this camera's viewport
+    pub fn viewport_contains(&self, x: f32, y: f32) -> bool {
+        self.viewport.contains_pixel(x, y, self.width, self.height)
+    }
+
     #[inline]
     pub fn mouse_eye_dir(&self, x: f32, y: f32) -> Vector4<f32> {
         let ndc = self.mouse_viewport_ndc(x, y);
@@ -86,9 +266,15 @@ impl CameraComponent {
     }
 
     pub fn click_ray(&self, x: f32, y: f32) -> Ray {
-        let eye = self.mouse_eye_dir(x, y);
+        self.screen_to_ray(Vector2::new(x, y))
+    }
 
-        let cam_to_world = self.parent.transform.view_matrix_rigid().to_matrix();
+    /// Returns the world space ray going from the camera through a pixel of the render target.
+    /// The pixel origin is the top left, like winit's cursor positions.
+    pub fn screen_to_ray(&self, screen: Vector2<f32>) -> Ray {
+        let eye = self.mouse_eye_dir(screen.x, screen.y);
+
+        let cam_to_world = self.parent.transform.rigid_global_isometry().to_matrix();
 
         let dir_world = (cam_to_world * eye).xyz().normalize();
         let origin = cam_to_world.transform_point(&Point3::origin());
@@ -96,9 +282,74 @@ impl CameraComponent {
         Ray::new(origin, dir_world)
     }
 
+    /// Projects a world space point to a pixel of the render target, with the origin at the top
+    /// left. Useful to place UI over 3D objects.
+    ///
+    /// Returns `None` if the point is behind the camera or in front of the near plane. Points
+    /// outside the viewport still return a position outside of its bounds.
+    pub fn world_to_screen(&self, world: Vector3<f32>) -> Option<Vector2<f32>> {
+        let world_to_cam = self.parent.transform.view_matrix_rigid();
+        let eye = world_to_cam.transform_point(&Point3::from(world));
+        if -eye.z < self.near {
+            return None;
+        }
+
+        let ndc = self.projection.project_point(&eye);
+        let (width, height) = self.viewport_resolution();
+        let x = (ndc.x + 1.0) * 0.5 * width + self.viewport.x * self.width;
+        let y = (1.0 - ndc.y) * 0.5 * height + self.viewport.y * self.height;
+        Some(Vector2::new(x, y))
+    }
+
+    /// Returns the world space view volume of the camera, e.g. for
+    /// [`World::objects_in_frustum`]
+    pub fn frustum(&self) -> Frustum {
+        let view_mat = self.parent.transform.view_matrix_rigid().to_matrix();
+        Frustum::from_matrix(&(self.projection.as_matrix() * view_mat))
+    }
+
+    /// Moves the camera back along its current view direction until the object's world bounds
+    /// fit into view, see [`frame_bounds`](Self::frame_bounds).
+    ///
+    /// Returns `false` and leaves the camera untouched if the object has no bounds.
+    pub fn frame_object(&mut self, obj: GameObjectId, padding: f32) -> bool {
+        let Some(bounds) = obj.world_bounds() else {
+            return false;
+        };
+
+        self.frame_bounds(bounds, padding);
+        true
+    }
+
+    /// Moves the camera back along its current view direction until the sphere fits into the
+    /// narrower of the two fov axes. `padding` grows the sphere, e.g. `0.1` leaves 10% margin.
+    ///
+    /// The camera stays at least `near` away from the sphere, and `far` is pushed out if it
+    /// would cut off the back of the sphere.
+    pub fn frame_bounds(&mut self, bounds: BoundingSphere, padding: f32) {
+        let (width, height) = self.viewport_resolution();
+        let half_fov_y = self.fov_active.to_radians() * 0.5;
+        let half_fov_x = (half_fov_y.tan() * width / height).atan();
+        let half_fov = half_fov_y.min(half_fov_x);
+
+        let radius = (bounds.radius * (1.0 + padding.max(0.0))).max(f32::EPSILON);
+        let distance = (radius / half_fov.sin()).max(radius + self.near);
+
+        let mut parent = self.parent;
+        let forward = parent.transform.forward();
+        parent
+            .transform
+            .set_position_vec(bounds.center - forward * distance);
+
+        if distance + radius > self.far {
+            self.set_far(distance + radius);
+        }
+    }
+
     pub fn regenerate(&mut self) {
+        let (width, height) = self.viewport_resolution();
         self.projection = Perspective3::new(
-            self.width / self.height,
+            width / height,
             self.fov_active.to_radians(),
             self.near,
             self.far,
@@ -125,8 +376,96 @@ impl CameraComponent {
         self.render_target
     }
 
-    pub fn set_render_target(&mut self, target: RenderTargetId) {
+    /// Sets the window this camera renders into. See [`Self::set_render_target`] for rendering
+    /// into a texture instead.
+    pub fn set_render_target_id(&mut self, target: RenderTargetId) {
         self.render_target = target;
+        self.mark_view_dirty();
+    }
+
+    /// The texture this camera renders into instead of a window, if any
+    pub fn render_texture(&self) -> Option<HTexture> {
+        self.render_texture
+    }
+
+    /// Renders this camera into a texture instead of a window, so it can be sampled by materials.
+    ///
+    /// Create the texture with [`Texture::new_render_target`](crate::assets::Texture::new_render_target).
+    /// The camera follows the size of the texture and is drawn before any window each frame.
+    /// A camera must not see materials that sample its own texture.
+    ///
+    /// `None` makes the camera render into its [render target window](Self::render_target) again.
+    pub fn set_render_target(&mut self, texture: Option<HTexture>) {
+        self.render_texture = texture;
+        self.mark_view_dirty();
+    }
+
+    pub fn viewport(&self) -> ViewportRect {
+        self.viewport
+    }
+
+    /// Restricts this camera to a region of its render target, in normalized coordinates.
+    /// Use this for split-screen setups together with [`World::add_active_camera`].
+    pub fn set_viewport(&mut self, viewport: ViewportRect) {
+        self.viewport = viewport.clamped();
+        self.regenerate();
+        self.view_dirty = true;
+    }
+
+    /// Cameras on the same render target are drawn in ascending order of depth
+    pub fn depth(&self) -> i32 {
+        self.depth
+    }
+
+    pub fn set_depth(&mut self, depth: i32) {
+        self.depth = depth;
+    }
+
+    /// Forces the camera to resend all of its render state on the next frame
+    pub fn mark_view_dirty(&mut self) {
+        self.view_dirty = true;
+        self.projection_dirty = true;
+        self.clear_dirty = true;
+    }
+
+    pub fn is_view_dirty(&self) -> bool {
+        self.view_dirty
+    }
+
+    pub fn clear_view_dirty(&mut self) {
+        self.view_dirty = false;
+    }
+
+    pub fn clear_color(&self) -> Option<Vector4<f32>> {
+        self.clear.color
+    }
+
+    /// Sets the background color in linear RGBA. `None` keeps the color output of whatever
+    /// was rendered before this camera, which is useful for overlay cameras.
+    pub fn set_clear_color(&mut self, color: Option<Vector4<f32>>) {
+        self.clear.color = color;
+        self.clear_dirty = true;
+    }
+
+    pub fn clear_flags(&self) -> ClearFlags {
+        self.clear.flags
+    }
+
+    pub fn set_clear_flags(&mut self, flags: ClearFlags) {
+        self.clear.flags = flags;
+        self.clear_dirty = true;
+    }
+
+    pub fn clear_config(&self) -> CameraClear {
+        self.clear
+    }
+
+    pub fn is_clear_dirty(&self) -> bool {
+        self.clear_dirty
+    }
+
+    pub fn clear_clear_dirty(&mut self) {
+        self.clear_dirty = false;
     }
 
     #[cfg(debug_assertions)]
@@ -134,7 +473,7 @@ impl CameraComponent {
         use crate::components::CameraDebug;
 
         let Some(mut debug) = self.parent.get_component::<CameraDebug>() else {
-            tracing::warn!("No camera debug drawable found!");
+            warn!("No camera debug drawable found!");
             return;
         };
 
@@ -154,7 +493,8 @@ impl NewComponent for CameraComponent {
             projection,
             projection_inverse,
             fov_active: 60.0,
-            fov_target: 0.0,
+            fov_base: 60.0,
+            zoom: 1.0,
             zoom_speed: 10.0,
             near: 0.01,
             far: 1000.0,
@@ -163,6 +503,12 @@ impl NewComponent for CameraComponent {
             parent,
             projection_dirty: true,
             render_target: RenderTargetId::PRIMARY,
+            clear: CameraClear::default(),
+            clear_dirty: true,
+            viewport: ViewportRect::FULL,
+            depth: 0,
+            view_dirty: true,
+            render_texture: None,
         }
     }
 }
@@ -171,18 +517,61 @@ impl Component for CameraComponent {
     fn update(&mut self, world: &mut World) {
         let delta_time = world.delta_time().as_secs_f32();
 
-        if self.fov_target != 0.0 && (self.fov_active - self.fov_target).abs() > f32::EPSILON {
+        let fov_target = self.zoomed_fov();
+        if (self.fov_active - fov_target).abs() > f32::EPSILON {
             self.fov_active = self
                 .fov_active
-                .lerp(self.fov_target, self.zoom_speed * delta_time);
+                .lerp(fov_target, self.zoom_speed * delta_time);
             self.regenerate();
         }
     }
+
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        let mut copy = target.add_component::<CameraComponent>();
+        copy.fov_active = self.fov_active;
+        copy.fov_base = self.fov_base;
+        copy.zoom = self.zoom;
+        copy.zoom_speed = self.zoom_speed;
+        copy.near = self.near;
+        copy.far = self.far;
+        copy.width = self.width;
+        copy.height = self.height;
+        copy.render_target = self.render_target;
+        copy.render_texture = self.render_texture;
+        copy.clear = self.clear;
+        copy.viewport = self.viewport;
+        copy.depth = self.depth;
+        copy.regenerate();
+        copy.mark_view_dirty();
+    }
+}
+
+/// Tangent of half the fov, which scales linearly with zoom
+fn half_tan(fov: f32) -> f32 {
+    (fov.to_radians() * 0.5).tan()
+}
+
+/// Clamps a camera parameter, warning if it was out of range
+fn clamp_checked(value: f32, min: f32, max: f32, name: &str) -> f32 {
+    if value >= min && value <= max {
+        return value;
+    }
+
+    let clamped = if value.is_nan() {
+        min
+    } else {
+        value.clamp(min, max)
+    };
+    warn!("Camera {name} {value} is out of range, using {clamped}");
+    clamped
 }
 
 #[cfg(debug_assertions)]
 fn add_debug_drawable(mut parent: GameObjectId) {
     use crate::components::CameraDebug;
 
-    parent.add_component::<CameraDebug>();
+    // duplicated cameras can already have one
+    if parent.get_component::<CameraDebug>().is_none() {
+        parent.add_component::<CameraDebug>();
+    }
 }
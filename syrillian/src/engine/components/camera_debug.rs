@@ -1,4 +1,6 @@
 use crate::components::Component;
+use crate::core::GameObjectId;
+use crate::physics::PhysicsManager;
 use crate::rendering::CPUDrawCtx;
 use crate::rendering::proxies::SceneProxy;
 use crate::rendering::proxies::debug_proxy::{DebugLine, DebugSceneProxy};
@@ -26,10 +28,18 @@ impl From<&DebugRay> for DebugLine {
     }
 }
 
+/// Length of a contact normal without any impulse
+const CONTACT_NORMAL_LENGTH: f32 = 0.2;
+/// Extra normal length per unit of impulse
+const CONTACT_IMPULSE_SCALE: f32 = 0.05;
+/// Impulse at which a contact is drawn fully red
+const CONTACT_IMPULSE_MAX: f32 = 10.0;
+
 pub struct CameraDebug {
     rays: Vec<DebugRay>,
     ray_times: Vec<Instant>,
     dirty: bool,
+    drew_contacts: bool,
 
     pub lifetime: Duration,
 }
@@ -77,6 +87,7 @@ impl Default for CameraDebug {
             lifetime: Duration::from_secs(5),
 
             dirty: true,
+            drew_contacts: false,
         }
     }
 }
@@ -92,22 +103,69 @@ impl Component for CameraDebug {
         }))
     }
 
-    fn update_proxy(&mut self, _world: &World, mut ctx: CPUDrawCtx) {
-        if self.rays.is_empty() {
-            return;
-        }
-
+    fn update_proxy(&mut self, world: &World, mut ctx: CPUDrawCtx) {
         self.timeout_rays();
 
-        if !self.dirty {
+        let draw_contacts = world.debug.show_contacts;
+        if !self.dirty && !draw_contacts && !self.drew_contacts {
             return;
         }
 
-        let lines = self.rays.iter().map_into().collect();
+        let mut lines: Vec<DebugLine> = self.rays.iter().map_into().collect();
+        if draw_contacts {
+            lines.extend(contact_lines(&world.physics));
+        }
+        self.dirty = false;
+        self.drew_contacts = draw_contacts;
+
         ctx.send_proxy_update(move |proxy| {
             let proxy: &mut DebugSceneProxy = proxy_data_mut!(proxy);
 
             proxy.lines = lines;
         })
     }
+
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        // cameras add one on their own
+        let mut copy = match target.get_component::<CameraDebug>() {
+            Some(copy) => copy,
+            None => target.add_component::<CameraDebug>(),
+        };
+        copy.lifetime = self.lifetime;
+    }
+}
+
+/// One line per active contact point, pointing along the contact normal
+fn contact_lines(physics: &PhysicsManager) -> Vec<DebugLine> {
+    let mut lines = Vec::new();
+
+    for pair in physics.narrow_phase.contact_pairs() {
+        if !pair.has_any_active_contact {
+            continue;
+        }
+        let Some(collider) = physics.collider_set.get(pair.collider1) else {
+            continue;
+        };
+        let pose = collider.position();
+
+        for manifold in &pair.manifolds {
+            let normal = manifold.data.normal;
+            for contact in &manifold.points {
+                let impulse = contact.data.impulse;
+                let heat = (impulse / CONTACT_IMPULSE_MAX).clamp(0.0, 1.0);
+                let color = Vector4::new(heat, 1.0 - heat, 0.0, 1.0);
+                let length = CONTACT_NORMAL_LENGTH + impulse * CONTACT_IMPULSE_SCALE;
+
+                let start = pose * contact.local_p1;
+                lines.push(DebugLine {
+                    start,
+                    start_color: color,
+                    end: start + normal * length,
+                    end_color: color,
+                });
+            }
+        }
+    }
+
+    lines
 }
@@ -0,0 +1,242 @@
+use crate::World;
+use crate::components::{CWeak, CameraComponent, Component, NewComponent};
+use crate::core::GameObjectId;
+use crate::utils::FloatMathExt;
+use crate::utils::ease::Easing;
+use crate::utils::math::catmull_rom;
+use nalgebra::{UnitQuaternion, Vector3};
+
+/// A camera pose at a point in time of a [`CameraTrack`]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CameraKeyframe {
+    /// Seconds from the start of the track
+    pub time: f32,
+    pub position: Vector3<f32>,
+    pub rotation: UnitQuaternion<f32>,
+    /// The fov in degrees. `None` leaves the fov of the camera as it is.
+    pub fov: Option<f32>,
+}
+
+impl CameraKeyframe {
+    pub fn new(time: f32, position: Vector3<f32>, rotation: UnitQuaternion<f32>) -> Self {
+        CameraKeyframe {
+            time,
+            position,
+            rotation,
+            fov: None,
+        }
+    }
+
+    pub fn with_fov(mut self, fov: f32) -> Self {
+        self.fov = Some(fov);
+        self
+    }
+}
+
+/// Emitted on [`World::events`] when a [`CameraTrack`] that doesn't loop reaches its end
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CameraTrackFinished {
+    /// The object of the track
+    pub track: GameObjectId,
+}
+
+/// Moves a camera along keyframes, for intros and cutscenes.
+///
+/// Positions follow a Catmull-Rom spline through the keyframes, rotations are slerped and fovs
+/// are interpolated linearly. The [easing](Self::set_easing) applies to the whole track, so the
+/// camera doesn't slow down at every keyframe.
+///
+/// The track drives the [active camera](World::active_camera), unless another
+/// [camera](Self::set_camera) is set. It overwrites the camera object's transform every update
+/// while playing, so camera controllers on it should be disabled meanwhile.
+pub struct CameraTrack {
+    parent: GameObjectId,
+    keyframes: Vec<CameraKeyframe>,
+    camera: CWeak<CameraComponent>,
+    easing: Easing,
+    looping: bool,
+    playing: bool,
+    time: f32,
+}
+
+impl NewComponent for CameraTrack {
+    fn new(parent: GameObjectId) -> Self {
+        CameraTrack {
+            parent,
+            keyframes: Vec::new(),
+            camera: CWeak::null(),
+            easing: Easing::default(),
+            looping: false,
+            playing: false,
+            time: 0.0,
+        }
+    }
+}
+
+impl Component for CameraTrack {
+    fn update(&mut self, world: &mut World) {
+        if !self.playing {
+            return;
+        }
+
+        let duration = self.duration();
+        self.time += world.delta_time().as_secs_f32();
+
+        if self.time >= duration {
+            if self.looping && duration > 0.0 {
+                self.time %= duration;
+            } else {
+                self.time = duration;
+                self.playing = false;
+                world
+                    .events
+                    .emit(CameraTrackFinished { track: self.parent });
+            }
+        }
+
+        self.apply(world);
+    }
+
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        let mut copy = target.add_component::<CameraTrack>();
+        copy.keyframes = self.keyframes.clone();
+        copy.camera = self.camera;
+        copy.easing = self.easing;
+        copy.looping = self.looping;
+        copy.playing = self.playing;
+        copy.time = self.time;
+    }
+}
+
+impl CameraTrack {
+    /// Inserts a keyframe, keeping the keyframes sorted by time
+    pub fn add_keyframe(&mut self, keyframe: CameraKeyframe) {
+        let index = self.keyframes.partition_point(|k| k.time <= keyframe.time);
+        self.keyframes.insert(index, keyframe);
+    }
+
+    pub fn keyframes(&self) -> &[CameraKeyframe] {
+        &self.keyframes
+    }
+
+    pub fn clear_keyframes(&mut self) {
+        self.keyframes.clear();
+        self.time = 0.0;
+    }
+
+    /// Returns the time of the last keyframe in seconds
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |k| k.time)
+    }
+
+    /// Returns the playback position in seconds
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    /// Starts or resumes playback. A finished track starts over.
+    pub fn play(&mut self) {
+        if self.time >= self.duration() {
+            self.time = 0.0;
+        }
+        self.playing = true;
+    }
+
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Jumps to `time` seconds into the track and moves the camera there right away
+    pub fn seek(&mut self, time: f32) {
+        self.time = time.clamp(0.0, self.duration());
+        self.apply(self.parent.world());
+    }
+
+    pub fn easing(&self) -> Easing {
+        self.easing
+    }
+
+    pub fn set_easing(&mut self, easing: Easing) {
+        self.easing = easing;
+    }
+
+    pub fn is_looping(&self) -> bool {
+        self.looping
+    }
+
+    /// Makes the track start over at the end instead of finishing
+    pub fn set_looping(&mut self, looping: bool) {
+        self.looping = looping;
+    }
+
+    /// Drives `camera` instead of the active camera. Pass a null handle to go back to the
+    /// active camera.
+    pub fn set_camera(&mut self, camera: CWeak<CameraComponent>) {
+        self.camera = camera;
+    }
+
+    /// Returns the interpolated keyframe at `time` seconds, with the easing applied
+    pub fn sample(&self, time: f32) -> Option<CameraKeyframe> {
+        let (first, last) = (self.keyframes.first()?, self.keyframes.last()?);
+        let duration = self.duration() - first.time;
+        if duration <= 0.0 {
+            return Some(*last);
+        }
+
+        let progress = ((time - first.time) / duration).clamp(0.0, 1.0);
+        let time = first.time + self.easing.apply(progress) * duration;
+
+        let next = self
+            .keyframes
+            .partition_point(|k| k.time <= time)
+            .clamp(1, self.keyframes.len() - 1);
+        let from = &self.keyframes[next - 1];
+        let to = &self.keyframes[next];
+
+        let span = to.time - from.time;
+        let t = if span > 0.0 {
+            ((time - from.time) / span).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+
+        let before = self.keyframes[next.saturating_sub(2)].position;
+        let after = self.keyframes[(next + 1).min(self.keyframes.len() - 1)].position;
+        let fov = match (from.fov, to.fov) {
+            (Some(a), Some(b)) => Some(a.lerp(b, t)),
+            (a, b) => a.or(b),
+        };
+
+        Some(CameraKeyframe {
+            time,
+            position: catmull_rom(before, from.position, to.position, after, t),
+            rotation: from.rotation.slerp(&to.rotation, t),
+            fov,
+        })
+    }
+
+    fn apply(&self, world: &World) {
+        let Some(pose) = self.sample(self.time) else {
+            return;
+        };
+        let camera = if self.camera.is_null() {
+            world.active_camera()
+        } else {
+            self.camera
+        };
+        let Some(mut camera) = camera.upgrade(world) else {
+            return;
+        };
+
+        let mut obj = camera.parent();
+        obj.transform.set_position_vec(pose.position);
+        obj.transform.set_rotation(pose.rotation);
+        if let Some(fov) = pose.fov {
+            camera.set_fov(fov);
+        }
+    }
+}
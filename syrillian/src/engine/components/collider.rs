@@ -1,29 +1,37 @@
-use self::ColliderError::{DesyncedCollider, InvalidMesh, InvalidMeshRef, NoMeshRenderer};
+use self::ColliderError::{
+    DegenerateHull, DesyncedCollider, InvalidMesh, InvalidMeshRef, NoMeshRenderer,
+};
 use crate::World;
 use crate::components::{Component, MeshRenderer, NewComponent, RigidBodyComponent};
 use crate::core::GameObjectId;
-use crate::engine::assets::{HMesh, Mesh};
-use nalgebra::{Point3, Vector3};
+use crate::engine::assets::{HMesh, Heightmap, Mesh};
+use nalgebra::{DMatrix, Point3, Vector2, Vector3};
+use rapier3d::parry::transformation::vhacd::VHACD;
 use rapier3d::prelude::*;
 use snafu::Snafu;
 use tracing::{trace, warn};
 
-#[cfg(debug_assertions)]
-use crate::assets::StoreType;
-#[cfg(debug_assertions)]
-use crate::core::Vertex3D;
 #[cfg(debug_assertions)]
 use crate::proxy_data_mut;
 #[cfg(debug_assertions)]
-use crate::rendering::proxies::DebugSceneProxy;
-#[cfg(debug_assertions)]
 use crate::rendering::proxies::SceneProxy;
 #[cfg(debug_assertions)]
-use crate::rendering::{CPUDrawCtx, DebugRenderer};
+use crate::rendering::proxies::debug_proxy::{DebugLine, DebugSceneProxy};
+#[cfg(debug_assertions)]
+use crate::rendering::{CPUDrawCtx, DebugRenderer, shape_outline};
 #[cfg(debug_assertions)]
-use nalgebra::{Matrix4, Vector4};
+use nalgebra::{Isometry3, Vector4};
 use syrillian_utils::debug_panic;
 
+/// Rings and segments of the hull that approximates a non-uniformly scaled sphere
+const ELLIPSOID_SUBDIVISIONS: u32 = 16;
+
+/// A physics collider following its object. Defaults to a box that fits the preset cube mesh.
+///
+/// Rapier can't scale colliders, so the object's scale is baked into the shape when it's set,
+/// and the shape is rebuilt in the next fixed update whenever the scale changes. That's cheap
+/// for boxes and spheres, but a [mesh](Self::use_mesh) or [convex](Self::set_convex_hull)
+/// collider rebuilds its whole shape, so avoid scaling those every frame.
 pub struct Collider3D {
     pub phys_handle: ColliderHandle,
     linked_to_body: Option<RigidBodyHandle>,
@@ -32,9 +40,11 @@ pub struct Collider3D {
     last_scale: Vector3<f32>,
 
     #[cfg(debug_assertions)]
-    enable_debug_render: bool, // TODO: Sync with GPU
+    enable_debug_render: bool,
+    #[cfg(debug_assertions)]
+    debug_outline: Option<Vec<[Point3<f32>; 2]>>,
     #[cfg(debug_assertions)]
-    debug_collider_mesh: Option<HMesh>,
+    debug_sent_pose: Option<(Isometry3<f32>, Vector4<f32>)>,
     #[cfg(debug_assertions)]
     was_debug_enabled: bool,
 }
@@ -42,7 +52,11 @@ pub struct Collider3D {
 #[derive(Debug, Clone)]
 enum ColliderShapeKind {
     Cuboid,
+    Sphere,
     Mesh(HMesh),
+    Heightfield(DMatrix<f32>, Vector3<f32>),
+    /// Unscaled point clouds, each wrapped in a convex hull
+    Convex(Vec<Vec<Point3<f32>>>),
 }
 
 #[derive(Debug, Snafu)]
@@ -61,6 +75,9 @@ pub enum ColliderError {
 
     #[snafu(display("The collider mesh was invalid"))]
     InvalidMesh,
+
+    #[snafu(display("The mesh has no volume to build a convex hull from"))]
+    DegenerateHull,
 }
 
 impl NewComponent for Collider3D {
@@ -82,7 +99,9 @@ impl NewComponent for Collider3D {
             #[cfg(debug_assertions)]
             enable_debug_render: true,
             #[cfg(debug_assertions)]
-            debug_collider_mesh: None,
+            debug_outline: None,
+            #[cfg(debug_assertions)]
+            debug_sent_pose: None,
             #[cfg(debug_assertions)]
             was_debug_enabled: true,
         };
@@ -95,11 +114,15 @@ impl NewComponent for Collider3D {
 
 impl Component for Collider3D {
     #[cfg(debug_assertions)]
-    fn update(&mut self, world: &mut World) {
-        if self.debug_collider_mesh.is_none() {
-            trace!("[Collider] Regenerating debug mesh");
-            let mesh = self.generate_collider_mesh(world);
-            self.debug_collider_mesh = Some(mesh);
+    fn update(&mut self, _world: &mut World) {
+        if self.debug_outline.is_none() {
+            trace!("[Collider] Regenerating debug outline");
+            let Some(collider) = self.collider() else {
+                debug_panic!("No collider attached to Collider 3D component");
+                return;
+            };
+            self.debug_outline = Some(shape_outline(collider.shared_shape()));
+            self.debug_sent_pose = None;
         }
     }
 
@@ -118,37 +141,60 @@ impl Component for Collider3D {
     }
 
     #[cfg(debug_assertions)]
-    fn create_render_proxy(&mut self, _world: &World) -> Option<Box<dyn SceneProxy>> {
-        let Some(mesh) = self.debug_collider_mesh else {
-            debug_panic!("Debug mode is enabled but no collider mesh was made in update");
+    fn create_render_proxy(&mut self, world: &World) -> Option<Box<dyn SceneProxy>> {
+        if self.debug_outline.is_none() {
+            debug_panic!("Debug mode is enabled but no collider outline was made in update");
             return None;
-        };
+        }
 
-        const COLOR: Vector4<f32> = Vector4::new(0.0, 1.0, 0.2, 1.0);
+        // new proxies start out enabled, update_proxy disables it again if needed
+        self.was_debug_enabled = true;
 
-        let transform = self.collider_debug_transform();
-        let mut proxy = Box::new(DebugSceneProxy::single_mesh(mesh));
-        proxy.color = COLOR;
-        proxy.set_override_transform(transform);
-        Some(proxy)
+        let lines = self.debug_lines(world);
+        Some(Box::new(DebugSceneProxy {
+            lines,
+            ..DebugSceneProxy::default()
+        }))
     }
 
     #[cfg(debug_assertions)]
-    fn update_proxy(&mut self, _world: &World, mut ctx: CPUDrawCtx) {
-        if !DebugRenderer::collider_mesh() && self.was_debug_enabled {
+    fn update_proxy(&mut self, world: &World, mut ctx: CPUDrawCtx) {
+        let enabled = (world.debug.show_colliders || DebugRenderer::collider_mesh())
+            && self.enable_debug_render;
+        if !enabled && self.was_debug_enabled {
             ctx.disable_proxy();
             self.was_debug_enabled = false;
-        } else if DebugRenderer::collider_mesh() && !self.was_debug_enabled {
+        } else if enabled && !self.was_debug_enabled {
             ctx.enable_proxy();
             self.was_debug_enabled = true;
         }
 
-        if DebugRenderer::collider_mesh() {
-            let transform = self.collider_debug_transform();
-            ctx.send_proxy_update(move |proxy| {
-                let proxy: &mut DebugSceneProxy = proxy_data_mut!(proxy);
-                proxy.set_override_transform(transform);
-            });
+        if !enabled {
+            return;
+        }
+
+        let Some(collider) = world.physics.collider_set.get(self.phys_handle) else {
+            return;
+        };
+        let pose = (*collider.position(), debug_color(world, collider));
+        if self.debug_sent_pose == Some(pose) {
+            return;
+        }
+        self.debug_sent_pose = Some(pose);
+
+        let lines = self.debug_lines(world);
+        ctx.send_proxy_update(move |proxy| {
+            let proxy: &mut DebugSceneProxy = proxy_data_mut!(proxy);
+            proxy.lines = lines;
+        });
+    }
+
+    fn on_active_changed(&mut self, world: &mut World, active: bool) {
+        if let Some(collider) = world.physics.collider_set.get_mut(self.phys_handle) {
+            collider.set_enabled(active);
+        }
+        if active {
+            self.sync_with_transform_world(world, false);
         }
     }
 
@@ -160,6 +206,31 @@ impl Component for Collider3D {
             false,
         );
     }
+
+    fn duplicate(&self, mut target: GameObjectId, world: &mut World) {
+        let Some(original) = self.collider().cloned() else {
+            return;
+        };
+
+        let mut copy = target.add_component::<Collider3D>();
+        copy.shape_kind = self.shape_kind.clone();
+        copy.last_scale = self.last_scale;
+        #[cfg(debug_assertions)]
+        {
+            copy.enable_debug_render = self.enable_debug_render;
+        }
+
+        if let Some(collider) = copy.collider_mut() {
+            collider.set_shape(original.shared_shape().clone());
+            collider.set_sensor(original.is_sensor());
+            collider.set_friction(original.friction());
+            collider.set_restitution(original.restitution());
+            collider.set_density(original.density());
+            collider.set_collision_groups(original.collision_groups());
+            collider.set_active_events(original.active_events());
+        }
+        copy.sync_with_transform_world(world, true);
+    }
 }
 
 impl Collider3D {
@@ -175,6 +246,20 @@ impl Collider3D {
         SharedShape::cuboid(scale.x * 0.5, scale.y * 0.5, scale.z * 0.5)
     }
 
+    /// Builds a ball for a uniform scale, and a convex ellipsoid otherwise
+    fn build_sphere_shape(scale: Vector3<f32>) -> SharedShape {
+        if scale.max() - scale.min() <= scale.max() * 1e-4 {
+            return SharedShape::ball(scale.max());
+        }
+
+        let (points, _) = Ball::new(1.0).to_trimesh(ELLIPSOID_SUBDIVISIONS, ELLIPSOID_SUBDIVISIONS);
+        let points: Vec<_> = points
+            .into_iter()
+            .map(|p| Point3::from(p.coords.component_mul(&scale)))
+            .collect();
+        SharedShape::convex_hull(&points).unwrap_or_else(|| SharedShape::ball(scale.max()))
+    }
+
     fn build_shape_for_scale_world(
         &self,
         world: &World,
@@ -182,15 +267,45 @@ impl Collider3D {
     ) -> Option<SharedShape> {
         match &self.shape_kind {
             ColliderShapeKind::Cuboid => Some(Self::build_cuboid_shape(scale)),
+            ColliderShapeKind::Sphere => Some(Self::build_sphere_shape(scale)),
+            ColliderShapeKind::Heightfield(heights, size) => Some(SharedShape::heightfield(
+                heights.clone(),
+                size.component_mul(&scale),
+            )),
             ColliderShapeKind::Mesh(handle) => {
                 let mesh = world.assets.meshes.try_get(*handle)?;
                 SharedShape::mesh_with_scale(&mesh, scale)
             }
+            ColliderShapeKind::Convex(hulls) => Self::build_convex_shape(hulls, scale),
+        }
+    }
+
+    fn build_convex_shape(hulls: &[Vec<Point3<f32>>], scale: Vector3<f32>) -> Option<SharedShape> {
+        let mut parts: Vec<_> = hulls
+            .iter()
+            .filter_map(|points| {
+                let scaled: Vec<_> = points
+                    .iter()
+                    .map(|p| Point3::from(p.coords.component_mul(&scale)))
+                    .collect();
+                SharedShape::convex_hull(&scaled)
+            })
+            .collect();
+
+        match parts.len() {
+            0 => None,
+            1 => parts.pop(),
+            _ => Some(SharedShape::compound(
+                parts
+                    .into_iter()
+                    .map(|part| (Isometry::identity(), part))
+                    .collect(),
+            )),
         }
     }
 
     fn sync_with_transform_world(&mut self, world: &mut World, force_pose: bool) {
-        let scale = self.parent.transform.scale();
+        let scale = Self::sanitize_scale(self.parent.transform.scale());
         let new_shape = ((scale - self.last_scale).norm() > f32::EPSILON)
             .then(|| self.build_shape_for_scale_world(world, scale));
 
@@ -212,6 +327,11 @@ impl Collider3D {
             collider.set_rotation(self.parent.transform.rotation());
         }
 
+        #[cfg(debug_assertions)]
+        if self.last_scale != scale {
+            self.debug_outline = None;
+        }
+
         self.last_scale = scale;
     }
 
@@ -247,6 +367,66 @@ impl Collider3D {
         self.sync_with_transform_world(world, force_pose);
     }
 
+    /// Uses a sphere that fits the preset sphere mesh. With a non-uniform scale, it's stretched
+    /// into an ellipsoid made of a convex hull.
+    pub fn use_sphere(&mut self) {
+        let world = World::instance();
+        let scale = Self::sanitize_scale(self.parent.transform.scale());
+
+        let Some(collider) = self.collider_mut() else {
+            debug_panic!("[Collider] No collider found when switching to a sphere");
+            return;
+        };
+        collider.set_shape(Self::build_sphere_shape(scale));
+
+        self.shape_kind = ColliderShapeKind::Sphere;
+        self.last_scale = scale;
+
+        #[cfg(debug_assertions)]
+        {
+            self.debug_outline = None;
+        }
+
+        self.sync_with_transform_world(world, self.linked_to_body.is_none());
+    }
+
+    /// Uses a heightfield, matching [`Mesh::from_heightmap`] with the same size and height
+    /// scale. The object's scale still applies on top.
+    ///
+    /// Pass a [downsampled](Heightmap::downsample) heightmap for a coarser, cheaper collider.
+    pub fn set_heightfield(
+        &mut self,
+        heightmap: &Heightmap,
+        size: Vector2<f32>,
+        height_scale: f32,
+    ) {
+        let world = World::instance();
+        let scale = Self::sanitize_scale(self.parent.transform.scale());
+        let heights = heightmap.to_matrix();
+        let size = Vector3::new(size.x, height_scale, size.y);
+
+        let Some(collider) = self.collider_mut() else {
+            debug_panic!("[Collider] No collider found when switching to a heightfield");
+            return;
+        };
+        collider.set_shape(SharedShape::heightfield(
+            heights.clone(),
+            size.component_mul(&scale),
+        ));
+
+        self.shape_kind = ColliderShapeKind::Heightfield(heights, size);
+        self.last_scale = scale;
+
+        #[cfg(debug_assertions)]
+        {
+            self.debug_outline = None;
+        }
+
+        self.sync_with_transform_world(world, self.linked_to_body.is_none());
+    }
+
+    /// Uses the mesh of the [`MeshRenderer`] as a triangle mesh, with the object's scale baked
+    /// into its vertices. Every change of the scale copies and scales all vertices again.
     pub fn use_mesh(&mut self) {
         if let Err(e) = self.try_use_mesh() {
             warn!("{e}");
@@ -285,7 +465,86 @@ impl Collider3D {
 
         #[cfg(debug_assertions)]
         {
-            self.debug_collider_mesh = None;
+            self.debug_outline = None;
+        }
+
+        self.sync_with_transform_world(world, self.linked_to_body.is_none());
+
+        Ok(())
+    }
+
+    /// Wraps the mesh of the [`MeshRenderer`] in a single convex hull. Unlike a
+    /// [mesh collider](Self::use_mesh), this works on dynamic rigid bodies, but fills in any
+    /// concave parts.
+    pub fn set_convex_hull(&mut self) -> Result<(), ColliderError> {
+        let (points, _) = self.renderer_mesh_data()?;
+        self.set_convex_hulls(vec![points])
+    }
+
+    /// Splits the mesh of the [`MeshRenderer`] into convex pieces with the default
+    /// [parameters](VHACDParameters), so dynamic rigid bodies can collide with concave shapes.
+    ///
+    /// The decomposition is slow for detailed meshes, so it's best done once while loading.
+    pub fn set_convex_decomposition(&mut self) -> Result<(), ColliderError> {
+        self.set_convex_decomposition_with(&VHACDParameters::default())
+    }
+
+    /// Like [`set_convex_decomposition`](Self::set_convex_decomposition). A higher
+    /// `resolution` and `max_convex_hulls` or a lower `concavity` follow the mesh more closely,
+    /// but take longer to build and to simulate.
+    pub fn set_convex_decomposition_with(
+        &mut self,
+        params: &VHACDParameters,
+    ) -> Result<(), ColliderError> {
+        let (points, indices) = self.renderer_mesh_data()?;
+        let hulls = VHACD::decompose(params, &points, &indices, true)
+            .compute_exact_convex_hulls(&points, &indices)
+            .into_iter()
+            .map(|(hull, _)| hull)
+            .collect();
+
+        self.set_convex_hulls(hulls)
+    }
+
+    fn renderer_mesh_data(&self) -> Result<(Vec<Point3<f32>>, Vec<[u32; 3]>), ColliderError> {
+        let world = World::instance();
+        let mesh_renderer = self
+            .parent
+            .get_component::<MeshRenderer>()
+            .ok_or(NoMeshRenderer)?;
+        let mesh = world
+            .assets
+            .meshes
+            .try_get(mesh_renderer.mesh())
+            .ok_or(InvalidMeshRef)?;
+        if mesh.triangle_count() == 0 {
+            return Err(InvalidMesh);
+        }
+
+        Ok((
+            mesh.data.make_point_cloud(),
+            mesh.data.make_triangle_indices(),
+        ))
+    }
+
+    fn set_convex_hulls(&mut self, hulls: Vec<Vec<Point3<f32>>>) -> Result<(), ColliderError> {
+        let world = World::instance();
+        let scale = Self::sanitize_scale(self.parent.transform.scale());
+        let shape = Self::build_convex_shape(&hulls, scale).ok_or(DegenerateHull)?;
+
+        world
+            .physics
+            .collider_set
+            .get_mut(self.phys_handle)
+            .ok_or(DesyncedCollider)?
+            .set_shape(shape);
+
+        self.shape_kind = ColliderShapeKind::Convex(hulls);
+        self.last_scale = scale;
+
+        #[cfg(debug_assertions)]
+        {
+            self.debug_outline = None;
         }
 
         self.sync_with_transform_world(world, self.linked_to_body.is_none());
@@ -303,41 +562,48 @@ impl Collider3D {
         self.enable_debug_render
     }
 
+    /// The collider outline in world space, colored by [`debug_color`]
     #[cfg(debug_assertions)]
-    fn generate_collider_mesh(&mut self, world: &mut World) -> HMesh {
-        let Some(collider) = self.collider() else {
-            debug_panic!("No collider attached to Collider 3D component");
-            return HMesh::UNIT_CUBE;
+    fn debug_lines(&self, world: &World) -> Vec<DebugLine> {
+        let (Some(outline), Some(collider)) = (
+            &self.debug_outline,
+            world.physics.collider_set.get(self.phys_handle),
+        ) else {
+            return Vec::new();
         };
 
-        let (vertices, indices) = collider.shared_shape().to_trimesh();
-        let vertices: Vec<_> = vertices
+        let pose = collider.position();
+        let color = debug_color(world, collider);
+        outline
             .iter()
-            .map(|v| Vertex3D::position_only(v.coords))
-            .collect();
+            .map(|[start, end]| DebugLine {
+                start: pose * start,
+                start_color: color,
+                end: pose * end,
+                end_color: color,
+            })
+            .collect()
+    }
+}
 
-        Mesh::builder(vertices)
-            .with_indices(indices.into_flattened())
-            .build()
-            .store(world)
+/// Sensors are yellow, and solid colliders are colored by their body: blue when static, green
+/// when dynamic and orange when kinematic.
+#[cfg(debug_assertions)]
+fn debug_color(world: &World, collider: &Collider) -> Vector4<f32> {
+    if collider.is_sensor() {
+        return Vector4::new(1.0, 0.9, 0.1, 1.0);
     }
 
-    #[cfg(debug_assertions)]
-    fn collider_debug_transform(&self) -> Matrix4<f32> {
-        if let Some(collider) = self.collider() {
-            let iso = collider.position();
-            let mut mat = Matrix4::identity();
-            let rot = iso.rotation.to_rotation_matrix().into_inner();
-            mat.fixed_view_mut::<3, 3>(0, 0).copy_from(&rot);
-            mat[(0, 3)] = iso.translation.vector.x;
-            mat[(1, 3)] = iso.translation.vector.y;
-            mat[(2, 3)] = iso.translation.vector.z;
-            mat
-        } else {
-            self.parent
-                .transform
-                .global_transform_matrix()
-                .to_homogeneous()
+    let body_type = collider
+        .parent()
+        .and_then(|h| world.physics.rigid_body_set.get(h))
+        .map(|body| body.body_type());
+
+    match body_type {
+        None | Some(RigidBodyType::Fixed) => Vector4::new(0.3, 0.6, 1.0, 1.0),
+        Some(RigidBodyType::Dynamic) => Vector4::new(0.0, 1.0, 0.2, 1.0),
+        Some(RigidBodyType::KinematicPositionBased | RigidBodyType::KinematicVelocityBased) => {
+            Vector4::new(1.0, 0.5, 0.0, 1.0)
         }
     }
 }
@@ -0,0 +1,195 @@
+use crate::World;
+use crate::assets::HTexture;
+use crate::components::{Component, NewComponent};
+use crate::core::GameObjectId;
+use crate::proxy_data_mut;
+use crate::rendering::CPUDrawCtx;
+use crate::rendering::proxies::{DecalProxy, SceneProxy};
+use nalgebra::Vector3;
+use web_time::Duration;
+
+#[derive(Debug, Copy, Clone)]
+struct DecalFade {
+    delay: Duration,
+    duration: Duration,
+    elapsed: Duration,
+}
+
+impl DecalFade {
+    fn opacity(&self) -> f32 {
+        if self.is_done() {
+            return 0.0;
+        }
+
+        let fading = self.elapsed.saturating_sub(self.delay);
+        if fading.is_zero() {
+            return 1.0;
+        }
+
+        (1.0 - fading.as_secs_f32() / self.duration.as_secs_f32()).clamp(0.0, 1.0)
+    }
+
+    fn is_done(&self) -> bool {
+        self.elapsed >= self.delay + self.duration
+    }
+}
+
+/// Projects a texture onto all geometry inside the object's oriented box.
+///
+/// The box is centered on the object and spans [`size`](Decal::size) scaled by the object's
+/// transform. The texture lies on the box's local XZ plane and is projected along its local Y
+/// axis, so an unrotated decal lands on floors.
+///
+/// Decals are drawn after the scene and blend over its final color. They are not drawn into
+/// render-texture cameras.
+#[derive(Debug)]
+pub struct Decal {
+    texture: HTexture,
+    size: Vector3<f32>,
+    color: Vector3<f32>,
+    alpha: f32,
+    fade: Option<DecalFade>,
+    dirty_texture: bool,
+    dirty_params: bool,
+}
+
+impl NewComponent for Decal {
+    fn new(_parent: GameObjectId) -> Self {
+        Decal {
+            texture: HTexture::FALLBACK_DIFFUSE,
+            size: Vector3::new(1.0, 1.0, 1.0),
+            color: Vector3::new(1.0, 1.0, 1.0),
+            alpha: 1.0,
+            fade: None,
+            dirty_texture: false,
+            dirty_params: false,
+        }
+    }
+}
+
+impl Component for Decal {
+    fn create_render_proxy(&mut self, _world: &World) -> Option<Box<dyn SceneProxy>> {
+        let mut proxy = DecalProxy::new(self.texture, self.size);
+        proxy.color = self.color;
+        proxy.alpha = self.opacity();
+        Some(Box::new(proxy))
+    }
+
+    fn update(&mut self, world: &mut World) {
+        let Some(fade) = &mut self.fade else {
+            return;
+        };
+        if fade.is_done() {
+            return;
+        }
+
+        fade.elapsed += world.delta_time();
+        self.dirty_params = true;
+    }
+
+    fn update_proxy(&mut self, _world: &World, mut ctx: CPUDrawCtx) {
+        if self.dirty_texture {
+            let texture = self.texture;
+            ctx.send_proxy_update(move |sc| {
+                let data: &mut DecalProxy = proxy_data_mut!(sc);
+                data.texture = texture;
+                data.texture_dirty = true;
+            });
+            self.dirty_texture = false;
+        }
+
+        if self.dirty_params {
+            let size = self.size;
+            let color = self.color;
+            let alpha = self.opacity();
+            ctx.send_proxy_update(move |sc| {
+                let data: &mut DecalProxy = proxy_data_mut!(sc);
+                data.size = size;
+                data.color = color;
+                data.alpha = alpha;
+            });
+            self.dirty_params = false;
+        }
+    }
+
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        let mut copy = target.add_component::<Decal>();
+        copy.texture = self.texture;
+        copy.size = self.size;
+        copy.color = self.color;
+        copy.alpha = self.alpha;
+        copy.fade = self.fade;
+    }
+}
+
+impl Decal {
+    pub fn set_texture(&mut self, texture: HTexture) {
+        if self.texture == texture {
+            return;
+        }
+        self.texture = texture;
+        self.dirty_texture = true;
+    }
+
+    /// Sets the extents of the projection box in local space
+    pub fn set_size(&mut self, size: Vector3<f32>) {
+        self.size = size.map(|e| e.abs());
+        self.dirty_params = true;
+    }
+
+    /// Tints the projected texture
+    pub fn set_color(&mut self, color: Vector3<f32>) {
+        self.color = color;
+        self.dirty_params = true;
+    }
+
+    pub fn set_alpha(&mut self, alpha: f32) {
+        self.alpha = alpha.clamp(0.0, 1.0);
+        self.dirty_params = true;
+    }
+
+    /// Stays fully visible for `delay`, then fades out linearly over `duration`.
+    ///
+    /// Restarts the timer if a fade was already running.
+    pub fn fade_out(&mut self, delay: Duration, duration: Duration) {
+        self.fade = Some(DecalFade {
+            delay,
+            duration,
+            elapsed: Duration::ZERO,
+        });
+        self.dirty_params = true;
+    }
+
+    /// Cancels a running fade and makes the decal fully visible again
+    pub fn cancel_fade(&mut self) {
+        self.fade = None;
+        self.dirty_params = true;
+    }
+
+    /// Returns true once a fade started with [`fade_out`](Decal::fade_out) has finished
+    pub fn is_faded(&self) -> bool {
+        self.fade.is_some_and(|f| f.is_done())
+    }
+
+    /// The current alpha, including the fade
+    pub fn opacity(&self) -> f32 {
+        let fade = self.fade.map_or(1.0, |f| f.opacity());
+        self.alpha * fade
+    }
+
+    pub fn texture(&self) -> HTexture {
+        self.texture
+    }
+
+    pub fn size(&self) -> Vector3<f32> {
+        self.size
+    }
+
+    pub fn color(&self) -> Vector3<f32> {
+        self.color
+    }
+
+    pub fn alpha(&self) -> f32 {
+        self.alpha
+    }
+}
@@ -0,0 +1,174 @@
+use crate::World;
+use crate::components::{Component, NewComponent};
+use crate::core::GameObjectId;
+use nalgebra::{UnitQuaternion, Vector3};
+
+/// The space the offset of a [`FollowComponent`] is in
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum FollowSpace {
+    /// The offset is added as is, so the follower keeps its direction when the target turns
+    #[default]
+    World,
+    /// The offset turns with the target, e.g. to stay behind it. The target's scale is ignored.
+    TargetLocal,
+}
+
+/// Makes the object follow another one, like a camera rig or a companion.
+///
+/// The follower moves in `late_update`, so it sees where the target ended up this frame. If the
+/// target is deleted, the follower stops where it is and forgets the target.
+pub struct FollowComponent {
+    parent: GameObjectId,
+    target: Option<GameObjectId>,
+    offset: Vector3<f32>,
+    space: FollowSpace,
+    smooth_time: f32,
+    look_at_target: bool,
+    velocity: Vector3<f32>,
+}
+
+impl NewComponent for FollowComponent {
+    fn new(parent: GameObjectId) -> Self {
+        FollowComponent {
+            parent,
+            target: None,
+            offset: Vector3::zeros(),
+            space: FollowSpace::default(),
+            smooth_time: 0.3,
+            look_at_target: false,
+            velocity: Vector3::zeros(),
+        }
+    }
+}
+
+impl Component for FollowComponent {
+    fn late_update(&mut self, world: &mut World) {
+        let Some(target) = self.live_target() else {
+            return;
+        };
+
+        let goal = self.goal_position(target);
+        if self.smooth_time <= 0.0 {
+            self.parent.transform.set_position_vec(goal);
+        } else {
+            let delta_time = world.delta_time().as_secs_f32();
+            self.parent.transform.smooth_damp(
+                goal,
+                &mut self.velocity,
+                self.smooth_time,
+                delta_time,
+            );
+        }
+
+        if self.look_at_target {
+            self.look_at(target);
+        }
+    }
+
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        let mut copy = target.add_component::<FollowComponent>();
+        copy.target = self.target;
+        copy.offset = self.offset;
+        copy.space = self.space;
+        copy.smooth_time = self.smooth_time;
+        copy.look_at_target = self.look_at_target;
+    }
+}
+
+impl FollowComponent {
+    /// Returns the followed object, unless it was deleted
+    pub fn target(&self) -> Option<GameObjectId> {
+        self.target.filter(|target| target.exists())
+    }
+
+    pub fn set_target(&mut self, target: GameObjectId) {
+        self.target = Some(target);
+        self.velocity = Vector3::zeros();
+    }
+
+    /// Stops following, and leaves the object where it is
+    pub fn clear_target(&mut self) {
+        self.target = None;
+        self.velocity = Vector3::zeros();
+    }
+
+    pub fn offset(&self) -> Vector3<f32> {
+        self.offset
+    }
+
+    /// Sets where the follower stays relative to the target, in the given space
+    pub fn set_offset(&mut self, offset: Vector3<f32>, space: FollowSpace) {
+        self.offset = offset;
+        self.space = space;
+    }
+
+    pub fn space(&self) -> FollowSpace {
+        self.space
+    }
+
+    pub fn smooth_time(&self) -> f32 {
+        self.smooth_time
+    }
+
+    /// Sets roughly how many seconds the follower takes to catch up, see
+    /// [`Transform::smooth_damp`](crate::core::Transform::smooth_damp). `0.0` sticks to the
+    /// target without lag. Defaults to `0.3`.
+    pub fn set_smooth_time(&mut self, smooth_time: f32) {
+        self.smooth_time = smooth_time.max(0.0);
+    }
+
+    pub fn looks_at_target(&self) -> bool {
+        self.look_at_target
+    }
+
+    /// Turns the follower so its forward faces the target, keeping the world up axis up
+    pub fn set_look_at_target(&mut self, look_at_target: bool) {
+        self.look_at_target = look_at_target;
+    }
+
+    /// Moves to the offset position right away, e.g. after teleporting the target
+    pub fn snap_to_target(&mut self) {
+        let Some(target) = self.live_target() else {
+            return;
+        };
+
+        let goal = self.goal_position(target);
+        self.parent.transform.set_position_vec(goal);
+        self.velocity = Vector3::zeros();
+        if self.look_at_target {
+            self.look_at(target);
+        }
+    }
+
+    fn live_target(&mut self) -> Option<GameObjectId> {
+        let target = self.target?;
+        if target.exists() {
+            Some(target)
+        } else {
+            self.clear_target();
+            None
+        }
+    }
+
+    fn goal_position(&self, target: GameObjectId) -> Vector3<f32> {
+        let offset = match self.space {
+            FollowSpace::World => self.offset,
+            FollowSpace::TargetLocal => target.transform.rotation() * self.offset,
+        };
+        target.transform.position() + offset
+    }
+
+    fn look_at(&mut self, target: GameObjectId) {
+        let to_target = target.transform.position() - self.parent.transform.position();
+        let up = Vector3::y();
+        if to_target.norm_squared() <= f32::EPSILON
+            || to_target.cross(&up).norm_squared() <= f32::EPSILON
+        {
+            return;
+        }
+
+        // face_towards points +z at the direction, the forward axis is -z
+        let rotation = UnitQuaternion::face_towards(&-to_target, &up);
+        self.parent.transform.set_rotation(rotation);
+    }
+}
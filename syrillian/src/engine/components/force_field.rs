@@ -0,0 +1,175 @@
+use crate::World;
+use crate::components::{Component, NewComponent};
+use crate::core::GameObjectId;
+use nalgebra::{Isometry3, Translation3, Vector3};
+use rapier3d::prelude::{Ball, Cuboid, QueryFilter, RigidBodyHandle, Shape};
+
+/// The volume of a [`ForceField`], centered on its object and turning with it. Scale is
+/// ignored.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ForceFieldShape {
+    Sphere { radius: f32 },
+    Box { half_extents: Vector3<f32> },
+}
+
+/// Which way a [`ForceField`] pushes
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ForceFieldMode {
+    /// Pushes along a direction in the local space of the field, like wind or a current
+    Directional(Vector3<f32>),
+    /// Pushes away from the center. A negative strength pulls in instead.
+    Radial,
+    /// Pushes around an axis in the local space of the field, like a whirlpool. A negative
+    /// strength turns the other way.
+    Vortex(Vector3<f32>),
+}
+
+/// How the strength of a [`ForceField`] fades from its center to its edge
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Falloff {
+    /// Full strength everywhere in the volume
+    #[default]
+    None,
+    Linear,
+    /// Drops off quickly, like the blast of an explosion
+    Quadratic,
+}
+
+/// Pushes the dynamic rigid bodies overlapping a volume in every fixed update, e.g. for wind,
+/// water currents or gravity wells. [`explode`](Self::explode) gives a single push instead.
+///
+/// The strength is a force in newtons, so heavier bodies are pushed less. Bodies of the field's
+/// own object aren't affected.
+pub struct ForceField {
+    parent: GameObjectId,
+    shape: ForceFieldShape,
+    mode: ForceFieldMode,
+    strength: f32,
+    falloff: Falloff,
+}
+
+impl NewComponent for ForceField {
+    fn new(parent: GameObjectId) -> Self {
+        ForceField {
+            parent,
+            shape: ForceFieldShape::Sphere { radius: 5.0 },
+            mode: ForceFieldMode::Directional(Vector3::x()),
+            strength: 10.0,
+            falloff: Falloff::default(),
+        }
+    }
+}
+
+impl Component for ForceField {
+    fn fixed_update(&mut self, world: &mut World) {
+        let dt = world.physics.integration_parameters.dt;
+        self.push(world, dt);
+    }
+
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        let mut copy = target.add_component::<ForceField>();
+        copy.shape = self.shape;
+        copy.mode = self.mode;
+        copy.strength = self.strength;
+        copy.falloff = self.falloff;
+    }
+}
+
+impl ForceField {
+    pub fn shape(&self) -> ForceFieldShape {
+        self.shape
+    }
+
+    /// Sets the volume bodies are pushed in. Defaults to a sphere with a radius of 5.
+    pub fn set_shape(&mut self, shape: ForceFieldShape) {
+        self.shape = shape;
+    }
+
+    pub fn mode(&self) -> ForceFieldMode {
+        self.mode
+    }
+
+    /// Sets which way bodies are pushed. Defaults to along the local x axis.
+    pub fn set_mode(&mut self, mode: ForceFieldMode) {
+        self.mode = mode;
+    }
+
+    pub fn strength(&self) -> f32 {
+        self.strength
+    }
+
+    /// Sets the force at full strength in newtons, or the impulse for
+    /// [`explode`](Self::explode)
+    pub fn set_strength(&mut self, strength: f32) {
+        self.strength = strength;
+    }
+
+    pub fn falloff(&self) -> Falloff {
+        self.falloff
+    }
+
+    pub fn set_falloff(&mut self, falloff: Falloff) {
+        self.falloff = falloff;
+    }
+
+    /// Gives every body in the volume a single push, with the [strength](Self::strength) as
+    /// the impulse, e.g. for an explosion with [`ForceFieldMode::Radial`]. Disable the component
+    /// if it should only push once.
+    pub fn explode(&self) {
+        self.push(self.parent.world(), 1.0);
+    }
+
+    /// Applies the field to all bodies in the volume, as an impulse of `scale` times the force
+    fn push(&self, world: &mut World, scale: f32) {
+        let center = self.parent.transform.position();
+        let rotation = self.parent.transform.rotation();
+        let pose = Isometry3::from_parts(Translation3::from(center), rotation);
+
+        let (shape, reach): (Box<dyn Shape>, f32) = match self.shape {
+            ForceFieldShape::Sphere { radius } => (Box::new(Ball::new(radius)), radius),
+            ForceFieldShape::Box { half_extents } => {
+                (Box::new(Cuboid::new(half_extents)), half_extents.norm())
+            }
+        };
+        let bodies: Vec<RigidBodyHandle> =
+            world
+                .physics
+                .bodies_in_shape(&pose, shape.as_ref(), QueryFilter::default());
+
+        let own_body = self.parent.as_ffi() as u128;
+        for handle in bodies {
+            let Some(body) = world.physics.rigid_body_set.get_mut(handle) else {
+                continue;
+            };
+            if !body.is_dynamic() || body.user_data == own_body {
+                continue;
+            }
+
+            let offset = body.center_of_mass().coords - center;
+            let direction = match self.mode {
+                ForceFieldMode::Directional(direction) => rotation * direction,
+                ForceFieldMode::Radial => offset,
+                ForceFieldMode::Vortex(axis) => (rotation * axis).cross(&offset),
+            };
+            let Some(direction) = direction.try_normalize(f32::EPSILON) else {
+                continue;
+            };
+
+            let force = direction * self.strength * self.falloff_at(offset.norm(), reach);
+            body.apply_impulse(force * scale, true);
+        }
+    }
+
+    fn falloff_at(&self, distance: f32, reach: f32) -> f32 {
+        let t = if reach > 0.0 {
+            (distance / reach).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        match self.falloff {
+            Falloff::None => 1.0,
+            Falloff::Linear => 1.0 - t,
+            Falloff::Quadratic => (1.0 - t) * (1.0 - t),
+        }
+    }
+}
@@ -37,7 +37,8 @@ pub struct FPSCameraConfig {
     pub idle_sway_amplitude: f32,
     /// Idle sway frequency. Default: 0.8
     pub idle_sway_frequency: f32,
-    /// The normal unzoomed fov
+    /// The normal unzoomed fov. Zoom and sprint kick are applied relative to it, on top of the
+    /// camera's base fov, which is set to this when the controller starts.
     pub normal_fov: f32,
     /// Maximum zoom FOV
     pub zoom_fov: f32,
@@ -134,6 +135,9 @@ impl NewComponent for FirstPersonCameraController {
 impl Component for FirstPersonCameraController {
     fn init(&mut self, _world: &mut World) {
         self.base_position = *self.parent.transform.local_position();
+        if let Some(mut camera) = self.parent.get_component::<CameraComponent>() {
+            camera.set_fov_instant(self.config.normal_fov);
+        }
     }
 
     fn update(&mut self, world: &mut World) {
@@ -162,10 +166,19 @@ impl Component for FirstPersonCameraController {
         self.update_jump_bob(transform);
 
         let mouse_delta = world.input.mouse_delta();
-        self.calculate_rotation(&world.input, delta_time, mouse_delta);
-        self.update_rotation(transform, delta_time, mouse_delta);
+        self.calculate_rotation(&world.input, delta_time, &mouse_delta);
+        self.update_rotation(transform, delta_time, &mouse_delta);
         self.update_zoom();
     }
+
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        let mut copy = target.add_component::<FirstPersonCameraController>();
+        copy.config = self.config.clone();
+        copy.yaw = self.yaw;
+        copy.pitch = self.pitch;
+        copy.interp_yaw = self.interp_yaw;
+        copy.interp_pitch = self.interp_pitch;
+    }
 }
 
 impl FirstPersonCameraController {
@@ -316,9 +329,10 @@ impl FirstPersonCameraController {
         transform.set_local_position_vec(self.base_position + bob_offset);
     }
 
+    /// Returns the zoom magnification relative to the normal fov
     fn calculate_zoom(&self) -> f32 {
         if !self.config.enable_zoom {
-            return self.config.normal_fov;
+            return 1.0;
         }
         let delta = self.config.normal_fov - self.config.zoom_fov;
         let zoomed = self.config.normal_fov - delta * self.zoom_factor.clamp(0.0, 1.0);
@@ -329,7 +343,8 @@ impl FirstPersonCameraController {
                 0.0
             };
 
-        zoomed + sprint_kick
+        let half_tan = |fov: f32| (fov.to_radians() * 0.5).tan();
+        half_tan(self.config.normal_fov) / half_tan(zoomed + sprint_kick)
     }
 
     fn update_zoom(&mut self) {
@@ -339,6 +354,6 @@ impl FirstPersonCameraController {
         };
 
         camera.zoom_speed = self.config.fov_lerp_speed;
-        camera.set_fov_target(self.calculate_zoom());
+        camera.set_zoom(self.calculate_zoom());
     }
 }
@@ -133,6 +133,25 @@ impl Component for FirstPersonMovementController {
 
         body.set_linvel(linvel, true);
     }
+
+    fn duplicate(&self, mut target: GameObjectId, world: &mut World) {
+        let mut copy = target.add_component::<FirstPersonMovementController>();
+        copy.move_speed = self.move_speed;
+        copy.jump_factor = self.jump_factor;
+        copy.sprint_multiplier = self.sprint_multiplier;
+        copy.velocity_interp_t = self.velocity_interp_t;
+        copy.air_control = self.air_control;
+        copy.feet_height = self.feet_height;
+
+        // the camera controller is on a child, which is duplicated after this
+        world.commands().push(move |_| {
+            copy.camera_controller = copy
+                .parent
+                .get_child_component::<FirstPersonCameraController>()
+                .map(CRef::downgrade)
+                .unwrap_or_default();
+        });
+    }
 }
 
 impl FirstPersonMovementController {
@@ -50,6 +50,14 @@ impl Component for FreecamController {
 
         self.update_movement(delta_time, input);
     }
+
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        let mut copy = target.add_component::<FreecamController>();
+        copy.move_speed = self.move_speed;
+        copy.look_sensitivity = self.look_sensitivity;
+        copy.yaw = self.yaw;
+        copy.pitch = self.pitch;
+    }
 }
 
 impl FreecamController {
@@ -77,7 +85,7 @@ impl FreecamController {
         if input.is_key_pressed(KeyCode::Space) {
             ud_movement = 1.0;
         }
-        if input.is_key_pressed(KeyCode::ControlLeft) {
+        if input.ctrl() {
             ud_movement = -1.0;
         }
 
@@ -103,7 +111,7 @@ impl FreecamController {
             + transform.up() * ud_movement
             + transform.forward() * fb_movement;
 
-        let move_speed = if input.is_key_pressed(KeyCode::ShiftLeft) {
+        let move_speed = if input.shift() {
             self.move_speed * 3.0
         } else {
             let controller_extra_speed =
@@ -4,10 +4,18 @@ use crate::World;
 use crate::components::{Component, NewComponent};
 use crate::core::GameObjectId;
 
+/// Pulls the object along a direction, or toward another object like a planet.
+///
+/// This moves the transform directly and is meant for objects without a
+/// [`RigidBodyComponent`](crate::components::RigidBodyComponent). Bodies follow the physics
+/// gravity or their [gravity override](crate::components::RigidBodyComponent::set_gravity_override).
 pub struct GravityComponent {
     pub acceleration_per_sec: f32,
+    /// Speed against the gravity direction, negative while falling
     pub velocity: f32,
     pub max_acceleration: f32,
+    direction: Vector3<f32>,
+    attractor: Option<GameObjectId>,
     parent: GameObjectId,
 }
 
@@ -17,6 +25,8 @@ impl NewComponent for GravityComponent {
             acceleration_per_sec: 9.80665,
             velocity: 0.0,
             max_acceleration: 100.0,
+            direction: -Vector3::y(),
+            attractor: None,
             parent,
         }
     }
@@ -28,7 +38,69 @@ impl Component for GravityComponent {
 
         self.velocity = (self.velocity - self.acceleration_per_sec * delta_time)
             .clamp(-self.max_acceleration, self.max_acceleration);
+
+        let up = -self.current_direction();
         let transform = &mut self.parent.transform;
-        transform.translate(Vector3::new(0.0, self.velocity, 0.0));
+        transform.set_position_vec(transform.position() + up * self.velocity);
+    }
+
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        let mut copy = target.add_component::<GravityComponent>();
+        copy.acceleration_per_sec = self.acceleration_per_sec;
+        copy.velocity = self.velocity;
+        copy.max_acceleration = self.max_acceleration;
+        copy.direction = self.direction;
+        copy.attractor = self.attractor;
+    }
+}
+
+impl GravityComponent {
+    /// Returns the world direction gravity pulls in, if there's no attractor
+    pub fn direction(&self) -> Vector3<f32> {
+        self.direction
+    }
+
+    /// Sets the world direction gravity pulls in. Defaults to straight down. A zero vector is
+    /// ignored.
+    pub fn set_direction(&mut self, direction: Vector3<f32>) {
+        if let Some(direction) = direction.try_normalize(f32::EPSILON) {
+            self.direction = direction;
+        }
+    }
+
+    pub fn strength(&self) -> f32 {
+        self.acceleration_per_sec
+    }
+
+    /// Sets how fast the fall speeds up, in units per second
+    pub fn set_strength(&mut self, strength: f32) {
+        self.acceleration_per_sec = strength;
+    }
+
+    /// Returns the object gravity pulls toward, unless it was deleted
+    pub fn attractor(&self) -> Option<GameObjectId> {
+        self.attractor.filter(|attractor| attractor.exists())
+    }
+
+    /// Pulls toward the position of `attractor` instead of along the direction, e.g. the
+    /// center of a spherical planet
+    pub fn set_attractor(&mut self, attractor: GameObjectId) {
+        self.attractor = Some(attractor);
+    }
+
+    /// Goes back to pulling along the [direction](Self::direction)
+    pub fn clear_attractor(&mut self) {
+        self.attractor = None;
+    }
+
+    /// Returns the direction gravity pulls in right now, toward the attractor if there is one
+    pub fn current_direction(&self) -> Vector3<f32> {
+        self.attractor()
+            .and_then(|attractor| {
+                let to_attractor =
+                    attractor.transform.position() - self.parent.transform.position();
+                to_attractor.try_normalize(f32::EPSILON)
+            })
+            .unwrap_or(self.direction)
     }
 }
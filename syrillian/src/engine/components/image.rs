@@ -6,7 +6,7 @@ use crate::rendering::UiContext;
 use crate::rendering::strobe::ImageScalingMode;
 use crate::strobe::UiImageDraw;
 use crate::windowing::RenderTargetId;
-use nalgebra::Matrix4;
+use nalgebra::{Matrix4, Vector2};
 
 #[derive(Debug)]
 pub struct Image {
@@ -14,6 +14,7 @@ pub struct Image {
     scaling: ImageScalingMode,
     translation: Matrix4<f32>,
     draw_order: u32,
+    preferred_size: Option<Vector2<f32>>,
     pub parent: GameObjectId,
     render_target: RenderTargetId,
 }
@@ -66,6 +67,23 @@ impl Image {
         self.translation
     }
 
+    /// The size a [`UiContainer`](crate::components::UiContainer) makes room for. Without one,
+    /// it's the size the scaling mode currently covers on `screen`.
+    pub fn preferred_size(&self, screen: Vector2<f32>) -> Vector2<f32> {
+        self.preferred_size
+            .unwrap_or_else(|| self.scaling.size_px(screen))
+    }
+
+    /// Fixes the preferred size. Layouts replace the scaling mode, so a container sets this to
+    /// the initial size before it arranges the image for the first time.
+    pub fn set_preferred_size(&mut self, size: Option<Vector2<f32>>) {
+        self.preferred_size = size;
+    }
+
+    pub(crate) fn has_preferred_size(&self) -> bool {
+        self.preferred_size.is_some()
+    }
+
     fn strobe_draw(&self) -> UiImageDraw {
         UiImageDraw {
             draw_order: self.draw_order(),
@@ -90,6 +108,7 @@ impl NewComponent for Image {
             },
             translation: Matrix4::identity(),
             draw_order: 0,
+            preferred_size: None,
             render_target: RenderTargetId::PRIMARY,
         }
     }
@@ -99,4 +118,14 @@ impl Component for Image {
     fn on_gui(&mut self, world: &mut World, ui: UiContext) {
         ui.image(world, self.render_target, self.strobe_draw());
     }
+
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        let mut copy = target.add_component::<Image>();
+        copy.material = self.material;
+        copy.scaling = self.scaling;
+        copy.translation = self.translation;
+        copy.draw_order = self.draw_order;
+        copy.preferred_size = self.preferred_size;
+        copy.render_target = self.render_target;
+    }
 }
@@ -0,0 +1,171 @@
+use crate::components::TypedComponentId;
+use nalgebra::Vector3;
+use std::mem;
+
+/// The value of a field shown by an [`Inspector`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum InspectValue {
+    Bool(bool),
+    Int(i64),
+    Float(f32),
+    Vector(Vector3<f32>),
+    /// An RGB color with channels in `[0, 1]`
+    Color(Vector3<f32>),
+    Text(String),
+}
+
+/// A field a component declared in [`Component::inspect`](crate::components::Component::inspect)
+#[derive(Debug, Clone, PartialEq)]
+pub struct InspectField {
+    pub name: &'static str,
+    pub value: InspectValue,
+    /// Whether the field is only shown, so edits to it are ignored
+    pub read_only: bool,
+}
+
+/// The fields of the transform or of one component of an object, returned by
+/// [`World::inspect_object`](crate::World::inspect_object)
+#[derive(Debug, Clone, PartialEq)]
+pub struct InspectorSection {
+    /// The type name of the component, or `"Transform"`
+    pub name: &'static str,
+    /// The component, or `None` for the transform
+    pub component: Option<TypedComponentId>,
+    pub fields: Vec<InspectField>,
+}
+
+/// Reads and writes component fields for editor UIs, without knowing the component types.
+///
+/// Components pass the current value of each field to a method like [`float`](Self::float), and
+/// apply the value if it returns `true`, which happens when an editor changed that field:
+///
+/// ```ignore
+/// fn inspect(&mut self, ui: &mut Inspector) {
+///     let mut speed = self.speed();
+///     if ui.float("Speed", &mut speed) {
+///         self.set_speed(speed);
+///     }
+/// }
+/// ```
+///
+/// The same call collects the fields for [`World::inspect_object`](crate::World::inspect_object)
+/// and applies edits from [`World::set_inspected_field`](crate::World::set_inspected_field), so
+/// fields should be declared the same way every time.
+#[derive(Debug, Default)]
+pub struct Inspector {
+    fields: Vec<InspectField>,
+    edit: Option<(String, InspectValue)>,
+    applied: bool,
+}
+
+impl Inspector {
+    /// Makes an inspector that only collects the fields
+    pub(crate) fn collect() -> Self {
+        Inspector::default()
+    }
+
+    /// Makes an inspector that sets `field` to `value`, if a field with that name and type is
+    /// declared
+    pub(crate) fn edit(field: &str, value: InspectValue) -> Self {
+        Inspector {
+            edit: Some((field.to_string(), value)),
+            ..Inspector::default()
+        }
+    }
+
+    pub(crate) fn into_fields(self) -> Vec<InspectField> {
+        self.fields
+    }
+
+    /// Returns whether the edit was handed to a field
+    pub(crate) fn applied(&self) -> bool {
+        self.applied
+    }
+
+    pub fn bool(&mut self, name: &'static str, value: &mut bool) -> bool {
+        match self.field(name, InspectValue::Bool(*value)) {
+            Some(InspectValue::Bool(new)) => {
+                *value = new;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn int(&mut self, name: &'static str, value: &mut i64) -> bool {
+        match self.field(name, InspectValue::Int(*value)) {
+            Some(InspectValue::Int(new)) => {
+                *value = new;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn float(&mut self, name: &'static str, value: &mut f32) -> bool {
+        match self.field(name, InspectValue::Float(*value)) {
+            Some(InspectValue::Float(new)) => {
+                *value = new;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn vector(&mut self, name: &'static str, value: &mut Vector3<f32>) -> bool {
+        match self.field(name, InspectValue::Vector(*value)) {
+            Some(InspectValue::Vector(new)) => {
+                *value = new;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn color(&mut self, name: &'static str, value: &mut Vector3<f32>) -> bool {
+        match self.field(name, InspectValue::Color(*value)) {
+            Some(InspectValue::Color(new)) => {
+                *value = new;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn text(&mut self, name: &'static str, value: &mut String) -> bool {
+        match self.field(name, InspectValue::Text(value.clone())) {
+            Some(InspectValue::Text(new)) => {
+                *value = new;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Shows a value that can't be edited, like a computed mass
+    pub fn label(&mut self, name: &'static str, value: InspectValue) {
+        self.fields.push(InspectField {
+            name,
+            value,
+            read_only: true,
+        });
+    }
+
+    /// Records the field, and returns the edited value if the edit is for it and has its type
+    fn field(&mut self, name: &'static str, value: InspectValue) -> Option<InspectValue> {
+        let matches = self.edit.as_ref().is_some_and(|(field, new)| {
+            field == name && mem::discriminant(new) == mem::discriminant(&value)
+        });
+        self.fields.push(InspectField {
+            name,
+            value,
+            read_only: false,
+        });
+
+        if !matches {
+            return None;
+        }
+        self.applied = true;
+        self.edit.take().map(|(_, new)| new)
+    }
+}
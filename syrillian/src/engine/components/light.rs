@@ -1,5 +1,5 @@
 use crate::World;
-use crate::components::{Component, NewComponent};
+use crate::components::{Component, Inspector, NewComponent};
 use crate::core::GameObjectId;
 use crate::rendering::CPUDrawCtx;
 use crate::rendering::lights::{Light, LightProxy, LightType};
@@ -114,6 +114,35 @@ impl<L: LightTypeTrait + 'static> Component for LightComponent<L> {
         }
     }
 
+    fn inspect(&mut self, ui: &mut Inspector) {
+        let data = *self.data();
+
+        let mut color = data.color;
+        if ui.color("Color", &mut color) {
+            self.set_color_vec(&color);
+        }
+        let mut intensity = data.intensity;
+        if ui.float("Intensity", &mut intensity) {
+            self.set_intensity(intensity);
+        }
+        if L::type_id() != LightType::Sun {
+            let mut range = data.range;
+            if ui.float("Range", &mut range) {
+                self.set_range(range);
+            }
+        }
+        if L::type_id() == LightType::Spot {
+            let mut inner = data.inner_angle.to_degrees();
+            if ui.float("Inner Angle", &mut inner) {
+                self.set_inner_angle(inner);
+            }
+            let mut outer = data.outer_angle.to_degrees();
+            if ui.float("Outer Angle", &mut outer) {
+                self.set_outer_angle(outer);
+            }
+        }
+    }
+
     fn create_light_proxy(&mut self, _world: &World) -> Option<Box<LightProxy>> {
         Some(Box::new(self.local_proxy))
     }
@@ -130,6 +159,26 @@ impl<L: LightTypeTrait + 'static> Component for LightComponent<L> {
 
         self.dirty = false;
     }
+
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        let mut copy = target.add_component::<LightComponent<L>>();
+        copy.target_inner_angle = self.target_inner_angle;
+        copy.target_outer_angle = self.target_outer_angle;
+        copy.inner_angle_t = self.inner_angle_t;
+        copy.outer_angle_t = self.outer_angle_t;
+        copy.tween_enabled = self.tween_enabled;
+
+        // the pose comes from the new object
+        let pose = copy.local_proxy;
+        copy.local_proxy = LightProxy {
+            position: pose.position,
+            direction: pose.direction,
+            up: pose.up,
+            view_mat: pose.view_mat,
+            ..self.local_proxy
+        };
+        copy.dirty = true;
+    }
 }
 
 impl<L: LightTypeTrait + 'static> Light for LightComponent<L> {
@@ -0,0 +1,180 @@
+use crate::World;
+use crate::components::{
+    Component, NewComponent, PointLightComponent, SpotLightComponent, SunLightComponent,
+};
+use crate::core::GameObjectId;
+use crate::rendering::lights::Light;
+use crate::utils::ease::Curve;
+use crate::utils::math::value_noise;
+use nalgebra::Vector3;
+use std::f32::consts::TAU;
+
+/// How a [`LightAnimator`] varies its light over time
+#[derive(Debug, Clone, PartialEq)]
+pub enum LightAnimation {
+    /// Smooth random changes, like fire
+    Noise,
+    /// A sine wave
+    Pulse,
+    /// Repeats the curve over each cycle, with the time going from `0` to `1`. Its values
+    /// should stay in `[-1, 1]`, like the other signals.
+    Curve(Curve),
+}
+
+/// Ready-made settings for a [`LightAnimator`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LightAnimationPreset {
+    /// Gentle, quick flicker
+    Candle,
+    /// Slow, even breathing
+    Pulse,
+    /// Hard, erratic flicker that almost turns the light off
+    BrokenNeon,
+}
+
+/// Animates the intensity, and optionally the color, of a point, spot or sun light on the same
+/// object, e.g. for torches and failing lamps.
+///
+/// The animation is a signal between `-1` and `1`. The intensity is the base intensity scaled by
+/// `1 + amplitude * signal`. The base intensity and color are read from the light in the first
+/// update, unless they're set before.
+pub struct LightAnimator {
+    parent: GameObjectId,
+    animation: LightAnimation,
+    amplitude: f32,
+    frequency: f32,
+    color_range: Option<(Vector3<f32>, Vector3<f32>)>,
+    base_intensity: Option<f32>,
+    seed: u32,
+    time: f32,
+}
+
+impl NewComponent for LightAnimator {
+    fn new(parent: GameObjectId) -> Self {
+        let mut animator = LightAnimator {
+            parent,
+            animation: LightAnimation::Noise,
+            amplitude: 0.0,
+            frequency: 0.0,
+            color_range: None,
+            base_intensity: None,
+            // so torches next to each other don't flicker in sync
+            seed: World::instance().rng().next_u32(),
+            time: 0.0,
+        };
+        animator.set_preset(LightAnimationPreset::Candle);
+        animator
+    }
+}
+
+impl Component for LightAnimator {
+    fn update(&mut self, world: &mut World) {
+        self.time += world.delta_time().as_secs_f32();
+
+        let signal = self.signal(self.time);
+        let amplitude = self.amplitude;
+        let color_range = self.color_range;
+        let mut base = self.base_intensity;
+
+        self.with_light(|light| {
+            let base = *base.get_or_insert(light.data().intensity);
+            light.set_intensity(base * (1.0 + amplitude * signal));
+            if let Some((dim, bright)) = color_range {
+                let color = dim.lerp(&bright, (signal + 1.0) * 0.5);
+                light.set_color_vec(&color);
+            }
+        });
+        self.base_intensity = base;
+    }
+
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        let mut copy = target.add_component::<LightAnimator>();
+        copy.animation = self.animation.clone();
+        copy.amplitude = self.amplitude;
+        copy.frequency = self.frequency;
+        copy.color_range = self.color_range;
+        copy.base_intensity = self.base_intensity;
+    }
+}
+
+impl LightAnimator {
+    /// Sets the animation, amplitude and frequency of a preset. Defaults to
+    /// [`LightAnimationPreset::Candle`].
+    pub fn set_preset(&mut self, preset: LightAnimationPreset) {
+        let (animation, amplitude, frequency) = match preset {
+            LightAnimationPreset::Candle => (LightAnimation::Noise, 0.2, 6.0),
+            LightAnimationPreset::Pulse => (LightAnimation::Pulse, 0.5, 0.5),
+            LightAnimationPreset::BrokenNeon => (LightAnimation::Noise, 0.9, 15.0),
+        };
+        self.animation = animation;
+        self.amplitude = amplitude;
+        self.frequency = frequency;
+    }
+
+    pub fn animation(&self) -> &LightAnimation {
+        &self.animation
+    }
+
+    pub fn set_animation(&mut self, animation: LightAnimation) {
+        self.animation = animation;
+    }
+
+    pub fn amplitude(&self) -> f32 {
+        self.amplitude
+    }
+
+    /// Sets how far the intensity strays from the base, as a fraction of it. `1.0` swings
+    /// between off and twice as bright.
+    pub fn set_amplitude(&mut self, amplitude: f32) {
+        self.amplitude = amplitude.max(0.0);
+    }
+
+    pub fn frequency(&self) -> f32 {
+        self.frequency
+    }
+
+    /// Sets how many cycles, or noise waves, play per second
+    pub fn set_frequency(&mut self, frequency: f32) {
+        self.frequency = frequency.max(0.0);
+    }
+
+    /// Blends the light color from `dim` at the lowest to `bright` at the highest intensity
+    pub fn set_color_range(&mut self, dim: Vector3<f32>, bright: Vector3<f32>) {
+        self.color_range = Some((dim, bright));
+    }
+
+    /// Stops changing the color, leaving it at its last value
+    pub fn clear_color_range(&mut self) {
+        self.color_range = None;
+    }
+
+    pub fn base_intensity(&self) -> Option<f32> {
+        self.base_intensity
+    }
+
+    /// Sets the intensity the animation varies around
+    pub fn set_base_intensity(&mut self, intensity: f32) {
+        self.base_intensity = Some(intensity.max(0.0));
+    }
+
+    /// Returns the animation signal in `[-1, 1]` at `time` seconds
+    pub fn signal(&self, time: f32) -> f32 {
+        let cycles = time * self.frequency;
+        let signal = match &self.animation {
+            LightAnimation::Noise => value_noise(cycles, self.seed),
+            LightAnimation::Pulse => (cycles * TAU).sin(),
+            LightAnimation::Curve(curve) => curve.sample(cycles.fract()),
+        };
+        signal.clamp(-1.0, 1.0)
+    }
+
+    fn with_light(&self, f: impl FnOnce(&mut dyn Light)) {
+        if let Some(mut light) = self.parent.get_component::<PointLightComponent>() {
+            f(&mut *light);
+        } else if let Some(mut light) = self.parent.get_component::<SpotLightComponent>() {
+            f(&mut *light);
+        } else if let Some(mut light) = self.parent.get_component::<SunLightComponent>() {
+            f(&mut *light);
+        }
+    }
+}
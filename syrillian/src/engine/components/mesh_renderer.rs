@@ -5,8 +5,8 @@ use crate::engine::assets::HMesh;
 use crate::engine::rendering::CPUDrawCtx;
 use crate::rendering::proxies::SceneProxy;
 use crate::rendering::proxies::mesh_proxy::MeshSceneProxy;
-use crate::{MAX_BONES, World, proxy_data_mut};
-use nalgebra::{Matrix4, Vector3};
+use crate::{World, proxy_data_mut};
+use nalgebra::{Matrix4, Vector3, Vector4};
 use tracing::warn;
 
 #[derive(Debug, Default, Clone)]
@@ -15,30 +15,31 @@ pub struct BoneData {
 }
 
 impl BoneData {
+    /// A single identity bone, bound for meshes without skin data so they're drawn rigidly
     #[rustfmt::skip]
-    pub const DUMMY: [Bone; MAX_BONES] = [Bone {
+    pub const RIGID: [Bone; 1] = [Bone {
         transform: Matrix4::new(
             1.0, 0.0, 0.0, 0.0,
             0.0, 1.0, 0.0, 0.0,
             0.0, 0.0, 1.0, 0.0,
             0.0, 0.0, 0.0, 1.0
         )
-    }; MAX_BONES];
+    }];
 
-    pub fn new_full_identity() -> Self {
+    /// An identity palette with `count` bones, at least one
+    pub fn identity(count: usize) -> Self {
         Self {
-            bones: vec![
-                Bone {
-                    transform: Matrix4::identity()
-                };
-                MAX_BONES
-            ],
+            bones: vec![BoneData::RIGID[0]; count.max(1)],
         }
     }
 
+    /// Copies the palette into the first bones, adding bones if the palette is longer
     pub fn set_first_n(&mut self, mats: &[Matrix4<f32>]) {
-        for (i, m) in mats.iter().take(self.bones.len()).enumerate() {
-            self.bones[i].transform = *m;
+        if self.bones.len() < mats.len() {
+            self.bones.resize(mats.len(), BoneData::RIGID[0]);
+        }
+        for (bone, m) in self.bones.iter_mut().zip(mats) {
+            bone.transform = *m;
         }
     }
 
@@ -58,13 +59,57 @@ pub struct DebugVertexNormal {
     normal: Vector3<f32>,
 }
 
+/// A highlight drawn around the silhouette of a [`MeshRenderer`]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Outline {
+    pub color: Vector4<f32>,
+    /// Thickness in pixels
+    pub width: f32,
+    /// Keeps the outline visible where other objects are in front of the mesh. The hidden part
+    /// of the mesh then shows as a silhouette in the outline color. Defaults to true.
+    pub on_top: bool,
+}
+
+impl Outline {
+    pub fn new(color: Vector4<f32>, width: f32) -> Self {
+        Outline {
+            color,
+            width,
+            on_top: true,
+        }
+    }
+
+    /// Sets whether the outline is drawn on top of objects in front of the mesh
+    pub fn on_top(mut self, on_top: bool) -> Self {
+        self.on_top = on_top;
+        self
+    }
+}
+
+/// A simpler mesh that's drawn once the renderer is at least `distance` away from the camera
+#[derive(Debug, Copy, Clone)]
+pub struct MeshLod {
+    pub distance: f32,
+    pub mesh: HMesh,
+}
+
 #[derive(Debug)]
 pub struct MeshRenderer {
     parent: GameObjectId,
     mesh: HMesh,
     materials: Vec<HMaterial>,
+    material_override: Option<HMaterial>,
+    submesh_overrides: Vec<Option<HMaterial>>,
+    outline: Option<Outline>,
+    lods: Vec<MeshLod>,
+    cull_distance: Option<f32>,
+    lod_hysteresis: f32,
+    lod_level: usize,
+    culled: bool,
     dirty_mesh: bool,
     dirty_materials: bool,
+    dirty_overrides: bool,
+    dirty_outline: bool,
 }
 
 impl NewComponent for MeshRenderer {
@@ -74,15 +119,25 @@ impl NewComponent for MeshRenderer {
             parent,
             mesh: HMesh::UNIT_CUBE,
             materials: vec![],
+            material_override: None,
+            submesh_overrides: vec![],
+            outline: None,
+            lods: vec![],
+            cull_distance: None,
+            lod_hysteresis: 0.1,
+            lod_level: 0,
+            culled: false,
             dirty_mesh: false,
             dirty_materials: false,
+            dirty_overrides: false,
+            dirty_outline: false,
         }
     }
 }
 
 impl Component for MeshRenderer {
     fn create_render_proxy(&mut self, world: &World) -> Option<Box<dyn SceneProxy>> {
-        let Some(mesh) = world.assets.meshes.try_get(self.mesh) else {
+        let Some(mesh) = world.assets.meshes.try_get(self.lod_mesh()) else {
             warn!(
                 "Mesh Renderer couldn't create its proxy because the mesh wasn't found in the asset store"
             );
@@ -90,12 +145,15 @@ impl Component for MeshRenderer {
         };
 
         Some(Box::new(MeshSceneProxy {
-            mesh: self.mesh,
+            mesh: self.lod_mesh(),
             materials: self.materials.clone(),
+            material_override: self.material_override,
+            submesh_overrides: self.submesh_overrides.clone(),
+            outline: self.outline,
             material_ranges: mesh.material_ranges.clone(),
-            bone_data: BoneData::new_full_identity(),
+            bone_data: BoneData::identity(mesh.bones.len()),
             bones_dirty: false,
-            bounding: mesh.bounding_sphere,
+            bounding: mesh.bounding_sphere(),
         }))
     }
 
@@ -113,11 +171,33 @@ impl Component for MeshRenderer {
             });
         }
 
+        self.update_lod(world, &mut ctx);
+
+        if self.dirty_overrides {
+            let material_override = self.material_override;
+            let submesh_overrides = self.submesh_overrides.clone();
+            ctx.send_proxy_update(move |sc| {
+                let data: &mut MeshSceneProxy = proxy_data_mut!(sc);
+                data.material_override = material_override;
+                data.submesh_overrides = submesh_overrides;
+            });
+            self.dirty_overrides = false;
+        }
+
+        if self.dirty_outline {
+            let outline = self.outline;
+            ctx.send_proxy_update(move |sc| {
+                let data: &mut MeshSceneProxy = proxy_data_mut!(sc);
+                data.outline = outline;
+            });
+            self.dirty_outline = false;
+        }
+
         if !self.dirty_mesh && !self.dirty_materials {
             return;
         }
 
-        let Some(mesh) = world.assets.meshes.try_get(self.mesh) else {
+        let Some(mesh) = world.assets.meshes.try_get(self.lod_mesh()) else {
             warn!(
                 "Mesh Renderer couldn't update its proxy because the mesh wasn't found in the asset store"
             );
@@ -125,8 +205,8 @@ impl Component for MeshRenderer {
         };
 
         if self.dirty_mesh {
-            let h_mesh = self.mesh;
-            let bounds = mesh.bounding_sphere;
+            let h_mesh = self.lod_mesh();
+            let bounds = mesh.bounding_sphere();
             ctx.send_proxy_update(move |sc| {
                 let data: &mut MeshSceneProxy = proxy_data_mut!(sc);
                 data.mesh = h_mesh;
@@ -144,6 +224,27 @@ impl Component for MeshRenderer {
             });
         }
     }
+
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        let mut copy = target.add_component::<MeshRenderer>();
+        *copy = MeshRenderer {
+            parent: target,
+            mesh: self.mesh,
+            materials: self.materials.clone(),
+            material_override: self.material_override,
+            submesh_overrides: self.submesh_overrides.clone(),
+            outline: self.outline,
+            lods: self.lods.clone(),
+            cull_distance: self.cull_distance,
+            lod_hysteresis: self.lod_hysteresis,
+            lod_level: self.lod_level,
+            culled: self.culled,
+            dirty_mesh: true,
+            dirty_materials: true,
+            dirty_overrides: true,
+            dirty_outline: true,
+        };
+    }
 }
 
 impl MeshRenderer {
@@ -175,6 +276,205 @@ impl MeshRenderer {
     pub fn mesh(&self) -> HMesh {
         self.mesh
     }
+
+    /// Draws every submesh of this renderer with `material`, without touching the shared mesh
+    /// or the material slots. Submesh overrides still take precedence.
+    pub fn set_material_override(&mut self, material: HMaterial) {
+        self.material_override = Some(material);
+        self.dirty_overrides = true;
+    }
+
+    pub fn clear_material_override(&mut self) {
+        self.material_override = None;
+        self.dirty_overrides = true;
+    }
+
+    pub fn material_override(&self) -> Option<HMaterial> {
+        self.material_override
+    }
+
+    /// Draws only submesh `idx` of this renderer with `material`
+    pub fn set_submesh_material_override(&mut self, idx: usize, material: HMaterial) {
+        if self.submesh_overrides.len() <= idx {
+            self.submesh_overrides.resize(idx + 1, None);
+        }
+        self.submesh_overrides[idx] = Some(material);
+        self.dirty_overrides = true;
+    }
+
+    pub fn clear_submesh_material_override(&mut self, idx: usize) {
+        if let Some(slot) = self.submesh_overrides.get_mut(idx) {
+            *slot = None;
+            self.dirty_overrides = true;
+        }
+    }
+
+    pub fn submesh_material_override(&self, idx: usize) -> Option<HMaterial> {
+        self.submesh_overrides.get(idx).copied().flatten()
+    }
+
+    /// Removes the material override and all submesh overrides
+    pub fn clear_material_overrides(&mut self) {
+        self.material_override = None;
+        self.submesh_overrides.clear();
+        self.dirty_overrides = true;
+    }
+
+    /// Outlines the mesh, e.g. to highlight a hovered or selected object. `None` removes it.
+    pub fn set_outline(&mut self, outline: Option<Outline>) {
+        if self.outline != outline {
+            self.outline = outline;
+            self.dirty_outline = true;
+        }
+    }
+
+    pub fn outline(&self) -> Option<Outline> {
+        self.outline
+    }
+
+    /// Returns the material submesh `idx` is drawn with, with overrides applied
+    pub fn material_for(&self, idx: usize) -> HMaterial {
+        resolve_material(
+            &self.materials,
+            self.material_override,
+            &self.submesh_overrides,
+            idx,
+        )
+    }
+
+    /// Draws `mesh` instead of the main mesh once the renderer is at least `distance` away from
+    /// the active camera. The distance is measured to the bounding sphere of the main mesh.
+    pub fn add_lod(&mut self, distance: f32, mesh: HMesh) {
+        let idx = self.lods.partition_point(|lod| lod.distance <= distance);
+        self.lods.insert(idx, MeshLod { distance, mesh });
+        // the current level now points at a different mesh
+        if idx < self.lod_level {
+            self.dirty_mesh = true;
+        }
+    }
+
+    pub fn clear_lods(&mut self) {
+        self.lods.clear();
+        self.set_lod_level(0);
+    }
+
+    /// Returns the levels of detail, sorted by distance
+    pub fn lods(&self) -> &[MeshLod] {
+        &self.lods
+    }
+
+    /// Hides the renderer entirely beyond `distance` from the active camera
+    pub fn set_cull_distance(&mut self, distance: Option<f32>) {
+        self.cull_distance = distance;
+    }
+
+    pub fn cull_distance(&self) -> Option<f32> {
+        self.cull_distance
+    }
+
+    /// Sets how far past a LOD or cull distance the camera has to move before switching,
+    /// as a fraction of that distance. Prevents flickering at the boundary. Defaults to 0.1.
+    pub fn set_lod_hysteresis(&mut self, hysteresis: f32) {
+        self.lod_hysteresis = hysteresis.max(0.0);
+    }
+
+    /// Returns the active level of detail, where 0 is the main mesh and `n` is the `n`th LOD
+    pub fn lod_level(&self) -> usize {
+        self.lod_level
+    }
+
+    /// Returns true if the renderer is hidden because it's beyond the cull distance
+    pub fn is_culled(&self) -> bool {
+        self.culled
+    }
+
+    /// Returns the mesh that's currently drawn, with the level of detail applied
+    pub fn lod_mesh(&self) -> HMesh {
+        match self.lod_level {
+            0 => self.mesh,
+            level => self.lods[level - 1].mesh,
+        }
+    }
+
+    fn set_lod_level(&mut self, level: usize) {
+        if self.lod_level != level {
+            self.lod_level = level;
+            self.dirty_mesh = true;
+            self.dirty_materials = true;
+        }
+    }
+
+    fn update_lod(&mut self, world: &World, ctx: &mut CPUDrawCtx) {
+        if self.lods.is_empty() && self.cull_distance.is_none() && !self.culled {
+            return;
+        }
+
+        let Some(camera_pos) = world
+            .active_camera()
+            .upgrade(world)
+            .map(|camera| camera.parent().transform.position())
+        else {
+            return;
+        };
+        let Some(mesh) = world.assets.meshes.try_get(self.mesh) else {
+            return;
+        };
+
+        let world_matrix = self
+            .parent
+            .transform
+            .global_transform_matrix()
+            .to_homogeneous();
+        let bounds = mesh.bounding_sphere().transformed(&world_matrix);
+        let distance = ((bounds.center - camera_pos).norm() - bounds.radius).max(0.0);
+
+        // a threshold that's already passed only releases once the camera is clearly back inside
+        let hysteresis = self.lod_hysteresis;
+        let passed = |threshold: f32, was_passed: bool| {
+            let factor = if was_passed {
+                1.0 - hysteresis
+            } else {
+                1.0 + hysteresis
+            };
+            distance >= threshold * factor
+        };
+
+        let level = self
+            .lods
+            .iter()
+            .enumerate()
+            .take_while(|(i, lod)| passed(lod.distance, *i < self.lod_level))
+            .count();
+        self.set_lod_level(level);
+
+        let culled = self
+            .cull_distance
+            .is_some_and(|cull| passed(cull, self.culled));
+        if culled != self.culled {
+            self.culled = culled;
+            if culled {
+                ctx.disable_proxy();
+            } else {
+                ctx.enable_proxy();
+            }
+        }
+    }
+}
+
+/// Picks the submesh override, then the renderer override, then the material slot
+pub(crate) fn resolve_material(
+    materials: &[HMaterial],
+    material_override: Option<HMaterial>,
+    submesh_overrides: &[Option<HMaterial>],
+    idx: usize,
+) -> HMaterial {
+    submesh_overrides
+        .get(idx)
+        .copied()
+        .flatten()
+        .or(material_override)
+        .or_else(|| materials.get(idx).copied())
+        .unwrap_or(HMaterial::FALLBACK)
 }
 
 impl From<&Vertex3D> for DebugVertexNormal {
@@ -38,46 +38,70 @@
 
 pub mod animation;
 pub mod audio;
+pub mod billboard;
 pub mod button;
 pub mod camera;
+pub mod camera_track;
 pub mod collider;
+pub mod decal;
+pub mod follow;
+pub mod force_field;
 pub mod fp_camera;
 pub mod fp_movement;
 pub mod freecam;
 pub mod gravity;
 pub mod image;
+pub mod inspector;
 pub mod light;
+pub mod light_animator;
 pub mod mesh_renderer;
+pub mod orbit_camera;
 pub mod panel;
+pub mod parallel;
+pub mod particle_emitter;
 pub mod rigid_body;
 pub mod rope;
 pub mod rotate;
 pub mod skeletal;
 pub mod spring;
 pub mod text;
+pub mod ui_button;
+pub mod ui_container;
 pub mod ui_rect;
 
 #[cfg(debug_assertions)]
 pub mod camera_debug;
 
 pub use animation::AnimationComponent;
+pub use billboard::{Billboard, BillboardMode};
 pub use button::Button;
-pub use camera::CameraComponent;
+pub use camera::{CameraClear, CameraComponent, ClearFlags, ViewportRect};
+pub use camera_track::{CameraKeyframe, CameraTrack, CameraTrackFinished};
 pub use collider::Collider3D;
+pub use decal::Decal;
+pub use follow::{FollowComponent, FollowSpace};
+pub use force_field::{Falloff, ForceField, ForceFieldMode, ForceFieldShape};
 pub use fp_camera::FirstPersonCameraController;
 pub use fp_movement::FirstPersonMovementController;
 pub use freecam::FreecamController;
 pub use gravity::GravityComponent;
 pub use image::Image;
+pub use inspector::{InspectField, InspectValue, Inspector, InspectorSection};
 pub use light::{PointLightComponent, SpotLightComponent, Sun, SunLightComponent};
+pub use light_animator::{LightAnimation, LightAnimationPreset, LightAnimator};
 pub use mesh_renderer::MeshRenderer;
+pub use orbit_camera::OrbitCameraController;
 pub use panel::Panel;
+pub use parallel::{ParallelContext, ParallelUpdate};
+pub use particle_emitter::ParticleEmitter;
 pub use rigid_body::RigidBodyComponent;
 pub use rope::RopeComponent;
 pub use rotate::RotateComponent;
 pub use skeletal::SkeletalComponent;
 pub use spring::SpringComponent;
 pub use text::{Text2D, Text3D};
+pub use ui_button::UiButton;
+pub use ui_container::{UiAlign, UiContainer, UiDirection};
 pub use ui_rect::UiRect;
 
 #[cfg(debug_assertions)]
@@ -92,6 +116,7 @@ use delegate::delegate;
 use slotmap::{Key, new_key_type};
 use std::any::{Any, TypeId};
 use std::borrow::Borrow;
+use std::cell::Cell;
 use std::fmt::{Debug, Formatter};
 use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
@@ -99,31 +124,42 @@ use std::mem;
 use std::ops::{Deref, DerefMut};
 use std::rc::Rc;
 use std::sync::Arc;
+use tracing::warn;
 
 new_key_type! { pub struct ComponentId; }
 
 pub struct ComponentContext {
     pub(crate) tid: TypedComponentId,
     pub(crate) parent: GameObjectId,
+    pub(crate) enabled: Cell<bool>,
 }
 
 pub type AComponentContext = Arc<ComponentContext>;
 
 impl ComponentContext {
     pub(crate) fn new(tid: TypedComponentId, parent: GameObjectId) -> Self {
-        Self { tid, parent }
+        Self {
+            tid,
+            parent,
+            enabled: Cell::new(true),
+        }
     }
 
     pub(crate) unsafe fn null() -> Self {
         ComponentContext {
             tid: TypedComponentId::null::<dyn Component>(),
             parent: GameObjectId::null(),
+            enabled: Cell::new(true),
         }
     }
 
     pub fn parent(&self) -> GameObjectId {
         self.parent
     }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.get()
+    }
 }
 
 pub struct CRef<C: Component + ?Sized> {
@@ -177,6 +213,12 @@ impl<C: Component> CRef<C> {
         self.into()
     }
 
+    /// Turns this component on or off without removing it. A disabled component keeps its
+    /// state, but doesn't update or draw.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.as_dyn().set_enabled(enabled);
+    }
+
     pub fn as_dyn(&self) -> CRef<dyn Component> {
         unsafe {
             CRef {
@@ -214,9 +256,29 @@ impl<C: Component + ?Sized> CRef<C> {
     pub fn parent(&self) -> GameObjectId {
         self.ctx.parent()
     }
+
+    /// Returns whether the component itself is enabled. It still doesn't run while its object
+    /// is inactive.
+    pub fn is_enabled(&self) -> bool {
+        self.ctx.is_enabled()
+    }
 }
 
 impl CRef<dyn Component> {
+    /// Turns this component on or off without removing it or its object.
+    ///
+    /// A disabled component keeps its state, but its updates, GUI and proxy updates are
+    /// skipped, and its render and light proxies are dropped until it's enabled again.
+    /// [`Component::on_active_changed`] is called like for a disabled object, and
+    /// [`Component::delete`] still runs when it's removed.
+    pub fn set_enabled(&self, enabled: bool) {
+        if self.ctx.enabled.replace(enabled) == enabled {
+            return;
+        }
+
+        World::instance().set_component_enabled(self.clone(), enabled);
+    }
+
     pub fn as_a<C: Component>(&self) -> Option<CRef<C>> {
         if !self.is_a::<C>() {
             return None;
@@ -380,21 +442,34 @@ pub trait Component: Any {
     // Gets called when the game object is created directly after new
     fn init(&mut self, world: &mut World) {}
 
-    // Gets called when the component should update anything state-related
+    // Gets called when the component should update anything state-related. Runs once per frame
+    // with the variable world.delta_time().
     fn update(&mut self, world: &mut World) {}
 
     // Gets called when the component should update any state that's necessary for physics
     fn late_update(&mut self, world: &mut World) {}
 
-    // Gets called before physics are evolved
+    // Gets called before physics are evolved. Runs zero or more times per frame, once per
+    // physics step, and world.delta_time() is the fixed step in here.
     fn pre_fixed_update(&mut self, world: &mut World) {}
 
-    // Gets called after physics have evolved
+    // Gets called after physics have evolved. Like pre_fixed_update, world.delta_time() is the
+    // fixed step in here.
     fn fixed_update(&mut self, world: &mut World) {}
 
     // Gets called after all other updates are done
     fn post_update(&mut self, world: &mut World) {}
 
+    /// Components with a lower order run first in every phase (update, late_update,
+    /// pre_fixed_update, fixed_update and post_update), across all objects. Equal orders run in
+    /// the order the components were added. The phases themselves always run in the same
+    /// sequence, so this can't make an update run after another component's post_update.
+    ///
+    /// The order is read once when the component is added.
+    fn update_order(&self) -> i32 {
+        0
+    }
+
     fn create_render_proxy(&mut self, world: &World) -> Option<Box<dyn SceneProxy>> {
         None
     }
@@ -409,8 +484,30 @@ pub trait Component: Any {
 
     fn on_gui(&mut self, world: &mut World, ctx: UiContext) {}
 
+    // Gets called when the game object is enabled or disabled through itself or a parent, or
+    // when the component itself is enabled or disabled
+    fn on_active_changed(&mut self, world: &mut World, active: bool) {}
+
     // Gets called when the component is about to be deleted
     fn delete(&mut self, world: &mut World) {}
+
+    /// Adds a copy of this component to `target` for [`World::duplicate`]. The default doesn't
+    /// copy anything and warns, so the component is left out of duplicates.
+    fn duplicate(&self, target: GameObjectId, world: &mut World) {
+        warn!(
+            "{} can't be duplicated, leaving it out of the copy",
+            self.type_name()
+        );
+    }
+
+    /// Declares the fields an editor can show and change, see [`Inspector`]. The default
+    /// declares none.
+    fn inspect(&mut self, ui: &mut Inspector) {}
+
+    /// Returns the type name of the component, e.g. for inspectors and debug output
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
 }
 
 /// Either you'll have to implement this, or Default
@@ -0,0 +1,169 @@
+use crate::World;
+use crate::components::{CameraComponent, Component, NewComponent};
+use crate::core::GameObjectId;
+use crate::input::InputManager;
+use crate::windowing::RenderTargetId;
+use nalgebra::{UnitQuaternion, Vector3};
+use winit::event::MouseButton;
+
+/// Orbits the camera object around a target point, like in model viewers and editors.
+///
+/// Left drag orbits, middle drag pans the target and the scroll wheel zooms. The camera is
+/// placed in world space, so the object can still be parented to anything.
+pub struct OrbitCameraController {
+    /// Degrees of rotation per pixel of mouse movement
+    pub orbit_sensitivity: f32,
+    /// Fraction of the distance moved per pixel of mouse movement while panning
+    pub pan_sensitivity: f32,
+    /// Fraction of the distance zoomed per scroll step
+    pub zoom_sensitivity: f32,
+    /// Rotation around the target's Y axis in degrees
+    pub yaw: f32,
+    /// Rotation above (positive) or below the target in degrees
+    pub pitch: f32,
+    parent: GameObjectId,
+    target: Vector3<f32>,
+    distance: f32,
+    min_distance: f32,
+    max_distance: f32,
+    min_pitch: f32,
+    max_pitch: f32,
+}
+
+impl NewComponent for OrbitCameraController {
+    fn new(parent: GameObjectId) -> Self {
+        OrbitCameraController {
+            orbit_sensitivity: 0.3,
+            pan_sensitivity: 0.002,
+            zoom_sensitivity: 0.1,
+            yaw: 0.0,
+            pitch: 20.0,
+            parent,
+            target: Vector3::zeros(),
+            distance: 5.0,
+            min_distance: 0.1,
+            max_distance: 1000.0,
+            min_pitch: -89.0,
+            max_pitch: 89.0,
+        }
+    }
+}
+
+impl Component for OrbitCameraController {
+    fn update(&mut self, world: &mut World) {
+        let target = self
+            .parent
+            .get_component::<CameraComponent>()
+            .map(|c| c.render_target())
+            .unwrap_or(RenderTargetId::PRIMARY);
+        world.input.set_active_target(target);
+
+        if world.input.is_window_focused() {
+            self.update_input(&world.input);
+        }
+
+        self.apply();
+    }
+
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        let mut copy = target.add_component::<OrbitCameraController>();
+        copy.orbit_sensitivity = self.orbit_sensitivity;
+        copy.pan_sensitivity = self.pan_sensitivity;
+        copy.zoom_sensitivity = self.zoom_sensitivity;
+        copy.yaw = self.yaw;
+        copy.pitch = self.pitch;
+        copy.target = self.target;
+        copy.distance = self.distance;
+        copy.min_distance = self.min_distance;
+        copy.max_distance = self.max_distance;
+        copy.min_pitch = self.min_pitch;
+        copy.max_pitch = self.max_pitch;
+    }
+}
+
+impl OrbitCameraController {
+    pub fn target(&self) -> Vector3<f32> {
+        self.target
+    }
+
+    /// Sets the world space point the camera orbits around
+    pub fn set_target(&mut self, target: Vector3<f32>) {
+        self.target = target;
+    }
+
+    pub fn distance(&self) -> f32 {
+        self.distance
+    }
+
+    /// Sets the distance to the target, clamped to the min and max distance
+    pub fn set_distance(&mut self, distance: f32) {
+        self.distance = distance.clamp(self.min_distance, self.max_distance);
+    }
+
+    pub fn min_distance(&self) -> f32 {
+        self.min_distance
+    }
+
+    pub fn set_min_distance(&mut self, min_distance: f32) {
+        self.min_distance = min_distance.max(0.0);
+        self.max_distance = self.max_distance.max(self.min_distance);
+        self.set_distance(self.distance);
+    }
+
+    pub fn max_distance(&self) -> f32 {
+        self.max_distance
+    }
+
+    pub fn set_max_distance(&mut self, max_distance: f32) {
+        self.max_distance = max_distance.max(self.min_distance);
+        self.set_distance(self.distance);
+    }
+
+    /// Returns the pitch limits in degrees as `(min, max)`
+    pub fn pitch_limits(&self) -> (f32, f32) {
+        (self.min_pitch, self.max_pitch)
+    }
+
+    /// Limits the pitch in degrees. Keep both within ±90° to avoid flipping over the poles.
+    pub fn set_pitch_limits(&mut self, min_pitch: f32, max_pitch: f32) {
+        self.min_pitch = min_pitch.min(max_pitch);
+        self.max_pitch = max_pitch.max(min_pitch);
+        self.pitch = self.pitch.clamp(self.min_pitch, self.max_pitch);
+    }
+
+    fn update_input(&mut self, input: &InputManager) {
+        let delta = input.mouse_delta();
+
+        if input.is_button_pressed(MouseButton::Left) {
+            self.yaw += delta.x * self.orbit_sensitivity;
+            self.pitch -= delta.y * self.orbit_sensitivity;
+        }
+
+        if input.is_button_pressed(MouseButton::Middle) {
+            let transform = &self.parent.transform;
+            let scale = self.distance * self.pan_sensitivity;
+            self.target += (transform.right() * delta.x - transform.up() * delta.y) * scale;
+        }
+
+        let scroll = input.mouse_wheel_delta();
+        if scroll.abs() > f32::EPSILON {
+            let factor = (1.0 - scroll * self.zoom_sensitivity).max(0.1);
+            self.set_distance(self.distance * factor);
+        }
+    }
+
+    /// Moves the camera onto the orbit described by the current settings
+    fn apply(&mut self) {
+        self.pitch = self.pitch.clamp(self.min_pitch, self.max_pitch);
+
+        let yaw_rotation =
+            UnitQuaternion::from_axis_angle(&Vector3::y_axis(), self.yaw.to_radians());
+        let pitch_rotation =
+            UnitQuaternion::from_axis_angle(&Vector3::x_axis(), -self.pitch.to_radians());
+        let rotation = yaw_rotation * pitch_rotation;
+
+        let transform = &mut self.parent.transform;
+        transform.set_position_vec(self.target + rotation * Vector3::new(0.0, 0.0, self.distance));
+        transform.set_rotation(rotation);
+    }
+}
@@ -43,9 +43,17 @@ impl Component for Panel {
 
         layout_children(self.parent.children(), &container_layout, world);
     }
+
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        target.add_component::<Panel>().padding = self.padding;
+    }
 }
 
-fn layout_children(children: &[GameObjectId], parent_layout: &UiRectLayout, world: &mut World) {
+pub(crate) fn layout_children(
+    children: &[GameObjectId],
+    parent_layout: &UiRectLayout,
+    world: &mut World,
+) {
     for &child in children {
         let rect = child.get_component::<UiRect>();
         let layout_from_rect = rect.as_ref().and_then(|rect| {
@@ -0,0 +1,125 @@
+//! Opt-in parallel updates for CPU-heavy components.
+//!
+//! Components and objects live behind `Rc`s and a thread-local [`World::instance`], so regular
+//! component hooks can only run on the game thread. A component that implements
+//! [`ParallelUpdate`] and is registered with [`World::register_parallel_update`] additionally
+//! gets [`ParallelUpdate::parallel_update`] called once per frame, between `update` and
+//! `late_update`. With the `parallel` feature, those calls are spread over a rayon thread pool.
+//!
+//! # Threading contract
+//!
+//! - `parallel_update` may only touch the component's own fields. The world isn't reachable
+//!   from it: dereferencing a [`GameObjectId`](crate::core::GameObjectId) or calling [`World::instance`] on a worker
+//!   thread panics, because the world isn't bound there.
+//! - Changes to the world are queued with [`ParallelContext::defer`]. Once all parallel updates
+//!   finished, they're moved into [`World::commands`] and applied with the other queued commands
+//!   after `late_update`. Deferred commands of different components run in no particular order.
+//! - The component type has to be [`Send`], which rules out holding [`CRef`](crate::components::CRef)s
+//!   or other `Rc`s. Ids like `GameObjectId` and asset handles are fine to keep and use in
+//!   deferred commands.
+//!
+//! ```rust
+//! use nalgebra::Vector3;
+//! use syrillian::components::{Component, NewComponent, ParallelContext, ParallelUpdate};
+//! use syrillian::core::GameObjectId;
+//!
+//! struct Drift {
+//!     parent: GameObjectId,
+//!     offset: Vector3<f32>,
+//! }
+//!
+//! impl NewComponent for Drift {
+//!     fn new(parent: GameObjectId) -> Self {
+//!         Drift { parent, offset: Vector3::zeros() }
+//!     }
+//! }
+//!
+//! impl Component for Drift {}
+//!
+//! impl ParallelUpdate for Drift {
+//!     fn parallel_update(&mut self, ctx: &ParallelContext) {
+//!         // expensive math on the component's own data...
+//!         self.offset.x += ctx.delta_time().as_secs_f32();
+//!
+//!         let (mut parent, offset) = (self.parent, self.offset);
+//!         ctx.defer(move |_world| parent.transform.set_local_position_vec(offset));
+//!     }
+//! }
+//! ```
+
+use crate::World;
+use crate::components::Component;
+use crossbeam_channel::Sender;
+use web_time::Duration;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// A change to the world queued from a parallel update
+pub type WorldCommand = Box<dyn FnOnce(&mut World) + Send>;
+
+/// A component whose heavy work can run concurrently with others of its kind, see the
+/// [module docs](self) for the rules.
+pub trait ParallelUpdate: Component + Send {
+    fn parallel_update(&mut self, ctx: &ParallelContext);
+}
+
+/// What a [`ParallelUpdate`] can see of the world
+pub struct ParallelContext {
+    delta_time: Duration,
+    time: Duration,
+    commands: Sender<WorldCommand>,
+}
+
+impl ParallelContext {
+    pub(crate) fn new(world: &World, commands: Sender<WorldCommand>) -> Self {
+        ParallelContext {
+            delta_time: world.delta_time(),
+            time: world.time(),
+            commands,
+        }
+    }
+
+    /// See [`World::delta_time`]
+    pub fn delta_time(&self) -> Duration {
+        self.delta_time
+    }
+
+    /// See [`World::time`]
+    pub fn time(&self) -> Duration {
+        self.time
+    }
+
+    /// Queues `command` to run on the game thread with the other [world commands](World::commands)
+    /// after `late_update`
+    pub fn defer(&self, command: impl FnOnce(&mut World) + Send + 'static) {
+        // the receiver lives until the commands are applied
+        let _ = self.commands.send(Box::new(command));
+    }
+}
+
+/// A unique pointer to a component that's handed to exactly one worker
+struct SendPtr<C>(*mut C);
+
+unsafe impl<C: Send> Send for SendPtr<C> {}
+
+/// Runs the parallel update of all active components of type `C`
+pub(crate) fn run_parallel_update<C: ParallelUpdate>(world: &mut World, ctx: &ParallelContext) {
+    // keeps the components alive while the workers use them
+    let mut components = world.active_components_of_type::<C>();
+    let jobs: Vec<SendPtr<C>> = components
+        .iter_mut()
+        .map(|comp| SendPtr(&mut **comp as *mut C))
+        .collect();
+
+    // SAFETY: every pointer is a different component, and nothing else touches them until
+    // all jobs are done
+    #[cfg(feature = "parallel")]
+    jobs.into_par_iter()
+        .for_each(|job| unsafe { (*job.0).parallel_update(ctx) });
+
+    #[cfg(not(feature = "parallel"))]
+    for job in jobs {
+        unsafe { (*job.0).parallel_update(ctx) }
+    }
+}
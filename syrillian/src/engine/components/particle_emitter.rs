@@ -0,0 +1,306 @@
+use crate::World;
+use crate::assets::HTexture;
+use crate::components::{Component, NewComponent};
+use crate::core::{BoundingSphere, GameObjectId};
+use crate::proxy_data_mut;
+use crate::rendering::CPUDrawCtx;
+use crate::rendering::proxies::{ParticleInstance, ParticleProxy, SceneProxy};
+use crate::utils::Rng;
+use nalgebra::{Vector3, Vector4};
+use std::cmp::Ordering;
+
+#[derive(Debug, Copy, Clone)]
+struct Particle {
+    position: Vector3<f32>,
+    velocity: Vector3<f32>,
+    age: f32,
+}
+
+/// Spawns, simulates and draws camera-facing particles, e.g. for smoke or sparks.
+///
+/// Particles are simulated on the CPU in world space, so they stay where they were emitted
+/// when the emitter moves. They blend like other transparent objects, sorted back to front.
+#[derive(Debug)]
+pub struct ParticleEmitter {
+    parent: GameObjectId,
+    particles: Vec<Particle>,
+
+    /// Particles emitted per second while emitting
+    pub emission_rate: f32,
+    /// How long a single particle lives, in seconds
+    pub lifetime: f32,
+    /// Initial velocity in the emitter's local space
+    pub velocity: Vector3<f32>,
+    /// Maximum random deviation added to the initial velocity, in units per second
+    pub velocity_spread: f32,
+    /// Acceleration applied to all particles, in world space
+    pub gravity: Vector3<f32>,
+    pub start_color: Vector4<f32>,
+    pub end_color: Vector4<f32>,
+    pub start_size: f32,
+    pub end_size: f32,
+    /// Older particles are dropped once this limit is reached
+    pub max_particles: usize,
+    /// How long a non-looping emitter keeps emitting, in seconds
+    pub duration: f32,
+
+    texture: Option<HTexture>,
+    looping: bool,
+    elapsed: f32,
+    emit_accumulator: f32,
+    pending_burst: usize,
+    rng: Rng,
+
+    dirty_texture: bool,
+    dirty_particles: bool,
+}
+
+impl NewComponent for ParticleEmitter {
+    fn new(parent: GameObjectId) -> Self {
+        ParticleEmitter {
+            parent,
+            particles: Vec::new(),
+
+            emission_rate: 10.0,
+            lifetime: 2.0,
+            velocity: Vector3::new(0.0, 1.0, 0.0),
+            velocity_spread: 0.5,
+            gravity: Vector3::zeros(),
+            start_color: Vector4::new(1.0, 1.0, 1.0, 1.0),
+            end_color: Vector4::new(1.0, 1.0, 1.0, 0.0),
+            start_size: 0.2,
+            end_size: 0.2,
+            max_particles: 1000,
+            duration: 5.0,
+
+            texture: None,
+            looping: true,
+            elapsed: 0.0,
+            emit_accumulator: 0.0,
+            pending_burst: 0,
+            rng: Rng::new(parent.object_hash() as u64),
+
+            dirty_texture: false,
+            dirty_particles: false,
+        }
+    }
+}
+
+impl Component for ParticleEmitter {
+    fn create_render_proxy(&mut self, _world: &World) -> Option<Box<dyn SceneProxy>> {
+        Some(Box::new(ParticleProxy::new(self.texture)))
+    }
+
+    fn update(&mut self, world: &mut World) {
+        let delta_time = world.delta_time().as_secs_f32();
+        self.simulate(delta_time);
+    }
+
+    fn update_proxy(&mut self, world: &World, mut ctx: CPUDrawCtx) {
+        if self.dirty_texture {
+            let texture = self.texture;
+            ctx.send_proxy_update(move |sc| {
+                let data: &mut ParticleProxy = proxy_data_mut!(sc);
+                data.texture = texture;
+                data.texture_dirty = true;
+            });
+            self.dirty_texture = false;
+        }
+
+        if !self.dirty_particles {
+            return;
+        }
+        self.dirty_particles = false;
+
+        let camera_pos = world
+            .active_camera()
+            .upgrade(world)
+            .map(|camera| camera.parent().transform.position());
+
+        let instances = self.instances(camera_pos);
+        let bounds = instance_bounds(&instances);
+        ctx.send_proxy_update(move |sc| {
+            let data: &mut ParticleProxy = proxy_data_mut!(sc);
+            data.instances = instances;
+            data.instances_dirty = true;
+            data.bounds = bounds;
+        });
+    }
+
+    // the copy starts without live particles, and emits with its own random seed
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        let mut copy = target.add_component::<ParticleEmitter>();
+        copy.emission_rate = self.emission_rate;
+        copy.lifetime = self.lifetime;
+        copy.velocity = self.velocity;
+        copy.velocity_spread = self.velocity_spread;
+        copy.gravity = self.gravity;
+        copy.start_color = self.start_color;
+        copy.end_color = self.end_color;
+        copy.start_size = self.start_size;
+        copy.end_size = self.end_size;
+        copy.max_particles = self.max_particles;
+        copy.duration = self.duration;
+        copy.texture = self.texture;
+        copy.looping = self.looping;
+        copy.elapsed = self.elapsed;
+    }
+}
+
+impl ParticleEmitter {
+    /// Emits `count` particles at once, on the next update
+    pub fn burst(&mut self, count: usize) {
+        self.pending_burst += count;
+    }
+
+    /// Looping emitters emit continuously. Others stop emitting after [`duration`](Self::duration)
+    /// seconds, but still accept bursts.
+    pub fn set_looping(&mut self, looping: bool) {
+        self.looping = looping;
+    }
+
+    /// Restarts the emission timer of a non-looping emitter
+    pub fn restart(&mut self) {
+        self.elapsed = 0.0;
+        self.emit_accumulator = 0.0;
+    }
+
+    /// Removes all live particles
+    pub fn clear(&mut self) {
+        self.particles.clear();
+        self.dirty_particles = true;
+    }
+
+    /// Sets the particle texture. Without one, particles are drawn as soft circles.
+    pub fn set_texture(&mut self, texture: Option<HTexture>) {
+        if self.texture == texture {
+            return;
+        }
+        self.texture = texture;
+        self.dirty_texture = true;
+    }
+
+    pub fn texture(&self) -> Option<HTexture> {
+        self.texture
+    }
+
+    pub fn is_looping(&self) -> bool {
+        self.looping
+    }
+
+    /// Whether the emitter still spawns particles on its own
+    pub fn is_emitting(&self) -> bool {
+        self.looping || self.elapsed < self.duration
+    }
+
+    /// The number of live particles
+    pub fn particle_count(&self) -> usize {
+        self.particles.len()
+    }
+
+    /// Advances the simulation by `delta_time` seconds
+    pub fn simulate(&mut self, delta_time: f32) {
+        let had_particles = !self.particles.is_empty();
+
+        for particle in &mut self.particles {
+            particle.velocity += self.gravity * delta_time;
+            particle.position += particle.velocity * delta_time;
+            particle.age += delta_time;
+        }
+
+        let lifetime = self.lifetime;
+        self.particles.retain(|p| p.age < lifetime);
+
+        let mut spawn_count = std::mem::take(&mut self.pending_burst);
+        if self.is_emitting() {
+            self.emit_accumulator += self.emission_rate.max(0.0) * delta_time;
+            let whole = self.emit_accumulator.floor();
+            self.emit_accumulator -= whole;
+            spawn_count += whole as usize;
+        }
+        self.elapsed += delta_time;
+
+        if spawn_count > 0 {
+            self.spawn(spawn_count);
+        }
+
+        self.dirty_particles |= had_particles || !self.particles.is_empty();
+    }
+
+    fn spawn(&mut self, count: usize) {
+        let transform = &self.parent.transform;
+        let origin = transform.position();
+        let base_velocity = transform.rotation() * self.velocity;
+
+        for _ in 0..count {
+            let spread = self.random_in_unit_sphere() * self.velocity_spread;
+            self.particles.push(Particle {
+                position: origin,
+                velocity: base_velocity + spread,
+                age: 0.0,
+            });
+        }
+
+        let overflow = self.particles.len().saturating_sub(self.max_particles);
+        self.particles.drain(..overflow);
+    }
+
+    fn instances(&self, camera_pos: Option<Vector3<f32>>) -> Vec<ParticleInstance> {
+        let lifetime = self.lifetime.max(f32::EPSILON);
+        let mut instances: Vec<ParticleInstance> = self
+            .particles
+            .iter()
+            .map(|p| {
+                let t = (p.age / lifetime).clamp(0.0, 1.0);
+                ParticleInstance {
+                    position: p.position,
+                    size: self.start_size + (self.end_size - self.start_size) * t,
+                    color: self.start_color.lerp(&self.end_color, t),
+                }
+            })
+            .collect();
+
+        if let Some(camera_pos) = camera_pos {
+            instances.sort_by(|a, b| {
+                let da = (a.position - camera_pos).norm_squared();
+                let db = (b.position - camera_pos).norm_squared();
+                db.partial_cmp(&da).unwrap_or(Ordering::Equal)
+            });
+        }
+
+        instances
+    }
+
+    /// Seeds the spread of new particles, making the emission reproducible
+    pub fn seed(&mut self, seed: u64) {
+        self.rng.seed(seed);
+    }
+
+    fn random_in_unit_sphere(&mut self) -> Vector3<f32> {
+        loop {
+            let v = Vector3::new(
+                self.rng.range(-1.0, 1.0),
+                self.rng.range(-1.0, 1.0),
+                self.rng.range(-1.0, 1.0),
+            );
+            if v.norm_squared() <= 1.0 {
+                return v;
+            }
+        }
+    }
+}
+
+fn instance_bounds(instances: &[ParticleInstance]) -> Option<BoundingSphere> {
+    let first = instances.first()?;
+
+    let (min, max, max_size) = instances.iter().fold(
+        (first.position, first.position, 0.0f32),
+        |(min, max, size), i| (min.inf(&i.position), max.sup(&i.position), size.max(i.size)),
+    );
+
+    let center = (min + max) * 0.5;
+    Some(BoundingSphere {
+        center,
+        radius: (max - center).norm() + max_size,
+    })
+}
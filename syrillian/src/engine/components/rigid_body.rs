@@ -1,15 +1,24 @@
 use crate::World;
-use crate::components::{Component, NewComponent};
+use crate::components::{Component, InspectValue, Inspector, NewComponent};
 use crate::core::GameObjectId;
+use crate::physics::PhysicsManager;
 use crate::utils::math::QuaternionEuler;
-use nalgebra::{Isometry3, Translation3};
+use nalgebra::{Isometry3, Translation3, Vector3};
 use rapier3d::prelude::*;
 use syrillian_utils::debug_panic;
 
+/// Gap a swept kinematic body keeps to what it stops at, so it doesn't start the next move
+/// already touching it
+const SWEEP_SKIN: f32 = 0.005;
+
 pub struct RigidBodyComponent {
     parent: GameObjectId,
     pub body_handle: RigidBodyHandle,
     kinematic: bool,
+    kinematic_swept: bool,
+    gravity_override: Option<Vector3<f32>>,
+    additional_mass: f32,
+    center_of_mass: Option<Vector3<f32>>,
     prev_iso: Isometry3<f32>,
     curr_iso: Isometry3<f32>,
 }
@@ -30,6 +39,10 @@ impl NewComponent for RigidBodyComponent {
             parent,
             body_handle,
             kinematic: false,
+            kinematic_swept: false,
+            gravity_override: None,
+            additional_mass: 0.0,
+            center_of_mass: None,
             prev_iso: Isometry3::default(),
             curr_iso: Isometry3::default(),
         }
@@ -38,18 +51,41 @@ impl NewComponent for RigidBodyComponent {
 
 impl Component for RigidBodyComponent {
     fn pre_fixed_update(&mut self, _world: &mut World) {
-        let rb = World::instance()
-            .physics
-            .rigid_body_set
-            .get_mut(self.body_handle);
+        // colliders can be attached or resized any time, so the override follows them
+        if self.center_of_mass.is_some() {
+            self.apply_mass_properties(false);
+        }
+
+        let physics = &mut World::instance().physics;
+        let world_gravity = physics.gravity;
+        let dt = physics.integration_parameters.dt;
+
+        let mut target = self.parent.transform.position();
+        if self.kinematic && self.kinematic_swept {
+            let stop = self.sweep_to(physics, target);
+            if stop != target {
+                self.parent.transform.set_position_vec(stop);
+                target = stop;
+            }
+        }
+
+        let rb = physics.rigid_body_set.get_mut(self.body_handle);
         if let Some(rb) = rb {
             if rb.is_dynamic() && self.parent.transform.is_dirty() {
                 rb.set_translation(self.parent.transform.position(), false);
                 rb.set_rotation(self.parent.transform.rotation(), false);
             } else if rb.is_kinematic() {
-                rb.set_next_kinematic_translation(self.parent.transform.position());
+                rb.set_next_kinematic_translation(target);
                 rb.set_next_kinematic_rotation(self.parent.transform.rotation());
             }
+
+            // cancel out the scaled world gravity the step is going to apply
+            if let Some(gravity) = self.gravity_override
+                && rb.is_dynamic()
+            {
+                let correction = gravity - world_gravity * rb.gravity_scale();
+                rb.apply_impulse(correction * rb.mass() * dt, false);
+            }
         } else {
             debug_panic!("de-synced - remake_rigid_body();");
         }
@@ -63,13 +99,75 @@ impl Component for RigidBodyComponent {
                 Isometry3::from_parts(Translation3::from(*rb.translation()), *rb.rotation());
             if rb.is_dynamic() {
                 self.parent.transform.set_position_vec(*rb.translation());
-                if rb.is_rotation_locked().iter().all(|l| !l) {
+                if !rb.is_rotation_locked().iter().all(|&l| l) {
                     self.parent.transform.set_rotation(*rb.rotation());
                 }
             }
         }
     }
 
+    fn inspect(&mut self, ui: &mut Inspector) {
+        let mut kinematic = self.kinematic;
+        if ui.bool("Kinematic", &mut kinematic) {
+            self.set_kinematic(kinematic);
+        }
+        ui.bool("Swept", &mut self.kinematic_swept);
+
+        let mut gravity_scale = self.gravity_scale();
+        if ui.float("Gravity Scale", &mut gravity_scale) {
+            self.set_gravity_scale(gravity_scale);
+        }
+        let mut additional_mass = self.additional_mass;
+        if ui.float("Additional Mass", &mut additional_mass) {
+            self.set_additional_mass(additional_mass);
+        }
+
+        let Some((mass, mut velocity)) = self.body().map(|rb| (rb.mass(), *rb.linvel())) else {
+            return;
+        };
+        ui.label("Mass", InspectValue::Float(mass));
+        if ui.vector("Velocity", &mut velocity)
+            && let Some(rb) = self.body_mut()
+        {
+            rb.set_linvel(velocity, true);
+        }
+    }
+
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        let mut copy = target.add_component::<RigidBodyComponent>();
+        copy.set_kinematic(self.kinematic);
+        copy.kinematic_swept = self.kinematic_swept;
+        copy.gravity_override = self.gravity_override;
+        copy.additional_mass = self.additional_mass;
+        copy.center_of_mass = self.center_of_mass;
+        copy.apply_mass_properties(true);
+
+        let Some(original) = self.body().cloned() else {
+            return;
+        };
+        if let Some(rb) = copy.body_mut() {
+            rb.set_gravity_scale(original.gravity_scale(), false);
+            rb.set_linear_damping(original.linear_damping());
+            rb.set_angular_damping(original.angular_damping());
+            rb.set_locked_axes(original.locked_axes(), false);
+            rb.enable_ccd(original.is_ccd_enabled());
+            rb.set_linvel(*original.linvel(), false);
+            rb.set_angvel(*original.angvel(), false);
+        }
+    }
+
+    fn on_active_changed(&mut self, world: &mut World, active: bool) {
+        let Some(rb) = world.physics.rigid_body_set.get_mut(self.body_handle) else {
+            return;
+        };
+        if active {
+            // the object might have been moved while it was disabled
+            rb.set_translation(self.parent.transform.position(), false);
+            rb.set_rotation(self.parent.transform.rotation(), false);
+        }
+        rb.set_enabled(active);
+    }
+
     fn delete(&mut self, world: &mut World) {
         world.physics.rigid_body_set.remove(
             self.body_handle,
@@ -97,6 +195,24 @@ impl RigidBodyComponent {
             .get_mut(self.body_handle)
     }
 
+    /// Stops all linear and angular motion and drops the render interpolation history, so the
+    /// body doesn't visibly slide over from where it was before being teleported.
+    pub fn reset_motion(&mut self) {
+        let iso = Isometry3::from_parts(
+            Translation3::from(self.parent.transform.position()),
+            self.parent.transform.rotation(),
+        );
+        self.prev_iso = iso;
+        self.curr_iso = iso;
+
+        if let Some(rb) = self.body_mut() {
+            rb.set_linvel(Vector3::zeros(), false);
+            rb.set_angvel(Vector3::zeros(), false);
+            rb.reset_forces(false);
+            rb.reset_torques(false);
+        }
+    }
+
     pub fn set_kinematic(&mut self, kinematic: bool) {
         let rb = self.body_mut().expect("Rigid body de-synced");
         if kinematic {
@@ -111,6 +227,179 @@ impl RigidBodyComponent {
         self.kinematic
     }
 
+    /// Makes the body, while [kinematic](Self::set_kinematic), sweep its colliders from where
+    /// it was to where the transform moved it, and stop at the first fixed or kinematic
+    /// collider in the way instead of teleporting through it. The transform is moved back to
+    /// where the body stopped.
+    ///
+    /// Useful for carried or dragged objects, which can move far in a single step. Only the
+    /// movement is swept, rotations are applied as they are. Dynamic bodies in the way are
+    /// still pushed aside.
+    pub fn set_kinematic_swept(&mut self, swept: bool) {
+        self.kinematic_swept = swept;
+    }
+
+    pub fn is_kinematic_swept(&self) -> bool {
+        self.kinematic_swept
+    }
+
+    /// Returns how strongly the world gravity affects this body
+    pub fn gravity_scale(&self) -> f32 {
+        self.body().map_or(1.0, RigidBody::gravity_scale)
+    }
+
+    /// Multiplies the [world gravity](World::set_gravity) for this body. `0.0` makes it float,
+    /// negative values make it fall upwards. Defaults to `1.0`.
+    ///
+    /// Ignored while a [gravity override](Self::set_gravity_override) is set.
+    pub fn set_gravity_scale(&mut self, scale: f32) {
+        if let Some(rb) = self.body_mut() {
+            rb.set_gravity_scale(scale, true);
+        }
+    }
+
+    pub fn gravity_override(&self) -> Option<Vector3<f32>> {
+        self.gravity_override
+    }
+
+    /// Gives this body its own gravity instead of the world's, e.g. for walking on planets.
+    /// Pass `None` to go back to the scaled world gravity.
+    pub fn set_gravity_override(&mut self, gravity: Option<Vector3<f32>>) {
+        self.gravity_override = gravity;
+        if let Some(rb) = self.body_mut() {
+            rb.wake_up(true);
+        }
+    }
+
+    /// Returns the mass added on top of the colliders' mass
+    pub fn additional_mass(&self) -> f32 {
+        self.additional_mass
+    }
+
+    /// Adds `mass` on top of the mass the attached colliders give through their density. The
+    /// inertia grows with it, but the center of mass stays where the colliders put it.
+    pub fn set_additional_mass(&mut self, mass: f32) {
+        self.additional_mass = mass.max(0.0);
+        self.apply_mass_properties(true);
+    }
+
+    pub fn center_of_mass(&self) -> Option<Vector3<f32>> {
+        self.center_of_mass
+    }
+
+    /// Moves the center of mass to `center` in the local space of the object, e.g. down for a
+    /// car that shouldn't tip over. Pass `None` to go back to the center the colliders give.
+    ///
+    /// The total mass and the rotational inertia still come from the colliders and the
+    /// [additional mass](Self::set_additional_mass), they're just centered on this point.
+    pub fn set_center_of_mass(&mut self, center: Option<Vector3<f32>>) {
+        self.center_of_mass = center;
+        self.apply_mass_properties(true);
+    }
+
+    /// Stops the body from moving along the given world axes, e.g. `z` for a 2.5D game.
+    /// Unlocked axes stay free.
+    pub fn lock_translations(&mut self, x: bool, y: bool, z: bool) {
+        if let Some(rb) = self.body_mut() {
+            rb.set_enabled_translations(!x, !y, !z, true);
+        }
+    }
+
+    /// Stops the body from rotating around the given world axes
+    pub fn lock_rotations(&mut self, x: bool, y: bool, z: bool) {
+        if let Some(rb) = self.body_mut() {
+            rb.set_enabled_rotations(!x, !y, !z, true);
+        }
+    }
+
+    pub fn locked_axes(&self) -> LockedAxes {
+        self.body()
+            .map_or(LockedAxes::empty(), RigidBody::locked_axes)
+    }
+
+    fn apply_mass_properties(&mut self, wake_up: bool) {
+        let physics = &mut World::instance().physics;
+        let Some(rb) = physics.rigid_body_set.get_mut(self.body_handle) else {
+            return;
+        };
+
+        let Some(center) = self.center_of_mass else {
+            rb.set_additional_mass(self.additional_mass, wake_up);
+            return;
+        };
+
+        // the body sums the colliders with the additional properties, so those make up the
+        // difference to the wanted result
+        let colliders = rb
+            .colliders()
+            .iter()
+            .filter_map(|&handle| physics.collider_set.get(handle))
+            .map(|collider| match collider.position_wrt_parent() {
+                Some(pose) => collider.mass_properties().transform_by(pose),
+                None => collider.mass_properties(),
+            })
+            .fold(MassProperties::zero(), |a, b| a + b);
+
+        let wanted = MassProperties::with_principal_inertia_frame(
+            center.into(),
+            colliders.mass() + self.additional_mass,
+            colliders.principal_inertia(),
+            colliders.principal_inertia_local_frame,
+        );
+        rb.set_additional_mass_properties(wanted - colliders, wake_up);
+    }
+
+    /// Returns how far the colliders of the body can move toward `target` before hitting a
+    /// fixed or kinematic collider
+    fn sweep_to(&self, physics: &PhysicsManager, target: Vector3<f32>) -> Vector3<f32> {
+        let Some(rb) = physics.rigid_body_set.get(self.body_handle) else {
+            return target;
+        };
+        let start = *rb.translation();
+        let motion = target - start;
+        if motion.norm_squared() <= f32::EPSILON {
+            return target;
+        }
+
+        let filter = QueryFilter::default()
+            .exclude_rigid_body(self.body_handle)
+            .exclude_dynamic()
+            .exclude_sensors();
+        let qp = physics.broad_phase.as_query_pipeline(
+            &DefaultQueryDispatcher,
+            &physics.rigid_body_set,
+            &physics.collider_set,
+            filter,
+        );
+        let mut options = ShapeCastOptions::with_max_time_of_impact(1.0);
+        options.target_distance = SWEEP_SKIN;
+        options.stop_at_penetration = false;
+
+        // colliders already touching something only stop when moving further into it
+        let toi = rb
+            .colliders()
+            .iter()
+            .filter_map(|&handle| physics.collider_set.get(handle))
+            .filter(|collider| !collider.is_sensor())
+            .filter_map(|collider| {
+                qp.cast_shape(collider.position(), &motion, collider.shape(), options)
+                    .map(|(_, hit)| hit.time_of_impact)
+            })
+            .fold(1.0, f32::min);
+
+        start + motion * toi
+    }
+
+    /// Drops the render interpolation history, so it starts at the current pose of the body
+    pub(crate) fn sync_interpolation(&mut self) {
+        let Some(rb) = self.body() else {
+            return;
+        };
+        let iso = *rb.position();
+        self.prev_iso = iso;
+        self.curr_iso = iso;
+    }
+
     pub fn render_isometry(&self, alpha: f32) -> Isometry3<f32> {
         let p0 = self.prev_iso.translation.vector;
         let p1 = self.curr_iso.translation.vector;
@@ -39,6 +39,14 @@ impl Component for RopeComponent {
     fn delete(&mut self, world: &mut World) {
         self.disconnect(world);
     }
+
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        let mut copy = target.add_component::<RopeComponent>();
+        copy.length = self.length;
+        if let Some(connected) = self.connected {
+            copy.connect_to(connected);
+        }
+    }
 }
 
 impl RopeComponent {
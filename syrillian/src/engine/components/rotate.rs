@@ -47,4 +47,13 @@ impl Component for RotateComponent {
         }
         self.iteration += delta_time;
     }
+
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        let mut copy = target.add_component::<RotateComponent>();
+        copy.rotate_speed = self.rotate_speed;
+        copy.iteration = self.iteration;
+        copy.y_rot = self.y_rot;
+        copy.scale_coefficient = self.scale_coefficient;
+        copy.default_scale = self.default_scale;
+    }
 }
@@ -75,6 +75,18 @@ impl Component for SkeletalComponent {
 
         self.dirty = true;
     }
+
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        let mut copy = target.add_component::<SkeletalComponent>();
+        copy.bones_static.clone_from(&self.bones_static);
+        copy.skin_transform.clone_from(&self.skin_transform);
+        copy.skin_rotation.clone_from(&self.skin_rotation);
+        copy.skin_scale.clone_from(&self.skin_scale);
+        copy.skin_local.clone_from(&self.skin_local);
+        copy.globals.clone_from(&self.globals);
+        copy.palette.clone_from(&self.palette);
+        copy.dirty = true;
+    }
 }
 
 impl SkeletalComponent {
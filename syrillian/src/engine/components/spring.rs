@@ -49,6 +49,16 @@ impl Component for SpringComponent {
             self.connected = None;
         }
     }
+
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        let mut copy = target.add_component::<SpringComponent>();
+        copy.rest_length = self.rest_length;
+        copy.stiffness = self.stiffness;
+        copy.damping = self.damping;
+        if let Some(connected) = self.connected {
+            copy.connect_to(connected);
+        }
+    }
 }
 
 impl SpringComponent {
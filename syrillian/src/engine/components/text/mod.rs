@@ -3,7 +3,8 @@ use crate::assets::HFont;
 use crate::components::{Component, NewComponent};
 use crate::core::GameObjectId;
 use crate::rendering::proxies::SceneProxy;
-use crate::rendering::proxies::text_proxy::{TextProxy, ThreeD, TwoD};
+use crate::rendering::proxies::text_proxy::{TextCameraView, TextProxy, ThreeD, TwoD};
+use crate::rendering::rich_text::StyledRun;
 use crate::rendering::strobe::{TextAlignment, UiTextDraw};
 use crate::rendering::{CPUDrawCtx, UiContext};
 use crate::windowing::RenderTargetId;
@@ -21,6 +22,31 @@ impl Text2D {
         self.proxy.size()
     }
 
+    pub fn alignment(&self) -> TextAlignment {
+        self.proxy.alignment()
+    }
+
+    pub fn position(&self) -> Vector2<f32> {
+        self.proxy.position()
+    }
+
+    /// Measures the text in pixels, e.g. for fitting it into a [`UiContainer`](crate::components::UiContainer).
+    /// Sizes of styled runs aren't taken into account.
+    pub fn preferred_size(&self, world: &World) -> Vector2<f32> {
+        world
+            .assets
+            .fonts
+            .try_get(self.proxy.font())
+            .map(|font| {
+                font.measure_text(
+                    self.proxy.text(),
+                    self.proxy.size(),
+                    self.proxy.letter_spacing(),
+                )
+            })
+            .unwrap_or_else(Vector2::zeros)
+    }
+
     pub fn draw_order(&self) -> u32 {
         self.proxy.draw_order()
     }
@@ -40,6 +66,7 @@ impl Text2D {
             color: self.proxy.color(),
             rainbow: self.proxy.rainbow_mode(),
             text: self.proxy.text().to_string(),
+            styled_runs: self.proxy.styled_runs().map(<[StyledRun]>::to_vec),
             object_hash: self.parent.object_hash(),
         }
     }
@@ -47,6 +74,9 @@ impl Text2D {
     delegate! {
         to self.proxy {
             pub fn set_text(&mut self, text: impl Into<String>);
+            pub fn set_rich_text(&mut self, markup: &str);
+            pub fn set_styled_runs(&mut self, runs: Vec<StyledRun>);
+            pub fn styled_runs(&self) -> Option<&[StyledRun]>;
             pub fn set_alignment(&mut self, alignment: TextAlignment);
             pub fn set_font(&mut self, font: HFont);
             pub fn set_letter_spacing(&mut self, spacing_em: f32);
@@ -75,8 +105,19 @@ impl Component for Text2D {
     fn on_gui(&mut self, world: &mut World, ui: UiContext) {
         ui.text(world, self.render_target(), self.strobe_draw());
     }
+
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        target.add_component::<Text2D>().proxy = self.proxy.clone();
+    }
 }
 
+/// Text that's drawn in the world, on the XY plane of its object.
+///
+/// The text follows the object's global transform, so it inherits the position, rotation and
+/// scale of its parents. With [`set_face_camera`](Self::set_face_camera) the rotation, including
+/// the one inherited from parents, is replaced by one facing the active camera. With
+/// [`set_constant_screen_size`](Self::set_constant_screen_size) the [size](Self::set_size) is in
+/// pixels instead of world units, and the transform's scale still multiplies it.
 #[derive(Debug, Clone)]
 pub struct Text3D {
     proxy: TextProxy<3, ThreeD>,
@@ -95,9 +136,24 @@ impl Text3D {
         self.proxy.render_target()
     }
 
+    pub fn face_camera(&self) -> bool {
+        self.proxy.face_camera()
+    }
+
+    pub fn constant_screen_size(&self) -> bool {
+        self.proxy.constant_screen_size()
+    }
+
     delegate! {
         to self.proxy {
+            /// Turns the text towards the active camera every frame, e.g. for damage numbers
+            pub const fn set_face_camera(&mut self, enable: bool);
+            /// Keeps the text the same size on screen regardless of its distance to the camera
+            pub const fn set_constant_screen_size(&mut self, enable: bool);
             pub fn set_text(&mut self, text: impl Into<String>);
+            pub fn set_rich_text(&mut self, markup: &str);
+            pub fn set_styled_runs(&mut self, runs: Vec<StyledRun>);
+            pub fn styled_runs(&self) -> Option<&[StyledRun]>;
             pub fn set_alignment(&mut self, alignment: TextAlignment);
             pub fn set_font(&mut self, font: HFont);
             pub fn set_letter_spacing(&mut self, spacing_em: f32);
@@ -126,7 +182,24 @@ impl Component for Text3D {
         Some(Box::new(self.proxy.clone()))
     }
 
-    fn update_proxy(&mut self, _world: &World, ctx: CPUDrawCtx) {
+    fn update_proxy(&mut self, world: &World, mut ctx: CPUDrawCtx) {
+        if self.proxy.needs_camera_view() {
+            let view = world.active_camera().upgrade(world).map(|camera| {
+                let transform = &camera.parent().transform;
+                let (_, height) = camera.viewport_resolution();
+                TextCameraView {
+                    position: transform.position(),
+                    up: transform.up(),
+                    forward: transform.forward(),
+                    pixel_size: 2.0 * (camera.fov().to_radians() * 0.5).tan() / height,
+                }
+            });
+            self.proxy.update_camera_view(view, &mut ctx);
+        }
         self.proxy.update_game_thread(ctx);
     }
+
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        target.add_component::<Text3D>().proxy = self.proxy.clone();
+    }
 }
@@ -0,0 +1,303 @@
+use crate::World;
+use crate::components::{Component, Image, NewComponent};
+use crate::core::GameObjectId;
+use nalgebra::Vector2;
+use winit::event::MouseButton;
+
+type UiClickHandler = Box<dyn FnMut(&mut World) + 'static>;
+type UiHoverHandler = Box<dyn FnMut(&mut World, bool) + 'static>;
+
+/// Makes the [`Image`] on the same object react to the mouse, so menus can be built from it.
+///
+/// Hovering is tested against the screen rect of the image's scaling mode. Only the image drawn on
+/// top is hovered, so other images cover the buttons below them. Images with the same draw order
+/// are ordered by their object id. A click is a left mouse press and release that both happen on
+/// the button.
+///
+/// Unlike [`Button`](crate::components::Button), this doesn't wait for the GPU picking pass,
+/// so it reacts in the same frame.
+pub struct UiButton {
+    parent: GameObjectId,
+    hovered: bool,
+    pressed: bool,
+    click_handlers: Vec<UiClickHandler>,
+    hover_handlers: Vec<UiHoverHandler>,
+}
+
+impl UiButton {
+    pub fn add_click_handler<F>(&mut self, handler: F)
+    where
+        F: FnMut(&mut World) + 'static,
+    {
+        self.click_handlers.push(Box::new(handler));
+    }
+
+    /// Adds a handler that's called with `true` when the mouse enters the button, and with
+    /// `false` when it leaves
+    pub fn add_hover_handler<F>(&mut self, handler: F)
+    where
+        F: FnMut(&mut World, bool) + 'static,
+    {
+        self.hover_handlers.push(Box::new(handler));
+    }
+
+    pub fn is_hovered(&self) -> bool {
+        self.hovered
+    }
+
+    /// Returns true while the button is held down after being pressed on
+    pub fn is_pressed(&self) -> bool {
+        self.pressed
+    }
+
+    fn set_hovered(&mut self, world: &mut World, hovered: bool) {
+        if self.hovered == hovered {
+            return;
+        }
+
+        self.hovered = hovered;
+        for handler in &mut self.hover_handlers {
+            handler(world, hovered);
+        }
+    }
+}
+
+/// Returns the object with the topmost image under the mouse. It's looked for once per frame
+/// and shared by all buttons.
+fn hovered_image(world: &mut World) -> Option<GameObjectId> {
+    if let Some(hovered) = world.ui_hover {
+        return hovered;
+    }
+
+    let hovered = if world.input.is_cursor_locked() {
+        None
+    } else {
+        world
+            .find_all_components_of_type::<Image>()
+            .iter()
+            .filter_map(|image| Some((hit_draw_order(image, world)?, image.parent())))
+            .max()
+            .map(|(_, obj)| obj)
+    };
+    world.ui_hover = Some(hovered);
+    hovered
+}
+
+/// Returns the draw order of the image if the mouse is over it
+fn hit_draw_order(image: &Image, world: &World) -> Option<u32> {
+    let obj = image.parent();
+    if !obj.is_alive() || !obj.active_in_hierarchy() {
+        return None;
+    }
+
+    let target = image.render_target();
+    if target != world.input.active_target() {
+        return None;
+    }
+
+    let size = world.viewport_size(target)?;
+    let screen = Vector2::new(size.width as f32, size.height as f32);
+    let mouse = world.input.mouse_position();
+
+    image
+        .scaling_mode()
+        .contains_px(Vector2::new(mouse.x, mouse.y), screen)
+        .then(|| image.draw_order())
+}
+
+impl NewComponent for UiButton {
+    fn new(parent: GameObjectId) -> Self {
+        UiButton {
+            parent,
+            hovered: false,
+            pressed: false,
+            click_handlers: Vec::new(),
+            hover_handlers: Vec::new(),
+        }
+    }
+}
+
+impl Component for UiButton {
+    fn update(&mut self, world: &mut World) {
+        let hovered = hovered_image(world) == Some(self.parent);
+        self.set_hovered(world, hovered);
+
+        if hovered && world.input.is_button_down(MouseButton::Left) {
+            self.pressed = true;
+        } else if world.input.is_button_released(MouseButton::Left) {
+            let clicked = self.pressed && hovered;
+            self.pressed = false;
+
+            if clicked {
+                for handler in &mut self.click_handlers {
+                    handler(world);
+                }
+            }
+        } else if !world.input.is_button_pressed(MouseButton::Left) {
+            self.pressed = false;
+        }
+    }
+
+    fn on_active_changed(&mut self, world: &mut World, active: bool) {
+        if !active {
+            self.pressed = false;
+            self.set_hovered(world, false);
+        }
+    }
+
+    // handlers can't be cloned, so the copy starts without any
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        target.add_component::<UiButton>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::strobe::ImageScalingMode;
+    use crate::windowing::RenderTargetId;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use winit::dpi::{PhysicalPosition, PhysicalSize};
+    use winit::event::{DeviceId, ElementState, WindowEvent};
+
+    fn world_with_viewport() -> Box<World> {
+        let (mut world, ..) = World::fresh();
+        world.set_viewport_size(RenderTargetId::PRIMARY, PhysicalSize::new(800, 600));
+        world.input.set_window_focus(RenderTargetId::PRIMARY, true);
+        world
+    }
+
+    // the rect is in pixels from the bottom left, the mouse from the top left
+    fn image(world: &mut World, left: f32, right: f32, draw_order: u32) -> GameObjectId {
+        let mut obj = world.new_object("image");
+        world.add_child(obj);
+
+        let mut image = obj.add_component::<Image>();
+        image.set_scaling_mode(ImageScalingMode::Absolute {
+            left,
+            right,
+            top: 600.0,
+            bottom: 500.0,
+        });
+        image.set_draw_order(draw_order);
+        obj
+    }
+
+    fn button(world: &mut World, left: f32, right: f32, draw_order: u32) -> GameObjectId {
+        let mut obj = image(world, left, right, draw_order);
+        obj.add_component::<UiButton>();
+        obj
+    }
+
+    fn move_mouse(world: &mut World, x: f64, y: f64) {
+        world.input.process_event(
+            RenderTargetId::PRIMARY,
+            &WindowEvent::CursorMoved {
+                device_id: DeviceId::dummy(),
+                position: PhysicalPosition::new(x, y),
+            },
+        );
+    }
+
+    fn left_mouse(world: &mut World, state: ElementState) {
+        world.input.process_event(
+            RenderTargetId::PRIMARY,
+            &WindowEvent::MouseInput {
+                device_id: DeviceId::dummy(),
+                state,
+                button: MouseButton::Left,
+            },
+        );
+    }
+
+    #[test]
+    fn press_and_release_on_the_button_clicks() {
+        let mut world = world_with_viewport();
+        let obj = button(&mut world, 0.0, 100.0, 0);
+        let mut button = obj.get_component::<UiButton>().unwrap();
+
+        let clicks = Rc::new(Cell::new(0));
+        let hovers = Rc::new(Cell::new(0));
+        let counter = clicks.clone();
+        button.add_click_handler(move |_| counter.set(counter.get() + 1));
+        let counter = hovers.clone();
+        button.add_hover_handler(move |_, hovered| {
+            if hovered {
+                counter.set(counter.get() + 1);
+            }
+        });
+
+        move_mouse(&mut world, 50.0, 50.0);
+        left_mouse(&mut world, ElementState::Pressed);
+        world.update();
+        assert!(button.is_hovered());
+        assert!(button.is_pressed());
+        assert_eq!(clicks.get(), 0);
+
+        world.input.next_frame_all();
+        left_mouse(&mut world, ElementState::Released);
+        world.update();
+        assert_eq!(clicks.get(), 1);
+        assert_eq!(hovers.get(), 1);
+
+        // releasing outside doesn't click
+        world.input.next_frame_all();
+        left_mouse(&mut world, ElementState::Pressed);
+        world.update();
+        world.input.next_frame_all();
+        move_mouse(&mut world, 150.0, 50.0);
+        left_mouse(&mut world, ElementState::Released);
+        world.update();
+        assert!(!button.is_hovered());
+        assert_eq!(clicks.get(), 1);
+    }
+
+    #[test]
+    fn topmost_button_wins() {
+        let mut world = world_with_viewport();
+        let below = button(&mut world, 0.0, 100.0, 1);
+        let above = button(&mut world, 50.0, 150.0, 2);
+        let below = below.get_component::<UiButton>().unwrap();
+        let above = above.get_component::<UiButton>().unwrap();
+
+        move_mouse(&mut world, 75.0, 50.0);
+        world.update();
+        assert!(!below.is_hovered());
+        assert!(above.is_hovered());
+
+        move_mouse(&mut world, 25.0, 50.0);
+        world.update();
+        assert!(below.is_hovered());
+        assert!(!above.is_hovered());
+    }
+
+    #[test]
+    fn buttons_with_the_same_draw_order_are_never_both_hovered() {
+        let mut world = world_with_viewport();
+        let first = button(&mut world, 0.0, 100.0, 1);
+        let second = button(&mut world, 0.0, 100.0, 1);
+        let first = first.get_component::<UiButton>().unwrap();
+        let second = second.get_component::<UiButton>().unwrap();
+
+        move_mouse(&mut world, 50.0, 50.0);
+        world.update();
+        assert!(first.is_hovered() != second.is_hovered());
+    }
+
+    #[test]
+    fn images_on_top_cover_buttons() {
+        let mut world = world_with_viewport();
+        let button = button(&mut world, 0.0, 100.0, 1);
+        image(&mut world, 50.0, 150.0, 2);
+        let button = button.get_component::<UiButton>().unwrap();
+
+        move_mouse(&mut world, 75.0, 50.0);
+        world.update();
+        assert!(!button.is_hovered());
+
+        move_mouse(&mut world, 25.0, 50.0);
+        world.update();
+        assert!(button.is_hovered());
+    }
+}
@@ -0,0 +1,394 @@
+use crate::World;
+use crate::components::panel::layout_children;
+use crate::components::ui_rect::{UiRectLayout, apply_layout};
+use crate::components::{Component, Image, NewComponent, Text2D, UiRect};
+use crate::core::GameObjectId;
+use nalgebra::Vector2;
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum UiDirection {
+    /// Left to right
+    Row,
+    /// Top to bottom
+    #[default]
+    Column,
+    /// On top of each other, in child order
+    Stack,
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum UiAlign {
+    #[default]
+    Start,
+    Center,
+    End,
+    /// Fills the available space. Along the direction, the free space is shared evenly.
+    Stretch,
+}
+
+/// Arranges the child objects in a row, a column or a stack, so HUDs and menus don't need
+/// manual pixel math.
+///
+/// The container itself is placed by a [`UiRect`] on its object, unless its parent object is a
+/// `UiContainer` as well. Each child gets a box of its preferred size:
+/// - the size of the child's [`UiRect`], which is resolved against the inner container size.
+///   Anchor, pivot and offset are ignored.
+/// - the content size of a nested `UiContainer`
+/// - otherwise the largest [`Text2D`] or [`Image`] of the child, see their `preferred_size`
+///
+/// The layout is updated every frame, so it follows the viewport size.
+#[derive(Debug)]
+pub struct UiContainer {
+    parent: GameObjectId,
+    direction: UiDirection,
+    padding: Vector2<f32>,
+    spacing: f32,
+    justify: UiAlign,
+    align: UiAlign,
+}
+
+impl UiContainer {
+    pub fn direction(&self) -> UiDirection {
+        self.direction
+    }
+
+    pub fn set_direction(&mut self, direction: UiDirection) {
+        self.direction = direction;
+    }
+
+    pub fn padding(&self) -> Vector2<f32> {
+        self.padding
+    }
+
+    /// Sets the space between the container edges and its children, horizontally and vertically
+    pub fn set_padding(&mut self, padding: Vector2<f32>) {
+        self.padding = padding;
+    }
+
+    pub fn spacing(&self) -> f32 {
+        self.spacing
+    }
+
+    /// Sets the gap between two children in a row or column
+    pub fn set_spacing(&mut self, spacing: f32) {
+        self.spacing = spacing;
+    }
+
+    pub fn justify(&self) -> UiAlign {
+        self.justify
+    }
+
+    /// Sets where the children go along the direction. A stack uses this horizontally.
+    pub fn set_justify(&mut self, justify: UiAlign) {
+        self.justify = justify;
+    }
+
+    pub fn align(&self) -> UiAlign {
+        self.align
+    }
+
+    /// Sets where the children go across the direction. A stack uses this vertically.
+    pub fn set_align(&mut self, align: UiAlign) {
+        self.align = align;
+    }
+
+    /// The size that fits all children with their preferred sizes, when `available` pixels
+    /// are available
+    pub fn content_size(&self, world: &World, available: Vector2<f32>) -> Vector2<f32> {
+        let inner = (available - self.padding * 2.0).sup(&Vector2::zeros());
+        let sizes: Vec<_> = self
+            .children()
+            .map(|child| preferred_size(child, inner, world))
+            .collect();
+
+        let max = sizes.iter().fold(Vector2::zeros(), |a, b| a.sup(b));
+        let gaps = sizes.len().saturating_sub(1) as f32 * self.spacing;
+        let content = match self.direction {
+            UiDirection::Row => Vector2::new(sizes.iter().map(|s| s.x).sum::<f32>() + gaps, max.y),
+            UiDirection::Column => {
+                Vector2::new(max.x, sizes.iter().map(|s| s.y).sum::<f32>() + gaps)
+            }
+            UiDirection::Stack => max,
+        };
+
+        content + self.padding * 2.0
+    }
+
+    /// Places the children inside `layout`, and continues its draw order
+    pub fn arrange(&self, world: &mut World, layout: &mut UiRectLayout) {
+        let origin = layout.top_left_px + self.padding;
+        let inner = (layout.size_px - self.padding * 2.0).sup(&Vector2::zeros());
+
+        let children: Vec<_> = self.children().collect();
+        let sizes: Vec<_> = children
+            .iter()
+            .map(|&child| preferred_size(child, inner, world))
+            .collect();
+        let boxes = self.child_boxes(&sizes, inner);
+
+        for (&child, (offset, size)) in children.iter().zip(boxes) {
+            pin_image_sizes(child, layout.screen);
+
+            let mut child_layout = UiRectLayout {
+                top_left_px: origin + offset,
+                size_px: size,
+                screen: layout.screen,
+                target: layout.target,
+                depth: layout.depth,
+                draw_order: layout.draw_order,
+            };
+            apply_layout(child, &mut child_layout, true);
+
+            if let Some(container) = child.get_component::<UiContainer>() {
+                container.arrange(world, &mut child_layout);
+            } else if !child.children().is_empty() {
+                layout_children(child.children(), &child_layout, world);
+            }
+            layout.draw_order = child_layout.draw_order;
+        }
+    }
+
+    /// Returns the offset from the inner top left and the size of every child
+    fn child_boxes(
+        &self,
+        sizes: &[Vector2<f32>],
+        inner: Vector2<f32>,
+    ) -> Vec<(Vector2<f32>, Vector2<f32>)> {
+        let (main, cross) = match self.direction {
+            UiDirection::Row | UiDirection::Stack => (0, 1),
+            UiDirection::Column => (1, 0),
+        };
+
+        if self.direction == UiDirection::Stack {
+            return sizes
+                .iter()
+                .map(|size| {
+                    let (x, width) = place(self.justify, size.x, inner.x);
+                    let (y, height) = place(self.align, size.y, inner.y);
+                    (Vector2::new(x, y), Vector2::new(width, height))
+                })
+                .collect();
+        }
+
+        let gaps = sizes.len().saturating_sub(1) as f32 * self.spacing;
+        let free = inner[main] - sizes.iter().map(|s| s[main]).sum::<f32>() - gaps;
+        let (mut cursor, grow) = match self.justify {
+            UiAlign::Start => (0.0, 0.0),
+            UiAlign::Center => (free * 0.5, 0.0),
+            UiAlign::End => (free, 0.0),
+            UiAlign::Stretch => (0.0, free.max(0.0) / sizes.len().max(1) as f32),
+        };
+
+        sizes
+            .iter()
+            .map(|size| {
+                let (cross_offset, cross_size) = place(self.align, size[cross], inner[cross]);
+
+                let mut offset = Vector2::zeros();
+                let mut child_size = Vector2::zeros();
+                offset[main] = cursor;
+                offset[cross] = cross_offset;
+                child_size[main] = size[main] + grow;
+                child_size[cross] = cross_size;
+
+                cursor += child_size[main] + self.spacing;
+                (offset, child_size)
+            })
+            .collect()
+    }
+
+    fn children(&self) -> impl Iterator<Item = GameObjectId> + '_ {
+        self.parent
+            .children()
+            .iter()
+            .copied()
+            .filter(|child| child.is_alive() && child.active_in_hierarchy())
+    }
+}
+
+/// Returns the offset and size of something of `size` aligned in `available`
+fn place(align: UiAlign, size: f32, available: f32) -> (f32, f32) {
+    match align {
+        UiAlign::Start => (0.0, size),
+        UiAlign::Center => ((available - size) * 0.5, size),
+        UiAlign::End => (available - size, size),
+        UiAlign::Stretch => (0.0, available),
+    }
+}
+
+fn preferred_size(obj: GameObjectId, available: Vector2<f32>, world: &World) -> Vector2<f32> {
+    if let Some(rect) = obj.get_component::<UiRect>() {
+        return rect.size().resolve(available);
+    }
+    if let Some(container) = obj.get_component::<UiContainer>() {
+        return container.content_size(world, available);
+    }
+
+    let screen = available.sup(&Vector2::new(1.0, 1.0));
+    obj.iter_dyn_components()
+        .filter_map(|component| {
+            if let Some(image) = component.as_a::<Image>() {
+                Some(image.preferred_size(screen))
+            } else {
+                component
+                    .as_a::<Text2D>()
+                    .map(|text| text.preferred_size(world))
+            }
+        })
+        .fold(Vector2::zeros(), |a, b| a.sup(&b))
+}
+
+/// Layouts overwrite the scaling mode of images, so their initial size is kept as their
+/// preferred size before that happens
+fn pin_image_sizes(obj: GameObjectId, screen: Vector2<f32>) {
+    for component in obj.iter_dyn_components() {
+        if let Some(mut image) = component.as_a::<Image>()
+            && !image.has_preferred_size()
+        {
+            let size = image.preferred_size(screen);
+            image.set_preferred_size(Some(size));
+        }
+    }
+}
+
+impl NewComponent for UiContainer {
+    fn new(parent: GameObjectId) -> Self {
+        UiContainer {
+            parent,
+            direction: UiDirection::default(),
+            padding: Vector2::new(5.0, 5.0),
+            spacing: 5.0,
+            justify: UiAlign::default(),
+            align: UiAlign::default(),
+        }
+    }
+}
+
+impl Component for UiContainer {
+    fn update(&mut self, world: &mut World) {
+        let arranged_by_parent = self
+            .parent
+            .parent()
+            .is_some_and(|parent| parent.get_component::<UiContainer>().is_some());
+        if arranged_by_parent {
+            return;
+        }
+
+        let Some(mut rect) = self.parent.get_component::<UiRect>() else {
+            return;
+        };
+        let Some(mut layout) = rect.layout(world) else {
+            return;
+        };
+
+        rect.apply_to_components(world, &mut layout);
+        self.arrange(world, &mut layout);
+    }
+
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        let mut copy = target.add_component::<UiContainer>();
+        copy.direction = self.direction;
+        copy.padding = self.padding;
+        copy.spacing = self.spacing;
+        copy.justify = self.justify;
+        copy.align = self.align;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::ui_rect::UiSize;
+    use crate::strobe::ImageScalingMode;
+    use crate::windowing::RenderTargetId;
+    use winit::dpi::PhysicalSize;
+
+    fn world_with_viewport() -> Box<World> {
+        let (mut world, ..) = World::fresh();
+        world.set_viewport_size(RenderTargetId::PRIMARY, PhysicalSize::new(800, 600));
+        world
+    }
+
+    fn sized_child(
+        world: &mut World,
+        parent: &mut GameObjectId,
+        width: f32,
+        height: f32,
+    ) -> GameObjectId {
+        let mut child = world.new_object("child");
+        let mut rect = child.add_component::<UiRect>();
+        rect.set_size(UiSize::Pixels { width, height });
+        child.add_component::<Image>();
+        parent.add_child(child);
+        child
+    }
+
+    fn image_rect(obj: GameObjectId) -> (f32, f32, f32, f32) {
+        match obj.get_component::<Image>().unwrap().scaling_mode() {
+            ImageScalingMode::Absolute {
+                left,
+                right,
+                top,
+                bottom,
+            } => (left, right, top, bottom),
+            other => panic!("expected absolute scaling, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn row_places_children_with_spacing_and_alignment() {
+        let mut world = world_with_viewport();
+        let mut root = world.new_object("row");
+        world.add_child(root);
+
+        let mut rect = root.add_component::<UiRect>();
+        rect.set_size(UiSize::Pixels {
+            width: 300.0,
+            height: 100.0,
+        });
+        let mut container = root.add_component::<UiContainer>();
+        container.set_direction(UiDirection::Row);
+        container.set_padding(Vector2::new(10.0, 10.0));
+        container.set_spacing(20.0);
+        container.set_align(UiAlign::Center);
+
+        let a = sized_child(&mut world, &mut root, 50.0, 40.0);
+        let b = sized_child(&mut world, &mut root, 60.0, 80.0);
+
+        container.update(&mut world);
+
+        // x from 10, y centered in the 80px inner height
+        assert_eq!(image_rect(a), (10.0, 60.0, 570.0, 530.0));
+        assert_eq!(image_rect(b), (80.0, 140.0, 590.0, 510.0));
+        assert_eq!(
+            container.content_size(&world, Vector2::new(300.0, 100.0)),
+            Vector2::new(150.0, 100.0)
+        );
+    }
+
+    #[test]
+    fn column_stretch_shares_free_space() {
+        let mut world = world_with_viewport();
+        let mut root = world.new_object("column");
+        world.add_child(root);
+
+        let mut rect = root.add_component::<UiRect>();
+        rect.set_size(UiSize::Pixels {
+            width: 100.0,
+            height: 200.0,
+        });
+        let mut container = root.add_component::<UiContainer>();
+        container.set_padding(Vector2::zeros());
+        container.set_spacing(0.0);
+        container.set_justify(UiAlign::Stretch);
+        container.set_align(UiAlign::Stretch);
+
+        let a = sized_child(&mut world, &mut root, 10.0, 50.0);
+        let b = sized_child(&mut world, &mut root, 10.0, 50.0);
+
+        container.update(&mut world);
+
+        assert_eq!(image_rect(a), (0.0, 100.0, 600.0, 500.0));
+        assert_eq!(image_rect(b), (0.0, 100.0, 500.0, 400.0));
+    }
+}
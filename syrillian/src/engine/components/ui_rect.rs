@@ -1,7 +1,7 @@
 use crate::World;
 use crate::components::{Component, Image, NewComponent, Text2D};
 use crate::core::GameObjectId;
-use crate::rendering::strobe::ImageScalingMode;
+use crate::rendering::strobe::TextAlignment;
 use crate::windowing::RenderTargetId;
 use nalgebra::Vector2;
 
@@ -120,41 +120,51 @@ impl UiRect {
     }
 
     pub fn apply_to_components(&mut self, _world: &mut World, layout: &mut UiRectLayout) {
-        for component in self.parent.iter_dyn_components() {
-            if let Some(mut image) = component.as_a::<Image>() {
-                let screen_h = layout.screen.y.max(1.0);
-
-                let left = layout.top_left_px.x.max(0.0).floor();
-                let right = (layout.top_left_px.x + layout.size_px.x).max(0.0).ceil();
-
-                let bottom = (screen_h - (layout.top_left_px.y + layout.size_px.y))
-                    .max(0.0)
-                    .floor();
-                let top = (screen_h - layout.top_left_px.y).max(0.0).ceil();
-
-                if top > bottom && right > left {
-                    image.set_scaling_mode(ImageScalingMode::Absolute {
-                        left,
-                        right,
-                        top,
-                        bottom,
-                    });
-                }
-
-                image.set_draw_order(layout.draw_order);
-
-                let translation =
-                    nalgebra::Translation3::new(0.0, 0.0, layout.depth).to_homogeneous();
-                image.set_translation(translation);
-
-                layout.draw_order += 1;
-            } else if let Some(mut text) = component.as_a::<Text2D>() {
-                text.set_position_vec(layout.top_left_px);
-                text.set_draw_order(layout.draw_order);
-                text.set_render_target(layout.target);
-
-                layout.draw_order += 1;
+        apply_layout(self.parent, layout, false);
+    }
+}
+
+/// Places the images and 2D texts of `obj` in the layout rectangle, and gives each of them the
+/// next draw order. With `align_text`, centered and right aligned texts are moved to the center
+/// or right edge of the rectangle instead of its top left.
+pub(crate) fn apply_layout(obj: GameObjectId, layout: &mut UiRectLayout, align_text: bool) {
+    for component in obj.iter_dyn_components() {
+        if let Some(mut image) = component.as_a::<Image>() {
+            let screen_h = layout.screen.y.max(1.0);
+
+            let left = layout.top_left_px.x.max(0.0).floor();
+            let right = (layout.top_left_px.x + layout.size_px.x).max(0.0).ceil();
+
+            let bottom = (screen_h - (layout.top_left_px.y + layout.size_px.y))
+                .max(0.0)
+                .floor();
+            let top = (screen_h - layout.top_left_px.y).max(0.0).ceil();
+
+            if top > bottom && right > left {
+                let scaling = image.scaling_mode().with_rect(left, right, top, bottom);
+                image.set_scaling_mode(scaling);
             }
+
+            image.set_draw_order(layout.draw_order);
+
+            let translation = nalgebra::Translation3::new(0.0, 0.0, layout.depth).to_homogeneous();
+            image.set_translation(translation);
+
+            layout.draw_order += 1;
+        } else if let Some(mut text) = component.as_a::<Text2D>() {
+            // aligned text is laid out around its position
+            let align_x = match text.alignment() {
+                _ if !align_text => 0.0,
+                TextAlignment::Left => 0.0,
+                TextAlignment::Center => 0.5,
+                TextAlignment::Right => 1.0,
+            };
+            let position = layout.top_left_px + Vector2::new(align_x * layout.size_px.x, 0.0);
+            text.set_position_vec(position);
+            text.set_draw_order(layout.draw_order);
+            text.set_render_target(layout.target);
+
+            layout.draw_order += 1;
         }
     }
 }
@@ -176,13 +186,24 @@ impl NewComponent for UiRect {
     }
 }
 
-impl Component for UiRect {}
+impl Component for UiRect {
+    fn duplicate(&self, mut target: GameObjectId, _world: &mut World) {
+        let mut copy = target.add_component::<UiRect>();
+        copy.anchor = self.anchor;
+        copy.pivot = self.pivot;
+        copy.offset = self.offset;
+        copy.size = self.size;
+        copy.depth = self.depth;
+        copy.render_target = self.render_target;
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rendering::strobe::{ImageScalingMode, SliceFill};
     use crate::windowing::RenderTargetId;
-    use nalgebra::{Translation3, Vector2};
+    use nalgebra::{Translation3, Vector2, Vector4};
     use slotmap::Key;
     use winit::dpi::PhysicalSize;
 
@@ -294,4 +315,65 @@ mod tests {
         assert_eq!(image.draw_order(), 3);
         assert!((image.translation()[(2, 3)] - 0.5).abs() < 1e-6);
     }
+
+    #[test]
+    fn apply_to_components_places_aligned_text_at_the_top_left() {
+        let mut world = world_with_viewport();
+        let mut obj = world.new_object("ui");
+        world.add_child(obj);
+
+        let mut rect = obj.add_component::<UiRect>();
+        rect.set_offset(Vector2::new(12.0, 18.0));
+        let mut text = obj.add_component::<Text2D>();
+        text.set_alignment(TextAlignment::Center);
+
+        let mut layout = rect.layout(&world).expect("viewport configured");
+        rect.apply_to_components(&mut world, &mut layout);
+        assert_eq!(text.position(), Vector2::new(12.0, 18.0));
+    }
+
+    #[test]
+    fn apply_to_components_keeps_nine_slice_borders() {
+        let mut world = world_with_viewport();
+        let mut obj = world.new_object("ui");
+        world.add_child(obj);
+
+        let mut rect = obj.add_component::<UiRect>();
+        rect.set_size(UiSize::Pixels {
+            width: 200.0,
+            height: 50.0,
+        });
+
+        let border = Vector4::new(8.0, 8.0, 4.0, 4.0);
+        let mut image = obj.add_component::<Image>();
+        image.set_scaling_mode(ImageScalingMode::NineSlice {
+            left: 0.0,
+            right: 32.0,
+            top: 32.0,
+            bottom: 0.0,
+            border,
+            center: SliceFill::Tile,
+            edges: SliceFill::Stretch,
+        });
+
+        let mut layout = rect.layout(&world).expect("viewport configured");
+        rect.apply_to_components(&mut world, &mut layout);
+
+        assert_eq!(
+            image.scaling_mode(),
+            ImageScalingMode::NineSlice {
+                left: 0.0,
+                right: 200.0,
+                top: 600.0,
+                bottom: 550.0,
+                border,
+                center: SliceFill::Tile,
+                edges: SliceFill::Stretch,
+            }
+        );
+        assert_eq!(
+            image.scaling_mode().size_px(layout.screen),
+            Vector2::new(200.0, 50.0)
+        );
+    }
 }
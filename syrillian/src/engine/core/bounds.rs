@@ -48,3 +48,85 @@ impl BoundingSphere {
         }
     }
 }
+
+#[derive(Debug, Clone, Copy)]
+pub struct FrustumPlane {
+    pub normal: Vector3<f32>,
+    pub d: f32,
+}
+
+impl FrustumPlane {
+    /// Signed distance from the plane to the sphere center, positive on the inner side
+    pub fn distance_to(&self, sphere: &BoundingSphere) -> f32 {
+        self.normal.dot(&sphere.center) + self.d
+    }
+}
+
+/// The six planes of a camera's view volume, for culling bounding spheres
+#[derive(Debug, Clone, Copy)]
+pub struct Frustum {
+    planes: [FrustumPlane; 6],
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FrustumSide {
+    Left,
+    Right,
+    Bottom,
+    Top,
+    Near,
+    Far,
+}
+
+impl Frustum {
+    /// Extracts the planes from a projection * view matrix
+    pub fn from_matrix(m: &Matrix4<f32>) -> Self {
+        let row0 = m.row(0).transpose();
+        let row1 = m.row(1).transpose();
+        let row2 = m.row(2).transpose();
+        let row3 = m.row(3).transpose();
+
+        let plane_from = |v: Vector4<f32>| {
+            let normal = Vector3::new(v.x, v.y, v.z);
+            let len = normal.norm();
+            if len > 0.0 {
+                FrustumPlane {
+                    normal: normal / len,
+                    d: v.w / len,
+                }
+            } else {
+                FrustumPlane { normal, d: v.w }
+            }
+        };
+
+        let planes = [
+            plane_from(row3 + row0), // left
+            plane_from(row3 - row0), // right
+            plane_from(row3 + row1), // bottom
+            plane_from(row3 - row1), // top
+            plane_from(row3 + row2), // near
+            plane_from(row3 - row2), // far
+        ];
+
+        Frustum { planes }
+    }
+
+    pub fn side(&self, side: FrustumSide) -> &FrustumPlane {
+        match side {
+            FrustumSide::Left => &self.planes[0],
+            FrustumSide::Right => &self.planes[1],
+            FrustumSide::Bottom => &self.planes[2],
+            FrustumSide::Top => &self.planes[3],
+            FrustumSide::Near => &self.planes[4],
+            FrustumSide::Far => &self.planes[5],
+        }
+    }
+
+    /// Returns true if any part of the sphere is inside the frustum. Spheres near the corners
+    /// can be reported as inside even though they're not.
+    pub fn intersects_sphere(&self, sphere: &BoundingSphere) -> bool {
+        self.planes
+            .iter()
+            .all(|p| p.distance_to(sphere) >= -sphere.radius)
+    }
+}
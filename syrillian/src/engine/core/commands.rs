@@ -0,0 +1,98 @@
+use crate::World;
+use crate::components::{CRef, NewComponent};
+use crate::core::GameObjectId;
+use crate::prefabs::Prefab;
+use tracing::warn;
+
+type Command = Box<dyn FnOnce(&mut World)>;
+
+/// Structural changes queued during an update, see [`World::commands`].
+///
+/// Creating, deleting or reparenting objects and adding components right away changes the
+/// world while it's iterating its components. Queued commands run in order at safe points
+/// instead: after `late_update`, after every fixed update step and after `post_update`.
+///
+/// Commands on objects that were deleted in the meantime are skipped. Commands deferred by
+/// [parallel updates](crate::components::ParallelContext::defer) end up in this queue as well,
+/// after the ones queued during `update`.
+#[derive(Default)]
+pub struct WorldCommands {
+    queue: Vec<Command>,
+}
+
+impl WorldCommands {
+    /// Spawns `prefab` at the world root
+    pub fn spawn<P: Prefab + 'static>(&mut self, prefab: P) {
+        self.push(move |world| {
+            world.spawn(&prefab);
+        });
+    }
+
+    /// Spawns `prefab` at the world root, then calls `then` with its root object
+    pub fn spawn_then<P, F>(&mut self, prefab: P, then: F)
+    where
+        P: Prefab + 'static,
+        F: FnOnce(&mut World, GameObjectId) + 'static,
+    {
+        self.push(move |world| {
+            let obj = world.spawn(&prefab);
+            then(world, obj);
+        });
+    }
+
+    pub fn delete(&mut self, obj: GameObjectId) {
+        self.push(move |world| world.delete_object(obj));
+    }
+
+    pub fn add_component<C: NewComponent + 'static>(&mut self, obj: GameObjectId) {
+        self.add_component_then::<C, _>(obj, |_| {});
+    }
+
+    /// Adds a component and calls `then` with it, e.g. to configure it
+    pub fn add_component_then<C, F>(&mut self, mut obj: GameObjectId, then: F)
+    where
+        C: NewComponent + 'static,
+        F: FnOnce(CRef<C>) + 'static,
+    {
+        self.push(move |_| {
+            if obj.exists() {
+                then(obj.add_component::<C>());
+            }
+        });
+    }
+
+    /// Moves `child` under `parent`, or to the world root if `parent` is `None`. See
+    /// [`GameObject::set_parent`](crate::core::GameObject::set_parent).
+    pub fn set_parent(
+        &mut self,
+        mut child: GameObjectId,
+        parent: Option<GameObjectId>,
+        keep_world_transform: bool,
+    ) {
+        self.push(move |_| {
+            if !child.exists() {
+                return;
+            }
+            if let Err(e) = child.set_parent(parent, keep_world_transform) {
+                warn!("Deferred reparenting of {:?} failed: {e}", child.name);
+            }
+        });
+    }
+
+    /// Queues any other change to the world
+    pub fn push(&mut self, command: impl FnOnce(&mut World) + 'static) {
+        self.queue.push(Box::new(command));
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    pub(crate) fn take(&mut self) -> Vec<Command> {
+        std::mem::take(&mut self.queue)
+    }
+}
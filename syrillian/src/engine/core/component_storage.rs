@@ -80,6 +80,11 @@ pub struct ComponentStorage {
     len: usize,
     pub(crate) fresh: Vec<TypedComponentId>,
     pub(crate) removed: Vec<TypedComponentId>,
+    /// Components sorted by their update order, then by insertion. Removed components are only
+    /// dropped from it in [`ordered_ids_into`](Self::ordered_ids_into).
+    ordered: Vec<(i32, TypedComponentId)>,
+    /// Number of removed components that are still in `ordered`
+    stale_ordered: usize,
 }
 
 impl ComponentStorage {
@@ -174,7 +179,24 @@ impl ComponentStorage {
             .flat_map(|store| store.iter_comps_mut())
     }
 
+    /// Fills `out` with all component ids in the order their update functions should run
+    pub(crate) fn ordered_ids_into(&mut self, out: &mut Vec<TypedComponentId>) {
+        if self.stale_ordered > 0 {
+            let inner = &self.inner;
+            self.ordered.retain(|(_, tid)| {
+                inner
+                    .get(&tid.0)
+                    .is_some_and(|map| map.get(tid.1).is_some())
+            });
+            self.stale_ordered = 0;
+        }
+
+        out.clear();
+        out.extend(self.ordered.iter().map(|(_, tid)| *tid));
+    }
+
     pub(crate) fn add<C: Component>(&mut self, component: C, parent: GameObjectId) -> CRef<C> {
+        let order = component.update_order();
         let comp = Rc::new(component);
 
         let store = self._get_or_insert_mut();
@@ -186,6 +208,9 @@ impl ComponentStorage {
         let tid = TypedComponentId::from_typed::<C>(id);
         let cref = store.get(id).expect("Element was just inserted").clone();
 
+        let pos = self.ordered.partition_point(|(o, _)| *o <= order);
+        self.ordered.insert(pos, (order, tid));
+
         self.len += 1;
         self.fresh.push(tid);
         cref
@@ -207,6 +232,10 @@ impl ComponentStorage {
             "Component wasn't found despite still being owned by a game object."
         );
         self.removed.push(ctid);
+        // removing it from the ordered list right away would shift the rest, so that's batched
+        if comp.is_some() {
+            self.stale_ordered += 1;
+        }
 
         debug_assert_ne!(self.len, 0);
 
@@ -0,0 +1,142 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+/// Typed events that components can send to each other without holding references.
+///
+/// Events emitted during a frame are delivered at the start of the next one. That way every
+/// component sees them during its update, no matter in which order components run. They stay
+/// readable for [`lifetime`](Self::lifetime) frames, one by default.
+///
+/// Each event type has its own queue, which keeps its allocation between frames.
+pub struct EventBus {
+    queues: HashMap<TypeId, Box<dyn AnyEventQueue>>,
+    frame: u64,
+    lifetime: u64,
+}
+
+struct EventQueue<E> {
+    /// Events with the frame they were emitted in, oldest first
+    events: Vec<(u64, E)>,
+    handlers: Vec<Box<dyn FnMut(&E)>>,
+}
+
+trait AnyEventQueue: Any {
+    fn advance(&mut self, frame: u64, lifetime: u64);
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<E: 'static> AnyEventQueue for EventQueue<E> {
+    fn advance(&mut self, frame: u64, lifetime: u64) {
+        self.events
+            .retain(|(emitted, _)| frame - emitted <= lifetime);
+
+        let delivered = self
+            .events
+            .iter()
+            .filter(|(emitted, _)| emitted + 1 == frame);
+        for (_, event) in delivered {
+            for handler in &mut self.handlers {
+                handler(event);
+            }
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl<E> EventQueue<E> {
+    /// Number of events that are already delivered in `frame`
+    fn visible(&self, frame: u64) -> usize {
+        self.events.partition_point(|(emitted, _)| *emitted < frame)
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        EventBus {
+            queues: HashMap::new(),
+            frame: 0,
+            lifetime: 1,
+        }
+    }
+}
+
+impl EventBus {
+    /// Queues an event. It can be read starting with the next frame.
+    pub fn emit<E: 'static>(&mut self, event: E) {
+        let frame = self.frame;
+        self.queue_mut::<E>().events.push((frame, event));
+    }
+
+    /// Returns the delivered events of a type without consuming them, so every reader sees them
+    pub fn read<E: 'static>(&self) -> impl Iterator<Item = &E> {
+        let events = self
+            .queue::<E>()
+            .map(|queue| &queue.events[..queue.visible(self.frame)])
+            .unwrap_or_default();
+        events.iter().map(|(_, event)| event)
+    }
+
+    /// Removes the delivered events of a type and returns them. Readers after this won't see
+    /// them anymore.
+    pub fn drain<E: 'static>(&mut self) -> impl Iterator<Item = E> {
+        let frame = self.frame;
+        let queue = self.queue_mut::<E>();
+        let visible = queue.visible(frame);
+        queue.events.drain(..visible).map(|(_, event)| event)
+    }
+
+    /// Calls `handler` for every event of a type when it's delivered
+    pub fn subscribe<E: 'static>(&mut self, handler: impl FnMut(&E) + 'static) {
+        self.queue_mut::<E>().handlers.push(Box::new(handler));
+    }
+
+    /// Returns how many frames events stay readable after they're delivered
+    pub fn lifetime(&self) -> u64 {
+        self.lifetime
+    }
+
+    /// Sets how many frames events stay readable after they're delivered, at least one.
+    ///
+    /// With more than one frame, [`read`](Self::read) returns the same event in several frames.
+    pub fn set_lifetime(&mut self, frames: u64) {
+        self.lifetime = frames.max(1);
+    }
+
+    /// Starts a new frame, delivering the events emitted in the last one and dropping expired
+    /// ones. Called by [`World::update`](crate::World::update).
+    pub fn advance_frame(&mut self) {
+        self.frame += 1;
+        for queue in self.queues.values_mut() {
+            queue.advance(self.frame, self.lifetime);
+        }
+    }
+
+    fn queue<E: 'static>(&self) -> Option<&EventQueue<E>> {
+        self.queues
+            .get(&TypeId::of::<E>())?
+            .as_any()
+            .downcast_ref::<EventQueue<E>>()
+    }
+
+    fn queue_mut<E: 'static>(&mut self) -> &mut EventQueue<E> {
+        self.queues
+            .entry(TypeId::of::<E>())
+            .or_insert_with(|| {
+                Box::new(EventQueue::<E> {
+                    events: Vec::new(),
+                    handlers: Vec::new(),
+                })
+            })
+            .as_any_mut()
+            .downcast_mut::<EventQueue<E>>()
+            .expect("Event queues are keyed by their type")
+    }
+}
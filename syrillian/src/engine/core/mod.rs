@@ -5,15 +5,25 @@
 
 pub mod bone;
 pub mod bounds;
+pub mod commands;
 pub mod component_storage;
+pub mod events;
 pub mod object;
 pub mod object_extensions;
+pub mod object_tree;
+pub mod snapshot;
+pub mod spatial;
 pub mod transform;
 pub mod vertex;
 
 pub use bone::*;
 pub use bounds::*;
+pub use commands::*;
+pub use events::*;
 pub use object::*;
 pub use object_extensions::*;
+pub use object_tree::*;
+pub use snapshot::*;
+pub use spatial::*;
 pub use transform::*;
 pub use vertex::*;
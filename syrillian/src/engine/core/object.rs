@@ -1,10 +1,11 @@
-use crate::components::{CRef, Component, NewComponent, TypedComponentId};
-use crate::core::Transform;
+use crate::components::{CRef, Component, MeshRenderer, NewComponent, TypedComponentId};
+use crate::core::{BoundingSphere, Transform};
 use crate::ensure_aligned;
 use crate::world::World;
 use itertools::Itertools;
-use nalgebra::{Matrix4, Translation3, Vector3};
+use nalgebra::{Affine3, Matrix4, Translation3, Vector3};
 use slotmap::{Key, KeyData, new_key_type};
+use snafu::{Snafu, ensure};
 use std::borrow::Borrow;
 use std::cell::Cell;
 use std::collections::HashMap;
@@ -13,6 +14,25 @@ use std::hash::{Hash, Hasher};
 use std::ops::{Deref, DerefMut};
 use std::ptr::null_mut;
 use syrillian_utils::debug_panic;
+use uuid::Uuid;
+
+#[derive(Debug, Snafu)]
+#[snafu(context(suffix(Err)))]
+pub enum ReparentError {
+    #[snafu(display("The object or its new parent was already deleted"))]
+    DeletedObject,
+    #[snafu(display("An object can't become a child of itself or of one of its children"))]
+    CyclicParent,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(context(suffix(Err)))]
+pub enum UuidError {
+    #[snafu(display("The object was already deleted"))]
+    DeletedObject,
+    #[snafu(display("The uuid {uuid} already belongs to {other:?}"))]
+    UuidTaken { uuid: Uuid, other: GameObjectId },
+}
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
 pub struct EventType(u32);
@@ -136,6 +156,21 @@ impl GameObjectId {
         GameObjectWeak(*self)
     }
 
+    /// Returns the bounding sphere of this object's [`MeshRenderer`] mesh in world space.
+    ///
+    /// Returns `None` if the object doesn't exist, has no mesh renderer or its mesh is missing.
+    pub fn world_bounds(&self) -> Option<BoundingSphere> {
+        if !self.exists() {
+            return None;
+        }
+
+        let renderer = self.get_component::<MeshRenderer>()?;
+        let mesh = self.world().assets.meshes.try_get(renderer.mesh())?;
+        let world_matrix = self.transform.global_transform_matrix().to_homogeneous();
+
+        Some(mesh.bounding_sphere().transformed(&world_matrix))
+    }
+
     pub(crate) fn as_ffi(&self) -> u64 {
         self.0.as_ffi()
     }
@@ -296,6 +331,10 @@ pub struct GameObject {
     pub name: String,
     /// Whether the object is still alive inside the world.
     pub(crate) alive: Cell<bool>,
+    /// Whether the object itself is enabled, regardless of its parents.
+    pub(crate) active: bool,
+    /// Whether the object and all of its parents are enabled.
+    pub(crate) active_in_hierarchy: bool,
     /// Game objects that are direct children of this object.
     pub(crate) children: Vec<GameObjectId>,
     /// Parent game object.
@@ -313,6 +352,8 @@ pub struct GameObject {
     pub(crate) event_mask: Cell<EventType>,
     /// Unique hash used for picking and lookup.
     pub(crate) hash: ObjectHash,
+    /// Persistent id that stays the same across runs, if one was assigned
+    pub(crate) uuid: Option<Uuid>,
 }
 
 impl GameObject {
@@ -325,6 +366,36 @@ impl GameObject {
         self.hash
     }
 
+    /// Returns the persistent id of this object, if it has one
+    pub fn uuid(&self) -> Option<Uuid> {
+        self.uuid
+    }
+
+    /// Assigns a persistent id, which unlike the [`GameObjectId`] stays the same across runs.
+    /// Save systems and networking can store it to find the object again with
+    /// [`World::find_by_uuid`]. Every uuid can only belong to one object, and duplicates don't
+    /// inherit it.
+    ///
+    /// Loaded scene nodes get the uuid stored in their
+    /// [`UUID_PROPERTY`](crate::assets::scene_loader::UUID_PROPERTY) extra.
+    pub fn set_uuid(&mut self, uuid: Uuid) -> Result<(), UuidError> {
+        self.world().set_object_uuid(self.id, Some(uuid))
+    }
+
+    /// Returns the persistent id of this object, assigning a random one first if it has none
+    pub fn ensure_uuid(&mut self) -> Result<Uuid, UuidError> {
+        if let Some(uuid) = self.uuid {
+            return Ok(uuid);
+        }
+        let uuid = Uuid::new_v4();
+        self.set_uuid(uuid)?;
+        Ok(uuid)
+    }
+
+    pub fn clear_uuid(&mut self) {
+        let _ = self.world().set_object_uuid(self.id, None);
+    }
+
     pub fn event_mask(&self) -> EventType {
         self.event_mask.get()
     }
@@ -351,6 +422,51 @@ impl GameObject {
         self.alive.set(false);
     }
 
+    /// Enables or disables this object and, through it, all of its children.
+    ///
+    /// Inactive objects keep their components and state, but their components don't update,
+    /// aren't rendered and their physics bodies and colliders are disabled. Re-enabling the
+    /// object restores all of it, which is much cheaper than deleting and respawning it.
+    pub fn set_active(&mut self, active: bool) {
+        if self.active == active {
+            return;
+        }
+        self.active = active;
+
+        let parent_active = self.parent.is_none_or(|p| p.active_in_hierarchy);
+        self.propagate_active(parent_active);
+    }
+
+    /// Returns whether this object itself is enabled. It may still be inactive through a
+    /// disabled parent, see [`active_in_hierarchy`](Self::active_in_hierarchy).
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Returns whether this object and all of its parents are enabled
+    pub fn active_in_hierarchy(&self) -> bool {
+        self.active_in_hierarchy
+    }
+
+    pub(crate) fn propagate_active(&mut self, parent_active: bool) {
+        let active = parent_active && self.active;
+        if self.active_in_hierarchy == active {
+            return;
+        }
+        self.active_in_hierarchy = active;
+
+        let world = self.world();
+        for comp in self.components.iter().filter(|c| c.is_enabled()) {
+            world.set_component_active(comp.clone(), active);
+        }
+
+        for mut child in self.children.clone() {
+            if child.exists() {
+                child.propagate_active(active);
+            }
+        }
+    }
+
     /// Returns the parent as a strong reference if it is still alive.
     pub fn parent_ref(&self) -> Option<GameObjectRef> {
         self.parent.and_then(|p| p.upgrade())
@@ -397,6 +513,46 @@ impl GameObject {
 
         self.children.push(child);
         child.parent = Some(self.id);
+        child.propagate_active(self.active_in_hierarchy);
+        child.transform.set_dirty();
+    }
+
+    /// Moves this object under `new_parent`, or to the world root if it's `None`.
+    ///
+    /// With `keep_world_transform`, the local transform is recomputed so the object stays where
+    /// it is in the world, e.g. when picking up an item into a hand. Otherwise, the local
+    /// transform is kept and the object moves along with its new parent.
+    pub fn set_parent(
+        &mut self,
+        new_parent: Option<GameObjectId>,
+        keep_world_transform: bool,
+    ) -> Result<(), ReparentError> {
+        ensure!(self.is_alive(), DeletedObjectErr);
+        if let Some(parent) = new_parent {
+            ensure!(parent.exists(), DeletedObjectErr);
+            ensure!(!parent.parents().contains(&self.id), CyclicParentErr);
+        }
+
+        let world_matrix = self.transform.global_transform_matrix();
+
+        match new_parent {
+            Some(mut parent) => parent.add_child(self.id),
+            None => {
+                self.unlink();
+                self.world().add_child(self.id);
+            }
+        }
+
+        if keep_world_transform {
+            let parent_matrix = new_parent
+                .map(|p| p.transform.global_transform_matrix())
+                .unwrap_or_else(Affine3::identity);
+            let local = parent_matrix.inverse() * world_matrix;
+            self.transform.set_local_transform_matrix(local.matrix());
+        }
+        self.transform.set_dirty();
+
+        Ok(())
     }
 
     /// Adds a new [`Component`] of type `C` to this game object, initializing the component within the world,
@@ -424,6 +580,9 @@ impl GameObject {
 
         let new_comp2 = new_comp.clone();
         self.components.push(new_comp.as_dyn());
+        if !self.active_in_hierarchy {
+            world.set_component_active(new_comp.as_dyn(), false);
+        }
         new_comp2
     }
 
@@ -508,6 +667,17 @@ impl GameObject {
         self.components.iter().filter_map(|c| c.clone().as_a())
     }
 
+    /// Returns the number of components attached to this game object.
+    pub fn component_count(&self) -> usize {
+        self.components.len()
+    }
+
+    /// Returns the [type names](Component::type_name) of all components attached to this game
+    /// object, in the order they were added.
+    pub fn component_type_names(&self) -> Vec<&'static str> {
+        self.components.iter().map(|c| c.type_name()).collect()
+    }
+
     /// Retrieves the first found [`Component`] of type `C` attached to a child of this game object.
     pub fn get_child_component<C>(&mut self) -> Option<CRef<C>>
     where
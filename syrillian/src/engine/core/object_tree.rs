@@ -0,0 +1,93 @@
+use crate::core::GameObjectId;
+use nalgebra::{UnitQuaternion, Vector3};
+use std::fmt::{Display, Formatter};
+
+/// The hierarchy of a world as plain data, taken with
+/// [`World::object_tree`](crate::World::object_tree), e.g. for an inspector or debug output.
+///
+/// The root node stands for the world itself and has no id. Its children are the root objects.
+/// Display formats the tree with one line per object, indented by depth.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectTreeNode {
+    /// The object, or `None` for the root node of the world
+    pub id: Option<GameObjectId>,
+    pub name: String,
+    /// Whether the object itself is enabled, regardless of its parents
+    pub active: bool,
+    /// Type names of the components, in the order they were added
+    pub components: Vec<&'static str>,
+    pub transform: TransformSummary,
+    pub children: Vec<ObjectTreeNode>,
+}
+
+/// The transform of an [`ObjectTreeNode`] when the tree was taken
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TransformSummary {
+    pub local_position: Vector3<f32>,
+    pub local_rotation: UnitQuaternion<f32>,
+    pub local_scale: Vector3<f32>,
+    pub position: Vector3<f32>,
+}
+
+impl Default for TransformSummary {
+    fn default() -> Self {
+        TransformSummary {
+            local_position: Vector3::zeros(),
+            local_rotation: UnitQuaternion::identity(),
+            local_scale: Vector3::new(1.0, 1.0, 1.0),
+            position: Vector3::zeros(),
+        }
+    }
+}
+
+impl ObjectTreeNode {
+    /// Returns the number of objects below this node, not counting the node itself
+    pub fn descendant_count(&self) -> usize {
+        self.children
+            .iter()
+            .map(|child| 1 + child.descendant_count())
+            .sum()
+    }
+
+    /// Returns the first node in pre-order with the given name, including this one
+    pub fn find(&self, name: &str) -> Option<&ObjectTreeNode> {
+        if self.name == name {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(name))
+    }
+
+    fn fmt_depth(&self, f: &mut Formatter<'_>, depth: usize) -> std::fmt::Result {
+        let indent = "  ".repeat(depth);
+        let p = self.transform.position;
+        write!(
+            f,
+            "{indent}- {} at ({:.2}, {:.2}, {:.2})",
+            self.name, p.x, p.y, p.z
+        )?;
+        if !self.active {
+            write!(f, " (inactive)")?;
+        }
+        writeln!(f)?;
+
+        if !self.components.is_empty() {
+            writeln!(f, "{indent}  -> Components: {}", self.components.join(", "))?;
+        }
+        for child in &self.children {
+            child.fmt_depth(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for ObjectTreeNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.id.is_some() {
+            return self.fmt_depth(f, 0);
+        }
+        for child in &self.children {
+            child.fmt_depth(f, 0)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+use crate::core::GameObjectId;
+use nalgebra::{Isometry3, UnitQuaternion, Vector3};
+use rapier3d::prelude::RigidBodyHandle;
+
+/// The transforms and physics body states of a world at one point in time, taken with
+/// [`World::snapshot`](crate::World::snapshot) and put back with
+/// [`World::restore`](crate::World::restore), e.g. for rollback netcode or replays.
+///
+/// Only transforms and rigid body poses and velocities are captured. Component internals,
+/// timers, the event queue, contacts and solver caches aren't, so gameplay state has to be
+/// rolled back separately and a restored simulation isn't guaranteed to repeat bit for bit.
+/// Objects and bodies created after the snapshot are left alone, deleted ones aren't
+/// brought back.
+#[derive(Debug, Clone, Default)]
+pub struct WorldSnapshot {
+    pub(crate) transforms: Vec<TransformState>,
+    pub(crate) bodies: Vec<BodyState>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct TransformState {
+    pub(crate) object: GameObjectId,
+    pub(crate) position: Vector3<f32>,
+    pub(crate) rotation: UnitQuaternion<f32>,
+    pub(crate) scale: Vector3<f32>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct BodyState {
+    pub(crate) handle: RigidBodyHandle,
+    pub(crate) pose: Isometry3<f32>,
+    pub(crate) linvel: Vector3<f32>,
+    pub(crate) angvel: Vector3<f32>,
+}
+
+impl WorldSnapshot {
+    /// Returns the number of captured object transforms
+    pub fn object_count(&self) -> usize {
+        self.transforms.len()
+    }
+
+    /// Returns the number of captured rigid bodies
+    pub fn body_count(&self) -> usize {
+        self.bodies.len()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.transforms.clear();
+        self.bodies.clear();
+    }
+}
@@ -0,0 +1,180 @@
+use crate::core::{BoundingSphere, Frustum, GameObjectId};
+use nalgebra::Vector3;
+use std::collections::{HashMap, HashSet};
+
+type Cell = (i32, i32, i32);
+
+/// A uniform grid over the world bounds of objects, to answer proximity and view queries
+/// without visiting every object.
+///
+/// Each object is stored in every cell its bounding sphere overlaps. The cell size should be
+/// around the size of typical objects and query radii: smaller cells make large objects span
+/// many cells, larger cells put more objects into each one.
+///
+/// The world keeps it in sync, see [`World::enable_spatial_index`](crate::World::enable_spatial_index).
+#[derive(Debug)]
+pub struct SpatialGrid {
+    cell_size: f32,
+    cells: HashMap<Cell, Vec<GameObjectId>>,
+    entries: HashMap<GameObjectId, Entry>,
+    auto_update: bool,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Entry {
+    bounds: BoundingSphere,
+    min: Cell,
+    max: Cell,
+}
+
+impl SpatialGrid {
+    pub fn new(cell_size: f32) -> Self {
+        SpatialGrid {
+            cell_size: cell_size.max(f32::EPSILON),
+            cells: HashMap::new(),
+            entries: HashMap::new(),
+            auto_update: true,
+        }
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Returns true if moved objects are updated at the end of every frame
+    pub fn auto_update(&self) -> bool {
+        self.auto_update
+    }
+
+    /// Turns the update of moved objects at the end of every frame on or off. Without it,
+    /// objects have to be updated with [`World::update_spatial_object`](crate::World::update_spatial_object)
+    /// or a [rebuild](crate::World::rebuild_spatial_index).
+    pub fn set_auto_update(&mut self, auto_update: bool) {
+        self.auto_update = auto_update;
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, obj: GameObjectId) -> bool {
+        self.entries.contains_key(&obj)
+    }
+
+    /// Returns the bounds `obj` was last stored with
+    pub fn bounds(&self, obj: GameObjectId) -> Option<BoundingSphere> {
+        self.entries.get(&obj).map(|entry| entry.bounds)
+    }
+
+    /// Inserts `obj`, or moves it if it's already stored
+    pub fn insert(&mut self, obj: GameObjectId, bounds: BoundingSphere) {
+        let (min, max) = self.cell_range(&bounds);
+        if let Some(entry) = self.entries.get_mut(&obj)
+            && entry.min == min
+            && entry.max == max
+        {
+            entry.bounds = bounds;
+            return;
+        }
+
+        self.remove(obj);
+        for cell in cells_between(min, max) {
+            self.cells.entry(cell).or_default().push(obj);
+        }
+        self.entries.insert(obj, Entry { bounds, min, max });
+    }
+
+    pub fn remove(&mut self, obj: GameObjectId) {
+        let Some(entry) = self.entries.remove(&obj) else {
+            return;
+        };
+
+        for cell in cells_between(entry.min, entry.max) {
+            if let Some(objects) = self.cells.get_mut(&cell) {
+                objects.retain(|&o| o != obj);
+                if objects.is_empty() {
+                    self.cells.remove(&cell);
+                }
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.entries.clear();
+    }
+
+    /// Returns all objects whose bounds overlap the sphere around `point`
+    pub fn query_sphere(&self, point: Vector3<f32>, radius: f32) -> Vec<GameObjectId> {
+        let query = BoundingSphere {
+            center: point,
+            radius: radius.max(0.0),
+        };
+        let (min, max) = self.cell_range(&query);
+
+        let mut seen = HashSet::new();
+        cells_between(min, max)
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .filter(|obj| seen.insert(*obj))
+            .filter(|obj| spheres_overlap(&self.entries[obj].bounds, &query))
+            .collect()
+    }
+
+    /// Returns all objects whose bounds intersect the frustum. Whole cells outside of it are
+    /// skipped.
+    pub fn query_frustum(&self, frustum: &Frustum) -> Vec<GameObjectId> {
+        let half_diagonal = self.cell_size * 0.5 * 3f32.sqrt();
+
+        let mut seen = HashSet::new();
+        self.cells
+            .iter()
+            .filter(|(cell, _)| {
+                let center = Vector3::new(cell.0 as f32, cell.1 as f32, cell.2 as f32)
+                    .add_scalar(0.5)
+                    * self.cell_size;
+                frustum.intersects_sphere(&BoundingSphere {
+                    center,
+                    radius: half_diagonal,
+                })
+            })
+            .flat_map(|(_, objects)| objects)
+            .copied()
+            .filter(|obj| seen.insert(*obj))
+            .filter(|obj| frustum.intersects_sphere(&self.entries[obj].bounds))
+            .collect()
+    }
+
+    fn cell_of(&self, point: Vector3<f32>) -> Cell {
+        let cell = point / self.cell_size;
+        (
+            cell.x.floor() as i32,
+            cell.y.floor() as i32,
+            cell.z.floor() as i32,
+        )
+    }
+
+    fn cell_range(&self, bounds: &BoundingSphere) -> (Cell, Cell) {
+        let extent = Vector3::repeat(bounds.radius.max(0.0));
+        (
+            self.cell_of(bounds.center - extent),
+            self.cell_of(bounds.center + extent),
+        )
+    }
+}
+
+fn cells_between(min: Cell, max: Cell) -> impl Iterator<Item = Cell> {
+    (min.0..=max.0).flat_map(move |x| {
+        (min.1..=max.1).flat_map(move |y| (min.2..=max.2).map(move |z| (x, y, z)))
+    })
+}
+
+pub(crate) fn spheres_overlap(a: &BoundingSphere, b: &BoundingSphere) -> bool {
+    let reach = a.radius + b.radius;
+    (a.center - b.center).norm_squared() <= reach * reach
+}
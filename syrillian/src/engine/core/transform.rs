@@ -1,6 +1,10 @@
+use crate::components::Inspector;
 use crate::core::GameObjectId;
-use nalgebra::{Affine3, Isometry3, Point, Scale3, Translation3, UnitQuaternion, Vector3};
+use crate::utils::math;
+use crate::utils::math::ExtraMatrixMath;
+use nalgebra::{Affine3, Isometry3, Matrix4, Point, Scale3, Translation3, UnitQuaternion, Vector3};
 use num_traits::AsPrimitive;
+use std::cell::Cell;
 
 /// Stores the translation, rotation and scale of a [`GameObject`](crate::core::GameObject).
 ///
@@ -19,6 +23,9 @@ pub struct Transform {
     invert_position: bool,
     compound_pos_first: bool,
 
+    world_mat: Cell<Option<Affine3<f32>>>,
+    inverse_world_mat: Cell<Option<Affine3<f32>>>,
+
     is_dirty: bool,
 }
 
@@ -41,6 +48,9 @@ impl Transform {
             invert_position: false,
             compound_pos_first: true,
 
+            world_mat: Cell::new(None),
+            inverse_world_mat: Cell::new(None),
+
             is_dirty: true,
         }
     }
@@ -58,6 +68,9 @@ impl Transform {
             invert_position: self.invert_position,
             compound_pos_first: self.compound_pos_first,
 
+            world_mat: Cell::new(None),
+            inverse_world_mat: Cell::new(None),
+
             is_dirty: self.is_dirty,
         }
     }
@@ -116,9 +129,53 @@ impl Transform {
         self.rigid_global_isometry().inverse()
     }
 
-    /// Returns the global model matrix for this transform.
+    /// Returns the global model matrix for this transform. It's cached until this transform or
+    /// one of its parents changes.
     pub fn global_transform_matrix(&self) -> Affine3<f32> {
-        self.global_transform_matrix_ext(true)
+        if let Some(mat) = self.world_mat.get() {
+            return mat;
+        }
+        let mat = self.global_transform_matrix_ext(true);
+        self.world_mat.set(Some(mat));
+        mat
+    }
+
+    /// Returns the inverse of the [global model matrix](Self::global_transform_matrix), which
+    /// maps world space into the local space of this transform. Cached like the global matrix.
+    pub fn inverse_global_transform_matrix(&self) -> Affine3<f32> {
+        if let Some(mat) = self.inverse_world_mat.get() {
+            return mat;
+        }
+        let mat = self.global_transform_matrix().inverse();
+        self.inverse_world_mat.set(Some(mat));
+        mat
+    }
+
+    /// Transforms a point from world space into the local space of this transform
+    pub fn world_to_local(&self, point: Vector3<f32>) -> Vector3<f32> {
+        self.inverse_global_transform_matrix()
+            .transform_point(&point.into())
+            .coords
+    }
+
+    /// Transforms a point from the local space of this transform into world space
+    pub fn local_to_world(&self, point: Vector3<f32>) -> Vector3<f32> {
+        self.global_transform_matrix()
+            .transform_point(&point.into())
+            .coords
+    }
+
+    /// Transforms a direction from world space into local space, ignoring the translation.
+    /// Scale still applies, so the result isn't normalized.
+    pub fn world_to_local_direction(&self, direction: Vector3<f32>) -> Vector3<f32> {
+        self.inverse_global_transform_matrix()
+            .transform_vector(&direction)
+    }
+
+    /// Transforms a direction from local space into world space, ignoring the translation.
+    /// Scale still applies, so the result isn't normalized.
+    pub fn local_to_world_direction(&self, direction: Vector3<f32>) -> Vector3<f32> {
+        self.global_transform_matrix().transform_vector(&direction)
     }
 
     /// Calculates the global rotation, optionally excluding this transform.
@@ -258,12 +315,66 @@ impl Transform {
         Vector3::new(x, y, z)
     }
 
+    /// Declares the local position, rotation in degrees and scale for an [`Inspector`]
+    pub fn inspect(&mut self, ui: &mut Inspector) {
+        let mut position = self.pos;
+        if ui.vector("Position", &mut position) {
+            self.set_local_position_vec(position);
+        }
+
+        let mut rotation = self.local_euler_rotation().map(f32::to_degrees);
+        if ui.vector("Rotation", &mut rotation) {
+            let rad = rotation.map(f32::to_radians);
+            self.set_local_rotation(UnitQuaternion::from_euler_angles(rad.x, rad.y, rad.z));
+        }
+
+        let mut scale = self.scale;
+        if ui.vector("Scale", &mut scale) {
+            self.set_nonuniform_local_scale(scale);
+        }
+    }
+
     /// Applies a relative rotation to the transform.
     pub fn rotate(&mut self, rot: UnitQuaternion<f32>) {
         self.rot *= rot;
         self.recalculate_combined_matrix();
     }
 
+    /// Moves the global position towards `target` by at most `max_delta`, stopping on it.
+    ///
+    /// Scale `max_delta` by the delta time for a constant speed. Unlike `position.lerp(target,
+    /// speed * delta)`, this can't overshoot when a long frame pushes the factor above `1.0`.
+    pub fn move_toward(&mut self, target: Vector3<f32>, max_delta: f32) {
+        let position = math::move_toward(self.position(), target, max_delta);
+        self.set_position_vec(position);
+    }
+
+    /// Turns the global rotation towards `target` by at most `max_radians`, stopping on it.
+    ///
+    /// Scale `max_radians` by the delta time for a constant angular speed.
+    pub fn rotate_toward(&mut self, target: UnitQuaternion<f32>, max_radians: f32) {
+        let rotation = math::rotate_toward(self.rotation(), target, max_radians);
+        self.set_rotation(rotation);
+    }
+
+    /// Smoothly moves the global position towards `target`, arriving in about `smooth_time`
+    /// seconds regardless of the frame rate.
+    ///
+    /// `velocity` must be kept by the caller between frames, starting at zero. This replaces
+    /// per-frame `lerp`s with factors like `10.0 * delta`, which overshoot and jitter once the
+    /// factor exceeds `1.0`. See [`math::smooth_damp`].
+    pub fn smooth_damp(
+        &mut self,
+        target: Vector3<f32>,
+        velocity: &mut Vector3<f32>,
+        smooth_time: f32,
+        delta_time: f32,
+    ) {
+        let position =
+            math::smooth_damp(self.position(), target, velocity, smooth_time, delta_time);
+        self.set_position_vec(position);
+    }
+
     /// Sets the local scale using three independent factors.
     pub fn set_nonuniform_local_scale(&mut self, scale: Vector3<f32>) {
         self.scale.x = scale.x.abs().max(f32::EPSILON);
@@ -307,6 +418,38 @@ impl Transform {
         global_scale.vector
     }
 
+    /// Sets the local position, rotation and scale from a matrix relative to the parent.
+    ///
+    /// Shear, e.g. from a rotated child of a non-uniformly scaled parent, can't be represented
+    /// and is dropped.
+    pub fn set_local_transform_matrix(&mut self, matrix: &Matrix4<f32>) {
+        let (position, rotation, scale) = matrix.decompose();
+        self.pos = if self.invert_position {
+            -position
+        } else {
+            position
+        };
+        self.rot = rotation;
+        self.set_nonuniform_local_scale(scale);
+        self.recalculate_pos_matrix();
+    }
+
+    /// Sets the whole local transform at once, without touching anything if it's unchanged
+    pub(crate) fn set_local_parts(
+        &mut self,
+        position: Vector3<f32>,
+        rotation: UnitQuaternion<f32>,
+        scale: Vector3<f32>,
+    ) {
+        if self.pos == position && self.rot == rotation && self.scale == scale {
+            return;
+        }
+        self.pos = position;
+        self.rot = rotation;
+        self.scale = scale;
+        self.regenerate_matrices();
+    }
+
     /// Recalculates all cached matrices.
     pub fn regenerate_matrices(&mut self) {
         self.recalculate_pos_matrix();
@@ -386,6 +529,8 @@ impl Transform {
 
     pub fn set_dirty(&mut self) {
         self.is_dirty = true;
+        self.world_mat.set(None);
+        self.inverse_world_mat.set(None);
 
         if !self.owner().exists() {
             return;
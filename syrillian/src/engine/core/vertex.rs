@@ -1,4 +1,4 @@
-use nalgebra::{Vector2, Vector3};
+use nalgebra::{Vector2, Vector3, Vector4};
 use static_assertions::const_assert_eq;
 use wgpu::{BufferAddress, VertexAttribute, VertexFormat};
 
@@ -46,6 +46,7 @@ impl SimpleVertex3D {
             tangent,
             bone_indices: [0xFF, 0xFF, 0xFF, 0xFF],
             bone_weights: [0.0, 0.0, 0.0, 0.0],
+            color: WHITE,
         }
     }
 }
@@ -60,8 +61,12 @@ pub struct Vertex3D {
     pub tangent: Vector3<f32>,
     pub bone_indices: [u32; 4],
     pub bone_weights: [f32; 4],
+    /// Linear RGBA color multiplied into the material's base color. White by default.
+    pub color: Vector4<f32>,
 }
 
+const WHITE: Vector4<f32> = Vector4::new(1.0, 1.0, 1.0, 1.0);
+
 impl Vertex3D {
     /// Creates a new vertex from individual attributes.
     pub fn new(
@@ -79,9 +84,16 @@ impl Vertex3D {
             tangent,
             bone_indices: pad_to_four(bone_indices, 0x0),
             bone_weights: pad_to_four(bone_weights, 0.0),
+            color: WHITE,
         }
     }
 
+    /// Returns this vertex with the given linear RGBA color.
+    pub const fn with_color(mut self, color: Vector4<f32>) -> Self {
+        self.color = color;
+        self
+    }
+
     /// Returns a [`wgpu::VertexBufferLayout`] describing the layout of this vertex.
     pub const fn continuous_descriptor<'a>() -> wgpu::VertexBufferLayout<'a> {
         use crate::utils::sizes::*;
@@ -120,6 +132,11 @@ impl Vertex3D {
                     offset: (VEC4_SIZE + VEC3_SIZE * 3 + VEC2_SIZE) as BufferAddress,
                     shader_location: 5,
                 },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: (VEC4_SIZE * 2 + VEC3_SIZE * 3 + VEC2_SIZE) as BufferAddress,
+                    shader_location: 6,
+                },
             ],
         };
 
@@ -136,6 +153,7 @@ impl Vertex3D {
             tangent: Vector3::new(1.0, 0.0, 0.0),
             bone_indices: [0; 4],
             bone_weights: [0.0; 4],
+            color: WHITE,
         }
     }
 
@@ -147,6 +165,7 @@ impl Vertex3D {
             tangent: Vector3::new(1.0, 0.0, 0.0),
             bone_indices: [0; 4],
             bone_weights: [0.0; 4],
+            color: WHITE,
         }
     }
 }
@@ -0,0 +1,96 @@
+//! Copy and paste through the system clipboard.
+//!
+//! On WASM, the browser only grants clipboard access asynchronously and after a user gesture,
+//! which doesn't fit a synchronous game loop. There, and wherever the system clipboard can't be
+//! opened (e.g. without a display server), the clipboard only lives inside the game: pasting
+//! returns what was copied in the game before.
+
+#[cfg(not(target_arch = "wasm32"))]
+use tracing::warn;
+
+/// Text access to the clipboard, see [`World::clipboard`](crate::World::clipboard)
+#[derive(Default)]
+pub struct Clipboard {
+    #[cfg(not(target_arch = "wasm32"))]
+    system: SystemClipboard,
+    /// Used when the system clipboard isn't available
+    local: Option<String>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Default)]
+enum SystemClipboard {
+    /// Opened on first use, so worlds that never touch the clipboard don't connect to it
+    #[default]
+    Unopened,
+    Open(arboard::Clipboard),
+    Unavailable,
+}
+
+impl Clipboard {
+    /// A clipboard that never opens the system clipboard, so copy and paste stays in the game
+    pub fn local() -> Self {
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            system: SystemClipboard::Unavailable,
+            local: None,
+        }
+    }
+
+    /// Returns the text on the clipboard. Returns `None` if the clipboard is empty or holds
+    /// something that isn't text, like an image.
+    pub fn get_text(&mut self) -> Option<String> {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(system) = self.system() {
+            match system.get_text() {
+                Ok(text) => return Some(text).filter(|text| !text.is_empty()),
+                Err(arboard::Error::ContentNotAvailable) => return None,
+                Err(e) => warn!("Couldn't read the clipboard: {e}"),
+            }
+        }
+
+        self.local.clone()
+    }
+
+    /// Puts `text` on the clipboard
+    pub fn set_text(&mut self, text: &str) {
+        self.local = Some(text.to_string());
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(system) = self.system()
+            && let Err(e) = system.set_text(text)
+        {
+            warn!("Couldn't write to the clipboard: {e}");
+        }
+    }
+
+    /// Returns true if copying and pasting reaches other applications
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn is_system_clipboard(&mut self) -> bool {
+        self.system().is_some()
+    }
+
+    /// Returns true if copying and pasting reaches other applications
+    #[cfg(target_arch = "wasm32")]
+    pub fn is_system_clipboard(&mut self) -> bool {
+        false
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn system(&mut self) -> Option<&mut arboard::Clipboard> {
+        if matches!(self.system, SystemClipboard::Unopened) {
+            self.system = match arboard::Clipboard::new() {
+                Ok(clipboard) => SystemClipboard::Open(clipboard),
+                Err(e) => {
+                    warn!("System clipboard unavailable, copy and paste stays in the game: {e}");
+                    SystemClipboard::Unavailable
+                }
+            };
+        }
+
+        match &mut self.system {
+            SystemClipboard::Open(clipboard) => Some(clipboard),
+            _ => None,
+        }
+    }
+}
@@ -1,22 +1,47 @@
+use gilrs::ev::filter::{Filter, axis_dpad_to_button};
 use gilrs::{Axis, Button, Event, EventType, Gilrs, GilrsBuilder};
+use nalgebra::Vector2;
 use std::collections::HashMap;
 use tracing::{debug, trace};
 use uuid::Uuid;
 
+/// How analog input past the deadzone maps to the returned value
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum ResponseCurve {
+    #[default]
+    Linear,
+    /// Finer control for small deflections, full speed at full deflection
+    Quadratic,
+}
+
+impl ResponseCurve {
+    pub fn apply(self, value: f32) -> f32 {
+        match self {
+            ResponseCurve::Linear => value,
+            ResponseCurve::Quadratic => value * value,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct GamePadManager {
     poller: Gilrs,
     axis: HashMap<Axis, f32>,
     buttons: HashMap<Button, f32>,
     buttons_just_updated: Vec<Button>,
+    stick_deadzone: f32,
+    trigger_threshold: f32,
+    response_curve: ResponseCurve,
 }
 
 impl Default for GamePadManager {
     fn default() -> Self {
         //let poller = Gilrs::new().expect("Init gamepad input failed");
+        // gilrs' default filters include its own stick deadzone, which would stack with ours
         let poller = GilrsBuilder::new()
             .add_env_mappings(true)
             .add_included_mappings(false)
+            .with_default_filters(false)
             .build()
             .unwrap();
 
@@ -25,6 +50,9 @@ impl Default for GamePadManager {
             axis: HashMap::new(),
             buttons: HashMap::new(),
             buttons_just_updated: Vec::new(),
+            stick_deadzone: 0.15,
+            trigger_threshold: 0.05,
+            response_curve: ResponseCurve::Linear,
         }
     }
 }
@@ -33,6 +61,12 @@ impl GamePadManager {
     pub fn poll(&mut self) {
         self.next_frame();
         while let Some(event) = self.poller.next_event() {
+            let Some(event) = Some(event).filter_ev(&axis_dpad_to_button, &mut self.poller) else {
+                continue;
+            };
+            if event.is_dropped() {
+                continue;
+            }
             trace!("[Gamepads] Handling Gamepad Event: {event:?}");
             match event.event {
                 EventType::Connected | EventType::Disconnected => {
@@ -75,14 +109,105 @@ impl GamePadManager {
         }
     }
 
+    /// Returns the value of an axis with the deadzone and response curve applied.
+    ///
+    /// Stick axes use a radial deadzone together with the other axis of their stick, so
+    /// diagonals aren't cut off. See [`raw_axis`](Self::raw_axis) for the unfiltered value.
     pub fn axis(&self, axis: Axis) -> f32 {
+        match axis {
+            Axis::LeftStickX => self.left_stick().x,
+            Axis::LeftStickY => self.left_stick().y,
+            Axis::RightStickX => self.right_stick().x,
+            Axis::RightStickY => self.right_stick().y,
+            Axis::LeftZ | Axis::RightZ => self.filter_trigger(self.raw_axis(axis)),
+            _ => self.raw_axis(axis),
+        }
+    }
+
+    pub fn raw_axis(&self, axis: Axis) -> f32 {
         self.axis.get(&axis).copied().unwrap_or(0.0)
     }
 
+    /// Returns the left stick with the deadzone and response curve applied
+    pub fn left_stick(&self) -> Vector2<f32> {
+        self.filter_stick(Vector2::new(
+            self.raw_axis(Axis::LeftStickX),
+            self.raw_axis(Axis::LeftStickY),
+        ))
+    }
+
+    /// Returns the right stick with the deadzone and response curve applied
+    pub fn right_stick(&self) -> Vector2<f32> {
+        self.filter_stick(Vector2::new(
+            self.raw_axis(Axis::RightStickX),
+            self.raw_axis(Axis::RightStickY),
+        ))
+    }
+
+    /// Returns the value of a button. Analog triggers have the trigger threshold and response
+    /// curve applied, see [`raw_button`](Self::raw_button) for the unfiltered value.
     pub fn button(&self, button: Button) -> f32 {
+        let value = self.raw_button(button);
+        match button {
+            Button::LeftTrigger2 | Button::RightTrigger2 => self.filter_trigger(value),
+            _ => value,
+        }
+    }
+
+    pub fn raw_button(&self, button: Button) -> f32 {
         self.buttons.get(&button).copied().unwrap_or(0.0)
     }
 
+    /// Applies the radial deadzone and response curve to a stick position.
+    ///
+    /// The result starts at 0 right past the deadzone and reaches a length of 1 at full
+    /// deflection.
+    pub fn filter_stick(&self, stick: Vector2<f32>) -> Vector2<f32> {
+        let magnitude = stick.norm();
+        if magnitude <= self.stick_deadzone {
+            return Vector2::zeros();
+        }
+
+        let scaled = ((magnitude - self.stick_deadzone) / (1.0 - self.stick_deadzone)).min(1.0);
+        stick / magnitude * self.response_curve.apply(scaled)
+    }
+
+    /// Applies the trigger threshold and response curve to a trigger value from 0 to 1
+    pub fn filter_trigger(&self, value: f32) -> f32 {
+        if value <= self.trigger_threshold {
+            return 0.0;
+        }
+
+        let scaled = ((value - self.trigger_threshold) / (1.0 - self.trigger_threshold)).min(1.0);
+        self.response_curve.apply(scaled)
+    }
+
+    pub fn stick_deadzone(&self) -> f32 {
+        self.stick_deadzone
+    }
+
+    /// Sets how far sticks have to be pushed before they register, from 0 to 1. Default: 0.15
+    pub fn set_stick_deadzone(&mut self, deadzone: f32) {
+        self.stick_deadzone = deadzone.clamp(0.0, 0.99);
+    }
+
+    pub fn trigger_threshold(&self) -> f32 {
+        self.trigger_threshold
+    }
+
+    /// Sets how far triggers have to be pulled before they register, from 0 to 1. Default: 0.05
+    pub fn set_trigger_threshold(&mut self, threshold: f32) {
+        self.trigger_threshold = threshold.clamp(0.0, 0.99);
+    }
+
+    pub fn response_curve(&self) -> ResponseCurve {
+        self.response_curve
+    }
+
+    pub fn set_response_curve(&mut self, curve: ResponseCurve) {
+        self.response_curve = curve;
+    }
+
     pub fn is_button_pressed(&self, button: Button) -> bool {
         self.button(button) > 0.5
     }
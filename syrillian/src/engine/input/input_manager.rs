@@ -1,6 +1,8 @@
 use crate::game_thread::GameAppEvent;
 use crate::input::gamepad_manager::GamePadManager;
+use crate::input::recording::{InputRecording, Playback, RecordedInput, Recorder};
 use crate::windowing::RenderTargetId;
+use bitflags::bitflags;
 use crossbeam_channel::Sender;
 use nalgebra::Vector2;
 use num_traits::Zero;
@@ -8,10 +10,40 @@ use std::collections::HashMap;
 use tracing::{info, trace};
 use winit::dpi::PhysicalPosition;
 use winit::event::{DeviceEvent, ElementState, MouseButton, MouseScrollDelta, WindowEvent};
-use winit::keyboard::{KeyCode, PhysicalKey};
+use winit::keyboard::{KeyCode, ModifiersState, PhysicalKey};
 
 pub type KeyState = ElementState;
 
+fn element_state(pressed: bool) -> KeyState {
+    if pressed {
+        KeyState::Pressed
+    } else {
+        KeyState::Released
+    }
+}
+
+bitflags! {
+    /// Modifier keys that are held down, without telling left and right apart
+    #[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+    pub struct Modifiers: u8 {
+        const SHIFT = 1 << 0;
+        const CTRL = 1 << 1;
+        const ALT = 1 << 2;
+        const SUPER = 1 << 3;
+    }
+}
+
+impl From<ModifiersState> for Modifiers {
+    fn from(state: ModifiersState) -> Self {
+        let mut modifiers = Modifiers::empty();
+        modifiers.set(Modifiers::SHIFT, state.shift_key());
+        modifiers.set(Modifiers::CTRL, state.control_key());
+        modifiers.set(Modifiers::ALT, state.alt_key());
+        modifiers.set(Modifiers::SUPER, state.super_key());
+        modifiers
+    }
+}
+
 #[derive(Debug, Default)]
 struct InputState {
     key_states: HashMap<KeyCode, KeyState>,
@@ -19,9 +51,11 @@ struct InputState {
     button_states: HashMap<MouseButton, ElementState>,
     button_just_updated: Vec<MouseButton>,
     mouse_wheel_delta: f32,
+    modifiers: Modifiers,
     mouse_pos: PhysicalPosition<f32>,
     mouse_delta: Vector2<f32>,
     is_locked: bool,
+    is_confined: bool,
 }
 
 #[derive(Debug)]
@@ -31,6 +65,8 @@ pub struct InputManager {
     active_target: RenderTargetId,
     pub gamepad: GamePadManager,
     game_event_tx: Sender<GameAppEvent>,
+    recorder: Option<Recorder>,
+    playback: Option<Playback>,
 }
 
 #[allow(unused)]
@@ -42,6 +78,8 @@ impl InputManager {
             active_target: RenderTargetId::PRIMARY,
             gamepad: GamePadManager::default(),
             game_event_tx,
+            recorder: None,
+            playback: None,
         }
     }
 
@@ -77,19 +115,19 @@ impl InputManager {
         self.is_window_focused_for(self.active_target)
     }
 
-    pub(crate) fn process_device_input_event(&mut self, device_event: &DeviceEvent) {
-        let state = self.state_mut();
-        if let DeviceEvent::MouseMotion { delta } = device_event {
-            state.mouse_delta = Vector2::new(-delta.0 as f32, -delta.1 as f32);
-            state.mouse_pos.x += state.mouse_delta.x;
-            state.mouse_pos.y += state.mouse_delta.y;
+    /// Accumulates raw mouse motion into [`mouse_delta`](Self::mouse_delta) while the active
+    /// window is focused. The cursor position is left alone.
+    pub fn process_device_input_event(&mut self, device_event: &DeviceEvent) {
+        if !self.is_window_focused() {
+            return;
         }
-    }
 
-    #[inline]
-    pub(crate) fn process_mouse_event(&mut self, position: &PhysicalPosition<f64>) {
-        let new_pos = PhysicalPosition::new(position.x as f32, position.y as f32);
-        self.state_mut().mouse_pos = new_pos;
+        if let DeviceEvent::MouseMotion { delta } = device_event {
+            self.receive(RecordedInput::MouseMotion {
+                dx: delta.0 as f32,
+                dy: delta.1 as f32,
+            });
+        }
     }
 
     pub fn process_event(&mut self, target: RenderTargetId, event: &WindowEvent) {
@@ -105,46 +143,132 @@ impl InputManager {
     }
 
     fn handle_window_event(&mut self, event: &WindowEvent) {
-        match event {
-            WindowEvent::KeyboardInput { event, .. } => {
-                let state = self.state_mut();
-                if let PhysicalKey::Code(code) = event.physical_key {
-                    if !event.state.is_pressed()
-                        || state
-                            .key_states
-                            .get(&code)
-                            .is_none_or(|state| !state.is_pressed())
-                    {
-                        state.key_just_updated.push(code);
-                    }
-
-                    state.key_states.insert(code, event.state);
-                }
+        let input = match event {
+            WindowEvent::KeyboardInput { event, .. } => match event.physical_key {
+                PhysicalKey::Code(key) => RecordedInput::Key {
+                    key,
+                    pressed: event.state.is_pressed(),
+                },
+                PhysicalKey::Unidentified(_) => return,
+            },
+            WindowEvent::ModifiersChanged(modifiers) => {
+                RecordedInput::modifiers(modifiers.state().into())
             }
             WindowEvent::CursorMoved {
                 position,
                 device_id: _,
-            } => self.process_mouse_event(position),
+            } => RecordedInput::CursorMoved {
+                x: position.x as f32,
+                y: position.y as f32,
+            },
             WindowEvent::MouseWheel { delta, .. } => {
                 let y = match delta {
                     MouseScrollDelta::LineDelta(_, y) => *y as f64,
                     MouseScrollDelta::PixelDelta(pos) => pos.y,
                 };
-                self.state_mut().mouse_wheel_delta += y as f32;
+                RecordedInput::Wheel { delta: y as f32 }
             }
-            WindowEvent::MouseInput { button, state, .. } => {
-                let state_entry = self.state_mut();
-                if !state.is_pressed()
-                    || state_entry
+            WindowEvent::MouseInput { button, state, .. } => RecordedInput::Button {
+                button: *button,
+                pressed: state.is_pressed(),
+            },
+            _ => return,
+        };
+        self.receive(input);
+    }
+
+    /// Applies real input, unless a recording is played back
+    fn receive(&mut self, input: RecordedInput) {
+        if self.playback.is_none() {
+            self.apply_input(input);
+        }
+    }
+
+    fn apply_input(&mut self, input: RecordedInput) {
+        if let Some(recorder) = &mut self.recorder {
+            recorder.record(input);
+        }
+
+        let state = self.state_mut();
+        match input {
+            RecordedInput::Key { key, pressed } => {
+                if !pressed
+                    || state
+                        .key_states
+                        .get(&key)
+                        .is_none_or(|state| !state.is_pressed())
+                {
+                    state.key_just_updated.push(key);
+                }
+                state.key_states.insert(key, element_state(pressed));
+            }
+            RecordedInput::Button { button, pressed } => {
+                if !pressed
+                    || state
                         .button_states
-                        .get(button)
+                        .get(&button)
                         .is_none_or(|state| !state.is_pressed())
                 {
-                    state_entry.button_just_updated.push(*button);
+                    state.button_just_updated.push(button);
                 }
-                state_entry.button_states.insert(*button, *state);
+                state.button_states.insert(button, element_state(pressed));
             }
-            _ => {}
+            RecordedInput::CursorMoved { x, y } => state.mouse_pos = PhysicalPosition::new(x, y),
+            RecordedInput::MouseMotion { dx, dy } => {
+                state.mouse_delta += Vector2::new(-dx, -dy);
+            }
+            RecordedInput::Wheel { delta } => state.mouse_wheel_delta += delta,
+            RecordedInput::Modifiers { bits } => {
+                state.modifiers = Modifiers::from_bits_truncate(bits);
+            }
+        }
+    }
+
+    /// Starts recording all input from here on, replacing a recording that's still running.
+    /// Frames are counted at the end of every frame, like [`is_key_down`](Self::is_key_down)
+    /// edges are.
+    pub fn start_recording(&mut self) {
+        self.recorder = Some(Recorder::new());
+    }
+
+    /// Stops recording and returns the recorded input, or `None` if nothing was recording
+    pub fn stop_recording(&mut self) -> Option<InputRecording> {
+        self.recorder.take().map(Recorder::finish)
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Plays back a recording, starting with the input of its first frame right away.
+    ///
+    /// Every recorded event is applied in the same frame, relative to the start, as it was
+    /// recorded in, so with fixed [ticks](crate::World::tick) the replay reproduces the original
+    /// run. Real input is ignored until the playback finished or is
+    /// [stopped](Self::stop_playback). Window focus isn't part of recordings.
+    pub fn play_recording(&mut self, recording: InputRecording) {
+        self.playback = Some(Playback::new(recording));
+        self.apply_playback();
+    }
+
+    pub fn stop_playback(&mut self) {
+        self.playback = None;
+    }
+
+    pub fn is_playing_back(&self) -> bool {
+        self.playback.is_some()
+    }
+
+    fn apply_playback(&mut self) {
+        let Some(playback) = &mut self.playback else {
+            return;
+        };
+        let inputs = playback.take_frame();
+        if playback.is_finished() {
+            self.playback = None;
+        }
+        for input in inputs {
+            self.apply_input(input);
         }
     }
 
@@ -178,6 +302,66 @@ impl InputManager {
         self.key_state(key_code) == KeyState::Released
     }
 
+    /// Returns the keys that were pressed this frame, in the order they went down
+    pub fn keys_just_pressed(&self) -> impl Iterator<Item = KeyCode> + '_ {
+        self.state()
+            .key_just_updated
+            .iter()
+            .copied()
+            .filter(|&key| self.key_state(key) == KeyState::Pressed)
+    }
+
+    /// Returns true on the frame the last key of `keys` goes down while all others are held,
+    /// e.g. `[KeyCode::ControlLeft, KeyCode::KeyS]` for a save shortcut. Holding the chord
+    /// doesn't fire again, and the order the other keys were pressed in doesn't matter.
+    ///
+    /// Left and right modifier keys are told apart, check [`modifiers`](Self::modifiers) and
+    /// [`is_key_down`](Self::is_key_down) to accept either.
+    pub fn chord(&self, keys: &[KeyCode]) -> bool {
+        let Some((&last, held)) = keys.split_last() else {
+            return false;
+        };
+        self.is_key_down(last) && held.iter().all(|&key| self.is_key_pressed(key))
+    }
+
+    /// Returns all held modifier keys.
+    ///
+    /// Combines winit's modifier state with the tracked keys, so either left or right counts.
+    pub fn modifiers(&self) -> Modifiers {
+        let mut modifiers = self.state().modifiers;
+        for (flag, left, right) in [
+            (Modifiers::SHIFT, KeyCode::ShiftLeft, KeyCode::ShiftRight),
+            (Modifiers::CTRL, KeyCode::ControlLeft, KeyCode::ControlRight),
+            (Modifiers::ALT, KeyCode::AltLeft, KeyCode::AltRight),
+            (Modifiers::SUPER, KeyCode::SuperLeft, KeyCode::SuperRight),
+        ] {
+            if self.is_key_pressed(left) || self.is_key_pressed(right) {
+                modifiers |= flag;
+            }
+        }
+        modifiers
+    }
+
+    /// Returns true while either shift key is held
+    pub fn shift(&self) -> bool {
+        self.modifiers().contains(Modifiers::SHIFT)
+    }
+
+    /// Returns true while either control key is held
+    pub fn ctrl(&self) -> bool {
+        self.modifiers().contains(Modifiers::CTRL)
+    }
+
+    /// Returns true while either alt key is held
+    pub fn alt(&self) -> bool {
+        self.modifiers().contains(Modifiers::ALT)
+    }
+
+    /// Returns true while either super key (Windows/Command) is held
+    pub fn super_key(&self) -> bool {
+        self.modifiers().contains(Modifiers::SUPER)
+    }
+
     pub fn button_state(&self, button: MouseButton) -> ElementState {
         *self
             .state()
@@ -205,8 +389,13 @@ impl InputManager {
         self.state().mouse_pos
     }
 
-    pub fn mouse_delta(&self) -> &Vector2<f32> {
-        &self.state().mouse_delta
+    /// Returns the raw mouse motion accumulated this frame, negated so that moving right and
+    /// down yields negative values.
+    ///
+    /// This comes straight from the mouse device instead of the cursor position, so it keeps
+    /// reporting motion when the cursor is locked, hidden or pinned to a screen edge.
+    pub fn mouse_delta(&self) -> Vector2<f32> {
+        self.state().mouse_delta
     }
 
     pub fn lock_cursor(&mut self) {
@@ -225,19 +414,64 @@ impl InputManager {
         let _ = self
             .game_event_tx
             .send(GameAppEvent::cursor_mode(self.active_target, false, true));
+
+        // unlocking releases the grab, so the confinement has to come back
+        if self.is_cursor_confined() {
+            let _ = self
+                .game_event_tx
+                .send(GameAppEvent::SetCursorConfined(self.active_target, true));
+        }
     }
 
     pub fn is_cursor_locked(&self) -> bool {
         self.state().is_locked
     }
 
+    /// Keeps the cursor visible but inside the active window, e.g. for edge scrolling in
+    /// strategy games. Unlike [`lock_cursor`](Self::lock_cursor), the cursor still moves and
+    /// [`mouse_position`](Self::mouse_position) follows it. A lock takes over while it's active,
+    /// and the confinement comes back once the cursor is unlocked.
+    ///
+    /// Platforms differ in what they support:
+    /// - Windows, X11 and Wayland confine the cursor to the window.
+    /// - macOS can't confine the cursor, so it's locked in place instead while staying visible.
+    ///   Only [`mouse_delta`](Self::mouse_delta) reports motion there.
+    /// - On the web, only locking is available, which browsers only allow after a user gesture.
+    ///
+    /// If neither works, a warning is logged and the cursor stays free.
+    pub fn set_cursor_confined(&mut self, confined: bool) {
+        trace!("GT: Set cursor confinement to {confined}");
+        self.state_mut().is_confined = confined;
+        if self.is_cursor_locked() {
+            return;
+        }
+        let _ = self.game_event_tx.send(GameAppEvent::SetCursorConfined(
+            self.active_target,
+            confined,
+        ));
+    }
+
+    pub fn is_cursor_confined(&self) -> bool {
+        self.state().is_confined
+    }
+
     pub fn next_frame_all(&mut self) {
         self.state.key_just_updated.clear();
         self.state.button_just_updated.clear();
         self.state.mouse_delta = Vector2::zero();
+        self.state.mouse_wheel_delta = 0.0;
         self.gamepad.poll();
+
+        if let Some(recorder) = &mut self.recorder {
+            recorder.next_frame();
+        }
+        if let Some(playback) = &mut self.playback {
+            playback.next_frame();
+            self.apply_playback();
+        }
     }
 
+    /// Returns how far the mouse wheel scrolled this frame. Positive scrolls up.
     pub fn mouse_wheel_delta(&self) -> f32 {
         self.state().mouse_wheel_delta
     }
@@ -246,6 +480,10 @@ impl InputManager {
         &self.gamepad
     }
 
+    /// Locks the cursor on left click and unlocks it on escape.
+    ///
+    /// While locked, read the look direction from [`mouse_delta`](Self::mouse_delta), as the
+    /// cursor position doesn't move anymore.
     pub fn auto_cursor_lock(&mut self) {
         if self.is_cursor_locked() {
             if self.is_key_down(KeyCode::Escape) {
@@ -267,7 +505,7 @@ impl InputManager {
     }
 
     pub fn is_sprinting(&self) -> bool {
-        self.is_key_pressed(KeyCode::ShiftLeft)
+        self.shift()
     }
 
     pub fn is_jump_down(&self) -> bool {
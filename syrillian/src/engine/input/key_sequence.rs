@@ -0,0 +1,97 @@
+use crate::input::InputManager;
+use web_time::Duration;
+use winit::keyboard::KeyCode;
+
+/// Detects keys pressed one after another within a time window, like fighting game combos.
+///
+/// Call [`update`](Self::update) every frame. It returns true on the frame the last key of the
+/// sequence goes down. Pressing a key that doesn't come next starts over, as does taking longer
+/// than the window from the first to the last key.
+///
+/// ```rust
+/// use syrillian::input::KeySequence;
+/// use web_time::Duration;
+/// use winit::keyboard::KeyCode;
+///
+/// let mut hadouken = KeySequence::new(
+///     [KeyCode::KeyS, KeyCode::KeyD, KeyCode::KeyJ],
+///     Duration::from_millis(500),
+/// );
+/// assert!(!hadouken.feed(KeyCode::KeyS, Duration::from_millis(0)));
+/// assert!(!hadouken.feed(KeyCode::KeyD, Duration::from_millis(100)));
+/// assert!(hadouken.feed(KeyCode::KeyJ, Duration::from_millis(200)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct KeySequence {
+    keys: Vec<KeyCode>,
+    window: Duration,
+    progress: usize,
+    started: Duration,
+}
+
+impl KeySequence {
+    pub fn new(keys: impl Into<Vec<KeyCode>>, window: Duration) -> Self {
+        KeySequence {
+            keys: keys.into(),
+            window,
+            progress: 0,
+            started: Duration::ZERO,
+        }
+    }
+
+    pub fn keys(&self) -> &[KeyCode] {
+        &self.keys
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Returns how many keys of the sequence were pressed so far
+    pub fn progress(&self) -> usize {
+        self.progress
+    }
+
+    pub fn reset(&mut self) {
+        self.progress = 0;
+    }
+
+    /// Feeds the keys pressed this frame, with `time` being e.g. [`World::time`](crate::World::time).
+    /// Returns true if the sequence was completed.
+    pub fn update(&mut self, input: &InputManager, time: Duration) -> bool {
+        let mut completed = false;
+        for key in input.keys_just_pressed() {
+            completed |= self.feed(key, time);
+        }
+        completed
+    }
+
+    /// Feeds a single key press at `time`. Returns true if it completed the sequence.
+    pub fn feed(&mut self, key: KeyCode, time: Duration) -> bool {
+        if self.keys.is_empty() {
+            return false;
+        }
+        if self.progress > 0 && time.saturating_sub(self.started) > self.window {
+            self.progress = 0;
+        }
+
+        if self.keys[self.progress] != key {
+            // the wrong key might be the start of a new attempt
+            self.progress = 0;
+            if self.keys[0] != key {
+                return false;
+            }
+        }
+
+        if self.progress == 0 {
+            self.started = time;
+        }
+        self.progress += 1;
+
+        if self.progress == self.keys.len() {
+            self.progress = 0;
+            return true;
+        }
+        false
+    }
+}
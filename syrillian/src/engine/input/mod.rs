@@ -1,9 +1,17 @@
-//! Handling of keyboard and mouse input.
+//! Handling of keyboard, mouse and gamepad input.
 //!
 //! [`InputManager`] tracks key states and mouse movement and is used by
-//! components and systems to react to user interaction.
+//! components and systems to react to user interaction. The [`Clipboard`] provides copy and
+//! paste.
 
+pub mod clipboard;
 mod gamepad_manager;
 pub mod input_manager;
+pub mod key_sequence;
+pub mod recording;
 
+pub use self::clipboard::Clipboard;
+pub use self::gamepad_manager::{GamePadManager, ResponseCurve};
 pub use self::input_manager::*;
+pub use self::key_sequence::KeySequence;
+pub use self::recording::{InputRecording, RecordedEvent, RecordedInput, RecordingError};
@@ -0,0 +1,157 @@
+use crate::input::Modifiers;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use web_time::{Duration, Instant};
+use winit::event::MouseButton;
+use winit::keyboard::KeyCode;
+
+#[derive(Debug, Snafu)]
+#[snafu(context(suffix(Err)))]
+pub enum RecordingError {
+    #[snafu(display("The input recording couldn't be read: {source}"))]
+    Parse { source: serde_json::Error },
+}
+
+/// A single input change, as applied to the [`InputManager`](crate::input::InputManager)
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RecordedInput {
+    Key {
+        key: KeyCode,
+        pressed: bool,
+    },
+    Button {
+        button: MouseButton,
+        pressed: bool,
+    },
+    /// Cursor position in physical pixels
+    CursorMoved {
+        x: f32,
+        y: f32,
+    },
+    /// Raw mouse motion, as reported by the device
+    MouseMotion {
+        dx: f32,
+        dy: f32,
+    },
+    Wheel {
+        delta: f32,
+    },
+    /// The held modifier keys, as [`Modifiers::bits`]
+    Modifiers {
+        bits: u8,
+    },
+}
+
+impl RecordedInput {
+    pub(crate) fn modifiers(modifiers: Modifiers) -> Self {
+        RecordedInput::Modifiers {
+            bits: modifiers.bits(),
+        }
+    }
+}
+
+/// An input change and when it happened
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    /// The frame it happened in, counted from the start of the recording
+    pub frame: u64,
+    /// The time since the start of the recording, for reference. Playback goes by frames.
+    pub time: Duration,
+    pub input: RecordedInput,
+}
+
+/// Input recorded with [`InputManager::start_recording`](crate::input::InputManager::start_recording),
+/// to play it back for reproducible tests or demos.
+///
+/// Recordings are stored as JSON with [`to_json`](Self::to_json) and read with
+/// [`from_json`](Self::from_json).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InputRecording {
+    /// Number of frames the recording spans
+    pub frames: u64,
+    pub events: Vec<RecordedEvent>,
+}
+
+impl InputRecording {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("input recordings are always serializable")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, RecordingError> {
+        serde_json::from_str(json).context(ParseErr)
+    }
+
+    /// Returns the time from the start of the recording to the last event
+    pub fn duration(&self) -> Duration {
+        self.events.last().map_or(Duration::ZERO, |e| e.time)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Recorder {
+    start: Instant,
+    frame: u64,
+    events: Vec<RecordedEvent>,
+}
+
+impl Recorder {
+    pub(crate) fn new() -> Self {
+        Recorder {
+            start: Instant::now(),
+            frame: 0,
+            events: Vec::new(),
+        }
+    }
+
+    pub(crate) fn record(&mut self, input: RecordedInput) {
+        self.events.push(RecordedEvent {
+            frame: self.frame,
+            time: self.start.elapsed(),
+            input,
+        });
+    }
+
+    pub(crate) fn next_frame(&mut self) {
+        self.frame += 1;
+    }
+
+    pub(crate) fn finish(self) -> InputRecording {
+        InputRecording {
+            frames: self.frame,
+            events: self.events,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Playback {
+    recording: InputRecording,
+    frame: u64,
+    next: usize,
+}
+
+impl Playback {
+    pub(crate) fn new(recording: InputRecording) -> Self {
+        Playback {
+            recording,
+            frame: 0,
+            next: 0,
+        }
+    }
+
+    /// Returns the inputs of the current frame
+    pub(crate) fn take_frame(&mut self) -> Vec<RecordedInput> {
+        let events = &self.recording.events[self.next..];
+        let count = events.iter().take_while(|e| e.frame <= self.frame).count();
+        self.next += count;
+        events[..count].iter().map(|e| e.input).collect()
+    }
+
+    pub(crate) fn next_frame(&mut self) {
+        self.frame += 1;
+    }
+
+    pub(crate) fn is_finished(&self) -> bool {
+        self.next >= self.recording.events.len() && self.frame >= self.recording.frames
+    }
+}
@@ -1,11 +1,16 @@
 use crate::World;
 use crate::core::GameObjectId;
 use nalgebra::Vector3;
-use rapier3d::parry::query::{DefaultQueryDispatcher, ShapeCastOptions};
+use rapier3d::parry::query::{DefaultQueryDispatcher, RayCast, ShapeCastOptions, ShapeCastStatus};
 use rapier3d::prelude::*;
+use std::collections::HashSet;
 use web_time::{Duration, Instant};
 
 pub struct PhysicsManager {
+    /// Acceleration applied to every dynamic body, scaled by its gravity scale.
+    /// Defaults to [`EARTH_GRAVITY`] pointing down the Y axis.
+    ///
+    /// Prefer [`set_gravity`](Self::set_gravity) to change it, which also wakes sleeping bodies.
     pub gravity: Vector3<f32>,
     pub rigid_body_set: RigidBodySet,
     pub collider_set: ColliderSet,
@@ -24,7 +29,33 @@ pub struct PhysicsManager {
     pub alpha: f32,
 }
 
-const EARTH_GRAVITY: f32 = 9.81;
+/// Contact found by [`PhysicsManager::cast_shape`], in world space
+#[derive(Debug, Copy, Clone)]
+pub struct SweepHit {
+    pub object: GameObjectId,
+    /// Time until contact, in multiples of the velocity. 0 if the shape started out overlapping.
+    pub time_of_impact: f32,
+    /// Contact point on the hit collider
+    pub point: Vector3<f32>,
+    /// Surface normal of the hit collider at the contact point
+    pub normal: Vector3<f32>,
+    /// True if the shape already overlapped the collider at its start position
+    pub penetrating: bool,
+}
+
+/// Hit found by [`PhysicsManager::raycast`], in world space
+#[derive(Debug, Copy, Clone)]
+pub struct RayHit {
+    pub object: GameObjectId,
+    /// Distance from the ray origin, in multiples of the ray direction
+    pub distance: f32,
+    pub point: Vector3<f32>,
+    /// Surface normal of the hit collider at the hit point
+    pub normal: Vector3<f32>,
+}
+
+/// Strength of the default gravity in m/s²
+pub const EARTH_GRAVITY: f32 = 9.81;
 
 impl Default for PhysicsManager {
     fn default() -> Self {
@@ -50,6 +81,22 @@ impl Default for PhysicsManager {
 }
 
 impl PhysicsManager {
+    /// Changes the gravity and wakes all sleeping bodies, so they start falling in the new
+    /// direction right away
+    pub fn set_gravity(&mut self, gravity: Vector3<f32>) {
+        self.gravity = gravity;
+        self.wake_all();
+    }
+
+    /// Wakes every dynamic body, e.g. after changing something that affects all of them
+    pub fn wake_all(&mut self) {
+        for (_, body) in self.rigid_body_set.iter_mut() {
+            if body.is_dynamic() {
+                body.wake_up(true);
+            }
+        }
+    }
+
     pub fn step(&mut self) {
         self.physics_pipeline.step(
             &self.gravity,
@@ -88,6 +135,108 @@ impl PhysicsManager {
         object.exists().then_some((distance, object))
     }
 
+    /// Casts a solid ray and returns the closest hit with its point and surface normal
+    pub fn raycast(&self, ray: &Ray, max_toi: f32, filter: QueryFilter) -> Option<RayHit> {
+        let qp = self.broad_phase.as_query_pipeline(
+            &DefaultQueryDispatcher,
+            &self.rigid_body_set,
+            &self.collider_set,
+            filter,
+        );
+        let (collider, hit) = qp.cast_ray_and_get_normal(ray, max_toi, true)?;
+
+        let object_id = self.collider_set.get(collider)?.user_data as u64;
+        let object = GameObjectId::from_ffi(object_id);
+
+        object.exists().then_some(RayHit {
+            object,
+            distance: hit.time_of_impact,
+            point: ray.point_at(hit.time_of_impact).coords,
+            normal: hit.normal,
+        })
+    }
+
+    /// Returns every object the segment from `start` to `end` passes through, together with the
+    /// length of the segment inside its collider.
+    ///
+    /// Colliders containing either end are skipped, so the objects at the ends don't count.
+    pub fn segment_occluders(
+        &self,
+        start: Vector3<f32>,
+        end: Vector3<f32>,
+        filter: QueryFilter,
+    ) -> Vec<(GameObjectId, f32)> {
+        let delta = end - start;
+        let length = delta.norm();
+        let Some(dir) = delta.try_normalize(f32::EPSILON) else {
+            return Vec::new();
+        };
+
+        let qp = self.broad_phase.as_query_pipeline(
+            &DefaultQueryDispatcher,
+            &self.rigid_body_set,
+            &self.collider_set,
+            filter,
+        );
+        let ray = Ray::new(start.into(), dir);
+        let back_ray = Ray::new(end.into(), -dir);
+
+        qp.intersect_ray(ray, length, true)
+            .filter_map(|(_, collider, hit)| {
+                let exit =
+                    collider
+                        .shape()
+                        .cast_ray(collider.position(), &back_ray, length, true)?;
+                if hit.time_of_impact <= 0.0 || exit <= 0.0 {
+                    return None;
+                }
+
+                let object = GameObjectId::from_ffi(collider.user_data as u64);
+                let thickness = (length - hit.time_of_impact - exit).max(0.0);
+                object.exists().then_some((object, thickness))
+            })
+            .collect()
+    }
+
+    /// Moves `shape` from `shape_pos` along `velocity` and returns the first collider it touches
+    /// within `max_toi` multiples of the velocity.
+    ///
+    /// A shape that already overlaps a collider at the start reports a hit with a time of impact
+    /// of 0, so callers can push it out along the normal.
+    pub fn cast_shape(
+        &self,
+        shape: &dyn Shape,
+        shape_pos: &Isometry<f32>,
+        velocity: &Vector<f32>,
+        max_toi: f32,
+        filter: QueryFilter,
+    ) -> Option<SweepHit> {
+        let qp = self.broad_phase.as_query_pipeline(
+            &DefaultQueryDispatcher,
+            &self.rigid_body_set,
+            &self.collider_set,
+            filter,
+        );
+        let mut options = ShapeCastOptions::with_max_time_of_impact(max_toi);
+        options.stop_at_penetration = true;
+        options.compute_impact_geometry_on_penetration = true;
+        let (collider, hit) = qp.cast_shape(shape_pos, velocity, shape, options)?;
+
+        let collider = self.collider_set.get(collider)?;
+        let object = GameObjectId::from_ffi(collider.user_data as u64);
+        let penetrating = hit.status == ShapeCastStatus::PenetratingOrWithinTargetDist;
+
+        // the witness and normal are local to the hit collider
+        let position = collider.position();
+        object.exists().then_some(SweepHit {
+            object,
+            time_of_impact: if penetrating { 0.0 } else { hit.time_of_impact },
+            point: (position * hit.witness2).coords,
+            normal: position.rotation * hit.normal2.into_inner(),
+            penetrating,
+        })
+    }
+
     pub fn cast_sphere(
         &self,
         radius: f32,
@@ -112,11 +261,58 @@ impl PhysicsManager {
         object.exists().then_some((hit, object))
     }
 
+    /// Returns every object with a collider overlapping `shape` at `shape_pos`. Objects with
+    /// several colliders are only listed once.
+    ///
+    /// Sensors are included, unless the filter excludes them with
+    /// [`QueryFilter::exclude_sensors`].
+    pub fn intersect_shape(
+        &self,
+        shape_pos: &Isometry<f32>,
+        shape: &dyn Shape,
+        filter: QueryFilter,
+    ) -> Vec<GameObjectId> {
+        let qp = self.broad_phase.as_query_pipeline(
+            &DefaultQueryDispatcher,
+            &self.rigid_body_set,
+            &self.collider_set,
+            filter,
+        );
+
+        let mut seen = HashSet::new();
+        qp.intersect_shape(*shape_pos, shape)
+            .map(|(_, collider)| GameObjectId::from_ffi(collider.user_data as u64))
+            .filter(|object| object.exists() && seen.insert(*object))
+            .collect()
+    }
+
+    /// Returns every rigid body with a collider overlapping `shape` at `shape_pos`, each once
+    pub fn bodies_in_shape(
+        &self,
+        shape_pos: &Isometry<f32>,
+        shape: &dyn Shape,
+        filter: QueryFilter,
+    ) -> Vec<RigidBodyHandle> {
+        let qp = self.broad_phase.as_query_pipeline(
+            &DefaultQueryDispatcher,
+            &self.rigid_body_set,
+            &self.collider_set,
+            filter,
+        );
+
+        let mut seen = HashSet::new();
+        qp.intersect_shape(*shape_pos, shape)
+            .filter_map(|(_, collider)| collider.parent())
+            .filter(|body| seen.insert(*body))
+            .collect()
+    }
+
+    /// Creates a ray from the topmost camera under the cursor, see [`World::camera_at`]
     pub fn cursor_ray(&self, world: &World) -> Option<Ray> {
         let cursor_pos = world.input.mouse_position();
+        let target = world.input.active_target();
         world
-            .active_camera()
-            .upgrade(world)
+            .camera_at(target, cursor_pos.x, cursor_pos.y)
             .map(|cam| cam.click_ray(cursor_pos.x, cursor_pos.y))
     }
 
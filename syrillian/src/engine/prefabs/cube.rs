@@ -1,24 +1,41 @@
 use crate::World;
 use crate::assets::{HMaterial, HMesh};
-use crate::components::MeshRenderer;
+use crate::components::{Collider3D, MeshRenderer};
 use crate::core::GameObjectId;
 use crate::prefabs::prefab::Prefab;
+use nalgebra::Vector3;
 
 pub struct CubePrefab {
     pub material: HMaterial,
+    /// Edge lengths of the cube, applied as the local scale
+    pub size: Vector3<f32>,
+    /// Attaches a [`Collider3D`] matching the size
+    pub collider: bool,
 }
 
 impl Default for CubePrefab {
     fn default() -> Self {
-        CubePrefab {
-            material: HMaterial::DEFAULT,
-        }
+        CubePrefab::new(HMaterial::DEFAULT)
     }
 }
 
 impl CubePrefab {
     pub const fn new(material: HMaterial) -> Self {
-        CubePrefab { material }
+        CubePrefab::with_size(material, Vector3::new(1.0, 1.0, 1.0))
+    }
+
+    pub const fn with_size(material: HMaterial, size: Vector3<f32>) -> Self {
+        CubePrefab {
+            material,
+            size,
+            collider: false,
+        }
+    }
+
+    /// Also attaches a [`Collider3D`] that matches the cube
+    pub const fn with_collider(mut self) -> Self {
+        self.collider = true;
+        self
     }
 }
 
@@ -30,9 +47,14 @@ impl Prefab for CubePrefab {
 
     fn build(&self, world: &mut World) -> GameObjectId {
         let mut cube = world.new_object("Cube");
+        cube.transform.set_nonuniform_local_scale(self.size);
         cube.add_component::<MeshRenderer>()
             .change_mesh(HMesh::UNIT_CUBE, Some(vec![self.material]));
 
+        if self.collider {
+            cube.add_component::<Collider3D>();
+        }
+
         cube
     }
 }
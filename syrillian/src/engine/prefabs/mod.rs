@@ -6,14 +6,17 @@
 pub mod camera;
 pub mod cube;
 pub mod first_person_player;
+pub mod pool;
 pub mod prefab;
 pub mod sphere;
 pub mod sun;
 
-pub use prefab::Prefab;
+pub use pool::ObjectPool;
+pub use prefab::{Prefab, PrefabContext};
 
 // Premade for you :)
 pub use camera::CameraPrefab;
 pub use cube::CubePrefab;
 pub use first_person_player::FirstPersonPlayerPrefab;
+pub use sphere::SpherePrefab;
 pub use sun::SunPrefab;
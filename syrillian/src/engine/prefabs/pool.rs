@@ -0,0 +1,107 @@
+use crate::World;
+use crate::components::RigidBodyComponent;
+use crate::core::GameObjectId;
+use crate::prefabs::Prefab;
+use nalgebra::{UnitQuaternion, Vector3};
+use std::collections::HashMap;
+
+/// Recycles objects built from a prefab instead of spawning and deleting them over and over.
+///
+/// Free objects are kept inactive. They're never deleted, so their components, physics bodies
+/// and any handles to them stay the same across reuse.
+pub struct ObjectPool<P: Prefab> {
+    prefab: P,
+    templates: HashMap<GameObjectId, LocalTransform>,
+    free: Vec<GameObjectId>,
+}
+
+/// The local transform an object had right after it was built
+#[derive(Debug, Copy, Clone)]
+struct LocalTransform {
+    position: Vector3<f32>,
+    rotation: UnitQuaternion<f32>,
+    scale: Vector3<f32>,
+}
+
+impl<P: Prefab> ObjectPool<P> {
+    /// Creates a pool with `capacity` inactive objects already spawned
+    pub fn new(world: &mut World, prefab: P, capacity: usize) -> Self {
+        let mut pool = ObjectPool {
+            prefab,
+            templates: HashMap::with_capacity(capacity),
+            free: Vec::with_capacity(capacity),
+        };
+        for _ in 0..capacity {
+            let obj = pool.spawn(world);
+            pool.free.push(obj);
+        }
+        pool
+    }
+
+    /// Activates a free object, or spawns a new one if the pool is empty.
+    ///
+    /// The object's transform is reset to how the prefab built it and rigid bodies are stopped.
+    pub fn acquire(&mut self, world: &mut World) -> GameObjectId {
+        let mut obj = loop {
+            match self.free.pop() {
+                Some(obj) if obj.exists() => break obj,
+                Some(obj) => {
+                    self.templates.remove(&obj);
+                }
+                None => break self.spawn(world),
+            }
+        };
+
+        let template = self.templates[&obj];
+        obj.transform.set_local_position_vec(template.position);
+        obj.transform.set_local_rotation(template.rotation);
+        obj.transform.set_nonuniform_local_scale(template.scale);
+        if let Some(mut rb) = obj.get_component::<RigidBodyComponent>() {
+            rb.reset_motion();
+        }
+
+        obj.set_active(true);
+        obj
+    }
+
+    /// Deactivates the object and returns it to the pool.
+    ///
+    /// Returns false if the object doesn't belong to this pool or was already released.
+    pub fn release(&mut self, mut obj: GameObjectId) -> bool {
+        if !self.templates.contains_key(&obj) || self.free.contains(&obj) {
+            return false;
+        }
+
+        obj.set_active(false);
+        self.free.push(obj);
+        true
+    }
+
+    /// Returns how many objects are ready to be acquired without spawning
+    pub fn available(&self) -> usize {
+        self.free.len()
+    }
+
+    /// Returns how many objects belong to this pool, acquired or not
+    pub fn len(&self) -> usize {
+        self.templates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.templates.is_empty()
+    }
+
+    fn spawn(&mut self, world: &mut World) -> GameObjectId {
+        let mut obj = self.prefab.spawn(world);
+        obj.set_active(false);
+
+        let transform = &obj.transform;
+        let template = LocalTransform {
+            position: *transform.local_position(),
+            rotation: *transform.local_rotation(),
+            scale: *transform.local_scale(),
+        };
+        self.templates.insert(obj, template);
+        obj
+    }
+}
@@ -1,12 +1,130 @@
 use crate::World;
 use crate::core::GameObjectId;
+use tracing::warn;
 
+/// A recipe for building a game object hierarchy.
+///
+/// Prefabs made of other prefabs are easiest to write by also implementing
+/// [`build_in`](Prefab::build_in), which gets a [`PrefabContext`] that parents nested prefabs to
+/// the root of this one. [`build`](Prefab::build) then starts a new context:
+///
+/// ```rust
+/// use syrillian::World;
+/// use syrillian::core::GameObjectId;
+/// use syrillian::prefabs::{CubePrefab, Prefab, PrefabContext, SpherePrefab};
+///
+/// struct CarPrefab;
+///
+/// impl Prefab for CarPrefab {
+///     fn prefab_name(&self) -> &'static str {
+///         "Car"
+///     }
+///
+///     fn build(&self, world: &mut World) -> GameObjectId {
+///         self.build_in(&mut PrefabContext::new(world))
+///     }
+///
+///     fn build_in(&self, ctx: &mut PrefabContext) -> GameObjectId {
+///         let body = ctx.spawn_root(&CubePrefab::default());
+///         for x in [-1.0, 1.0] {
+///             let mut wheel = ctx.spawn_child(&SpherePrefab::default());
+///             wheel.transform.set_local_position(x, -0.5, 0.0);
+///         }
+///         body
+///     }
+/// }
+/// ```
 pub trait Prefab {
     fn prefab_name(&self) -> &'static str;
+
+    /// Builds the prefab and returns its root object
     fn build(&self, world: &mut World) -> GameObjectId;
+
+    /// Builds the prefab with a context for spawning nested prefabs, and returns its root object.
+    /// Defaults to [`build`](Prefab::build) in the context's world.
+    fn build_in(&self, ctx: &mut PrefabContext) -> GameObjectId {
+        self.build(ctx.world())
+    }
+
     fn spawn(&self, world: &mut World) -> GameObjectId {
         let obj = self.build(world);
         world.add_child(obj);
         obj
     }
 }
+
+/// Passed to [`Prefab::build_in`] to compose prefabs out of other prefabs.
+///
+/// The first object created through the context becomes the root that nested prefabs are
+/// parented to.
+pub struct PrefabContext<'a> {
+    world: &'a mut World,
+    root: Option<GameObjectId>,
+}
+
+impl<'a> PrefabContext<'a> {
+    pub fn new(world: &'a mut World) -> Self {
+        PrefabContext { world, root: None }
+    }
+
+    pub fn world(&mut self) -> &mut World {
+        self.world
+    }
+
+    /// Returns the object nested prefabs are parented to, if one was created yet
+    pub fn root(&self) -> Option<GameObjectId> {
+        self.root
+    }
+
+    /// Makes `root` the object nested prefabs are parented to
+    pub fn set_root(&mut self, root: GameObjectId) {
+        self.root = Some(root);
+    }
+
+    /// Creates an object. The first one becomes the root if none was set yet.
+    pub fn new_object(&mut self, name: impl Into<String>) -> GameObjectId {
+        let obj = self.world.new_object(name);
+        self.root.get_or_insert(obj);
+        obj
+    }
+
+    /// Builds another prefab and makes its root object the root of this context
+    pub fn spawn_root<P: Prefab + ?Sized>(&mut self, prefab: &P) -> GameObjectId {
+        let root = self.build_nested(prefab);
+        self.root = Some(root);
+        root
+    }
+
+    /// Builds another prefab as a child of the root object and returns the child's id.
+    ///
+    /// Without a root, the child ends up at the world root.
+    pub fn spawn_child<P: Prefab + ?Sized>(&mut self, prefab: &P) -> GameObjectId {
+        match self.root {
+            Some(root) => self.spawn_child_of(root, prefab),
+            None => {
+                warn!(
+                    "Prefab {:?} was spawned as a child before a root object was created",
+                    prefab.prefab_name()
+                );
+                let child = self.build_nested(prefab);
+                self.world.add_child(child);
+                child
+            }
+        }
+    }
+
+    /// Builds another prefab as a child of `parent`, which is usually part of this prefab
+    pub fn spawn_child_of<P: Prefab + ?Sized>(
+        &mut self,
+        mut parent: GameObjectId,
+        prefab: &P,
+    ) -> GameObjectId {
+        let child = self.build_nested(prefab);
+        parent.add_child(child);
+        child
+    }
+
+    fn build_nested<P: Prefab + ?Sized>(&mut self, prefab: &P) -> GameObjectId {
+        prefab.build_in(&mut PrefabContext::new(self.world))
+    }
+}
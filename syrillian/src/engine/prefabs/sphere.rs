@@ -1,24 +1,40 @@
 use crate::World;
 use crate::assets::{HMaterial, HMesh};
-use crate::components::MeshRenderer;
+use crate::components::{Collider3D, MeshRenderer};
 use crate::core::GameObjectId;
 use crate::prefabs::prefab::Prefab;
 
 pub struct SpherePrefab {
     pub material: HMaterial,
+    /// Radius of the sphere, applied as the local scale
+    pub radius: f32,
+    /// Attaches a spherical [`Collider3D`] matching the radius
+    pub collider: bool,
 }
 
 impl Default for SpherePrefab {
     fn default() -> Self {
-        Self {
-            material: HMaterial::DEFAULT,
-        }
+        Self::new(HMaterial::DEFAULT)
     }
 }
 
 impl SpherePrefab {
     pub const fn new(material: HMaterial) -> Self {
-        Self { material }
+        Self::with_radius(material, 1.0)
+    }
+
+    pub const fn with_radius(material: HMaterial, radius: f32) -> Self {
+        Self {
+            material,
+            radius,
+            collider: false,
+        }
+    }
+
+    /// Also attaches a spherical [`Collider3D`] that matches the sphere
+    pub const fn with_collider(mut self) -> Self {
+        self.collider = true;
+        self
     }
 }
 
@@ -30,10 +46,15 @@ impl Prefab for SpherePrefab {
 
     fn build(&self, world: &mut World) -> GameObjectId {
         let mut sphere = world.new_object(self.prefab_name());
+        sphere.transform.set_uniform_local_scale(self.radius);
         sphere
             .add_component::<MeshRenderer>()
             .change_mesh(HMesh::SPHERE, Some(vec![self.material]));
 
+        if self.collider {
+            sphere.add_component::<Collider3D>().use_sphere();
+        }
+
         sphere
     }
 }
@@ -0,0 +1,71 @@
+use wgpu::{
+    Device, Extent3d, SurfaceConfiguration, Texture, TextureDescriptor, TextureDimension,
+    TextureFormat, TextureUsages, TextureView, TextureViewDescriptor,
+};
+
+pub const AMBIENT_OCCLUSION_FORMAT: TextureFormat = TextureFormat::R8Unorm;
+
+/// How much of the ambient light is blocked at each pixel, written by the ambient occlusion pass
+/// and read by the lit shaders.
+///
+/// Stores the occlusion rather than the visibility, so the empty texture means nothing is
+/// occluded. While disabled, the texture is a single empty pixel.
+pub struct AmbientOcclusionSurface {
+    enabled: bool,
+    view: TextureView,
+}
+
+impl AmbientOcclusionSurface {
+    pub fn new(device: &Device, config: &SurfaceConfiguration, enabled: bool) -> Self {
+        let (width, height) = if enabled {
+            (config.width.max(1), config.height.max(1))
+        } else {
+            (1, 1)
+        };
+
+        let view = create_occlusion_texture(device, width, height)
+            .create_view(&TextureViewDescriptor::default());
+
+        AmbientOcclusionSurface { enabled, view }
+    }
+
+    /// A surface that never occludes anything, for targets without an ambient occlusion pass
+    pub fn disabled(device: &Device) -> Self {
+        let view =
+            create_occlusion_texture(device, 1, 1).create_view(&TextureViewDescriptor::default());
+
+        AmbientOcclusionSurface {
+            enabled: false,
+            view,
+        }
+    }
+
+    pub fn recreate(&mut self, device: &Device, config: &SurfaceConfiguration) {
+        *self = Self::new(device, config, self.enabled);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn view(&self) -> &TextureView {
+        &self.view
+    }
+}
+
+fn create_occlusion_texture(device: &Device, width: u32, height: u32) -> Texture {
+    device.create_texture(&TextureDescriptor {
+        label: Some("Ambient Occlusion Texture"),
+        size: Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format: AMBIENT_OCCLUSION_FORMAT,
+        usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+        view_formats: &[],
+    })
+}
@@ -6,10 +6,16 @@ use crate::engine::assets::*;
 use crate::engine::rendering::State;
 use crate::engine::rendering::cache::generic_cache::Cache;
 use crate::rendering::cache::GpuTexture;
-use crate::rendering::{FontAtlas, RuntimeMaterial, RuntimeMesh, RuntimeShader};
+use crate::rendering::{
+    FontAtlas, RuntimeComputeShader, RuntimeMaterial, RuntimeMesh, RuntimeShader,
+};
+use std::sync::atomic::{AtomicU16, Ordering};
 use std::sync::{Arc, Mutex};
 use web_time::Instant;
-use wgpu::BindGroupLayout;
+use wgpu::{BindGroupLayout, DownlevelFlags};
+
+/// The highest anisotropy level that any backend accepts.
+pub const MAX_ANISOTROPY: u16 = 16;
 
 pub struct AssetCache {
     pub meshes: Cache<Mesh>,
@@ -18,9 +24,13 @@ pub struct AssetCache {
     pub materials: Cache<Material>,
     pub bgls: Cache<BGL>,
     pub fonts: Cache<Font>,
+    pub compute: Cache<ComputeShader>,
 
     store: Arc<AssetStore>,
 
+    anisotropy: AtomicU16,
+    max_anisotropy: u16,
+
     last_refresh: Mutex<Instant>,
 }
 
@@ -28,6 +38,17 @@ impl AssetCache {
     pub fn new(store: Arc<AssetStore>, state: &State) -> Self {
         let device = &state.device;
         let queue = &state.queue;
+        let max_anisotropy = if state
+            .adapter
+            .get_downlevel_capabilities()
+            .flags
+            .contains(DownlevelFlags::ANISOTROPIC_FILTERING)
+        {
+            MAX_ANISOTROPY
+        } else {
+            1
+        };
+
         Self {
             meshes: Cache::new(store.meshes.clone(), device.clone(), queue.clone()),
             shaders: Cache::new(store.shaders.clone(), device.clone(), queue.clone()),
@@ -35,11 +56,38 @@ impl AssetCache {
             materials: Cache::new(store.materials.clone(), device.clone(), queue.clone()),
             bgls: Cache::new(store.bgls.clone(), device.clone(), queue.clone()),
             fonts: Cache::new(store.fonts.clone(), device.clone(), queue.clone()),
+            compute: Cache::new(store.compute.clone(), device.clone(), queue.clone()),
             store,
+            anisotropy: AtomicU16::new(1),
+            max_anisotropy,
             last_refresh: Mutex::new(Instant::now()),
         }
     }
 
+    /// The default anisotropy level used by materials that don't specify their own.
+    pub fn anisotropy(&self) -> u16 {
+        self.anisotropy.load(Ordering::Acquire)
+    }
+
+    /// The highest anisotropy level supported by the adapter. 1 means unsupported.
+    pub fn max_anisotropy(&self) -> u16 {
+        self.max_anisotropy
+    }
+
+    /// Clamps the anisotropy level to what the adapter supports.
+    pub fn clamp_anisotropy(&self, level: u16) -> u16 {
+        level.clamp(1, self.max_anisotropy)
+    }
+
+    /// Sets the default anisotropy level and rebuilds all materials. Returns the clamped level.
+    pub fn set_anisotropy(&self, level: u16) -> u16 {
+        let level = self.clamp_anisotropy(level);
+        if self.anisotropy.swap(level, Ordering::AcqRel) != level {
+            self.materials.invalidate_all();
+        }
+        level
+    }
+
     pub fn store(&self) -> &AssetStore {
         &self.store
     }
@@ -70,6 +118,10 @@ impl AssetCache {
         self.shaders.get(HShader::POST_PROCESS, self)
     }
 
+    pub fn compute_shader(&self, handle: HComputeShader) -> Option<Arc<RuntimeComputeShader>> {
+        self.compute.try_get(handle, self)
+    }
+
     pub fn texture(&self, handle: HTexture) -> Arc<GpuTexture> {
         self.textures.get(handle, self)
     }
@@ -135,6 +187,12 @@ impl AssetCache {
             .expect("Post Process is a default layout")
     }
 
+    pub fn bgl_depth(&self) -> Arc<BindGroupLayout> {
+        self.bgls
+            .try_get(HBGL::DEPTH, self)
+            .expect("Depth is a default layout")
+    }
+
     pub fn font(&self, handle: HFont) -> Arc<FontAtlas> {
         self.fonts.get(handle, self)
     }
@@ -147,6 +205,7 @@ impl AssetCache {
         refreshed_count += self.materials.refresh_dirty();
         refreshed_count += self.textures.refresh_dirty();
         refreshed_count += self.bgls.refresh_dirty();
+        refreshed_count += self.compute.refresh_dirty();
 
         *self.last_refresh.lock().unwrap() = Instant::now();
 
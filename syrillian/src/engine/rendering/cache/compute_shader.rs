@@ -0,0 +1,128 @@
+use crate::engine::assets::{ComputeShader, ShaderValidationError};
+use crate::engine::rendering::cache::AssetCache;
+use crate::engine::rendering::cache::generic_cache::CacheType;
+use std::borrow::Cow;
+use tracing::error;
+use wgpu::*;
+
+/// A compiled [`ComputeShader`], for recording dispatches on the render thread.
+///
+/// Scene proxies can use this to generate geometry on the GPU: create a buffer with
+/// `BufferUsages::STORAGE | BufferUsages::VERTEX`, bind it with
+/// [`bind_buffers`](Self::bind_buffers), [`dispatch`](Self::dispatch) into it, and then draw it
+/// as a vertex buffer in the same frame.
+#[derive(Debug)]
+pub struct RuntimeComputeShader {
+    name: String,
+    /// None if the shader failed to validate
+    pipeline: Option<ComputePipeline>,
+}
+
+impl CacheType for ComputeShader {
+    type Hot = RuntimeComputeShader;
+
+    fn upload(self, device: &Device, _queue: &Queue, cache: &AssetCache) -> Self::Hot {
+        let name = self.name().to_string();
+        let expanded = self
+            .with_includes(&cache.store().shader_includes)
+            .map_err(|source| ShaderValidationError::Include { source })
+            .and_then(|expanded| expanded.validate().map(|_| expanded));
+        let expanded = match expanded {
+            Ok(expanded) => expanded,
+            Err(e) => {
+                error!("{e}");
+                return RuntimeComputeShader {
+                    name,
+                    pipeline: None,
+                };
+            }
+        };
+
+        let module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some(self.name()),
+            source: ShaderSource::Wgsl(Cow::Borrowed(expanded.code())),
+        });
+
+        let pipeline = device.create_compute_pipeline(&ComputePipelineDescriptor {
+            label: Some(self.name()),
+            layout: None,
+            module: &module,
+            entry_point: Some(self.entry_point()),
+            compilation_options: PipelineCompilationOptions::default(),
+            cache: None,
+        });
+
+        RuntimeComputeShader {
+            name,
+            pipeline: Some(pipeline),
+        }
+    }
+}
+
+impl RuntimeComputeShader {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns false if the shader code was invalid. Dispatching it does nothing then.
+    pub fn is_valid(&self) -> bool {
+        self.pipeline.is_some()
+    }
+
+    pub fn pipeline(&self) -> Option<&ComputePipeline> {
+        self.pipeline.as_ref()
+    }
+
+    /// The layout of bind group `index`, as it's declared in the shader
+    pub fn bind_group_layout(&self, index: u32) -> Option<BindGroupLayout> {
+        Some(self.pipeline.as_ref()?.get_bind_group_layout(index))
+    }
+
+    /// Creates a bind group for group 0, with the nth buffer at `@binding(n)`.
+    ///
+    /// The shader has to use every binding, otherwise it's not part of the layout.
+    pub fn bind_buffers(&self, device: &Device, buffers: &[&Buffer]) -> Option<BindGroup> {
+        let layout = self.bind_group_layout(0)?;
+        let entries: Vec<_> = buffers
+            .iter()
+            .enumerate()
+            .map(|(binding, buffer)| BindGroupEntry {
+                binding: binding as u32,
+                resource: buffer.as_entire_binding(),
+            })
+            .collect();
+
+        Some(device.create_bind_group(&BindGroupDescriptor {
+            label: Some(&self.name),
+            layout: &layout,
+            entries: &entries,
+        }))
+    }
+
+    /// Records a compute pass that runs `x * y * z` workgroups, with the nth bind group in
+    /// group n. Returns false if the shader is invalid.
+    pub fn dispatch(
+        &self,
+        encoder: &mut CommandEncoder,
+        bind_groups: &[&BindGroup],
+        x: u32,
+        y: u32,
+        z: u32,
+    ) -> bool {
+        let Some(pipeline) = &self.pipeline else {
+            return false;
+        };
+
+        let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor {
+            label: Some(&self.name),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(pipeline);
+        for (index, bind_group) in bind_groups.iter().enumerate() {
+            pass.set_bind_group(index as u32, Some(*bind_group), &[]);
+        }
+        pass.dispatch_workgroups(x, y, z);
+
+        true
+    }
+}
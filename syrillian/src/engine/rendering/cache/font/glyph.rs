@@ -1,5 +1,6 @@
 use crate::rendering::FontAtlas;
 use crate::rendering::msdf_atlas::{FontLineMetrics, GlyphAtlasEntry};
+use crate::rendering::rich_text::{GlyphStyle, StyledRun};
 use crate::rendering::strobe::TextAlignment;
 use nalgebra::Vector2;
 use static_assertions::{const_assert, const_assert_eq};
@@ -10,6 +11,8 @@ use ttf_parser::Face;
 pub struct GlyphVertex {
     pos: [f32; 2],
     uv: [f32; 2],
+    /// The alpha is 1 if the glyph has its own color, otherwise the text color is used
+    color: [f32; 4],
 }
 
 pub const GLYPH_TRIANGLE_COUNT: usize = 2;
@@ -26,7 +29,7 @@ impl GlyphRenderData {
     pub const VERTEX_COUNT: usize = GLYPH_VERTEX_COUNT;
 }
 
-const_assert_eq!(size_of::<GlyphVertex>(), size_of::<[f32; 4]>());
+const_assert_eq!(size_of::<GlyphVertex>(), size_of::<[f32; 8]>());
 const_assert_eq!(align_of::<GlyphVertex>(), align_of::<f32>());
 const_assert_eq!(
     size_of::<GlyphRenderData>(),
@@ -47,30 +50,38 @@ pub struct GlyphBitmap {
 }
 
 impl GlyphRenderData {
-    fn from_entry(origin_em: Vector2<f32>, entry: &GlyphAtlasEntry) -> Self {
-        let l = origin_em.x + entry.plane_min[0];
-        let r = origin_em.x + entry.plane_max[0];
-        let b = origin_em.y + entry.plane_min[1];
-        let t = origin_em.y + entry.plane_max[1];
+    fn from_entry(origin_em: Vector2<f32>, entry: &GlyphAtlasEntry, style: GlyphStyle) -> Self {
+        let l = origin_em.x + entry.plane_min[0] * style.scale;
+        let r = origin_em.x + entry.plane_max[0] * style.scale;
+        let b = origin_em.y + entry.plane_min[1] * style.scale;
+        let t = origin_em.y + entry.plane_max[1] * style.scale;
 
         let uv_min = entry.uv_min;
         let uv_max = entry.uv_max;
+        let color = match style.color {
+            Some(c) => [c.x, c.y, c.z, 1.0],
+            None => [0.0; 4],
+        };
 
         let v_tl = GlyphVertex {
             pos: [l, t],
             uv: [uv_min[0], uv_min[1]],
+            color,
         };
         let v_tr = GlyphVertex {
             pos: [r, t],
             uv: [uv_max[0], uv_min[1]],
+            color,
         };
         let v_bl = GlyphVertex {
             pos: [l, b],
             uv: [uv_min[0], uv_max[1]],
+            color,
         };
         let v_br = GlyphVertex {
             pos: [r, b],
             uv: [uv_max[0], uv_max[1]],
+            color,
         };
 
         Self {
@@ -135,6 +146,33 @@ pub fn generate_glyph_geometry_stream(
     alignment: TextAlignment,
     line_height_mul: f32,
     letter_spacing_em: f32,
+) -> Vec<GlyphRenderData> {
+    let chars: Vec<_> = text.chars().map(|c| (c, GlyphStyle::default())).collect();
+    generate_styled_geometry(&chars, atlas, alignment, line_height_mul, letter_spacing_em)
+}
+
+/// Like [`generate_glyph_geometry_stream`], with the color and size of every run applied to
+/// its glyphs. A line is moved down further if it contains larger text.
+pub fn generate_rich_glyph_geometry_stream(
+    runs: &[StyledRun],
+    atlas: &FontAtlas,
+    alignment: TextAlignment,
+    line_height_mul: f32,
+    letter_spacing_em: f32,
+) -> Vec<GlyphRenderData> {
+    let chars: Vec<_> = runs
+        .iter()
+        .flat_map(|run| run.text.chars().map(|c| (c, run.style())))
+        .collect();
+    generate_styled_geometry(&chars, atlas, alignment, line_height_mul, letter_spacing_em)
+}
+
+fn generate_styled_geometry(
+    text: &[(char, GlyphStyle)],
+    atlas: &FontAtlas,
+    alignment: TextAlignment,
+    line_height_mul: f32,
+    letter_spacing_em: f32,
 ) -> Vec<GlyphRenderData> {
     if text.is_empty() {
         return vec![];
@@ -163,7 +201,7 @@ fn baseline_step(metrics: FontLineMetrics, line_height_mul: f32) -> f32 {
 }
 
 fn layout_text_lines(
-    text: &str,
+    text: &[(char, GlyphStyle)],
     atlas: &FontAtlas,
     baseline_dy: f32,
     face: Option<&Face<'_>>,
@@ -176,11 +214,14 @@ fn layout_text_lines(
     let mut row_glyphs = 0usize;
     let mut row_width_em = 0.0f32;
     let mut prev_char: Option<char> = None;
+    // the first line starts at the top, so only the following ones are scaled
+    let mut next_line_scales = text.split(|(c, _)| *c == '\n').map(line_scale).skip(1);
 
-    for ch in text.chars() {
+    for &(ch, style) in text {
         if ch == '\n' {
             push_row(&mut row_data, row_glyphs, row_width_em);
-            begin_new_line(&mut cursor, baseline_dy);
+            let row_scale = next_line_scales.next().unwrap_or(1.0);
+            begin_new_line(&mut cursor, baseline_dy * row_scale);
             row_glyphs = 0;
             row_width_em = 0.0;
             prev_char = None;
@@ -192,21 +233,21 @@ fn layout_text_lines(
         }
 
         if let (Some(prev), Some(face_ref)) = (prev_char, face) {
-            let kern = kerning_adjustment(face_ref, prev, ch, units_per_em);
+            let kern = kerning_adjustment(face_ref, prev, ch, units_per_em) * style.scale;
             if kern.abs() > f32::EPSILON {
                 cursor.x += kern;
             }
         }
 
         if let Some(entry) = glyph_entry(atlas, ch) {
-            quads.push(GlyphRenderData::from_entry(cursor, &entry));
-            cursor.x += entry.advance_em;
+            quads.push(GlyphRenderData::from_entry(cursor, &entry, style));
+            cursor.x += entry.advance_em * style.scale;
             row_width_em = row_width_em.max(cursor.x);
             row_glyphs += 1;
         } else if let Some(face_ref) = face
             && let Some(advance) = advance_from_face(face_ref, ch, units_per_em)
         {
-            cursor.x += advance;
+            cursor.x += advance * style.scale;
             row_width_em = row_width_em.max(cursor.x);
         }
 
@@ -217,6 +258,14 @@ fn layout_text_lines(
     (quads, row_data)
 }
 
+/// The largest glyph scale on a line, which its line height is scaled by
+fn line_scale(line: &[(char, GlyphStyle)]) -> f32 {
+    line.iter()
+        .map(|(_, style)| style.scale)
+        .reduce(f32::max)
+        .unwrap_or(1.0)
+}
+
 fn push_row(rows: &mut Vec<(usize, f32)>, glyphs: usize, width_em: f32) {
     rows.push((glyphs, width_em));
 }
@@ -21,6 +21,7 @@ use fdsm::bezier::prepared::PreparedColoredShape;
 
 pub mod glyph;
 pub mod msdf_atlas;
+pub mod rich_text;
 
 pub struct FontAtlas {
     atlas: Arc<RwLock<MsdfAtlas>>,
@@ -35,7 +36,7 @@ pub struct FontAtlas {
     pending: RwLock<std::collections::VecDeque<char>>,
 
     #[cfg(target_arch = "wasm32")]
-    wasm_face_bytes: Arc<Vec<u8>>,
+    wasm_faces: Vec<Arc<Vec<u8>>>,
     #[cfg(target_arch = "wasm32")]
     wasm_units_per_em: f32,
     #[cfg(target_arch = "wasm32")]
@@ -52,17 +53,24 @@ impl CacheType for Font {
             self.font_bytes.clone(),
             self.atlas_em_px,
             16.0,
-            4.0,
+            self.msdf_range as f64,
             cache.store(),
         );
         let atlas = Arc::new(RwLock::new(msdf));
 
+        let fallback_faces = self
+            .fallbacks
+            .iter()
+            .filter_map(|font| cache.store().fonts.try_get(*font))
+            .map(|font| font.font_bytes.clone())
+            .filter(|bytes| !Arc::ptr_eq(bytes, &self.font_bytes));
+
         #[cfg(not(target_arch = "wasm32"))]
-        let (gen_tx, ready_rx) = spawn_native_worker(&atlas);
+        let (gen_tx, ready_rx) = spawn_native_worker(&atlas, fallback_faces);
 
         #[cfg(target_arch = "wasm32")]
-        let (pending, wasm_face_bytes, wasm_units_per_em, wasm_shrinkage, wasm_range) =
-            prepare_wasm_state(&atlas);
+        let (pending, wasm_faces, wasm_units_per_em, wasm_shrinkage, wasm_range) =
+            prepare_wasm_state(&atlas, fallback_faces);
 
         FontAtlas {
             atlas,
@@ -76,7 +84,7 @@ impl CacheType for Font {
             #[cfg(target_arch = "wasm32")]
             pending,
             #[cfg(target_arch = "wasm32")]
-            wasm_face_bytes,
+            wasm_faces,
             #[cfg(target_arch = "wasm32")]
             wasm_units_per_em,
             #[cfg(target_arch = "wasm32")]
@@ -98,6 +106,11 @@ impl FontAtlas {
         self.atlas.read().unwrap().metrics()
     }
 
+    /// The distance range of the glyphs in atlas pixels, which the text shaders need to know
+    pub fn msdf_range(&self) -> f32 {
+        self.atlas.read().unwrap().range() as f32
+    }
+
     pub fn face_data(&self) -> (Arc<Vec<u8>>, f32) {
         let atlas = self.atlas.read().unwrap();
         let (bytes, units_per_em, ..) = atlas.font_params();
@@ -140,7 +153,7 @@ impl FontAtlas {
             };
 
             if let Some(bmp) = rasterize_msdf_glyph(
-                &self.wasm_face_bytes,
+                &self.wasm_faces,
                 ch,
                 self.wasm_shrinkage,
                 self.wasm_range,
@@ -192,14 +205,18 @@ impl FontAtlas {
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-fn spawn_native_worker(atlas: &Arc<RwLock<MsdfAtlas>>) -> (Sender<char>, Receiver<GlyphBitmap>) {
+fn spawn_native_worker(
+    atlas: &Arc<RwLock<MsdfAtlas>>,
+    fallback_faces: impl Iterator<Item = Arc<Vec<u8>>>,
+) -> (Sender<char>, Receiver<GlyphBitmap>) {
     let (tx_req, rx_req) = unbounded();
     let (tx_ready, rx_ready) = unbounded();
     let (face_bytes, units_per_em, shrinkage, range) = atlas.read().unwrap().font_params();
+    let faces: Vec<_> = std::iter::once(face_bytes).chain(fallback_faces).collect();
 
     std::thread::spawn(move || {
         while let Ok(ch) = rx_req.recv() {
-            if let Some(bmp) = rasterize_msdf_glyph(&face_bytes, ch, shrinkage, range, units_per_em)
+            if let Some(bmp) = rasterize_msdf_glyph(&faces, ch, shrinkage, range, units_per_em)
                 && tx_ready.send(bmp).is_err()
             {
                 break;
@@ -213,33 +230,53 @@ fn spawn_native_worker(atlas: &Arc<RwLock<MsdfAtlas>>) -> (Sender<char>, Receive
 #[cfg(target_arch = "wasm32")]
 fn prepare_wasm_state(
     atlas: &Arc<RwLock<MsdfAtlas>>,
+    fallback_faces: impl Iterator<Item = Arc<Vec<u8>>>,
 ) -> (
     RwLock<std::collections::VecDeque<char>>,
-    Arc<Vec<u8>>,
+    Vec<Arc<Vec<u8>>>,
     f32,
     f64,
     f64,
 ) {
     let (fb, upm, s, r) = atlas.read().unwrap().font_params();
-    (RwLock::default(), fb, upm, s, r)
+    let faces = std::iter::once(fb).chain(fallback_faces).collect();
+    (RwLock::default(), faces, upm, s, r)
 }
 
+/// Rasterizes `ch` from the first of `faces` that contains it. The first face is the primary
+/// font, which the shrinkage is relative to.
 fn rasterize_msdf_glyph(
-    face_bytes: &Arc<Vec<u8>>,
+    faces: &[Arc<Vec<u8>>],
     ch: char,
     shrinkage: f64,
     range: f64,
-    metrics_units_per_em: f32,
+    primary_units_per_em: f32,
 ) -> Option<GlyphBitmap> {
-    let face = Face::parse(face_bytes, 0).ok()?;
-    let gid = face.glyph_index(ch)?;
+    faces.iter().find_map(|face_bytes| {
+        let face = Face::parse(face_bytes, 0).ok()?;
+        let gid = face.glyph_index(ch)?;
+
+        // glyphs of faces with a different em size still need the same pixels per em
+        let units_per_em = face.units_per_em() as f32;
+        let shrinkage = shrinkage * (units_per_em / primary_units_per_em) as f64;
+        rasterize_face_glyph(&face, gid, ch, shrinkage, range, units_per_em)
+    })
+}
 
-    let bbox = glyph_bounds(&face, gid);
+fn rasterize_face_glyph(
+    face: &Face,
+    gid: ttf_parser::GlyphId,
+    ch: char,
+    shrinkage: f64,
+    range: f64,
+    metrics_units_per_em: f32,
+) -> Option<GlyphBitmap> {
+    let bbox = glyph_bounds(face, gid);
     let plane = plane_bounds(metrics_units_per_em, bbox, shrinkage, range);
     let (width_px, height_px) = glyph_dimensions(bbox, shrinkage, range);
     let transform = glyph_transform(bbox, shrinkage, range);
 
-    let mut shape: Shape<_> = load_shape_from_face(&face, gid)?;
+    let mut shape: Shape<_> = load_shape_from_face(face, gid)?;
     shape.transform(&transform);
     let prepared = Shape::edge_coloring_simple(shape, 0.03, 0xD15EA5u64).prepare();
 
@@ -337,3 +374,68 @@ fn expand_to_rgba(msdf: &RgbImage) -> Vec<u8> {
 
     pixels_rgba
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static NOTO_SANS_REGULAR: &[u8] = include_bytes!("../../../assets/font/NotoSans-Regular.ttf");
+
+    #[test]
+    fn glyphs_missing_in_the_primary_face_are_rasterized_from_fallbacks() {
+        let noto = Arc::new(NOTO_SANS_REGULAR.to_vec());
+        let units_per_em = Face::parse(&noto, 0).unwrap().units_per_em() as f32;
+        // a face that can't be read has no glyphs at all
+        let empty = Arc::new(Vec::new());
+        let rasterize =
+            |faces: &[Arc<Vec<u8>>], ch| rasterize_msdf_glyph(faces, ch, 16.0, 4.0, units_per_em);
+
+        assert!(rasterize(&[empty.clone()], 'A').is_none());
+        let direct = rasterize(&[noto.clone()], 'A').unwrap();
+        let fallback = rasterize(&[empty, noto.clone()], 'A').unwrap();
+        assert_eq!(fallback.ch, 'A');
+        assert_eq!(
+            (fallback.width_px, fallback.height_px),
+            (direct.width_px, direct.height_px)
+        );
+        assert_eq!(fallback.pixels_rgba, direct.pixels_rgba);
+
+        // noto sans has no CJK glyphs, and neither do its fallbacks here
+        assert!(rasterize(&[noto.clone(), noto], '\u{4E2D}').is_none());
+    }
+
+    #[test]
+    fn magnified_msdf_glyphs_keep_sharp_edges() {
+        let noto = Arc::new(NOTO_SANS_REGULAR.to_vec());
+        let units_per_em = Face::parse(&noto, 0).unwrap().units_per_em() as f32;
+        let (shrinkage, range) = (16.0, 4.0);
+        let glyph = rasterize_msdf_glyph(&[noto], 'I', shrinkage, range, units_per_em).unwrap();
+
+        // 'I' is a single stem, so the middle row crosses exactly two edges
+        let width = glyph.width_px as usize;
+        let row_start = glyph.height_px as usize / 2 * width * 4;
+        let row = &glyph.pixels_rgba[row_start..row_start + width * 4];
+        let channel = |x: usize, c: usize| row[x * 4 + c] as f32 / 255.0;
+
+        // the coverage msdf.wgsl computes for 200px text from the same atlas entry
+        let scale = 200.0 / (units_per_em / shrinkage as f32);
+        let screen_px_range = range as f32 * scale;
+        let alpha = |u: f32| {
+            let x0 = (u.floor().max(0.0) as usize).min(width - 1);
+            let x1 = (x0 + 1).min(width - 1);
+            let t = (u - x0 as f32).clamp(0.0, 1.0);
+            let [r, g, b] = [0, 1, 2].map(|c| channel(x0, c) * (1.0 - t) + channel(x1, c) * t);
+            let median = r.min(g).max(r.max(g).min(b));
+            (screen_px_range * (median - 0.5) + 0.5).clamp(0.0, 1.0)
+        };
+
+        let coverage: Vec<f32> = (0..(width as f32 * scale) as usize)
+            .map(|s| alpha((s as f32 + 0.5) / scale - 0.5))
+            .collect();
+        let solid = coverage.iter().filter(|&&a| a > 0.99).count();
+        let blurred = coverage.iter().filter(|&&a| a > 0.01 && a <= 0.99).count();
+        assert!(solid > 0);
+        // each edge blends over at most two screen pixels, however far it's magnified
+        assert!(blurred <= 4, "{blurred} pixels are blurred");
+    }
+}
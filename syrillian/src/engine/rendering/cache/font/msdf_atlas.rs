@@ -114,6 +114,11 @@ impl MsdfAtlas {
         )
     }
 
+    /// The distance range of the glyphs in atlas pixels
+    pub fn range(&self) -> f64 {
+        self.range
+    }
+
     pub(crate) fn integrate_ready_glyph(
         &mut self,
         cache: &AssetCache,
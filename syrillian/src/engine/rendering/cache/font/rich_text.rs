@@ -0,0 +1,166 @@
+//! Styled text runs and the markup they're parsed from.
+//!
+//! The markup only knows two tags, which can be nested:
+//! - `[color=#ff0000]red[/color]` colors the text, `#rgb` and `#rrggbb` are accepted
+//! - `[size=1.5]big[/size]` multiplies the text size
+//!
+//! Anything else in brackets, and tags without a matching opening tag, are kept as text.
+
+use nalgebra::Vector3;
+
+/// A piece of text with its own style
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyledRun {
+    pub text: String,
+    /// Replaces the color of the text. `None` uses the color of the whole text.
+    pub color: Option<Vector3<f32>>,
+    /// Multiplies the size of the whole text
+    pub scale: f32,
+}
+
+/// The style of a single glyph, as it's used for layouting
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GlyphStyle {
+    pub color: Option<Vector3<f32>>,
+    pub scale: f32,
+}
+
+impl Default for GlyphStyle {
+    fn default() -> Self {
+        GlyphStyle {
+            color: None,
+            scale: 1.0,
+        }
+    }
+}
+
+impl StyledRun {
+    pub fn new(text: impl Into<String>) -> Self {
+        StyledRun {
+            text: text.into(),
+            color: None,
+            scale: 1.0,
+        }
+    }
+
+    pub fn color(mut self, color: Vector3<f32>) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn style(&self) -> GlyphStyle {
+        GlyphStyle {
+            color: self.color,
+            scale: self.scale,
+        }
+    }
+}
+
+enum Tag {
+    Color(Vector3<f32>),
+    Size(f32),
+}
+
+/// Splits `markup` into runs, see the [module docs](self) for the syntax
+pub fn parse_rich_text(markup: &str) -> Vec<StyledRun> {
+    let mut runs = Vec::new();
+    let mut colors: Vec<Vector3<f32>> = Vec::new();
+    let mut scales: Vec<f32> = Vec::new();
+    let mut current = String::new();
+    let mut rest = markup;
+
+    while let Some(open) = rest.find('[') {
+        current.push_str(&rest[..open]);
+        rest = &rest[open..];
+
+        let Some(close) = rest.find(']') else {
+            break;
+        };
+        let tag = &rest[1..close];
+
+        let known = match tag {
+            "/color" => !colors.is_empty(),
+            "/size" => !scales.is_empty(),
+            _ => parse_tag(tag).is_some(),
+        };
+        if !known {
+            current.push('[');
+            rest = &rest[1..];
+            continue;
+        }
+
+        push_run(&mut runs, &mut current, &colors, &scales);
+        match tag {
+            "/color" => {
+                colors.pop();
+            }
+            "/size" => {
+                scales.pop();
+            }
+            _ => match parse_tag(tag) {
+                Some(Tag::Color(color)) => colors.push(color),
+                Some(Tag::Size(scale)) => scales.push(scale),
+                None => {}
+            },
+        }
+        rest = &rest[close + 1..];
+    }
+
+    current.push_str(rest);
+    push_run(&mut runs, &mut current, &colors, &scales);
+    runs
+}
+
+fn push_run(runs: &mut Vec<StyledRun>, text: &mut String, colors: &[Vector3<f32>], scales: &[f32]) {
+    if text.is_empty() {
+        return;
+    }
+
+    runs.push(StyledRun {
+        text: std::mem::take(text),
+        color: colors.last().copied(),
+        scale: scales.iter().product(),
+    });
+}
+
+fn parse_tag(tag: &str) -> Option<Tag> {
+    let (name, value) = tag.split_once('=')?;
+    match name.trim() {
+        "color" => parse_hex_color(value.trim()).map(Tag::Color),
+        "size" => value
+            .trim()
+            .parse::<f32>()
+            .ok()
+            .filter(|scale| scale.is_finite() && *scale > 0.0)
+            .map(Tag::Size),
+        _ => None,
+    }
+}
+
+fn parse_hex_color(value: &str) -> Option<Vector3<f32>> {
+    let hex = value.strip_prefix('#')?;
+    if !hex.is_ascii() {
+        return None;
+    }
+
+    let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+    let (r, g, b) = match hex.len() {
+        3 => {
+            let short = |i: usize| channel(&hex[i..i + 1]).map(|c| c * 17);
+            (short(0)?, short(1)?, short(2)?)
+        }
+        6 => (
+            channel(&hex[0..2])?,
+            channel(&hex[2..4])?,
+            channel(&hex[4..6])?,
+        ),
+        _ => return None,
+    };
+
+    Some(Vector3::new(r, g, b).cast::<f32>() / 255.0)
+}
@@ -88,6 +88,10 @@ impl<T: CacheType> Cache<T> {
         dirty.len()
     }
 
+    pub fn invalidate_all(&self) {
+        self.data.clear();
+    }
+
     fn try_refresh_item(
         &self,
         h: H<T>,
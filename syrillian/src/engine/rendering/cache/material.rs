@@ -1,5 +1,5 @@
 use crate::assets::HShader;
-use crate::engine::assets::{HTexture, Material};
+use crate::engine::assets::{HTexture, MAX_CUSTOM_PARAMS, Material};
 use crate::engine::rendering::cache::{AssetCache, CacheType};
 use crate::engine::rendering::uniform::ShaderUniform;
 use crate::ensure_aligned;
@@ -16,24 +16,34 @@ pub(crate) enum MaterialUniformIndex {
     DiffuseSampler = 2,
     NormalView = 3,
     NormalSampler = 4,
-    RoughnessView = 5,
-    RoughnessSampler = 6,
+    MetallicRoughnessView = 5,
+    MetallicRoughnessSampler = 6,
+    CustomParams = 7,
 }
 
+/// Bound by materials that don't have custom parameters, like decals
+pub(crate) const NO_CUSTOM_PARAMS: [f32; MAX_CUSTOM_PARAMS] = [0.0; MAX_CUSTOM_PARAMS];
+
 bitflags! {
     #[repr(C)]
     #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
     pub struct MaterialParams: u32 {
-        const use_diffuse_texture   = 1;
-        const use_normal_texture    = 1 << 1;
-        const use_roughness_texture = 1 << 2;
-        const lit                   = 1 << 3;
-        const cast_shadows          = 1 << 4;
-        const grayscale_diffuse     = 1 << 5;
-        const has_transparency      = 1 << 6;
+        const use_diffuse_texture            = 1;
+        const use_normal_texture             = 1 << 1;
+        const use_metallic_roughness_texture = 1 << 2;
+        const lit                            = 1 << 3;
+        const cast_shadows                   = 1 << 4;
+        const grayscale_diffuse              = 1 << 5;
+        const has_transparency               = 1 << 6;
     }
 }
 
+#[allow(non_upper_case_globals)]
+impl MaterialParams {
+    #[deprecated(note = "use `use_metallic_roughness_texture`")]
+    pub const use_roughness_texture: Self = Self::use_metallic_roughness_texture;
+}
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct MaterialUniform {
@@ -73,8 +83,8 @@ impl CacheType for Material {
         if self.normal_texture.is_some() {
             params |= MaterialParams::use_normal_texture;
         }
-        if self.roughness_texture.is_some() {
-            params |= MaterialParams::use_roughness_texture;
+        if self.metallic_roughness_texture.is_some() {
+            params |= MaterialParams::use_metallic_roughness_texture;
         }
 
         let diffuse = cache.texture_opt(self.diffuse_texture, HTexture::FALLBACK_DIFFUSE);
@@ -104,17 +114,33 @@ impl CacheType for Material {
 
         let mat_bgl = cache.bgl_material();
         let normal = cache.texture_opt(self.normal_texture, HTexture::FALLBACK_NORMAL);
-        let roughness = cache.texture_opt(self.roughness_texture, HTexture::FALLBACK_ROUGHNESS);
+        let metallic_roughness = cache.texture_opt(
+            self.metallic_roughness_texture,
+            HTexture::FALLBACK_ROUGHNESS,
+        );
+
+        let anisotropy = match self.anisotropy {
+            Some(level) => cache.clamp_anisotropy(level),
+            None => cache.anisotropy(),
+        };
+        let diffuse_sampler = diffuse.anisotropic_sampler(device, anisotropy);
+        let normal_sampler = normal.anisotropic_sampler(device, anisotropy);
+        let metallic_roughness_sampler = metallic_roughness.anisotropic_sampler(device, anisotropy);
 
         // TODO: Add additional material mapping properties and such
         let uniform = ShaderUniform::<MaterialUniformIndex>::builder(&mat_bgl)
             .with_buffer_data(&data)
             .with_texture(&diffuse.view)
-            .with_sampler(&diffuse.sampler)
+            .with_sampler(diffuse_sampler.as_ref().unwrap_or(&diffuse.sampler))
             .with_texture(&normal.view)
-            .with_sampler(&normal.sampler)
-            .with_texture(&roughness.view)
-            .with_sampler(&roughness.sampler)
+            .with_sampler(normal_sampler.as_ref().unwrap_or(&normal.sampler))
+            .with_texture(&metallic_roughness.view)
+            .with_sampler(
+                metallic_roughness_sampler
+                    .as_ref()
+                    .unwrap_or(&metallic_roughness.sampler),
+            )
+            .with_buffer_data(self.custom_params())
             .build(device);
 
         RuntimeMaterial {
@@ -134,8 +160,14 @@ impl MaterialUniform {
         self.params.contains(MaterialParams::use_normal_texture)
     }
 
+    pub fn has_metallic_roughness_texture(&self) -> bool {
+        self.params
+            .contains(MaterialParams::use_metallic_roughness_texture)
+    }
+
+    #[deprecated(note = "use `has_metallic_roughness_texture`")]
     pub fn has_roughness_texture(&self) -> bool {
-        self.params.contains(MaterialParams::use_roughness_texture)
+        self.has_metallic_roughness_texture()
     }
 
     pub fn is_lit(&self) -> bool {
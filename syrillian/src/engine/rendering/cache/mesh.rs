@@ -2,6 +2,7 @@ use crate::core::Vertex3D;
 use crate::engine::assets::Mesh;
 use crate::engine::rendering::cache::AssetCache;
 use crate::engine::rendering::cache::generic_cache::CacheType;
+use crate::engine::rendering::stats::FrameStats;
 use more_asserts::debug_assert_le;
 use std::ops::Range;
 use syrillian_utils::debug_panic;
@@ -40,7 +41,7 @@ impl Meshlet {
         }
     }
 
-    pub fn draw(&self, range: Range<u32>, pass: &mut wgpu::RenderPass<'_>) {
+    pub fn draw(&self, range: Range<u32>, pass: &mut wgpu::RenderPass<'_>, stats: &FrameStats) {
         let Some(inner_range) = self.clamp_range(range) else {
             debug_panic!("Meshlet received invalid draw command");
             return;
@@ -48,6 +49,7 @@ impl Meshlet {
 
         self.bind(pass);
 
+        stats.record_draw(inner_range.len() as u32, 1);
         if self.has_indices() {
             pass.draw_indexed(inner_range, 0, 0..1);
         } else {
@@ -60,6 +62,7 @@ impl Meshlet {
         range: Range<u32>,
         vertices_range: Range<u32>,
         pass: &mut wgpu::RenderPass<'_>,
+        stats: &FrameStats,
     ) {
         let Some(inner_range) = self.clamp_range(range) else {
             debug_panic!("Meshlet received invalid draw command");
@@ -68,6 +71,7 @@ impl Meshlet {
 
         self.bind(pass);
 
+        stats.record_draw(vertices_range.len() as u32, inner_range.len() as u32);
         if self.has_indices() {
             pass.draw_indexed(vertices_range, 0, inner_range);
         } else {
@@ -132,26 +136,27 @@ impl RuntimeMesh {
         }
     }
 
-    pub fn draw_all(&self, pass: &mut wgpu::RenderPass<'_>) {
-        self.draw(0..self.total_point_count(), pass);
+    pub fn draw_all(&self, pass: &mut wgpu::RenderPass<'_>, stats: &FrameStats) {
+        self.draw(0..self.total_point_count(), pass, stats);
     }
 
     pub fn draw_all_as_instances(
         &self,
         vertices_range: Range<u32>,
         pass: &mut wgpu::RenderPass<'_>,
+        stats: &FrameStats,
     ) {
-        self.draw_as_instances(0..self.total_point_count(), vertices_range, pass);
+        self.draw_as_instances(0..self.total_point_count(), vertices_range, pass, stats);
     }
 
-    pub fn draw(&self, range: Range<u32>, pass: &mut wgpu::RenderPass<'_>) {
+    pub fn draw(&self, range: Range<u32>, pass: &mut wgpu::RenderPass<'_>, stats: &FrameStats) {
         // TODO: Check that meshlets are ordered so that iteration can end when the range passes a meshlet
         for mesh in &self.meshlets {
             if range.end < mesh.offset || range.start > mesh.offset + mesh.point_count() {
                 continue;
             }
 
-            mesh.draw(range.clone(), pass);
+            mesh.draw(range.clone(), pass, stats);
         }
     }
 
@@ -160,13 +165,14 @@ impl RuntimeMesh {
         range: Range<u32>,
         vertices_range: Range<u32>,
         pass: &mut wgpu::RenderPass<'_>,
+        stats: &FrameStats,
     ) {
         for mesh in &self.meshlets {
             if !mesh.applies_to(range.clone()) {
                 continue;
             }
 
-            mesh.draw_as_instances(range.clone(), vertices_range.clone(), pass);
+            mesh.draw_as_instances(range.clone(), vertices_range.clone(), pass, stats);
         }
     }
 
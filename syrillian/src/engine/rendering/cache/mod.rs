@@ -2,14 +2,16 @@ mod generic_cache;
 
 mod asset_cache;
 mod bind_group_layout;
+mod compute_shader;
 mod font;
 mod material;
 mod mesh;
 mod shader;
 mod texture;
 
-pub use self::asset_cache::AssetCache;
+pub use self::asset_cache::{AssetCache, MAX_ANISOTROPY};
 
+pub use self::compute_shader::RuntimeComputeShader;
 pub use self::font::*;
 pub use self::material::*;
 pub use self::mesh::*;
@@ -63,6 +63,8 @@ pub struct RenderPipelineBuilder<'a> {
     pub module: &'a ShaderModule,
     pub is_post_process: bool,
     pub has_depth: bool,
+    pub depth_compare: CompareFunction,
+    pub depth_write: bool,
     pub polygon_mode: PolygonMode,
     pub topology: PrimitiveTopology,
     pub vertex_buffers: &'a [VertexBufferLayout<'a>],
@@ -86,7 +88,11 @@ impl<'a> RenderPipelineBuilder<'a> {
 
     pub fn desc(&'a self) -> RenderPipelineDescriptor<'a> {
         let depth_stencil =
-            (!self.is_post_process && self.has_depth).then_some(DEFAULT_DEPTH_STENCIL);
+            (!self.is_post_process && self.has_depth).then_some(DepthStencilState {
+                depth_compare: self.depth_compare,
+                depth_write_enabled: self.depth_write,
+                ..DEFAULT_DEPTH_STENCIL
+            });
         let cull_mode = (!self.is_custom && !self.is_post_process).then_some(Face::Back);
 
         RenderPipelineDescriptor {
@@ -168,6 +174,8 @@ impl<'a> RenderPipelineBuilder<'a> {
         let is_custom = shader.is_custom();
         let has_shadow_transparency = shader.has_shadow_transparency();
         let has_depth = shader.is_depth_enabled();
+        let depth_compare = shader.depth_compare();
+        let depth_write = shader.has_depth_write();
         let color_target = shader.color_target();
 
         debug_assert!(
@@ -193,6 +201,8 @@ impl<'a> RenderPipelineBuilder<'a> {
             module,
             is_post_process,
             has_depth,
+            depth_compare,
+            depth_write,
             is_custom,
             has_shadow_transparency,
             polygon_mode,
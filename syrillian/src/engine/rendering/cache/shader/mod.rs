@@ -1,9 +1,10 @@
-use crate::assets::ShaderType;
-use crate::engine::assets::{BindGroupMap, Shader, ShaderGen};
+use crate::assets::{HShader, ShaderType};
+use crate::engine::assets::{BindGroupMap, Shader};
 use crate::engine::rendering::cache::AssetCache;
 use crate::engine::rendering::cache::generic_cache::CacheType;
 use crate::rendering::{GPUDrawCtx, RenderPassType, RenderPipelineBuilder};
 use std::borrow::Cow;
+use tracing::error;
 use wgpu::*;
 
 pub mod builder;
@@ -22,47 +23,82 @@ pub struct RuntimeShader {
 impl CacheType for Shader {
     type Hot = RuntimeShader;
 
-    fn upload(self, device: &Device, _queue: &Queue, cache: &AssetCache) -> Self::Hot {
-        let bind_groups = self.bind_group_map();
-        let code = ShaderGen::new(&self, &bind_groups).generate();
-
-        debug_assert!(
-            code.contains("@fragment"),
-            "No fragment entry point in shader {:?}: \n{code}",
-            self.name()
-        );
-        debug_assert!(
-            code.contains("@vertex"),
-            "No vertex entry point in shader {:?}: \n{code}",
-            self.name()
-        );
-
-        let module = device.create_shader_module(ShaderModuleDescriptor {
-            label: Some(self.name()),
-            source: ShaderSource::Wgsl(Cow::Owned(code)),
-        });
-        let name = self.name().to_string();
-
-        let solid_layout = self.solid_layout(device, cache);
-        let solid_builder = RenderPipelineBuilder::builder(&self, &solid_layout, &module);
-        let pipeline = solid_builder.build(device);
-        let shadow_pipeline = self.shadow_layout(device, cache).and_then(|layout| {
-            let shadow_builder = RenderPipelineBuilder::builder(&self, &layout, &module);
-            shadow_builder.build_shadow(device)
-        });
-
-        RuntimeShader {
-            name,
-            module,
-            pipeline,
-            shadow_pipeline,
-            immediate_size: self.immediate_size(),
-            bind_groups,
-            shader_type: self.stage(),
+    fn upload(self, device: &Device, queue: &Queue, cache: &AssetCache) -> Self::Hot {
+        match self.validated_with(&cache.store().shader_includes) {
+            Ok((expanded, code)) => create_runtime_shader(&expanded, code, device, cache),
+            Err(e) => {
+                error!("{e}");
+                let (fallback, code) = fallback_for(&self, cache);
+                create_runtime_shader(&fallback, code, device, cache)
+            }
         }
     }
 }
 
+/// Picks the shader drawn in place of an invalid one, and returns it with its generated code
+fn fallback_for(shader: &Shader, cache: &AssetCache) -> (Shader, String) {
+    let builtin = match shader.stage() {
+        ShaderType::Default => Some(HShader::FALLBACK),
+        ShaderType::PostProcessing => Some(HShader::POST_PROCESS),
+        // custom shaders can have any layout, so no built-in shader fits them
+        ShaderType::Custom => None,
+    };
+
+    if let Some(builtin) = builtin {
+        let fallback = cache.store().shaders.get(builtin).clone();
+        // the fallback could've been replaced with broken code too
+        if let Ok(valid) = fallback.validated_with(&cache.store().shader_includes) {
+            return valid;
+        }
+    }
+
+    let fallback = shader.invisible_fallback();
+    let code = fallback.gen_code();
+    (fallback, code)
+}
+
+fn create_runtime_shader(
+    shader: &Shader,
+    code: String,
+    device: &Device,
+    cache: &AssetCache,
+) -> RuntimeShader {
+    debug_assert!(
+        code.contains("@fragment"),
+        "No fragment entry point in shader {:?}: \n{code}",
+        shader.name()
+    );
+    debug_assert!(
+        code.contains("@vertex"),
+        "No vertex entry point in shader {:?}: \n{code}",
+        shader.name()
+    );
+
+    let module = device.create_shader_module(ShaderModuleDescriptor {
+        label: Some(shader.name()),
+        source: ShaderSource::Wgsl(Cow::Owned(code)),
+    });
+    let name = shader.name().to_string();
+
+    let solid_layout = shader.solid_layout(device, cache);
+    let solid_builder = RenderPipelineBuilder::builder(shader, &solid_layout, &module);
+    let pipeline = solid_builder.build(device);
+    let shadow_pipeline = shader.shadow_layout(device, cache).and_then(|layout| {
+        let shadow_builder = RenderPipelineBuilder::builder(shader, &layout, &module);
+        shadow_builder.build_shadow(device)
+    });
+
+    RuntimeShader {
+        name,
+        module,
+        pipeline,
+        shadow_pipeline,
+        immediate_size: shader.immediate_size(),
+        bind_groups: shader.bind_group_map(),
+        shader_type: shader.stage(),
+    }
+}
+
 impl RuntimeShader {
     pub fn solid_pipeline(&self) -> &RenderPipeline {
         &self.pipeline
@@ -77,7 +113,9 @@ impl RuntimeShader {
             RenderPassType::Color
             | RenderPassType::Color2D
             | RenderPassType::Picking
-            | RenderPassType::PickingUi => Some(&self.pipeline),
+            | RenderPassType::PickingUi
+            | RenderPassType::Decal
+            | RenderPassType::GBuffer => Some(&self.pipeline),
             RenderPassType::Shadow => self.shadow_pipeline.as_ref(),
         }
     }
@@ -97,6 +135,12 @@ impl RuntimeShader {
         if let Some(shadow) = self.bind_groups.shadow {
             pass.set_bind_group(shadow, ctx.shadow_bind_group, &[]);
         }
+        if let Some(depth) = self.bind_groups.depth {
+            let Some(depth_bind_group) = ctx.depth_bind_group else {
+                return false;
+            };
+            pass.set_bind_group(depth, depth_bind_group, &[]);
+        }
 
         true
     }
@@ -1,7 +1,10 @@
 use crate::engine::assets::Texture as CpuTexture;
 use crate::engine::rendering::cache::{AssetCache, CacheType};
 use wgpu::util::{DeviceExt, TextureDataOrder};
-use wgpu::{Device, Extent3d, Queue, Sampler, Texture as WgpuTexture, TextureFormat, TextureView};
+use wgpu::{
+    Device, Extent3d, FilterMode, MipmapFilterMode, Queue, Sampler, SamplerDescriptor,
+    Texture as WgpuTexture, TextureFormat, TextureView,
+};
 
 #[derive(Debug)]
 pub struct GpuTexture {
@@ -11,6 +14,30 @@ pub struct GpuTexture {
     pub size: Extent3d,
     pub format: TextureFormat,
     pub has_transparency: bool,
+    pub(crate) sampler_desc: SamplerDescriptor<'static>,
+}
+
+impl GpuTexture {
+    /// Creates a variant of this texture's sampler with anisotropic filtering enabled.
+    ///
+    /// Returns `None` if the level is 1 (off) or the sampler isn't fully linear, which anisotropic
+    /// filtering requires. In that case the default [`GpuTexture::sampler`] should be used.
+    pub fn anisotropic_sampler(&self, device: &Device, level: u16) -> Option<Sampler> {
+        let desc = &self.sampler_desc;
+        let is_linear = desc.mag_filter == FilterMode::Linear
+            && desc.min_filter == FilterMode::Linear
+            && desc.mipmap_filter == MipmapFilterMode::Linear;
+
+        if level <= 1 || !is_linear {
+            return None;
+        }
+
+        Some(device.create_sampler(&SamplerDescriptor {
+            label: Some("Anisotropic Sampler"),
+            anisotropy_clamp: level,
+            ..desc.clone()
+        }))
+    }
 }
 
 impl CacheType for CpuTexture {
@@ -27,7 +54,8 @@ impl CacheType for CpuTexture {
         };
 
         let view = texture.create_view(&self.view_desc());
-        let sampler = device.create_sampler(&self.sampler_desc());
+        let sampler_desc = self.sampler_desc();
+        let sampler = device.create_sampler(&sampler_desc);
 
         GpuTexture {
             texture,
@@ -36,6 +64,7 @@ impl CacheType for CpuTexture {
             size: desc.size,
             format: desc.format,
             has_transparency: self.has_transparency,
+            sampler_desc,
         }
     }
 }
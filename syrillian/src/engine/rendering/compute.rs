@@ -0,0 +1,422 @@
+//! Dispatching compute shaders from the game thread.
+//!
+//! A [`ComputeJob`] collects the data for one dispatch and is sent to the renderer with
+//! [`World::dispatch_compute`](crate::World::dispatch_compute). The renderer runs all jobs at the
+//! start of its next frame, and the results of read-back buffers show up in the
+//! [`ComputeReadback`] once the GPU is done. The renderer doesn't wait for that, so the results
+//! usually arrive a frame or two later.
+//!
+//! The buffers of a job only live for its dispatch. Feeding results into a vertex buffer on the
+//! GPU isn't supported, since that needs buffers that outlive a job. Results that should be drawn
+//! have to go through a read-back and be uploaded again, e.g. as a mesh.
+//!
+//! ```rust
+//! use syrillian::assets::ComputeShader;
+//! use syrillian::rendering::ComputeJob;
+//! use syrillian::World;
+//!
+//! const DOUBLE: &str = "
+//! @group(0) @binding(0) var<storage, read_write> values: array<f32>;
+//!
+//! @compute @workgroup_size(64)
+//! fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+//!     if id.x < arrayLength(&values) {
+//!         values[id.x] *= 2.0;
+//!     }
+//! }
+//! ";
+//!
+//! fn start(world: &mut World) {
+//!     let shader = world.assets.compute.add(ComputeShader::new("Double", DOUBLE));
+//!     let values = [1.0f32; 256];
+//!     let job = ComputeJob::new(shader, 256 / 64, 1, 1).storage_read_back(&values);
+//!     let readback = world.dispatch_compute(job);
+//!
+//!     // a few frames later
+//!     if let Some(output) = readback.try_take() {
+//!         let doubled: Vec<f32> = output.cast(0).unwrap();
+//!     }
+//! }
+//! ```
+
+use crate::assets::HComputeShader;
+use crate::rendering::AssetCache;
+use bytemuck::Pod;
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+use wgpu::util::{BufferInitDescriptor, DeviceExt};
+use wgpu::{
+    Buffer, BufferDescriptor, BufferUsages, COPY_BUFFER_ALIGNMENT, CommandEncoder, Device, MapMode,
+    Queue,
+};
+
+/// Uniform buffers need their size to be a multiple of 16 on some backends
+const UNIFORM_ALIGNMENT: usize = 16;
+
+#[derive(Debug, Clone)]
+enum ComputeBinding {
+    Uniform(Vec<u8>),
+    Storage { data: Vec<u8>, read_back: bool },
+}
+
+/// One dispatch of a [`ComputeShader`](crate::assets::ComputeShader) with the buffers it uses.
+///
+/// Buffers are bound to group 0, the first one added at `@binding(0)`, the next at
+/// `@binding(1)` and so on.
+#[derive(Debug)]
+pub struct ComputeJob {
+    shader: HComputeShader,
+    bindings: Vec<ComputeBinding>,
+    workgroups: [u32; 3],
+    readback: ComputeReadback,
+}
+
+/// The result of a [`ComputeJob`], filled in by the renderer once the GPU finished it
+#[derive(Debug, Clone, Default)]
+pub struct ComputeReadback(Arc<Mutex<ReadbackState>>);
+
+#[derive(Debug, Default)]
+enum ReadbackState {
+    #[default]
+    Pending,
+    Done(ComputeOutput),
+    Failed,
+    Taken,
+}
+
+/// The contents of the read-back buffers of a [`ComputeJob`], in the order they were added
+#[derive(Debug, Clone, Default)]
+pub struct ComputeOutput {
+    buffers: Vec<Vec<u8>>,
+}
+
+impl ComputeJob {
+    /// Prepares a dispatch of `x * y * z` workgroups
+    pub fn new(shader: HComputeShader, x: u32, y: u32, z: u32) -> Self {
+        ComputeJob {
+            shader,
+            bindings: Vec::new(),
+            workgroups: [x, y, z],
+            readback: ComputeReadback::default(),
+        }
+    }
+
+    /// Binds `value` as a `var<uniform>`
+    pub fn uniform<T: Pod>(mut self, value: &T) -> Self {
+        let data = bytemuck::bytes_of(value).to_vec();
+        self.bindings.push(ComputeBinding::Uniform(data));
+        self
+    }
+
+    /// Binds `data` as a `var<storage>` that isn't read back
+    pub fn storage<T: Pod>(mut self, data: &[T]) -> Self {
+        self.bindings.push(ComputeBinding::Storage {
+            data: bytemuck::cast_slice(data).to_vec(),
+            read_back: false,
+        });
+        self
+    }
+
+    /// Binds `data` as a `var<storage, read_write>` which is copied back after the dispatch
+    pub fn storage_read_back<T: Pod>(mut self, data: &[T]) -> Self {
+        self.bindings.push(ComputeBinding::Storage {
+            data: bytemuck::cast_slice(data).to_vec(),
+            read_back: true,
+        });
+        self
+    }
+
+    pub fn shader(&self) -> HComputeShader {
+        self.shader
+    }
+
+    pub fn workgroups(&self) -> [u32; 3] {
+        self.workgroups
+    }
+
+    /// Returns the handle the results are delivered to
+    pub fn readback(&self) -> ComputeReadback {
+        self.readback.clone()
+    }
+
+    /// Records the dispatch into `encoder`, and returns the buffers the results are copied into
+    pub(crate) fn record(
+        &self,
+        device: &Device,
+        cache: &AssetCache,
+        encoder: &mut CommandEncoder,
+    ) -> Option<Vec<ReadbackBuffer>> {
+        let Some(shader) = cache.compute_shader(self.shader) else {
+            warn!("Compute shader {} doesn't exist", self.shader);
+            return None;
+        };
+        if !shader.is_valid() {
+            return None;
+        }
+
+        let buffers: Vec<Buffer> = self
+            .bindings
+            .iter()
+            .map(|binding| binding.create_buffer(device, shader.name()))
+            .collect();
+        let bind_group = shader.bind_buffers(device, &buffers.iter().collect::<Vec<_>>())?;
+        let [x, y, z] = self.workgroups;
+        shader.dispatch(encoder, &[&bind_group], x, y, z);
+
+        let readbacks = self
+            .bindings
+            .iter()
+            .zip(&buffers)
+            .filter_map(|(binding, buffer)| match binding {
+                ComputeBinding::Storage {
+                    data,
+                    read_back: true,
+                } => Some((data.len(), buffer)),
+                _ => None,
+            })
+            .map(|(len, buffer)| {
+                let staging = device.create_buffer(&BufferDescriptor {
+                    label: Some("Compute Readback Buffer"),
+                    size: buffer.size(),
+                    usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                });
+                encoder.copy_buffer_to_buffer(buffer, 0, &staging, 0, buffer.size());
+                ReadbackBuffer { staging, len }
+            })
+            .collect();
+
+        Some(readbacks)
+    }
+}
+
+/// A mappable copy of a read-back storage buffer
+pub(crate) struct ReadbackBuffer {
+    staging: Buffer,
+    /// The size of the data without padding
+    len: usize,
+}
+
+impl ComputeBinding {
+    fn create_buffer(&self, device: &Device, label: &str) -> Buffer {
+        let (data, alignment, usage) = match self {
+            ComputeBinding::Uniform(data) => (data, UNIFORM_ALIGNMENT, BufferUsages::UNIFORM),
+            ComputeBinding::Storage { data, .. } => (
+                data,
+                COPY_BUFFER_ALIGNMENT as usize,
+                BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+            ),
+        };
+
+        // empty buffers can't be bound, so there's always at least one aligned chunk
+        let size = data.len().max(1).next_multiple_of(alignment);
+        let mut contents = data.clone();
+        contents.resize(size, 0);
+
+        device.create_buffer_init(&BufferInitDescriptor {
+            label: Some(label),
+            contents: &contents,
+            usage,
+        })
+    }
+}
+
+/// The read-back buffers of a submitted job, which the GPU is still working on.
+///
+/// The renderer keeps these around and polls the device once per frame without blocking. The
+/// mapping callbacks run during that poll, and the last one to finish hands the results to the
+/// [`ComputeReadback`].
+pub(crate) struct InFlightReadback {
+    readback: ComputeReadback,
+    _buffers: Arc<[ReadbackBuffer]>,
+}
+
+struct MapProgress {
+    remaining: usize,
+    buffers: Vec<Vec<u8>>,
+    failed: bool,
+}
+
+impl MapProgress {
+    /// Stores the mapped data of buffer `index`, or `None` if mapping it failed. Returns the
+    /// final state of the readback once every buffer is done.
+    fn finish(&mut self, index: usize, data: Option<Vec<u8>>) -> Option<ReadbackState> {
+        match data {
+            Some(data) => self.buffers[index] = data,
+            None => self.failed = true,
+        }
+
+        self.remaining -= 1;
+        if self.remaining > 0 {
+            return None;
+        }
+
+        Some(if self.failed {
+            ReadbackState::Failed
+        } else {
+            ReadbackState::Done(ComputeOutput {
+                buffers: std::mem::take(&mut self.buffers),
+            })
+        })
+    }
+}
+
+impl InFlightReadback {
+    /// Requests the read-back buffers to be mapped. Has to be called after the job was submitted.
+    pub(crate) fn map(
+        queue: &Queue,
+        readback: ComputeReadback,
+        buffers: Vec<ReadbackBuffer>,
+    ) -> Self {
+        let buffers: Arc<[ReadbackBuffer]> = buffers.into();
+
+        if buffers.is_empty() {
+            let done = readback.clone();
+            queue.on_submitted_work_done(move || {
+                done.set(ReadbackState::Done(ComputeOutput::default()))
+            });
+        }
+
+        let progress = Arc::new(Mutex::new(MapProgress {
+            remaining: buffers.len(),
+            buffers: vec![Vec::new(); buffers.len()],
+            failed: false,
+        }));
+
+        for index in 0..buffers.len() {
+            let progress = progress.clone();
+            let mapped = buffers.clone();
+            let readback = readback.clone();
+            buffers[index]
+                .staging
+                .slice(..)
+                .map_async(MapMode::Read, move |res| {
+                    let buffer = &mapped[index];
+                    let data = res.ok().map(|()| {
+                        let view = buffer.staging.slice(..).get_mapped_range();
+                        let data = view[..buffer.len].to_vec();
+                        drop(view);
+                        buffer.staging.unmap();
+                        data
+                    });
+
+                    let mut progress = progress.lock().unwrap_or_else(|e| e.into_inner());
+                    if let Some(state) = progress.finish(index, data) {
+                        readback.set(state);
+                    }
+                });
+        }
+
+        InFlightReadback {
+            readback,
+            _buffers: buffers,
+        }
+    }
+
+    pub(crate) fn is_pending(&self) -> bool {
+        self.readback.is_pending()
+    }
+}
+
+impl ComputeReadback {
+    /// Returns true until the renderer finished the job
+    pub fn is_pending(&self) -> bool {
+        matches!(*self.state(), ReadbackState::Pending)
+    }
+
+    /// Returns true if the job couldn't run, e.g. because the shader is invalid
+    pub fn is_failed(&self) -> bool {
+        matches!(*self.state(), ReadbackState::Failed)
+    }
+
+    /// Takes the output once the job is done. Only the first call after that returns it.
+    pub fn try_take(&self) -> Option<ComputeOutput> {
+        let mut state = self.state();
+        if !matches!(*state, ReadbackState::Done(_)) {
+            return None;
+        }
+        match std::mem::replace(&mut *state, ReadbackState::Taken) {
+            ReadbackState::Done(output) => Some(output),
+            _ => None,
+        }
+    }
+
+    /// Marks a job that couldn't be recorded as failed
+    pub(crate) fn fail(&self) {
+        self.set(ReadbackState::Failed);
+    }
+
+    fn set(&self, state: ReadbackState) {
+        *self.state() = state;
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, ReadbackState> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl ComputeOutput {
+    /// The number of read-back buffers
+    pub fn len(&self) -> usize {
+        self.buffers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffers.is_empty()
+    }
+
+    /// The raw bytes of the nth read-back buffer
+    pub fn bytes(&self, index: usize) -> Option<&[u8]> {
+        self.buffers.get(index).map(Vec::as_slice)
+    }
+
+    /// The nth read-back buffer as a list of `T`
+    pub fn cast<T: Pod>(&self, index: usize) -> Option<Vec<T>> {
+        Some(bytemuck::pod_collect_to_vec(self.bytes(index)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(buffers: usize) -> MapProgress {
+        MapProgress {
+            remaining: buffers,
+            buffers: vec![Vec::new(); buffers],
+            failed: false,
+        }
+    }
+
+    #[test]
+    fn readbacks_resolve_once_every_buffer_is_mapped() {
+        let readback = ComputeReadback::default();
+        let mut progress = progress(2);
+
+        let second = bytemuck::cast_slice(&[3.0f32, 4.0]).to_vec();
+        assert!(progress.finish(1, Some(second)).is_none());
+        assert!(readback.is_pending());
+
+        let first = bytemuck::cast_slice(&[2.0f32]).to_vec();
+        readback.set(progress.finish(0, Some(first)).unwrap());
+        assert!(!readback.is_pending());
+
+        let output = readback.try_take().unwrap();
+        assert_eq!(output.len(), 2);
+        assert_eq!(output.cast::<f32>(0).unwrap(), [2.0]);
+        assert_eq!(output.cast::<f32>(1).unwrap(), [3.0, 4.0]);
+        assert!(readback.try_take().is_none());
+    }
+
+    #[test]
+    fn a_failed_mapping_fails_the_readback() {
+        let readback = ComputeReadback::default();
+        let mut progress = progress(2);
+
+        assert!(progress.finish(0, None).is_none());
+        readback.set(progress.finish(1, Some(vec![1, 2, 3, 4])).unwrap());
+
+        assert!(readback.is_failed());
+        assert!(readback.try_take().is_none());
+    }
+}
@@ -3,6 +3,7 @@ use crate::core::ObjectHash;
 use crate::rendering::lights::LightProxy;
 use crate::rendering::message::RenderMsg;
 use crate::rendering::proxies::SceneProxy;
+use crate::rendering::stats::FrameStats;
 use crate::strobe::{CacheId, UiDraw, UiImageDraw, UiTextDraw};
 use crate::{RenderTargetId, World};
 use std::hash::{DefaultHasher, Hash, Hasher};
@@ -20,6 +21,8 @@ pub enum RenderPassType {
     Shadow,
     Picking,
     PickingUi,
+    Decal,
+    GBuffer,
 }
 
 pub struct GPUDrawCtx<'a> {
@@ -29,7 +32,11 @@ pub struct GPUDrawCtx<'a> {
     pub render_bind_group: &'a BindGroup,
     pub light_bind_group: &'a BindGroup,
     pub shadow_bind_group: &'a BindGroup,
+    /// The scene depth, only available in passes that run after the scene was drawn
+    pub depth_bind_group: Option<&'a BindGroup>,
     pub transparency_pass: bool,
+    /// Counts the draw calls of the renderer's current frame
+    pub stats: &'a FrameStats,
 }
 
 pub struct CPUDrawCtx<'a> {
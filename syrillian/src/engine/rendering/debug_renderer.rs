@@ -1,8 +1,25 @@
+use crate::components::collider::MeshShapeExtra;
+use nalgebra::{Isometry3, Point3, Vector3};
+use rapier3d::geometry::{SharedShape, TypedShape};
+use std::collections::HashSet;
+use std::f32::consts::{PI, TAU};
 use std::sync::RwLock;
 
+const CIRCLE_SEGMENTS: usize = 24;
+
 #[cfg(debug_assertions)]
 static DEBUG_RENDERER: RwLock<DebugRenderer> = RwLock::new(DebugRenderer::default_const());
 
+/// Debug drawing of a single world, set through [`World::debug`](crate::World::debug)
+#[derive(Debug, Clone, Default)]
+pub struct DebugSettings {
+    /// Draws the wireframe of every collider, colored by the type of body it's attached to
+    pub show_colliders: bool,
+    /// Draws every physics contact point with its normal. The normal grows longer and turns from
+    /// green to red with the impulse applied at the contact.
+    pub show_contacts: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct DebugRenderer {
     pub mesh_edges: bool,
@@ -102,3 +119,106 @@ impl DebugRenderer {
         }
     }
 }
+
+/// Line segments outlining a shape in its local space.
+///
+/// Balls and capsules are drawn as rings, cuboids as their 12 edges and every other shape as the
+/// edges of its triangle mesh.
+pub fn shape_outline(shape: &SharedShape) -> Vec<[Point3<f32>; 2]> {
+    let mut lines = Vec::new();
+    push_shape_outline(shape, &Isometry3::identity(), &mut lines);
+    lines
+}
+
+fn push_shape_outline(
+    shape: &SharedShape,
+    pose: &Isometry3<f32>,
+    lines: &mut Vec<[Point3<f32>; 2]>,
+) {
+    match shape.as_typed_shape() {
+        TypedShape::Ball(ball) => {
+            let center = pose.translation.vector.into();
+            for (u, v) in [
+                (Vector3::x(), Vector3::y()),
+                (Vector3::y(), Vector3::z()),
+                (Vector3::z(), Vector3::x()),
+            ] {
+                let (u, v) = (pose.rotation * u, pose.rotation * v);
+                push_arc(lines, center, u * ball.radius, v * ball.radius, TAU);
+            }
+        }
+        TypedShape::Cuboid(cuboid) => {
+            let he = cuboid.half_extents;
+            let corner = |i: usize| {
+                let sign = |bit: usize| if i & bit == 0 { -1.0 } else { 1.0 };
+                pose * Point3::new(he.x * sign(1), he.y * sign(2), he.z * sign(4))
+            };
+            for i in 0..8 {
+                for bit in [1, 2, 4] {
+                    if i & bit == 0 {
+                        lines.push([corner(i), corner(i | bit)]);
+                    }
+                }
+            }
+        }
+        TypedShape::Capsule(capsule) => {
+            let a = pose * capsule.segment.a;
+            let b = pose * capsule.segment.b;
+            let r = capsule.radius;
+            let axis = (b - a)
+                .try_normalize(f32::EPSILON)
+                .unwrap_or(pose.rotation * Vector3::y());
+            let u = axis
+                .cross(&Vector3::x())
+                .try_normalize(1e-3)
+                .unwrap_or_else(|| axis.cross(&Vector3::z()).normalize());
+            let v = axis.cross(&u);
+
+            push_arc(lines, a, u * r, v * r, TAU);
+            push_arc(lines, b, u * r, v * r, TAU);
+            for side in [u, -u, v, -v] {
+                lines.push([a + side * r, b + side * r]);
+            }
+            for side in [u, v] {
+                push_arc(lines, b, side * r, axis * r, PI);
+                push_arc(lines, a, -side * r, -axis * r, PI);
+            }
+        }
+        TypedShape::Compound(compound) => {
+            for (local_pose, part) in compound.shapes() {
+                push_shape_outline(part, &(pose * local_pose), lines);
+            }
+        }
+        _ => {
+            let (vertices, indices) = shape.to_trimesh();
+
+            let mut edges = HashSet::new();
+            for [i0, i1, i2] in indices {
+                for (a, b) in [(i0, i1), (i1, i2), (i2, i0)] {
+                    if edges.insert((a.min(b), a.max(b))) {
+                        lines.push([pose * vertices[a as usize], pose * vertices[b as usize]]);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Pushes an arc around `center`, starting at `center + from` and sweeping `angle` towards `to`
+fn push_arc(
+    lines: &mut Vec<[Point3<f32>; 2]>,
+    center: Point3<f32>,
+    from: Vector3<f32>,
+    to: Vector3<f32>,
+    angle: f32,
+) {
+    let segments = ((CIRCLE_SEGMENTS as f32 * angle / TAU).ceil() as usize).max(1);
+    let point = |i: usize| {
+        let t = angle * i as f32 / segments as f32;
+        center + from * t.cos() + to * t.sin()
+    };
+
+    for i in 0..segments {
+        lines.push([point(i), point(i + 1)]);
+    }
+}
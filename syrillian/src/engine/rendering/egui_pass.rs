@@ -0,0 +1,417 @@
+//! Draws the egui output of the game thread on top of the final frame of the primary render
+//! target, see [`AppState::ui`](crate::AppState::ui).
+
+use crate::rendering::message::EguiPaint;
+use crate::rendering::stats::FrameStats;
+use egui::epaint::{ImageDelta, Primitive, Vertex};
+use egui::{
+    ClippedPrimitive, ImageData, TextureFilter, TextureId, TextureOptions, TextureWrapMode,
+};
+use std::collections::HashMap;
+use std::mem::size_of;
+use std::ops::Range;
+use tracing::warn;
+use wgpu::*;
+use winit::dpi::PhysicalSize;
+
+const SHADER: &str = include_str!("../assets/shader/shaders/egui.wgsl");
+
+const VERTEX_ATTRIBUTES: [VertexAttribute; 3] =
+    vertex_attr_array![0 => Float32x2, 1 => Float32x2, 2 => Unorm8x4];
+
+struct EguiTexture {
+    texture: Texture,
+    bind_group: BindGroup,
+}
+
+struct EguiDraw {
+    texture: TextureId,
+    scissor: [u32; 4],
+    indices: Range<u32>,
+    base_vertex: i32,
+}
+
+pub(crate) struct EguiRenderer {
+    module: ShaderModule,
+    texture_bgl: BindGroupLayout,
+    layout: PipelineLayout,
+    pipeline: Option<(TextureFormat, RenderPipeline)>,
+    screen_buffer: Buffer,
+    screen_bind_group: BindGroup,
+    vertex_buffer: Buffer,
+    index_buffer: Buffer,
+    textures: HashMap<TextureId, EguiTexture>,
+    primitives: Vec<ClippedPrimitive>,
+    pixels_per_point: f32,
+    pending_free: Vec<TextureId>,
+    draws: Vec<EguiDraw>,
+}
+
+impl EguiRenderer {
+    pub(crate) fn new(device: &Device) -> Self {
+        let module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("Egui Shader"),
+            source: ShaderSource::Wgsl(SHADER.into()),
+        });
+
+        let screen_bgl = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Egui Screen Bind Group Layout"),
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::VERTEX,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let texture_bgl = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Egui Texture Bind Group Layout"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("Egui Pipeline Layout"),
+            bind_group_layouts: &[&screen_bgl, &texture_bgl],
+            immediate_size: 0,
+        });
+
+        let screen_buffer = device.create_buffer(&BufferDescriptor {
+            label: Some("Egui Screen Buffer"),
+            size: 16,
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let screen_bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("Egui Screen Bind Group"),
+            layout: &screen_bgl,
+            entries: &[BindGroupEntry {
+                binding: 0,
+                resource: screen_buffer.as_entire_binding(),
+            }],
+        });
+
+        EguiRenderer {
+            module,
+            texture_bgl,
+            layout,
+            pipeline: None,
+            screen_buffer,
+            screen_bind_group,
+            vertex_buffer: Self::create_buffer(
+                device,
+                "Egui Vertex Buffer",
+                4096,
+                BufferUsages::VERTEX,
+            ),
+            index_buffer: Self::create_buffer(
+                device,
+                "Egui Index Buffer",
+                4096,
+                BufferUsages::INDEX,
+            ),
+            textures: HashMap::new(),
+            primitives: Vec::new(),
+            pixels_per_point: 1.0,
+            pending_free: Vec::new(),
+            draws: Vec::new(),
+        }
+    }
+
+    /// Uploads the textures of a new egui frame and keeps its meshes for the next paint.
+    /// Textures the frame frees are kept until they've been painted once more.
+    pub(crate) fn update(&mut self, paint: EguiPaint, device: &Device, queue: &Queue) {
+        for (id, delta) in &paint.textures_delta.set {
+            self.set_texture(*id, delta, device, queue);
+        }
+        self.pending_free.extend(paint.textures_delta.free);
+        self.primitives = paint.primitives;
+        self.pixels_per_point = paint.pixels_per_point;
+    }
+
+    /// Uploads the meshes of the latest frame for a target of `format` and `size`
+    pub(crate) fn prepare(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        format: TextureFormat,
+        size: PhysicalSize<u32>,
+    ) {
+        if self.pipeline.as_ref().is_none_or(|(f, _)| *f != format) {
+            self.pipeline = Some((format, self.create_pipeline(device, format)));
+        }
+
+        let ppp = self.pixels_per_point;
+        let screen = [size.width as f32 / ppp, size.height as f32 / ppp, 0.0, 0.0];
+        queue.write_buffer(&self.screen_buffer, 0, bytemuck::cast_slice(&screen));
+
+        let mut vertices: Vec<Vertex> = Vec::new();
+        let mut indices: Vec<u32> = Vec::new();
+        self.draws.clear();
+
+        for ClippedPrimitive {
+            clip_rect,
+            primitive,
+        } in &self.primitives
+        {
+            let Primitive::Mesh(mesh) = primitive else {
+                continue;
+            };
+
+            let min_x = ((clip_rect.min.x * ppp).round().max(0.0) as u32).min(size.width);
+            let min_y = ((clip_rect.min.y * ppp).round().max(0.0) as u32).min(size.height);
+            let max_x = ((clip_rect.max.x * ppp).round().max(0.0) as u32).min(size.width);
+            let max_y = ((clip_rect.max.y * ppp).round().max(0.0) as u32).min(size.height);
+            if max_x <= min_x || max_y <= min_y || mesh.indices.is_empty() {
+                continue;
+            }
+
+            let first_index = indices.len() as u32;
+            self.draws.push(EguiDraw {
+                texture: mesh.texture_id,
+                scissor: [min_x, min_y, max_x - min_x, max_y - min_y],
+                indices: first_index..first_index + mesh.indices.len() as u32,
+                base_vertex: vertices.len() as i32,
+            });
+            vertices.extend_from_slice(&mesh.vertices);
+            indices.extend_from_slice(&mesh.indices);
+        }
+
+        let vertex_bytes: &[u8] = bytemuck::cast_slice(&vertices);
+        let index_bytes: &[u8] = bytemuck::cast_slice(&indices);
+        Self::ensure_capacity(
+            device,
+            &mut self.vertex_buffer,
+            "Egui Vertex Buffer",
+            vertex_bytes.len() as u64,
+            BufferUsages::VERTEX,
+        );
+        Self::ensure_capacity(
+            device,
+            &mut self.index_buffer,
+            "Egui Index Buffer",
+            index_bytes.len() as u64,
+            BufferUsages::INDEX,
+        );
+        queue.write_buffer(&self.vertex_buffer, 0, vertex_bytes);
+        queue.write_buffer(&self.index_buffer, 0, index_bytes);
+    }
+
+    /// Draws the meshes uploaded by [`prepare`](Self::prepare), then frees the textures egui
+    /// doesn't need anymore
+    pub(crate) fn paint(&mut self, pass: &mut RenderPass, stats: &FrameStats) {
+        if let Some((_, pipeline)) = &self.pipeline
+            && !self.draws.is_empty()
+        {
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, &self.screen_bind_group, &[]);
+            pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+            pass.set_index_buffer(self.index_buffer.slice(..), IndexFormat::Uint32);
+
+            for draw in &self.draws {
+                let Some(texture) = self.textures.get(&draw.texture) else {
+                    continue;
+                };
+                let [x, y, width, height] = draw.scissor;
+                pass.set_scissor_rect(x, y, width, height);
+                pass.set_bind_group(1, &texture.bind_group, &[]);
+                stats.record_draw(draw.indices.len() as u32, 1);
+                pass.draw_indexed(draw.indices.clone(), draw.base_vertex, 0..1);
+            }
+        }
+
+        for id in self.pending_free.drain(..) {
+            self.textures.remove(&id);
+        }
+    }
+
+    fn set_texture(&mut self, id: TextureId, delta: &ImageDelta, device: &Device, queue: &Queue) {
+        let ImageData::Color(image) = &delta.image;
+        let [width, height] = image.size.map(|side| side as u32);
+        let size = Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+
+        let origin = match delta.pos {
+            Some([x, y]) => {
+                if !self.textures.contains_key(&id) {
+                    warn!("egui updated texture {id:?}, which doesn't exist");
+                    return;
+                }
+                Origin3d {
+                    x: x as u32,
+                    y: y as u32,
+                    z: 0,
+                }
+            }
+            None => {
+                let texture = self.create_texture(device, size, delta.options);
+                self.textures.insert(id, texture);
+                Origin3d::ZERO
+            }
+        };
+
+        let texture = &self.textures[&id].texture;
+        queue.write_texture(
+            TexelCopyTextureInfo {
+                texture,
+                mip_level: 0,
+                origin,
+                aspect: TextureAspect::All,
+            },
+            bytemuck::cast_slice(&image.pixels),
+            TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(width * 4),
+                rows_per_image: Some(height),
+            },
+            size,
+        );
+    }
+
+    fn create_texture(
+        &self,
+        device: &Device,
+        size: Extent3d,
+        options: TextureOptions,
+    ) -> EguiTexture {
+        let texture = device.create_texture(&TextureDescriptor {
+            label: Some("Egui Texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8UnormSrgb,
+            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&TextureViewDescriptor::default());
+
+        let address_mode = match options.wrap_mode {
+            TextureWrapMode::ClampToEdge => AddressMode::ClampToEdge,
+            TextureWrapMode::Repeat => AddressMode::Repeat,
+            TextureWrapMode::MirroredRepeat => AddressMode::MirrorRepeat,
+        };
+        let sampler = device.create_sampler(&SamplerDescriptor {
+            label: Some("Egui Sampler"),
+            address_mode_u: address_mode,
+            address_mode_v: address_mode,
+            address_mode_w: address_mode,
+            mag_filter: filter_mode(options.magnification),
+            min_filter: filter_mode(options.minification),
+            ..SamplerDescriptor::default()
+        });
+
+        let bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("Egui Texture Bind Group"),
+            layout: &self.texture_bgl,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(&view),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+
+        EguiTexture {
+            texture,
+            bind_group,
+        }
+    }
+
+    fn create_pipeline(&self, device: &Device, format: TextureFormat) -> RenderPipeline {
+        device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("Egui Pipeline"),
+            layout: Some(&self.layout),
+            vertex: VertexState {
+                module: &self.module,
+                entry_point: None,
+                compilation_options: PipelineCompilationOptions::default(),
+                buffers: &[VertexBufferLayout {
+                    array_stride: size_of::<Vertex>() as BufferAddress,
+                    step_mode: VertexStepMode::Vertex,
+                    attributes: &VERTEX_ATTRIBUTES,
+                }],
+            },
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            fragment: Some(FragmentState {
+                module: &self.module,
+                entry_point: None,
+                compilation_options: PipelineCompilationOptions::default(),
+                targets: &[Some(ColorTargetState {
+                    format,
+                    // egui colors are premultiplied
+                    blend: Some(BlendState {
+                        color: BlendComponent {
+                            src_factor: BlendFactor::One,
+                            dst_factor: BlendFactor::OneMinusSrcAlpha,
+                            operation: BlendOperation::Add,
+                        },
+                        alpha: BlendComponent {
+                            src_factor: BlendFactor::OneMinusDstAlpha,
+                            dst_factor: BlendFactor::One,
+                            operation: BlendOperation::Add,
+                        },
+                    }),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            multiview_mask: None,
+            cache: None,
+        })
+    }
+
+    fn create_buffer(device: &Device, label: &str, size: u64, usage: BufferUsages) -> Buffer {
+        device.create_buffer(&BufferDescriptor {
+            label: Some(label),
+            size,
+            usage: usage | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    fn ensure_capacity(
+        device: &Device,
+        buffer: &mut Buffer,
+        label: &str,
+        size: u64,
+        usage: BufferUsages,
+    ) {
+        if buffer.size() < size {
+            *buffer = Self::create_buffer(device, label, size.next_power_of_two(), usage);
+        }
+    }
+}
+
+fn filter_mode(filter: TextureFilter) -> FilterMode {
+    match filter {
+        TextureFilter::Nearest => FilterMode::Nearest,
+        TextureFilter::Linear => FilterMode::Linear,
+    }
+}
@@ -0,0 +1,72 @@
+use wgpu::{
+    Device, Extent3d, SurfaceConfiguration, Texture, TextureDescriptor, TextureDimension,
+    TextureFormat, TextureUsages, TextureView, TextureViewDescriptor,
+};
+
+pub const GBUFFER_FORMAT: TextureFormat = TextureFormat::Rgba16Float;
+
+/// World space normals and positions of the opaque scene, written by the G-buffer pass.
+///
+/// Pixels without geometry are zero, including their alpha. While disabled, both textures are a
+/// single empty pixel so the post-processing bind group stays valid.
+pub struct GBufferSurface {
+    enabled: bool,
+    normal_view: TextureView,
+    position_view: TextureView,
+}
+
+impl GBufferSurface {
+    pub fn new(device: &Device, config: &SurfaceConfiguration, enabled: bool) -> Self {
+        let (width, height) = if enabled {
+            (config.width.max(1), config.height.max(1))
+        } else {
+            (1, 1)
+        };
+
+        let normal = create_gbuffer_texture(device, "G-Buffer Normal Texture", width, height);
+        let position = create_gbuffer_texture(device, "G-Buffer Position Texture", width, height);
+        let normal_view = normal.create_view(&TextureViewDescriptor::default());
+        let position_view = position.create_view(&TextureViewDescriptor::default());
+
+        GBufferSurface {
+            enabled,
+            normal_view,
+            position_view,
+        }
+    }
+
+    pub fn recreate(&mut self, device: &Device, config: &SurfaceConfiguration) {
+        *self = Self::new(device, config, self.enabled);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn normal_view(&self) -> &TextureView {
+        &self.normal_view
+    }
+
+    pub fn position_view(&self) -> &TextureView {
+        &self.position_view
+    }
+}
+
+fn create_gbuffer_texture(device: &Device, label: &str, width: u32, height: u32) -> Texture {
+    device.create_texture(&TextureDescriptor {
+        label: Some(label),
+        size: Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format: GBUFFER_FORMAT,
+        usage: TextureUsages::RENDER_ATTACHMENT
+            | TextureUsages::TEXTURE_BINDING
+            | TextureUsages::COPY_SRC,
+        view_formats: &[],
+    })
+}
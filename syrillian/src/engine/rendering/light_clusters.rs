@@ -0,0 +1,388 @@
+//! Clustered forward light culling.
+//!
+//! The view of a camera is split into screen tiles and logarithmic depth slices. Every light with
+//! a limited range is sorted into the clusters its sphere of influence overlaps, so the fragment
+//! shader only shades the lights that can reach it. Sun lights reach everywhere and are kept in a
+//! separate list that every fragment goes through.
+
+use crate::ensure_aligned;
+use crate::rendering::lights::{LightProxy, LightType};
+use crate::rendering::render_data::CameraUniform;
+use nalgebra::{Point3, Vector2, Vector3};
+
+/// Dimensions of the grid lights are sorted into, see
+/// [`Renderer::set_light_clusters`](crate::rendering::renderer::Renderer::set_light_clusters)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ClusterConfig {
+    /// Screen tiles from left to right
+    pub tiles_x: u32,
+    /// Screen tiles from bottom to top
+    pub tiles_y: u32,
+    /// Depth slices from the near to the far plane, thinner close to the camera
+    pub slices: u32,
+    /// Lights beyond this many are skipped in a cluster
+    pub max_lights_per_cluster: u32,
+}
+
+impl Default for ClusterConfig {
+    fn default() -> Self {
+        ClusterConfig {
+            tiles_x: 16,
+            tiles_y: 9,
+            slices: 24,
+            max_lights_per_cluster: 128,
+        }
+    }
+}
+
+impl ClusterConfig {
+    pub fn cluster_count(&self) -> usize {
+        self.tiles_x as usize * self.tiles_y as usize * self.slices as usize
+    }
+
+    /// Returns the config with every dimension at least 1
+    pub(crate) fn sanitized(self) -> Self {
+        ClusterConfig {
+            tiles_x: self.tiles_x.max(1),
+            tiles_y: self.tiles_y.max(1),
+            slices: self.slices.max(1),
+            max_lights_per_cluster: self.max_lights_per_cluster,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub(crate) struct ClusterParams {
+    pub(crate) tiles_x: u32,
+    pub(crate) tiles_y: u32,
+    pub(crate) slices: u32,
+    /// Zero makes the shaders go through all lights instead
+    pub(crate) enabled: u32,
+    pub(crate) near: f32,
+    pub(crate) far: f32,
+    /// Number of sun lights at the start of the light indices
+    pub(crate) global_count: u32,
+    pub(crate) _padding: u32,
+}
+
+ensure_aligned!(ClusterParams { tiles_x, near }, align <= 16 * 2 => size);
+
+/// The lights of every cluster for a single view, as uploaded to the GPU
+#[derive(Debug, Clone)]
+pub(crate) struct LightClusters {
+    pub(crate) params: ClusterParams,
+    /// Offset into the indices and number of lights, for every cluster
+    pub(crate) grid: Vec<[u32; 2]>,
+    /// Indices into the light buffer. Sun lights first, then the lights of each cluster.
+    pub(crate) indices: Vec<u32>,
+    filled: Vec<u32>,
+}
+
+enum LightReach {
+    Nowhere,
+    Everywhere,
+    /// Inclusive range of clusters, as x, y and slice
+    Clusters {
+        min: [u32; 3],
+        max: [u32; 3],
+    },
+}
+
+impl Default for LightClusters {
+    fn default() -> Self {
+        LightClusters {
+            params: ClusterParams::default(),
+            grid: vec![[0, 0]],
+            indices: vec![0],
+            filled: Vec::new(),
+        }
+    }
+}
+
+impl LightClusters {
+    /// Sorts the lights into the clusters of the camera's view. Clustering is disabled if the
+    /// camera has no valid perspective projection.
+    pub(crate) fn build(
+        &mut self,
+        config: ClusterConfig,
+        camera: &CameraUniform,
+        lights: &[LightProxy],
+    ) {
+        let config = config.sanitized();
+        let near = camera.projection_mat.znear();
+        let far = camera.projection_mat.zfar();
+
+        self.grid.clear();
+        self.indices.clear();
+
+        if !(near > 0.0 && far > near && far.is_finite()) {
+            self.params = ClusterParams::default();
+            self.grid.push([0, 0]);
+            self.indices.push(0);
+            return;
+        }
+
+        let mut reached = Vec::new();
+        for (i, light) in lights.iter().enumerate() {
+            match light_reach(light, camera, &config, near, far) {
+                LightReach::Nowhere => {}
+                LightReach::Everywhere => self.indices.push(i as u32),
+                LightReach::Clusters { min, max } => reached.push((i as u32, min, max)),
+            }
+        }
+        let global_count = self.indices.len() as u32;
+
+        let clusters = config.cluster_count();
+        self.grid.resize(clusters, [0, 0]);
+        for (_, min, max) in &reached {
+            for_each_cluster(&config, *min, *max, |c| self.grid[c][1] += 1);
+        }
+
+        let mut offset = global_count;
+        for cell in &mut self.grid {
+            cell[1] = cell[1].min(config.max_lights_per_cluster);
+            cell[0] = offset;
+            offset += cell[1];
+        }
+
+        self.indices.resize(offset as usize, 0);
+        self.filled.clear();
+        self.filled.resize(clusters, 0);
+        for (light, min, max) in &reached {
+            for_each_cluster(&config, *min, *max, |c| {
+                let [start, count] = self.grid[c];
+                let filled = &mut self.filled[c];
+                if *filled < count {
+                    self.indices[(start + *filled) as usize] = *light;
+                    *filled += 1;
+                }
+            });
+        }
+
+        // storage bindings can't be empty
+        if self.indices.is_empty() {
+            self.indices.push(0);
+        }
+
+        self.params = ClusterParams {
+            tiles_x: config.tiles_x,
+            tiles_y: config.tiles_y,
+            slices: config.slices,
+            enabled: 1,
+            near,
+            far,
+            global_count,
+            _padding: 0,
+        };
+    }
+}
+
+fn light_reach(
+    light: &LightProxy,
+    camera: &CameraUniform,
+    config: &ClusterConfig,
+    near: f32,
+    far: f32,
+) -> LightReach {
+    let light_type = LightType::try_from(light.type_id).unwrap_or(LightType::Point);
+    // a range of zero or less means the light never fades out
+    if light_type == LightType::Sun || light.range <= 0.0 {
+        return LightReach::Everywhere;
+    }
+
+    let radius = light.range;
+    let center = camera
+        .view_mat
+        .transform_point(&Point3::from(light.position));
+    let depth = -center.z;
+    if depth + radius < near || depth - radius > far {
+        return LightReach::Nowhere;
+    }
+
+    let min_slice = depth_slice((depth - radius).max(near), near, far, config.slices);
+    let max_slice = depth_slice((depth + radius).min(far), near, far, config.slices);
+
+    // lights around the camera can't be projected, and reach the whole screen anyway
+    if depth - radius <= near {
+        return LightReach::Clusters {
+            min: [0, 0, min_slice],
+            max: [config.tiles_x - 1, config.tiles_y - 1, max_slice],
+        };
+    }
+
+    let proj = camera.projection_mat.as_matrix();
+    let mut min = Vector2::repeat(f32::INFINITY);
+    let mut max = Vector2::repeat(f32::NEG_INFINITY);
+    for corner in 0..8 {
+        let sign = |bit: u32| if corner & bit == 0 { -1.0 } else { 1.0 };
+        let offset = Vector3::new(sign(1), sign(2), sign(4)) * radius;
+        let clip = proj * (center + offset).to_homogeneous();
+        let ndc = clip.xy() / clip.w;
+        min = min.inf(&ndc);
+        max = max.sup(&ndc);
+    }
+
+    if max.x < -1.0 || min.x > 1.0 || max.y < -1.0 || min.y > 1.0 {
+        return LightReach::Nowhere;
+    }
+
+    LightReach::Clusters {
+        min: [
+            ndc_tile(min.x, config.tiles_x),
+            ndc_tile(min.y, config.tiles_y),
+            min_slice,
+        ],
+        max: [
+            ndc_tile(max.x, config.tiles_x),
+            ndc_tile(max.y, config.tiles_y),
+            max_slice,
+        ],
+    }
+}
+
+/// Same as `light_cluster` in the 3D shader
+fn ndc_tile(ndc: f32, tiles: u32) -> u32 {
+    (((ndc * 0.5 + 0.5) * tiles as f32).max(0.0) as u32).min(tiles - 1)
+}
+
+/// Same as `light_cluster` in the 3D shader
+fn depth_slice(depth: f32, near: f32, far: f32, slices: u32) -> u32 {
+    let ratio = (depth.max(near) / near).ln() / (far / near).ln();
+    ((ratio * slices as f32).max(0.0) as u32).min(slices - 1)
+}
+
+fn for_each_cluster(
+    config: &ClusterConfig,
+    min: [u32; 3],
+    max: [u32; 3],
+    mut f: impl FnMut(usize),
+) {
+    for z in min[2]..=max[2] {
+        for y in min[1]..=max[1] {
+            for x in min[0]..=max[0] {
+                f(((z * config.tiles_y + y) * config.tiles_x + x) as usize);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::{Matrix4, Perspective3};
+    use std::f32::consts::FRAC_PI_2;
+
+    fn camera() -> CameraUniform {
+        let mut camera = CameraUniform::empty();
+        let projection = Perspective3::new(1.0, FRAC_PI_2, 0.1, 100.0);
+        camera.update(&projection, &Vector3::zeros(), &Matrix4::identity());
+        camera
+    }
+
+    fn light(light_type: LightType, position: Vector3<f32>, range: f32) -> LightProxy {
+        LightProxy {
+            position,
+            range,
+            type_id: light_type as u32,
+            ..LightProxy::dummy()
+        }
+    }
+
+    impl LightClusters {
+        /// Returns the lights of the cluster at the tile and slice, without the sun lights
+        fn cluster_lights(&self, x: u32, y: u32, slice: u32) -> &[u32] {
+            let p = &self.params;
+            let index = (slice * p.tiles_y + y) * p.tiles_x + x;
+            let Some(&[start, count]) = self.grid.get(index as usize) else {
+                return &[];
+            };
+            &self.indices[start as usize..(start + count) as usize]
+        }
+
+        /// Returns the sun lights, which every cluster is lit by
+        fn global_lights(&self) -> &[u32] {
+            &self.indices[..self.params.global_count as usize]
+        }
+    }
+
+    fn config() -> ClusterConfig {
+        ClusterConfig {
+            tiles_x: 4,
+            tiles_y: 4,
+            slices: 8,
+            max_lights_per_cluster: 8,
+        }
+    }
+
+    #[test]
+    fn lights_only_land_in_clusters_they_reach() {
+        let lights = [
+            // far to the right, in front of the camera
+            light(LightType::Point, Vector3::new(8.0, 0.0, -10.0), 1.0),
+            // far to the left
+            light(LightType::Point, Vector3::new(-8.0, 0.0, -10.0), 1.0),
+            // behind the camera
+            light(LightType::Point, Vector3::new(0.0, 0.0, 10.0), 1.0),
+        ];
+
+        let mut clusters = LightClusters::default();
+        clusters.build(config(), &camera(), &lights);
+
+        assert_eq!(clusters.params.enabled, 1);
+        assert!(clusters.global_lights().is_empty());
+
+        let slice = depth_slice(10.0, 0.1, 100.0, 8);
+        assert_eq!(clusters.cluster_lights(3, 2, slice), &[0]);
+        assert_eq!(clusters.cluster_lights(0, 2, slice), &[1]);
+        assert!(clusters.cluster_lights(1, 2, slice).is_empty());
+        assert!(clusters.cluster_lights(3, 2, 0).is_empty());
+
+        assert!(!clusters.indices.contains(&2));
+    }
+
+    #[test]
+    fn sun_lights_and_endless_lights_reach_everywhere() {
+        let lights = [
+            light(LightType::Sun, Vector3::zeros(), 10.0),
+            light(LightType::Point, Vector3::new(0.0, 0.0, 50.0), 0.0),
+        ];
+
+        let mut clusters = LightClusters::default();
+        clusters.build(config(), &camera(), &lights);
+
+        assert_eq!(clusters.global_lights(), &[0, 1]);
+        assert!(clusters.grid.iter().all(|[_, count]| *count == 0));
+    }
+
+    #[test]
+    fn lights_around_the_camera_cover_the_screen_and_respect_the_cap() {
+        let lights: Vec<_> = (0..4)
+            .map(|_| light(LightType::Point, Vector3::new(0.0, 0.0, -0.5), 2.0))
+            .collect();
+        let config = ClusterConfig {
+            max_lights_per_cluster: 3,
+            ..config()
+        };
+
+        let mut clusters = LightClusters::default();
+        clusters.build(config, &camera(), &lights);
+
+        for x in 0..4 {
+            for y in 0..4 {
+                assert_eq!(clusters.cluster_lights(x, y, 0), &[0, 1, 2]);
+            }
+        }
+    }
+
+    #[test]
+    fn clustering_is_disabled_without_a_projection() {
+        let lights = [light(LightType::Point, Vector3::zeros(), 1.0)];
+
+        let mut clusters = LightClusters::default();
+        clusters.build(config(), &CameraUniform::empty(), &lights);
+
+        assert_eq!(clusters.params.enabled, 0);
+        assert_eq!(clusters.grid.len(), 1);
+    }
+}
@@ -3,8 +3,11 @@ use crate::components::TypedComponentId;
 use crate::rendering::AssetCache;
 #[cfg(debug_assertions)]
 use crate::rendering::Renderer;
+use crate::rendering::ambient_occlusion::AmbientOcclusionSurface;
+use crate::rendering::light_clusters::{ClusterConfig, LightClusters};
 use crate::rendering::lights::{LightProxy, LightType, LightUniformIndex, ShadowUniformIndex};
 use crate::rendering::message::LightProxyCommand;
+use crate::rendering::render_data::CameraUniform;
 use crate::rendering::uniform::ShaderUniform;
 #[cfg(debug_assertions)]
 use crate::try_activate_shader;
@@ -19,6 +22,30 @@ use wgpu::{
 
 const DUMMY_POINT_LIGHT: LightProxy = LightProxy::dummy();
 
+/// Number of shadow map layers. Point lights take 6, spot lights 1.
+pub const SHADOW_MAP_LAYERS: u32 = 48;
+pub const DEFAULT_SHADOW_RESOLUTION: u32 = 1024;
+/// Largest PCF kernel, in taps per axis
+pub const MAX_SHADOW_PCF_TAPS: u32 = 7;
+
+/// Depth bias applied when sampling shadow maps, see [`Renderer::set_shadow_bias`](crate::rendering::renderer::Renderer::set_shadow_bias)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ShadowBias {
+    pub constant: f32,
+    pub slope: f32,
+    pub normal_offset: f32,
+}
+
+impl Default for ShadowBias {
+    fn default() -> Self {
+        ShadowBias {
+            constant: 0.0,
+            slope: 0.0001,
+            normal_offset: 0.002,
+        }
+    }
+}
+
 pub struct LightManager {
     proxy_owners: Vec<TypedComponentId>,
     proxies: Vec<LightProxy>,
@@ -29,6 +56,14 @@ pub struct LightManager {
     empty_shadow_uniform: ShaderUniform<ShadowUniformIndex>,
     pub(crate) shadow_texture: HTexture,
     pub(crate) _shadow_sampler: Sampler,
+    no_occlusion: AmbientOcclusionSurface,
+    shadow_bias: ShadowBias,
+    shadow_pcf_taps: u32,
+
+    cluster_config: ClusterConfig,
+    clusters: LightClusters,
+    /// Light uniforms with the clusters of each view drawn in a frame, sharing the light buffers
+    view_uniforms: Vec<Option<ShaderUniform<LightUniformIndex>>>,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -164,6 +199,23 @@ impl LightManager {
         &self.shadow_uniform
     }
 
+    /// Builds a shadow uniform like [`shadow_uniform`](Self::shadow_uniform), with the ambient
+    /// occlusion of a render target. Has to be rebuilt when the shadow resolution changes.
+    pub fn occluded_shadow_uniform(
+        &self,
+        cache: &AssetCache,
+        device: &Device,
+        occlusion: &TextureView,
+    ) -> ShaderUniform<ShadowUniformIndex> {
+        let texture = cache.textures.try_get(self.shadow_texture, cache).unwrap();
+        let bgl = cache.bgl_shadow();
+        ShaderUniform::builder(&bgl)
+            .with_texture(&texture.view)
+            .with_sampler(&self._shadow_sampler)
+            .with_texture(occlusion)
+            .build(device)
+    }
+
     pub fn shadow_assignments(&self) -> &[ShadowAssignment] {
         &self.shadow_assignments
     }
@@ -175,8 +227,12 @@ impl LightManager {
     pub fn new(cache: &AssetCache, device: &Device) -> Self {
         const DUMMY_POINT_LIGHT: LightProxy = LightProxy::dummy();
 
-        let shadow_texture =
-            Texture::new_2d_shadow_map_array(48, 1024, 1024).store(&cache.textures.store());
+        let shadow_texture = Texture::new_2d_shadow_map_array(
+            SHADOW_MAP_LAYERS,
+            DEFAULT_SHADOW_RESOLUTION,
+            DEFAULT_SHADOW_RESOLUTION,
+        )
+        .store(&cache.textures.store());
         let empty_shadow_texture =
             Texture::new_2d_shadow_map_array(2, 1, 1).store(&cache.textures.store());
         let texture = cache.textures.try_get(shadow_texture, cache).unwrap();
@@ -184,9 +240,13 @@ impl LightManager {
 
         let bgl = cache.bgl_light();
         let count: u32 = 0;
+        let clusters = LightClusters::default();
         let uniform = ShaderUniform::builder(&bgl)
             .with_buffer_data(&count)
             .with_storage_buffer_data(&[DUMMY_POINT_LIGHT])
+            .with_buffer_data(&clusters.params)
+            .with_storage_buffer_data(&clusters.grid)
+            .with_storage_buffer_data(&clusters.indices)
             .build(device);
 
         let shadow_sampler = device.create_sampler(&SamplerDescriptor {
@@ -204,15 +264,19 @@ impl LightManager {
             border_color: None,
         });
 
+        let no_occlusion = AmbientOcclusionSurface::disabled(device);
+
         let bgl = cache.bgl_shadow();
         let shadow_uniform = ShaderUniform::builder(&bgl)
             .with_texture(&texture.view)
             .with_sampler(&shadow_sampler)
+            .with_texture(no_occlusion.view())
             .build(device);
 
         let empty_shadow_uniform = ShaderUniform::builder(&bgl)
             .with_texture(&empty_texture.view)
             .with_sampler(&shadow_sampler)
+            .with_texture(no_occlusion.view())
             .build(device);
 
         Self {
@@ -224,10 +288,56 @@ impl LightManager {
             empty_shadow_uniform,
             shadow_texture,
             _shadow_sampler: shadow_sampler,
+            no_occlusion,
+            shadow_bias: ShadowBias::default(),
+            shadow_pcf_taps: 3,
+            cluster_config: ClusterConfig::default(),
+            clusters,
+            view_uniforms: Vec::new(),
         }
     }
 
+    pub fn shadow_resolution(&self, cache: &AssetCache) -> u32 {
+        cache.textures.store().get(self.shadow_texture).width
+    }
+
+    /// Resizes every shadow map layer to `resolution`² texels and rebinds the new texture
+    pub fn set_shadow_resolution(&mut self, cache: &AssetCache, device: &Device, resolution: u32) {
+        {
+            let mut texture = cache.textures.store().get_mut(self.shadow_texture);
+            texture.width = resolution;
+            texture.height = resolution;
+        }
+        cache.textures.refresh_dirty();
+
+        self.shadow_uniform = self.occluded_shadow_uniform(cache, device, self.no_occlusion.view());
+    }
+
+    pub fn shadow_bias(&self) -> ShadowBias {
+        self.shadow_bias
+    }
+
+    pub fn set_shadow_bias(&mut self, bias: ShadowBias) {
+        self.shadow_bias = bias;
+    }
+
+    pub fn shadow_pcf_taps(&self) -> u32 {
+        self.shadow_pcf_taps
+    }
+
+    pub fn set_shadow_pcf_taps(&mut self, taps: u32) {
+        self.shadow_pcf_taps = taps.clamp(1, MAX_SHADOW_PCF_TAPS);
+    }
+
     pub fn update(&mut self, cache: &AssetCache, queue: &Queue, device: &Device) {
+        let bias = self.shadow_bias;
+        for proxy in &mut self.proxies {
+            proxy.shadow_constant_bias = bias.constant;
+            proxy.shadow_slope_bias = bias.slope;
+            proxy.shadow_normal_offset = bias.normal_offset;
+            proxy.shadow_pcf_taps = self.shadow_pcf_taps;
+        }
+
         let queue = &queue;
         let proxies = proxy_buffer_slice(&self.proxies);
         let size = proxies.len();
@@ -238,15 +348,84 @@ impl LightManager {
         let data = self.uniform.buffer(LightUniformIndex::Lights);
         if size_of_val(proxies) > data.size() as usize {
             let bgl = cache.bgl_light();
+            let uniform = &self.uniform;
             self.uniform = ShaderUniform::builder(&bgl)
                 .with_buffer(count.clone())
                 .with_storage_buffer_data(proxies)
+                .with_buffer(uniform.buffer(LightUniformIndex::Clusters).clone())
+                .with_storage_buffer(uniform.buffer(LightUniformIndex::ClusterGrid).clone())
+                .with_storage_buffer(uniform.buffer(LightUniformIndex::ClusterLights).clone())
                 .build(device);
+            // they still point at the old light buffer
+            self.view_uniforms.clear();
         } else {
             queue.write_buffer(data, 0, bytemuck::cast_slice(proxies));
         }
     }
 
+    pub fn cluster_config(&self) -> ClusterConfig {
+        self.cluster_config
+    }
+
+    pub fn set_cluster_config(&mut self, config: ClusterConfig) {
+        self.cluster_config = config.sanitized();
+    }
+
+    /// Sorts the lights into the clusters of a camera's view, for [`view_uniform`](Self::view_uniform).
+    /// Every view drawn in the same submission needs its own `slot`.
+    pub fn prepare_view(
+        &mut self,
+        slot: usize,
+        camera: &CameraUniform,
+        cache: &AssetCache,
+        queue: &Queue,
+        device: &Device,
+    ) {
+        self.clusters
+            .build(self.cluster_config, camera, &self.proxies);
+        let grid: &[u8] = bytemuck::cast_slice(&self.clusters.grid);
+        let indices: &[u8] = bytemuck::cast_slice(&self.clusters.indices);
+
+        if self.view_uniforms.len() <= slot {
+            self.view_uniforms.resize(slot + 1, None);
+        }
+
+        let fits = self.view_uniforms[slot].as_ref().is_some_and(|uniform| {
+            size_of_val(grid) <= uniform.buffer(LightUniformIndex::ClusterGrid).size() as usize
+                && size_of_val(indices)
+                    <= uniform.buffer(LightUniformIndex::ClusterLights).size() as usize
+        });
+
+        if let Some(uniform) = self.view_uniforms[slot].as_ref().filter(|_| fits) {
+            queue.write_buffer(
+                uniform.buffer(LightUniformIndex::Clusters),
+                0,
+                bytemuck::bytes_of(&self.clusters.params),
+            );
+            queue.write_buffer(uniform.buffer(LightUniformIndex::ClusterGrid), 0, grid);
+            queue.write_buffer(uniform.buffer(LightUniformIndex::ClusterLights), 0, indices);
+        } else {
+            let bgl = cache.bgl_light();
+            let uniform = ShaderUniform::builder(&bgl)
+                .with_buffer(self.uniform.buffer(LightUniformIndex::Count).clone())
+                .with_storage_buffer(self.uniform.buffer(LightUniformIndex::Lights).clone())
+                .with_buffer_data(&self.clusters.params)
+                .with_storage_buffer_data(&self.clusters.grid)
+                // leave room for more lights, so the buffer isn't recreated every frame
+                .with_storage_buffer_data(&padded(&self.clusters.indices))
+                .build(device);
+            self.view_uniforms[slot] = Some(uniform);
+        }
+    }
+
+    /// Returns the light uniform prepared for the view in `slot`, or the unsorted one
+    pub fn view_uniform(&self, slot: usize) -> &ShaderUniform<LightUniformIndex> {
+        self.view_uniforms
+            .get(slot)
+            .and_then(Option::as_ref)
+            .unwrap_or(&self.uniform)
+    }
+
     #[cfg(debug_assertions)]
     pub fn render_debug_lights(&self, renderer: &Renderer, ctx: &crate::rendering::GPUDrawCtx) {
         use crate::assets::HShader;
@@ -267,6 +446,7 @@ impl LightManager {
             };
 
             pass.set_immediates(0, bytemuck::bytes_of(&(i as u32)));
+            ctx.stats.record_line_draw();
             match type_id {
                 LightType::Point => pass.draw(0..2, 0..6),
                 LightType::Sun => pass.draw(0..2, 0..9),
@@ -276,6 +456,13 @@ impl LightManager {
     }
 }
 
+/// Doubles the length of the slice, so it can grow without reallocating the buffer
+fn padded(indices: &[u32]) -> Vec<u32> {
+    let mut padded = indices.to_vec();
+    padded.resize(indices.len() * 2, 0);
+    padded
+}
+
 pub fn proxy_buffer_slice(proxies: &[LightProxy]) -> &[LightProxy] {
     if proxies.is_empty() {
         &[DUMMY_POINT_LIGHT]
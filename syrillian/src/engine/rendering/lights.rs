@@ -20,6 +20,10 @@ pub struct LightProxy {
     pub outer_angle: f32,
     pub type_id: u32, // LightType
     pub shadow_map_id: u32,
+    pub shadow_constant_bias: f32,
+    pub shadow_slope_bias: f32,
+    pub shadow_normal_offset: f32,
+    pub shadow_pcf_taps: u32,
     pub view_mat: Matrix4<f32>,
 }
 
@@ -38,12 +42,16 @@ impl LightProxy {
             outer_angle: 0.0,
             type_id: LightType::Point as u32,
             shadow_map_id: 0,
+            shadow_constant_bias: 0.0,
+            shadow_slope_bias: 0.0,
+            shadow_normal_offset: 0.0,
+            shadow_pcf_taps: 1,
             view_mat: MATRIX4_ID,
         }
     }
 }
 
-ensure_aligned!(LightProxy { position, up, direction, color, view_mat }, align <= 16 * 9 => size);
+ensure_aligned!(LightProxy { position, up, direction, color, view_mat }, align <= 16 * 10 => size);
 
 pub trait Light: Component {
     fn light_type(&self) -> LightType;
@@ -99,6 +107,9 @@ pub enum LightType {
 pub enum LightUniformIndex {
     Count = 0,
     Lights = 1,
+    Clusters = 2,
+    ClusterGrid = 3,
+    ClusterLights = 4,
 }
 
 #[repr(u8)]
@@ -106,4 +117,5 @@ pub enum LightUniformIndex {
 pub enum ShadowUniformIndex {
     ShadowMaps = 0,
     ShadowSampler = 1,
+    AmbientOcclusion = 2,
 }
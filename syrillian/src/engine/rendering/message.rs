@@ -1,10 +1,12 @@
 use crate::assets::HTexture;
-use crate::components::TypedComponentId;
+use crate::components::{CameraClear, TypedComponentId, ViewportRect};
 use crate::core::ObjectHash;
+use crate::rendering::compute::ComputeJob;
 use crate::rendering::lights::LightProxy;
-use crate::rendering::picking::PickRequest;
+use crate::rendering::picking::{PickRequest, PickResult};
 use crate::rendering::proxies::SceneProxy;
 use crate::rendering::render_data::CameraUniform;
+use crate::rendering::stats::RenderStats;
 use crate::rendering::strobe::StrobeFrame;
 use crate::windowing::RenderTargetId;
 use nalgebra::Affine3;
@@ -15,6 +17,39 @@ pub type ProxyUpdateCommand = Box<dyn FnOnce(&mut dyn SceneProxy) + Send>;
 pub type LightProxyCommand = Box<dyn FnOnce(&mut LightProxy) + Send>;
 pub type CameraUpdateCommand = Box<dyn FnOnce(&mut CameraUniform) + Send>;
 
+/// What the renderer sends back to the world
+#[derive(Debug, Copy, Clone)]
+pub enum RenderFeedback {
+    /// The object under the cursor, for a pick request of the world
+    Pick(PickResult),
+    /// What was drawn in the last frame of the primary render target
+    Stats(RenderStats),
+}
+
+/// A camera drawing into a render target, and the region it draws into
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CameraView {
+    pub camera: TypedComponentId,
+    pub viewport: ViewportRect,
+}
+
+/// A camera drawing into a texture asset, and the size it expects the texture to have
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TextureCameraView {
+    pub camera: TypedComponentId,
+    pub texture: HTexture,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// The tessellated egui output of a frame, drawn over the primary render target
+#[cfg(feature = "egui")]
+pub struct EguiPaint {
+    pub primitives: Vec<egui::ClippedPrimitive>,
+    pub textures_delta: egui::TexturesDelta,
+    pub pixels_per_point: f32,
+}
+
 pub enum RenderMsg {
     RegisterProxy(
         TypedComponentId,
@@ -27,14 +62,21 @@ pub enum RenderMsg {
     UpdateTransform(TypedComponentId, Affine3<f32>),
     ProxyUpdate(TypedComponentId, ProxyUpdateCommand),
     LightProxyUpdate(TypedComponentId, LightProxyCommand),
-    UpdateActiveCamera(RenderTargetId, CameraUpdateCommand),
-    ProxyState(TypedComponentId, bool), // enabled
+    UpdateActiveCamera(RenderTargetId, TypedComponentId, CameraUpdateCommand),
+    UpdateCameraClear(RenderTargetId, TypedComponentId, CameraClear),
+    UpdateCameraViews(RenderTargetId, Vec<CameraView>), // sorted by draw order
+    UpdateTextureCameras(Vec<TextureCameraView>),       // sorted by draw order
+    ProxyState(TypedComponentId, bool),                 // enabled
     PickRequest(PickRequest),
     CommandBatch(Vec<RenderMsg>),
     CaptureOffscreenTexture(RenderTargetId, PathBuf),
     CapturePickingTexture(RenderTargetId, PathBuf),
     CaptureTexture(HTexture, PathBuf),
+    CaptureFrame(RenderTargetId, PathBuf),
     UpdateStrobe(StrobeFrame),
+    DispatchCompute(ComputeJob),
+    #[cfg(feature = "egui")]
+    UpdateEgui(EguiPaint),
 }
 
 impl Debug for RenderMsg {
@@ -47,13 +89,20 @@ impl Debug for RenderMsg {
             RenderMsg::ProxyUpdate(..) => "Proxy Update",
             RenderMsg::LightProxyUpdate(..) => "Light Proxy Update",
             RenderMsg::UpdateActiveCamera(..) => "Update Active Camera",
+            RenderMsg::UpdateCameraClear(..) => "Update Camera Clear",
+            RenderMsg::UpdateCameraViews(..) => "Update Camera Views",
+            RenderMsg::UpdateTextureCameras(..) => "Update Texture Cameras",
             RenderMsg::ProxyState(_, enable) => &format!("Proxy Enabled: {enable}"),
             RenderMsg::PickRequest(..) => "Pick Request",
             RenderMsg::CommandBatch(inner) => &format!("Command Batch {inner:?}"),
             RenderMsg::CaptureOffscreenTexture(_, _) => "Capture Offscreen Texture",
             RenderMsg::CapturePickingTexture(_, _) => "Capture Picking Texture",
             RenderMsg::CaptureTexture(_, _) => "Capture Texture",
+            RenderMsg::CaptureFrame(_, _) => "Capture Frame",
             RenderMsg::UpdateStrobe(_) => "Update Strobe Draw List",
+            RenderMsg::DispatchCompute(_) => "Dispatch Compute",
+            #[cfg(feature = "egui")]
+            RenderMsg::UpdateEgui(_) => "Update Egui",
         };
 
         write!(f, "{name}")
@@ -27,9 +27,15 @@
 //!
 //! You can create scene proxies in [`Components`](crate::components)
 
+mod ambient_occlusion;
 pub mod cache;
+pub mod compute;
 mod context;
+#[cfg(feature = "egui")]
+mod egui_pass;
 pub mod error;
+mod gbuffer;
+pub mod light_clusters;
 pub mod light_manager;
 pub mod lights;
 pub mod message;
@@ -39,7 +45,9 @@ mod post_process_pass;
 pub mod proxies;
 pub(crate) mod render_data;
 pub mod renderer;
+mod scene_depth;
 pub mod state;
+pub mod stats;
 pub mod texture_export;
 pub(crate) mod uniform;
 
@@ -48,12 +56,16 @@ pub mod debug_renderer;
 pub mod strobe;
 
 pub use cache::*;
+pub use compute::{ComputeJob, ComputeOutput, ComputeReadback};
 pub use context::*;
 pub use message::*;
 pub use picking::*;
+pub use stats::{FrameStats, RenderStats};
 
 #[cfg(debug_assertions)]
 pub use debug_renderer::*;
 
+pub(crate) use ambient_occlusion::AMBIENT_OCCLUSION_FORMAT;
+pub(crate) use gbuffer::GBUFFER_FORMAT;
 pub(crate) use renderer::*;
 pub(crate) use state::*;
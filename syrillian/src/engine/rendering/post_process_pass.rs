@@ -1,7 +1,10 @@
+use crate::engine::rendering::gbuffer::GBufferSurface;
 use crate::engine::rendering::uniform::ShaderUniform;
+use crate::ensure_aligned;
+use nalgebra::Vector3;
 use syrillian_macros::UniformIndex;
 use wgpu::{
-    AddressMode, BindGroupLayout, Device, FilterMode, MipmapFilterMode, SamplerDescriptor,
+    AddressMode, BindGroupLayout, Device, FilterMode, MipmapFilterMode, Queue, SamplerDescriptor,
     TextureView,
 };
 
@@ -11,10 +14,74 @@ pub enum PostProcessUniformIndex {
     Color = 0,
     Sampler = 1,
     Depth = 2,
+    Fog = 3,
+    Ssao = 4,
+    Normal = 5,
+    Position = 6,
+}
+
+/// Linear depth fog applied by the built-in post-processing pass
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct FogUniform {
+    pub(crate) color: Vector3<f32>,
+    pub(crate) enabled: u32,
+    pub(crate) start: f32,
+    pub(crate) end: f32,
+    pub(crate) _padding: [u32; 2],
+}
+
+ensure_aligned!(FogUniform { color }, align <= 16 * 2 => size);
+
+impl FogUniform {
+    pub fn new(color: Vector3<f32>, start: f32, end: f32) -> Self {
+        FogUniform {
+            color,
+            enabled: 1,
+            start,
+            end,
+            _padding: [0; 2],
+        }
+    }
+
+    pub fn disabled() -> Self {
+        FogUniform::default()
+    }
+}
+
+/// Screen-space ambient occlusion, computed from the G-buffer before the color pass
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct SsaoUniform {
+    pub(crate) enabled: u32,
+    pub(crate) radius: f32,
+    pub(crate) intensity: f32,
+    pub(crate) bias: f32,
+}
+
+ensure_aligned!(SsaoUniform { enabled, radius }, align <= 4 * 4 => size);
+
+impl Default for SsaoUniform {
+    fn default() -> Self {
+        SsaoUniform {
+            enabled: 0,
+            radius: 0.5,
+            intensity: 1.0,
+            bias: 0.025,
+        }
+    }
+}
+
+/// Settings of the built-in post-processing effects, shared by all render targets
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct PostProcessSettings {
+    pub(crate) fog: FogUniform,
+    pub(crate) ssao: SsaoUniform,
 }
 
 pub struct PostProcessData {
     pub(crate) uniform: ShaderUniform<PostProcessUniformIndex>,
+    pub(crate) settings: PostProcessSettings,
 }
 
 impl PostProcessData {
@@ -23,6 +90,8 @@ impl PostProcessData {
         layout: &BindGroupLayout,
         color_view: &TextureView,
         depth_view: &TextureView,
+        gbuffer: &GBufferSurface,
+        settings: PostProcessSettings,
     ) -> Self {
         let sampler = device.create_sampler(&SamplerDescriptor {
             label: Some("PostProcess Sampler"),
@@ -39,8 +108,26 @@ impl PostProcessData {
             .with_texture(color_view)
             .with_sampler(&sampler)
             .with_texture(depth_view)
+            .with_buffer_data(&settings.fog)
+            .with_buffer_data(&settings.ssao)
+            .with_texture(gbuffer.normal_view())
+            .with_texture(gbuffer.position_view())
             .build(device);
 
-        Self { uniform }
+        Self { uniform, settings }
+    }
+
+    pub(crate) fn set_settings(&mut self, queue: &Queue, settings: PostProcessSettings) {
+        self.settings = settings;
+        queue.write_buffer(
+            self.uniform.buffer(PostProcessUniformIndex::Fog),
+            0,
+            bytemuck::bytes_of(&settings.fog),
+        );
+        queue.write_buffer(
+            self.uniform.buffer(PostProcessUniformIndex::Ssao),
+            0,
+            bytemuck::bytes_of(&settings.ssao),
+        );
     }
 }
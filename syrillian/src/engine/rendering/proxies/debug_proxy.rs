@@ -122,7 +122,7 @@ impl DebugSceneProxy {
         };
         let uniform = ShaderUniform::builder(&bgl)
             .with_buffer_data(&mesh_data)
-            .with_buffer_data(&BoneData::DUMMY)
+            .with_storage_buffer_data(&BoneData::RIGID)
             .build(device);
 
         Some(RuntimeMeshData { mesh_data, uniform })
@@ -179,6 +179,7 @@ impl DebugSceneProxy {
 
         pass.set_vertex_buffer(0, line_buffer.slice(..));
         let vertices = self.lines.len() as u32 * 2;
+        ctx.stats.record_line_draw();
         pass.draw(0..vertices, 0..1);
     }
 
@@ -211,7 +212,7 @@ impl DebugSceneProxy {
                 pass.set_bind_group(idx, data.uniform.bind_group(), &[]);
             }
 
-            runtime_mesh.draw_all(&mut pass);
+            runtime_mesh.draw_all(&mut pass, ctx.stats);
         }
     }
 }
@@ -0,0 +1,200 @@
+use crate::assets::{AssetStore, HShader, HTexture};
+use crate::components::mesh_renderer::BoneData;
+use crate::core::{BoundingSphere, ModelUniform};
+use crate::rendering::cache::{
+    MaterialParams, MaterialUniform, MaterialUniformIndex, NO_CUSTOM_PARAMS,
+};
+use crate::rendering::proxies::mesh_proxy::{MeshUniformIndex, RuntimeMeshData};
+use crate::rendering::proxies::{PROXY_PRIORITY_DECAL, SceneProxy, SceneProxyBinding};
+use crate::rendering::uniform::ShaderUniform;
+use crate::rendering::{AssetCache, GPUDrawCtx, Renderer};
+use crate::{proxy_data, proxy_data_mut, try_activate_shader};
+use nalgebra::{Matrix4, Vector3};
+use std::any::Any;
+use wgpu::{Device, Queue};
+
+/// Radius of the unit cube the decal volume is built from
+const UNIT_CUBE_RADIUS: f32 = 0.866_025_4;
+
+#[derive(Debug)]
+pub struct DecalRenderData {
+    model: RuntimeMeshData,
+    material: ShaderUniform<MaterialUniformIndex>,
+    material_data: MaterialUniform,
+    world_to_decal: Matrix4<f32>,
+}
+
+/// Projects a texture onto everything inside an oriented box.
+///
+/// The box spans `size` around the local origin and projects along its local Y axis.
+#[derive(Debug, Clone)]
+pub struct DecalProxy {
+    pub texture: HTexture,
+    pub size: Vector3<f32>,
+    pub color: Vector3<f32>,
+    pub alpha: f32,
+    pub texture_dirty: bool,
+}
+
+impl DecalProxy {
+    pub fn new(texture: HTexture, size: Vector3<f32>) -> Self {
+        Self {
+            texture,
+            size,
+            color: Vector3::new(1.0, 1.0, 1.0),
+            alpha: 1.0,
+            texture_dirty: false,
+        }
+    }
+
+    fn volume_matrix(&self, local_to_world: &Matrix4<f32>) -> Matrix4<f32> {
+        local_to_world * Matrix4::new_nonuniform_scaling(&self.size)
+    }
+
+    fn material_data(&self) -> MaterialUniform {
+        MaterialUniform {
+            diffuse: self.color,
+            roughness: 1.0,
+            metallic: 0.0,
+            alpha: self.alpha,
+            params: MaterialParams::use_diffuse_texture | MaterialParams::has_transparency,
+            _padding: 0x0,
+        }
+    }
+
+    fn new_material_uniform(
+        &self,
+        cache: &AssetCache,
+        device: &Device,
+        data: &MaterialUniform,
+    ) -> ShaderUniform<MaterialUniformIndex> {
+        let bgl = cache.bgl_material();
+        let diffuse = cache.texture(self.texture);
+        let normal = cache.texture(HTexture::FALLBACK_NORMAL);
+        let roughness = cache.texture(HTexture::FALLBACK_ROUGHNESS);
+
+        ShaderUniform::<MaterialUniformIndex>::builder(&bgl)
+            .with_buffer_data(data)
+            .with_texture(&diffuse.view)
+            .with_sampler(&diffuse.sampler)
+            .with_texture(&normal.view)
+            .with_sampler(&normal.sampler)
+            .with_texture(&roughness.view)
+            .with_sampler(&roughness.sampler)
+            .with_buffer_data(&NO_CUSTOM_PARAMS)
+            .build(device)
+    }
+
+    fn update_material(
+        &mut self,
+        data: &mut DecalRenderData,
+        cache: &AssetCache,
+        device: &Device,
+        queue: &Queue,
+    ) {
+        data.material_data = self.material_data();
+
+        if self.texture_dirty {
+            data.material = self.new_material_uniform(cache, device, &data.material_data);
+            self.texture_dirty = false;
+            return;
+        }
+
+        queue.write_buffer(
+            data.material.buffer(MaterialUniformIndex::Material),
+            0,
+            bytemuck::bytes_of(&data.material_data),
+        );
+    }
+}
+
+impl SceneProxy for DecalProxy {
+    fn setup_render(&mut self, renderer: &Renderer, local_to_world: &Matrix4<f32>) -> Box<dyn Any> {
+        let device = &renderer.state.device;
+        let model_bgl = renderer.cache.bgl_model();
+
+        let volume = self.volume_matrix(local_to_world);
+        let mesh_data = ModelUniform::from_matrix(&volume);
+        let uniform = ShaderUniform::<MeshUniformIndex>::builder(&model_bgl)
+            .with_buffer_data(&mesh_data)
+            .with_storage_buffer_data(&BoneData::RIGID)
+            .build(device);
+
+        let material_data = self.material_data();
+        let material = self.new_material_uniform(&renderer.cache, device, &material_data);
+        self.texture_dirty = false;
+
+        Box::new(DecalRenderData {
+            model: RuntimeMeshData { mesh_data, uniform },
+            material,
+            material_data,
+            world_to_decal: volume.try_inverse().unwrap_or_else(Matrix4::zeros),
+        })
+    }
+
+    fn update_render(
+        &mut self,
+        renderer: &Renderer,
+        data: &mut dyn Any,
+        local_to_world: &Matrix4<f32>,
+    ) {
+        let data: &mut DecalRenderData = proxy_data_mut!(data);
+
+        let volume = self.volume_matrix(local_to_world);
+        data.model.mesh_data.model_mat = volume;
+        // a degenerate volume covers no pixels, so the zeroed inverse is never used
+        data.world_to_decal = volume.try_inverse().unwrap_or_else(Matrix4::zeros);
+
+        renderer.state.queue.write_buffer(
+            data.model.uniform.buffer(MeshUniformIndex::MeshData),
+            0,
+            bytemuck::bytes_of(&data.model.mesh_data),
+        );
+
+        self.update_material(
+            data,
+            &renderer.cache,
+            &renderer.state.device,
+            &renderer.state.queue,
+        );
+    }
+
+    // Decals are drawn in their own pass, once the scene depth is complete
+    fn render(&self, _renderer: &Renderer, _ctx: &GPUDrawCtx, _binding: &SceneProxyBinding) {}
+
+    fn render_decals(&self, renderer: &Renderer, ctx: &GPUDrawCtx, binding: &SceneProxyBinding) {
+        let data: &DecalRenderData = proxy_data!(binding.proxy_data());
+
+        if self.alpha <= 0.0 {
+            return;
+        }
+
+        let shader = renderer.cache.shader(HShader::DECAL);
+        let mut pass = ctx.pass.write().unwrap();
+        try_activate_shader!(shader, &mut pass, ctx => return);
+
+        let groups = shader.bind_groups();
+        if let Some(idx) = groups.model {
+            pass.set_bind_group(idx, data.model.uniform.bind_group(), &[]);
+        }
+        if let Some(idx) = groups.material {
+            pass.set_bind_group(idx, data.material.bind_group(), &[]);
+        }
+
+        pass.set_immediates(0, bytemuck::bytes_of(&data.world_to_decal));
+        ctx.stats.record_draw(36, 1);
+        pass.draw(0..36, 0..1);
+    }
+
+    fn priority(&self, _store: &AssetStore) -> u32 {
+        PROXY_PRIORITY_DECAL
+    }
+
+    fn bounds(&self, local_to_world: &Matrix4<f32>) -> Option<BoundingSphere> {
+        let unit = BoundingSphere {
+            center: Vector3::zeros(),
+            radius: UNIT_CUBE_RADIUS,
+        };
+        Some(unit.transformed(&self.volume_matrix(local_to_world)))
+    }
+}
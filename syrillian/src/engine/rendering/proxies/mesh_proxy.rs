@@ -1,5 +1,5 @@
 use crate::assets::{AssetStore, H, HMaterial, HMesh, HShader, Shader};
-use crate::components::mesh_renderer::BoneData;
+use crate::components::mesh_renderer::{BoneData, Outline, resolve_material};
 use crate::core::{BoundingSphere, ModelUniform};
 #[cfg(debug_assertions)]
 use crate::rendering::DebugRenderer;
@@ -12,7 +12,7 @@ use crate::rendering::{
     AssetCache, GPUDrawCtx, RenderPassType, Renderer, RuntimeMesh, RuntimeShader,
 };
 use crate::{proxy_data, proxy_data_mut, try_activate_shader};
-use nalgebra::Matrix4;
+use nalgebra::{Matrix4, Vector4};
 use std::any::Any;
 use std::ops::Range;
 use std::sync::RwLockWriteGuard;
@@ -26,6 +26,14 @@ pub enum MeshUniformIndex {
     BoneData = 1,
 }
 
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct OutlineImmediates {
+    pub color: Vector4<f32>,
+    pub width: f32,
+    _padding: [f32; 3],
+}
+
 #[derive(Debug, Clone)]
 pub struct RuntimeMeshData {
     pub mesh_data: ModelUniform,
@@ -38,6 +46,11 @@ pub struct RuntimeMeshData {
 pub struct MeshSceneProxy {
     pub mesh: HMesh,
     pub materials: Vec<HMaterial>,
+    /// Replaces all materials for this instance only
+    pub material_override: Option<HMaterial>,
+    /// Replaces the material of single submeshes for this instance only
+    pub submesh_overrides: Vec<Option<HMaterial>>,
+    pub outline: Option<Outline>,
     pub material_ranges: Vec<Range<u32>>,
     pub bone_data: BoneData,
     pub bones_dirty: bool,
@@ -77,11 +90,16 @@ impl SceneProxy for MeshSceneProxy {
         // TODO: Consider Rigid Body render isometry interpolation for mesh local to world
 
         if self.bones_dirty {
-            renderer.state.queue.write_buffer(
-                data.uniform.buffer(MeshUniformIndex::BoneData),
-                0,
-                self.bone_data.as_bytes(),
-            );
+            let bones = data.uniform.buffer(MeshUniformIndex::BoneData);
+            // the palette is sized to the skeleton, so a new mesh can need a bigger buffer
+            if bones.size() == self.bone_data.as_bytes().len() as u64 {
+                renderer
+                    .state
+                    .queue
+                    .write_buffer(bones, 0, self.bone_data.as_bytes());
+            } else {
+                *data = self.setup_mesh_data(renderer, local_to_world);
+            }
             self.bones_dirty = false;
         }
 
@@ -104,6 +122,14 @@ impl SceneProxy for MeshSceneProxy {
         let mut pass = ctx.pass.write().unwrap();
         self.draw_mesh(ctx, &renderer.cache, &mesh, data, &mut pass);
 
+        // after every opaque object, so an outline on top can be drawn over the ones in front
+        if let Some(outline) = self.outline
+            && ctx.pass_type == RenderPassType::Color
+            && ctx.transparency_pass
+        {
+            self.draw_outline(ctx, &renderer.cache, &mesh, data, &mut pass, outline);
+        }
+
         #[cfg(debug_assertions)]
         if !ctx.transparency_pass && DebugRenderer::mesh_edges() {
             draw_edges(ctx, &renderer.cache, &mesh, data, &mut pass);
@@ -152,18 +178,37 @@ impl SceneProxy for MeshSceneProxy {
         pass.set_immediates(0, bytemuck::bytes_of(&color));
 
         if mesh_data.material_ranges.is_empty() {
-            mesh.draw_all(&mut pass);
+            mesh.draw_all(&mut pass, ctx.stats);
             return;
         }
 
         for range in mesh_data.material_ranges.iter() {
-            mesh.draw(range.clone(), &mut pass);
+            mesh.draw(range.clone(), &mut pass, ctx.stats);
         }
     }
 
+    fn render_gbuffer(&self, renderer: &Renderer, ctx: &GPUDrawCtx, binding: &SceneProxyBinding) {
+        let data: &RuntimeMeshData = proxy_data!(binding.proxy_data());
+
+        let Some(mesh) = renderer.cache.mesh(self.mesh) else {
+            return;
+        };
+
+        let mut pass = ctx.pass.write().unwrap();
+        let shader = renderer.cache.shader(HShader::GBUFFER);
+        try_activate_shader!(shader, &mut pass, ctx => return);
+
+        if let Some(model) = shader.bind_groups().model {
+            pass.set_bind_group(model, data.uniform.bind_group(), &[]);
+        }
+
+        mesh.draw_all(&mut pass, ctx.stats);
+    }
+
     fn priority(&self, store: &AssetStore) -> u32 {
-        if self.materials.iter().any(|m| {
-            let material = store.materials.get(*m);
+        let submeshes = self.material_ranges.len().max(self.materials.len());
+        if (0..submeshes).any(|i| {
+            let material = store.materials.get(self.material_for(i));
             material.is_transparent()
         }) {
             PROXY_PRIORITY_TRANSPARENT
@@ -178,6 +223,16 @@ impl SceneProxy for MeshSceneProxy {
 }
 
 impl MeshSceneProxy {
+    /// Returns the material submesh `i` is drawn with, with overrides applied
+    pub fn material_for(&self, i: usize) -> HMaterial {
+        resolve_material(
+            &self.materials,
+            self.material_override,
+            &self.submesh_overrides,
+            i,
+        )
+    }
+
     fn draw_mesh(
         &self,
         ctx: &GPUDrawCtx,
@@ -185,6 +240,19 @@ impl MeshSceneProxy {
         mesh: &RuntimeMesh,
         runtime: &RuntimeMeshData,
         pass: &mut RwLockWriteGuard<RenderPass>,
+    ) {
+        self.draw_submeshes(ctx, cache, mesh, runtime, pass, ctx.transparency_pass);
+    }
+
+    /// Draws the submeshes whose material transparency matches `transparent`
+    fn draw_submeshes(
+        &self,
+        ctx: &GPUDrawCtx,
+        cache: &AssetCache,
+        mesh: &RuntimeMesh,
+        runtime: &RuntimeMeshData,
+        pass: &mut RwLockWriteGuard<RenderPass>,
+        transparent: bool,
     ) {
         let current_shader = HShader::DIM3;
         let shader = cache.shader_3d();
@@ -193,7 +261,41 @@ impl MeshSceneProxy {
             return;
         }
 
-        self.draw_materials(ctx, cache, mesh, runtime, pass, current_shader);
+        self.draw_materials(ctx, cache, mesh, runtime, pass, current_shader, transparent);
+    }
+
+    fn draw_outline(
+        &self,
+        ctx: &GPUDrawCtx,
+        cache: &AssetCache,
+        mesh: &RuntimeMesh,
+        runtime: &RuntimeMeshData,
+        pass: &mut RwLockWriteGuard<RenderPass>,
+        outline: Outline,
+    ) {
+        let h_shader = if outline.on_top {
+            HShader::OUTLINE_ON_TOP
+        } else {
+            HShader::OUTLINE
+        };
+        let shader = cache.shader(h_shader);
+        if !runtime.activate_shader(&shader, ctx, pass) {
+            return;
+        }
+
+        let immediates = OutlineImmediates {
+            color: outline.color,
+            width: outline.width,
+            _padding: [0.0; 3],
+        };
+        pass.set_immediates(0, bytemuck::bytes_of(&immediates));
+        mesh.draw_all(pass, ctx.stats);
+
+        // the outline on top covers the whole silhouette, so the visible part of the mesh is
+        // drawn again. It passes the depth test exactly where it's in front.
+        if outline.on_top {
+            self.draw_submeshes(ctx, cache, mesh, runtime, pass, false);
+        }
     }
 
     fn draw_materials(
@@ -204,17 +306,13 @@ impl MeshSceneProxy {
         runtime: &RuntimeMeshData,
         pass: &mut RwLockWriteGuard<RenderPass>,
         current_shader: H<Shader>,
+        transparent: bool,
     ) {
         for (i, range) in self.material_ranges.iter().enumerate() {
-            let h_mat = self
-                .materials
-                .get(i)
-                .cloned()
-                .unwrap_or(HMaterial::FALLBACK);
-            let material = cache.material(h_mat);
+            let material = cache.material(self.material_for(i));
 
             if ctx.pass_type == RenderPassType::Color
-                && material.data.has_transparency() ^ ctx.transparency_pass
+                && material.data.has_transparency() ^ transparent
             {
                 continue; // either transparent in a non-transparency pass, or transparent in a non-transparency pass
             }
@@ -236,7 +334,7 @@ impl MeshSceneProxy {
                 pass.set_bind_group(idx, material.uniform.bind_group(), &[]);
             }
 
-            mesh.draw(range.clone(), pass);
+            mesh.draw(range.clone(), pass, ctx.stats);
         }
     }
 
@@ -251,7 +349,7 @@ impl MeshSceneProxy {
 
         let uniform = ShaderUniform::<MeshUniformIndex>::builder(&model_bgl)
             .with_buffer_data(&mesh_data)
-            .with_buffer_data_slice(self.bone_data.bones.as_slice())
+            .with_storage_buffer_data(self.bone_data.bones.as_slice())
             .build(device);
 
         RuntimeMeshData { mesh_data, uniform }
@@ -277,7 +375,7 @@ fn draw_edges(
 
     pass.set_immediates(0, bytemuck::bytes_of(&COLOR));
 
-    mesh.draw_all(pass);
+    mesh.draw_all(pass, ctx.stats);
 }
 
 #[cfg(debug_assertions)]
@@ -293,5 +391,5 @@ fn draw_vertex_normals(
         return;
     }
 
-    mesh.draw_all_as_instances(0..2, pass);
+    mesh.draw_all_as_instances(0..2, pass, ctx.stats);
 }
@@ -6,13 +6,17 @@ use nalgebra::{Affine3, Matrix4};
 use std::any::Any;
 use std::fmt::Debug;
 
+pub mod decal_proxy;
 pub mod mesh_proxy;
+pub mod particle_proxy;
 pub mod text_proxy;
 
 #[cfg(debug_assertions)]
 pub mod debug_proxy;
 
+pub use decal_proxy::*;
 pub use mesh_proxy::*;
+pub use particle_proxy::*;
 pub use text_proxy::*;
 
 use crate::assets::AssetStore;
@@ -49,9 +53,13 @@ macro_rules! proxy_data {
 
 pub const PROXY_PRIORITY_SOLID: u32 = 99;
 pub const PROXY_PRIORITY_TRANSPARENT: u32 = 999;
+pub const PROXY_PRIORITY_DECAL: u32 = 1999;
 
 pub trait SceneProxy: Send + Any + Debug {
+    /// Creates the GPU data of the proxy once, when it's registered
     fn setup_render(&mut self, renderer: &Renderer, local_to_world: &Matrix4<f32>) -> Box<dyn Any>;
+    /// Called every frame with the data from [`setup_render`](Self::setup_render). Write into
+    /// its buffers instead of replacing them, so a proxy that didn't change doesn't allocate.
     fn update_render(
         &mut self,
         renderer: &Renderer,
@@ -76,6 +84,19 @@ pub trait SceneProxy: Send + Any + Debug {
     ) {
     }
 
+    /// Draws into the scene after all opaque and transparent geometry, with the scene depth bound
+    fn render_decals(&self, _renderer: &Renderer, _ctx: &GPUDrawCtx, _binding: &SceneProxyBinding) {
+    }
+
+    /// Writes world space normals and positions of opaque geometry into the G-buffer
+    fn render_gbuffer(
+        &self,
+        _renderer: &Renderer,
+        _ctx: &GPUDrawCtx,
+        _binding: &SceneProxyBinding,
+    ) {
+    }
+
     fn priority(&self, store: &AssetStore) -> u32;
 
     fn bounds(&self, _local_to_world: &Matrix4<f32>) -> Option<BoundingSphere> {
@@ -151,6 +172,8 @@ impl SceneProxyBinding {
             RenderPassType::Picking | RenderPassType::PickingUi => {
                 self.proxy.render_picking(renderer, ctx, self)
             }
+            RenderPassType::Decal => self.proxy.render_decals(renderer, ctx, self),
+            RenderPassType::GBuffer => self.proxy.render_gbuffer(renderer, ctx, self),
         }
     }
 }
@@ -0,0 +1,186 @@
+use crate::assets::{AssetStore, HShader, HTexture};
+use crate::core::BoundingSphere;
+use crate::ensure_aligned;
+use crate::rendering::cache::{
+    MaterialParams, MaterialUniform, MaterialUniformIndex, NO_CUSTOM_PARAMS,
+};
+use crate::rendering::proxies::{PROXY_PRIORITY_TRANSPARENT, SceneProxy, SceneProxyBinding};
+use crate::rendering::uniform::ShaderUniform;
+use crate::rendering::{AssetCache, GPUDrawCtx, RenderPassType, Renderer};
+use crate::{proxy_data, proxy_data_mut, try_activate_shader};
+use nalgebra::{Matrix4, Vector3, Vector4};
+use std::any::Any;
+use wgpu::util::{BufferInitDescriptor, DeviceExt};
+use wgpu::{Buffer, BufferUsages, Device};
+
+/// A single particle as it is uploaded to the GPU, positioned in world space
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ParticleInstance {
+    pub position: Vector3<f32>,
+    pub size: f32,
+    pub color: Vector4<f32>,
+}
+
+ensure_aligned!(ParticleInstance { position, color }, align <= 16 * 2 => size);
+
+#[derive(Debug)]
+pub struct ParticleRenderData {
+    instances: Buffer,
+    capacity: usize,
+    material: ShaderUniform<MaterialUniformIndex>,
+}
+
+/// Draws the particles of an emitter as camera-facing quads, one instance per particle.
+///
+/// Particles are simulated in world space, so the proxy transform is ignored.
+#[derive(Debug, Clone, Default)]
+pub struct ParticleProxy {
+    /// Expected to be sorted back to front
+    pub instances: Vec<ParticleInstance>,
+    pub texture: Option<HTexture>,
+    pub texture_dirty: bool,
+    pub instances_dirty: bool,
+    pub bounds: Option<BoundingSphere>,
+}
+
+impl ParticleProxy {
+    pub fn new(texture: Option<HTexture>) -> Self {
+        Self {
+            texture,
+            ..Self::default()
+        }
+    }
+
+    fn new_material_uniform(
+        &self,
+        cache: &AssetCache,
+        device: &Device,
+    ) -> ShaderUniform<MaterialUniformIndex> {
+        let mut params = MaterialParams::has_transparency;
+        if self.texture.is_some() {
+            params |= MaterialParams::use_diffuse_texture;
+        }
+
+        let data = MaterialUniform {
+            diffuse: Vector3::new(1.0, 1.0, 1.0),
+            roughness: 1.0,
+            metallic: 0.0,
+            alpha: 1.0,
+            params,
+            _padding: 0x0,
+        };
+
+        let bgl = cache.bgl_material();
+        let diffuse = cache.texture_opt(self.texture, HTexture::FALLBACK_DIFFUSE);
+        let normal = cache.texture(HTexture::FALLBACK_NORMAL);
+        let roughness = cache.texture(HTexture::FALLBACK_ROUGHNESS);
+
+        ShaderUniform::<MaterialUniformIndex>::builder(&bgl)
+            .with_buffer_data(&data)
+            .with_texture(&diffuse.view)
+            .with_sampler(&diffuse.sampler)
+            .with_texture(&normal.view)
+            .with_sampler(&normal.sampler)
+            .with_texture(&roughness.view)
+            .with_sampler(&roughness.sampler)
+            .with_buffer_data(&NO_CUSTOM_PARAMS)
+            .build(device)
+    }
+
+    fn new_instance_buffer(&self, device: &Device, capacity: usize) -> Buffer {
+        let mut contents = self.instances.clone();
+        contents.resize(capacity, bytemuck::Zeroable::zeroed());
+
+        device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("Particle Instance Buffer"),
+            contents: bytemuck::cast_slice(&contents),
+            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+        })
+    }
+}
+
+impl SceneProxy for ParticleProxy {
+    fn setup_render(
+        &mut self,
+        renderer: &Renderer,
+        _local_to_world: &Matrix4<f32>,
+    ) -> Box<dyn Any> {
+        let device = &renderer.state.device;
+        let capacity = self.instances.len().max(1);
+
+        let data = ParticleRenderData {
+            instances: self.new_instance_buffer(device, capacity),
+            capacity,
+            material: self.new_material_uniform(&renderer.cache, device),
+        };
+        self.texture_dirty = false;
+        self.instances_dirty = false;
+
+        Box::new(data)
+    }
+
+    fn update_render(
+        &mut self,
+        renderer: &Renderer,
+        data: &mut dyn Any,
+        _local_to_world: &Matrix4<f32>,
+    ) {
+        let data: &mut ParticleRenderData = proxy_data_mut!(data);
+        let device = &renderer.state.device;
+
+        if self.texture_dirty {
+            data.material = self.new_material_uniform(&renderer.cache, device);
+            self.texture_dirty = false;
+        }
+
+        if !self.instances_dirty {
+            return;
+        }
+        self.instances_dirty = false;
+
+        if self.instances.len() > data.capacity {
+            // grow geometrically so steady emission doesn't reallocate every frame
+            data.capacity = self.instances.len().next_power_of_two();
+            data.instances = self.new_instance_buffer(device, data.capacity);
+            return;
+        }
+
+        renderer.state.queue.write_buffer(
+            &data.instances,
+            0,
+            bytemuck::cast_slice(&self.instances),
+        );
+    }
+
+    fn render(&self, renderer: &Renderer, ctx: &GPUDrawCtx, binding: &SceneProxyBinding) {
+        if ctx.pass_type != RenderPassType::Color || !ctx.transparency_pass {
+            return;
+        }
+        if self.instances.is_empty() {
+            return;
+        }
+
+        let data: &ParticleRenderData = proxy_data!(binding.proxy_data());
+
+        let shader = renderer.cache.shader(HShader::PARTICLE);
+        let mut pass = ctx.pass.write().unwrap();
+        try_activate_shader!(shader, &mut pass, ctx => return);
+
+        if let Some(idx) = shader.bind_groups().material {
+            pass.set_bind_group(idx, data.material.bind_group(), &[]);
+        }
+
+        pass.set_vertex_buffer(0, data.instances.slice(..));
+        ctx.stats.record_draw(6, self.instances.len() as u32);
+        pass.draw(0..6, 0..self.instances.len() as u32);
+    }
+
+    fn priority(&self, _store: &AssetStore) -> u32 {
+        PROXY_PRIORITY_TRANSPARENT
+    }
+
+    fn bounds(&self, _local_to_world: &Matrix4<f32>) -> Option<BoundingSphere> {
+        self.bounds
+    }
+}
@@ -1,12 +1,16 @@
-use crate::assets::{AssetStore, HFont, HShader};
+use crate::assets::{AssetStore, DEFAULT_MSDF_RANGE, HFont, HShader};
+use crate::components::BillboardMode;
 use crate::components::mesh_renderer::BoneData;
 use crate::core::ModelUniform;
 #[cfg(debug_assertions)]
 use crate::rendering::DebugRenderer;
-use crate::rendering::glyph::{GlyphRenderData, generate_glyph_geometry_stream};
+use crate::rendering::glyph::{
+    GlyphRenderData, generate_glyph_geometry_stream, generate_rich_glyph_geometry_stream,
+};
 use crate::rendering::picking::hash_to_rgba;
 use crate::rendering::proxies::mesh_proxy::MeshUniformIndex;
 use crate::rendering::proxies::{PROXY_PRIORITY_TRANSPARENT, SceneProxy, SceneProxyBinding};
+use crate::rendering::rich_text::{StyledRun, parse_rich_text};
 use crate::rendering::strobe::TextAlignment;
 use crate::rendering::uniform::ShaderUniform;
 use crate::rendering::{AssetCache, CPUDrawCtx, GPUDrawCtx, RenderPassType, Renderer};
@@ -15,7 +19,7 @@ use crate::windowing::RenderTargetId;
 use crate::{ensure_aligned, must_pipeline, proxy_data, proxy_data_mut, try_activate_shader};
 use delegate::delegate;
 use etagere::euclid::approxeq::ApproxEq;
-use nalgebra::{Matrix4, Vector2, Vector3};
+use nalgebra::{Affine3, Matrix4, Vector2, Vector3};
 use std::any::Any;
 use std::fmt::Debug;
 use std::marker::PhantomData;
@@ -42,6 +46,16 @@ pub struct TextImmediates {
 
 ensure_aligned!(TextImmediates { position, color }, align <= 16 * 2 => size);
 
+/// The parts of the active camera that 3D text needs to face it or to keep a constant size
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TextCameraView {
+    pub position: Vector3<f32>,
+    pub up: Vector3<f32>,
+    pub forward: Vector3<f32>,
+    /// The world size of one pixel at a view depth of 1
+    pub pixel_size: f32,
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct ThreeD;
 #[derive(Debug, Copy, Clone)]
@@ -59,6 +73,8 @@ pub trait TextDim<const D: u8>: Copy + Clone + Debug + Send + Sync + 'static {
 #[derive(Debug, Clone)]
 pub struct TextProxy<const D: u8, DIM: TextDim<D>> {
     text: String,
+    /// Set if the text was given as rich text. `text` then holds it without styling.
+    styled_runs: Option<Vec<StyledRun>>,
     alignment: TextAlignment,
     last_text_len: usize,
     glyph_data: Vec<GlyphRenderData>,
@@ -69,7 +85,10 @@ pub struct TextProxy<const D: u8, DIM: TextDim<D>> {
 
     pc: TextImmediates,
     rainbow_mode: bool,
+    face_camera: bool,
+    constant_screen_size: bool,
     constants_dirty: bool,
+    camera_view: Option<TextCameraView>,
     translation: ModelUniform,
 
     draw_order: u32,
@@ -84,6 +103,7 @@ impl<const D: u8, DIM: TextDim<D>> TextProxy<D, DIM> {
     pub fn new(text: String, font: HFont, em_scale: f32) -> Self {
         Self {
             text,
+            styled_runs: None,
             alignment: TextAlignment::Left,
             last_text_len: 0,
             glyph_data: Vec::new(),
@@ -96,11 +116,14 @@ impl<const D: u8, DIM: TextDim<D>> TextProxy<D, DIM> {
                 em_scale,
                 position: Vector2::zeros(),
                 color: Vector3::new(1., 1., 1.),
-                msdf_range_px: 4.0,
+                msdf_range_px: DEFAULT_MSDF_RANGE,
                 padding: 0,
             },
             rainbow_mode: false,
+            face_camera: false,
+            constant_screen_size: false,
             constants_dirty: false,
+            camera_view: None,
             translation: ModelUniform::empty(),
 
             draw_order: 0,
@@ -144,6 +167,10 @@ impl<const D: u8, DIM: TextDim<D>> TextProxy<D, DIM> {
             pub fn letter_spacing(&self) -> f32;
             #[field]
             pub fn rainbow_mode(&self) -> bool;
+            #[field]
+            pub fn face_camera(&self) -> bool;
+            #[field]
+            pub fn constant_screen_size(&self) -> bool;
         }
 
         to self.pc {
@@ -160,11 +187,15 @@ impl<const D: u8, DIM: TextDim<D>> TextProxy<D, DIM> {
         if self.constants_dirty {
             let constants = self.pc;
             let rainbow_mode = self.rainbow_mode;
+            let face_camera = self.face_camera;
+            let constant_screen_size = self.constant_screen_size;
             ctx.send_proxy_update(move |proxy| {
                 let proxy: &mut Self = proxy_data_mut!(proxy);
 
                 proxy.pc = constants;
                 proxy.rainbow_mode = rainbow_mode;
+                proxy.face_camera = face_camera;
+                proxy.constant_screen_size = constant_screen_size;
             });
             self.constants_dirty = false;
         }
@@ -180,6 +211,7 @@ impl<const D: u8, DIM: TextDim<D>> TextProxy<D, DIM> {
 
         if self.text_dirty {
             let text = self.text.clone();
+            let styled_runs = self.styled_runs.clone();
             let font = self.font;
             let alignment = self.alignment;
             let spacing = self.letter_spacing_em;
@@ -187,6 +219,7 @@ impl<const D: u8, DIM: TextDim<D>> TextProxy<D, DIM> {
                 let proxy: &mut Self = proxy_data_mut!(proxy);
 
                 proxy.text = text;
+                proxy.styled_runs = styled_runs;
                 proxy.font = font;
                 proxy.alignment = alignment;
                 proxy.letter_spacing_em = spacing;
@@ -197,6 +230,51 @@ impl<const D: u8, DIM: TextDim<D>> TextProxy<D, DIM> {
         }
     }
 
+    /// Returns true if the text depends on the camera and needs [`Self::update_camera_view`]
+    /// every frame
+    pub fn needs_camera_view(&self) -> bool {
+        self.face_camera || self.constant_screen_size
+    }
+
+    /// Sends the active camera to the render thread, which orients and scales the text with it
+    pub fn update_camera_view(&mut self, view: Option<TextCameraView>, ctx: &mut CPUDrawCtx) {
+        if self.camera_view == view {
+            return;
+        }
+
+        self.camera_view = view;
+        ctx.send_proxy_update(move |proxy| {
+            let proxy: &mut Self = proxy_data_mut!(proxy);
+            proxy.camera_view = view;
+        });
+    }
+
+    /// Applies the camera facing and screen size modes to the object's transform
+    fn view_transform(&self, local_to_world: &Matrix4<f32>) -> Matrix4<f32> {
+        let Some(view) = self.camera_view else {
+            return *local_to_world;
+        };
+
+        let mut transform = *local_to_world;
+        if self.face_camera {
+            transform = BillboardMode::Full
+                .facing_transform(
+                    &Affine3::from_matrix_unchecked(transform),
+                    view.position,
+                    view.up,
+                )
+                .to_homogeneous();
+        }
+
+        if self.constant_screen_size {
+            let position = transform.fixed_view::<3, 1>(0, 3).into_owned();
+            let depth = (position - view.position).dot(&view.forward).max(0.0);
+            transform *= Matrix4::new_scaling(depth * view.pixel_size);
+        }
+
+        transform
+    }
+
     pub fn update_render_thread(
         &mut self,
         renderer: &Renderer,
@@ -215,7 +293,8 @@ impl<const D: u8, DIM: TextDim<D>> TextProxy<D, DIM> {
             self.text_dirty = true;
         }
 
-        self.translation.update(local_to_world);
+        let local_to_world = self.view_transform(local_to_world);
+        self.translation.update(&local_to_world);
 
         if self.text_dirty {
             self.regenerate_geometry(renderer);
@@ -242,7 +321,7 @@ impl<const D: u8, DIM: TextDim<D>> TextProxy<D, DIM> {
         }
 
         if self.rainbow_mode {
-            let time = renderer.start_time().elapsed().as_secs_f32() * 100.;
+            let time = renderer.time().as_secs_f32() * 100.;
             self.pc.color = hsv_to_rgb(time % 360., 1.0, 1.0);
         }
 
@@ -268,12 +347,15 @@ impl<const D: u8, DIM: TextDim<D>> TextProxy<D, DIM> {
         let material = cache.material(font.atlas());
         let groups = shader.bind_groups();
 
+        let mut pc = self.pc;
+        pc.msdf_range_px = font.msdf_range();
+
         let mut pass = pass.write().unwrap();
         must_pipeline!(pipeline = shader, ctx.pass_type => return);
 
         pass.set_pipeline(pipeline);
         pass.set_vertex_buffer(0, data.glyph_vbo.slice(..));
-        pass.set_immediates(0, bytemuck::bytes_of(&self.pc));
+        pass.set_immediates(0, bytemuck::bytes_of(&pc));
         pass.set_bind_group(groups.render, ctx.render_bind_group, &[]);
         if let Some(idx) = groups.model {
             pass.set_bind_group(idx, data.uniform.bind_group(), &[]);
@@ -282,6 +364,7 @@ impl<const D: u8, DIM: TextDim<D>> TextProxy<D, DIM> {
             pass.set_bind_group(idx, material.uniform.bind_group(), &[]);
         }
 
+        ctx.stats.record_draw(self.glyph_data.len() as u32 * 6, 1);
         pass.draw(0..self.glyph_data.len() as u32 * 6, 0..1);
 
         #[cfg(debug_assertions)]
@@ -311,6 +394,7 @@ impl<const D: u8, DIM: TextDim<D>> TextProxy<D, DIM> {
 
         pass.set_immediates(0, bytemuck::bytes_of(&self.pc));
 
+        ctx.stats.record_draw(self.glyph_data.len() as u32 * 6, 1);
         pass.draw(0..self.glyph_data.len() as u32 * 6, 0..1);
     }
 
@@ -319,25 +403,58 @@ impl<const D: u8, DIM: TextDim<D>> TextProxy<D, DIM> {
 
         hot_font.request_glyphs(self.text.chars());
 
-        self.glyph_data = generate_glyph_geometry_stream(
-            &self.text,
-            &hot_font,
-            self.alignment,
-            1.0,
-            self.letter_spacing_em,
-        );
+        self.glyph_data = match &self.styled_runs {
+            Some(runs) => generate_rich_glyph_geometry_stream(
+                runs,
+                &hot_font,
+                self.alignment,
+                1.0,
+                self.letter_spacing_em,
+            ),
+            None => generate_glyph_geometry_stream(
+                &self.text,
+                &hot_font,
+                self.alignment,
+                1.0,
+                self.letter_spacing_em,
+            ),
+        };
     }
 
     pub fn set_text(&mut self, text: impl Into<String>) {
         let new_text = text.into();
-        if self.text == new_text {
+        if self.text == new_text && self.styled_runs.is_none() {
             return;
         }
 
         self.text = new_text;
+        self.styled_runs = None;
+        self.text_dirty = true;
+    }
+
+    /// Sets text with colored or resized parts, written in the markup described in
+    /// [`rich_text`](crate::rendering::rich_text), e.g. `"Deals [color=#ff0000]42[/color] damage"`
+    pub fn set_rich_text(&mut self, markup: &str) {
+        self.set_styled_runs(parse_rich_text(markup));
+    }
+
+    /// Sets the text as a list of styled parts. Glyphs without their own color use the text
+    /// color, so they also follow the rainbow mode.
+    pub fn set_styled_runs(&mut self, runs: Vec<StyledRun>) {
+        if self.styled_runs.as_ref() == Some(&runs) {
+            return;
+        }
+
+        self.text = runs.iter().map(|run| run.text.as_str()).collect();
+        self.styled_runs = Some(runs);
         self.text_dirty = true;
     }
 
+    /// Returns the styled parts if the text was set as rich text
+    pub fn styled_runs(&self) -> Option<&[StyledRun]> {
+        self.styled_runs.as_deref()
+    }
+
     pub fn set_font(&mut self, font: HFont) {
         if self.font == font {
             return;
@@ -393,6 +510,16 @@ impl<const D: u8, DIM: TextDim<D>> TextProxy<D, DIM> {
         self.rainbow_mode = enabled;
         self.constants_dirty = true;
     }
+
+    pub const fn set_face_camera(&mut self, enabled: bool) {
+        self.face_camera = enabled;
+        self.constants_dirty = true;
+    }
+
+    pub const fn set_constant_screen_size(&mut self, enabled: bool) {
+        self.constant_screen_size = enabled;
+        self.constants_dirty = true;
+    }
 }
 
 impl<const D: u8, DIM: TextDim<D>> SceneProxy for TextProxy<D, DIM> {
@@ -414,7 +541,7 @@ impl<const D: u8, DIM: TextDim<D>> SceneProxy for TextProxy<D, DIM> {
         let model_bgl = renderer.cache.bgl_model();
         let uniform = ShaderUniform::<MeshUniformIndex>::builder(&model_bgl)
             .with_buffer_data(&self.translation)
-            .with_buffer_data(&BoneData::DUMMY)
+            .with_storage_buffer_data(&BoneData::RIGID)
             .build(device);
 
         Box::new(TextRenderData { uniform, glyph_vbo })
@@ -475,9 +602,11 @@ impl<const D: u8, DIM: TextDim<D>> SceneProxy for TextProxy<D, DIM> {
         let color = hash_to_rgba(binding.object_hash);
         let mut pc = self.pc;
         pc.color = Vector3::new(color[0], color[1], color[2]);
+        pc.msdf_range_px = font.msdf_range();
 
         pass.set_immediates(0, bytemuck::bytes_of(&pc));
         pass.set_vertex_buffer(0, data.glyph_vbo.slice(..));
+        ctx.stats.record_draw(self.glyph_data.len() as u32 * 6, 1);
         pass.draw(0..self.glyph_data.len() as u32 * 6, 0..1);
     }
 
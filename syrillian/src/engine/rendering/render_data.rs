@@ -41,11 +41,23 @@ pub struct SystemUniform {
 
 ensure_aligned!(SystemUniform { screen_size }, align <= 8 * 2 => size);
 
+/// The `globals` every shader can read, derived from the [`SystemUniform`]
+#[repr(C)]
+#[derive(Default, Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct GlobalsUniform {
+    pub(crate) resolution: Vector2<f32>,
+    pub(crate) time: f32,
+    pub(crate) delta_time: f32,
+}
+
+ensure_aligned!(GlobalsUniform { resolution }, align <= 8 * 2 => size);
+
 #[repr(u8)]
 #[derive(Copy, Clone, Debug, UniformIndex)]
 pub enum RenderUniformIndex {
     Camera = 0,
     System = 1,
+    Globals = 2,
 }
 
 pub struct RenderUniformData {
@@ -121,13 +133,25 @@ impl SystemUniform {
     }
 }
 
+impl GlobalsUniform {
+    pub fn from_system(system: &SystemUniform) -> Self {
+        GlobalsUniform {
+            resolution: system.screen_size.cast(),
+            time: system.time,
+            delta_time: system.delta_time,
+        }
+    }
+}
+
 impl RenderUniformData {
     pub fn empty(device: &Device, render_bgl: &BindGroupLayout) -> Self {
         let camera_data = CameraUniform::empty();
         let system_data = SystemUniform::empty();
+        let globals_data = GlobalsUniform::from_system(&system_data);
         let uniform = ShaderUniform::<RenderUniformIndex>::builder(render_bgl)
             .with_buffer_data(&camera_data)
             .with_buffer_data(&system_data)
+            .with_buffer_data(&globals_data)
             .build(device);
 
         RenderUniformData {
@@ -191,11 +215,17 @@ impl RenderUniformData {
         );
     }
 
+    /// Uploads the system data, and the globals derived from it
     pub fn upload_system_data(&self, queue: &Queue) {
         queue.write_buffer(
             self.uniform.buffer(RenderUniformIndex::System),
             0,
             bytemuck::bytes_of(&self.system_data),
         );
+        queue.write_buffer(
+            self.uniform.buffer(RenderUniformIndex::Globals),
+            0,
+            bytemuck::bytes_of(&GlobalsUniform::from_system(&self.system_data)),
+        );
     }
 }
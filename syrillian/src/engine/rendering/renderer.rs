@@ -6,27 +6,42 @@
 
 use super::error::*;
 use crate::RenderTargetId;
-use crate::components::TypedComponentId;
-use crate::core::BoundingSphere;
-use crate::engine::assets::{AssetStore, HTexture};
+use crate::components::{CameraClear, ClearFlags, TypedComponentId, ViewportRect};
+use crate::core::{Frustum, FrustumSide};
+use crate::engine::assets::{AssetStore, HShader, HTexture, Texture as CpuTexture};
 use crate::engine::rendering::FrameCtx;
+use crate::engine::rendering::ambient_occlusion::AmbientOcclusionSurface;
 use crate::engine::rendering::cache::{AssetCache, GpuTexture};
+use crate::engine::rendering::gbuffer::GBufferSurface;
 use crate::engine::rendering::offscreen_surface::OffscreenSurface;
-use crate::engine::rendering::post_process_pass::PostProcessData;
+use crate::engine::rendering::post_process_pass::{
+    FogUniform, PostProcessData, PostProcessSettings,
+};
 #[cfg(debug_assertions)]
 use crate::rendering::DebugRenderer;
-use crate::rendering::light_manager::LightManager;
-use crate::rendering::lights::LightType;
-use crate::rendering::message::RenderMsg;
+use crate::rendering::compute::{ComputeJob, InFlightReadback};
+#[cfg(feature = "egui")]
+use crate::rendering::egui_pass::EguiRenderer;
+use crate::rendering::light_clusters::ClusterConfig;
+use crate::rendering::light_manager::{LightManager, ShadowBias};
+use crate::rendering::lights::{LightType, ShadowUniformIndex};
+use crate::rendering::message::{CameraView, RenderFeedback, RenderMsg, TextureCameraView};
 use crate::rendering::picking::{PickRequest, PickResult, color_bytes_to_hash};
-use crate::rendering::proxies::SceneProxyBinding;
+use crate::rendering::proxies::{
+    PROXY_PRIORITY_DECAL, PROXY_PRIORITY_TRANSPARENT, SceneProxyBinding,
+};
 use crate::rendering::render_data::RenderUniformData;
+use crate::rendering::scene_depth::SceneDepthData;
+use crate::rendering::stats::{FrameStats, RenderStats};
 use crate::rendering::strobe::StrobeRenderer;
-use crate::rendering::texture_export::{TextureExportError, save_texture_to_png};
+use crate::rendering::texture_export::{
+    TextureExportError, read_texture_rgba, save_texture_to_png,
+};
+use crate::rendering::uniform::ShaderUniform;
 use crate::rendering::{GPUDrawCtx, RenderPassType, State};
 use crossbeam_channel::{Receiver, Sender};
-use itertools::Itertools;
-use nalgebra::{Matrix4, Vector2, Vector3, Vector4};
+use image::RgbaImage;
+use nalgebra::{Vector2, Vector3, Vector4};
 use snafu::ResultExt;
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -82,16 +97,93 @@ impl PickingSurface {
     }
 }
 
+/// A camera drawing into a region of a viewport
+struct ViewCamera {
+    id: TypedComponentId,
+    viewport: ViewportRect,
+    render_data: RenderUniformData,
+    clear: CameraClear,
+}
+
+/// A camera drawing into a texture asset instead of a window
+struct TextureCamera {
+    camera: ViewCamera,
+    texture: HTexture,
+    depth_texture: Option<Texture>,
+}
+
+impl TextureCamera {
+    /// Returns the depth texture for the given size, recreating it if the size changed
+    fn depth_view(&mut self, device: &Device, width: u32, height: u32) -> TextureView {
+        let outdated = self
+            .depth_texture
+            .as_ref()
+            .is_none_or(|d| d.width() != width || d.height() != height);
+        if outdated {
+            self.depth_texture = Some(create_depth_texture(device, width, height));
+        }
+
+        self.depth_texture
+            .as_ref()
+            .expect("depth texture was just created")
+            .create_view(&TextureViewDescriptor::default())
+    }
+}
+
+#[derive(Copy, Clone)]
+struct RenderView<'a> {
+    render_data: &'a RenderUniformData,
+    clear: CameraClear,
+    rect: ViewportRect,
+}
+
+/// Where a viewport presents its final image
+enum ViewportOutput {
+    Window {
+        window: Window,
+        surface: Surface<'static>,
+    },
+    /// Rendered into a texture that can be read back, without a window or swapchain
+    Headless { texture: Texture },
+}
+
+impl ViewportOutput {
+    fn headless(device: &Device, config: &SurfaceConfiguration) -> Self {
+        let texture = device.create_texture(&TextureDescriptor {
+            label: Some("Headless Frame Texture"),
+            size: Extent3d {
+                width: config.width,
+                height: config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: config.format,
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+
+        ViewportOutput::Headless { texture }
+    }
+}
+
 pub struct RenderViewport {
-    window: Window,
-    surface: Surface<'static>,
+    output: ViewportOutput,
     config: SurfaceConfiguration,
     depth_texture: Texture,
     offscreen_surface: OffscreenSurface,
     picking_surface: PickingSurface,
+    gbuffer: GBufferSurface,
+    ambient_occlusion: AmbientOcclusionSurface,
+    /// The shadow maps with this viewport's ambient occlusion, rebuilt when either is recreated
+    shadow_uniform: Option<ShaderUniform<ShadowUniformIndex>>,
     post_process_data: PostProcessData,
+    scene_depth: SceneDepthData,
     render_data: RenderUniformData,
-    start_time: Instant,
+    clear: CameraClear,
+    cameras: Vec<ViewCamera>,
+    time: Duration,
     delta_time: Duration,
     last_frame_time: Instant,
     frame_count: usize,
@@ -99,42 +191,58 @@ pub struct RenderViewport {
 
 impl RenderViewport {
     fn new(
-        window: Window,
-        surface: Surface<'static>,
+        output: ViewportOutput,
         mut config: SurfaceConfiguration,
         state: &State,
         cache: &AssetCache,
+        post_settings: PostProcessSettings,
+        gbuffer_enabled: bool,
     ) -> Self {
         Self::clamp_config(&mut config);
-        surface.configure(&state.device, &config);
+        if let ViewportOutput::Window { surface, .. } = &output {
+            surface.configure(&state.device, &config);
+        }
 
         let render_bgl = cache.bgl_render();
         let pp_bgl = cache.bgl_post_process();
+        let depth_bgl = cache.bgl_depth();
 
         let offscreen_surface = OffscreenSurface::new(&state.device, &config);
-        let depth_texture = Self::create_depth_texture(&state.device, &config);
+        let depth_texture = create_depth_texture(&state.device, config.width, config.height);
         let depth_view = depth_texture.create_view(&TextureViewDescriptor::default());
         let picking_surface = PickingSurface::new(&state.device, &config);
+        // the ambient occlusion is computed from the depth and normals of the G-buffer pass
+        let ssao_enabled = post_settings.ssao.enabled != 0;
+        let gbuffer = GBufferSurface::new(&state.device, &config, gbuffer_enabled || ssao_enabled);
+        let ambient_occlusion = AmbientOcclusionSurface::new(&state.device, &config, ssao_enabled);
 
         let post_process_data = PostProcessData::new(
             &state.device,
             &pp_bgl,
             offscreen_surface.view(),
             &depth_view,
+            &gbuffer,
+            post_settings,
         );
+        let scene_depth = SceneDepthData::new(&state.device, &depth_bgl, &depth_view);
 
         let render_data = RenderUniformData::empty(&state.device, &render_bgl);
 
         RenderViewport {
-            window,
-            surface,
+            output,
             config,
             depth_texture,
             offscreen_surface,
             picking_surface,
+            gbuffer,
+            ambient_occlusion,
+            shadow_uniform: None,
             post_process_data,
+            scene_depth,
             render_data,
-            start_time: Instant::now(),
+            clear: CameraClear::default(),
+            cameras: Vec::new(),
+            time: Duration::default(),
             delta_time: Duration::default(),
             last_frame_time: Instant::now(),
             frame_count: 0,
@@ -147,27 +255,73 @@ impl RenderViewport {
     }
 
     #[instrument(skip_all)]
-    fn recreate_surface(&mut self, state: &State) {
-        self.surface.configure(&state.device, &self.config);
+    fn recreate_surface(&self, state: &State) {
+        if let ViewportOutput::Window { surface, .. } = &self.output {
+            surface.configure(&state.device, &self.config);
+        }
     }
 
     #[instrument(skip_all)]
     fn resize(&mut self, new_size: PhysicalSize<u32>, state: &State, cache: &AssetCache) {
-        let Ok(mut new_config) = state
-            .surface_config(&self.surface, new_size)
-            .context(StateErr)
-        else {
-            warn!("Couldn't acquire surface config for surface reconfiguration");
-            return;
-        };
-
-        Self::clamp_config(&mut new_config);
-        self.config = new_config;
-        self.surface.configure(&state.device, &self.config);
+        match &mut self.output {
+            ViewportOutput::Window { surface, .. } => {
+                let Ok(mut new_config) = state.surface_config(surface, new_size).context(StateErr)
+                else {
+                    warn!("Couldn't acquire surface config for surface reconfiguration");
+                    return;
+                };
+
+                Self::clamp_config(&mut new_config);
+                self.config = new_config;
+                surface.configure(&state.device, &self.config);
+            }
+            ViewportOutput::Headless { .. } => {
+                self.config = state.headless_config(new_size);
+                self.output = ViewportOutput::headless(&state.device, &self.config);
+            }
+        }
 
         self.offscreen_surface.recreate(&state.device, &self.config);
-        self.depth_texture = Self::create_depth_texture(&state.device, &self.config);
+        self.depth_texture =
+            create_depth_texture(&state.device, self.config.width, self.config.height);
         self.picking_surface.recreate(&state.device, &self.config);
+        self.gbuffer.recreate(&state.device, &self.config);
+        self.ambient_occlusion.recreate(&state.device, &self.config);
+        self.shadow_uniform = None;
+        self.rebuild_bind_groups(state, cache);
+    }
+
+    /// Turns the G-buffer pass on or off, allocating or freeing its textures
+    fn set_gbuffer_enabled(&mut self, enabled: bool, state: &State, cache: &AssetCache) {
+        if self.gbuffer.is_enabled() == enabled {
+            return;
+        }
+
+        self.gbuffer = GBufferSurface::new(&state.device, &self.config, enabled);
+        self.rebuild_bind_groups(state, cache);
+    }
+
+    /// Turns the ambient occlusion pass on or off, allocating or freeing its texture
+    fn set_ambient_occlusion_enabled(&mut self, enabled: bool, state: &State) {
+        if self.ambient_occlusion.is_enabled() == enabled {
+            return;
+        }
+
+        self.ambient_occlusion = AmbientOcclusionSurface::new(&state.device, &self.config, enabled);
+        self.shadow_uniform = None;
+    }
+
+    fn shadow_uniform<'a>(
+        &'a self,
+        lights: &'a LightManager,
+    ) -> &'a ShaderUniform<ShadowUniformIndex> {
+        self.shadow_uniform
+            .as_ref()
+            .unwrap_or_else(|| lights.shadow_uniform())
+    }
+
+    /// Recreates the bind groups that reference the viewport's render targets
+    fn rebuild_bind_groups(&mut self, state: &State, cache: &AssetCache) {
         let pp_bgl = cache.bgl_post_process();
         let depth_view = self
             .depth_texture
@@ -177,24 +331,11 @@ impl RenderViewport {
             &pp_bgl,
             self.offscreen_surface.view(),
             &depth_view,
+            &self.gbuffer,
+            self.post_process_data.settings,
         );
-    }
-
-    fn create_depth_texture(device: &Device, config: &SurfaceConfiguration) -> Texture {
-        device.create_texture(&TextureDescriptor {
-            label: Some("Depth Texture"),
-            size: Extent3d {
-                width: config.width.max(1),
-                height: config.height.max(1),
-                depth_or_array_layers: 1,
-            },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: TextureDimension::D2,
-            format: TextureFormat::Depth32Float,
-            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
-            view_formats: &[],
-        })
+        let depth_bgl = cache.bgl_depth();
+        self.scene_depth = SceneDepthData::new(&state.device, &depth_bgl, &depth_view);
     }
 
     #[instrument(skip_all)]
@@ -217,29 +358,101 @@ impl RenderViewport {
     }
 
     fn update_system_data(&mut self, queue: &Queue) {
-        let window_size = self.window.inner_size();
+        let window_size = match &self.output {
+            ViewportOutput::Window { window, .. } => window.inner_size(),
+            ViewportOutput::Headless { .. } => self.size(),
+        };
         let window_size = Vector2::new(window_size.width.max(1), window_size.height.max(1));
 
         let system_data = &mut self.render_data.system_data;
         system_data.screen_size = window_size;
-        system_data.time = self.start_time.elapsed().as_secs_f32();
+        system_data.time = self.time.as_secs_f32();
         system_data.delta_time = self.delta_time.as_secs_f32();
 
         self.render_data.upload_system_data(queue);
+
+        for camera in &mut self.cameras {
+            camera.render_data.system_data = self.render_data.system_data;
+            camera.render_data.upload_system_data(queue);
+        }
+    }
+
+    fn camera_mut(&mut self, id: TypedComponentId) -> Option<&mut ViewCamera> {
+        self.cameras.iter_mut().find(|c| c.id == id)
+    }
+
+    /// Replaces the cameras drawing into this viewport, keeping the state of known cameras
+    fn set_camera_views(&mut self, views: Vec<CameraView>, device: &Device, bgl: &BindGroupLayout) {
+        let mut previous = mem::take(&mut self.cameras);
+
+        self.cameras = views
+            .into_iter()
+            .map(
+                |view| match previous.iter().position(|c| c.id == view.camera) {
+                    Some(idx) => {
+                        let mut camera = previous.swap_remove(idx);
+                        camera.viewport = view.viewport;
+                        camera
+                    }
+                    None => ViewCamera {
+                        id: view.camera,
+                        viewport: view.viewport,
+                        render_data: RenderUniformData::empty(device, bgl),
+                        clear: CameraClear::default(),
+                    },
+                },
+            )
+            .collect();
     }
 
-    /// Updates the delta time based on the elapsed time since the last frame
-    fn tick_delta_time(&mut self) {
-        self.delta_time = self.last_frame_time.elapsed();
+    /// The last camera presents, so post-processing and UI see its camera data
+    fn sync_presenting_camera(&mut self, queue: &Queue) {
+        let Some(camera) = self.cameras.last() else {
+            return;
+        };
+
+        self.render_data.camera_data = camera.render_data.camera_data;
+        self.clear = camera.clear;
+        self.render_data.upload_camera_data(queue);
+    }
+
+    /// All views in draw order. Falls back to a single full-screen view if no camera is known.
+    fn views(&self) -> impl Iterator<Item = RenderView<'_>> {
+        let fallback = self.cameras.is_empty().then(|| RenderView {
+            render_data: &self.render_data,
+            clear: self.clear,
+            rect: ViewportRect::FULL,
+        });
+
+        fallback
+            .into_iter()
+            .chain(self.cameras.iter().map(|c| RenderView {
+                render_data: &c.render_data,
+                clear: c.clear,
+                rect: c.viewport,
+            }))
+    }
+
+    /// Updates the time and the delta time for the next frame. Without a fixed delta, the delta
+    /// time is the elapsed time since the last frame.
+    fn tick_delta_time(&mut self, time: Duration, fixed_delta: Option<Duration>) {
+        self.time = time;
+        self.delta_time = fixed_delta.unwrap_or_else(|| self.last_frame_time.elapsed());
         self.last_frame_time = Instant::now();
     }
 
-    fn window(&self) -> &Window {
-        &self.window
+    fn window(&self) -> Option<&Window> {
+        match &self.output {
+            ViewportOutput::Window { window, .. } => Some(window),
+            ViewportOutput::Headless { .. } => None,
+        }
     }
 
-    fn window_mut(&mut self) -> &mut Window {
-        &mut self.window
+    fn window_mut(&mut self) -> Option<&mut Window> {
+        match &mut self.output {
+            ViewportOutput::Window { window, .. } => Some(window),
+            ViewportOutput::Headless { .. } => None,
+        }
     }
 
     fn size(&self) -> PhysicalSize<u32> {
@@ -256,41 +469,96 @@ pub struct Renderer {
     pub cache: AssetCache,
     shadow_render_data: RenderUniformData,
     viewports: HashMap<RenderTargetId, RenderViewport>,
+    texture_cameras: Vec<TextureCamera>,
     window_map: HashMap<WindowId, RenderTargetId>,
     game_rx: Receiver<RenderMsg>,
     proxies: HashMap<TypedComponentId, SceneProxyBinding>,
     sorted_proxies: Vec<(u32, TypedComponentId)>,
+    // reused every frame, so sorting proxies doesn't allocate once they're big enough
+    view_proxies: Vec<(u32, TypedComponentId)>,
+    proxy_sort_keys: Vec<ProxySortKey>,
     strobe: RefCell<StrobeRenderer>,
     start_time: Instant,
-    pick_result_tx: Sender<PickResult>,
+    fixed_delta: Option<Duration>,
+    fixed_time: Duration,
+    feedback_tx: Sender<RenderFeedback>,
     pending_pick_requests: Vec<PickRequest>,
+    pending_compute: Vec<ComputeJob>,
+    compute_in_flight: Vec<InFlightReadback>,
+    post_settings: PostProcessSettings,
+    gbuffer_enabled: bool,
     pub(super) lights: LightManager,
+    frame_stats: FrameStats,
+    stats: RenderStats,
+    #[cfg(feature = "egui")]
+    egui: EguiRenderer,
 }
 
 impl Renderer {
     pub fn new(
         game_rx: Receiver<RenderMsg>,
-        pick_result_tx: Sender<PickResult>,
+        feedback_tx: Sender<RenderFeedback>,
         main_window: Window,
         store: Arc<AssetStore>,
     ) -> Result<Self> {
         let (state, surface, config) = State::new(&main_window).context(StateErr)?;
+        let output = ViewportOutput::Window {
+            window: main_window,
+            surface,
+        };
+
+        Self::with_primary_viewport(game_rx, feedback_tx, state, output, config, store)
+    }
+
+    /// Creates a renderer without any window. The primary render target draws into a texture
+    /// of `size`, which can be read with [`Renderer::read_frame`].
+    pub fn new_headless(
+        game_rx: Receiver<RenderMsg>,
+        feedback_tx: Sender<RenderFeedback>,
+        size: PhysicalSize<u32>,
+        store: Arc<AssetStore>,
+    ) -> Result<Self> {
+        let state = State::new_headless().context(StateErr)?;
+        let config = state.headless_config(size);
+        let output = ViewportOutput::headless(&state.device, &config);
+
+        Self::with_primary_viewport(game_rx, feedback_tx, state, output, config, store)
+    }
+
+    fn with_primary_viewport(
+        game_rx: Receiver<RenderMsg>,
+        feedback_tx: Sender<RenderFeedback>,
+        state: State,
+        output: ViewportOutput,
+        config: SurfaceConfiguration,
+        store: Arc<AssetStore>,
+    ) -> Result<Self> {
         let cache = AssetCache::new(store, &state);
 
         let render_bgl = cache.bgl_render();
         let shadow_render_data = RenderUniformData::empty(&state.device, &render_bgl);
         let lights = LightManager::new(&cache, &state.device);
+        #[cfg(feature = "egui")]
+        let egui = EguiRenderer::new(&state.device);
         let start_time = Instant::now();
 
-        main_window.request_redraw();
-
         let mut window_map = HashMap::new();
-        window_map.insert(main_window.id(), RenderTargetId::PRIMARY);
+        if let ViewportOutput::Window { window, .. } = &output {
+            window.request_redraw();
+            window_map.insert(window.id(), RenderTargetId::PRIMARY);
+        }
 
         let mut viewports = HashMap::new();
         viewports.insert(
             RenderTargetId::PRIMARY,
-            RenderViewport::new(main_window, surface, config, &state, &cache),
+            RenderViewport::new(
+                output,
+                config,
+                &state,
+                &cache,
+                PostProcessSettings::default(),
+                false,
+            ),
         );
 
         Ok(Renderer {
@@ -298,15 +566,28 @@ impl Renderer {
             cache,
             shadow_render_data,
             viewports,
+            texture_cameras: Vec::new(),
             window_map,
             game_rx,
             start_time,
+            fixed_delta: None,
+            fixed_time: Duration::ZERO,
             proxies: HashMap::new(),
             sorted_proxies: Vec::new(),
+            view_proxies: Vec::new(),
+            proxy_sort_keys: Vec::new(),
             strobe: RefCell::new(StrobeRenderer::default()),
-            pick_result_tx,
+            feedback_tx,
             pending_pick_requests: Vec::new(),
+            pending_compute: Vec::new(),
+            compute_in_flight: Vec::new(),
+            post_settings: PostProcessSettings::default(),
+            gbuffer_enabled: false,
             lights,
+            frame_stats: FrameStats::default(),
+            stats: RenderStats::default(),
+            #[cfg(feature = "egui")]
+            egui,
         })
     }
 
@@ -326,14 +607,68 @@ impl Renderer {
         self.window_map.get(window_id).copied()
     }
 
+    /// Returns the window of a viewport, or `None` if it doesn't exist or renders headless
     pub fn window(&self, viewport: RenderTargetId) -> Option<&Window> {
-        self.viewports.get(&viewport).map(RenderViewport::window)
+        self.viewports.get(&viewport)?.window()
     }
 
     pub fn window_mut(&mut self, viewport: RenderTargetId) -> Option<&mut Window> {
-        self.viewports
-            .get_mut(&viewport)
-            .map(RenderViewport::window_mut)
+        self.viewports.get_mut(&viewport)?.window_mut()
+    }
+
+    /// Reads the last frame a headless viewport rendered, see [`Renderer::new_headless`]
+    pub fn read_frame(&self, target: RenderTargetId) -> Result<RgbaImage, TextureExportError> {
+        let (viewport, texture) = self.headless_frame(target)?;
+        let (width, height) = (viewport.config.width, viewport.config.height);
+        let pixels = read_texture_rgba(
+            &self.state.device,
+            &self.state.queue,
+            texture,
+            viewport.config.format,
+            width,
+            height,
+        )?;
+
+        RgbaImage::from_raw(width, height, pixels)
+            .ok_or(TextureExportError::InvalidDimensions { width, height })
+    }
+
+    /// Export the last frame a headless viewport rendered as a PNG image.
+    pub fn export_frame_png(
+        &self,
+        target: RenderTargetId,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<(), TextureExportError> {
+        let (viewport, texture) = self.headless_frame(target)?;
+
+        save_texture_to_png(
+            &self.state.device,
+            &self.state.queue,
+            texture,
+            viewport.config.format,
+            viewport.config.width,
+            viewport.config.height,
+            path,
+        )
+    }
+
+    fn headless_frame(
+        &self,
+        target: RenderTargetId,
+    ) -> Result<(&RenderViewport, &Texture), TextureExportError> {
+        let viewport = self
+            .viewports
+            .get(&target)
+            .ok_or(TextureExportError::Unavailable {
+                reason: "render target not found",
+            })?;
+
+        match &viewport.output {
+            ViewportOutput::Headless { texture } => Ok((viewport, texture)),
+            ViewportOutput::Window { .. } => Err(TextureExportError::Unavailable {
+                reason: "swapchain frames can't be read back, only headless ones",
+            }),
+        }
     }
 
     /// Export the offscreen render target for a viewport as a PNG image.
@@ -412,10 +747,189 @@ impl Renderer {
         self.export_texture_png(&gpu_tex, path)
     }
 
+    /// Sets the default anisotropic filtering level for material samplers.
+    ///
+    /// The level is clamped to what the adapter supports. 1 disables anisotropic filtering.
+    /// Returns the level that was actually applied.
+    pub fn set_anisotropy(&mut self, level: u16) -> u16 {
+        self.cache.set_anisotropy(level)
+    }
+
+    /// Sets the background color of a render target. `None` disables clearing the color output.
+    ///
+    /// This is overridden by the clear configuration of the target's active camera when it changes.
+    pub fn set_clear_color(&mut self, target: RenderTargetId, color: Option<Vector4<f32>>) {
+        if let Some(viewport) = self.viewports.get_mut(&target) {
+            viewport.clear.color = color;
+            for camera in &mut viewport.cameras {
+                camera.clear.color = color;
+            }
+        }
+    }
+
+    /// Enables linear depth fog on every render target.
+    ///
+    /// Geometry closer than `start` is unaffected, and everything past `end` is fully covered by
+    /// `color`. The sky stays untouched.
+    pub fn set_fog(&mut self, color: Vector3<f32>, start: f32, end: f32) {
+        self.post_settings.fog = FogUniform::new(color, start, end.max(start));
+        self.apply_post_settings();
+    }
+
+    pub fn clear_fog(&mut self) {
+        self.post_settings.fog = FogUniform::disabled();
+        self.apply_post_settings();
+    }
+
+    /// Enables screen-space ambient occlusion, which darkens the ambient light in creases and
+    /// contact areas. Direct light isn't affected.
+    ///
+    /// The occlusion is computed from the G-buffer before the color pass, so this also runs the
+    /// G-buffer pass while enabled.
+    pub fn set_ssao(&mut self, enabled: bool) {
+        self.post_settings.ssao.enabled = enabled as u32;
+        self.apply_post_settings();
+        for viewport in self.viewports.values_mut() {
+            viewport.set_ambient_occlusion_enabled(enabled, &self.state);
+            viewport.set_gbuffer_enabled(self.gbuffer_enabled || enabled, &self.state, &self.cache);
+        }
+    }
+
+    /// Tunes the ambient occlusion.
+    ///
+    /// `radius` is the world space distance that is searched for occluders, `intensity` scales
+    /// the darkening and `bias` avoids flat surfaces occluding themselves.
+    pub fn set_ssao_params(&mut self, radius: f32, intensity: f32, bias: f32) {
+        let ssao = &mut self.post_settings.ssao;
+        ssao.radius = radius.max(0.0);
+        ssao.intensity = intensity.max(0.0);
+        ssao.bias = bias.max(0.0);
+        self.apply_post_settings();
+    }
+
+    /// Sets the width and height of the shadow maps in texels, clamped to what the adapter
+    /// supports. Returns the resolution that was actually applied.
+    ///
+    /// Higher resolutions give sharper shadows at the cost of VRAM. All lights share an array of
+    /// 48 depth layers with 4 bytes per texel: 1024 uses 192 MiB, 2048 uses 768 MiB and 4096
+    /// uses 3 GiB.
+    pub fn set_shadow_resolution(&mut self, resolution: u32) -> u32 {
+        let max = self.state.device.limits().max_texture_dimension_2d;
+        let resolution = resolution.clamp(1, max);
+        self.lights
+            .set_shadow_resolution(&self.cache, &self.state.device, resolution);
+        for viewport in self.viewports.values_mut() {
+            viewport.shadow_uniform = None;
+        }
+        resolution
+    }
+
+    pub fn shadow_resolution(&self) -> u32 {
+        self.lights.shadow_resolution(&self.cache)
+    }
+
+    /// Offsets the depth that's compared against the shadow maps.
+    ///
+    /// `constant` is added everywhere, `slope` grows as surfaces face away from the light. Raise
+    /// them against shadow acne (stripes on lit surfaces), lower them if shadows detach from
+    /// their casters (peter-panning).
+    pub fn set_shadow_bias(&mut self, constant: f32, slope: f32) {
+        let mut bias = self.lights.shadow_bias();
+        bias.constant = constant;
+        bias.slope = slope.max(0.0);
+        self.lights.set_shadow_bias(bias);
+    }
+
+    /// Moves the shadow lookup this far along the surface normal, in world units. Fixes acne on
+    /// surfaces at grazing angles without pushing shadows away like a large depth bias does.
+    pub fn set_shadow_normal_offset(&mut self, offset: f32) {
+        let mut bias = self.lights.shadow_bias();
+        bias.normal_offset = offset.max(0.0);
+        self.lights.set_shadow_bias(bias);
+    }
+
+    pub fn shadow_bias(&self) -> ShadowBias {
+        self.lights.shadow_bias()
+    }
+
+    /// Softens shadow edges with percentage-closer filtering over a `taps` x `taps` grid, up to
+    /// 7. 1 gives hard shadows and is the cheapest, the default is 3.
+    ///
+    /// The taps are one shadow map texel apart, so a higher
+    /// [shadow resolution](Self::set_shadow_resolution) also gives tighter edges. Returns the tap
+    /// count that was actually applied.
+    pub fn set_shadow_softness(&mut self, taps: u32) -> u32 {
+        self.lights.set_shadow_pcf_taps(taps);
+        self.lights.shadow_pcf_taps()
+    }
+
+    /// Returns what was drawn in the last frame of the primary render target
+    pub fn stats(&self) -> RenderStats {
+        self.stats
+    }
+
+    /// Sets the grid lights are sorted into, so every pixel only shades the lights that can reach
+    /// it. Point and spot lights only reach as far as their range, sun lights reach everywhere.
+    ///
+    /// Finer grids cull more precisely but take longer to fill each frame. Lights beyond
+    /// `max_lights_per_cluster` in a single cluster are left out there. Dimensions of 0 are
+    /// raised to 1. Returns the config that was actually applied.
+    pub fn set_light_clusters(&mut self, config: ClusterConfig) -> ClusterConfig {
+        self.lights.set_cluster_config(config);
+        self.lights.cluster_config()
+    }
+
+    pub fn light_clusters(&self) -> ClusterConfig {
+        self.lights.cluster_config()
+    }
+
+    /// Renders world space normals and positions of opaque meshes into a G-buffer every frame.
+    ///
+    /// Post-processing shaders can read them with `post_gbuffer_normal` and
+    /// `post_gbuffer_position`. The pass also runs while [ambient occlusion](Self::set_ssao) is
+    /// enabled.
+    pub fn enable_gbuffer(&mut self, enabled: bool) {
+        self.gbuffer_enabled = enabled;
+        // ambient occlusion keeps the G-buffer pass running
+        let needed = enabled || self.post_settings.ssao.enabled != 0;
+        for viewport in self.viewports.values_mut() {
+            viewport.set_gbuffer_enabled(needed, &self.state, &self.cache);
+        }
+    }
+
+    pub fn is_gbuffer_enabled(&self) -> bool {
+        self.gbuffer_enabled
+    }
+
+    fn apply_post_settings(&mut self) {
+        for viewport in self.viewports.values_mut() {
+            viewport
+                .post_process_data
+                .set_settings(&self.state.queue, self.post_settings);
+        }
+    }
+
     pub fn start_time(&self) -> Instant {
         self.start_time
     }
 
+    /// The time since the renderer started, as shaders and UI animations see it.
+    ///
+    /// With a [fixed delta](Renderer::set_fixed_delta), this advances by exactly that delta each
+    /// frame instead of following the wall clock.
+    pub fn time(&self) -> Duration {
+        match self.fixed_delta {
+            Some(_) => self.fixed_time,
+            None => self.start_time.elapsed(),
+        }
+    }
+
+    /// Advances the renderer [time](Renderer::time) by `delta` per [update](Renderer::update),
+    /// so the same number of frames always renders the same images
+    pub fn set_fixed_delta(&mut self, delta: Duration) {
+        self.fixed_delta = Some(delta);
+    }
+
     #[instrument(skip_all)]
     pub fn handle_events(&mut self) {
         loop {
@@ -443,7 +957,7 @@ impl Renderer {
             return false;
         };
 
-        viewport.tick_delta_time();
+        viewport.tick_delta_time(self.time(), self.fixed_delta);
         let rendered = self.render_frame(target_id, &mut viewport);
 
         self.viewports.insert(target_id, viewport);
@@ -453,6 +967,13 @@ impl Renderer {
 
     #[instrument(skip_all)]
     pub fn update(&mut self) {
+        if let Some(delta) = self.fixed_delta {
+            self.fixed_time += delta;
+        }
+
+        // before the proxies, so they can draw what the jobs generated this frame
+        self.run_compute_jobs();
+
         let mut proxies = mem::take(&mut self.proxies);
         for proxy in proxies.values_mut() {
             proxy.update(self);
@@ -469,6 +990,48 @@ impl Renderer {
             .update(&self.cache, &self.state.queue, &self.state.device);
     }
 
+    #[instrument(skip_all)]
+    fn run_compute_jobs(&mut self) {
+        if !self.pending_compute.is_empty() {
+            let device = &self.state.device;
+            let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+                label: Some("Compute Encoder"),
+            });
+            let jobs: Vec<_> = self
+                .pending_compute
+                .drain(..)
+                .map(|job| {
+                    let buffers = job.record(device, &self.cache, &mut encoder);
+                    (job.readback(), buffers)
+                })
+                .collect();
+            self.state.queue.submit(Some(encoder.finish()));
+
+            for (readback, buffers) in jobs {
+                match buffers {
+                    Some(buffers) => self.compute_in_flight.push(InFlightReadback::map(
+                        &self.state.queue,
+                        readback,
+                        buffers,
+                    )),
+                    None => readback.fail(),
+                }
+            }
+        }
+
+        self.poll_compute_readbacks();
+    }
+
+    /// Hands the results of finished compute jobs to their readbacks, without waiting for the rest
+    fn poll_compute_readbacks(&mut self) {
+        if self.compute_in_flight.is_empty() {
+            return;
+        }
+
+        let _ = self.state.device.poll(PollType::Poll);
+        self.compute_in_flight.retain(InFlightReadback::is_pending);
+    }
+
     #[instrument(skip_all)]
     fn resort_proxies(&mut self) {
         let frustum = self
@@ -476,8 +1039,13 @@ impl Renderer {
             .get(&RenderTargetId::PRIMARY)
             .map(|vp| Frustum::from_matrix(&vp.render_data.camera_data.proj_view_mat));
 
-        self.sorted_proxies =
-            sorted_enabled_proxy_ids(&self.proxies, self.cache.store(), frustum.as_ref());
+        sort_enabled_proxy_ids(
+            &self.proxies,
+            self.cache.store(),
+            frustum.as_ref(),
+            &mut self.proxy_sort_keys,
+            &mut self.sorted_proxies,
+        );
     }
 
     #[instrument(skip_all)]
@@ -487,18 +1055,48 @@ impl Renderer {
         viewport: &mut RenderViewport,
     ) -> bool {
         let mut ctx = viewport.begin_render();
-
-        let frustum = Frustum::from_matrix(&viewport.render_data.camera_data.proj_view_mat);
-        self.sorted_proxies =
-            sorted_enabled_proxy_ids(&self.proxies, self.cache.store(), Some(&frustum));
+        viewport.sync_presenting_camera(&self.state.queue);
+
+        // With multiple cameras, shadow casters can be outside any single camera's frustum
+        let frustum = (viewport.cameras.len() <= 1)
+            .then(|| Frustum::from_matrix(&viewport.render_data.camera_data.proj_view_mat));
+        sort_enabled_proxy_ids(
+            &self.proxies,
+            self.cache.store(),
+            frustum.as_ref(),
+            &mut self.proxy_sort_keys,
+            &mut self.sorted_proxies,
+        );
 
         if let Some(request) = self.take_pick_request(target_id) {
             self.picking_pass(viewport, &mut ctx, request);
         }
 
+        if viewport.gbuffer.is_enabled() {
+            self.gbuffer_pass(viewport, &mut ctx);
+        }
+
+        if viewport.ambient_occlusion.is_enabled() {
+            self.ambient_occlusion_pass(viewport);
+        }
+        if viewport.shadow_uniform.is_none() {
+            viewport.shadow_uniform = Some(self.lights.occluded_shadow_uniform(
+                &self.cache,
+                &self.state.device,
+                viewport.ambient_occlusion.view(),
+            ));
+        }
+
         self.render(target_id, viewport, &mut ctx);
 
-        match self.end_render(viewport) {
+        // the final pass is a draw call as well
+        let result = self.end_render(target_id, viewport);
+        if target_id.is_primary() {
+            self.stats = self.frame_stats.finish_frame();
+            let _ = self.feedback_tx.send(RenderFeedback::Stats(self.stats));
+        }
+
+        match result {
             Ok(ctx) => ctx,
             Err(RenderError::Surface {
                 source: SurfaceError::Lost,
@@ -523,6 +1121,120 @@ impl Renderer {
         true
     }
 
+    /// Draws the opaque scene of every camera into the G-buffer.
+    ///
+    /// Runs before the color pass, which clears the depth again if its camera asks for it.
+    #[instrument(skip_all)]
+    fn gbuffer_pass(&mut self, viewport: &RenderViewport, ctx: &mut FrameCtx) {
+        let mut encoder = self
+            .state
+            .device
+            .create_command_encoder(&CommandEncoderDescriptor {
+                label: Some("G-Buffer Encoder"),
+            });
+
+        for (i, view) in viewport.views().enumerate() {
+            let load = if i == 0 {
+                LoadOp::Clear(Color::TRANSPARENT)
+            } else {
+                LoadOp::Load
+            };
+            let depth_load = if i == 0 {
+                LoadOp::Clear(1.0)
+            } else if view.rect.is_full() {
+                depth_load_op(&view.clear)
+            } else {
+                if view.clear.clears_depth() {
+                    self.clear_view_depth(&mut encoder, &view, viewport, ctx);
+                }
+                LoadOp::Load
+            };
+
+            let mut pass = encoder.begin_render_pass(&RenderPassDescriptor {
+                label: Some("G-Buffer Pass"),
+                color_attachments: &[
+                    gbuffer_attachment(viewport.gbuffer.normal_view(), load),
+                    gbuffer_attachment(viewport.gbuffer.position_view(), load),
+                ],
+                depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+                    view: &ctx.depth_view,
+                    depth_ops: Some(Operations {
+                        load: depth_load,
+                        store: StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }),
+                ..RenderPassDescriptor::default()
+            });
+            set_pass_viewport(
+                &mut pass,
+                view.rect,
+                viewport.config.width,
+                viewport.config.height,
+            );
+
+            // sorted by priority, so the opaque proxies come first
+            let proxies = self.view_proxies(&view);
+            let opaque =
+                proxies.partition_point(|(priority, _)| *priority < PROXY_PRIORITY_TRANSPARENT);
+            self.render_scene(
+                ctx,
+                pass,
+                RenderPassType::GBuffer,
+                &proxies[..opaque],
+                view.render_data,
+                self.lights.uniform().bind_group(),
+                self.lights.placeholder_shadow_uniform().bind_group(),
+            );
+            self.view_proxies = proxies;
+        }
+
+        self.state.queue.submit(Some(encoder.finish()));
+    }
+
+    /// Writes the ambient occlusion of the G-buffer's opaque scene, which the color pass
+    /// darkens the ambient light with
+    #[instrument(skip_all)]
+    fn ambient_occlusion_pass(&self, viewport: &RenderViewport) {
+        let mut encoder = self
+            .state
+            .device
+            .create_command_encoder(&CommandEncoderDescriptor {
+                label: Some("Ambient Occlusion Encoder"),
+            });
+        let mut pass = encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some("Ambient Occlusion Pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: viewport.ambient_occlusion.view(),
+                depth_slice: None,
+                resolve_target: None,
+                ops: Operations {
+                    load: LoadOp::Clear(Color::TRANSPARENT),
+                    store: StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            ..RenderPassDescriptor::default()
+        });
+
+        let shader = self.cache.shader(HShader::AMBIENT_OCCLUSION);
+        let groups = shader.bind_groups();
+        pass.set_pipeline(shader.solid_pipeline());
+        pass.set_bind_group(
+            groups.render,
+            viewport.render_data.uniform.bind_group(),
+            &[],
+        );
+        if let Some(idx) = groups.post_process {
+            pass.set_bind_group(idx, viewport.post_process_data.uniform.bind_group(), &[]);
+        }
+        self.frame_stats.record_draw(6, 1);
+        pass.draw(0..6, 0..1);
+        drop(pass);
+
+        self.state.queue.submit(Some(encoder.finish()));
+    }
+
     #[instrument(skip_all)]
     fn picking_pass(
         &mut self,
@@ -537,20 +1249,28 @@ impl Renderer {
                 label: Some("Picking Encoder"),
             });
 
-        {
-            let pass = encoder.begin_render_pass(&RenderPassDescriptor {
+        // Every camera is drawn into its own region of the picking texture in draw order, so the
+        // picked object is the one visible under the cursor from the topmost camera covering it.
+        for (i, view) in viewport.views().enumerate() {
+            let color_load = if i == 0 {
+                LoadOp::Clear(Color {
+                    r: 0.0,
+                    g: 0.0,
+                    b: 0.0,
+                    a: 0.0,
+                })
+            } else {
+                LoadOp::Load
+            };
+
+            let mut pass = encoder.begin_render_pass(&RenderPassDescriptor {
                 label: Some("Picking Pass"),
                 color_attachments: &[Some(RenderPassColorAttachment {
                     view: viewport.picking_surface.view(),
                     depth_slice: None,
                     resolve_target: None,
                     ops: Operations {
-                        load: LoadOp::Clear(Color {
-                            r: 0.0,
-                            g: 0.0,
-                            b: 0.0,
-                            a: 0.0,
-                        }),
+                        load: color_load,
                         store: StoreOp::Store,
                     },
                 })],
@@ -564,14 +1284,24 @@ impl Renderer {
                 }),
                 ..RenderPassDescriptor::default()
             });
+            set_pass_viewport(
+                &mut pass,
+                view.rect,
+                viewport.config.width,
+                viewport.config.height,
+            );
 
+            let proxies = self.view_proxies(&view);
             self.render_scene(
                 ctx,
                 pass,
                 RenderPassType::Picking,
-                &self.sorted_proxies,
-                &viewport.render_data,
+                &proxies,
+                view.render_data,
+                self.lights.uniform().bind_group(),
+                self.lights.placeholder_shadow_uniform().bind_group(),
             );
+            self.view_proxies = proxies;
         }
 
         let render_ui = self.strobe.borrow().has_draws(request.target);
@@ -598,7 +1328,9 @@ impl Renderer {
                 render_bind_group: viewport.render_data.uniform.bind_group(),
                 light_bind_group: self.lights.uniform().bind_group(),
                 shadow_bind_group: self.lights.placeholder_shadow_uniform().bind_group(),
+                depth_bind_group: None,
                 transparency_pass: false,
+                stats: &self.frame_stats,
             };
             let mut strobe = self.strobe.borrow_mut();
 
@@ -607,7 +1339,7 @@ impl Renderer {
                 &self.cache,
                 &self.state,
                 request.target,
-                self.start_time,
+                self.time(),
                 viewport.size(),
             );
         }
@@ -648,7 +1380,7 @@ impl Renderer {
         self.state.queue.submit(Some(encoder.finish()));
 
         if let Some(result) = self.resolve_pick_buffer(read_buffer, request) {
-            let _ = self.pick_result_tx.send(result);
+            let _ = self.feedback_tx.send(RenderFeedback::Pick(result));
         }
     }
 
@@ -683,7 +1415,14 @@ impl Renderer {
 
     #[instrument(skip_all)]
     fn render(&mut self, target_id: RenderTargetId, viewport: &RenderViewport, ctx: &mut FrameCtx) {
+        let draw_calls = self.frame_stats.draw_calls();
         self.shadow_pass(ctx);
+        self.frame_stats
+            .record_shadow_draw_calls(self.frame_stats.draw_calls() - draw_calls);
+        // The primary target is drawn first each frame, so every window can sample the results
+        if target_id.is_primary() {
+            self.texture_camera_pass(viewport);
+        }
         self.main_pass(target_id, viewport, ctx);
     }
 
@@ -762,11 +1501,152 @@ impl Renderer {
             RenderPassType::Shadow,
             &self.sorted_proxies,
             &self.shadow_render_data,
+            self.lights.uniform().bind_group(),
+            self.lights.placeholder_shadow_uniform().bind_group(),
         );
 
         self.state.queue.submit(Some(encoder.finish()));
     }
 
+    #[instrument(skip_all)]
+    fn texture_camera_pass(&mut self, viewport: &RenderViewport) {
+        if self.texture_cameras.is_empty() {
+            return;
+        }
+
+        let mut cameras = mem::take(&mut self.texture_cameras);
+        let mut encoder = self
+            .state
+            .device
+            .create_command_encoder(&CommandEncoderDescriptor {
+                label: Some("Texture Camera Encoder"),
+            });
+
+        for (slot, camera) in cameras.iter_mut().enumerate() {
+            let target = self.cache.texture(camera.texture);
+            if target.format != CpuTexture::RENDER_TARGET_FORMAT
+                || target.size.depth_or_array_layers != 1
+            {
+                warn!(
+                    "Camera render texture {} needs to be a single layer {:?} texture",
+                    camera.texture,
+                    CpuTexture::RENDER_TARGET_FORMAT
+                );
+                continue;
+            }
+
+            let (width, height) = (target.size.width, target.size.height);
+            let ctx = FrameCtx {
+                depth_view: camera.depth_view(&self.state.device, width, height),
+            };
+
+            let render_data = &mut camera.camera.render_data;
+            render_data.system_data = viewport.render_data.system_data;
+            render_data.system_data.screen_size = Vector2::new(width, height);
+            render_data.upload_system_data(&self.state.queue);
+
+            let clear = camera.camera.clear;
+            let pass = encoder.begin_render_pass(&RenderPassDescriptor {
+                label: Some("Texture Camera Render Pass"),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: &target.view,
+                    depth_slice: None,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: color_load_op(&clear),
+                        store: StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+                    view: &ctx.depth_view,
+                    depth_ops: Some(Operations {
+                        load: depth_load_op(&clear),
+                        store: StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }),
+                ..RenderPassDescriptor::default()
+            });
+
+            let view = RenderView {
+                render_data: &camera.camera.render_data,
+                clear,
+                rect: ViewportRect::FULL,
+            };
+            self.lights.prepare_view(
+                slot,
+                &view.render_data.camera_data,
+                &self.cache,
+                &self.state.queue,
+                &self.state.device,
+            );
+
+            let proxies = self.view_proxies(&view);
+            self.record_culling(proxies.len());
+            self.render_scene(
+                &ctx,
+                pass,
+                RenderPassType::Color,
+                &proxies,
+                view.render_data,
+                self.lights.view_uniform(slot).bind_group(),
+                self.lights.shadow_uniform().bind_group(),
+            );
+            self.view_proxies = proxies;
+        }
+
+        self.state.queue.submit(Some(encoder.finish()));
+        self.texture_cameras = cameras;
+    }
+
+    /// Replaces the cameras drawing into textures, keeping the state of known cameras
+    fn set_texture_cameras(&mut self, views: Vec<TextureCameraView>) {
+        let bgl = self.cache.bgl_render();
+        let mut previous = mem::take(&mut self.texture_cameras);
+        let mut resized = false;
+
+        for view in &views {
+            let size = self.cache.texture(view.texture).size;
+            resized |= size.width != view.width || size.height != view.height;
+        }
+
+        // Materials hold on to the texture views they sample, so they need to be rebuilt as well
+        if resized {
+            self.cache.textures.refresh_dirty();
+            self.cache.materials.invalidate_all();
+        }
+
+        self.texture_cameras = views
+            .into_iter()
+            .map(
+                |view| match previous.iter().position(|c| c.camera.id == view.camera) {
+                    Some(idx) => {
+                        let mut camera = previous.swap_remove(idx);
+                        camera.texture = view.texture;
+                        camera
+                    }
+                    None => TextureCamera {
+                        camera: ViewCamera {
+                            id: view.camera,
+                            viewport: ViewportRect::FULL,
+                            render_data: RenderUniformData::empty(&self.state.device, &bgl),
+                            clear: CameraClear::default(),
+                        },
+                        texture: view.texture,
+                        depth_texture: None,
+                    },
+                },
+            )
+            .collect();
+    }
+
+    fn texture_camera_mut(&mut self, id: TypedComponentId) -> Option<&mut ViewCamera> {
+        self.texture_cameras
+            .iter_mut()
+            .map(|c| &mut c.camera)
+            .find(|c| c.id == id)
+    }
+
     #[instrument(skip_all)]
     fn main_pass(
         &mut self,
@@ -781,16 +1661,62 @@ impl Renderer {
                 label: Some("Main Encoder"),
             });
 
-        {
-            let pass = self.prepare_main_render_pass(&mut encoder, viewport, ctx);
+        for (i, view) in viewport.views().enumerate() {
+            let mut clear = view.clear;
+            // Clearing always affects the whole target, so a partial viewport drawn after
+            // another camera must not clear the output of the cameras before it. Its depth is
+            // cleared by drawing over its region instead.
+            if i != 0 && !view.rect.is_full() {
+                clear.color = None;
+                if clear.clears_depth() {
+                    self.clear_view_depth(&mut encoder, &view, viewport, ctx);
+                    clear.flags.remove(ClearFlags::DEPTH);
+                }
+            }
+
+            self.lights.prepare_view(
+                i,
+                &view.render_data.camera_data,
+                &self.cache,
+                &self.state.queue,
+                &self.state.device,
+            );
 
+            let mut pass = self.prepare_main_render_pass(&mut encoder, viewport, ctx, &clear);
+            set_pass_viewport(
+                &mut pass,
+                view.rect,
+                viewport.config.width,
+                viewport.config.height,
+            );
+
+            let proxies = self.view_proxies(&view);
+            self.record_culling(proxies.len());
             self.render_scene(
                 ctx,
                 pass,
                 RenderPassType::Color,
-                &self.sorted_proxies,
-                &viewport.render_data,
+                &proxies,
+                view.render_data,
+                self.lights.view_uniform(i).bind_group(),
+                viewport.shadow_uniform(&self.lights).bind_group(),
             );
+
+            // sorted by priority, so the decals are next to each other
+            let first_decal = proxies.partition_point(|(p, _)| *p < PROXY_PRIORITY_DECAL);
+            let last_decal = proxies.partition_point(|(p, _)| *p <= PROXY_PRIORITY_DECAL);
+            let decals = &proxies[first_decal..last_decal];
+            if !decals.is_empty() {
+                let mut pass = self.prepare_decal_render_pass(&mut encoder, viewport);
+                set_pass_viewport(
+                    &mut pass,
+                    view.rect,
+                    viewport.config.width,
+                    viewport.config.height,
+                );
+                self.render_decals(ctx, pass, decals, view.render_data, viewport);
+            }
+            self.view_proxies = proxies;
         }
 
         let has_ui_draws_queued = self.strobe.borrow().has_draws(target_id);
@@ -804,7 +1730,9 @@ impl Renderer {
                 render_bind_group: viewport.render_data.uniform.bind_group(),
                 light_bind_group: self.lights.uniform().bind_group(),
                 shadow_bind_group: self.lights.placeholder_shadow_uniform().bind_group(),
+                depth_bind_group: None,
                 transparency_pass: false,
+                stats: &self.frame_stats,
             };
 
             self.strobe.borrow_mut().render(
@@ -812,7 +1740,7 @@ impl Renderer {
                 &self.cache,
                 &self.state,
                 target_id,
-                self.start_time,
+                self.time(),
                 viewport.size(),
             );
         }
@@ -820,6 +1748,28 @@ impl Renderer {
         self.state.queue.submit(Some(encoder.finish()));
     }
 
+    /// Counts the proxies a camera drew and the ones it culled
+    fn record_culling(&self, drawn: usize) {
+        let enabled = self.proxies.values().filter(|b| b.enabled).count();
+        self.frame_stats
+            .record_proxies(drawn, enabled.saturating_sub(drawn));
+    }
+
+    /// Sorts the proxies inside the view. Hand the list back to `self.view_proxies` when it's
+    /// drawn, so the next view reuses it.
+    fn view_proxies(&mut self, view: &RenderView) -> Vec<(u32, TypedComponentId)> {
+        let frustum = Frustum::from_matrix(&view.render_data.camera_data.proj_view_mat);
+        let mut proxies = mem::take(&mut self.view_proxies);
+        sort_enabled_proxy_ids(
+            &self.proxies,
+            self.cache.store(),
+            Some(&frustum),
+            &mut self.proxy_sort_keys,
+            &mut proxies,
+        );
+        proxies
+    }
+
     #[instrument(skip_all)]
     fn render_scene(
         &self,
@@ -828,23 +1778,19 @@ impl Renderer {
         pass_type: RenderPassType,
         proxies: &[(u32, TypedComponentId)],
         render_uniform: &RenderUniformData,
+        light_bind_group: &BindGroup,
+        shadow_bind_group: &BindGroup,
     ) {
-        let shadow_bind_group = match pass_type {
-            RenderPassType::Color | RenderPassType::Color2D => self.lights.shadow_uniform(),
-            RenderPassType::Shadow | RenderPassType::Picking | RenderPassType::PickingUi => {
-                self.lights.placeholder_shadow_uniform()
-            }
-        }
-        .bind_group();
-
         let mut draw_ctx = GPUDrawCtx {
             frame: frame_ctx,
             pass: RwLock::new(pass),
             pass_type,
             render_bind_group: render_uniform.uniform.bind_group(),
-            light_bind_group: self.lights.uniform().bind_group(),
+            light_bind_group,
             shadow_bind_group,
+            depth_bind_group: None,
             transparency_pass: false,
+            stats: &self.frame_stats,
         };
 
         self.render_proxies(&mut draw_ctx, proxies);
@@ -855,6 +1801,31 @@ impl Renderer {
         }
     }
 
+    /// Projects decals onto the scene that was just drawn into the viewport
+    #[instrument(skip_all)]
+    fn render_decals(
+        &self,
+        frame_ctx: &FrameCtx,
+        pass: RenderPass,
+        decals: &[(u32, TypedComponentId)],
+        render_uniform: &RenderUniformData,
+        viewport: &RenderViewport,
+    ) {
+        let mut draw_ctx = GPUDrawCtx {
+            frame: frame_ctx,
+            pass: RwLock::new(pass),
+            pass_type: RenderPassType::Decal,
+            render_bind_group: render_uniform.uniform.bind_group(),
+            light_bind_group: self.lights.uniform().bind_group(),
+            shadow_bind_group: self.lights.placeholder_shadow_uniform().bind_group(),
+            depth_bind_group: Some(viewport.scene_depth.uniform.bind_group()),
+            transparency_pass: false,
+            stats: &self.frame_stats,
+        };
+
+        self.render_proxies(&mut draw_ctx, decals);
+    }
+
     #[instrument(skip_all)]
     fn render_proxies(&self, ctx: &mut GPUDrawCtx, proxies: &[(u32, TypedComponentId)]) {
         ctx.transparency_pass = false;
@@ -869,7 +1840,7 @@ impl Renderer {
 
         match ctx.pass_type {
             RenderPassType::Color | RenderPassType::Shadow => (),
-            RenderPassType::Picking => return,
+            RenderPassType::Picking | RenderPassType::Decal | RenderPassType::GBuffer => return,
             RenderPassType::Color2D | RenderPassType::PickingUi => {
                 debug_panic!("Shouldn't render scene in 2D passes");
                 return;
@@ -888,23 +1859,37 @@ impl Renderer {
     }
 
     #[instrument(skip_all)]
-    fn end_render(&mut self, viewport: &mut RenderViewport) -> Result<()> {
-        let mut output = viewport.surface.get_current_texture().context(SurfaceErr)?;
-        if output.suboptimal {
-            warn!("Surface Output is suboptimal. Recreating...");
-            drop(output);
-            viewport.recreate_surface(&self.state);
-            output = viewport.surface.get_current_texture().context(SurfaceErr)?;
-        }
+    fn end_render(
+        &mut self,
+        target_id: RenderTargetId,
+        viewport: &mut RenderViewport,
+    ) -> Result<()> {
+        match &viewport.output {
+            ViewportOutput::Window { surface, .. } => {
+                let mut output = surface.get_current_texture().context(SurfaceErr)?;
+                if output.suboptimal {
+                    warn!("Surface Output is suboptimal. Recreating...");
+                    drop(output);
+                    viewport.recreate_surface(&self.state);
+                    output = surface.get_current_texture().context(SurfaceErr)?;
+                }
 
-        let color_view = output
-            .texture
-            .create_view(&TextureViewDescriptor::default());
+                let color_view = output
+                    .texture
+                    .create_view(&TextureViewDescriptor::default());
 
-        self.render_final_pass(viewport, &color_view);
+                self.render_final_pass(target_id, viewport, &color_view);
 
-        viewport.window.pre_present_notify();
-        output.present();
+                if let Some(window) = viewport.window() {
+                    window.pre_present_notify();
+                }
+                output.present();
+            }
+            ViewportOutput::Headless { texture } => {
+                let color_view = texture.create_view(&TextureViewDescriptor::default());
+                self.render_final_pass(target_id, viewport, &color_view);
+            }
+        }
 
         if self.cache.last_refresh().elapsed().as_secs_f32() > 5.0 {
             trace!("Refreshing cache...");
@@ -918,7 +1903,26 @@ impl Renderer {
     }
 
     #[instrument(skip_all)]
-    fn render_final_pass(&mut self, viewport: &RenderViewport, color_view: &TextureView) {
+    #[cfg_attr(not(feature = "egui"), allow(unused_variables))]
+    fn render_final_pass(
+        &mut self,
+        target_id: RenderTargetId,
+        viewport: &RenderViewport,
+        color_view: &TextureView,
+    ) {
+        // egui only draws over the primary render target
+        #[cfg(feature = "egui")]
+        let draw_egui = target_id.is_primary();
+        #[cfg(feature = "egui")]
+        if draw_egui {
+            self.egui.prepare(
+                &self.state.device,
+                &self.state.queue,
+                viewport.config.format,
+                viewport.size(),
+            );
+        }
+
         let mut encoder = self
             .state
             .device
@@ -951,8 +1955,14 @@ impl Renderer {
         if let Some(idx) = groups.post_process {
             pass.set_bind_group(idx, viewport.post_process_data.uniform.bind_group(), &[]);
         }
+        self.frame_stats.record_draw(6, 1);
         pass.draw(0..6, 0..1);
 
+        #[cfg(feature = "egui")]
+        if draw_egui {
+            self.egui.paint(&mut pass, &self.frame_stats);
+        }
+
         drop(pass);
 
         self.state.queue.submit(Some(encoder.finish()));
@@ -988,12 +1998,46 @@ impl Renderer {
             RenderMsg::LightProxyUpdate(cid, command) => {
                 self.lights.execute_light_command(cid, command);
             }
-            RenderMsg::UpdateActiveCamera(render_target_id, camera_data) => {
+            RenderMsg::UpdateActiveCamera(render_target_id, cid, camera_data) => {
+                if let Some(camera) = self.texture_camera_mut(cid) {
+                    camera_data(&mut camera.render_data.camera_data);
+                    camera.render_data.upload_camera_data(&self.state.queue);
+                    return;
+                }
+                let Some(vp) = self.viewports.get_mut(&render_target_id) else {
+                    return;
+                };
+                match vp.camera_mut(cid) {
+                    Some(camera) => {
+                        camera_data(&mut camera.render_data.camera_data);
+                        camera.render_data.upload_camera_data(&self.state.queue);
+                    }
+                    None => {
+                        camera_data(&mut vp.render_data.camera_data);
+                        vp.update_view_camera_data(&self.state.queue);
+                    }
+                }
+            }
+            RenderMsg::UpdateCameraClear(render_target_id, cid, clear) => {
+                if let Some(camera) = self.texture_camera_mut(cid) {
+                    camera.clear = clear;
+                    return;
+                }
+                let Some(vp) = self.viewports.get_mut(&render_target_id) else {
+                    return;
+                };
+                match vp.camera_mut(cid) {
+                    Some(camera) => camera.clear = clear,
+                    None => vp.clear = clear,
+                }
+            }
+            RenderMsg::UpdateCameraViews(render_target_id, views) => {
+                let bgl = self.cache.bgl_render();
                 if let Some(vp) = self.viewports.get_mut(&render_target_id) {
-                    camera_data(&mut vp.render_data.camera_data);
-                    vp.update_view_camera_data(&self.state.queue);
+                    vp.set_camera_views(views, &self.state.device, &bgl);
                 }
             }
+            RenderMsg::UpdateTextureCameras(views) => self.set_texture_cameras(views),
             RenderMsg::ProxyState(cid, enabled) => {
                 if let Some(binding) = self.proxies.get_mut(&cid) {
                     binding.enabled = enabled;
@@ -1026,9 +2070,20 @@ impl Renderer {
                     warn!("Couldn't capture picking texture: {e}");
                 }
             }
+            RenderMsg::CaptureFrame(target, path) => {
+                if let Err(e) = self.export_frame_png(target, &path) {
+                    warn!("Couldn't capture frame: {e}");
+                }
+            }
             RenderMsg::UpdateStrobe(frame) => {
                 self.strobe.borrow_mut().update_frame(frame);
             }
+            RenderMsg::DispatchCompute(job) => self.pending_compute.push(job),
+            #[cfg(feature = "egui")]
+            RenderMsg::UpdateEgui(paint) => {
+                self.egui
+                    .update(paint, &self.state.device, &self.state.queue)
+            }
         }
     }
 
@@ -1049,7 +2104,14 @@ impl Renderer {
 
         self.window_map.insert(window.id(), target_id);
 
-        let viewport = RenderViewport::new(window, surface, config, &self.state, &self.cache);
+        let viewport = RenderViewport::new(
+            ViewportOutput::Window { window, surface },
+            config,
+            &self.state,
+            &self.cache,
+            self.post_settings,
+            self.gbuffer_enabled,
+        );
         self.viewports.insert(target_id, viewport);
 
         Ok(())
@@ -1082,6 +2144,7 @@ impl Renderer {
         encoder: &'a mut CommandEncoder,
         viewport: &RenderViewport,
         ctx: &mut FrameCtx,
+        clear: &CameraClear,
     ) -> RenderPass<'a> {
         encoder.begin_render_pass(&RenderPassDescriptor {
             label: Some("Offscreen Render Pass"),
@@ -1090,14 +2153,14 @@ impl Renderer {
                 depth_slice: None,
                 resolve_target: None,
                 ops: Operations {
-                    load: LoadOp::Clear(Color::BLACK),
+                    load: color_load_op(clear),
                     store: StoreOp::Store,
                 },
             })],
             depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
                 view: &ctx.depth_view,
                 depth_ops: Some(Operations {
-                    load: LoadOp::Clear(1.0),
+                    load: depth_load_op(clear),
                     store: StoreOp::Store,
                 }),
                 stencil_ops: None,
@@ -1106,6 +2169,69 @@ impl Renderer {
         })
     }
 
+    /// Resets the depth inside the region of a view to the far plane, leaving the rest of the
+    /// target untouched
+    fn clear_view_depth(
+        &self,
+        encoder: &mut CommandEncoder,
+        view: &RenderView,
+        viewport: &RenderViewport,
+        ctx: &FrameCtx,
+    ) {
+        let mut pass = encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some("Viewport Depth Clear Pass"),
+            color_attachments: &[],
+            depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+                view: &ctx.depth_view,
+                depth_ops: Some(Operations {
+                    load: LoadOp::Load,
+                    store: StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            ..RenderPassDescriptor::default()
+        });
+        set_pass_viewport(
+            &mut pass,
+            view.rect,
+            viewport.config.width,
+            viewport.config.height,
+        );
+
+        let shader = self.cache.shader(HShader::VIEWPORT_CLEAR);
+        pass.set_pipeline(shader.solid_pipeline());
+        pass.set_bind_group(
+            shader.bind_groups().render,
+            view.render_data.uniform.bind_group(),
+            &[],
+        );
+        self.frame_stats.record_draw(3, 1);
+        pass.draw(0..3, 0..1);
+    }
+
+    /// The scene depth is sampled by decals, so it can't be attached here
+    #[instrument(skip_all)]
+    fn prepare_decal_render_pass<'a>(
+        &self,
+        encoder: &'a mut CommandEncoder,
+        viewport: &RenderViewport,
+    ) -> RenderPass<'a> {
+        encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some("Decal Render Pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: viewport.offscreen_surface.view(),
+                depth_slice: None,
+                resolve_target: None,
+                ops: Operations {
+                    load: LoadOp::Load,
+                    store: StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            ..RenderPassDescriptor::default()
+        })
+    }
+
     #[instrument(skip_all)]
     fn prepare_ui_render_pass<'a>(
         &self,
@@ -1130,111 +2256,109 @@ impl Renderer {
     }
 }
 
-#[instrument(skip_all)]
-fn sorted_enabled_proxy_ids(
-    proxies: &HashMap<TypedComponentId, SceneProxyBinding>,
-    store: &AssetStore,
-    frustum: Option<&Frustum>,
-) -> Vec<(u32, TypedComponentId)> {
-    proxies
-        .iter()
-        .filter(|(_, binding)| binding.enabled)
-        .filter_map(|(tid, binding)| {
-            let priority = binding.proxy.priority(store);
-            let mut distance = 0.0;
-            if let Some(f) = frustum
-                && let Some(bounds) = binding.bounds()
-            {
-                if !f.intersects_sphere(&bounds) {
-                    return None;
-                }
-                distance = f.side(FrustumSide::Near).distance_to(&bounds);
-            };
-
-            Some((tid, priority, distance))
-        })
-        .sorted_by_key(|(_, priority, distance)| (*priority, -(*distance * 100000.0) as i64))
-        .map(|(tid, priority, _)| (priority, *tid))
-        .collect()
+fn create_depth_texture(device: &Device, width: u32, height: u32) -> Texture {
+    device.create_texture(&TextureDescriptor {
+        label: Some("Depth Texture"),
+        size: Extent3d {
+            width: width.max(1),
+            height: height.max(1),
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format: TextureFormat::Depth32Float,
+        usage: TextureUsages::RENDER_ATTACHMENT
+            | TextureUsages::TEXTURE_BINDING
+            | TextureUsages::COPY_SRC,
+        view_formats: &[],
+    })
 }
 
-#[derive(Debug, Clone, Copy)]
-struct FrustumPlane {
-    normal: Vector3<f32>,
-    d: f32,
+fn set_pass_viewport(pass: &mut RenderPass, rect: ViewportRect, width: u32, height: u32) {
+    if rect.is_full() {
+        return;
+    }
+
+    let (x, y, w, h) = rect.to_pixels(width, height);
+    pass.set_viewport(x as f32, y as f32, w as f32, h as f32, 0.0, 1.0);
+    pass.set_scissor_rect(x, y, w, h);
 }
 
-impl FrustumPlane {
-    fn distance_to(&self, sphere: &BoundingSphere) -> f32 {
-        self.normal.dot(&sphere.center) + self.d
+fn color_load_op(clear: &CameraClear) -> LoadOp<Color> {
+    match clear.clear_color() {
+        Some(color) => LoadOp::Clear(Color {
+            r: color.x as f64,
+            g: color.y as f64,
+            b: color.z as f64,
+            a: color.w as f64,
+        }),
+        None => LoadOp::Load,
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Frustum {
-    planes: [FrustumPlane; 6],
+fn gbuffer_attachment(
+    view: &TextureView,
+    load: LoadOp<Color>,
+) -> Option<RenderPassColorAttachment<'_>> {
+    Some(RenderPassColorAttachment {
+        view,
+        depth_slice: None,
+        resolve_target: None,
+        ops: Operations {
+            load,
+            store: StoreOp::Store,
+        },
+    })
 }
 
-#[allow(unused)]
-enum FrustumSide {
-    Left,
-    Right,
-    Bottom,
-    Top,
-    Near,
-    Far,
+fn depth_load_op(clear: &CameraClear) -> LoadOp<f32> {
+    if clear.clears_depth() {
+        LoadOp::Clear(1.0)
+    } else {
+        LoadOp::Load
+    }
 }
 
-impl Frustum {
-    #[instrument(skip_all)]
-    fn from_matrix(m: &Matrix4<f32>) -> Self {
-        let row0 = m.row(0).transpose();
-        let row1 = m.row(1).transpose();
-        let row2 = m.row(2).transpose();
-        let row3 = m.row(3).transpose();
-
-        let plane_from = |v: Vector4<f32>| {
-            let normal = Vector3::new(v.x, v.y, v.z);
-            let len = normal.norm();
-            if len > 0.0 {
-                FrustumPlane {
-                    normal: normal / len,
-                    d: v.w / len,
-                }
-            } else {
-                FrustumPlane { normal, d: v.w }
-            }
-        };
-
-        let planes = [
-            plane_from(row3 + row0), // left
-            plane_from(row3 - row0), // right
-            plane_from(row3 + row1), // bottom
-            plane_from(row3 - row1), // top
-            plane_from(row3 + row2), // near
-            plane_from(row3 - row2), // far
-        ];
-
-        Frustum { planes }
-    }
-
-    pub fn side(&self, side: FrustumSide) -> &FrustumPlane {
-        match side {
-            FrustumSide::Left => &self.planes[0],
-            FrustumSide::Right => &self.planes[1],
-            FrustumSide::Bottom => &self.planes[2],
-            FrustumSide::Top => &self.planes[3],
-            FrustumSide::Near => &self.planes[4],
-            FrustumSide::Far => &self.planes[5],
-        }
-    }
+/// Priority, then the negated distance to the near plane in fixed point, so farther proxies
+/// are drawn first
+type ProxySortKey = (u32, i64, TypedComponentId);
 
-    #[instrument(skip_all)]
-    fn intersects_sphere(&self, sphere: &BoundingSphere) -> bool {
-        self.planes
+/// Sorts the enabled proxies inside the frustum into `out`. Both buffers are cleared and reused,
+/// so this doesn't allocate once they've grown to the number of proxies.
+#[instrument(skip_all)]
+fn sort_enabled_proxy_ids(
+    proxies: &HashMap<TypedComponentId, SceneProxyBinding>,
+    store: &AssetStore,
+    frustum: Option<&Frustum>,
+    keys: &mut Vec<ProxySortKey>,
+    out: &mut Vec<(u32, TypedComponentId)>,
+) {
+    keys.clear();
+    keys.extend(
+        proxies
             .iter()
-            .all(|p| p.distance_to(sphere) >= -sphere.radius)
-    }
+            .filter(|(_, binding)| binding.enabled)
+            .filter_map(|(tid, binding)| {
+                let priority = binding.proxy.priority(store);
+                let mut distance = 0.0;
+                if let Some(f) = frustum
+                    && let Some(bounds) = binding.bounds()
+                {
+                    if !f.intersects_sphere(&bounds) {
+                        return None;
+                    }
+                    distance = f.side(FrustumSide::Near).distance_to(&bounds);
+                };
+
+                Some((priority, -(distance * 100000.0) as i64, *tid))
+            }),
+    );
+    // a stable sort would allocate, and the map has no order to keep anyway
+    keys.sort_unstable_by_key(|(priority, distance, _)| (*priority, *distance));
+
+    out.clear();
+    out.extend(keys.iter().map(|(priority, _, tid)| (*priority, *tid)));
 }
 
 #[cfg(test)]
@@ -1242,6 +2366,7 @@ mod tests {
     use super::*;
     use crate::components::ComponentId;
     use crate::rendering::proxies::SceneProxy;
+    use crate::rendering::stats;
     use nalgebra::{Affine3, Matrix4};
     use slotmap::Key;
     use std::any::{Any, TypeId};
@@ -1279,7 +2404,7 @@ mod tests {
         let id_low = insert_proxy::<MarkerLow>(&mut proxies, 10, true);
         let id_mid = insert_proxy::<MarkerMid>(&mut proxies, 50, true);
 
-        let sorted = sorted_enabled_proxy_ids(&proxies, &store, None);
+        let sorted = sorted_ids(&proxies, &store);
         assert_eq!(sorted, vec![(10, id_low), (50, id_mid), (900, id_high)]);
     }
 
@@ -1294,11 +2419,41 @@ mod tests {
         let id_enabled = insert_proxy::<MarkerEnabled>(&mut proxies, 5, true);
         let id_disabled = insert_proxy::<MarkerDisabled>(&mut proxies, 1, false);
 
-        let sorted = sorted_enabled_proxy_ids(&proxies, &store, None);
+        let sorted = sorted_ids(&proxies, &store);
         assert_eq!(sorted, vec![(5, id_enabled)]);
         assert!(!sorted.contains(&(1, id_disabled)));
     }
 
+    #[cfg(feature = "alloc-stats")]
+    #[test]
+    fn resorting_proxies_reuses_the_buffers() {
+        struct MarkerA;
+        struct MarkerB;
+
+        let store = AssetStore::new();
+        let mut proxies = HashMap::new();
+        insert_proxy::<MarkerA>(&mut proxies, 20, true);
+        insert_proxy::<MarkerB>(&mut proxies, 10, true);
+
+        let mut keys = Vec::new();
+        let mut sorted = Vec::new();
+        sort_enabled_proxy_ids(&proxies, &store, None, &mut keys, &mut sorted);
+
+        let before = stats::thread_allocations();
+        sort_enabled_proxy_ids(&proxies, &store, None, &mut keys, &mut sorted);
+        assert_eq!(stats::thread_allocations(), before);
+        assert_eq!(sorted.len(), 2);
+    }
+
+    fn sorted_ids(
+        proxies: &HashMap<TypedComponentId, SceneProxyBinding>,
+        store: &AssetStore,
+    ) -> Vec<(u32, TypedComponentId)> {
+        let mut sorted = Vec::new();
+        sort_enabled_proxy_ids(proxies, store, None, &mut Vec::new(), &mut sorted);
+        sorted
+    }
+
     fn insert_proxy<T: 'static>(
         proxies: &mut HashMap<TypedComponentId, SceneProxyBinding>,
         priority: u32,
@@ -0,0 +1,24 @@
+use crate::engine::rendering::uniform::ShaderUniform;
+use syrillian_macros::UniformIndex;
+use wgpu::{BindGroupLayout, Device, TextureView};
+
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, UniformIndex)]
+pub enum SceneDepthUniformIndex {
+    Depth = 0,
+}
+
+/// Exposes the scene depth to passes that run after the main scene was drawn, like decals
+pub struct SceneDepthData {
+    pub(crate) uniform: ShaderUniform<SceneDepthUniformIndex>,
+}
+
+impl SceneDepthData {
+    pub(crate) fn new(device: &Device, layout: &BindGroupLayout, depth_view: &TextureView) -> Self {
+        let uniform = ShaderUniform::<SceneDepthUniformIndex>::builder(layout)
+            .with_texture(depth_view)
+            .build(device);
+
+        Self { uniform }
+    }
+}
@@ -147,6 +147,37 @@ impl State {
         Ok(unsafe { mem::transmute::<Surface<'_>, Surface<'static>>(surface) })
     }
 
+    /// Creates a device without a window, for rendering into textures only
+    pub fn new_headless() -> Result<Self> {
+        let instance = Self::setup_instance();
+        let adapter = block_on(Self::setup_adapter(&instance, None));
+        let (device, queue) = block_on(Self::get_device_and_queue(&adapter))?;
+
+        Ok(State {
+            instance,
+            adapter,
+            device,
+            queue,
+            preferred_format: TextureFormat::Bgra8UnormSrgb,
+        })
+    }
+
+    /// A configuration for render targets that aren't presented to a surface
+    pub fn headless_config(&self, size: PhysicalSize<u32>) -> SurfaceConfiguration {
+        let size = Self::clamp_size(size);
+
+        SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            format: self.preferred_format,
+            width: size.width,
+            height: size.height,
+            present_mode: wgpu::PresentMode::Fifo,
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+            view_formats: vec![],
+            desired_maximum_frame_latency: 1,
+        }
+    }
+
     pub fn new(window: &Window) -> Result<(Self, Surface<'static>, SurfaceConfiguration)> {
         let instance = Self::setup_instance();
         let surface = instance.create_surface(window).context(CreateSurfaceErr)?;
@@ -0,0 +1,192 @@
+//! Counters of what the renderer drew each frame, see [`RenderStats`].
+//!
+//! Draw calls are recorded where they're issued, which is often far from the renderer, so every
+//! renderer hands its [`FrameStats`] to the draw code through the
+//! [`GPUDrawCtx`](crate::rendering::GPUDrawCtx).
+//!
+//! With the `alloc-stats` feature, installing [`CountingAllocator`] as the global allocator also
+//! counts the heap allocations of the render thread, to catch hot paths that start allocating
+//! every frame.
+
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+/// What the renderer drew in a frame, e.g. to check batching and culling or to spot
+/// performance regressions.
+///
+/// Everything drawn since the previous frame of the primary render target is counted, so other
+/// windows and texture cameras are included.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct RenderStats {
+    /// Draw calls of all passes, including shadows and picking
+    pub draw_calls: u32,
+    /// Triangles of all draw calls. Lines don't count.
+    pub triangles: u64,
+    /// Scene proxies inside the view of a camera
+    pub proxies_drawn: u32,
+    /// Scene proxies skipped by a camera because they were outside its view
+    pub proxies_culled: u32,
+    /// Draw calls into shadow maps, which are part of the `draw_calls`
+    pub shadow_draw_calls: u32,
+    /// Heap allocations of the render thread since its previous frame. Only counted with the
+    /// `alloc-stats` feature and [`CountingAllocator`] as global allocator, and `0` otherwise.
+    pub allocations: u64,
+}
+
+/// Counts what a renderer draws in the current frame, until
+/// [`finish_frame`](Self::finish_frame) turns it into [`RenderStats`].
+///
+/// The counters are atomics, so they can be shared with the draw code by reference.
+#[derive(Debug, Default)]
+pub struct FrameStats {
+    draw_calls: AtomicU32,
+    triangles: AtomicU64,
+    proxies_drawn: AtomicU32,
+    proxies_culled: AtomicU32,
+    shadow_draw_calls: AtomicU32,
+    frame_start_allocations: AtomicU64,
+}
+
+impl FrameStats {
+    /// Counts a draw call of a triangle list with `vertices` vertices or indices, drawn
+    /// `instances` times
+    pub fn record_draw(&self, vertices: u32, instances: u32) {
+        let triangles = (vertices / 3) as u64 * instances as u64;
+        self.draw_calls.fetch_add(1, Ordering::Relaxed);
+        self.triangles.fetch_add(triangles, Ordering::Relaxed);
+    }
+
+    /// Counts a draw call that doesn't draw triangles, like lines
+    pub fn record_line_draw(&self) {
+        self.draw_calls.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_proxies(&self, drawn: usize, culled: usize) {
+        self.proxies_drawn
+            .fetch_add(drawn as u32, Ordering::Relaxed);
+        self.proxies_culled
+            .fetch_add(culled as u32, Ordering::Relaxed);
+    }
+
+    /// Returns the draw calls of the current frame so far
+    pub(crate) fn draw_calls(&self) -> u32 {
+        self.draw_calls.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn record_shadow_draw_calls(&self, count: u32) {
+        self.shadow_draw_calls.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Ends the current frame and returns its stats. Called on the render thread, whose
+    /// allocations are counted.
+    pub(crate) fn finish_frame(&self) -> RenderStats {
+        let allocations = thread_allocations();
+        let frame_start = self
+            .frame_start_allocations
+            .swap(allocations, Ordering::Relaxed);
+
+        RenderStats {
+            draw_calls: self.draw_calls.swap(0, Ordering::Relaxed),
+            triangles: self.triangles.swap(0, Ordering::Relaxed),
+            proxies_drawn: self.proxies_drawn.swap(0, Ordering::Relaxed),
+            proxies_culled: self.proxies_culled.swap(0, Ordering::Relaxed),
+            shadow_draw_calls: self.shadow_draw_calls.swap(0, Ordering::Relaxed),
+            allocations: allocations.saturating_sub(frame_start),
+        }
+    }
+}
+
+thread_local! {
+    static THREAD_ALLOCATIONS: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Wraps the system allocator to count the allocations of every thread, for
+/// [`RenderStats::allocations`]. It has to be installed by the binary:
+///
+/// ```ignore
+/// #[global_allocator]
+/// static ALLOCATOR: syrillian::rendering::stats::CountingAllocator =
+///     syrillian::rendering::stats::CountingAllocator;
+/// ```
+#[cfg(feature = "alloc-stats")]
+pub struct CountingAllocator;
+
+#[cfg(feature = "alloc-stats")]
+unsafe impl std::alloc::GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+        count_allocation();
+        unsafe { std::alloc::System.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: std::alloc::Layout) -> *mut u8 {
+        count_allocation();
+        unsafe { std::alloc::System.alloc_zeroed(layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: std::alloc::Layout, new_size: usize) -> *mut u8 {
+        count_allocation();
+        unsafe { std::alloc::System.realloc(ptr, layout, new_size) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+        unsafe { std::alloc::System.dealloc(ptr, layout) }
+    }
+}
+
+#[cfg(all(test, feature = "alloc-stats"))]
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[cfg(feature = "alloc-stats")]
+fn count_allocation() {
+    // the counter is gone while the thread is torn down
+    let _ = THREAD_ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+}
+
+/// Returns the allocations the current thread made so far. Stays `0` without the
+/// [`CountingAllocator`].
+pub(crate) fn thread_allocations() -> u64 {
+    THREAD_ALLOCATIONS.try_with(Cell::get).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finishing_a_frame_resets_the_counters() {
+        let frame = FrameStats::default();
+        frame.record_draw(36, 2);
+        frame.record_line_draw();
+        frame.record_proxies(3, 5);
+        frame.record_shadow_draw_calls(1);
+
+        let stats = frame.finish_frame();
+        assert_eq!(stats.draw_calls, 2);
+        assert_eq!(stats.triangles, 24);
+        assert_eq!(stats.proxies_drawn, 3);
+        assert_eq!(stats.proxies_culled, 5);
+        assert_eq!(stats.shadow_draw_calls, 1);
+
+        assert_eq!(frame.finish_frame().draw_calls, 0);
+    }
+
+    #[test]
+    fn renderers_count_their_frames_separately() {
+        let first = FrameStats::default();
+        let second = FrameStats::default();
+        first.record_draw(3, 1);
+
+        assert_eq!(second.finish_frame().draw_calls, 0);
+        assert_eq!(first.finish_frame().draw_calls, 1);
+    }
+
+    #[cfg(feature = "alloc-stats")]
+    #[test]
+    fn the_allocations_of_the_thread_are_counted() {
+        let before = thread_allocations();
+        drop(std::hint::black_box(Box::new(5)));
+
+        assert!(thread_allocations() > before);
+    }
+}
@@ -4,7 +4,8 @@ use crate::rendering::proxies::MeshUniformIndex;
 use crate::rendering::{RenderPassType, hash_to_rgba};
 use crate::strobe::UiDrawContext;
 use crate::strobe::ui_element::UiElement;
-use nalgebra::{Matrix4, Scale3, Translation3};
+use bytemuck::{Pod, Zeroable};
+use nalgebra::{Matrix4, Point3, Scale3, Translation3, Vector2, Vector4};
 use num_traits::Zero;
 
 #[derive(Debug, Clone)]
@@ -15,6 +16,24 @@ pub struct UiImageDraw {
     pub object_hash: ObjectHash,
 }
 
+/// How the edges and the center of a [`ImageScalingMode::NineSlice`] fill their space
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SliceFill {
+    #[default]
+    Stretch,
+    /// Repeats the texture region at its original pixel size
+    Tile,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub(crate) struct NineSliceImmediates {
+    size: [f32; 2],
+    tile: u32,
+    _padding: u32,
+    border: [f32; 4],
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ImageScalingMode {
     Absolute {
@@ -41,9 +60,113 @@ pub enum ImageScalingMode {
         center: [f32; 2],
         size: [f32; 2],
     },
+    /// Placed like [`Absolute`](ImageScalingMode::Absolute), but the texture is split into
+    /// 9 regions so it can grow without distorting its corners.
+    ///
+    /// The corners keep their pixel size, the edges grow along their side and the center grows
+    /// both ways. If the image is smaller than its borders, the borders shrink to fit.
+    NineSlice {
+        left: f32,
+        right: f32,
+        top: f32,
+        bottom: f32,
+        /// The left, right, top and bottom border in texture pixels, drawn at the same size
+        border: Vector4<f32>,
+        center: SliceFill,
+        edges: SliceFill,
+    },
 }
 
 impl ImageScalingMode {
+    /// The size the image covers on a screen of `screen` pixels
+    pub fn size_px(&self, screen: Vector2<f32>) -> Vector2<f32> {
+        let size = match *self {
+            ImageScalingMode::Absolute {
+                left,
+                right,
+                top,
+                bottom,
+            }
+            | ImageScalingMode::NineSlice {
+                left,
+                right,
+                top,
+                bottom,
+                ..
+            } => Vector2::new(right - left, top - bottom),
+            ImageScalingMode::Relative {
+                width,
+                height,
+                left,
+                right,
+                top,
+                bottom,
+            } => Vector2::new(
+                (right - left) / width * screen.x,
+                (top - bottom) / height * screen.y,
+            ),
+            ImageScalingMode::RelativeStretch {
+                left,
+                right,
+                top,
+                bottom,
+            } => Vector2::new((right - left) * screen.x, (top - bottom) * screen.y),
+            ImageScalingMode::Ndc { size, .. } => {
+                Vector2::new(size[0] * screen.x, size[1] * screen.y) * 0.5
+            }
+        };
+        size.abs()
+    }
+
+    /// The top left and bottom right corner the image covers on a screen of `screen` pixels.
+    /// Like cursor positions, y goes down.
+    pub fn screen_rect_px(&self, screen: Vector2<f32>) -> (Vector2<f32>, Vector2<f32>) {
+        let matrix = self.screen_matrix(screen.x, screen.y);
+        let to_px = |ndc: Point3<f32>| {
+            Vector2::new(
+                (ndc.x + 1.0) * 0.5 * screen.x,
+                (1.0 - ndc.y) * 0.5 * screen.y,
+            )
+        };
+
+        let a = to_px(matrix.transform_point(&Point3::new(-1.0, -1.0, 0.0)));
+        let b = to_px(matrix.transform_point(&Point3::new(1.0, 1.0, 0.0)));
+        (a.inf(&b), a.sup(&b))
+    }
+
+    /// Returns true if the pixel `point` on a screen of `screen` pixels is on the image
+    pub fn contains_px(&self, point: Vector2<f32>, screen: Vector2<f32>) -> bool {
+        let (min, max) = self.screen_rect_px(screen);
+        point.x >= min.x && point.x < max.x && point.y >= min.y && point.y < max.y
+    }
+
+    /// Moves the image to the given pixel rect. A nine slice keeps its borders, every other
+    /// mode becomes [`Absolute`](ImageScalingMode::Absolute).
+    pub fn with_rect(self, left: f32, right: f32, top: f32, bottom: f32) -> Self {
+        match self {
+            ImageScalingMode::NineSlice {
+                border,
+                center,
+                edges,
+                ..
+            } => ImageScalingMode::NineSlice {
+                left,
+                right,
+                top,
+                bottom,
+                border,
+                center,
+                edges,
+            },
+            _ => ImageScalingMode::Absolute {
+                left,
+                right,
+                top,
+                bottom,
+            },
+        }
+    }
+
     pub fn screen_matrix(&self, window_width: f32, window_height: f32) -> Matrix4<f32> {
         match self {
             ImageScalingMode::Absolute {
@@ -51,6 +174,13 @@ impl ImageScalingMode {
                 right,
                 top,
                 bottom,
+            }
+            | ImageScalingMode::NineSlice {
+                left,
+                right,
+                top,
+                bottom,
+                ..
             } => {
                 let left = (*left / window_width) * 2.0 - 1.0;
                 let right = (*right / window_width) * 2.0 - 1.0;
@@ -119,13 +249,49 @@ impl ImageScalingMode {
     }
 }
 
+impl UiImageDraw {
+    fn nine_slice_immediates(&self) -> Option<NineSliceImmediates> {
+        let ImageScalingMode::NineSlice {
+            left,
+            right,
+            top,
+            bottom,
+            border,
+            center,
+            edges,
+        } = self.scaling
+        else {
+            return None;
+        };
+
+        let mut tile = 0;
+        if center == SliceFill::Tile {
+            tile |= 1;
+        }
+        if edges == SliceFill::Tile {
+            tile |= 1 << 1;
+        }
+
+        Some(NineSliceImmediates {
+            size: [(right - left).abs(), (top - bottom).abs()],
+            tile,
+            _padding: 0,
+            border: border.sup(&Vector4::zeros()).into(),
+        })
+    }
+}
+
 impl UiElement for UiImageDraw {
     fn draw_order(&self) -> u32 {
         self.draw_order
     }
 
     fn render(&self, ctx: &mut UiDrawContext) {
+        let nine_slice = self.nine_slice_immediates();
         let shader = match ctx.pass_type() {
+            RenderPassType::Color2D if nine_slice.is_some() => {
+                Some(ctx.cache().shader(HShader::NINE_SLICE))
+            }
             RenderPassType::Color2D => Some(ctx.cache().shader_2d()),
             RenderPassType::PickingUi => Some(ctx.cache().shader(HShader::DIM2_PICKING)),
             _ => None,
@@ -165,6 +331,9 @@ impl UiElement for UiImageDraw {
                 if let Some(idx) = shader.bind_groups().material {
                     pass.set_bind_group(idx, material.uniform.bind_group(), &[]);
                 }
+                if let Some(immediates) = nine_slice {
+                    pass.set_immediates(0, bytemuck::bytes_of(&immediates));
+                }
             }
             RenderPassType::PickingUi => {
                 let color = hash_to_rgba(self.object_hash);
@@ -173,6 +342,8 @@ impl UiElement for UiImageDraw {
             _ => {}
         }
 
-        ctx.cache().mesh_unit_square().draw_all(&mut pass);
+        ctx.cache()
+            .mesh_unit_square()
+            .draw_all(&mut pass, ctx.gpu_ctx().stats);
     }
 }
@@ -13,7 +13,7 @@ use nalgebra::Matrix4;
 use std::collections::HashMap;
 use std::mem;
 use std::sync::RwLock;
-use web_time::Instant;
+use web_time::Duration;
 use wgpu::{BindGroup, BufferDescriptor, BufferUsages, RenderPass};
 use winit::dpi::PhysicalSize;
 
@@ -31,7 +31,7 @@ pub struct UiDrawContext<'a, 'b, 'c, 'd, 'e> {
     cache: &'c AssetCache,
     cache_id: CacheId,
     viewport_size: PhysicalSize<u32>,
-    start_time: Instant,
+    time: Duration,
     state: &'d State,
 }
 
@@ -51,7 +51,7 @@ impl<'a, 'b, 'c, 'd, 'e> UiDrawContext<'a, 'b, 'c, 'd, 'e> {
             pub fn viewport_size(&self) -> PhysicalSize<u32>;
 
             #[field]
-            pub fn start_time(&self) -> Instant;
+            pub fn time(&self) -> Duration;
 
             #[field]
             pub fn state(&self) -> &State;
@@ -85,7 +85,7 @@ impl<'a, 'b, 'c, 'd, 'e> UiDrawContext<'a, 'b, 'c, 'd, 'e> {
             let model = ModelUniform::empty();
             let uniform = ShaderUniform::<MeshUniformIndex>::builder(&model_bgl)
                 .with_buffer_data(&model)
-                .with_buffer_data(&BoneData::DUMMY)
+                .with_storage_buffer_data(&BoneData::RIGID)
                 .build(&self.state.device);
 
             let glyph_vbo = self.state.device.create_buffer(&BufferDescriptor {
@@ -105,7 +105,7 @@ impl<'a, 'b, 'c, 'd, 'e> UiDrawContext<'a, 'b, 'c, 'd, 'e> {
             let model = ModelUniform::empty();
             let uniform = ShaderUniform::<MeshUniformIndex>::builder(&model_bgl)
                 .with_buffer_data(&model)
-                .with_buffer_data(&BoneData::DUMMY)
+                .with_storage_buffer_data(&BoneData::RIGID)
                 .build(&self.state.device);
 
             let mesh_data = ModelUniform {
@@ -136,7 +136,7 @@ impl StrobeRenderer {
         cache: &AssetCache,
         state: &State,
         target: RenderTargetId,
-        start_time: Instant,
+        time: Duration,
         viewport_size: PhysicalSize<u32>,
     ) {
         let draw_map = mem::take(&mut self.draws);
@@ -152,7 +152,7 @@ impl StrobeRenderer {
             cache,
             cache_id: 0,
             viewport_size,
-            start_time,
+            time,
             state,
         };
 
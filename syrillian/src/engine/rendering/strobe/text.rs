@@ -1,7 +1,10 @@
 use crate::assets::HFont;
 use crate::core::{ModelUniform, ObjectHash};
-use crate::rendering::glyph::{GlyphRenderData, generate_glyph_geometry_stream};
+use crate::rendering::glyph::{
+    GlyphRenderData, generate_glyph_geometry_stream, generate_rich_glyph_geometry_stream,
+};
 use crate::rendering::proxies::{MeshUniformIndex, TextImmediates};
+use crate::rendering::rich_text::StyledRun;
 use crate::rendering::{RenderPassType, hash_to_rgba};
 use crate::strobe::UiDrawContext;
 use crate::strobe::ui_element::UiElement;
@@ -18,7 +21,13 @@ pub enum TextAlignment {
     Center,
 }
 
+/// A line of text drawn on top of everything else, see
+/// [`UiContext::text`](crate::rendering::UiContext::text).
+///
+/// Created with [`new`](Self::new), so new fields don't break existing code. Everything else
+/// can be set on the fields afterward.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct UiTextDraw {
     pub draw_order: u32,
     pub font: HFont,
@@ -29,9 +38,36 @@ pub struct UiTextDraw {
     pub color: Vector3<f32>,
     pub rainbow: bool,
     pub text: String,
+    /// Replaces `text` with styled parts if it's set
+    pub styled_runs: Option<Vec<StyledRun>>,
     pub object_hash: ObjectHash,
 }
 
+impl UiTextDraw {
+    /// Creates a white, left aligned text at the top left corner. `object_hash` is reported when
+    /// the text is picked.
+    pub fn new(
+        text: impl Into<String>,
+        font: HFont,
+        size_em: f32,
+        object_hash: ObjectHash,
+    ) -> Self {
+        UiTextDraw {
+            draw_order: 0,
+            font,
+            alignment: TextAlignment::Left,
+            letter_spacing_em: 0.0,
+            position: Vector2::zeros(),
+            size_em,
+            color: Vector3::new(1.0, 1.0, 1.0),
+            rainbow: false,
+            text: text.into(),
+            styled_runs: None,
+            object_hash,
+        }
+    }
+}
+
 impl UiElement for UiTextDraw {
     fn draw_order(&self) -> u32 {
         self.draw_order
@@ -53,13 +89,22 @@ impl UiElement for UiTextDraw {
         font.request_glyphs(self.text.chars());
         let _ = font.pump(ctx.cache(), &ctx.state().queue, 10);
 
-        let glyphs: Vec<GlyphRenderData> = generate_glyph_geometry_stream(
-            &self.text,
-            &font,
-            self.alignment,
-            1.0,
-            self.letter_spacing_em,
-        );
+        let glyphs: Vec<GlyphRenderData> = match &self.styled_runs {
+            Some(runs) => generate_rich_glyph_geometry_stream(
+                runs,
+                &font,
+                self.alignment,
+                1.0,
+                self.letter_spacing_em,
+            ),
+            None => generate_glyph_geometry_stream(
+                &self.text,
+                &font,
+                self.alignment,
+                1.0,
+                self.letter_spacing_em,
+            ),
+        };
 
         if glyphs.is_empty() {
             return;
@@ -93,13 +138,13 @@ impl UiElement for UiTextDraw {
         let mut pc = TextImmediates {
             position: self.position,
             em_scale: self.size_em,
-            msdf_range_px: 4.0,
+            msdf_range_px: font.msdf_range(),
             color: self.color,
             padding: 0,
         };
 
         if self.rainbow {
-            let time = ctx.start_time().elapsed().as_secs_f32() * 100.0;
+            let time = ctx.time().as_secs_f32() * 100.0;
             pc.color = hsv_to_rgb(time % 360.0, 1.0, 1.0);
         }
 
@@ -122,6 +167,7 @@ impl UiElement for UiTextDraw {
 
         pass.set_immediates(0, bytemuck::bytes_of(&pc));
         pass.set_vertex_buffer(0, cached_text.glyph_vbo.slice(..));
+        ctx.gpu_ctx().stats.record_draw(glyphs.len() as u32 * 6, 1);
         pass.draw(0..glyphs.len() as u32 * 6, 0..1);
     }
 }
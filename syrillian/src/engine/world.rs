@@ -4,38 +4,62 @@
 //! It maintains the scene graph, input state and physics simulation and
 //! offers utilities such as methods to create, find and remove game objects.
 
-use crate::assets::{BGL, Material, Mesh, Shader, Sound, Store, Texture};
+use crate::assets::{BGL, ComputeShader, HSound, Material, Mesh, Shader, Sound, Store, Texture};
 use crate::audio::AudioScene;
-use crate::components::{CRef, CWeak, CameraComponent, Component};
+use crate::components::parallel::{WorldCommand, run_parallel_update};
+use crate::components::{
+    Billboard, CRef, CWeak, CameraComponent, Component, InspectValue, Inspector, InspectorSection,
+    ParallelContext, ParallelUpdate, RigidBodyComponent, TypedComponentId,
+};
 use crate::core::component_storage::ComponentStorage;
-use crate::core::{EventType, GameObject, GameObjectId, GameObjectRef, ObjectHash, Transform};
+use crate::core::snapshot::{BodyState, TransformState};
+use crate::core::{
+    BoundingSphere, EventBus, EventType, Frustum, GameObject, GameObjectId, GameObjectRef,
+    ObjectHash, ObjectTreeNode, SpatialGrid, Transform, TransformSummary, UuidError, WorldCommands,
+    WorldSnapshot, spheres_overlap,
+};
 use crate::engine::assets::AssetStore;
 use crate::engine::prefabs::prefab::Prefab;
 use crate::game_thread::GameAppEvent;
-use crate::input::InputManager;
-use crate::physics::PhysicsManager;
-use crate::prefabs::CameraPrefab;
-use crate::rendering::message::RenderMsg;
+use crate::input::{Clipboard, InputManager};
+use crate::physics::{PhysicsManager, RayHit, SweepHit};
+use crate::prefabs::{CameraPrefab, ObjectPool};
+use crate::rendering::message::{CameraView, RenderFeedback, RenderMsg, TextureCameraView};
 use crate::rendering::picking::PickRequest;
 use crate::rendering::picking::PickResult;
 use crate::rendering::strobe::StrobeFrame;
-use crate::rendering::{CPUDrawCtx, UiContext};
+use crate::rendering::{
+    CPUDrawCtx, ComputeJob, ComputeReadback, DebugSettings, RenderStats, UiContext,
+};
+use crate::utils::Rng;
 use crate::windowing::RenderTargetId;
-use nalgebra::Matrix4;
+use nalgebra::{Isometry3, Matrix4, Translation3, UnitQuaternion, Vector2, Vector3};
+use rapier3d::prelude::{Ball, Cuboid, QueryFilter, Ray, Shape};
 use slotmap::{Key, SlotMap};
+use std::any::TypeId;
 use std::cell::Cell;
 use std::collections::{HashMap, HashSet};
 use std::mem;
 use std::mem::swap;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tracing::info;
+use tracing::{info, warn};
+use uuid::Uuid;
 use web_time::{Duration, Instant};
 
 use crossbeam_channel::unbounded;
 use crossbeam_channel::{Receiver, Sender};
-use winit::dpi::PhysicalSize;
+use image::RgbaImage;
+use winit::dpi::{LogicalSize, PhysicalSize};
 use winit::event::MouseButton;
+use winit::window::{CursorIcon, Icon};
+
+/// The longest delta time a frame reports by default
+pub const DEFAULT_MAX_DELTA: Duration = Duration::from_millis(100);
+/// How often watched shader files are checked for changes by default
+pub const DEFAULT_SHADER_WATCH_INTERVAL: Duration = Duration::from_millis(250);
+/// Weight of the newest frame in the smoothed delta time
+const DELTA_SMOOTHING: f32 = 0.1;
 
 thread_local! {
     static CURRENT_WORLD: Cell<*mut World> = const { Cell::new(std::ptr::null_mut()) };
@@ -70,15 +94,33 @@ impl Drop for WorldBinding {
 #[derive(Clone)]
 pub struct RenderTargets {
     pub active_camera: CWeak<CameraComponent>,
+    /// Additional cameras drawing into this target next to the active camera
+    pub cameras: Vec<CWeak<CameraComponent>>,
     pub size: PhysicalSize<u32>,
+    /// The ratio of physical to logical pixels of the window
+    pub scale_factor: f64,
+    views: Vec<CameraView>,
+}
+
+impl RenderTargets {
+    fn new(active_camera: CWeak<CameraComponent>, size: PhysicalSize<u32>) -> Self {
+        RenderTargets {
+            active_camera,
+            cameras: Vec::new(),
+            size,
+            scale_factor: 1.0,
+            views: Vec::new(),
+        }
+    }
 }
 
 #[derive(Clone)]
 pub struct WorldChannels {
     pub render_tx: Sender<RenderMsg>,
     pub game_event_tx: Sender<GameAppEvent>,
-    pub pick_result_rx: Receiver<PickResult>,
+    pub feedback_rx: Receiver<RenderFeedback>,
     targets: HashMap<RenderTargetId, RenderTargets>,
+    texture_views: Vec<TextureCameraView>,
     next_target_id: u64,
 }
 
@@ -86,34 +128,55 @@ impl WorldChannels {
     pub fn new(
         render_tx: Sender<RenderMsg>,
         game_event_tx: Sender<GameAppEvent>,
-        pick_result_rx: Receiver<PickResult>,
+        feedback_rx: Receiver<RenderFeedback>,
     ) -> Self {
         let mut targets = HashMap::new();
         targets.insert(
             RenderTargetId::PRIMARY,
-            RenderTargets {
-                active_camera: CWeak::null(),
-                size: PhysicalSize::new(0, 0),
-            },
+            RenderTargets::new(CWeak::null(), PhysicalSize::new(0, 0)),
         );
 
         Self {
             render_tx,
             game_event_tx,
-            pick_result_rx,
+            feedback_rx,
             targets,
+            texture_views: Vec::new(),
             next_target_id: RenderTargetId::PRIMARY.get() + 1,
         }
     }
 
     pub fn set_active_camera(&mut self, target: RenderTargetId, camera: CWeak<CameraComponent>) {
-        let entry = self.targets.entry(target).or_insert(RenderTargets {
-            active_camera: CWeak::null(),
-            size: PhysicalSize::new(0, 0),
-        });
+        let entry = self
+            .targets
+            .entry(target)
+            .or_insert_with(|| RenderTargets::new(CWeak::null(), PhysicalSize::new(0, 0)));
+        entry.cameras.retain(|c| *c != camera);
         entry.active_camera = camera;
     }
 
+    pub fn add_camera(&mut self, target: RenderTargetId, camera: CWeak<CameraComponent>) {
+        for (id, entry) in self.targets.iter_mut() {
+            if *id != target {
+                entry.cameras.retain(|c| *c != camera);
+            }
+        }
+
+        let entry = self
+            .targets
+            .entry(target)
+            .or_insert_with(|| RenderTargets::new(CWeak::null(), PhysicalSize::new(0, 0)));
+        if entry.active_camera != camera && !entry.cameras.contains(&camera) {
+            entry.cameras.push(camera);
+        }
+    }
+
+    pub fn remove_camera(&mut self, camera: CWeak<CameraComponent>) {
+        for entry in self.targets.values_mut() {
+            entry.cameras.retain(|c| *c != camera);
+        }
+    }
+
     pub fn active_camera_for(&self, target: RenderTargetId) -> CWeak<CameraComponent> {
         self.targets
             .get(&target)
@@ -122,31 +185,34 @@ impl WorldChannels {
 
     pub fn set_viewport_size(&mut self, target: RenderTargetId, size: PhysicalSize<u32>) {
         let size = PhysicalSize::new(size.width.max(1), size.height.max(1));
-        let entry = self.targets.entry(target).or_insert(RenderTargets {
-            active_camera: CWeak::null(),
-            size,
-        });
+        let entry = self
+            .targets
+            .entry(target)
+            .or_insert_with(|| RenderTargets::new(CWeak::null(), size));
         entry.size = size;
     }
 
+    pub fn set_scale_factor(&mut self, target: RenderTargetId, scale_factor: f64) {
+        if let Some(entry) = self.targets.get_mut(&target) {
+            entry.scale_factor = scale_factor;
+        }
+    }
+
     pub fn add_window(
         &mut self,
         active_camera: CWeak<CameraComponent>,
         size: PhysicalSize<u32>,
     ) -> RenderTargetId {
         let target_id = RenderTargetId(self.next_target_id);
-        self.targets.insert(
-            target_id,
-            RenderTargets {
-                active_camera,
-                size,
-            },
-        );
+        self.targets
+            .insert(target_id, RenderTargets::new(active_camera, size));
         self.next_target_id += 1;
         target_id
     }
 }
 
+type ParallelUpdateFn = fn(&mut World, &ParallelContext);
+
 /// Central structure representing the running scene.
 ///
 /// The world keeps track of all [`GameObject`](GameObject)
@@ -169,31 +235,71 @@ pub struct World {
     click_listeners: HashSet<GameObjectId>,
     /// Allocated hashes to keep them unique per object
     object_hashes: HashSet<ObjectHash>,
+    /// Objects with a persistent id, see [`GameObject::set_uuid`]
+    objects_by_uuid: HashMap<Uuid, GameObjectId>,
     /// The currently active camera used for rendering
     main_active_camera: CWeak<CameraComponent>,
     /// Physics simulation system
     pub physics: PhysicsManager,
     /// Input management system
     pub input: InputManager,
+    /// Text copy and paste, opened on first use
+    clipboard: Clipboard,
     /// Asset storage containing meshes, textures, materials, etc.
     pub assets: Arc<AssetStore>,
     /// Spatial audio
     pub audio: AudioScene,
+    /// Typed events sent between components
+    pub events: EventBus,
+    /// Debug drawing of this world
+    pub debug: DebugSettings,
+    /// Structural changes queued during updates
+    commands: WorldCommands,
+    /// Optional index over object bounds for proximity and view queries
+    spatial: Option<SpatialGrid>,
+    /// Component types that get a parallel update, in registration order
+    parallel_updates: Vec<(TypeId, ParallelUpdateFn)>,
+    /// Reused between update phases to hold the component ids in update order
+    update_queue: Vec<TypedComponentId>,
+    /// Shared random number generator, seeded from the time unless seeded explicitly
+    rng: Rng,
 
     /// Time when the world was created
     start_time: Instant,
-    /// Time elapsed since the last frame
+    /// Time elapsed since the last frame, clamped to `max_delta`
     delta_time: Duration,
+    /// Time elapsed since the last frame, as measured
+    raw_delta_time: Duration,
+    /// Exponential moving average of the clamped delta time
+    smoothed_delta_time: Duration,
+    /// Upper bound for the delta time of a single frame and the physics catch-up
+    max_delta: Duration,
     /// Time when the last frame started
     last_frame_time: Instant,
+    /// Time between checks of the watched shader files
+    shader_watch_interval: Duration,
+    /// Time when the watched shader files were last checked
+    last_shader_watch: Instant,
+    /// Time that [`World::tick`] passed on, which hasn't filled a physics step yet
+    tick_physics_lag: Duration,
+    /// Sum of the deltas [`World::tick`] passed on. Replaces the wall clock once the world is
+    /// ticked.
+    tick_time: Option<Duration>,
+    /// Set while the fixed update phases run, so [`World::delta_time`] reports the fixed step
+    in_fixed_step: bool,
     /// Sequence id for picking requests
     next_pick_request_id: u64,
 
     /// Flag indicating whether a shutdown has been requested
     requested_shutdown: bool,
     pub(crate) channels: WorldChannels,
+    /// What the renderer reported about its last finished frame
+    render_stats: RenderStats,
     thread_binding: Option<WorldBinding>,
     pub strobe: StrobeFrame,
+    /// The object whose image is on top under the mouse, once a
+    /// [`UiButton`](crate::components::UiButton) looked for it this frame
+    pub(crate) ui_hover: Option<Option<GameObjectId>>,
 }
 
 impl World {
@@ -207,21 +313,40 @@ impl World {
             pending_deletions: HashSet::new(),
             click_listeners: HashSet::new(),
             object_hashes: HashSet::new(),
+            objects_by_uuid: HashMap::new(),
             main_active_camera: CWeak::null(),
             physics: PhysicsManager::default(),
             input: InputManager::new(channels.game_event_tx.clone()),
+            clipboard: Clipboard::default(),
             assets,
             audio: AudioScene::default(),
+            events: EventBus::default(),
+            debug: DebugSettings::default(),
+            commands: WorldCommands::default(),
+            spatial: None,
+            parallel_updates: Vec::new(),
+            update_queue: Vec::new(),
+            rng: Rng::default(),
 
             start_time: Instant::now(),
             delta_time: Duration::default(),
+            raw_delta_time: Duration::default(),
+            smoothed_delta_time: Duration::default(),
+            max_delta: DEFAULT_MAX_DELTA,
             last_frame_time: Instant::now(),
+            shader_watch_interval: DEFAULT_SHADER_WATCH_INTERVAL,
+            last_shader_watch: Instant::now(),
+            tick_physics_lag: Duration::ZERO,
+            tick_time: None,
+            in_fixed_step: false,
             next_pick_request_id: 0,
 
             requested_shutdown: false,
             channels,
+            render_stats: RenderStats::default(),
             thread_binding: None,
             strobe: StrobeFrame::default(),
+            ui_hover: None,
         })
     }
 
@@ -230,9 +355,9 @@ impl World {
         assets: Arc<AssetStore>,
         render_tx: Sender<RenderMsg>,
         game_event_tx: Sender<GameAppEvent>,
-        pick_result_rx: Receiver<PickResult>,
+        feedback_rx: Receiver<RenderFeedback>,
     ) -> Box<World> {
-        let channels = WorldChannels::new(render_tx, game_event_tx, pick_result_rx);
+        let channels = WorldChannels::new(render_tx, game_event_tx, feedback_rx);
         World::new_with_channels(assets, channels)
     }
 
@@ -248,14 +373,14 @@ impl World {
         Box<World>,
         Receiver<RenderMsg>,
         Receiver<GameAppEvent>,
-        Sender<PickResult>,
+        Sender<RenderFeedback>,
     ) {
         let (tx1, rx1) = unbounded();
         let (tx2, rx2) = unbounded();
-        let (pick_tx, pick_rx) = unbounded();
+        let (feedback_tx, feedback_rx) = unbounded();
         let store = AssetStore::new();
-        let world = World::new(store, tx1, tx2, pick_rx);
-        (world, rx1, rx2, pick_tx)
+        let world = World::new(store, tx1, tx2, feedback_rx);
+        (world, rx1, rx2, feedback_tx)
     }
 
     /// Returns a mutable reference to the global [`World`] instance.
@@ -363,6 +488,12 @@ impl World {
         if !self.objects.contains_key(obj) {
             return;
         }
+        if let Some(spatial) = &mut self.spatial {
+            spatial.remove(obj);
+        }
+        if let Some(uuid) = self.objects[obj].uuid {
+            self.objects_by_uuid.remove(&uuid);
+        }
 
         if !self.pending_deletions.insert(obj) {
             return;
@@ -430,6 +561,8 @@ impl World {
             id: GameObjectId::null(),
             name: name.into(),
             alive: Cell::new(true),
+            active: true,
+            active_in_hierarchy: true,
             children: vec![],
             parent: None,
             owning_world: self,
@@ -438,6 +571,7 @@ impl World {
             custom_properties: HashMap::new(),
             event_mask: Cell::new(EventType::empty()),
             hash: 0,
+            uuid: None,
         };
 
         let id = self.objects.insert(Box::new(obj));
@@ -473,7 +607,7 @@ impl World {
     }
 
     pub fn set_active_camera(&mut self, mut camera: CRef<CameraComponent>) {
-        camera.set_render_target(RenderTargetId::PRIMARY);
+        camera.set_render_target_id(RenderTargetId::PRIMARY);
         self.main_active_camera = camera.downgrade();
         self.channels
             .set_active_camera(RenderTargetId::PRIMARY, self.main_active_camera);
@@ -484,7 +618,7 @@ impl World {
         target: RenderTargetId,
         mut camera: CRef<CameraComponent>,
     ) {
-        camera.set_render_target(target);
+        camera.set_render_target_id(target);
         self.channels.set_active_camera(target, camera.downgrade());
     }
 
@@ -499,12 +633,85 @@ impl World {
         }
     }
 
+    /// Adds a camera that renders into its render target next to the active camera.
+    ///
+    /// Every camera draws into its own [`ViewportRect`](crate::components::ViewportRect), in
+    /// ascending order of [`CameraComponent::depth`]. This is how split-screen is set up.
+    pub fn add_active_camera(&mut self, camera: CRef<CameraComponent>) {
+        let target = camera.render_target();
+        self.add_active_camera_for_target(target, camera);
+    }
+
+    pub fn add_active_camera_for_target(
+        &mut self,
+        target: RenderTargetId,
+        mut camera: CRef<CameraComponent>,
+    ) {
+        camera.set_render_target_id(target);
+        if let Some(size) = self.viewport_size(target) {
+            camera.resize(size.width as f32, size.height as f32);
+        }
+        self.channels.add_camera(target, camera.downgrade());
+    }
+
+    /// Stops a camera that was added with [`World::add_active_camera`] from rendering
+    pub fn remove_active_camera(&mut self, camera: CRef<CameraComponent>) {
+        self.channels.remove_camera(camera.downgrade());
+    }
+
+    /// Returns all cameras rendering into the target, sorted by the order they're drawn in
+    pub fn active_cameras_for(&self, target: RenderTargetId) -> Vec<CRef<CameraComponent>> {
+        let mut cameras: Vec<CRef<CameraComponent>> = self
+            .active_camera_for_target(target)
+            .into_iter()
+            .chain(
+                self.channels
+                    .targets
+                    .get(&target)
+                    .into_iter()
+                    .flat_map(|t| t.cameras.iter().copied()),
+            )
+            .filter_map(|c| c.upgrade(self))
+            .filter(|c| c.render_texture().is_none())
+            .collect();
+
+        cameras.sort_by_key(|c| c.depth());
+        cameras
+    }
+
+    /// Returns all cameras rendering into a texture, sorted by the order they're drawn in
+    pub fn texture_cameras(&self) -> Vec<CRef<CameraComponent>> {
+        let Some(cameras) = self.components.values_of_type::<CameraComponent>() else {
+            return Vec::new();
+        };
+
+        let mut cameras: Vec<CRef<CameraComponent>> = cameras
+            .filter(|c| c.render_texture().is_some())
+            .cloned()
+            .collect();
+
+        cameras.sort_by_key(|c| c.depth());
+        cameras
+    }
+
+    /// Returns the topmost camera whose viewport contains the given position on the target.
+    ///
+    /// This is the camera that cursor rays and picking refer to.
+    pub fn camera_at(
+        &self,
+        target: RenderTargetId,
+        x: f32,
+        y: f32,
+    ) -> Option<CRef<CameraComponent>> {
+        self.active_cameras_for(target)
+            .into_iter()
+            .rev()
+            .find(|c| c.viewport_contains(x, y))
+    }
+
     pub fn set_viewport_size(&mut self, target: RenderTargetId, size: PhysicalSize<u32>) {
         self.channels.set_viewport_size(target, size);
-        if let Some(mut cam) = self
-            .active_camera_for_target(target)
-            .and_then(|c| c.upgrade(self))
-        {
+        for mut cam in self.active_cameras_for(target) {
             cam.resize(size.width as f32, size.height as f32);
         }
     }
@@ -513,6 +720,41 @@ impl World {
         self.channels.targets.get(&target).map(|t| t.size)
     }
 
+    pub fn set_scale_factor(&mut self, target: RenderTargetId, scale_factor: f64) {
+        self.channels.set_scale_factor(target, scale_factor);
+    }
+
+    /// The ratio of physical to logical pixels of a window, e.g. 2.0 on most high DPI screens
+    pub fn scale_factor_for(&self, target: RenderTargetId) -> Option<f64> {
+        self.channels.targets.get(&target).map(|t| t.scale_factor)
+    }
+
+    /// The size of the primary window in physical pixels, which is also its framebuffer size
+    pub fn window_size(&self) -> PhysicalSize<u32> {
+        self.viewport_size(RenderTargetId::PRIMARY)
+            .unwrap_or(PhysicalSize::new(0, 0))
+    }
+
+    /// The ratio of physical to logical pixels of the primary window
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor_for(RenderTargetId::PRIMARY)
+            .unwrap_or(1.0)
+    }
+
+    /// The size of the primary window in logical pixels, which doesn't depend on the screen DPI
+    pub fn logical_size(&self) -> LogicalSize<f64> {
+        self.window_size().to_logical(self.scale_factor())
+    }
+
+    /// Width divided by height of the primary window, or 1 while it has no size yet
+    pub fn aspect_ratio(&self) -> f32 {
+        let size = self.window_size();
+        if size.width == 0 || size.height == 0 {
+            return 1.0;
+        }
+        size.width as f32 / size.height as f32
+    }
+
     pub fn create_window(&mut self) -> RenderTargetId {
         self.create_window_with_size(PhysicalSize::new(800, 600))
     }
@@ -537,28 +779,37 @@ impl World {
         }
     }
 
-    fn process_pick_results(&mut self) {
-        while let Ok(result) = self.channels.pick_result_rx.try_recv() {
-            let Some(obj_hash) = result.hash else {
-                continue;
-            };
+    fn process_render_feedback(&mut self) {
+        while let Ok(feedback) = self.channels.feedback_rx.try_recv() {
+            match feedback {
+                RenderFeedback::Pick(result) => self.process_pick_result(result),
+                RenderFeedback::Stats(stats) => self.render_stats = stats,
+            }
+        }
+    }
 
-            let Some((obj, _)) = self
-                .objects
-                .iter()
-                .find(|(_, o)| o.object_hash() == obj_hash)
-            else {
-                continue;
-            };
+    fn process_pick_result(&mut self, result: PickResult) {
+        let Some(obj_hash) = result.hash else {
+            return;
+        };
 
-            if !obj.is_alive() || !obj.is_notified_for(EventType::CLICK) {
-                continue;
-            }
+        let Some((obj, _)) = self
+            .objects
+            .iter()
+            .find(|(_, o)| o.object_hash() == obj_hash)
+        else {
+            return;
+        };
+
+        if !obj.is_alive() || !obj.is_notified_for(EventType::CLICK) {
+            return;
+        }
 
-            let components = obj.components.clone();
-            let world = self as *mut World;
+        let components = obj.components.clone();
+        let world = self as *mut World;
 
-            for mut comp in components {
+        for mut comp in components {
+            if comp.is_enabled() {
                 unsafe { comp.on_click(&mut *world) }
             }
         }
@@ -607,6 +858,17 @@ impl World {
         let mut obj = *obj.as_ref();
         self.children.push(obj);
         obj.parent = None;
+        obj.propagate_active(true);
+        obj.transform.set_dirty();
+    }
+
+    /// Plays a sound once at a world position, independent of any object. Overlapping sounds
+    /// mix instead of cutting each other off. Returns false if the sound couldn't be played.
+    pub fn play_sound_at(&mut self, sound: HSound, position: Vector3<f32>) -> bool {
+        let Some(sound) = self.assets.sounds.try_get(sound) else {
+            return false;
+        };
+        self.audio.play_one_shot(&sound, position)
     }
 
     /// Spawns a game object from a prefab
@@ -614,29 +876,209 @@ impl World {
         prefab.spawn(self)
     }
 
-    /// Executes a component function on all components of all game objects
+    /// Copies an object with its components and children, and places the copy next to it under
+    /// the same parent. Returns the copy.
+    ///
+    /// Transforms, names, properties, event registrations and active flags are copied as they
+    /// are. Components are copied through [`Component::duplicate`] with their enabled flag, and
+    /// components that don't implement it are left out with a warning. Components connecting to
+    /// other objects, like [`RopeComponent`](crate::components::RopeComponent), keep pointing at
+    /// the original target, even if that target was duplicated as well. Components that link to
+    /// their own children, like the [`AnimationComponent`](crate::components::AnimationComponent),
+    /// do so once the [queued commands](World::commands) run.
+    pub fn duplicate(&mut self, obj: GameObjectId) -> GameObjectId {
+        let parent = obj.parent;
+        let at_root = parent.is_none() && self.children.contains(&obj);
+        self.duplicate_under(obj, parent, at_root)
+    }
+
+    fn duplicate_under(
+        &mut self,
+        obj: GameObjectId,
+        parent: Option<GameObjectId>,
+        at_root: bool,
+    ) -> GameObjectId {
+        let mut copy = self.new_object(obj.name.clone());
+        copy.transform
+            .set_local_position_vec(*obj.transform.local_position());
+        copy.transform
+            .set_local_rotation(*obj.transform.local_rotation());
+        copy.transform
+            .set_nonuniform_local_scale(*obj.transform.local_scale());
+
+        // placed first, so components see the final global transform when they're added
+        match parent {
+            Some(mut parent) => parent.add_child(copy),
+            None if at_root => self.add_child(copy),
+            None => {}
+        }
+
+        copy.add_properties(obj.properties().clone());
+        copy.notify_for(self, obj.event_mask());
+
+        for comp in obj.components.clone() {
+            let added = copy.components.len();
+            comp.duplicate(copy, self);
+            if comp.is_enabled() {
+                continue;
+            }
+            for dup in copy.components[added..].to_vec() {
+                dup.ctx.enabled.set(false);
+                self.set_component_enabled(dup, false);
+            }
+        }
+        for child in obj.children.clone() {
+            if child.exists() {
+                self.duplicate_under(child, Some(copy), false);
+            }
+        }
+
+        copy.set_active(obj.is_active());
+        copy
+    }
+
+    /// Creates an [`ObjectPool`] with `capacity` inactive objects spawned from the prefab
+    pub fn pool<P: Prefab>(&mut self, prefab: P, capacity: usize) -> ObjectPool<P> {
+        ObjectPool::new(self, prefab, capacity)
+    }
+
+    /// Executes a component function on all components of active game objects
     pub(crate) fn execute_component_func<F>(&mut self, func: F)
     where
         F: Fn(&mut dyn Component, &mut World),
     {
+        // components can be added and removed while the phase runs, so it goes over a copy
+        let mut queue = std::mem::take(&mut self.update_queue);
+        self.components.ordered_ids_into(&mut queue);
+
         let world = unsafe { &mut *(self as *mut World) };
-        self.components.values_mut().for_each(|c| func(c, world))
+        for &tid in &queue {
+            // skips components removed by one that ran earlier
+            let Some(mut comp) = self.components.get_dyn(tid) else {
+                continue;
+            };
+            if world.is_component_active(&comp) {
+                func(&mut *comp, world);
+            }
+        }
+
+        self.update_queue = queue;
+    }
+
+    fn is_object_active(&self, obj: GameObjectId) -> bool {
+        self.objects
+            .get(obj)
+            .is_none_or(|o| o.active_in_hierarchy())
+    }
+
+    fn is_component_active<C: Component + ?Sized>(&self, comp: &CRef<C>) -> bool {
+        comp.is_enabled() && self.is_object_active(comp.parent())
+    }
+
+    /// Applies a changed component enabled flag. Components of inactive objects are already
+    /// inactive, so nothing changes for them until the object is enabled.
+    pub(crate) fn set_component_enabled(&mut self, comp: CRef<dyn Component>, enabled: bool) {
+        if self.is_object_active(comp.parent()) {
+            self.set_component_active(comp, enabled);
+        }
+    }
+
+    /// Registers or drops the render and light proxies of a component when its object is
+    /// enabled or disabled, and lets the component react to it.
+    pub(crate) fn set_component_active(&mut self, mut comp: CRef<dyn Component>, active: bool) {
+        let tid = comp.typed_id();
+        let (pending, opposite) = if active {
+            (&mut self.components.removed, &mut self.components.fresh)
+        } else {
+            (&mut self.components.fresh, &mut self.components.removed)
+        };
+
+        // a proxy that wasn't synced to the render thread yet doesn't need to be touched
+        match pending.iter().position(|c| *c == tid) {
+            Some(pos) => {
+                pending.remove(pos);
+            }
+            None => opposite.push(tid),
+        }
+
+        comp.on_active_changed(self, active);
     }
 
     /// Runs possible physics update if the timestep time has elapsed yet
+    ///
+    /// After a stall longer than the [max delta](World::set_max_delta), the skipped time is
+    /// dropped instead of being simulated all at once.
     pub fn fixed_update(&mut self) {
-        while self.physics.last_update.elapsed() >= self.physics.timestep {
-            self.execute_component_func(Component::pre_fixed_update);
+        let behind = self.physics.last_update.elapsed();
+        if behind > self.max_delta {
+            self.physics.last_update += behind - self.max_delta;
+        }
 
+        while self.physics.last_update.elapsed() >= self.physics.timestep {
             self.physics.last_update += self.physics.timestep;
-            self.physics.step();
-
-            self.execute_component_func(Component::fixed_update);
+            self.fixed_step();
         }
 
         let rem = self.physics.last_update.elapsed();
+        self.update_physics_alpha(rem);
+    }
+
+    /// Advances the world by exactly `delta`, without a window, renderer or real clock.
+    ///
+    /// Runs one frame like the App runtime would: the physics steps that fit into the time
+    /// since the last step, [`update`](World::update), [`post_update`](World::post_update)
+    /// and the input reset of [`next_frame`](World::next_frame). [`delta_time`](World::delta_time) reports
+    /// `delta` (clamped to the [max delta](World::set_max_delta)) during the frame, and
+    /// [`time`](World::time) is the sum of all ticks, so the same ticks always produce the
+    /// same result. Meant for testing components, e.g. with a
+    /// world from [`World::fresh`].
+    ///
+    /// Physics, components, input, events and audio bookkeeping are active. Nothing is drawn:
+    /// render messages are still sent, but there is no renderer receiving them, so picking
+    /// never resolves either. [`AppState`](crate::AppState) hooks aren't called.
+    ///
+    /// Don't mix this with [`fixed_update`](World::fixed_update) on the same world, which
+    /// measures the real time since the last step.
+    pub fn tick(&mut self, delta: Duration) {
+        self.tick_fixed_update(delta);
+        self.update();
+        self.post_update();
+        self.finish_tick();
+    }
+
+    /// Starts a [tick](World::tick) of `delta`, and runs the physics steps that fit into it
+    pub(crate) fn tick_fixed_update(&mut self, delta: Duration) {
+        self.set_frame_delta(delta);
+        *self.tick_time.get_or_insert_default() += self.delta_time;
+
+        let mut behind = self.tick_physics_lag + self.delta_time;
+        while behind >= self.physics.timestep {
+            behind -= self.physics.timestep;
+            self.fixed_step();
+        }
+        self.tick_physics_lag = behind;
+        self.update_physics_alpha(behind);
+    }
+
+    /// Ends a [tick](World::tick), like [`next_frame`](World::next_frame) without measuring the
+    /// delta time
+    pub(crate) fn finish_tick(&mut self) {
+        self.finish_frame();
+        self.last_frame_time = Instant::now();
+    }
+
+    fn fixed_step(&mut self) {
+        self.in_fixed_step = true;
+        self.execute_component_func(Component::pre_fixed_update);
+        self.physics.step();
+        self.execute_component_func(Component::fixed_update);
+        self.apply_commands();
+        self.in_fixed_step = false;
+    }
+
+    fn update_physics_alpha(&mut self, remainder: Duration) {
         self.physics.alpha =
-            (rem.as_secs_f32() / self.physics.timestep.as_secs_f32()).clamp(0.0, 1.0);
+            (remainder.as_secs_f32() / self.physics.timestep.as_secs_f32()).clamp(0.0, 1.0);
     }
 
     /// Updates all game objects and their components
@@ -646,10 +1088,80 @@ impl World {
     /// If you're using the App runtime, this will be handled for you. Only call this function
     /// if you are trying to use a detached world context.
     pub fn update(&mut self) {
-        self.process_pick_results();
+        self.events.advance_frame();
+        self.ui_hover = None;
+        self.reload_watched_shaders();
+        self.audio.cleanup_one_shots();
+        self.process_render_feedback();
         self.maybe_request_pick();
         self.execute_component_func(Component::update);
+        self.run_parallel_updates();
         self.execute_component_func(Component::late_update);
+        self.apply_commands();
+    }
+
+    /// Queues structural changes, like spawning and deleting objects, to run once the current
+    /// update phase is done. Prefer this over the immediate functions inside component updates.
+    pub fn commands(&mut self) -> &mut WorldCommands {
+        &mut self.commands
+    }
+
+    /// Runs all queued [commands](Self::commands) now, including the ones they queue
+    pub fn apply_commands(&mut self) {
+        loop {
+            let commands = self.commands.take();
+            if commands.is_empty() {
+                break;
+            }
+            for command in commands {
+                command(self);
+            }
+        }
+    }
+
+    /// Makes all active `C` components get [`ParallelUpdate::parallel_update`] called every
+    /// frame, between `update` and `late_update`. Registering a type twice does nothing.
+    ///
+    /// See [`components::parallel`](crate::components::parallel) for the threading contract.
+    pub fn register_parallel_update<C: ParallelUpdate>(&mut self) {
+        let type_id = TypeId::of::<C>();
+        if self.parallel_updates.iter().all(|(id, _)| *id != type_id) {
+            self.parallel_updates
+                .push((type_id, run_parallel_update::<C>));
+        }
+    }
+
+    pub fn unregister_parallel_update<C: ParallelUpdate>(&mut self) {
+        let type_id = TypeId::of::<C>();
+        self.parallel_updates.retain(|(id, _)| *id != type_id);
+    }
+
+    fn run_parallel_updates(&mut self) {
+        if self.parallel_updates.is_empty() {
+            return;
+        }
+
+        let (tx, rx) = unbounded::<WorldCommand>();
+        let ctx = ParallelContext::new(self, tx);
+        for (_, run) in self.parallel_updates.clone() {
+            run(self, &ctx);
+        }
+        drop(ctx);
+
+        // applied with everything else queued during this update, after `late_update`
+        for command in rx.try_iter() {
+            self.commands.push(command);
+        }
+    }
+
+    pub(crate) fn active_components_of_type<C: Component>(&self) -> Vec<CRef<C>> {
+        self.components
+            .values_of_type::<C>()
+            .into_iter()
+            .flatten()
+            .filter(|comp| self.is_component_active(comp))
+            .cloned()
+            .collect()
     }
 
     /// Performs late update operations after the main update
@@ -661,19 +1173,23 @@ impl World {
         self.execute_component_func(Component::post_update);
 
         for mut comp in self.components.iter_refs() {
+            if !self.is_component_active(&comp) {
+                continue;
+            }
             let ctx = UiContext::new(comp.ctx.parent.hash, comp.ctx.tid);
             unsafe {
                 comp.on_gui(&mut *world, ctx);
             }
         }
 
+        self.apply_commands();
         self.sync_fresh_components();
         self.sync_removed_components();
 
         let mut command_batch = Vec::with_capacity(self.components.len());
 
         for (_, obj) in self.objects.iter() {
-            if !obj.is_alive() || !obj.transform.is_dirty() {
+            if !obj.is_alive() || !obj.active_in_hierarchy() || !obj.transform.is_dirty() {
                 continue;
             }
             for comp in obj.components.iter() {
@@ -683,22 +1199,43 @@ impl World {
                 ));
             }
         }
-        for (ctid, comp) in self.components.iter_mut() {
-            let ctx = CPUDrawCtx::new(ctid, &mut command_batch);
+        self.push_billboard_transforms(&mut command_batch);
+
+        for mut comp in self.components.iter_refs() {
+            if !self.is_component_active(&comp) {
+                continue;
+            }
+            let ctx = CPUDrawCtx::new(comp.typed_id(), &mut command_batch);
             unsafe {
                 comp.update_proxy(&*world, ctx);
             }
         }
 
-        for target_id in self.channels.targets.keys().copied() {
-            if let Some(mut camera) = self
-                .active_camera_for_target(target_id)
-                .and_then(|c| c.upgrade(self))
+        let target_ids: Vec<RenderTargetId> = self.channels.targets.keys().copied().collect();
+        for target_id in target_ids {
+            let cameras = self.active_cameras_for(target_id);
+            let views: Vec<CameraView> = cameras
+                .iter()
+                .map(|c| CameraView {
+                    camera: c.typed_id(),
+                    viewport: c.viewport(),
+                })
+                .collect();
+
+            if let Some(target) = self.channels.targets.get_mut(&target_id)
+                && target.views != views
             {
+                command_batch.push(RenderMsg::UpdateCameraViews(target_id, views.clone()));
+                target.views = views;
+            }
+
+            for mut camera in cameras {
                 Self::push_camera_updates(target_id, &mut command_batch, &mut camera);
             }
         }
 
+        self.push_texture_camera_updates(&mut command_batch);
+
         self.strobe.sort();
 
         self.channels
@@ -712,13 +1249,91 @@ impl World {
             .send(RenderMsg::UpdateStrobe(mem::take(&mut self.strobe)));
     }
 
+    /// Overrides the render transform of billboarded objects, since they depend on the camera.
+    /// Only sent when the facing changed, or when the real transform was just sent instead.
+    fn push_billboard_transforms(&self, batch: &mut Vec<RenderMsg>) {
+        let Some(billboards) = self.components.values_of_type::<Billboard>() else {
+            return;
+        };
+        let Some(camera) = self.active_camera().upgrade(self) else {
+            return;
+        };
+
+        let camera_transform = &camera.parent().transform;
+        let camera_position = camera_transform.position();
+        let camera_up = camera_transform.up();
+
+        for billboard in billboards {
+            let mut billboard = billboard.clone();
+            let obj = billboard.parent();
+            if !obj.is_alive() || !obj.active_in_hierarchy() {
+                continue;
+            }
+
+            if obj.transform.is_dirty() {
+                billboard.reset_facing();
+            }
+            let Some(local_to_world) = billboard.changed_facing(
+                &obj.transform.global_transform_matrix(),
+                camera_position,
+                camera_up,
+            ) else {
+                continue;
+            };
+            for comp in obj.components.iter() {
+                batch.push(RenderMsg::UpdateTransform(comp.typed_id(), local_to_world));
+            }
+        }
+    }
+
+    fn push_texture_camera_updates(&mut self, batch: &mut Vec<RenderMsg>) {
+        let mut cameras = Vec::new();
+        let mut views = Vec::new();
+        for mut camera in self.texture_cameras() {
+            let Some(texture) = camera.render_texture() else {
+                continue;
+            };
+            let Some((width, height)) = self
+                .assets
+                .textures
+                .try_get(texture)
+                .map(|t| (t.width.max(1), t.height.max(1)))
+            else {
+                continue;
+            };
+
+            if camera.resolution() != (width as f32, height as f32) {
+                camera.resize(width as f32, height as f32);
+            }
+
+            views.push(TextureCameraView {
+                camera: camera.typed_id(),
+                texture,
+                width,
+                height,
+            });
+            cameras.push(camera);
+        }
+
+        if self.channels.texture_views != views {
+            batch.push(RenderMsg::UpdateTextureCameras(views.clone()));
+            self.channels.texture_views = views;
+        }
+
+        for mut camera in cameras {
+            let target_id = camera.render_target();
+            Self::push_camera_updates(target_id, batch, &mut camera);
+        }
+    }
+
     fn push_camera_updates(
         target_id: RenderTargetId,
         batch: &mut Vec<RenderMsg>,
         active_camera: &mut CRef<CameraComponent>,
     ) {
+        let cid = active_camera.typed_id();
         let obj = active_camera.parent();
-        if obj.transform.is_dirty() {
+        if obj.transform.is_dirty() || active_camera.is_view_dirty() {
             let pos = obj.transform.position();
             let view_mat = obj.transform.view_matrix_rigid().to_matrix();
             let view_proj_mat = active_camera.projection.as_matrix() * view_mat;
@@ -727,6 +1342,7 @@ impl World {
                 .unwrap_or_else(Matrix4::identity);
             batch.push(RenderMsg::UpdateActiveCamera(
                 target_id,
+                cid,
                 Box::new(move |cam| {
                     cam.view_mat = view_mat;
                     cam.proj_view_mat = view_proj_mat;
@@ -740,6 +1356,7 @@ impl World {
             let proj_mat = active_camera.projection;
             batch.push(RenderMsg::UpdateActiveCamera(
                 target_id,
+                cid,
                 Box::new(move |cam| {
                     cam.projection_mat = proj_mat;
                     let view_proj_mat = cam.projection_mat.as_matrix() * cam.view_mat;
@@ -751,6 +1368,17 @@ impl World {
             ));
             active_camera.clear_projection_dirty();
         }
+
+        if active_camera.is_clear_dirty() {
+            batch.push(RenderMsg::UpdateCameraClear(
+                target_id,
+                cid,
+                active_camera.clear_config(),
+            ));
+            active_camera.clear_clear_dirty();
+        }
+
+        active_camera.clear_view_dirty();
     }
 
     /// Internally sync removed components to the Render Thread for proxy deletion
@@ -785,6 +1413,10 @@ impl World {
 
             let local_to_world = comp.parent().transform.global_transform_matrix();
             if let Some(proxy) = comp.create_render_proxy(self) {
+                // the new proxy starts with the real transform, so the facing has to be resent
+                if let Some(mut billboard) = comp.parent().get_component::<Billboard>() {
+                    billboard.reset_facing();
+                }
                 self.channels
                     .render_tx
                     .send(RenderMsg::RegisterProxy(
@@ -809,13 +1441,29 @@ impl World {
     /// If you're using the App runtime, this will be handled for you. Only call this function
     /// if you are trying to use a detached world context.
     pub fn next_frame(&mut self) {
+        self.finish_frame();
+        self.tick_delta_time();
+    }
+
+    fn finish_frame(&mut self) {
+        if self.spatial.as_ref().is_some_and(SpatialGrid::auto_update) {
+            let moved: Vec<_> = self
+                .objects
+                .iter()
+                .filter(|(_, o)| o.is_alive() && o.transform.is_dirty())
+                .map(|(id, _)| id)
+                .collect();
+            for obj in moved {
+                self.update_spatial_object(obj);
+            }
+        }
+
         for child in self.objects.values_mut() {
             if child.is_alive() {
                 child.transform.clear_dirty();
             }
         }
         self.input.next_frame_all();
-        self.tick_delta_time();
     }
 
     /// Finds a game object by its name
@@ -858,6 +1506,141 @@ impl World {
         collection.extend(obj.iter_components::<C>());
     }
 
+    /// Iterates over all objects in the scene graph, depth-first in pre-order: each object comes
+    /// before its children, and siblings stay in the order they were added.
+    ///
+    /// Only objects [added to the world](World::add_child), or to one of its objects, are
+    /// visited.
+    pub fn iter_objects(&self) -> impl Iterator<Item = &GameObject> {
+        self.hierarchy_ids()
+            .into_iter()
+            .filter_map(|id| self.get_object(id))
+    }
+
+    /// Calls `f` for every object in the scene graph, in the same order as
+    /// [`iter_objects`](World::iter_objects).
+    ///
+    /// The objects are collected before the first call, so objects added by `f` are skipped,
+    /// and objects deleted by `f` aren't visited anymore.
+    pub fn for_each_object(&mut self, mut f: impl FnMut(&mut GameObject)) {
+        for id in self.hierarchy_ids() {
+            if let Some(obj) = self.get_object_mut(id) {
+                f(obj);
+            }
+        }
+    }
+
+    /// Collects the ids of the scene graph in pre-order
+    fn hierarchy_ids(&self) -> Vec<GameObjectId> {
+        let mut ids = Vec::with_capacity(self.objects.len());
+        let mut stack: Vec<GameObjectId> = self.children.iter().rev().copied().collect();
+
+        while let Some(id) = stack.pop() {
+            let Some(obj) = self.get_object(id) else {
+                continue;
+            };
+            ids.push(id);
+            stack.extend(obj.children.iter().rev());
+        }
+
+        ids
+    }
+
+    /// Collects the fields of the transform and of every component of `obj` that an editor can
+    /// show, see [`Component::inspect`]. The transform comes first, then the components in the
+    /// order they were added.
+    pub fn inspect_object(&mut self, obj: GameObjectId) -> Vec<InspectorSection> {
+        let Some(object) = self.get_object_mut(obj) else {
+            return Vec::new();
+        };
+
+        let mut ui = Inspector::collect();
+        object.transform.inspect(&mut ui);
+        let mut sections = vec![InspectorSection {
+            name: "Transform",
+            component: None,
+            fields: ui.into_fields(),
+        }];
+
+        for mut comp in object.components.clone() {
+            let mut ui = Inspector::collect();
+            comp.inspect(&mut ui);
+            sections.push(InspectorSection {
+                name: comp.type_name(),
+                component: Some(comp.typed_id()),
+                fields: ui.into_fields(),
+            });
+        }
+
+        sections
+    }
+
+    /// Sets a field from [`inspect_object`](World::inspect_object) on the transform, for a
+    /// `component` of `None`, or on a component of `obj`.
+    ///
+    /// Returns `false` if there's no editable field with that name, or the value has another
+    /// type.
+    pub fn set_inspected_field(
+        &mut self,
+        obj: GameObjectId,
+        component: Option<TypedComponentId>,
+        field: &str,
+        value: InspectValue,
+    ) -> bool {
+        let Some(object) = self.get_object_mut(obj) else {
+            return false;
+        };
+
+        let mut ui = Inspector::edit(field, value);
+        match component {
+            None => object.transform.inspect(&mut ui),
+            Some(id) => {
+                let Some(mut comp) = object
+                    .components
+                    .iter()
+                    .find(|c| c.typed_id() == id)
+                    .cloned()
+                else {
+                    return false;
+                };
+                comp.inspect(&mut ui);
+            }
+        }
+        ui.applied()
+    }
+
+    /// Finds the object with the given persistent id, see [`GameObject::set_uuid`]
+    pub fn find_by_uuid(&self, uuid: Uuid) -> Option<GameObjectId> {
+        self.objects_by_uuid.get(&uuid).copied()
+    }
+
+    pub(crate) fn set_object_uuid(
+        &mut self,
+        obj: GameObjectId,
+        uuid: Option<Uuid>,
+    ) -> Result<(), UuidError> {
+        let Some(object) = self.objects.get(obj).filter(|o| o.is_alive()) else {
+            return Err(UuidError::DeletedObject);
+        };
+        if let Some(uuid) = uuid
+            && let Some(&other) = self.objects_by_uuid.get(&uuid)
+        {
+            return match other == obj {
+                true => Ok(()),
+                false => Err(UuidError::UuidTaken { uuid, other }),
+            };
+        }
+
+        if let Some(old) = object.uuid {
+            self.objects_by_uuid.remove(&old);
+        }
+        if let Some(uuid) = uuid {
+            self.objects_by_uuid.insert(uuid, obj);
+        }
+        self.objects[obj].uuid = uuid;
+        Ok(())
+    }
+
     /// Find all objects that contain a property with the given key
     pub fn find_objects_with_property(&self, key: &str) -> Vec<GameObjectId> {
         self.objects
@@ -878,6 +1661,183 @@ impl World {
             .collect()
     }
 
+    /// Indexes all objects in a [`SpatialGrid`] with cells of `cell_size` units, which
+    /// [`objects_near`](Self::objects_near) and [`objects_in_frustum`](Self::objects_in_frustum)
+    /// use from then on.
+    ///
+    /// Objects are indexed by their [world bounds](GameObjectId::world_bounds), or as a point at
+    /// their position if they have none. Moved objects are updated at the end of every frame,
+    /// unless that's turned off with [`SpatialGrid::set_auto_update`]. A changed mesh doesn't
+    /// move the object, so it needs [`update_spatial_object`](Self::update_spatial_object).
+    ///
+    /// Small scenes are fine without the index, the queries then check every object.
+    pub fn enable_spatial_index(&mut self, cell_size: f32) {
+        self.spatial = Some(SpatialGrid::new(cell_size));
+        self.rebuild_spatial_index();
+    }
+
+    pub fn disable_spatial_index(&mut self) {
+        self.spatial = None;
+    }
+
+    pub fn spatial_index(&self) -> Option<&SpatialGrid> {
+        self.spatial.as_ref()
+    }
+
+    pub fn spatial_index_mut(&mut self) -> Option<&mut SpatialGrid> {
+        self.spatial.as_mut()
+    }
+
+    /// Re-indexes every object from scratch. Does nothing if the index is disabled.
+    pub fn rebuild_spatial_index(&mut self) {
+        let Some(spatial) = &mut self.spatial else {
+            return;
+        };
+
+        spatial.clear();
+        for (id, obj) in &self.objects {
+            if obj.is_alive() {
+                spatial.insert(id, spatial_bounds(id));
+            }
+        }
+    }
+
+    /// Updates the bounds of one object in the index. Does nothing if the index is disabled.
+    pub fn update_spatial_object(&mut self, obj: GameObjectId) {
+        let Some(spatial) = &mut self.spatial else {
+            return;
+        };
+
+        if obj.exists() {
+            spatial.insert(obj, spatial_bounds(obj));
+        } else {
+            spatial.remove(obj);
+        }
+    }
+
+    /// Returns all objects whose bounds overlap the sphere of `radius` around `point`
+    pub fn objects_near(&self, point: Vector3<f32>, radius: f32) -> Vec<GameObjectId> {
+        if let Some(spatial) = &self.spatial {
+            return spatial.query_sphere(point, radius);
+        }
+
+        let query = BoundingSphere {
+            center: point,
+            radius: radius.max(0.0),
+        };
+        self.alive_object_ids()
+            .filter(|&obj| spheres_overlap(&spatial_bounds(obj), &query))
+            .collect()
+    }
+
+    /// Returns all objects whose bounds are at least partly inside the frustum, e.g. what a
+    /// [camera](CameraComponent::frustum) sees. The renderer culls on its own, this is for
+    /// gameplay.
+    pub fn objects_in_frustum(&self, frustum: &Frustum) -> Vec<GameObjectId> {
+        if let Some(spatial) = &self.spatial {
+            return spatial.query_frustum(frustum);
+        }
+
+        self.alive_object_ids()
+            .filter(|&obj| frustum.intersects_sphere(&spatial_bounds(obj)))
+            .collect()
+    }
+
+    /// Captures the transforms of all objects and the poses and velocities of all rigid
+    /// bodies, see [`WorldSnapshot`] for what's left out.
+    pub fn snapshot(&self) -> WorldSnapshot {
+        let mut snapshot = WorldSnapshot::default();
+        self.snapshot_into(&mut snapshot);
+        snapshot
+    }
+
+    /// Like [`snapshot`](Self::snapshot), but reuses the allocations of an older snapshot,
+    /// e.g. for a ring buffer of rollback frames
+    pub fn snapshot_into(&self, snapshot: &mut WorldSnapshot) {
+        snapshot.clear();
+        snapshot
+            .transforms
+            .extend(
+                self.objects
+                    .iter()
+                    .filter(|(_, o)| o.is_alive())
+                    .map(|(object, o)| TransformState {
+                        object,
+                        position: *o.transform.local_position(),
+                        rotation: *o.transform.local_rotation(),
+                        scale: *o.transform.local_scale(),
+                    }),
+            );
+        snapshot
+            .bodies
+            .extend(
+                self.physics
+                    .rigid_body_set
+                    .iter()
+                    .map(|(handle, body)| BodyState {
+                        handle,
+                        pose: *body.position(),
+                        linvel: *body.linvel(),
+                        angvel: *body.angvel(),
+                    }),
+            );
+    }
+
+    /// Puts transforms and rigid bodies back to how they were in the snapshot. Bodies are
+    /// teleported, get their old velocities back and lose their accumulated forces, and render
+    /// interpolation starts over from the restored pose.
+    pub fn restore(&mut self, snapshot: &WorldSnapshot) {
+        for state in &snapshot.transforms {
+            if let Some(obj) = self.objects.get_mut(state.object)
+                && obj.is_alive()
+            {
+                obj.transform
+                    .set_local_parts(state.position, state.rotation, state.scale);
+            }
+        }
+
+        for state in &snapshot.bodies {
+            let Some(body) = self.physics.rigid_body_set.get_mut(state.handle) else {
+                continue;
+            };
+            body.set_position(state.pose, true);
+            body.set_linvel(state.linvel, false);
+            body.set_angvel(state.angvel, false);
+            body.reset_forces(false);
+            body.reset_torques(false);
+        }
+
+        let bodies: Vec<_> = self
+            .components
+            .values_of_type::<RigidBodyComponent>()
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect();
+        for mut body in bodies {
+            body.sync_interpolation();
+        }
+    }
+
+    fn alive_object_ids(&self) -> impl Iterator<Item = GameObjectId> + '_ {
+        self.objects
+            .iter()
+            .filter(|(_, o)| o.is_alive())
+            .map(|(id, _)| id)
+    }
+
+    /// Sends a compute job to the renderer, which runs it at the start of its next frame.
+    ///
+    /// The returned handle receives the read-back buffers once the GPU finished.
+    pub fn dispatch_compute(&self, job: ComputeJob) -> ComputeReadback {
+        let readback = job.readback();
+        let _ = self
+            .channels
+            .render_tx
+            .send(RenderMsg::DispatchCompute(job));
+        readback
+    }
+
     pub fn capture_offscreen_texture(
         &self,
         target: RenderTargetId,
@@ -889,6 +1849,17 @@ impl World {
             .is_ok()
     }
 
+    /// Saves the last frame of a headless render target as a PNG image. Windowed targets
+    /// present to a swapchain and can't be captured, see [`AppRuntime::headless`].
+    ///
+    /// [`AppRuntime::headless`]: crate::AppRuntime::headless
+    pub fn capture_frame(&self, target: RenderTargetId, path: impl Into<PathBuf>) -> bool {
+        self.channels
+            .render_tx
+            .send(RenderMsg::CaptureFrame(target, path.into()))
+            .is_ok()
+    }
+
     pub fn capture_picking_texture(
         &self,
         target: RenderTargetId,
@@ -900,6 +1871,13 @@ impl World {
             .is_ok()
     }
 
+    /// Returns what the renderer drew in its last finished frame, e.g. for a debug overlay.
+    /// All zero until a frame was rendered. The renderer reports its frames back to the world, so
+    /// the stats trail behind by a frame or so.
+    pub fn render_stats(&self) -> RenderStats {
+        self.render_stats
+    }
+
     /// Prints information about all game objects in the world to the log
     ///
     /// This method will print out the scene graph to the console and add some information about
@@ -907,18 +1885,222 @@ impl World {
     pub fn print_objects(&self) {
         let alive = self.objects.values().filter(|o| o.is_alive()).count();
         info!("{alive} game objects in world.");
-        print_objects_rec(&self.children, 0)
+        for line in self.object_tree().to_string().lines() {
+            info!("{line}");
+        }
+    }
+
+    /// Collects the scene graph as data, with the names, components, transforms and children
+    /// of all objects, e.g. to show it in an inspector. The root node stands for the world.
+    pub fn object_tree(&self) -> ObjectTreeNode {
+        ObjectTreeNode {
+            id: None,
+            name: "World".to_string(),
+            active: true,
+            components: Vec::new(),
+            transform: TransformSummary::default(),
+            children: self.object_tree_nodes(&self.children),
+        }
+    }
+
+    fn object_tree_nodes(&self, ids: &[GameObjectId]) -> Vec<ObjectTreeNode> {
+        ids.iter()
+            .filter_map(|id| self.get_object(*id))
+            .map(|obj| ObjectTreeNode {
+                id: Some(obj.id),
+                name: obj.name.clone(),
+                active: obj.active,
+                components: obj.component_type_names(),
+                transform: TransformSummary {
+                    local_position: *obj.transform.local_position(),
+                    local_rotation: *obj.transform.local_rotation(),
+                    local_scale: *obj.transform.local_scale(),
+                    position: obj.transform.position(),
+                },
+                children: self.object_tree_nodes(&obj.children),
+            })
+            .collect()
     }
 
     /// Updates the delta time based on the elapsed time since the last frame
     fn tick_delta_time(&mut self) {
-        self.delta_time = self.last_frame_time.elapsed();
+        self.set_frame_delta(self.last_frame_time.elapsed());
         self.last_frame_time = Instant::now();
     }
 
-    /// Returns the time elapsed since the last frame
+    fn set_frame_delta(&mut self, raw: Duration) {
+        self.raw_delta_time = raw;
+        self.delta_time = raw.min(self.max_delta);
+
+        self.smoothed_delta_time = if self.smoothed_delta_time.is_zero() {
+            self.delta_time
+        } else {
+            self.smoothed_delta_time.mul_f32(1.0 - DELTA_SMOOTHING)
+                + self.delta_time.mul_f32(DELTA_SMOOTHING)
+        };
+    }
+
+    /// Returns the time elapsed since the last frame, clamped to the [max delta](World::max_delta)
+    ///
+    /// During `pre_fixed_update` and `fixed_update` this is the
+    /// [fixed delta time](World::fixed_delta_time) instead, since that's the time one physics
+    /// step covers.
     pub fn delta_time(&self) -> Duration {
-        self.delta_time
+        if self.in_fixed_step {
+            self.physics.timestep
+        } else {
+            self.delta_time
+        }
+    }
+
+    /// Returns the time one physics step covers, configured through
+    /// [`PhysicsManager::timestep`]. Defaults to 1/60 of a second.
+    pub fn fixed_delta_time(&self) -> Duration {
+        self.physics.timestep
+    }
+
+    /// Returns the time elapsed since the last frame without clamping
+    pub fn raw_delta_time(&self) -> Duration {
+        self.raw_delta_time
+    }
+
+    /// Returns a moving average of [`delta_time`](World::delta_time), which is steadier for
+    /// things like camera motion or FPS displays
+    pub fn smoothed_delta_time(&self) -> Duration {
+        self.smoothed_delta_time
+    }
+
+    /// Returns the longest delta time a single frame can report. Defaults to 100ms.
+    pub fn max_delta(&self) -> Duration {
+        self.max_delta
+    }
+
+    /// Sets the longest delta time a single frame can report.
+    ///
+    /// Longer frames, e.g. after dragging the window or alt-tabbing, are slowed down to this
+    /// instead of making objects jump. Physics won't catch up on more than this either.
+    pub fn set_max_delta(&mut self, max_delta: Duration) {
+        self.max_delta = max_delta;
+    }
+
+    /// Sets how often the files of [watched shaders](Store::watch) are checked for changes.
+    ///
+    /// Every check asks the file system about each watched file, so it doesn't happen every frame.
+    /// Defaults to [`DEFAULT_SHADER_WATCH_INTERVAL`].
+    pub fn set_shader_watch_interval(&mut self, interval: Duration) {
+        self.shader_watch_interval = interval;
+    }
+
+    fn reload_watched_shaders(&mut self) {
+        if self.last_shader_watch.elapsed() < self.shader_watch_interval {
+            return;
+        }
+        self.last_shader_watch = Instant::now();
+
+        for reloaded in self
+            .assets
+            .shaders
+            .reload_watched(&self.assets.shader_includes)
+        {
+            self.events.emit(reloaded);
+        }
+    }
+
+    /// Returns the global gravity. Defaults to 9.81 m/s² pointing down the Y axis.
+    pub fn gravity(&self) -> Vector3<f32> {
+        self.physics.gravity
+    }
+
+    /// Sets the global gravity, e.g. weaker for underwater scenes. Bodies scale it by their
+    /// [gravity scale](crate::components::RigidBodyComponent::set_gravity_scale).
+    ///
+    /// Sleeping bodies are woken up so they respond to the change.
+    pub fn set_gravity(&mut self, gravity: Vector3<f32>) {
+        self.physics.set_gravity(gravity);
+    }
+
+    /// Turns off gravity for the whole world, e.g. for space scenes
+    pub fn set_zero_gravity(&mut self) {
+        self.set_gravity(Vector3::zeros());
+    }
+
+    /// Sweeps `shape` from `start` along `velocity` and returns the first contact within
+    /// `max_toi` multiples of the velocity. Useful for fast projectiles or checking if an object
+    /// fits somewhere before moving it.
+    ///
+    /// See [`PhysicsManager::cast_shape`] for what happens if the shape starts out overlapping.
+    pub fn shape_cast(
+        &self,
+        shape: &dyn Shape,
+        start: &Isometry3<f32>,
+        velocity: Vector3<f32>,
+        max_toi: f32,
+        filter: QueryFilter,
+    ) -> Option<SweepHit> {
+        self.physics
+            .cast_shape(shape, start, &velocity, max_toi, filter)
+    }
+
+    /// Returns every object with a collider overlapping the sphere, e.g. to find what an
+    /// explosion hits. See [`PhysicsManager::intersect_shape`] for how sensors are handled.
+    pub fn intersect_sphere(
+        &self,
+        center: Vector3<f32>,
+        radius: f32,
+        filter: QueryFilter,
+    ) -> Vec<GameObjectId> {
+        let pose = Isometry3::translation(center.x, center.y, center.z);
+        self.physics
+            .intersect_shape(&pose, &Ball::new(radius.max(0.0)), filter)
+    }
+
+    /// Returns every object with a collider overlapping the box. See
+    /// [`PhysicsManager::intersect_shape`] for how sensors are handled.
+    pub fn intersect_box(
+        &self,
+        center: Vector3<f32>,
+        half_extents: Vector3<f32>,
+        rotation: UnitQuaternion<f32>,
+        filter: QueryFilter,
+    ) -> Vec<GameObjectId> {
+        let pose = Isometry3::from_parts(Translation3::from(center), rotation);
+        let shape = Cuboid::new(half_extents.abs());
+        self.physics.intersect_shape(&pose, &shape, filter)
+    }
+
+    /// Casts a ray from the active camera straight ahead, through the center of its view, and
+    /// returns the closest hit, e.g. for interacting with what the player looks at
+    pub fn raycast_from_camera(&self, max_toi: f32, filter: QueryFilter) -> Option<RayHit> {
+        let camera = self.active_camera().upgrade(self)?.parent();
+        let ray = Ray::new(
+            camera.transform.position().into(),
+            camera.transform.forward(),
+        );
+        self.physics.raycast(&ray, max_toi, filter)
+    }
+
+    /// Casts a ray from the active camera through a pixel of its render target, with the
+    /// origin at the top left like cursor positions, and returns the closest hit.
+    ///
+    /// With several cameras on screen, [`camera_at`](Self::camera_at) finds the one under the
+    /// pixel.
+    pub fn raycast_from_screen(
+        &self,
+        x: f32,
+        y: f32,
+        max_toi: f32,
+        filter: QueryFilter,
+    ) -> Option<RayHit> {
+        let camera = self.active_camera().upgrade(self)?;
+        let ray = camera.screen_to_ray(Vector2::new(x, y));
+        self.physics.raycast(&ray, max_toi, filter)
+    }
+
+    /// Returns the world's random number generator.
+    ///
+    /// Seed it with [`Rng::seed`] to make runs reproducible.
+    pub fn rng(&mut self) -> &mut Rng {
+        &mut self.rng
     }
 
     /// Returns the instant in time when the world was created
@@ -926,9 +2108,10 @@ impl World {
         self.start_time
     }
 
-    /// Returns the total time elapsed since the world was created
+    /// Returns the total time elapsed since the world was created. A [ticked](World::tick)
+    /// world returns the sum of its ticks instead.
     pub fn time(&self) -> Duration {
-        self.start_time.elapsed()
+        self.tick_time.unwrap_or_else(|| self.start_time.elapsed())
     }
 
     /// Marks a game object for deletion. This will immediately run the object internal destruction routine
@@ -968,6 +2151,51 @@ impl World {
             .send(GameAppEvent::UpdateWindowTitle(target_id, title));
     }
 
+    pub fn set_default_window_icon(&mut self, icon: &RgbaImage) {
+        self.set_window_icon(RenderTargetId::PRIMARY, icon);
+    }
+
+    /// Sets the icon shown in the title bar and task bar, where the platform supports it
+    pub fn set_window_icon(&mut self, target_id: RenderTargetId, icon: &RgbaImage) {
+        let icon = match Icon::from_rgba(icon.as_raw().clone(), icon.width(), icon.height()) {
+            Ok(icon) => icon,
+            Err(e) => {
+                warn!("Invalid window icon: {e}");
+                return;
+            }
+        };
+
+        let _ = self
+            .channels
+            .game_event_tx
+            .send(GameAppEvent::SetWindowIcon(target_id, icon));
+    }
+
+    /// The clipboard for copy and paste, e.g. `world.clipboard().get_text()`
+    pub fn clipboard(&mut self) -> &mut Clipboard {
+        &mut self.clipboard
+    }
+
+    /// Sets the cursor shown over the window the input currently comes from, e.g.
+    /// [`CursorIcon::Pointer`] over buttons
+    pub fn set_cursor_icon(&mut self, icon: CursorIcon) {
+        let target = self.input.active_target();
+        let _ = self
+            .channels
+            .game_event_tx
+            .send(GameAppEvent::SetCursorIcon(target, icon));
+    }
+
+    /// Shows or hides the cursor over the window the input currently comes from, without
+    /// locking it like [`InputManager::lock_cursor`] does
+    pub fn set_cursor_visible(&mut self, visible: bool) {
+        let target = self.input.active_target();
+        let _ = self
+            .channels
+            .game_event_tx
+            .send(GameAppEvent::SetCursorVisible(target, visible));
+    }
+
     /// Requests a shutdown of the world
     ///
     /// The world might not shut down immediately as cleanup will be started after this.
@@ -1011,6 +2239,7 @@ impl World {
         self.components = ComponentStorage::default();
         self.click_listeners.clear();
         self.object_hashes.clear();
+        self.objects_by_uuid.clear();
         self.next_pick_request_id = 0;
         self.pending_deletions.clear();
     }
@@ -1052,17 +2281,16 @@ impl AsRef<Store<Sound>> for World {
     }
 }
 
-fn print_objects_rec(children: &Vec<GameObjectId>, i: i32) {
-    for child in children {
-        if !child.exists() {
-            continue;
-        }
-        info!("{}- {}", "  ".repeat(i as usize), &child.name);
-        info!(
-            "{}-> Components: {}",
-            "  ".repeat(i as usize + 1),
-            child.components.len()
-        );
-        print_objects_rec(&child.children, i + 1);
+impl AsRef<Store<ComputeShader>> for World {
+    fn as_ref(&self) -> &Store<ComputeShader> {
+        &self.assets.compute
     }
 }
+
+/// The bounds an object is indexed with: its mesh bounds, or a point at its position
+fn spatial_bounds(obj: GameObjectId) -> BoundingSphere {
+    obj.world_bounds().unwrap_or(BoundingSphere {
+        center: obj.transform.position(),
+        radius: 0.0,
+    })
+}
@@ -0,0 +1,295 @@
+//! Easing functions and curves for animating values over time.
+//!
+//! Every easing function maps a progress `t` in `[0, 1]` to an eased progress, starting at `0`
+//! and ending at `1`. The elastic easings overshoot in between. Inputs outside of
+//! `[0, 1]` are clamped.
+//!
+//! Only plain `f32` math is used here, without allocations, apart from [`Curve`] storing its
+//! control points.
+
+use std::f32::consts::PI;
+
+const ELASTIC_PERIOD: f32 = (2.0 * PI) / 3.0;
+const ELASTIC_PERIOD_IN_OUT: f32 = (2.0 * PI) / 4.5;
+
+pub fn linear(t: f32) -> f32 {
+    t.clamp(0.0, 1.0)
+}
+
+pub fn quad_in(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    t * t
+}
+
+pub fn quad_out(t: f32) -> f32 {
+    let t = 1.0 - t.clamp(0.0, 1.0);
+    1.0 - t * t
+}
+
+pub fn quad_in_out(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    if t < 0.5 {
+        2.0 * t * t
+    } else {
+        let t = -2.0 * t + 2.0;
+        1.0 - t * t / 2.0
+    }
+}
+
+pub fn cubic_in(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    t * t * t
+}
+
+pub fn cubic_out(t: f32) -> f32 {
+    let t = 1.0 - t.clamp(0.0, 1.0);
+    1.0 - t * t * t
+}
+
+pub fn cubic_in_out(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    if t < 0.5 {
+        4.0 * t * t * t
+    } else {
+        let t = -2.0 * t + 2.0;
+        1.0 - t * t * t / 2.0
+    }
+}
+
+pub fn sine_in(t: f32) -> f32 {
+    1.0 - (t.clamp(0.0, 1.0) * PI / 2.0).cos()
+}
+
+pub fn sine_out(t: f32) -> f32 {
+    (t.clamp(0.0, 1.0) * PI / 2.0).sin()
+}
+
+pub fn sine_in_out(t: f32) -> f32 {
+    -((t.clamp(0.0, 1.0) * PI).cos() - 1.0) / 2.0
+}
+
+pub fn expo_in(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    if t <= 0.0 {
+        0.0
+    } else {
+        2f32.powf(10.0 * t - 10.0)
+    }
+}
+
+pub fn expo_out(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    if t >= 1.0 {
+        1.0
+    } else {
+        1.0 - 2f32.powf(-10.0 * t)
+    }
+}
+
+pub fn expo_in_out(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    if t <= 0.0 {
+        0.0
+    } else if t >= 1.0 {
+        1.0
+    } else if t < 0.5 {
+        2f32.powf(20.0 * t - 10.0) / 2.0
+    } else {
+        (2.0 - 2f32.powf(-20.0 * t + 10.0)) / 2.0
+    }
+}
+
+pub fn elastic_in(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    if t <= 0.0 || t >= 1.0 {
+        return t;
+    }
+    -2f32.powf(10.0 * t - 10.0) * ((t * 10.0 - 10.75) * ELASTIC_PERIOD).sin()
+}
+
+pub fn elastic_out(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    if t <= 0.0 || t >= 1.0 {
+        return t;
+    }
+    2f32.powf(-10.0 * t) * ((t * 10.0 - 0.75) * ELASTIC_PERIOD).sin() + 1.0
+}
+
+pub fn elastic_in_out(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    if t <= 0.0 || t >= 1.0 {
+        return t;
+    }
+    let wave = ((20.0 * t - 11.125) * ELASTIC_PERIOD_IN_OUT).sin();
+    if t < 0.5 {
+        -(2f32.powf(20.0 * t - 10.0) * wave) / 2.0
+    } else {
+        2f32.powf(-20.0 * t + 10.0) * wave / 2.0 + 1.0
+    }
+}
+
+pub fn bounce_in(t: f32) -> f32 {
+    1.0 - bounce_out(1.0 - t.clamp(0.0, 1.0))
+}
+
+pub fn bounce_out(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+
+    let t = t.clamp(0.0, 1.0);
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+pub fn bounce_in_out(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    if t < 0.5 {
+        (1.0 - bounce_out(1.0 - 2.0 * t)) / 2.0
+    } else {
+        (1.0 + bounce_out(2.0 * t - 1.0)) / 2.0
+    }
+}
+
+/// Names one of the easing functions, so it can be stored or configured
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Easing {
+    #[default]
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    SineIn,
+    SineOut,
+    SineInOut,
+    ExpoIn,
+    ExpoOut,
+    ExpoInOut,
+    ElasticIn,
+    ElasticOut,
+    ElasticInOut,
+    BounceIn,
+    BounceOut,
+    BounceInOut,
+}
+
+impl Easing {
+    /// Eases the progress `t` in `[0, 1]`
+    pub fn apply(self, t: f32) -> f32 {
+        match self {
+            Easing::Linear => linear(t),
+            Easing::QuadIn => quad_in(t),
+            Easing::QuadOut => quad_out(t),
+            Easing::QuadInOut => quad_in_out(t),
+            Easing::CubicIn => cubic_in(t),
+            Easing::CubicOut => cubic_out(t),
+            Easing::CubicInOut => cubic_in_out(t),
+            Easing::SineIn => sine_in(t),
+            Easing::SineOut => sine_out(t),
+            Easing::SineInOut => sine_in_out(t),
+            Easing::ExpoIn => expo_in(t),
+            Easing::ExpoOut => expo_out(t),
+            Easing::ExpoInOut => expo_in_out(t),
+            Easing::ElasticIn => elastic_in(t),
+            Easing::ElasticOut => elastic_out(t),
+            Easing::ElasticInOut => elastic_in_out(t),
+            Easing::BounceIn => bounce_in(t),
+            Easing::BounceOut => bounce_out(t),
+            Easing::BounceInOut => bounce_in_out(t),
+        }
+    }
+
+    /// Interpolates from `from` to `to` with the eased progress `t`
+    pub fn interpolate(self, from: f32, to: f32, t: f32) -> f32 {
+        from + (to - from) * self.apply(t)
+    }
+}
+
+/// A value that changes over time, defined by `(time, value)` control points.
+///
+/// Between two points, the value is interpolated with the curve's [`Easing`]. Before the first
+/// and after the last point, the value of the closest point is held.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Curve {
+    points: Vec<(f32, f32)>,
+    easing: Easing,
+}
+
+impl Curve {
+    /// Creates a linear curve through the given points, which don't need to be sorted
+    pub fn new(points: impl IntoIterator<Item = (f32, f32)>) -> Self {
+        let mut curve = Curve::default();
+        for (time, value) in points {
+            curve.add_point(time, value);
+        }
+        curve
+    }
+
+    /// A curve that is `value` everywhere
+    pub fn constant(value: f32) -> Self {
+        Curve::new([(0.0, value)])
+    }
+
+    /// Sets the easing used between every pair of neighbouring points
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    pub fn set_easing(&mut self, easing: Easing) {
+        self.easing = easing;
+    }
+
+    pub fn easing(&self) -> Easing {
+        self.easing
+    }
+
+    /// Inserts a control point, keeping the points sorted by time
+    pub fn add_point(&mut self, time: f32, value: f32) {
+        let idx = self.points.partition_point(|&(t, _)| t <= time);
+        self.points.insert(idx, (time, value));
+    }
+
+    /// The control points, sorted by time
+    pub fn points(&self) -> &[(f32, f32)] {
+        &self.points
+    }
+
+    /// Returns the value at `time`, or `0` if the curve has no points.
+    /// A `NaN` time samples the first point.
+    pub fn sample(&self, time: f32) -> f32 {
+        let (Some(&first), Some(&last)) = (self.points.first(), self.points.last()) else {
+            return 0.0;
+        };
+
+        if time.is_nan() || time <= first.0 {
+            return first.1;
+        }
+        if time >= last.0 {
+            return last.1;
+        }
+
+        let idx = self.points.partition_point(|&(t, _)| t <= time);
+        let (t0, v0) = self.points[idx - 1];
+        let (t1, v1) = self.points[idx];
+
+        let span = t1 - t0;
+        if span <= f32::EPSILON {
+            return v1;
+        }
+
+        self.easing.interpolate(v0, v1, (time - t0) / span)
+    }
+}
@@ -87,6 +87,105 @@ impl FloatMathExt for f32 {
     }
 }
 
+/// Moves `current` towards `target` by at most `max_delta`, without overshooting
+pub fn move_toward(current: Vector3<f32>, target: Vector3<f32>, max_delta: f32) -> Vector3<f32> {
+    let to_target = target - current;
+    let distance = to_target.norm();
+    if distance <= max_delta.max(0.0) || distance <= f32::EPSILON {
+        return target;
+    }
+    current + to_target / distance * max_delta.max(0.0)
+}
+
+/// Rotates `current` towards `target` by at most `max_radians`, without overshooting
+pub fn rotate_toward(
+    current: UnitQuaternion<f32>,
+    target: UnitQuaternion<f32>,
+    max_radians: f32,
+) -> UnitQuaternion<f32> {
+    let angle = current.angle_to(&target);
+    if angle <= max_radians.max(0.0) || angle <= f32::EPSILON {
+        return target;
+    }
+    current.slerp(&target, max_radians.max(0.0) / angle)
+}
+
+/// Smoothly moves `current` towards `target` like a critically damped spring, reaching it in
+/// roughly `smooth_time` seconds.
+///
+/// `velocity` is owned by the caller and must be kept between calls. Start it at zero.
+pub fn smooth_damp(
+    current: Vector3<f32>,
+    target: Vector3<f32>,
+    velocity: &mut Vector3<f32>,
+    smooth_time: f32,
+    delta_time: f32,
+) -> Vector3<f32> {
+    if delta_time <= 0.0 {
+        return current;
+    }
+
+    let smooth_time = smooth_time.max(1e-4);
+    let omega = 2.0 / smooth_time;
+    let x = omega * delta_time;
+    let decay = 1.0 / (1.0 + x + 0.48 * x * x + 0.235 * x * x * x);
+
+    let change = current - target;
+    let temp = (*velocity + change * omega) * delta_time;
+    *velocity = (*velocity - temp * omega) * decay;
+    let output = target + (change + temp) * decay;
+
+    // don't overshoot, the spring would swing back otherwise
+    if (target - current).dot(&(output - target)) > 0.0 {
+        *velocity = Vector3::zeros();
+        return target;
+    }
+
+    output
+}
+
+/// Interpolates between `p1` and `p2` on a Catmull-Rom spline, which passes through every point
+/// and uses `p0` and `p3` to keep the curve smooth across segments. `t` is in `[0, 1]`.
+pub fn catmull_rom(
+    p0: Vector3<f32>,
+    p1: Vector3<f32>,
+    p2: Vector3<f32>,
+    p3: Vector3<f32>,
+    t: f32,
+) -> Vector3<f32> {
+    let t2 = t * t;
+    let t3 = t2 * t;
+
+    (p1 * 2.0
+        + (p2 - p0) * t
+        + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * t2
+        + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * t3)
+        * 0.5
+}
+
+/// Smooth 1D value noise in `[-1, 1]`, e.g. for flickering or wobbling. The same `x` and
+/// `seed` always give the same value, and whole numbers of `x` are roughly one "wave" apart.
+pub fn value_noise(x: f32, seed: u32) -> f32 {
+    let cell = x.floor();
+    let t = x - cell;
+    let t = t * t * (3.0 - 2.0 * t);
+
+    let a = hash_unit(cell as i32, seed);
+    let b = hash_unit(cell as i32 + 1, seed);
+    a + (b - a) * t
+}
+
+/// Hashes a lattice point to `[-1, 1]`
+fn hash_unit(i: i32, seed: u32) -> f32 {
+    let mut h = (i as u32).wrapping_mul(0x9E37_79B1) ^ seed.wrapping_mul(0x85EB_CA6B);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x2C1B_3C6D);
+    h ^= h >> 12;
+    h = h.wrapping_mul(0x297A_2D39);
+    h ^= h >> 15;
+    h as f32 / u32::MAX as f32 * 2.0 - 1.0
+}
+
 #[allow(non_snake_case)]
 pub fn light_range(E: f32, a0: f32, a1: f32, a2: f32, T: f32) -> Option<f32> {
     if T <= 0.0 || E <= 0.0 {
@@ -2,11 +2,13 @@ pub mod animation;
 pub mod buffer;
 pub mod checks;
 pub mod color;
+pub mod ease;
 pub mod fat_ptr;
 pub mod frame_counter;
 pub(crate) mod hacks;
 pub mod iter;
 pub mod math;
+pub mod rng;
 pub mod sizes;
 
 pub use buffer::*;
@@ -14,3 +16,4 @@ pub use checks::*;
 pub use color::*;
 pub use frame_counter::*;
 pub use math::*;
+pub use rng::Rng;
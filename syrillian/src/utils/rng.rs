@@ -0,0 +1,169 @@
+//! A small, seedable random number generator for deterministic gameplay.
+//!
+//! The same seed always produces the same sequence on every platform, so seeded runs can be
+//! replayed or procedurally generated content can be recreated.
+
+use std::ops::Range;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+const PCG_MULTIPLIER: u64 = 6364136223846793005;
+const PCG_INCREMENT: u64 = 1442695040888963407;
+
+/// A PCG32 random number generator.
+///
+/// The world owns one, reachable through [`World::rng`](crate::World::rng), but components that
+/// need an independent, reproducible stream can keep their own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Creates a generator that produces the sequence for `seed`
+    pub fn new(seed: u64) -> Self {
+        let mut rng = Rng { state: 0 };
+        rng.seed(seed);
+        rng
+    }
+
+    /// Creates a generator seeded from the current time
+    pub fn from_time() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |t| t.as_nanos() as u64);
+        Self::new(nanos)
+    }
+
+    /// Restarts the generator at the beginning of the sequence for `seed`
+    pub fn seed(&mut self, seed: u64) {
+        self.state = 0;
+        self.next_u32();
+        self.state = self.state.wrapping_add(seed);
+        self.next_u32();
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old.wrapping_mul(PCG_MULTIPLIER).wrapping_add(PCG_INCREMENT);
+
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        let rot = (old >> 59) as u32;
+        xorshifted.rotate_right(rot)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        ((self.next_u32() as u64) << 32) | self.next_u32() as u64
+    }
+
+    /// Returns a random value of `T`. Floats are in `[0, 1)`.
+    ///
+    /// This is what other crates call `gen`, which is a reserved keyword in Rust 2024.
+    pub fn random<T: RandomValue>(&mut self) -> T {
+        T::random(self)
+    }
+
+    /// Returns a random value in `[min, max)`, or `min` if the range is empty
+    pub fn range<T: RandomRange>(&mut self, min: T, max: T) -> T {
+        T::random_in(self, min..max)
+    }
+
+    /// Returns true with the given probability
+    pub fn chance(&mut self, probability: f32) -> bool {
+        self.random::<f32>() < probability
+    }
+
+    /// Picks a random element, or `None` if the slice is empty
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+        items.get(self.range(0, items.len()))
+    }
+
+    /// Shuffles the slice in place
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.range(0, i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+impl Default for Rng {
+    fn default() -> Self {
+        Self::from_time()
+    }
+}
+
+/// Types that [`Rng::random`] can produce
+pub trait RandomValue {
+    fn random(rng: &mut Rng) -> Self;
+}
+
+/// Types that [`Rng::range`] can produce
+pub trait RandomRange: Sized {
+    fn random_in(rng: &mut Rng, range: Range<Self>) -> Self;
+}
+
+impl RandomValue for f32 {
+    fn random(rng: &mut Rng) -> Self {
+        (rng.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+}
+
+impl RandomValue for f64 {
+    fn random(rng: &mut Rng) -> Self {
+        (rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl RandomValue for bool {
+    fn random(rng: &mut Rng) -> Self {
+        rng.next_u32() & 1 == 1
+    }
+}
+
+macro_rules! impl_random_int {
+    ($($ty:ty),*) => {
+        $(
+            impl RandomValue for $ty {
+                fn random(rng: &mut Rng) -> Self {
+                    rng.next_u64() as $ty
+                }
+            }
+
+            impl RandomRange for $ty {
+                fn random_in(rng: &mut Rng, range: Range<Self>) -> Self {
+                    if range.start >= range.end {
+                        return range.start;
+                    }
+                    let span = range.end.abs_diff(range.start) as u64;
+                    // the modulo bias is negligible for the spans games use
+                    let offset = rng.next_u64() % span;
+                    range.start.wrapping_add(offset as $ty)
+                }
+            }
+        )*
+    };
+}
+
+impl_random_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+macro_rules! impl_random_float {
+    ($($ty:ty),*) => {
+        $(
+            impl RandomRange for $ty {
+                fn random_in(rng: &mut Rng, range: Range<Self>) -> Self {
+                    if range.start >= range.end {
+                        return range.start;
+                    }
+                    let value = range.start + (range.end - range.start) * rng.random::<$ty>();
+                    // rounding can land exactly on the end for tiny spans
+                    if value >= range.end { range.start } else { value }
+                }
+            }
+        )*
+    };
+}
+
+impl_random_float!(f32, f64);
@@ -2,12 +2,15 @@ use crate::AppState;
 use crate::assets::AssetStore;
 use crate::game_thread::GameAppEvent;
 use crate::rendering::Renderer;
+use crate::windowing::RenderTargetId;
+#[cfg(feature = "egui")]
+use crate::windowing::egui_integration::EguiIntegration;
 use crate::windowing::game_thread::GameThread;
 use crate::world::WorldChannels;
 use crossbeam_channel::unbounded;
 use std::error::Error;
 use std::marker::PhantomData;
-use tracing::{error, info, instrument, trace};
+use tracing::{error, info, instrument, trace, warn};
 use winit::application::ApplicationHandler;
 use winit::dpi::Size;
 use winit::error::EventLoopError;
@@ -22,6 +25,8 @@ pub struct App<S: AppState> {
     main_window_attributes: WindowAttributes,
     renderer: Option<Renderer>,
     game_thread: Option<GameThread<S>>,
+    #[cfg(feature = "egui")]
+    egui: Option<EguiIntegration>,
 }
 
 pub struct AppSettings<S: AppState> {
@@ -48,6 +53,8 @@ impl<S: AppState> AppSettings<S> {
             main_window_attributes: self.main_window,
             renderer: None,
             game_thread: None,
+            #[cfg(feature = "egui")]
+            egui: None,
         };
 
         Ok((event_loop, app))
@@ -75,11 +82,12 @@ impl<S: AppState> App<S> {
 
         let (render_state_tx, render_state_rx) = unbounded();
         let (game_event_tx, game_event_rx) = unbounded();
-        let (pick_result_tx, pick_result_rx) = unbounded();
+        let (feedback_tx, feedback_rx) = unbounded();
 
         let main_window = event_loop
             .create_window(self.main_window_attributes.clone())
             .unwrap();
+        let scale_factor = main_window.scale_factor();
 
         #[cfg(target_arch = "wasm32")]
         if let Some(canvas) = main_window.canvas() {
@@ -98,7 +106,7 @@ impl<S: AppState> App<S> {
 
         let renderer = match Renderer::new(
             render_state_rx,
-            pick_result_tx,
+            feedback_tx,
             main_window,
             asset_store.clone(),
         ) {
@@ -112,17 +120,41 @@ impl<S: AppState> App<S> {
 
         trace!("Created Renderer");
 
-        let channels = WorldChannels::new(render_state_tx, game_event_tx, pick_result_rx);
+        #[cfg(feature = "egui")]
+        let egui = renderer.window(RenderTargetId::PRIMARY).map(|window| {
+            let max_texture_side = renderer.state.device.limits().max_texture_dimension_2d;
+            EguiIntegration::new(window, max_texture_side as usize)
+        });
+
+        let channels = WorldChannels::new(render_state_tx, game_event_tx, feedback_rx);
         let game_thread = GameThread::new(asset_store.clone(), channels, game_event_rx);
 
-        if !game_thread.init() {
+        // sent first, so the scale factor is known during init
+        if game_thread
+            .scale_factor(RenderTargetId::PRIMARY, scale_factor)
+            .is_err()
+            || !game_thread.init()
+        {
             error!("Couldn't initialize Game Thread");
             event_loop.exit();
             return;
         }
 
+        #[cfg(feature = "egui")]
+        if let Some(egui) = &egui
+            && game_thread.egui_init(egui.context().clone()).is_err()
+        {
+            error!("Couldn't hand the egui context to the Game Thread");
+            event_loop.exit();
+            return;
+        }
+
         self.renderer = Some(renderer);
         self.game_thread = Some(game_thread);
+        #[cfg(feature = "egui")]
+        {
+            self.egui = egui;
+        }
     }
 
     #[instrument(skip_all)]
@@ -130,6 +162,7 @@ impl<S: AppState> App<S> {
         renderer: &mut Renderer,
         game_thread: &GameThread<S>,
         event_loop: &ActiveEventLoop,
+        #[cfg(feature = "egui")] mut egui: Option<&mut EguiIntegration>,
     ) -> bool {
         for event in game_thread.game_event_rx.try_iter() {
             match event {
@@ -160,6 +193,37 @@ impl<S: AppState> App<S> {
                         }
                     }
                 }
+                GameAppEvent::SetCursorConfined(event_target, confined) => {
+                    if let Some(window) = renderer.window_mut(event_target) {
+                        let grab = if confined {
+                            trace!("RT: Confined cursor");
+                            window
+                                .set_cursor_grab(CursorGrabMode::Confined)
+                                .or_else(|_| window.set_cursor_grab(CursorGrabMode::Locked))
+                        } else {
+                            trace!("RT: Released cursor");
+                            window.set_cursor_grab(CursorGrabMode::None)
+                        };
+                        if let Err(e) = grab {
+                            warn!("Couldn't change the cursor confinement: {e}");
+                        }
+                    }
+                }
+                GameAppEvent::SetCursorIcon(event_target, icon) => {
+                    if let Some(window) = renderer.window_mut(event_target) {
+                        window.set_cursor(icon);
+                    }
+                }
+                GameAppEvent::SetCursorVisible(event_target, visible) => {
+                    if let Some(window) = renderer.window_mut(event_target) {
+                        window.set_cursor_visible(visible);
+                    }
+                }
+                GameAppEvent::SetWindowIcon(event_target, icon) => {
+                    if let Some(window) = renderer.window_mut(event_target) {
+                        window.set_window_icon(Some(icon));
+                    }
+                }
                 GameAppEvent::AddWindow(event_target, size) => {
                     let window = match event_loop.create_window(
                         WindowAttributes::default()
@@ -173,10 +237,25 @@ impl<S: AppState> App<S> {
                         }
                     };
 
+                    let scale_factor = window.scale_factor();
                     if let Err(e) = renderer.add_window(event_target, window) {
                         error!("Failed to create window: {e}");
                         return false;
                     }
+                    if game_thread
+                        .scale_factor(event_target, scale_factor)
+                        .is_err()
+                    {
+                        return false;
+                    }
+                }
+                #[cfg(feature = "egui")]
+                GameAppEvent::EguiOutput(event_target, output) => {
+                    if let Some(egui) = egui.as_deref_mut()
+                        && let Some(window) = renderer.window(event_target)
+                    {
+                        egui.handle_output(window, output.0);
+                    }
                 }
                 GameAppEvent::Shutdown => return false,
             }
@@ -194,7 +273,13 @@ impl<S: AppState> App<S> {
             return true;
         };
 
-        Self::handle_events(renderer, game_thread, event_loop)
+        Self::handle_events(
+            renderer,
+            game_thread,
+            event_loop,
+            #[cfg(feature = "egui")]
+            self.egui.as_mut(),
+        )
     }
 }
 
@@ -246,8 +331,28 @@ impl<S: AppState> ApplicationHandler for App<S> {
             .expect("runtime missing for window");
         let drives_update = target_id.is_primary();
 
+        // egui only draws over the primary window
+        #[cfg(feature = "egui")]
+        let mut egui = self.egui.as_mut().filter(|_| drives_update);
+        #[cfg(feature = "egui")]
+        let egui_consumed = match (egui.as_deref_mut(), renderer.window(target_id)) {
+            (Some(egui), Some(window)) => egui.on_window_event(window, &event),
+            _ => false,
+        };
+
         match event {
             WindowEvent::RedrawRequested => {
+                #[cfg(feature = "egui")]
+                if let Some(egui) = egui
+                    && let Some(window) = renderer.window(target_id)
+                    && game_thread
+                        .egui_input(target_id, egui.take_input(window))
+                        .is_err()
+                {
+                    event_loop.exit();
+                    return;
+                }
+
                 if drives_update {
                     match game_thread.next_frame(target_id) {
                         Ok(()) => {
@@ -277,6 +382,13 @@ impl<S: AppState> ApplicationHandler for App<S> {
                     event_loop.exit();
                 }
             }
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                if game_thread.scale_factor(target_id, scale_factor).is_err() {
+                    event_loop.exit();
+                }
+            }
+            #[cfg(feature = "egui")]
+            _ if egui_consumed && egui_may_consume(&event) => {}
             _ => {
                 if game_thread.input(target_id, event).is_err() {
                     event_loop.exit();
@@ -308,3 +420,20 @@ impl<S: AppState> ApplicationHandler for App<S> {
         }
     }
 }
+
+/// Returns true for the input egui can take away from the world. Releases and focus changes
+/// always reach the world, so keys and buttons that were held before egui took over get released.
+#[cfg(feature = "egui")]
+fn egui_may_consume(event: &WindowEvent) -> bool {
+    use winit::event::TouchPhase;
+
+    match event {
+        WindowEvent::KeyboardInput { event, .. } => event.state.is_pressed(),
+        WindowEvent::MouseInput { state, .. } => state.is_pressed(),
+        WindowEvent::Touch(touch) => matches!(touch.phase, TouchPhase::Started | TouchPhase::Moved),
+        WindowEvent::CursorMoved { .. } | WindowEvent::MouseWheel { .. } | WindowEvent::Ime(_) => {
+            true
+        }
+        _ => false,
+    }
+}
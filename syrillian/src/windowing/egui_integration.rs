@@ -0,0 +1,44 @@
+//! Feeds the events of the primary window into egui, and applies what egui wants from the
+//! window. The interface itself is built on the game thread, see
+//! [`AppState::ui`](crate::AppState::ui).
+
+use egui::{Context, PlatformOutput, RawInput, ViewportId};
+use winit::event::WindowEvent;
+use winit::window::Window;
+
+pub(crate) struct EguiIntegration {
+    state: egui_winit::State,
+}
+
+impl EguiIntegration {
+    pub(crate) fn new(window: &Window, max_texture_side: usize) -> Self {
+        let state = egui_winit::State::new(
+            Context::default(),
+            ViewportId::ROOT,
+            window,
+            Some(window.scale_factor() as f32),
+            None,
+            Some(max_texture_side),
+        );
+
+        EguiIntegration { state }
+    }
+
+    pub(crate) fn context(&self) -> &Context {
+        self.state.egui_ctx()
+    }
+
+    /// Returns whether egui used the event, e.g. a click on a widget, so the world shouldn't get it
+    pub(crate) fn on_window_event(&mut self, window: &Window, event: &WindowEvent) -> bool {
+        self.state.on_window_event(window, event).consumed
+    }
+
+    /// Takes the input gathered since the previous frame
+    pub(crate) fn take_input(&mut self, window: &Window) -> RawInput {
+        self.state.take_egui_input(window)
+    }
+
+    pub(crate) fn handle_output(&mut self, window: &Window, output: PlatformOutput) {
+        self.state.handle_platform_output(window, output);
+    }
+}
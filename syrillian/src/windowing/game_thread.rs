@@ -1,11 +1,15 @@
 use crate::assets::AssetStore;
+#[cfg(feature = "egui")]
+use crate::rendering::message::{EguiPaint, RenderMsg};
 use crate::world::{World, WorldChannels};
 use crate::{AppState, RenderTargetId};
 use crossbeam_channel::{Receiver, SendError, Sender, TryRecvError, unbounded};
 use std::sync::Arc;
 use tracing::{debug, error, info, instrument};
+use web_time::Duration;
 use winit::dpi::PhysicalSize;
 use winit::event::{DeviceEvent, DeviceId, WindowEvent};
+use winit::window::{CursorIcon, Icon};
 
 #[cfg(not(target_arch = "wasm32"))]
 use std::marker::PhantomData;
@@ -24,16 +28,41 @@ pub enum RenderAppEvent {
     DeviceEvent(DeviceId, DeviceEvent),
     StartFrame(RenderEventTarget),
     Resize(RenderEventTarget, PhysicalSize<u32>),
+    ScaleFactor(RenderEventTarget, f64),
+    /// The egui context the main thread feeds input into
+    #[cfg(feature = "egui")]
+    EguiInit(egui::Context),
+    /// The egui input gathered since the previous frame
+    #[cfg(feature = "egui")]
+    EguiInput(RenderEventTarget, egui::RawInput),
 }
 
 #[derive(Debug, Clone)]
 pub enum GameAppEvent {
     UpdateWindowTitle(RenderTargetId, String),
     SetCursorMode(RenderTargetId, bool, bool),
+    SetCursorConfined(RenderTargetId, bool),
+    SetCursorIcon(RenderTargetId, CursorIcon),
+    SetCursorVisible(RenderTargetId, bool),
+    SetWindowIcon(RenderTargetId, Icon),
     AddWindow(RenderTargetId, PhysicalSize<u32>),
+    #[cfg(feature = "egui")]
+    EguiOutput(RenderTargetId, EguiOutput),
     Shutdown,
 }
 
+/// What egui wants from the window after a frame, like a cursor icon or copied text
+#[cfg(feature = "egui")]
+#[derive(Clone)]
+pub struct EguiOutput(pub egui::PlatformOutput);
+
+#[cfg(feature = "egui")]
+impl std::fmt::Debug for EguiOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EguiOutput").finish_non_exhaustive()
+    }
+}
+
 impl GameAppEvent {
     pub fn cursor_mode(target: RenderTargetId, locked: bool, visible: bool) -> GameAppEvent {
         Self::SetCursorMode(target, locked, visible)
@@ -54,10 +83,16 @@ pub struct GameThread<S: AppState> {
     render_event_tx: Sender<RenderAppEvent>,
 }
 
-struct GameThreadInner<S: AppState> {
+pub(crate) struct GameThreadInner<S: AppState> {
     world: Box<World>,
     state: S,
     render_event_rx: Receiver<RenderAppEvent>,
+    /// Ticks the world by this instead of the measured frame time, see [`World::tick`]
+    fixed_delta: Option<Duration>,
+    #[cfg(feature = "egui")]
+    egui: Option<egui::Context>,
+    #[cfg(feature = "egui")]
+    egui_input: egui::RawInput,
 }
 
 impl<S: AppState> GameThreadInner<S> {
@@ -74,7 +109,7 @@ impl<S: AppState> GameThreadInner<S> {
         })
     }
 
-    fn spawn_local(
+    pub(crate) fn spawn_local(
         state: S,
         asset_store: Arc<AssetStore>,
         channels: WorldChannels,
@@ -86,6 +121,11 @@ impl<S: AppState> GameThreadInner<S> {
             world,
             state,
             render_event_rx,
+            fixed_delta: None,
+            #[cfg(feature = "egui")]
+            egui: None,
+            #[cfg(feature = "egui")]
+            egui_input: egui::RawInput::default(),
         }
     }
 }
@@ -153,6 +193,40 @@ impl<S: AppState> GameThread<S> {
             .map_err(Box::new)
     }
 
+    pub fn scale_factor(
+        &self,
+        target: RenderTargetId,
+        scale_factor: f64,
+    ) -> Result<(), Box<SendError<RenderAppEvent>>> {
+        self.render_event_tx
+            .send(RenderAppEvent::ScaleFactor(
+                RenderEventTarget { id: target },
+                scale_factor,
+            ))
+            .map_err(Box::new)
+    }
+
+    #[cfg(feature = "egui")]
+    pub fn egui_init(&self, ctx: egui::Context) -> Result<(), Box<SendError<RenderAppEvent>>> {
+        self.render_event_tx
+            .send(RenderAppEvent::EguiInit(ctx))
+            .map_err(Box::new)
+    }
+
+    #[cfg(feature = "egui")]
+    pub fn egui_input(
+        &self,
+        target: RenderTargetId,
+        input: egui::RawInput,
+    ) -> Result<(), Box<SendError<RenderAppEvent>>> {
+        self.render_event_tx
+            .send(RenderAppEvent::EguiInput(
+                RenderEventTarget { id: target },
+                input,
+            ))
+            .map_err(Box::new)
+    }
+
     // TODO: Think about if render frame and world should be linked
     #[instrument(skip_all)]
     pub fn next_frame(&self, target: RenderTargetId) -> Result<(), Box<SendError<RenderAppEvent>>> {
@@ -210,6 +284,34 @@ impl<S: AppState> GameThread<S> {
         ))
     }
 
+    pub fn scale_factor(
+        &self,
+        target: RenderTargetId,
+        scale_factor: f64,
+    ) -> Result<(), SendError<RenderAppEvent>> {
+        self.render_event_tx.send(RenderAppEvent::ScaleFactor(
+            RenderEventTarget { id: target },
+            scale_factor,
+        ))
+    }
+
+    #[cfg(feature = "egui")]
+    pub fn egui_init(&self, ctx: egui::Context) -> Result<(), SendError<RenderAppEvent>> {
+        self.render_event_tx.send(RenderAppEvent::EguiInit(ctx))
+    }
+
+    #[cfg(feature = "egui")]
+    pub fn egui_input(
+        &self,
+        target: RenderTargetId,
+        input: egui::RawInput,
+    ) -> Result<(), SendError<RenderAppEvent>> {
+        self.render_event_tx.send(RenderAppEvent::EguiInput(
+            RenderEventTarget { id: target },
+            input,
+        ))
+    }
+
     // TODO: Think about if render frame and world should be linked
     pub fn next_frame(&self, target: RenderTargetId) -> Result<(), SendError<RenderAppEvent>> {
         self.render_event_tx
@@ -249,12 +351,26 @@ impl<S: AppState> GameThreadInner<S> {
                 }
                 RenderAppEvent::Input(target, event) => self.input(target.id, event),
                 RenderAppEvent::Resize(target, size) => self.resize(target.id, size),
+                RenderAppEvent::ScaleFactor(target, scale_factor) => {
+                    self.world.set_scale_factor(target.id, scale_factor);
+                    true
+                }
                 RenderAppEvent::StartFrame(target) => {
                     self.world.input.set_active_target(target.id);
                     update_signaled = true;
                     true
                 }
                 RenderAppEvent::DeviceEvent(id, event) => self.device_event(id, &event),
+                #[cfg(feature = "egui")]
+                RenderAppEvent::EguiInit(ctx) => {
+                    self.egui = Some(ctx);
+                    true
+                }
+                #[cfg(feature = "egui")]
+                RenderAppEvent::EguiInput(_, input) => {
+                    self.egui_input.append(input);
+                    true
+                }
             };
 
             if !keep_running {
@@ -287,6 +403,17 @@ impl<S: AppState> GameThreadInner<S> {
         self.world.input.set_active_target(target);
         self.world.input.process_event(target, &event);
 
+        let world = self.world.as_mut();
+        let result = match &event {
+            WindowEvent::DroppedFile(path) => self.state.file_dropped(world, path),
+            WindowEvent::HoveredFile(path) => self.state.file_hovered(world, path),
+            WindowEvent::HoveredFileCancelled => self.state.file_hover_cancelled(world),
+            _ => Ok(()),
+        };
+        if let Err(e) = result {
+            error!("Error happened when calling file drop function hook: {e}");
+        }
+
         true
     }
 
@@ -302,6 +429,11 @@ impl<S: AppState> GameThreadInner<S> {
         true
     }
 
+    /// Advances the world by exactly `delta` per update, instead of the real time between them
+    pub fn set_fixed_delta(&mut self, delta: Duration) {
+        self.fixed_delta = Some(delta);
+    }
+
     // TODO: Think about if renderer delta time should be linked to world tick time
     pub fn update(&mut self) -> bool {
         let world = self.world.as_mut();
@@ -314,17 +446,163 @@ impl<S: AppState> GameThreadInner<S> {
             error!("Error happened when calling update function hook: {e}");
         }
 
-        world.fixed_update();
+        match self.fixed_delta {
+            Some(delta) => world.tick_fixed_update(delta),
+            None => world.fixed_update(),
+        }
         world.update();
 
         if let Err(e) = self.state.late_update(world) {
             error!("Error happened when calling late update function hook: {e}");
         }
 
+        #[cfg(feature = "egui")]
+        self.run_egui();
+
+        let world = self.world.as_mut();
         world.post_update();
 
-        world.next_frame();
+        if self.fixed_delta.is_some() {
+            world.finish_tick();
+        } else {
+            world.next_frame();
+        }
 
         true
     }
+
+    /// Runs [`AppState::ui`] with the input gathered since the last frame, and hands the output
+    /// to the renderer and the window
+    #[cfg(feature = "egui")]
+    fn run_egui(&mut self) {
+        let Some(ctx) = &self.egui else {
+            return;
+        };
+
+        let input = std::mem::take(&mut self.egui_input);
+        let world = self.world.as_mut();
+        let state = &mut self.state;
+        let output = ctx.run(input, |ctx| {
+            if let Err(e) = state.ui(ctx, world) {
+                error!("Error happened when calling ui function hook: {e}");
+            }
+        });
+
+        let primitives = ctx.tessellate(output.shapes, output.pixels_per_point);
+        let _ = world
+            .channels
+            .render_tx
+            .send(RenderMsg::UpdateEgui(EguiPaint {
+                primitives,
+                textures_delta: output.textures_delta,
+                pixels_per_point: output.pixels_per_point,
+            }));
+        let _ = world.channels.game_event_tx.send(GameAppEvent::EguiOutput(
+            RenderTargetId::PRIMARY,
+            EguiOutput(output.platform_output),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+    use std::path::{Path, PathBuf};
+
+    #[derive(Default)]
+    struct CountFrames {
+        frames: u32,
+    }
+
+    impl AppState for CountFrames {
+        fn update(&mut self, _world: &mut World) -> Result<(), Box<dyn Error>> {
+            self.frames += 1;
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum FileEvent {
+        Hovered(PathBuf),
+        Cancelled,
+        Dropped(PathBuf),
+    }
+
+    #[derive(Default)]
+    struct RecordFiles {
+        events: Vec<FileEvent>,
+    }
+
+    impl AppState for RecordFiles {
+        fn file_dropped(&mut self, _world: &mut World, path: &Path) -> Result<(), Box<dyn Error>> {
+            self.events.push(FileEvent::Dropped(path.to_path_buf()));
+            // errors are only logged, they don't stop the game
+            Err("can't open this file".into())
+        }
+
+        fn file_hovered(&mut self, _world: &mut World, path: &Path) -> Result<(), Box<dyn Error>> {
+            self.events.push(FileEvent::Hovered(path.to_path_buf()));
+            Ok(())
+        }
+
+        fn file_hover_cancelled(&mut self, _world: &mut World) -> Result<(), Box<dyn Error>> {
+            self.events.push(FileEvent::Cancelled);
+            Ok(())
+        }
+    }
+
+    fn spawn_test_game<S: AppState>() -> GameThreadInner<S> {
+        let (render_tx, _) = unbounded();
+        let (game_event_tx, _) = unbounded();
+        let (_, feedback_rx) = unbounded();
+        let (_, render_event_rx) = unbounded();
+        let channels = WorldChannels::new(render_tx, game_event_tx, feedback_rx);
+        GameThreadInner::spawn_local(S::default(), AssetStore::new(), channels, render_event_rx)
+    }
+
+    #[test]
+    fn fixed_delta_frames_advance_the_world_time_exactly() {
+        let delta = Duration::from_millis(20);
+        let mut game = spawn_test_game::<CountFrames>();
+        game.set_fixed_delta(delta);
+        assert!(game.init());
+
+        for _ in 0..30 {
+            assert!(game.update());
+        }
+
+        assert_eq!(game.state.frames, 30);
+        assert_eq!(game.world.time(), delta * 30);
+        assert_eq!(game.world.delta_time(), delta);
+    }
+
+    #[test]
+    fn file_drops_reach_the_app_state() {
+        let mut game = spawn_test_game::<RecordFiles>();
+        assert!(game.init());
+
+        let (a, b) = (PathBuf::from("a.glb"), PathBuf::from("b.png"));
+        let events = [
+            WindowEvent::HoveredFile(a.clone()),
+            WindowEvent::HoveredFile(b.clone()),
+            WindowEvent::HoveredFileCancelled,
+            WindowEvent::HoveredFile(a.clone()),
+            WindowEvent::DroppedFile(a.clone()),
+        ];
+        for event in events {
+            assert!(game.input(RenderTargetId::PRIMARY, event));
+        }
+
+        assert_eq!(
+            game.state.events,
+            [
+                FileEvent::Hovered(a.clone()),
+                FileEvent::Hovered(b),
+                FileEvent::Cancelled,
+                FileEvent::Hovered(a.clone()),
+                FileEvent::Dropped(a),
+            ]
+        );
+    }
 }
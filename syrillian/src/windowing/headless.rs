@@ -0,0 +1,81 @@
+//! Running an app without a window.
+//!
+//! The primary render target draws into a texture instead of a swapchain, and the game and
+//! renderer are driven in lockstep on the calling thread for a fixed number of frames. Both
+//! advance by the same fixed [delta](HeadlessSettings::delta) each frame instead of following the
+//! wall clock, which makes frames reproducible, e.g. for golden image tests in CI.
+
+use crate::AppState;
+use crate::assets::AssetStore;
+use crate::rendering::Renderer;
+use crate::windowing::RenderTargetId;
+use crate::windowing::game_thread::GameThreadInner;
+use crate::world::WorldChannels;
+use crossbeam_channel::unbounded;
+use image::RgbaImage;
+use std::error::Error;
+use std::marker::PhantomData;
+use tracing::{info, trace};
+use web_time::Duration;
+use winit::dpi::PhysicalSize;
+
+/// Settings for a windowless run, see [`AppRuntime::headless`](crate::AppRuntime::headless)
+pub struct HeadlessSettings<S: AppState> {
+    /// The size of the primary render target, in pixels
+    pub size: PhysicalSize<u32>,
+    /// How many frames are updated and rendered before the run ends
+    pub frames: u32,
+    /// The time that passes in the world and the renderer each frame
+    pub delta: Duration,
+    pub(crate) _state_type: PhantomData<S>,
+}
+
+impl<S: AppState> HeadlessSettings<S> {
+    /// Runs the app for the configured number of frames and returns the last rendered frame.
+    ///
+    /// The run ends early if the world shuts down. Requests meant for windows, like titles or
+    /// cursor changes, are ignored.
+    pub fn run(self) -> Result<RgbaImage, Box<dyn Error>> {
+        info!("Initializing headless render state");
+
+        let asset_store = AssetStore::new();
+
+        let (render_state_tx, render_state_rx) = unbounded();
+        let (game_event_tx, game_event_rx) = unbounded();
+        let (feedback_tx, feedback_rx) = unbounded();
+        let (_render_event_tx, render_event_rx) = unbounded();
+
+        let mut renderer =
+            Renderer::new_headless(render_state_rx, feedback_tx, self.size, asset_store.clone())?;
+
+        let channels = WorldChannels::new(render_state_tx, game_event_tx, feedback_rx);
+        let mut game =
+            GameThreadInner::<S>::spawn_local(S::default(), asset_store, channels, render_event_rx);
+
+        renderer.set_fixed_delta(self.delta);
+        game.set_fixed_delta(self.delta);
+        game.resize(RenderTargetId::PRIMARY, self.size);
+        if !game.init() {
+            return Err("Couldn't initialize the game state".into());
+        }
+
+        for frame in 0..self.frames {
+            if !game.update() {
+                trace!("World shut down after {frame} headless frames");
+                break;
+            }
+            game_event_rx.try_iter().for_each(drop);
+
+            renderer.handle_events();
+            renderer.update();
+            if !renderer.redraw(RenderTargetId::PRIMARY) {
+                return Err("Couldn't render headless frame".into());
+            }
+        }
+
+        // captures requested during the last frame read the frame that was just rendered
+        renderer.handle_events();
+
+        Ok(renderer.read_frame(RenderTargetId::PRIMARY)?)
+    }
+}
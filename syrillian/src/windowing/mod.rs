@@ -4,10 +4,14 @@
 //! application state management into a compact runtime that can be easily used.
 
 pub mod app;
+#[cfg(feature = "egui")]
+mod egui_integration;
 pub mod game_thread;
+pub mod headless;
 pub mod state;
 
 pub use app::*;
+pub use headless::*;
 pub use state::*;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
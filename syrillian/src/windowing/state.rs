@@ -1,7 +1,9 @@
-use crate::AppSettings;
 use crate::world::World;
+use crate::{AppSettings, HeadlessSettings};
 use std::error::Error;
 use std::marker::PhantomData;
+use std::path::Path;
+use web_time::Duration;
 use winit::dpi::{PhysicalSize, Size};
 use winit::window::WindowAttributes;
 
@@ -22,12 +24,39 @@ pub trait AppState: Sized + Default + 'static {
     fn destroy(&mut self, world: &mut World) -> Result<(), Box<dyn Error>> {
         Ok(())
     }
+
+    /// Called when a file is dropped onto a window
+    fn file_dropped(&mut self, world: &mut World, path: &Path) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a file is dragged over a window. Multiple files call this once per file.
+    fn file_hovered(&mut self, world: &mut World, path: &Path) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when the hovered files leave the window without being dropped
+    fn file_hover_cancelled(&mut self, world: &mut World) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Builds the egui interface drawn over the primary window, after
+    /// [`late_update`](Self::late_update). Input egui uses, like clicks on a widget, isn't passed
+    /// to the world.
+    #[cfg(feature = "egui")]
+    fn ui(&mut self, ctx: &egui::Context, world: &mut World) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
 }
 
 pub trait AppRuntime: AppState {
     fn configure(title: &str, width: u32, height: u32) -> AppSettings<Self>;
 
     fn default_config() -> AppSettings<Self>;
+
+    /// Renders `frames` frames into a `width`x`height` texture, without opening a window.
+    /// Each frame advances the world and the renderer by 1/60 of a second.
+    fn headless(width: u32, height: u32, frames: u32) -> HeadlessSettings<Self>;
 }
 
 impl<S: AppState> AppRuntime for S {
@@ -51,4 +80,13 @@ impl<S: AppState> AppRuntime for S {
             _state_type: PhantomData,
         }
     }
+
+    fn headless(width: u32, height: u32, frames: u32) -> HeadlessSettings<Self> {
+        HeadlessSettings {
+            size: PhysicalSize { width, height },
+            frames,
+            delta: Duration::from_secs(1) / 60,
+            _state_type: PhantomData,
+        }
+    }
 }
@@ -1,8 +1,11 @@
 use nalgebra::{Vector2, Vector3};
 use syrillian::assets::{
-    AssetStore, Font, HMaterial, HMesh, HShader, HTexture, Material, Mesh, Shader, Sound, Texture,
+    AssetStore, ComputeShader, CustomParamsError, DEFAULT_MSDF_RANGE, Font, HBGL, HFont, HMaterial,
+    HMesh, HShader, HTexture, MAX_CUSTOM_PARAMS, Material, MaterialTextureError, Mesh, Shader,
+    ShaderCode, ShaderIncludeError, ShaderType, ShaderValidationError, Sound, Texture,
 };
 use syrillian::core::Vertex3D;
+use syrillian::rendering::ComputeJob;
 
 #[test]
 fn test_predefined_meshes() {
@@ -52,6 +55,20 @@ fn test_mesh_store() {
     assert_eq!(retrieved_mesh.unwrap().vertex_count(), 3);
 }
 
+#[test]
+fn test_mesh_bounds() {
+    let store = AssetStore::new();
+    let cube = store.meshes.get(HMesh::UNIT_CUBE);
+
+    let (min, max) = cube.aabb();
+    assert_eq!(min, Vector3::new(-0.5, -0.5, -0.5));
+    assert_eq!(max, Vector3::new(0.5, 0.5, 0.5));
+
+    let sphere = cube.bounding_sphere();
+    assert_eq!(sphere.center, Vector3::zeros());
+    assert!((sphere.radius - 0.75f32.sqrt()).abs() < 1e-5);
+}
+
 #[test]
 fn test_shader_store() {
     let store = AssetStore::new();
@@ -85,6 +102,56 @@ fn test_material_store() {
     assert_eq!(retrieved_material.unwrap().name, "Test Material");
 }
 
+#[test]
+fn test_material_metallic_roughness() {
+    let material = Material::builder().name("Default PBR").build();
+    assert_eq!(material.metallic, 0.0);
+    assert_eq!(material.roughness, 0.5);
+    assert!(material.metallic_roughness_texture.is_none());
+
+    let material = Material::builder()
+        .name("Packed PBR")
+        .metallic(1.0)
+        .roughness(1.0)
+        .metallic_roughness_texture(HTexture::FALLBACK_ROUGHNESS)
+        .build();
+    assert_eq!(material.metallic, 1.0);
+    assert_eq!(
+        material.metallic_roughness_texture,
+        Some(HTexture::FALLBACK_ROUGHNESS)
+    );
+}
+
+#[test]
+fn test_material_texture_paths() {
+    let store = AssetStore::new();
+    let dir = std::env::temp_dir().join("syrillian_material_textures");
+    std::fs::create_dir_all(&dir).unwrap();
+    let diffuse = dir.join("diffuse.png");
+    image::RgbaImage::from_pixel(2, 2, image::Rgba([255, 0, 0, 255]))
+        .save(&diffuse)
+        .unwrap();
+
+    let material = Material::builder()
+        .name("Textured")
+        .diffuse_texture_path(&diffuse)
+        .normal_texture_path(&diffuse)
+        .build_in(&*store.textures)
+        .unwrap();
+
+    let diffuse_texture = store.textures.get(material.diffuse_texture.unwrap());
+    assert_eq!(diffuse_texture.width, 2);
+    assert_eq!(diffuse_texture.format, wgpu::TextureFormat::Bgra8UnormSrgb);
+    let normal_texture = store.textures.get(material.normal_texture.unwrap());
+    assert_eq!(normal_texture.format, wgpu::TextureFormat::Bgra8Unorm);
+
+    let missing = Material::builder()
+        .name("Missing")
+        .diffuse_texture_path(dir.join("missing.png"))
+        .build_in(&*store.textures);
+    assert!(matches!(missing, Err(MaterialTextureError::Read { .. })));
+}
+
 #[test]
 #[ignore]
 fn test_font_store() {
@@ -95,6 +162,32 @@ fn test_font_store() {
     assert!(retrieved_font.is_some());
 }
 
+#[test]
+fn test_font_msdf_range() {
+    let store = AssetStore::new();
+    let mut font = store.fonts.get(HFont::DEFAULT).clone();
+    assert_eq!(font.msdf_range(), DEFAULT_MSDF_RANGE);
+
+    font.set_msdf_range(8.0);
+    assert_eq!(font.msdf_range(), 8.0);
+    font.set_msdf_range(0.0);
+    assert_eq!(font.msdf_range(), 1.0);
+}
+
+#[test]
+fn test_font_fallbacks() {
+    let store = AssetStore::new();
+    let mut font = store.fonts.get(HFont::DEFAULT).clone();
+    assert!(font.fallbacks().is_empty());
+
+    font.set_fallbacks(vec![HFont::DEFAULT]);
+    let handle = store.fonts.add(font);
+    assert_eq!(
+        store.fonts.try_get(handle).unwrap().fallbacks(),
+        &[HFont::DEFAULT]
+    );
+}
+
 #[test]
 #[cfg(not(target_arch = "wasm32"))]
 fn test_sound_store() {
@@ -105,6 +198,23 @@ fn test_sound_store() {
     assert!(retrieved_sound.is_some());
 }
 
+#[test]
+#[cfg(not(target_arch = "wasm32"))]
+fn test_streamed_sound() {
+    let loaded = Sound::load_sound("examples/assets/pop.wav").expect("Failed to load sound");
+    let mut streamed =
+        Sound::stream_from_file("examples/assets/pop.wav").expect("Failed to stream sound");
+
+    assert!(streamed.is_streaming());
+    assert!(!loaded.is_streaming());
+    assert_eq!(streamed.duration(), loaded.duration());
+    assert!(streamed.inner().is_none());
+    assert!(streamed.sample_rate().is_none());
+
+    streamed.set_loop_region(..);
+    assert!(streamed.is_streaming());
+}
+
 #[test]
 #[ignore]
 fn test_find_font() {
@@ -131,6 +241,11 @@ fn test_predefined_shaders() {
     store.shaders.try_get(HShader::POST_PROCESS).unwrap();
     store.shaders.try_get(HShader::TEXT_2D).unwrap();
     store.shaders.try_get(HShader::TEXT_3D).unwrap();
+    store.shaders.try_get(HShader::OUTLINE).unwrap();
+    store.shaders.try_get(HShader::OUTLINE_ON_TOP).unwrap();
+    store.shaders.try_get(HShader::NINE_SLICE).unwrap();
+    store.shaders.try_get(HShader::AMBIENT_OCCLUSION).unwrap();
+    store.shaders.try_get(HShader::VIEWPORT_CLEAR).unwrap();
 
     #[cfg(debug_assertions)]
     {
@@ -213,3 +328,251 @@ fn test_iterate_assets() {
 
     assert!(count >= 7);
 }
+
+#[test]
+fn test_mesh_optimize_welds_vertices() {
+    let corner = |x: f32, z: f32| {
+        Vertex3D::basic(
+            Vector3::new(x, 0.0, z),
+            Vector2::new(x, z),
+            Vector3::new(0.0, 1.0, 0.0),
+        )
+    };
+    let nudged = Vertex3D {
+        position: Vector3::new(1.0 + 1e-7, 0.0, 1.0),
+        ..corner(1.0, 1.0)
+    };
+    let vertices = vec![
+        corner(0.0, 0.0),
+        corner(1.0, 1.0),
+        corner(1.0, 0.0),
+        corner(0.0, 0.0),
+        corner(0.0, 1.0),
+        nudged,
+    ];
+
+    let mut mesh = Mesh::builder(vertices).build();
+    mesh.optimize();
+
+    assert_eq!(mesh.vertex_count(), 4);
+    assert_eq!(mesh.indices(), Some(&[0, 1, 2, 0, 3, 1][..]));
+    assert_eq!(mesh.triangle_count(), 2);
+
+    let store = AssetStore::new();
+    assert!(!store.meshes.get(HMesh::UNIT_CUBE).has_indices());
+
+    store.meshes.optimize_builtins();
+    let cube = store.meshes.get(HMesh::UNIT_CUBE);
+    assert!(cube.has_indices());
+    assert_eq!(cube.vertex_count(), 24);
+}
+
+#[test]
+fn test_removed_handles_stay_invalid() {
+    let store = AssetStore::new();
+    let material = store
+        .materials
+        .add(Material::builder().name("Removed").build());
+    assert!(store.materials.contains(material));
+
+    store.materials.remove(material);
+    let replacement = store.materials.add(Material::builder().name("New").build());
+
+    assert!(!store.materials.contains(material));
+    assert!(store.materials.get_checked(material).is_none());
+    assert_ne!(material, replacement);
+    assert_eq!(
+        store.materials.get_checked(replacement).unwrap().name,
+        "New"
+    );
+
+    assert!(store.materials.contains(HMaterial::FALLBACK));
+    assert!(store.materials.remove(HMaterial::FALLBACK).is_none());
+    assert!(store.materials.contains(HMaterial::FALLBACK));
+}
+
+#[test]
+fn test_shader_includes() {
+    let store = AssetStore::new();
+    let includes = &store.shader_includes;
+    includes.register(
+        "test_tint",
+        "#include \"test_scale\"\nfn tint(c: vec4f) -> vec4f { return scale(c) * 0.5; }",
+    );
+    includes.register(
+        "test_scale",
+        "fn scale(c: vec4f) -> vec4f { return c * 2.0; }",
+    );
+
+    let shader = Shader::new_default(
+        "Includes",
+        "#include builtin:default_vertex3d\n#include \"test_tint\"\n@fragment fn fs_main() {}",
+    );
+    let code = shader
+        .with_includes(includes)
+        .unwrap()
+        .try_gen_code()
+        .unwrap();
+    assert!(code.contains("fn scale(c: vec4f)"));
+    assert!(code.find("fn scale").unwrap() < code.find("fn tint").unwrap());
+    assert!(code.contains("@vertex"));
+    assert!(!code.contains("#include"));
+
+    // snippets are only visible to the store they're registered in
+    assert!(matches!(
+        shader.try_gen_code(),
+        Err(ShaderIncludeError::Unknown { .. })
+    ));
+    let other = AssetStore::new();
+    assert!(shader.with_includes(&other.shader_includes).is_err());
+
+    let missing = Shader::new_default("Missing", "\n#include \"test_missing\"");
+    let error = missing.with_includes(includes).unwrap_err();
+    assert!(matches!(error, ShaderIncludeError::Unknown { line: 2, .. }));
+    assert!(error.to_string().starts_with("Missing:2:"));
+
+    includes.register("test_cycle_a", "#include \"test_cycle_b\"");
+    includes.register("test_cycle_b", "// loops back\n#include \"test_cycle_a\"");
+    let cyclic = Shader::new_default("Cyclic", "#include \"test_cycle_a\"");
+    let error = cyclic.with_includes(includes).unwrap_err();
+    let ShaderIncludeError::Cycle {
+        source_name,
+        line,
+        chain,
+    } = error
+    else {
+        panic!("expected an include cycle, got {error}");
+    };
+    assert_eq!(source_name, "test_cycle_b");
+    assert_eq!(line, 2);
+    assert_eq!(chain, ["test_cycle_a", "test_cycle_b", "test_cycle_a"]);
+
+    assert!(includes.unregister("test_cycle_a"));
+    assert!(!includes.unregister("test_cycle_a"));
+
+    let malformed = Shader::new_default("Malformed", "#include test_tint");
+    assert!(matches!(
+        malformed.with_includes(includes),
+        Err(ShaderIncludeError::Malformed { line: 1, .. })
+    ));
+}
+
+#[test]
+fn test_shader_validation() {
+    let store = AssetStore::new();
+
+    store.validate_shader(HShader::DIM3).unwrap();
+    store.validate_shader(HShader::POST_PROCESS).unwrap();
+    store.validate_shader(HShader::AMBIENT_OCCLUSION).unwrap();
+
+    let broken = Shader::new_fragment(
+        "Broken",
+        "@fragment fn fs_main() -> @location(0) vec4f {\n    return undefined_color;\n}",
+    );
+    let error = store.add_validated_shader(broken.clone()).unwrap_err();
+    let ShaderValidationError::Invalid { name, diagnostic } = &error else {
+        panic!("expected a validation error, got {error}");
+    };
+    assert_eq!(name, "Broken");
+    assert!(diagnostic.contains("undefined_color"));
+
+    let handle = store.shaders.add(broken);
+    assert!(store.validate_shader(handle).is_err());
+
+    let mut shader = Shader::new_default("Replaced", "");
+    let valid = include_str!("../src/engine/assets/shader/shaders/fallback_shader3d.wgsl");
+    let includes = &store.shader_includes;
+    shader.try_set_code(valid.to_string(), includes).unwrap();
+    assert!(
+        shader
+            .try_set_code("not wgsl".to_string(), includes)
+            .is_err()
+    );
+    assert_eq!(shader.code().code(), valid);
+
+    store.shaders.remove(handle);
+    assert!(matches!(
+        store.validate_shader(handle),
+        Err(ShaderValidationError::NotFound { .. })
+    ));
+}
+
+#[test]
+fn test_material_custom_params() {
+    let mut material = Material::builder().name("Custom").build();
+    assert_eq!(material.custom_params(), &[0.0; MAX_CUSTOM_PARAMS]);
+
+    material.set_custom_params(&[1.0, 0.5, 0.25]).unwrap();
+    assert_eq!(material.custom_params()[..4], [1.0, 0.5, 0.25, 0.0]);
+
+    let too_many = [1.0; MAX_CUSTOM_PARAMS + 1];
+    assert!(matches!(
+        material.set_custom_params(&too_many),
+        Err(CustomParamsError::TooMany { count }) if count == MAX_CUSTOM_PARAMS + 1
+    ));
+    assert_eq!(material.custom_params()[0], 1.0);
+
+    let custom = Shader::builder()
+        .shader_type(ShaderType::Custom)
+        .name("Custom Params")
+        .code(ShaderCode::Full("#use custom\n".to_string()))
+        .build();
+    assert!(custom.needs_bgl(HBGL::MATERIAL));
+    assert!(custom.gen_code().contains("fn custom_param(index: u32)"));
+    assert!(
+        Shader::new_fragment("Default", "")
+            .gen_code()
+            .contains("fn custom_param")
+    );
+}
+
+#[test]
+fn test_globals_in_every_shader() {
+    let fragment = Shader::new_fragment(
+        "Animated",
+        "@fragment fn fs_main() -> @location(0) vec4f {\n    return vec4f(sin(globals.time), globals.resolution / 1000.0, 1.0);\n}",
+    );
+    fragment.validate().unwrap();
+
+    let post = Shader::new_post_process("Post", "");
+    assert!(post.gen_code().contains("var<uniform> globals: Globals"));
+}
+
+#[test]
+fn test_compute_shaders() {
+    const DOUBLE: &str = "
+@group(0) @binding(0) var<storage, read_write> values: array<f32>;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x < arrayLength(&values) {
+        values[id.x] *= 2.0;
+    }
+}
+";
+
+    let store = AssetStore::new();
+
+    let shader = ComputeShader::new("Double", DOUBLE);
+    assert_eq!(shader.entry_point(), "main");
+    shader.validate().unwrap();
+    let handle = store.compute.add(shader);
+    assert_eq!(store.compute.try_get(handle).unwrap().name(), "Double");
+    assert!(!handle.is_builtin());
+
+    let broken = ComputeShader::builder()
+        .name("Broken")
+        .code("@compute @workgroup_size(1) fn run() { missing(); }")
+        .entry_point("run")
+        .build();
+    assert!(matches!(
+        broken.validate(),
+        Err(ShaderValidationError::Invalid { .. })
+    ));
+
+    let job = ComputeJob::new(handle, 4, 1, 1).storage_read_back(&[1.0f32; 256]);
+    assert_eq!(job.workgroups(), [4, 1, 1]);
+    let readback = job.readback();
+    assert!(readback.is_pending());
+    assert!(readback.try_take().is_none());
+}
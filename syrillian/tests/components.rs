@@ -1,8 +1,23 @@
-use nalgebra::Vector3;
+use nalgebra::{Affine3, Isometry3, Matrix4, UnitQuaternion, Vector2, Vector3, Vector4};
+use rapier3d::prelude::{Ball, ColliderBuilder, QueryFilter};
+use slotmap::Key;
 use std::any::TypeId;
+use std::cell::RefCell;
+use std::f32::consts::FRAC_PI_2;
 use syrillian::World;
-use syrillian::components::{Component, NewComponent};
+use syrillian::assets::{HMaterial, HMesh, Material};
+use syrillian::components::mesh_renderer::{BoneData, Outline};
+use syrillian::components::{
+    Billboard, BillboardMode, CameraComponent, CameraKeyframe, CameraTrack, CameraTrackFinished,
+    Collider3D, Component, Decal, Falloff, FollowComponent, FollowSpace, ForceField,
+    ForceFieldMode, ForceFieldShape, GravityComponent, InspectValue, LightAnimation,
+    LightAnimationPreset, LightAnimator, MeshRenderer, NewComponent, ParallelContext,
+    ParallelUpdate, ParticleEmitter, PointLightComponent, RigidBodyComponent, Text3D,
+};
 use syrillian::core::GameObjectId;
+use syrillian::prefabs::{CubePrefab, SpherePrefab};
+use syrillian::rendering::RenderMsg;
+use web_time::Duration;
 
 struct MyComponent {
     parent: GameObjectId,
@@ -78,3 +93,1099 @@ fn check_typed() {
 
     assert_eq!(world.components.values().count(), 0);
 }
+
+#[test]
+fn mesh_renderer_world_bounds() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut obj = world.new_object("Mesh");
+    assert!(obj.world_bounds().is_none());
+
+    obj.add_component::<MeshRenderer>();
+    obj.transform.set_position(2.0, 0.0, 0.0);
+    obj.transform.set_scale(2.0);
+
+    let bounds = obj.world_bounds().unwrap();
+    assert!((bounds.center - Vector3::new(2.0, 0.0, 0.0)).norm() < 1e-5);
+    assert!((bounds.radius - 2.0 * 0.75f32.sqrt()).abs() < 1e-5);
+}
+
+#[test]
+fn camera_frames_object() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut target = world.new_object("Target");
+    target.add_component::<MeshRenderer>();
+    target.transform.set_position(0.0, 0.0, -20.0);
+
+    let mut cam_obj = world.new_object("Camera");
+    let mut camera = cam_obj.add_component::<CameraComponent>();
+
+    assert!(camera.frame_object(target, 0.1));
+    let bounds = target.world_bounds().unwrap();
+    let offset = bounds.center - cam_obj.transform.position();
+    assert!(offset.normalize().dot(&cam_obj.transform.forward()) > 0.999);
+    assert!(offset.norm() > bounds.radius + camera.near());
+
+    let half_fov = (camera.fov().to_radians() * 0.5)
+        .min(((camera.fov().to_radians() * 0.5).tan() * 800.0 / 600.0).atan());
+    let fitted = bounds.radius * 1.1 / offset.norm();
+    assert!((fitted - half_fov.sin()).abs() < 1e-4);
+
+    let empty = world.new_object("Empty");
+    assert!(!camera.frame_object(empty, 0.1));
+}
+
+#[test]
+fn camera_world_screen_round_trip() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut cam_obj = world.new_object("Camera");
+    let camera = cam_obj.add_component::<CameraComponent>();
+    cam_obj.transform.set_position(3.0, 2.0, 10.0);
+    cam_obj.transform.set_euler_rotation_deg(0.0, 30.0, 0.0);
+
+    let ahead = cam_obj.transform.position() + cam_obj.transform.forward() * 5.0;
+    let center = camera.world_to_screen(ahead).unwrap();
+    assert!((center - Vector2::new(400.0, 300.0)).norm() < 1e-2);
+
+    let point = ahead + cam_obj.transform.right() * 1.0 + cam_obj.transform.up() * 0.5;
+    let screen = camera.world_to_screen(point).unwrap();
+    assert!(screen.x > 400.0 && screen.y < 300.0);
+
+    let ray = camera.screen_to_ray(screen);
+    let to_point = (point - ray.origin.coords).normalize();
+    assert!(ray.dir.normalize().dot(&to_point) > 0.9999);
+
+    let behind = cam_obj.transform.position() - cam_obj.transform.forward() * 5.0;
+    assert!(camera.world_to_screen(behind).is_none());
+}
+
+#[test]
+fn mesh_renderer_lods_switch_with_hysteresis() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut camera = world.new_camera();
+    camera.parent().transform.set_position(0.0, 0.0, 0.0);
+
+    let mut target = world.new_object("Target");
+    world.add_child(target);
+    let mut renderer = target.add_component::<MeshRenderer>();
+    renderer.add_lod(10.0, HMesh::SPHERE);
+    renderer.set_cull_distance(Some(50.0));
+
+    let radius = renderer_radius(&world, &renderer);
+    let mut lod_at = |world: &mut World, distance: f32| {
+        target
+            .transform
+            .set_position(0.0, 0.0, -(distance + radius));
+        world.post_update();
+        (renderer.lod_level(), renderer.is_culled())
+    };
+
+    assert_eq!(lod_at(&mut world, 5.0), (0, false));
+    assert_eq!(lod_at(&mut world, 10.5), (0, false));
+    assert_eq!(lod_at(&mut world, 11.5), (1, false));
+    assert_eq!(renderer.lod_mesh(), HMesh::SPHERE);
+    assert_eq!(lod_at(&mut world, 9.5), (1, false));
+    assert_eq!(lod_at(&mut world, 8.5), (0, false));
+    assert_eq!(lod_at(&mut world, 60.0), (1, true));
+    assert_eq!(lod_at(&mut world, 48.0), (1, true));
+    assert_eq!(lod_at(&mut world, 40.0), (1, false));
+}
+
+fn renderer_radius(world: &World, renderer: &MeshRenderer) -> f32 {
+    world
+        .assets
+        .meshes
+        .get(renderer.mesh())
+        .bounding_sphere()
+        .radius
+}
+
+#[test]
+fn orbit_camera_places_camera_on_orbit() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut cam_obj = world.new_object("Orbit Camera");
+    world.add_child(cam_obj);
+    let mut orbit = cam_obj.add_component::<OrbitCameraController>();
+
+    orbit.set_min_distance(2.0);
+    orbit.set_distance(1.0);
+    assert_eq!(orbit.distance(), 2.0);
+
+    orbit.set_max_distance(10.0);
+    orbit.set_distance(4.0);
+    orbit.set_target(Vector3::new(1.0, 2.0, 3.0));
+    orbit.set_pitch_limits(-10.0, 45.0);
+    orbit.pitch = 80.0;
+    orbit.yaw = 30.0;
+
+    world.update();
+
+    assert_eq!(orbit.pitch, 45.0);
+    let offset = cam_obj.transform.position() - orbit.target();
+    assert!((offset.norm() - 4.0).abs() < 1e-4);
+    assert!(offset.y > 0.0);
+    assert!(offset.normalize().dot(&-cam_obj.transform.forward()) > 0.999);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn collider_outlines() {
+    use rapier3d::prelude::SharedShape;
+    use syrillian::rendering::shape_outline;
+
+    let cuboid = shape_outline(&SharedShape::cuboid(1.0, 2.0, 3.0));
+    assert_eq!(cuboid.len(), 12);
+    for [a, b] in cuboid {
+        assert_eq!((a - b).iter().filter(|c| c.abs() > f32::EPSILON).count(), 1);
+    }
+
+    let ball = shape_outline(&SharedShape::ball(2.0));
+    assert!(!ball.is_empty());
+    for [a, b] in ball {
+        assert!((a.coords.norm() - 2.0).abs() < 1e-4);
+        assert!((b.coords.norm() - 2.0).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn decal_fade_out() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut obj = world.new_object("Decal");
+    let mut decal = obj.add_component::<Decal>();
+
+    decal.set_alpha(0.5);
+    assert_eq!(decal.opacity(), 0.5);
+    assert!(!decal.is_faded());
+
+    decal.fade_out(Duration::from_secs(60), Duration::from_secs(1));
+    assert_eq!(decal.opacity(), 0.5);
+    assert!(!decal.is_faded());
+
+    decal.fade_out(Duration::ZERO, Duration::ZERO);
+    assert!(decal.is_faded());
+    assert_eq!(decal.opacity(), 0.0);
+
+    decal.cancel_fade();
+    assert_eq!(decal.opacity(), 0.5);
+}
+
+#[test]
+fn particle_emitter_burst_and_lifetime() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut obj = world.new_object("Emitter");
+    let mut emitter = obj.add_component::<ParticleEmitter>();
+
+    emitter.set_looping(false);
+    emitter.duration = 0.0;
+    emitter.lifetime = 1.0;
+
+    emitter.burst(25);
+    emitter.simulate(0.1);
+    assert_eq!(emitter.particle_count(), 25);
+    assert!(!emitter.is_emitting());
+
+    emitter.simulate(0.5);
+    assert_eq!(emitter.particle_count(), 25);
+
+    emitter.simulate(0.5);
+    assert_eq!(emitter.particle_count(), 0);
+
+    emitter.max_particles = 10;
+    emitter.burst(25);
+    emitter.simulate(0.1);
+    assert_eq!(emitter.particle_count(), 10);
+}
+
+#[test]
+fn particle_emitter_emits_at_rate() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut obj = world.new_object("Emitter");
+    let mut emitter = obj.add_component::<ParticleEmitter>();
+
+    emitter.emission_rate = 10.0;
+    emitter.lifetime = 100.0;
+
+    for _ in 0..10 {
+        emitter.simulate(0.1);
+    }
+    assert_eq!(emitter.particle_count(), 10);
+}
+
+#[test]
+fn billboard_faces_camera_and_keeps_scale() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut obj = world.new_object("Billboard");
+    let mut billboard = obj.add_component::<Billboard>();
+
+    let position = Vector3::new(1.0, 0.0, 0.0);
+    let local_to_world = Affine3::from_matrix_unchecked(
+        Matrix4::new_translation(&position) * Matrix4::new_scaling(2.0),
+    );
+    let camera = Vector3::new(1.0, 5.0, 5.0);
+
+    let full = billboard.facing_transform(&local_to_world, camera, Vector3::y());
+    let facing = full.matrix().fixed_view::<3, 1>(0, 2).into_owned();
+    assert!((facing.norm() - 2.0).abs() < 1e-5);
+    assert!((facing.normalize() - (camera - position).normalize()).norm() < 1e-5);
+    assert!((full.matrix().column(3).xyz() - position).norm() < 1e-5);
+
+    billboard.set_mode(BillboardMode::YAxisLocked);
+    let locked = billboard.facing_transform(&local_to_world, camera, Vector3::y());
+    let facing = locked.matrix().fixed_view::<3, 1>(0, 2).normalize();
+    assert!((facing - Vector3::z()).norm() < 1e-5);
+}
+
+#[test]
+fn billboards_only_resend_changed_facing() {
+    let (mut world, render_rx, _rx2, _pick_tx) = World::fresh();
+    let camera = world.new_camera();
+    let mut obj = world.new_object("Billboard");
+    world.add_child(obj);
+    let billboard = obj.add_component::<Billboard>();
+    let id = billboard.typed_id();
+
+    let sent_transforms = |world: &mut World| {
+        world.post_update();
+        world.next_frame();
+        render_rx
+            .try_iter()
+            .flat_map(|msg| match msg {
+                RenderMsg::CommandBatch(batch) => batch,
+                msg => vec![msg],
+            })
+            .filter(|msg| matches!(msg, RenderMsg::UpdateTransform(cid, _) if *cid == id))
+            .count()
+    };
+
+    // the real transform, then the facing on top
+    assert_eq!(sent_transforms(&mut world), 2);
+    assert_eq!(sent_transforms(&mut world), 0);
+
+    let mut cam_obj = camera.parent();
+    cam_obj.transform.set_position(0.0, 2.0, 5.0);
+    assert_eq!(sent_transforms(&mut world), 1);
+    assert_eq!(sent_transforms(&mut world), 0);
+
+    obj.transform.set_position(1.0, 0.0, 0.0);
+    assert_eq!(sent_transforms(&mut world), 2);
+}
+
+#[derive(Default)]
+struct UpdateCounter {
+    updates: usize,
+    active_changes: Vec<bool>,
+}
+
+impl Component for UpdateCounter {
+    fn update(&mut self, _world: &mut World) {
+        self.updates += 1;
+    }
+
+    fn on_active_changed(&mut self, _world: &mut World, active: bool) {
+        self.active_changes.push(active);
+    }
+}
+
+#[test]
+fn inactive_objects_skip_updates() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut parent = world.new_object("Parent");
+    let mut child = world.new_object("Child");
+    world.add_child(parent);
+    parent.add_child(child);
+
+    let counter = child.add_component::<UpdateCounter>();
+    world.update();
+    assert_eq!(counter.updates, 1);
+
+    parent.set_active(false);
+    assert!(!parent.is_active());
+    assert!(child.is_active());
+    assert!(!child.active_in_hierarchy());
+    world.update();
+    assert_eq!(counter.updates, 1);
+
+    parent.set_active(true);
+    assert!(child.active_in_hierarchy());
+    world.update();
+    assert_eq!(counter.updates, 2);
+    assert_eq!(counter.active_changes, vec![false, true]);
+
+    let mut inactive = world.new_object("Inactive");
+    world.add_child(inactive);
+    inactive.set_active(false);
+    inactive.add_child(child);
+    assert!(!child.active_in_hierarchy());
+    world.update();
+    assert_eq!(counter.updates, 2);
+}
+
+#[test]
+fn disabled_components_skip_updates() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut obj = world.new_object("Object");
+    world.add_child(obj);
+
+    let counter = obj.add_component::<UpdateCounter>();
+    counter.set_enabled(false);
+    assert!(!counter.is_enabled());
+    world.update();
+    assert_eq!(counter.updates, 0);
+
+    // the object toggling doesn't wake a disabled component
+    obj.set_active(false);
+    obj.set_active(true);
+    world.update();
+    assert_eq!(counter.updates, 0);
+
+    counter.set_enabled(true);
+    world.update();
+    assert_eq!(counter.updates, 1);
+    assert_eq!(counter.active_changes, vec![false, true]);
+
+    // disabling it on an inactive object doesn't notify it again
+    obj.set_active(false);
+    counter.set_enabled(false);
+    obj.set_active(true);
+    assert_eq!(counter.active_changes, vec![false, true, false]);
+}
+
+#[test]
+fn camera_track_drives_active_camera() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let camera = world.new_camera();
+    let mut obj = world.new_object("Track");
+    world.add_child(obj);
+
+    let turned = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), 1.0);
+    let mut track = obj.add_component::<CameraTrack>();
+    track.add_keyframe(
+        CameraKeyframe::new(2.0, Vector3::new(10.0, 0.0, 0.0), turned).with_fov(40.0),
+    );
+    track.add_keyframe(
+        CameraKeyframe::new(0.0, Vector3::zeros(), UnitQuaternion::identity()).with_fov(80.0),
+    );
+    assert_eq!(track.duration(), 2.0);
+
+    track.seek(1.0);
+    let cam_obj = camera.parent();
+    assert!((cam_obj.transform.position() - Vector3::new(5.0, 0.0, 0.0)).norm() < 1e-4);
+    assert!((camera.fov() - 60.0).abs() < 1e-4);
+
+    track.play();
+    world.tick(Duration::from_millis(500));
+    assert!((track.time() - 1.5).abs() < 1e-4);
+    assert!(track.is_playing());
+
+    world.tick(Duration::from_secs(1));
+    assert!(!track.is_playing());
+    assert_eq!(track.time(), 2.0);
+    assert!((cam_obj.transform.position() - Vector3::new(10.0, 0.0, 0.0)).norm() < 1e-4);
+    assert!(cam_obj.transform.rotation().angle_to(&turned) < 1e-4);
+
+    world.tick(Duration::from_millis(16));
+    let finished: Vec<_> = world.events.read::<CameraTrackFinished>().collect();
+    assert_eq!(finished, vec![&CameraTrackFinished { track: obj }]);
+}
+
+struct Drift {
+    parent: GameObjectId,
+    steps: usize,
+}
+
+impl NewComponent for Drift {
+    fn new(parent: GameObjectId) -> Self {
+        Drift { parent, steps: 0 }
+    }
+}
+
+impl Component for Drift {}
+
+impl ParallelUpdate for Drift {
+    fn parallel_update(&mut self, ctx: &ParallelContext) {
+        self.steps += 1;
+        let (mut parent, steps) = (self.parent, self.steps as f32);
+        ctx.defer(move |_world| parent.transform.set_local_position(steps, 0.0, 0.0));
+    }
+}
+
+#[test]
+fn parallel_updates_apply_deferred_commands() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let drifts: Vec<_> = (0..16)
+        .map(|i| {
+            let mut obj = world.new_object(format!("Drift {i}"));
+            world.add_child(obj);
+            obj.add_component::<Drift>()
+        })
+        .collect();
+    drifts[0].parent().set_active(false);
+
+    // unregistered types don't run
+    world.update();
+    assert!(drifts.iter().all(|drift| drift.steps == 0));
+
+    world.register_parallel_update::<Drift>();
+    world.register_parallel_update::<Drift>();
+    world.update();
+    world.update();
+
+    assert_eq!(drifts[0].steps, 0);
+    for drift in &drifts[1..] {
+        assert_eq!(drift.steps, 2);
+        assert_eq!(drift.parent().transform.local_position().x, 2.0);
+    }
+
+    // deferred commands share the world's queue, and run after what was queued before them
+    let mut first = drifts[1].parent();
+    world
+        .commands()
+        .push(move |_| first.transform.set_local_position(-1.0, 0.0, 0.0));
+    world.update();
+    assert_eq!(first.transform.local_position().x, 3.0);
+    assert!(world.commands().is_empty());
+}
+
+#[test]
+fn convex_colliders_follow_the_mesh() {
+    use rapier3d::prelude::{TypedShape, VHACDParameters};
+    use syrillian::components::collider::ColliderError;
+
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let size = Vector3::new(2.0, 1.0, 3.0);
+    let mut cube = world.spawn(&CubePrefab::with_size(HMaterial::DEFAULT, size));
+    let mut collider = cube.add_component::<Collider3D>();
+
+    collider.try_use_mesh().unwrap();
+    let mesh_aabb = collider.collider().unwrap().shape().compute_local_aabb();
+
+    collider.set_convex_hull().unwrap();
+    let shape = collider.collider().unwrap().shape();
+    assert!(matches!(
+        shape.as_typed_shape(),
+        TypedShape::ConvexPolyhedron(_)
+    ));
+    let hull_aabb = shape.compute_local_aabb();
+    assert!((hull_aabb.mins - mesh_aabb.mins).norm() < 1e-4);
+    assert!((hull_aabb.maxs - mesh_aabb.maxs).norm() < 1e-4);
+
+    let params = VHACDParameters {
+        resolution: 16,
+        ..VHACDParameters::default()
+    };
+    collider.set_convex_decomposition_with(&params).unwrap();
+    let shape = collider.collider().unwrap().shape();
+    assert!(matches!(
+        shape.as_typed_shape(),
+        TypedShape::ConvexPolyhedron(_) | TypedShape::Compound(_)
+    ));
+
+    let mut empty = world.new_object("Empty");
+    let mut collider = empty.add_component::<Collider3D>();
+    assert!(matches!(
+        collider.set_convex_hull(),
+        Err(ColliderError::NoMeshRenderer)
+    ));
+}
+
+#[test]
+fn follow_component_tracks_target() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut target = world.new_object("Target");
+    let mut follower = world.new_object("Follower");
+    world.add_child(target);
+    world.add_child(follower);
+
+    let mut follow = follower.add_component::<FollowComponent>();
+    follow.set_target(target);
+    follow.set_offset(Vector3::new(0.0, 0.0, 5.0), FollowSpace::TargetLocal);
+    follow.set_smooth_time(0.0);
+    follow.set_look_at_target(true);
+
+    target.transform.set_position(10.0, 0.0, 0.0);
+    target
+        .transform
+        .set_rotation(UnitQuaternion::from_axis_angle(
+            &Vector3::y_axis(),
+            FRAC_PI_2,
+        ));
+    world.update();
+
+    // a quarter turn around y moves the local +z offset to +x
+    assert!((follower.transform.position() - Vector3::new(15.0, 0.0, 0.0)).norm() < 1e-4);
+    assert!((follower.transform.forward() - Vector3::new(-1.0, 0.0, 0.0)).norm() < 1e-4);
+
+    // smoothing lags behind, but gets there
+    follow.set_smooth_time(0.1);
+    target.transform.set_position(20.0, 0.0, 0.0);
+    world.tick(Duration::from_millis(16));
+    let x = follower.transform.position().x;
+    assert!(x > 15.0 && x < 25.0);
+    for _ in 0..120 {
+        world.tick(Duration::from_millis(16));
+    }
+    assert!((follower.transform.position().x - 25.0).abs() < 1e-2);
+
+    world.delete_object(target);
+    world.update();
+    assert_eq!(follow.target(), None);
+    assert!((follower.transform.position().x - 25.0).abs() < 1e-2);
+}
+
+#[test]
+fn sized_prefabs_match_their_colliders() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+
+    let size = Vector3::new(2.0, 1.0, 4.0);
+    let cube = world.spawn(&CubePrefab::with_size(HMaterial::DEFAULT, size).with_collider());
+    assert_eq!(*cube.transform.local_scale(), size);
+    let collider = cube.get_component::<Collider3D>().unwrap();
+    let cuboid = collider.collider().unwrap().shape().as_cuboid().unwrap();
+    assert!((cuboid.half_extents - size * 0.5).norm() < 1e-5);
+
+    let sphere = world.spawn(&SpherePrefab::with_radius(HMaterial::DEFAULT, 3.0).with_collider());
+    let collider = sphere.get_component::<Collider3D>().unwrap();
+    let ball = collider.collider().unwrap().shape().as_ball().unwrap();
+    assert!((ball.radius - 3.0).abs() < 1e-5);
+
+    let plain = world.spawn(&CubePrefab::new(HMaterial::DEFAULT));
+    assert!(plain.get_component::<Collider3D>().is_none());
+}
+
+#[test]
+fn colliders_bake_and_rebake_the_scale() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut cube = world.new_object("Cube");
+    world.add_child(cube);
+    cube.transform.set_nonuniform_scale(2.0, 1.0, 4.0);
+    let collider = cube.add_component::<Collider3D>();
+    let cuboid = collider.collider().unwrap().shape().as_cuboid().unwrap();
+    assert!((cuboid.half_extents - Vector3::new(1.0, 0.5, 2.0)).norm() < 1e-5);
+
+    cube.transform.set_nonuniform_scale(6.0, 2.0, 1.0);
+    world.tick(Duration::from_millis(20));
+    let cuboid = collider.collider().unwrap().shape().as_cuboid().unwrap();
+    assert!((cuboid.half_extents - Vector3::new(3.0, 1.0, 0.5)).norm() < 1e-5);
+
+    let mut sphere = world.new_object("Sphere");
+    world.add_child(sphere);
+    sphere.transform.set_nonuniform_scale(1.0, 3.0, 1.0);
+    let mut collider = sphere.add_component::<Collider3D>();
+    collider.use_sphere();
+    let aabb = collider.collider().unwrap().shape().compute_local_aabb();
+    assert!((aabb.maxs.coords - Vector3::new(1.0, 3.0, 1.0)).norm() < 1e-2);
+    assert!((aabb.mins.coords + Vector3::new(1.0, 3.0, 1.0)).norm() < 1e-2);
+
+    sphere.transform.set_scale(2.0);
+    world.tick(Duration::from_millis(20));
+    let ball = collider.collider().unwrap().shape().as_ball().unwrap();
+    assert!((ball.radius - 2.0).abs() < 1e-5);
+}
+
+#[test]
+fn segment_occluders_measure_thickness() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let size = Vector3::new(4.0, 4.0, 1.0);
+    let wall = world.spawn(&CubePrefab::with_size(HMaterial::DEFAULT, size).with_collider());
+    world.physics.step();
+
+    let occluders = world.physics.segment_occluders(
+        Vector3::new(0.0, 0.0, 5.0),
+        Vector3::new(0.0, 0.0, -5.0),
+        QueryFilter::default(),
+    );
+    assert_eq!(occluders.len(), 1);
+    assert_eq!(occluders[0].0, wall);
+    assert!((occluders[0].1 - 1.0).abs() < 1e-4);
+
+    let from_inside = world.physics.segment_occluders(
+        Vector3::zeros(),
+        Vector3::new(0.0, 0.0, 5.0),
+        QueryFilter::default(),
+    );
+    assert!(from_inside.is_empty());
+}
+
+#[test]
+fn overlap_queries_find_objects_once() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let near = world.spawn(&CubePrefab::new(HMaterial::DEFAULT).with_collider());
+    let far = world.spawn(&SpherePrefab::new(HMaterial::DEFAULT).with_collider());
+    let collider = far.get_component::<Collider3D>().unwrap();
+    collider
+        .collider_mut()
+        .unwrap()
+        .set_translation(Vector3::new(10.0, 0.0, 0.0));
+    // a second collider on `near` that also touches the query sphere
+    let extra = ColliderBuilder::ball(0.5)
+        .translation(Vector3::new(1.0, 0.0, 0.0))
+        .user_data(near.as_ffi() as u128)
+        .build();
+    world.physics.collider_set.insert(extra);
+    world.physics.step();
+
+    let hits = world.intersect_sphere(Vector3::new(1.0, 0.0, 0.0), 1.0, QueryFilter::default());
+    assert_eq!(hits, vec![near]);
+
+    let hits = world.intersect_box(
+        Vector3::new(5.0, 0.0, 0.0),
+        Vector3::new(6.0, 1.0, 1.0),
+        UnitQuaternion::identity(),
+        QueryFilter::default(),
+    );
+    assert_eq!(hits.len(), 2);
+    assert!(hits.contains(&near) && hits.contains(&far));
+
+    let empty = world.intersect_sphere(Vector3::new(0.0, 5.0, 0.0), 1.0, QueryFilter::default());
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn shape_cast_reports_first_contact() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let wall = world.spawn(
+        &CubePrefab::with_size(HMaterial::DEFAULT, Vector3::new(4.0, 4.0, 1.0)).with_collider(),
+    );
+    world.physics.step();
+
+    let ball = Ball::new(0.5);
+    let start = Isometry3::translation(0.0, 0.0, 5.0);
+    let velocity = Vector3::new(0.0, 0.0, -1.0);
+    let hit = world
+        .shape_cast(&ball, &start, velocity, 10.0, QueryFilter::default())
+        .unwrap();
+    assert_eq!(hit.object, wall);
+    assert!(!hit.penetrating);
+    assert!((hit.time_of_impact - 4.0).abs() < 1e-3);
+    assert!((hit.point.z - 0.5).abs() < 1e-3);
+    assert!(hit.normal.z > 0.99);
+
+    let short = world.shape_cast(&ball, &start, velocity, 3.0, QueryFilter::default());
+    assert!(short.is_none());
+
+    let inside = Isometry3::translation(0.0, 0.0, 0.2);
+    let hit = world
+        .shape_cast(&ball, &inside, velocity, 10.0, QueryFilter::default())
+        .unwrap();
+    assert!(hit.penetrating);
+    assert_eq!(hit.time_of_impact, 0.0);
+}
+
+#[test]
+fn shape_cast_hits_are_in_world_space() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut wall = world.spawn(
+        &CubePrefab::with_size(HMaterial::DEFAULT, Vector3::new(4.0, 4.0, 1.0)).with_collider(),
+    );
+    // a quarter turn around y turns the thin side to face +x, leaving a 1x4x4 block
+    wall.transform.set_position(3.0, 1.0, -5.0);
+    wall.transform.set_rotation(UnitQuaternion::from_axis_angle(
+        &Vector3::y_axis(),
+        FRAC_PI_2,
+    ));
+    world.tick(Duration::from_millis(20));
+
+    let ball = Ball::new(0.5);
+    let start = Isometry3::translation(3.0, 1.0, 5.0);
+    let hit = world
+        .shape_cast(
+            &ball,
+            &start,
+            Vector3::new(0.0, 0.0, -1.0),
+            20.0,
+            QueryFilter::default(),
+        )
+        .unwrap();
+    assert_eq!(hit.object, wall);
+    assert!((hit.time_of_impact - 7.5).abs() < 1e-3);
+    assert!((hit.point - Vector3::new(3.0, 1.0, -3.0)).norm() < 1e-3);
+    assert!((hit.normal - Vector3::z()).norm() < 1e-3);
+}
+
+thread_local! {
+    static UPDATE_LOG: RefCell<Vec<(i32, GameObjectId)>> = const { RefCell::new(Vec::new()) };
+}
+
+struct Ordered<const ORDER: i32> {
+    parent: GameObjectId,
+}
+
+impl<const ORDER: i32> NewComponent for Ordered<ORDER> {
+    fn new(parent: GameObjectId) -> Self {
+        Self { parent }
+    }
+}
+
+impl<const ORDER: i32> Component for Ordered<ORDER> {
+    fn update(&mut self, _world: &mut World) {
+        UPDATE_LOG.with_borrow_mut(|log| log.push((ORDER, self.parent)));
+    }
+
+    fn update_order(&self) -> i32 {
+        ORDER
+    }
+}
+
+#[test]
+fn component_type_names_list_attached_components() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut obj = world.new_object("Test");
+    assert_eq!(obj.component_count(), 0);
+    assert!(obj.component_type_names().is_empty());
+
+    obj.add_component::<MyComponent>();
+    let gravity = obj.add_component::<GravityComponent>();
+    assert_eq!(obj.component_count(), 2);
+    assert_eq!(
+        obj.component_type_names(),
+        [
+            std::any::type_name::<MyComponent>(),
+            std::any::type_name::<GravityComponent>(),
+        ]
+    );
+
+    obj.remove_component(&gravity, &mut world);
+    assert_eq!(obj.component_count(), 1);
+}
+
+#[test]
+fn inspector_reads_and_edits_fields() {
+    use syrillian::rendering::lights::Light;
+
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut obj = world.new_object("Lamp");
+    world.add_child(obj);
+    obj.add_component::<MyComponent>();
+    let light = obj.add_component::<PointLightComponent>();
+    obj.add_component::<RigidBodyComponent>();
+
+    let sections = world.inspect_object(obj);
+    let names: Vec<_> = sections.iter().map(|s| s.name).collect();
+    assert_eq!(
+        names,
+        [
+            "Transform",
+            std::any::type_name::<MyComponent>(),
+            std::any::type_name::<PointLightComponent>(),
+            std::any::type_name::<RigidBodyComponent>(),
+        ]
+    );
+    assert_eq!(sections[0].component, None);
+    assert!(sections[1].fields.is_empty());
+    let light_id = sections[2].component.unwrap();
+    let body_id = sections[3].component.unwrap();
+    let mass = sections[3]
+        .fields
+        .iter()
+        .find(|f| f.name == "Mass")
+        .unwrap();
+    assert!(mass.read_only);
+
+    assert!(world.set_inspected_field(
+        obj,
+        None,
+        "Position",
+        InspectValue::Vector(Vector3::new(1.0, 2.0, 3.0)),
+    ));
+    assert_eq!(obj.transform.position(), Vector3::new(1.0, 2.0, 3.0));
+
+    assert!(world.set_inspected_field(obj, Some(light_id), "Intensity", InspectValue::Float(50.0)));
+    assert_eq!(light.data().intensity, 50.0);
+
+    assert!(!world.set_inspected_field(obj, Some(light_id), "Intensity", InspectValue::Bool(true)));
+    assert!(!world.set_inspected_field(obj, Some(body_id), "Mass", InspectValue::Float(3.0)));
+    assert!(!world.set_inspected_field(obj, Some(body_id), "Missing", InspectValue::Int(1)));
+
+    assert!(world.set_inspected_field(obj, Some(body_id), "Kinematic", InspectValue::Bool(true)));
+    let body = obj.get_component::<RigidBodyComponent>().unwrap();
+    assert!(body.is_kinematic());
+}
+
+#[test]
+fn components_update_by_order() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut first = world.new_object("First");
+    let mut second = world.new_object("Second");
+    world.add_child(first);
+    world.add_child(second);
+
+    first.add_component::<Ordered<10>>();
+    second.add_component::<Ordered<0>>();
+    first.add_component::<Ordered<0>>();
+    second.add_component::<Ordered<-5>>();
+
+    UPDATE_LOG.with_borrow_mut(Vec::clear);
+    world.update();
+
+    let log = UPDATE_LOG.with_borrow(Clone::clone);
+    assert_eq!(
+        log,
+        vec![(-5, second), (0, second), (0, first), (10, first)]
+    );
+
+    // removed components drop out of the order, the rest keeps it
+    let early = second.get_component::<Ordered<-5>>().unwrap();
+    second.remove_component(&early, &mut world);
+    first.add_component::<Ordered<-5>>();
+
+    UPDATE_LOG.with_borrow_mut(Vec::clear);
+    world.update();
+
+    let log = UPDATE_LOG.with_borrow(Clone::clone);
+    assert_eq!(log, vec![(-5, first), (0, second), (0, first), (10, first)]);
+}
+
+#[test]
+fn camera_parameters_are_validated() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut camera = world.new_camera();
+
+    camera.set_fov(200.0);
+    assert_eq!(camera.base_fov(), 179.0);
+    camera.set_fov(-5.0);
+    assert_eq!(camera.base_fov(), 1.0);
+
+    camera.set_far(100.0);
+    camera.set_near(0.0);
+    assert!(camera.near() > 0.0);
+    camera.set_near(500.0);
+    assert!(camera.near() < camera.far());
+    camera.set_far(0.0);
+    assert!(camera.far() > camera.near());
+
+    camera.set_fov(60.0);
+    camera.set_zoom(2.0);
+    camera.set_fov(90.0);
+    assert!((camera.fov() - 53.13).abs() < 0.01);
+    camera.set_fov_instant(90.0);
+    assert_eq!(camera.zoom(), 1.0);
+    assert_eq!(camera.fov(), 90.0);
+}
+
+#[test]
+fn bone_palettes_are_sized_to_the_skeleton() {
+    let rigid = BoneData::identity(0);
+    assert_eq!(rigid.count(), 1);
+    assert_eq!(rigid.as_bytes(), bytemuck::cast_slice(&BoneData::RIGID));
+
+    let mut palette = BoneData::identity(2);
+    let moved = Matrix4::new_translation(&Vector3::new(1.0, 2.0, 3.0));
+    palette.set_first_n(&[moved; 3]);
+    assert_eq!(palette.count(), 3);
+
+    let mats: &[Matrix4<f32>] = bytemuck::cast_slice(palette.as_bytes());
+    assert!(mats.iter().all(|m| *m == moved));
+}
+
+#[test]
+fn mesh_renderer_material_overrides() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let red = Material::builder().name("Red").store(&world);
+    let blue = Material::builder().name("Blue").store(&world);
+
+    let mut obj = world.new_object("Recolored");
+    let mut renderer = obj.add_component::<MeshRenderer>();
+    renderer.set_materials(vec![HMaterial::DEFAULT, HMaterial::DEFAULT]);
+
+    renderer.set_material_override(red);
+    assert_eq!(renderer.material_for(0), red);
+    assert_eq!(renderer.material_for(1), red);
+
+    renderer.set_submesh_material_override(1, blue);
+    assert_eq!(renderer.material_for(0), red);
+    assert_eq!(renderer.material_for(1), blue);
+
+    renderer.clear_material_override();
+    assert_eq!(renderer.material_for(0), HMaterial::DEFAULT);
+    assert_eq!(renderer.material_for(1), blue);
+
+    renderer.clear_material_overrides();
+    assert_eq!(renderer.material_for(1), HMaterial::DEFAULT);
+    assert_eq!(renderer.mesh(), HMesh::UNIT_CUBE);
+}
+
+#[test]
+fn mesh_renderer_outline() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut obj = world.new_object("Selected");
+    let mut renderer = obj.add_component::<MeshRenderer>();
+    assert_eq!(renderer.outline(), None);
+
+    let outline = Outline::new(Vector4::new(1.0, 0.5, 0.0, 1.0), 3.0);
+    assert!(outline.on_top);
+
+    renderer.set_outline(Some(outline.on_top(false)));
+    let set = renderer.outline().unwrap();
+    assert_eq!(set.width, 3.0);
+    assert!(!set.on_top);
+
+    renderer.set_outline(None);
+    assert_eq!(renderer.outline(), None);
+}
+
+#[test]
+fn text3d_camera_modes() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut obj = world.new_object("Damage Number");
+    let mut text = obj.add_component::<Text3D>();
+    assert!(!text.face_camera());
+    assert!(!text.constant_screen_size());
+
+    text.set_face_camera(true);
+    text.set_constant_screen_size(true);
+    assert!(text.face_camera());
+    assert!(text.constant_screen_size());
+
+    world.post_update();
+}
+
+#[test]
+fn gravity_pulls_along_its_direction_or_toward_an_attractor() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut obj = world.new_object("Falling");
+    world.add_child(obj);
+    let mut gravity = obj.add_component::<GravityComponent>();
+    assert_eq!(gravity.direction(), Vector3::new(0.0, -1.0, 0.0));
+
+    gravity.set_direction(Vector3::new(2.0, 0.0, 0.0));
+    gravity.set_strength(20.0);
+    assert_eq!(gravity.direction(), Vector3::x());
+    world.tick(Duration::from_millis(100));
+    let position = obj.transform.position();
+    assert!(position.x > 0.0);
+    assert!(position.y.abs() < 1e-6);
+
+    let mut planet = world.new_object("Planet");
+    world.add_child(planet);
+    planet.transform.set_position(0.0, 0.0, -50.0);
+    gravity.set_attractor(planet);
+    gravity.velocity = 0.0;
+    assert!(
+        (gravity.current_direction() - (planet.transform.position() - position).normalize()).norm()
+            < 1e-5
+    );
+    world.tick(Duration::from_millis(100));
+    assert!(obj.transform.position().z < position.z);
+
+    world.delete_object(planet);
+    assert_eq!(gravity.attractor(), None);
+    assert_eq!(gravity.current_direction(), Vector3::x());
+}
+
+#[test]
+fn force_fields_push_bodies_inside_them() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    world.physics.set_gravity(Vector3::zeros());
+    let step = Duration::from_millis(20);
+
+    let body_at = |world: &mut World, x: f32| {
+        let mut obj = world.new_object("Body");
+        obj.transform.set_position(x, 0.0, 0.0);
+        obj.add_component::<Collider3D>();
+        obj.add_component::<RigidBodyComponent>();
+        world.add_child(obj);
+        obj
+    };
+    let near = body_at(&mut world, 2.0);
+    let far = body_at(&mut world, 50.0);
+
+    let mut field_obj = world.new_object("Wind");
+    world.add_child(field_obj);
+    let mut field = field_obj.add_component::<ForceField>();
+    field.set_shape(ForceFieldShape::Sphere { radius: 5.0 });
+    field.set_mode(ForceFieldMode::Directional(Vector3::z()));
+    field.set_falloff(Falloff::Linear);
+    field.set_strength(50.0);
+
+    for _ in 0..10 {
+        world.tick(step);
+    }
+    let velocity = |obj: GameObjectId| {
+        *obj.get_component::<RigidBodyComponent>()
+            .unwrap()
+            .body()
+            .unwrap()
+            .linvel()
+    };
+    assert!(velocity(near).z > 0.0);
+    assert!(velocity(near).x.abs() < 1e-3);
+    assert_eq!(velocity(far), Vector3::zeros());
+
+    field.set_mode(ForceFieldMode::Radial);
+    field.set_falloff(Falloff::None);
+    let before = velocity(near);
+    field.explode();
+    assert!(velocity(near).x > before.x);
+}
+
+#[test]
+fn light_animator_drives_the_light_on_its_object() {
+    use syrillian::rendering::lights::Light;
+    use syrillian::utils::ease::Curve;
+
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut obj = world.new_object("Torch");
+    world.add_child(obj);
+    let mut light = obj.add_component::<PointLightComponent>();
+    light.set_intensity(100.0);
+
+    let mut animator = obj.add_component::<LightAnimator>();
+    animator.set_animation(LightAnimation::Curve(Curve::constant(1.0)));
+    animator.set_amplitude(0.5);
+    animator.set_color_range(Vector3::new(1.0, 0.0, 0.0), Vector3::new(1.0, 1.0, 0.0));
+    world.tick(Duration::from_millis(16));
+
+    assert_eq!(animator.base_intensity(), Some(100.0));
+    assert!((light.data().intensity - 150.0).abs() < 1e-3);
+    assert_eq!(light.data().color, Vector3::new(1.0, 1.0, 0.0));
+
+    animator.set_animation(LightAnimation::Curve(Curve::constant(-1.0)));
+    world.tick(Duration::from_millis(16));
+    assert!((light.data().intensity - 50.0).abs() < 1e-3);
+    assert_eq!(light.data().color, Vector3::new(1.0, 0.0, 0.0));
+
+    animator.set_preset(LightAnimationPreset::Pulse);
+    animator.set_frequency(1.0);
+    assert!((animator.signal(0.25) - 1.0).abs() < 1e-5);
+    assert!((animator.signal(0.75) + 1.0).abs() < 1e-5);
+}
+
+#[test]
+fn swept_kinematic_bodies_stop_at_walls() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let step = Duration::from_millis(20);
+
+    let mut wall = world.new_object("Wall");
+    wall.transform.set_position(5.0, 0.0, 0.0);
+    wall.transform
+        .set_nonuniform_scale_vec(Vector3::new(0.1, 5.0, 5.0));
+    wall.add_component::<Collider3D>();
+    world.add_child(wall);
+
+    let carried_at = |world: &mut World, z: f32, swept: bool| {
+        let mut obj = world.new_object("Carried");
+        obj.transform.set_position(0.0, 0.0, z);
+        obj.add_component::<Collider3D>();
+        let mut body = obj.add_component::<RigidBodyComponent>();
+        body.set_kinematic(true);
+        body.set_kinematic_swept(swept);
+        world.add_child(obj);
+        obj
+    };
+    let mut swept = carried_at(&mut world, -1.0, true);
+    let mut teleported = carried_at(&mut world, 1.0, false);
+    world.tick(step);
+
+    swept.transform.set_position(10.0, 0.0, -1.0);
+    teleported.transform.set_position(10.0, 0.0, 1.0);
+    world.tick(step);
+
+    // the wall's face is at 4.95 and the box reaches 0.5 in front of its center
+    let stopped = swept.transform.position().x;
+    assert!(stopped > 4.4 && stopped < 4.45, "stopped at {stopped}");
+    assert_eq!(teleported.transform.position().x, 10.0);
+
+    // moving away from the wall isn't blocked
+    swept.transform.set_position(0.0, 0.0, -1.0);
+    world.tick(step);
+    assert_eq!(swept.transform.position().x, 0.0);
+}
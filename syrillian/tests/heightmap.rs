@@ -0,0 +1,65 @@
+use nalgebra::{Vector2, Vector3};
+use syrillian::World;
+use syrillian::assets::{Heightmap, HeightmapError, Mesh};
+use syrillian::components::Collider3D;
+
+fn slope() -> Heightmap {
+    // rises along X, flat along Z
+    let heights = (0..5)
+        .flat_map(|_| (0..5).map(|x| x as f32 / 4.0))
+        .collect();
+    Heightmap::new(5, 5, heights).unwrap()
+}
+
+#[test]
+fn heightmap_validates_dimensions() {
+    assert!(matches!(
+        Heightmap::new(1, 4, vec![0.0; 4]),
+        Err(HeightmapError::TooSmall { .. })
+    ));
+    assert!(matches!(
+        Heightmap::new(2, 2, vec![0.0; 3]),
+        Err(HeightmapError::SizeMismatch { .. })
+    ));
+}
+
+#[test]
+fn downsample_keeps_edges() {
+    let map = slope();
+    let coarse = map.downsample(2);
+
+    assert_eq!((coarse.width(), coarse.depth()), (3, 3));
+    assert_eq!(coarse.height(0, 0), 0.0);
+    assert_eq!(coarse.height(2, 2), 1.0);
+    assert!((coarse.height(1, 1) - 0.5).abs() < 1e-5);
+}
+
+#[test]
+fn heightmap_mesh_spans_size() {
+    let mesh = Mesh::from_heightmap(&slope(), Vector2::new(10.0, 20.0), 2.0);
+
+    assert_eq!(mesh.vertex_count(), 25);
+    assert_eq!(mesh.triangle_count(), 32);
+
+    let first = mesh.vertices()[0];
+    let last = mesh.vertices()[24];
+    assert_eq!(first.position, Vector3::new(-5.0, 0.0, -10.0));
+    assert_eq!(last.position, Vector3::new(5.0, 2.0, 10.0));
+
+    // the slope rises 2 units over 10 along X
+    let expected = Vector3::new(-0.2, 1.0, 0.0).normalize();
+    assert!((mesh.vertices()[12].normal - expected).norm() < 1e-5);
+}
+
+#[test]
+fn heightfield_collider_matches_mesh() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut terrain = world.new_object("Terrain");
+    let mut collider = terrain.add_component::<Collider3D>();
+
+    collider.set_heightfield(&slope().downsample(2), Vector2::new(10.0, 20.0), 2.0);
+
+    let aabb = collider.collider().unwrap().shape().compute_local_aabb();
+    assert!((aabb.mins - nalgebra::Point3::new(-5.0, 0.0, -10.0)).norm() < 1e-5);
+    assert!((aabb.maxs - nalgebra::Point3::new(5.0, 2.0, 10.0)).norm() < 1e-5);
+}
@@ -0,0 +1,258 @@
+use nalgebra::Vector2;
+use syrillian::World;
+use syrillian::input::{Clipboard, Modifiers, ResponseCurve};
+use syrillian::windowing::RenderTargetId;
+use winit::event::{DeviceEvent, WindowEvent};
+use winit::keyboard::ModifiersState;
+
+#[test]
+fn modifiers_follow_winit_state() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    world.input.set_window_focus(RenderTargetId::PRIMARY, true);
+    assert_eq!(world.input.modifiers(), Modifiers::empty());
+
+    let state = ModifiersState::SHIFT | ModifiersState::CONTROL;
+    world.input.process_event(
+        RenderTargetId::PRIMARY,
+        &WindowEvent::ModifiersChanged(state.into()),
+    );
+
+    assert!(world.input.shift());
+    assert!(world.input.ctrl());
+    assert!(!world.input.alt());
+    assert!(!world.input.super_key());
+    assert_eq!(world.input.modifiers(), Modifiers::SHIFT | Modifiers::CTRL);
+}
+
+#[test]
+fn raw_mouse_delta_accumulates_per_frame() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    world.input.set_window_focus(RenderTargetId::PRIMARY, true);
+    let position = world.input.mouse_position();
+
+    for delta in [(3.0, -1.0), (2.0, 4.0)] {
+        world
+            .input
+            .process_device_input_event(&DeviceEvent::MouseMotion { delta });
+    }
+
+    assert_eq!(world.input.mouse_delta(), Vector2::new(-5.0, -3.0));
+    assert_eq!(world.input.mouse_position(), position);
+
+    world.input.next_frame_all();
+    assert_eq!(world.input.mouse_delta(), Vector2::zeros());
+
+    world.input.set_window_focus(RenderTargetId::PRIMARY, false);
+    world
+        .input
+        .process_device_input_event(&DeviceEvent::MouseMotion { delta: (1.0, 1.0) });
+    assert_eq!(world.input.mouse_delta(), Vector2::zeros());
+}
+
+#[test]
+fn gamepad_deadzones_rescale_input() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let gamepad = &mut world.input.gamepad;
+    gamepad.set_stick_deadzone(0.2);
+    gamepad.set_trigger_threshold(0.1);
+
+    assert_eq!(
+        gamepad.filter_stick(Vector2::new(0.1, 0.1)),
+        Vector2::zeros()
+    );
+    let just_past = gamepad.filter_stick(Vector2::new(0.21, 0.0));
+    assert!(just_past.x > 0.0 && just_past.x < 0.02);
+    let full = gamepad.filter_stick(Vector2::new(0.0, -1.0));
+    assert!((full - Vector2::new(0.0, -1.0)).norm() < 1e-6);
+    let diagonal = gamepad.filter_stick(Vector2::new(0.6, 0.8));
+    assert!((diagonal.norm() - 1.0).abs() < 1e-6);
+
+    assert_eq!(gamepad.filter_trigger(0.05), 0.0);
+    assert!((gamepad.filter_trigger(0.55) - 0.5).abs() < 1e-6);
+
+    gamepad.set_response_curve(ResponseCurve::Quadratic);
+    assert!((gamepad.filter_trigger(0.55) - 0.25).abs() < 1e-6);
+    assert_eq!(gamepad.filter_trigger(1.0), 1.0);
+}
+
+#[test]
+fn clipboard_round_trips_text() {
+    // the local clipboard leaves the clipboard of the machine running the tests alone
+    let mut clipboard = Clipboard::local();
+    assert!(!clipboard.is_system_clipboard());
+    assert_eq!(clipboard.get_text(), None);
+
+    clipboard.set_text("syrillian clipboard");
+    assert_eq!(clipboard.get_text().as_deref(), Some("syrillian clipboard"));
+    clipboard.set_text("replaced");
+    assert_eq!(clipboard.get_text().as_deref(), Some("replaced"));
+}
+
+#[test]
+fn cursor_confinement_survives_a_lock() {
+    use syrillian::game_thread::GameAppEvent;
+
+    let (mut world, _rx1, game_rx, _pick_tx) = World::fresh();
+    let target = RenderTargetId::PRIMARY;
+
+    world.input.set_cursor_confined(true);
+    assert!(world.input.is_cursor_confined());
+    world.input.lock_cursor();
+    world.input.unlock_cursor();
+    world.input.set_cursor_confined(false);
+
+    let events: Vec<_> = game_rx.try_iter().collect();
+    assert!(matches!(
+        events[..],
+        [
+            GameAppEvent::SetCursorConfined(t1, true),
+            GameAppEvent::SetCursorMode(t2, true, false),
+            GameAppEvent::SetCursorMode(t3, false, true),
+            GameAppEvent::SetCursorConfined(t4, true),
+            GameAppEvent::SetCursorConfined(t5, false),
+        ] if [t1, t2, t3, t4, t5].iter().all(|&t| t == target)
+    ));
+    assert!(!world.input.is_cursor_confined());
+}
+
+#[test]
+fn key_sequences_need_the_right_order_in_time() {
+    use syrillian::input::KeySequence;
+    use web_time::Duration;
+    use winit::keyboard::KeyCode;
+
+    let ms = Duration::from_millis;
+    let mut combo = KeySequence::new(
+        [KeyCode::ArrowDown, KeyCode::ArrowRight, KeyCode::KeyJ],
+        ms(300),
+    );
+
+    assert!(!combo.feed(KeyCode::ArrowDown, ms(0)));
+    assert!(!combo.feed(KeyCode::ArrowRight, ms(100)));
+    assert!(combo.feed(KeyCode::KeyJ, ms(200)));
+    assert_eq!(combo.progress(), 0);
+
+    // a wrong key starts over, but can begin a new attempt
+    combo.feed(KeyCode::ArrowDown, ms(1000));
+    combo.feed(KeyCode::ArrowDown, ms(1100));
+    assert_eq!(combo.progress(), 1);
+    combo.feed(KeyCode::ArrowRight, ms(1150));
+    assert!(combo.feed(KeyCode::KeyJ, ms(1200)));
+
+    // too slow
+    combo.feed(KeyCode::ArrowDown, ms(2000));
+    combo.feed(KeyCode::ArrowRight, ms(2100));
+    assert!(!combo.feed(KeyCode::KeyJ, ms(2400)));
+    assert_eq!(combo.progress(), 0);
+
+    let (world, _rx1, _rx2, _pick_tx) = World::fresh();
+    assert!(!world.input.chord(&[KeyCode::ControlLeft, KeyCode::KeyS]));
+    assert!(!world.input.chord(&[]));
+}
+
+#[test]
+fn chords_fire_once_when_the_last_key_goes_down() {
+    use syrillian::input::{InputRecording, RecordedEvent, RecordedInput};
+    use web_time::Duration;
+    use winit::keyboard::KeyCode;
+
+    // winit's key events can't be built outside of winit, so the keys are played back. That
+    // applies them the same way KeyboardInput events are.
+    let key = |frame, key, pressed| RecordedEvent {
+        frame,
+        time: Duration::ZERO,
+        input: RecordedInput::Key { key, pressed },
+    };
+    let save = [KeyCode::ControlLeft, KeyCode::KeyS];
+
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    world.input.play_recording(InputRecording {
+        frames: 6,
+        events: vec![
+            key(0, KeyCode::ControlLeft, true),
+            key(1, KeyCode::KeyS, true),
+            key(3, KeyCode::KeyS, false),
+            key(3, KeyCode::ControlLeft, false),
+            key(4, KeyCode::KeyS, true),
+            key(5, KeyCode::ControlLeft, true),
+        ],
+    });
+
+    let mut fired = Vec::new();
+    for _ in 0..6 {
+        fired.push(world.input.chord(&save));
+        world.input.next_frame_all();
+    }
+
+    // fires on the press of S, not again while held, and not when S was down first
+    assert_eq!(fired, [false, true, false, false, false, false]);
+}
+
+#[test]
+fn recorded_input_plays_back_frame_by_frame() {
+    use syrillian::input::InputRecording;
+    use winit::dpi::PhysicalPosition;
+    use winit::event::{DeviceId, ElementState, MouseButton};
+
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    world.input.set_window_focus(RenderTargetId::PRIMARY, true);
+    world.input.start_recording();
+    assert!(world.input.is_recording());
+
+    world.input.process_event(
+        RenderTargetId::PRIMARY,
+        &WindowEvent::CursorMoved {
+            device_id: DeviceId::dummy(),
+            position: PhysicalPosition::new(40.0, 30.0),
+        },
+    );
+    world.input.next_frame_all();
+    world.input.process_event(
+        RenderTargetId::PRIMARY,
+        &WindowEvent::MouseInput {
+            device_id: DeviceId::dummy(),
+            state: ElementState::Pressed,
+            button: MouseButton::Left,
+        },
+    );
+    world
+        .input
+        .process_device_input_event(&DeviceEvent::MouseMotion { delta: (2.0, 1.0) });
+    world.input.next_frame_all();
+
+    let recording = world.input.stop_recording().unwrap();
+    assert_eq!(recording.frames, 2);
+    assert_eq!(recording.events.len(), 3);
+    let recording = InputRecording::from_json(&recording.to_json()).unwrap();
+    assert!(InputRecording::from_json("not a recording").is_err());
+
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    world.input.play_recording(recording);
+    assert!(world.input.is_playing_back());
+    assert_eq!(
+        world.input.mouse_position(),
+        PhysicalPosition::new(40.0, 30.0)
+    );
+    assert!(!world.input.is_button_pressed(MouseButton::Left));
+
+    world.input.next_frame_all();
+    assert!(world.input.is_button_down(MouseButton::Left));
+    assert_eq!(world.input.mouse_delta(), Vector2::new(-2.0, -1.0));
+
+    // real input is ignored while playing back
+    world.input.process_event(
+        RenderTargetId::PRIMARY,
+        &WindowEvent::CursorMoved {
+            device_id: DeviceId::dummy(),
+            position: PhysicalPosition::new(0.0, 0.0),
+        },
+    );
+    assert_eq!(
+        world.input.mouse_position(),
+        PhysicalPosition::new(40.0, 30.0)
+    );
+
+    world.input.next_frame_all();
+    assert!(!world.input.is_playing_back());
+    assert!(world.input.is_button_pressed(MouseButton::Left));
+}
@@ -0,0 +1,43 @@
+use nalgebra::Vector3;
+use syrillian::rendering::rich_text::{StyledRun, parse_rich_text};
+
+#[test]
+fn plain_text_is_one_run() {
+    assert_eq!(parse_rich_text("Hello"), vec![StyledRun::new("Hello")]);
+    assert!(parse_rich_text("").is_empty());
+}
+
+#[test]
+fn color_and_size_tags() {
+    let runs = parse_rich_text("Deals [color=#ff0000]42 [size=2]crit[/size][/color] damage");
+    let red = Vector3::new(1.0, 0.0, 0.0);
+
+    assert_eq!(
+        runs,
+        vec![
+            StyledRun::new("Deals "),
+            StyledRun::new("42 ").color(red),
+            StyledRun::new("crit").color(red).scale(2.0),
+            StyledRun::new(" damage"),
+        ]
+    );
+}
+
+#[test]
+fn nested_sizes_multiply() {
+    let runs = parse_rich_text("[size=2][size=1.5]big[/size][/size]");
+    assert_eq!(runs, vec![StyledRun::new("big").scale(3.0)]);
+}
+
+#[test]
+fn short_hex_colors() {
+    let runs = parse_rich_text("[color=#0f0]green[/color]");
+    assert_eq!(runs[0].color, Some(Vector3::new(0.0, 1.0, 0.0)));
+}
+
+#[test]
+fn unknown_tags_stay_text() {
+    let markup = "[b]bold[/b] [color=red]x[/color] [/size] [color=#ff0000";
+    let runs = parse_rich_text(markup);
+    assert_eq!(runs, vec![StyledRun::new(markup)]);
+}
@@ -1,7 +1,9 @@
+use std::cell::RefCell;
 use std::path::PathBuf;
+use std::rc::Rc;
 
 use syrillian::World;
-use syrillian::assets::scene_loader::SceneLoader;
+use syrillian::assets::scene_loader::{SceneLoadOptions, SceneLoader};
 
 fn asset_path(relative: &str) -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(relative)
@@ -69,3 +71,38 @@ fn load_scene_from_buffer_spawns_world_objects() {
         "expected child nodes to be spawned under the scene root"
     );
 }
+
+#[test]
+fn missing_textures_are_remapped_and_skipped() {
+    const GLTF: &str = r#"{
+        "asset": { "version": "2.0" },
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [{ "name": "Empty" }],
+        "images": [{ "uri": "C:\\Artist\\Textures\\missing%20wood.png" }],
+        "textures": [{ "source": 0 }],
+        "materials": [{ "pbrMetallicRoughness": { "baseColorTexture": { "index": 0 } } }]
+    }"#;
+
+    let dir = std::env::temp_dir().join("syrillian_missing_textures");
+    std::fs::create_dir_all(&dir).expect("failed to create temp dir");
+    let path = dir.join("scene.gltf");
+    std::fs::write(&path, GLTF).expect("failed to write test scene");
+
+    let (mut world, _render_rx, _event_rx, _pick_tx) = World::fresh();
+
+    let remapped = Rc::new(RefCell::new(Vec::new()));
+    let seen = remapped.clone();
+    let options = SceneLoadOptions::default().remap_path(move |uri| {
+        seen.borrow_mut().push(uri.to_string());
+        PathBuf::from("textures/wood.png")
+    });
+
+    let root = SceneLoader::load_with(world.as_mut(), &path.to_string_lossy(), options)
+        .expect("missing textures shouldn't fail the load");
+
+    assert_eq!(
+        *remapped.borrow(),
+        ["C:\\Artist\\Textures\\missing%20wood.png"]
+    );
+    assert_eq!(root.children().len(), 1);
+}
@@ -1,5 +1,7 @@
 use nalgebra::{UnitQuaternion, Vector3};
 use slotmap::Key;
+use std::f32::consts::FRAC_PI_2;
+use syrillian::World;
 use syrillian::engine::core::{GameObjectId, Transform};
 
 #[test]
@@ -21,3 +23,37 @@ fn rotation_and_scale() {
     t.set_uniform_local_scale(2.0);
     assert_eq!(*t.local_scale(), Vector3::new(2.0, 2.0, 2.0));
 }
+
+#[test]
+fn world_local_conversions_follow_the_hierarchy() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut parent = world.new_object("Parent");
+    let mut child = world.new_object("Child");
+    world.add_child(parent);
+    parent.add_child(child);
+
+    parent.transform.set_position(10.0, 0.0, 0.0);
+    parent.transform.set_uniform_local_scale(2.0);
+    child
+        .transform
+        .set_local_rotation(UnitQuaternion::from_euler_angles(0.0, FRAC_PI_2, 0.0));
+
+    let local = Vector3::new(0.0, 0.0, -1.0);
+    let world_point = child.transform.local_to_world(local);
+    assert!((world_point - Vector3::new(8.0, 0.0, 0.0)).norm() < 1e-5);
+    assert!((child.transform.world_to_local(world_point) - local).norm() < 1e-5);
+
+    let world_dir = child.transform.local_to_world_direction(local);
+    assert!((world_dir - Vector3::new(-2.0, 0.0, 0.0)).norm() < 1e-5);
+    assert!((child.transform.world_to_local_direction(world_dir) - local).norm() < 1e-5);
+
+    // moving the parent invalidates the cached matrices of the child
+    parent.transform.set_position(0.0, 5.0, 0.0);
+    let moved = child.transform.local_to_world(local);
+    assert!((moved - Vector3::new(-2.0, 5.0, 0.0)).norm() < 1e-5);
+    assert!((child.transform.world_to_local(moved) - local).norm() < 1e-5);
+
+    // so does reparenting
+    child.set_parent(None, false).unwrap();
+    assert!((child.transform.local_to_world(local) - Vector3::new(-1.0, 0.0, 0.0)).norm() < 1e-5);
+}
@@ -0,0 +1,65 @@
+use syrillian::utils::ease::{self, Curve, Easing};
+
+const ALL: [Easing; 19] = [
+    Easing::Linear,
+    Easing::QuadIn,
+    Easing::QuadOut,
+    Easing::QuadInOut,
+    Easing::CubicIn,
+    Easing::CubicOut,
+    Easing::CubicInOut,
+    Easing::SineIn,
+    Easing::SineOut,
+    Easing::SineInOut,
+    Easing::ExpoIn,
+    Easing::ExpoOut,
+    Easing::ExpoInOut,
+    Easing::ElasticIn,
+    Easing::ElasticOut,
+    Easing::ElasticInOut,
+    Easing::BounceIn,
+    Easing::BounceOut,
+    Easing::BounceInOut,
+];
+
+#[test]
+fn easings_start_at_zero_and_end_at_one() {
+    for easing in ALL {
+        assert!(easing.apply(0.0).abs() < 1e-5, "{easing:?} at 0");
+        assert!((easing.apply(1.0) - 1.0).abs() < 1e-5, "{easing:?} at 1");
+        assert_eq!(easing.apply(-1.0), easing.apply(0.0), "{easing:?} below 0");
+        assert_eq!(easing.apply(2.0), easing.apply(1.0), "{easing:?} above 1");
+    }
+}
+
+#[test]
+fn in_out_easings_are_symmetric() {
+    assert!((ease::quad_in_out(0.5) - 0.5).abs() < 1e-5);
+    assert!((ease::cubic_in_out(0.5) - 0.5).abs() < 1e-5);
+    assert!((ease::sine_in_out(0.5) - 0.5).abs() < 1e-5);
+    assert!(ease::quad_in(0.25) < 0.25);
+    assert!(ease::quad_out(0.25) > 0.25);
+}
+
+#[test]
+fn curve_samples_between_points() {
+    let curve = Curve::new([(1.0, 10.0), (0.0, 0.0), (2.0, 0.0)]);
+
+    assert_eq!(curve.points()[0], (0.0, 0.0));
+    assert_eq!(curve.sample(-1.0), 0.0);
+    assert!((curve.sample(0.5) - 5.0).abs() < 1e-5);
+    assert_eq!(curve.sample(1.0), 10.0);
+    assert!((curve.sample(1.5) - 5.0).abs() < 1e-5);
+    assert_eq!(curve.sample(3.0), 0.0);
+
+    let eased = curve.with_easing(Easing::QuadIn);
+    assert!((eased.sample(0.5) - 2.5).abs() < 1e-5);
+
+    assert_eq!(Curve::default().sample(0.5), 0.0);
+    assert_eq!(Curve::constant(3.0).sample(100.0), 3.0);
+
+    let ramp = Curve::new([(0.0, 1.0), (1.0, 2.0)]);
+    assert_eq!(ramp.sample(f32::NAN), 1.0);
+    assert_eq!(ramp.sample(f32::NEG_INFINITY), 1.0);
+    assert_eq!(ramp.sample(f32::INFINITY), 2.0);
+}
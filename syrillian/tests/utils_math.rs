@@ -1,5 +1,8 @@
 use nalgebra::{Matrix4, UnitQuaternion, Vector3};
-use syrillian::utils::math::{ExtraMatrixMath, FloatMathExt, QuaternionEuler, light_range};
+use syrillian::utils::math::{
+    ExtraMatrixMath, FloatMathExt, QuaternionEuler, catmull_rom, light_range, move_toward,
+    rotate_toward, smooth_damp, value_noise,
+};
 
 #[test]
 fn matrix_decompose_recovers_transform_components() {
@@ -46,3 +49,66 @@ fn light_range_handles_constant_and_quadratic_terms() {
     let range = light_range(100.0, 1.0, 0.7, 0.2, 1.0).expect("range should exist");
     assert!(range >= 0.0);
 }
+
+#[test]
+fn move_and_rotate_toward_stop_at_target() {
+    let target = Vector3::new(3.0, 0.0, 4.0);
+    let moved = move_toward(Vector3::zeros(), target, 1.0);
+    assert!((moved.norm() - 1.0).abs() < 1e-5);
+    assert_eq!(move_toward(moved, target, 100.0), target);
+
+    let from = UnitQuaternion::identity();
+    let to = UnitQuaternion::from_euler_angles(0.0, 1.0, 0.0);
+    let turned = rotate_toward(from, to, 0.25);
+    assert!((from.angle_to(&turned) - 0.25).abs() < 1e-5);
+    assert_eq!(rotate_toward(turned, to, 10.0), to);
+}
+
+#[test]
+fn smooth_damp_converges_without_overshoot() {
+    let target = Vector3::new(10.0, 0.0, 0.0);
+    let mut position = Vector3::zeros();
+    let mut velocity = Vector3::zeros();
+
+    for _ in 0..120 {
+        position = smooth_damp(position, target, &mut velocity, 0.2, 1.0 / 60.0);
+        assert!(position.x <= target.x);
+    }
+    assert!((position - target).norm() < 1e-2);
+
+    // a single long frame must not overshoot either
+    let mut velocity = Vector3::zeros();
+    let jumped = smooth_damp(Vector3::zeros(), target, &mut velocity, 0.1, 5.0);
+    assert!(jumped.x <= target.x);
+}
+
+#[test]
+fn catmull_rom_passes_through_control_points() {
+    let p0 = Vector3::new(-1.0, 0.0, 0.0);
+    let p1 = Vector3::new(0.0, 0.0, 0.0);
+    let p2 = Vector3::new(1.0, 1.0, 0.0);
+    let p3 = Vector3::new(2.0, 1.0, 0.0);
+
+    assert!((catmull_rom(p0, p1, p2, p3, 0.0) - p1).norm() < 1e-6);
+    assert!((catmull_rom(p0, p1, p2, p3, 1.0) - p2).norm() < 1e-6);
+
+    // evenly spaced points on a line stay on it
+    let line = catmull_rom(p0, p1, Vector3::x(), Vector3::x() * 2.0, 0.25);
+    assert!((line - Vector3::new(0.25, 0.0, 0.0)).norm() < 1e-6);
+}
+
+#[test]
+fn value_noise_is_smooth_and_bounded() {
+    let mut previous = value_noise(0.0, 7);
+    let mut differs = false;
+    for i in 1..1000 {
+        let x = i as f32 * 0.01;
+        let value = value_noise(x, 7);
+        assert!((-1.0..=1.0).contains(&value));
+        assert!((value - previous).abs() < 0.1);
+        assert_eq!(value, value_noise(x, 7));
+        differs |= value != value_noise(x, 8);
+        previous = value;
+    }
+    assert!(differs);
+}
@@ -0,0 +1,63 @@
+use syrillian::World;
+use syrillian::utils::Rng;
+
+#[test]
+fn same_seed_same_sequence() {
+    let mut a = Rng::new(1234);
+    let mut b = Rng::new(1234);
+    for _ in 0..100 {
+        assert_eq!(a.next_u32(), b.next_u32());
+    }
+
+    let mut c = Rng::new(4321);
+    let differs = (0..100).any(|_| a.next_u32() != c.next_u32());
+    assert!(differs);
+}
+
+#[test]
+fn reseeding_restarts_sequence() {
+    let mut rng = Rng::new(7);
+    let first: Vec<u32> = (0..10).map(|_| rng.next_u32()).collect();
+
+    rng.seed(7);
+    let second: Vec<u32> = (0..10).map(|_| rng.next_u32()).collect();
+    assert_eq!(first, second);
+}
+
+#[test]
+fn values_stay_in_range() {
+    let mut rng = Rng::new(42);
+    for _ in 0..1000 {
+        let f = rng.random::<f32>();
+        assert!((0.0..1.0).contains(&f));
+
+        let r = rng.range(-2.5f32, 3.0);
+        assert!((-2.5..3.0).contains(&r));
+
+        let i = rng.range(-5i32, 5);
+        assert!((-5..5).contains(&i));
+    }
+
+    assert_eq!(rng.range(3u32, 3), 3);
+    assert_eq!(rng.range(1.0f32, 0.0), 1.0);
+    assert_eq!(rng.choose::<u8>(&[]), None);
+}
+
+#[test]
+fn shuffle_keeps_elements() {
+    let mut rng = Rng::new(99);
+    let mut items: Vec<u32> = (0..32).collect();
+    rng.shuffle(&mut items);
+    items.sort();
+    assert_eq!(items, (0..32).collect::<Vec<_>>());
+}
+
+#[test]
+fn world_rng_is_seedable() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    world.rng().seed(5);
+    let a = world.rng().range(0, 1000);
+
+    world.rng().seed(5);
+    assert_eq!(world.rng().range(0, 1000), a);
+}
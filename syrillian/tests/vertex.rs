@@ -1,4 +1,4 @@
-use nalgebra::{Vector2, Vector3};
+use nalgebra::{Vector2, Vector3, Vector4};
 use syrillian::engine::core::Vertex3D;
 
 #[test]
@@ -14,3 +14,16 @@ fn vertex_creation_pads_indices() {
     assert_eq!(v.bone_indices, [1, 2, 0, 0]);
     assert_eq!(v.bone_weights, [0.5, 0.5, 0.0, 0.0]);
 }
+
+#[test]
+fn vertex_color_defaults_to_white() {
+    let v = Vertex3D::basic(
+        Vector3::zeros(),
+        Vector2::zeros(),
+        Vector3::new(0.0, 1.0, 0.0),
+    );
+    assert_eq!(v.color, Vector4::new(1.0, 1.0, 1.0, 1.0));
+
+    let red = Vector4::new(1.0, 0.0, 0.0, 1.0);
+    assert_eq!(v.with_color(red).color, red);
+}
@@ -1,5 +1,16 @@
+use nalgebra::{UnitQuaternion, Vector3};
+use std::cell::Cell;
+use std::fs::{self, File};
+use std::rc::Rc;
+use std::time::SystemTime;
 use syrillian::World;
-use syrillian::core::EventType;
+use syrillian::assets::{Shader, ShaderReloaded, Sound};
+use syrillian::components::{
+    Collider3D, Component, ParticleEmitter, RigidBodyComponent, RotateComponent, Text3D,
+};
+use syrillian::core::{EventType, GameObjectId, ReparentError, UuidError};
+use syrillian::prefabs::{Prefab, PrefabContext};
+use uuid::Uuid;
 use web_time::Duration;
 
 #[test]
@@ -24,6 +35,158 @@ fn delta_time_advances() {
     assert!(world.delta_time() > Duration::ZERO);
 }
 
+#[test]
+fn delta_time_is_clamped() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    world.set_max_delta(Duration::from_millis(1));
+    std::thread::sleep(Duration::from_millis(5));
+    world.next_frame();
+
+    assert_eq!(world.delta_time(), Duration::from_millis(1));
+    assert!(world.raw_delta_time() >= Duration::from_millis(5));
+    assert!(world.smoothed_delta_time() <= world.max_delta());
+}
+
+fn falling_body(world: &mut World) -> GameObjectId {
+    let mut obj = world.new_object("Falling");
+    obj.transform.set_position(0.0, 10.0, 0.0);
+    obj.add_component::<Collider3D>();
+    obj.add_component::<RigidBodyComponent>();
+    world.add_child(obj);
+    obj
+}
+
+#[test]
+fn tick_advances_by_the_given_delta() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let obj = falling_body(&mut world);
+
+    for _ in 0..30 {
+        world.tick(Duration::from_secs_f64(1.0 / 30.0));
+        assert_eq!(world.delta_time(), Duration::from_secs_f64(1.0 / 30.0));
+    }
+
+    let y = obj.transform.position().y;
+    assert!(y < 6.0 && y > 4.0, "fell to {y} in a second");
+
+    // the same ticks give the same result
+    let (mut other, _rx1, _rx2, _pick_tx) = World::fresh();
+    let other_obj = falling_body(&mut other);
+    for _ in 0..30 {
+        other.tick(Duration::from_secs_f64(1.0 / 30.0));
+    }
+    assert_eq!(other_obj.transform.position().y, y);
+}
+
+#[test]
+fn spatial_index_matches_linear_queries() {
+    let (mut world, ..) = World::fresh();
+    let objects: Vec<GameObjectId> = (0..20)
+        .map(|i| {
+            let mut obj = world.new_object(format!("Object {i}"));
+            obj.transform.set_position(i as f32 * 3.0, 0.0, 0.0);
+            world.add_child(obj);
+            obj
+        })
+        .collect();
+
+    let sorted = |mut ids: Vec<GameObjectId>| {
+        ids.sort();
+        ids
+    };
+    let linear = sorted(world.objects_near(Vector3::new(30.0, 0.0, 0.0), 7.0));
+    assert_eq!(linear, sorted(objects[8..=12].to_vec()));
+
+    world.enable_spatial_index(4.0);
+    assert_eq!(world.spatial_index().unwrap().len(), 20);
+    assert_eq!(
+        sorted(world.objects_near(Vector3::new(30.0, 0.0, 0.0), 7.0)),
+        linear
+    );
+
+    // moved objects are picked up at the end of the frame
+    let mut far = objects[0];
+    far.transform.set_position(31.0, 0.0, 0.0);
+    world.tick(Duration::from_millis(16));
+    assert!(
+        world
+            .objects_near(Vector3::new(30.0, 0.0, 0.0), 7.0)
+            .contains(&far)
+    );
+
+    world.delete_object(objects[10]);
+    assert!(
+        !world
+            .objects_near(Vector3::new(30.0, 0.0, 0.0), 7.0)
+            .contains(&objects[10])
+    );
+}
+
+#[derive(Default)]
+struct DeltaRecorder {
+    update: Vec<Duration>,
+    fixed: Vec<Duration>,
+}
+
+impl Component for DeltaRecorder {
+    fn update(&mut self, world: &mut World) {
+        self.update.push(world.delta_time());
+    }
+
+    fn fixed_update(&mut self, world: &mut World) {
+        self.fixed.push(world.delta_time());
+    }
+}
+
+#[test]
+fn fixed_update_sees_the_fixed_delta() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut obj = world.new_object("Recorder");
+    world.add_child(obj);
+    let recorder = obj.add_component::<DeltaRecorder>();
+
+    let frame = Duration::from_millis(50);
+    world.tick(frame);
+
+    let fixed = world.fixed_delta_time();
+    assert_eq!(fixed, world.physics.timestep);
+    assert_eq!(recorder.update, vec![frame]);
+    assert_eq!(recorder.fixed.len(), 3);
+    assert!(recorder.fixed.iter().all(|&dt| dt == fixed));
+    assert_eq!(world.delta_time(), frame);
+}
+
+#[test]
+fn rigid_body_mass_properties_and_locks() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let obj = falling_body(&mut world);
+    let step = Duration::from_millis(20);
+
+    // the collider links to the body in its first fixed update
+    world.tick(step);
+    world.tick(step);
+    let mut body = obj.get_component::<RigidBodyComponent>().unwrap();
+    let collider_mass = body.body().unwrap().mass();
+    assert!(collider_mass > 0.0);
+
+    let center = Vector3::new(0.0, -0.25, 0.0);
+    body.set_additional_mass(2.0);
+    body.set_center_of_mass(Some(center));
+    world.tick(step);
+
+    let rb = body.body().unwrap();
+    assert!((rb.mass() - (collider_mass + 2.0)).abs() < 1e-3);
+    let com = rb.mass_properties().local_mprops.local_com;
+    assert!((com.coords - center).norm() < 1e-3);
+
+    body.lock_translations(false, true, false);
+    let y = obj.transform.position().y;
+    for _ in 0..10 {
+        world.tick(step);
+    }
+    assert!((obj.transform.position().y - y).abs() < 1e-4);
+}
+
 #[test]
 fn strong_refs_keep_objects_alive_until_drop() {
     let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
@@ -92,3 +255,705 @@ fn click_registration_toggles() {
     obj.stop_notify_for(&mut world, EventType::CLICK);
     assert!(!world.is_listening_for(obj, EventType::CLICK));
 }
+
+#[test]
+fn split_screen_cameras_pick_topmost_viewport() {
+    use syrillian::RenderTargetId;
+    use syrillian::components::ViewportRect;
+    use winit::dpi::PhysicalSize;
+
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    world.set_viewport_size(RenderTargetId::PRIMARY, PhysicalSize::new(800, 600));
+
+    let mut left = world.new_camera();
+    left.set_viewport(ViewportRect::new(0.0, 0.0, 0.5, 1.0));
+
+    let mut right = world.new_camera();
+    right.set_viewport(ViewportRect::new(0.5, 0.0, 0.5, 1.0));
+    right.set_depth(1);
+    world.add_active_camera(right.clone());
+
+    let cameras = world.active_cameras_for(RenderTargetId::PRIMARY);
+    assert_eq!(cameras, vec![left.clone(), right.clone()]);
+
+    assert_eq!(
+        world.camera_at(RenderTargetId::PRIMARY, 100.0, 300.0),
+        Some(left)
+    );
+    assert_eq!(
+        world.camera_at(RenderTargetId::PRIMARY, 700.0, 300.0),
+        Some(right.clone())
+    );
+    assert_eq!(right.viewport_resolution(), (400.0, 600.0));
+
+    world.remove_active_camera(right);
+    assert_eq!(world.active_cameras_for(RenderTargetId::PRIMARY).len(), 1);
+}
+
+#[test]
+fn texture_camera_follows_texture_size() {
+    use syrillian::RenderTargetId;
+    use syrillian::assets::Texture;
+
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let main = world.new_camera();
+
+    let texture = world
+        .assets
+        .textures
+        .add(Texture::new_render_target(256, 128));
+    let mut monitor = world.new_camera();
+    monitor.set_render_target(Some(texture));
+
+    assert_eq!(world.texture_cameras(), vec![monitor.clone()]);
+    assert_eq!(
+        world.active_cameras_for(RenderTargetId::PRIMARY),
+        vec![main]
+    );
+
+    world.post_update();
+    assert_eq!(monitor.resolution(), (256.0, 128.0));
+
+    world.assets.textures.get_mut(texture).width = 512;
+    world.post_update();
+    assert_eq!(monitor.resolution(), (512.0, 128.0));
+}
+
+#[test]
+fn set_parent_keeps_world_transform() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut parent = world.new_object("Parent");
+    let mut child = world.new_object("Child");
+    world.add_child(parent);
+    world.add_child(child);
+
+    parent.transform.set_position(5.0, 0.0, 0.0);
+    parent
+        .transform
+        .set_rotation(UnitQuaternion::from_euler_angles(0.0, 1.0, 0.0));
+    parent.transform.set_uniform_local_scale(2.0);
+    child.transform.set_position(1.0, 2.0, 3.0);
+
+    child.set_parent(Some(parent), true).unwrap();
+    assert_eq!(*child.parent(), Some(parent));
+    assert_eq!(world.children.len(), 1);
+    assert!((child.transform.position() - Vector3::new(1.0, 2.0, 3.0)).norm() < 1e-4);
+    assert!((child.transform.scale() - Vector3::new(1.0, 1.0, 1.0)).norm() < 1e-4);
+
+    child.set_parent(None, true).unwrap();
+    assert_eq!(*child.parent(), None);
+    assert_eq!(world.children.len(), 2);
+    assert!((child.transform.position() - Vector3::new(1.0, 2.0, 3.0)).norm() < 1e-4);
+
+    child.set_parent(Some(parent), false).unwrap();
+    assert_eq!(*child.parent(), Some(parent));
+    assert!(matches!(
+        parent.set_parent(Some(child), true),
+        Err(ReparentError::CyclicParent)
+    ));
+    assert!(matches!(
+        parent.set_parent(Some(parent), true),
+        Err(ReparentError::CyclicParent)
+    ));
+    assert_eq!(*parent.parent(), None);
+}
+
+#[test]
+fn gravity_changes_wake_bodies() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    assert_eq!(world.gravity(), Vector3::new(0.0, -9.81, 0.0));
+
+    let mut obj = world.new_object("Body");
+    let mut body = obj.add_component::<RigidBodyComponent>();
+    assert_eq!(body.gravity_scale(), 1.0);
+    body.set_gravity_scale(0.5);
+    assert_eq!(body.gravity_scale(), 0.5);
+
+    body.body_mut().unwrap().sleep();
+    world.set_zero_gravity();
+    assert_eq!(world.gravity(), Vector3::zeros());
+    assert!(!body.body().unwrap().is_sleeping());
+}
+
+struct BulletPrefab;
+
+impl Prefab for BulletPrefab {
+    fn prefab_name(&self) -> &'static str {
+        "Bullet"
+    }
+
+    fn build(&self, world: &mut World) -> GameObjectId {
+        let mut bullet = world.new_object("Bullet");
+        bullet.transform.set_position(0.0, 1.0, 0.0);
+        bullet.add_component::<RigidBodyComponent>();
+        bullet
+    }
+}
+
+#[test]
+fn object_pool_recycles_objects() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut pool = world.pool(BulletPrefab, 2);
+    assert_eq!(pool.len(), 2);
+    assert_eq!(pool.available(), 2);
+
+    let mut bullet = pool.acquire(&mut world);
+    assert!(bullet.active_in_hierarchy());
+    assert_eq!(pool.available(), 1);
+
+    bullet.transform.set_position(5.0, 5.0, 5.0);
+    let mut body = bullet.get_component::<RigidBodyComponent>().unwrap();
+    body.body_mut()
+        .unwrap()
+        .set_linvel(Vector3::new(10.0, 0.0, 0.0), true);
+
+    assert!(pool.release(bullet));
+    assert!(!pool.release(bullet));
+    assert!(!bullet.active_in_hierarchy());
+
+    let reused = pool.acquire(&mut world);
+    assert_eq!(reused, bullet);
+    assert_eq!(reused.transform.position(), Vector3::new(0.0, 1.0, 0.0));
+    assert_eq!(*body.body().unwrap().linvel(), Vector3::zeros());
+    assert!(reused.get_component::<RigidBodyComponent>().unwrap() == body);
+
+    let _other = pool.acquire(&mut world);
+    let _spawned = pool.acquire(&mut world);
+    assert_eq!(pool.len(), 3);
+    assert_eq!(pool.available(), 0);
+}
+
+#[derive(Debug, PartialEq)]
+struct EnemyDied(u32);
+
+#[test]
+fn events_are_delivered_next_frame() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let handled = Rc::new(Cell::new(0));
+    let counter = handled.clone();
+    world
+        .events
+        .subscribe::<EnemyDied>(move |_| counter.set(counter.get() + 1));
+
+    world.events.emit(EnemyDied(1));
+    world.events.emit(EnemyDied(2));
+    assert_eq!(world.events.read::<EnemyDied>().count(), 0);
+
+    world.update();
+    assert_eq!(handled.get(), 2);
+    let seen: Vec<_> = world.events.read::<EnemyDied>().collect();
+    assert_eq!(seen, [&EnemyDied(1), &EnemyDied(2)]);
+    assert_eq!(world.events.drain::<EnemyDied>().next(), Some(EnemyDied(1)));
+    assert_eq!(world.events.read::<EnemyDied>().count(), 0);
+
+    world.events.emit(EnemyDied(3));
+    world.update();
+    world.update();
+    assert_eq!(world.events.read::<EnemyDied>().count(), 0);
+    assert_eq!(handled.get(), 3);
+}
+
+struct WheelPrefab;
+
+impl Prefab for WheelPrefab {
+    fn prefab_name(&self) -> &'static str {
+        "Wheel"
+    }
+
+    fn build(&self, world: &mut World) -> GameObjectId {
+        world.new_object("Wheel")
+    }
+}
+
+struct CarPrefab {
+    wheels: usize,
+}
+
+impl Prefab for CarPrefab {
+    fn prefab_name(&self) -> &'static str {
+        "Car"
+    }
+
+    fn build(&self, world: &mut World) -> GameObjectId {
+        self.build_in(&mut PrefabContext::new(world))
+    }
+
+    fn build_in(&self, ctx: &mut PrefabContext) -> GameObjectId {
+        let body = ctx.new_object("Car");
+        for i in 0..self.wheels {
+            let mut wheel = ctx.spawn_child(&WheelPrefab);
+            wheel.transform.set_local_position(i as f32, 0.0, 0.0);
+        }
+        body
+    }
+}
+
+struct GaragePrefab;
+
+impl Prefab for GaragePrefab {
+    fn prefab_name(&self) -> &'static str {
+        "Garage"
+    }
+
+    fn build(&self, world: &mut World) -> GameObjectId {
+        self.build_in(&mut PrefabContext::new(world))
+    }
+
+    fn build_in(&self, ctx: &mut PrefabContext) -> GameObjectId {
+        let garage = ctx.new_object("Garage");
+        ctx.spawn_child(&CarPrefab { wheels: 2 });
+        garage
+    }
+}
+
+#[test]
+fn queued_commands_run_after_the_update() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let parent = world.new_object("Parent");
+    let child = world.new_object("Child");
+    let victim = world.new_object("Victim");
+    world.add_child(parent);
+    world.add_child(child);
+    world.add_child(victim);
+
+    let spawned = Rc::new(Cell::new(None));
+    let slot = spawned.clone();
+    world
+        .commands()
+        .spawn_then(WheelPrefab, move |_, wheel| slot.set(Some(wheel)));
+    world.commands().delete(victim);
+    world.commands().set_parent(child, Some(parent), false);
+    world
+        .commands()
+        .add_component_then::<RotateComponent, _>(child, |mut rotate| rotate.rotate_speed = 10.0);
+
+    // commands on objects deleted in the meantime are skipped
+    let gone = world.new_object("Gone");
+    world.commands().add_component::<RotateComponent>(gone);
+    world.delete_object(gone);
+
+    assert_eq!(world.commands().len(), 5);
+    assert!(victim.exists());
+
+    world.update();
+    assert!(world.commands().is_empty());
+    assert!(!victim.exists());
+    assert_eq!(*child.parent(), Some(parent));
+    let rotate = child.get_component::<RotateComponent>().unwrap();
+    assert_eq!(rotate.rotate_speed, 10.0);
+    assert!(world.children.contains(&spawned.get().unwrap()));
+}
+
+#[test]
+fn nested_prefabs_are_parented_to_their_root() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+
+    let car = world.spawn(&CarPrefab { wheels: 4 });
+    assert_eq!(world.children, vec![car]);
+    assert_eq!(car.children().len(), 4);
+    assert!(
+        car.children()
+            .iter()
+            .all(|wheel| *wheel.parent() == Some(car))
+    );
+
+    let garage = world.spawn(&GaragePrefab);
+    let nested_car = garage.children()[0];
+    assert_eq!(nested_car.name, "Car");
+    assert_eq!(nested_car.children().len(), 2);
+    assert_eq!(world.children.len(), 2);
+}
+
+#[test]
+fn watched_shaders_reload_when_valid() {
+    const VALID: &str = include_str!("../examples/dynamic_shader/shader.wgsl");
+
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    world.set_shader_watch_interval(Duration::ZERO);
+    let path = std::env::temp_dir().join(format!(
+        "syrillian_watched_shader_{}_{}.wgsl",
+        std::process::id(),
+        Uuid::new_v4()
+    ));
+    let write = |source: &str, age: u64| {
+        fs::write(&path, source).unwrap();
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000 + age);
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    };
+    let reloads = |world: &mut World| {
+        world.update();
+        world.update();
+        world
+            .events
+            .read::<ShaderReloaded>()
+            .map(|reloaded| reloaded.result.is_ok())
+            .collect::<Vec<_>>()
+    };
+
+    write(VALID, 0);
+    let shader = world
+        .assets
+        .shaders
+        .add(Shader::new_fragment("Watched", VALID));
+    assert!(world.assets.shaders.watch(shader, &path));
+    assert!(reloads(&mut world).is_empty());
+
+    write("this isn't wgsl", 1);
+    assert_eq!(reloads(&mut world), [false]);
+    assert_eq!(world.assets.shaders.get(shader).code().code(), VALID);
+
+    let changed = format!("{VALID}\n// changed");
+    write(&changed, 2);
+    assert_eq!(reloads(&mut world), [true]);
+    assert_eq!(world.assets.shaders.get(shader).code().code(), changed);
+
+    world.assets.shaders.unwatch(shader);
+    write(VALID, 3);
+    assert!(reloads(&mut world).is_empty());
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn window_size_accessors_follow_resize() {
+    use syrillian::RenderTargetId;
+    use winit::dpi::{LogicalSize, PhysicalSize};
+
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    assert_eq!(world.aspect_ratio(), 1.0);
+    assert_eq!(world.scale_factor(), 1.0);
+
+    world.set_viewport_size(RenderTargetId::PRIMARY, PhysicalSize::new(1600, 900));
+    world.set_scale_factor(RenderTargetId::PRIMARY, 2.0);
+
+    assert_eq!(world.window_size(), PhysicalSize::new(1600, 900));
+    assert_eq!(world.logical_size(), LogicalSize::new(800.0, 450.0));
+    assert!((world.aspect_ratio() - 16.0 / 9.0).abs() < 1e-6);
+
+    world.set_viewport_size(RenderTargetId::PRIMARY, PhysicalSize::new(600, 600));
+    assert_eq!(world.aspect_ratio(), 1.0);
+    assert_eq!(world.scale_factor_for(RenderTargetId(99)), None);
+}
+
+#[test]
+fn cursor_and_icon_requests_reach_the_window_thread() {
+    use image::RgbaImage;
+    use syrillian::RenderTargetId;
+    use syrillian::game_thread::GameAppEvent;
+    use winit::window::CursorIcon;
+
+    let (mut world, _rx1, game_rx, _pick_tx) = World::fresh();
+
+    world.set_cursor_icon(CursorIcon::Pointer);
+    world.set_cursor_visible(false);
+    world.set_default_window_icon(&RgbaImage::new(16, 16));
+
+    let events: Vec<_> = game_rx.try_iter().collect();
+    assert!(matches!(
+        events[..],
+        [
+            GameAppEvent::SetCursorIcon(RenderTargetId::PRIMARY, CursorIcon::Pointer),
+            GameAppEvent::SetCursorVisible(RenderTargetId::PRIMARY, false),
+            GameAppEvent::SetWindowIcon(RenderTargetId::PRIMARY, _),
+        ]
+    ));
+}
+
+#[test]
+fn duplicate_copies_components_and_children() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut root = world.new_object("Root");
+    world.add_child(root);
+
+    let mut obj = world.new_object("Spinner");
+    obj.transform.set_local_position(1.0, 2.0, 3.0);
+    obj.add_property("team", serde_json::json!("red"));
+    let mut rotate = obj.add_component::<RotateComponent>();
+    rotate.rotate_speed = 10.0;
+    root.add_child(obj);
+
+    let mut child = world.new_object("Child");
+    child.set_active(false);
+    obj.add_child(child);
+
+    let copy = world.duplicate(obj);
+    assert_ne!(copy, obj);
+    assert_eq!(copy.name, "Spinner");
+    assert_eq!(*copy.parent(), Some(root));
+    assert_eq!(root.children(), &[obj, copy]);
+    assert_eq!(
+        *copy.transform.local_position(),
+        Vector3::new(1.0, 2.0, 3.0)
+    );
+    assert_eq!(copy.property("team"), Some(&serde_json::json!("red")));
+
+    let rotate = copy.get_component::<RotateComponent>().unwrap();
+    assert_eq!(rotate.rotate_speed, 10.0);
+    assert_eq!(rotate.parent(), copy);
+
+    let [copied_child] = copy.children() else {
+        panic!("expected one child");
+    };
+    assert_ne!(*copied_child, child);
+    assert_eq!(copied_child.name, "Child");
+    assert!(!copied_child.is_active());
+}
+
+#[test]
+fn duplicate_copies_built_in_components_and_enabled_flags() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut obj = world.new_object("Effects");
+    world.add_child(obj);
+
+    let mut text = obj.add_component::<Text3D>();
+    text.set_size(2.5);
+    text.set_face_camera(true);
+    let mut emitter = obj.add_component::<ParticleEmitter>();
+    emitter.emission_rate = 3.0;
+    emitter.set_looping(false);
+    emitter.set_enabled(false);
+
+    let copy = world.duplicate(obj);
+
+    let text = copy.get_component::<Text3D>().unwrap();
+    assert_eq!(text.size(), 2.5);
+    assert!(text.face_camera());
+    assert!(text.is_enabled());
+
+    let emitter = copy.get_component::<ParticleEmitter>().unwrap();
+    assert_eq!(emitter.emission_rate, 3.0);
+    assert!(!emitter.is_looping());
+    assert!(!emitter.is_enabled());
+}
+
+#[test]
+fn objects_are_found_by_uuid() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut a = world.new_object("A");
+    let mut b = world.new_object("B");
+    world.add_child(a);
+    world.add_child(b);
+
+    let uuid = Uuid::from_u128(0x1234);
+    a.set_uuid(uuid).unwrap();
+    assert_eq!(a.uuid(), Some(uuid));
+    assert_eq!(world.find_by_uuid(uuid), Some(a));
+    assert!(matches!(b.set_uuid(uuid), Err(UuidError::UuidTaken { other, .. }) if other == a));
+
+    let generated = b.ensure_uuid().unwrap();
+    assert_eq!(b.ensure_uuid().unwrap(), generated);
+    assert_eq!(world.find_by_uuid(generated), Some(b));
+
+    let copy = world.duplicate(a);
+    assert_eq!(copy.uuid(), None);
+
+    world.delete_object(a);
+    assert_eq!(world.find_by_uuid(uuid), None);
+    b.set_uuid(uuid).unwrap();
+    assert_eq!(world.find_by_uuid(uuid), Some(b));
+    assert_eq!(world.find_by_uuid(generated), None);
+
+    b.clear_uuid();
+    assert_eq!(world.find_by_uuid(uuid), None);
+}
+
+#[test]
+fn restoring_a_snapshot_rolls_back_bodies_and_transforms() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let obj = falling_body(&mut world);
+    let mut marker = world.new_object("Marker");
+    world.add_child(marker);
+    let step = Duration::from_millis(20);
+
+    for _ in 0..5 {
+        world.tick(step);
+    }
+    marker.transform.set_local_position(1.0, 2.0, 3.0);
+    let snapshot = world.snapshot();
+    let position = obj.transform.position();
+    let body = obj.get_component::<RigidBodyComponent>().unwrap();
+    let linvel = *body.body().unwrap().linvel();
+    assert!(linvel.y < 0.0);
+
+    for _ in 0..5 {
+        world.tick(step);
+    }
+    marker.transform.set_local_position(0.0, 0.0, 0.0);
+    assert!(obj.transform.position().y < position.y);
+
+    world.restore(&snapshot);
+    assert!((obj.transform.position() - position).norm() < 1e-5);
+    assert!((body.body().unwrap().linvel() - linvel).norm() < 1e-5);
+    assert!((body.body().unwrap().translation() - position).norm() < 1e-5);
+    assert_eq!(
+        *marker.transform.local_position(),
+        Vector3::new(1.0, 2.0, 3.0)
+    );
+
+    let mut reused = world.snapshot();
+    world.snapshot_into(&mut reused);
+    assert_eq!(reused.object_count(), snapshot.object_count());
+    assert_eq!(reused.body_count(), snapshot.body_count());
+}
+
+#[test]
+fn raycasts_from_the_active_camera() {
+    use rapier3d::prelude::QueryFilter;
+    use syrillian::RenderTargetId;
+    use winit::dpi::PhysicalSize;
+
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    assert!(
+        world
+            .raycast_from_camera(100.0, QueryFilter::default())
+            .is_none()
+    );
+
+    let _camera = world.new_camera();
+    world.set_viewport_size(RenderTargetId::PRIMARY, PhysicalSize::new(200, 100));
+
+    let mut wall = world.new_object("Wall");
+    wall.transform.set_position(0.0, 0.0, -5.0);
+    wall.add_component::<Collider3D>();
+    world.add_child(wall);
+    world.tick(Duration::from_millis(20));
+
+    let hit = world
+        .raycast_from_camera(100.0, QueryFilter::default())
+        .expect("the wall is straight ahead");
+    assert_eq!(hit.object, wall);
+    assert!(hit.normal.z > 0.99);
+    assert!(hit.point.z > -5.0 && hit.point.z < 0.0);
+    assert!((hit.distance + hit.point.z).abs() < 1e-4);
+    assert!(
+        world
+            .raycast_from_camera(1.0, QueryFilter::default())
+            .is_none()
+    );
+
+    let center = world.raycast_from_screen(100.0, 50.0, 100.0, QueryFilter::default());
+    assert_eq!(center.map(|hit| hit.object), Some(wall));
+    let corner = world.raycast_from_screen(0.0, 0.0, 100.0, QueryFilter::default());
+    assert!(corner.is_none());
+}
+
+#[test]
+fn objects_are_iterated_depth_first() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut a = world.new_object("A");
+    let mut a1 = world.new_object("A1");
+    let a1a = world.new_object("A1a");
+    let a2 = world.new_object("A2");
+    let b = world.new_object("B");
+    world.new_object("Detached");
+    world.add_child(a);
+    world.add_child(b);
+    a.add_child(a1);
+    a.add_child(a2);
+    a1.add_child(a1a);
+
+    let names: Vec<_> = world.iter_objects().map(|o| o.name.clone()).collect();
+    assert_eq!(names, ["A", "A1", "A1a", "A2", "B"]);
+
+    let late = world.new_object("Late");
+    let mut visited = Vec::new();
+    world.for_each_object(|obj| {
+        visited.push(obj.name.clone());
+        if obj.name == "A1" {
+            let mut doomed = b;
+            doomed.delete();
+            obj.add_child(late);
+        }
+    });
+    assert_eq!(visited, ["A", "A1", "A1a", "A2"]);
+
+    let names: Vec<_> = world.iter_objects().map(|o| o.name.clone()).collect();
+    assert_eq!(names, ["A", "A1", "A1a", "Late", "A2"]);
+}
+
+#[test]
+fn object_tree_describes_the_hierarchy() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut parent = world.new_object("Parent");
+    parent.transform.set_position(1.0, 2.0, 3.0);
+    parent.add_component::<RotateComponent>();
+    world.add_child(parent);
+
+    let mut child = world.new_object("Child");
+    child.transform.set_local_position(0.0, 1.0, 0.0);
+    child.set_active(false);
+    parent.add_child(child);
+
+    let tree = world.object_tree();
+    assert_eq!(tree.id, None);
+    assert_eq!(tree.descendant_count(), 2);
+
+    let [node] = tree.children.as_slice() else {
+        panic!("expected one root object");
+    };
+    assert_eq!(node.id, Some(parent));
+    assert_eq!(node.name, "Parent");
+    assert_eq!(node.components, [std::any::type_name::<RotateComponent>()]);
+
+    let child_node = tree.find("Child").unwrap();
+    assert_eq!(child_node.id, Some(child));
+    assert!(!child_node.active);
+    assert_eq!(
+        child_node.transform.local_position,
+        Vector3::new(0.0, 1.0, 0.0)
+    );
+    assert_eq!(child_node.transform.position, Vector3::new(1.0, 3.0, 3.0));
+
+    let text = tree.to_string();
+    assert!(text.starts_with("- Parent at (1.00, 2.00, 3.00)\n"));
+    assert!(text.contains("\n  - Child at (1.00, 3.00, 3.00) (inactive)\n"));
+}
+
+#[test]
+fn render_stats_come_from_the_renderer() {
+    use syrillian::rendering::{RenderFeedback, RenderStats};
+
+    let (mut world, _rx1, _rx2, feedback_tx) = World::fresh();
+    assert_eq!(world.render_stats(), RenderStats::default());
+
+    let stats = RenderStats {
+        draw_calls: 12,
+        triangles: 400,
+        ..RenderStats::default()
+    };
+    feedback_tx.send(RenderFeedback::Stats(stats)).unwrap();
+    world.update();
+
+    assert_eq!(world.render_stats(), stats);
+}
+
+#[test]
+fn one_shot_sounds_overlap_and_clean_up() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let sound = Sound::load_sound("examples/assets/pop.wav").expect("Failed to load sound");
+    let duration = sound.duration();
+    let removed = world.assets.sounds.add(sound.clone());
+    world.assets.sounds.remove(removed);
+    let pop = world.assets.sounds.add(sound);
+
+    assert!(!world.play_sound_at(removed, Vector3::zeros()));
+    assert_eq!(world.audio.one_shot_count(), 0);
+
+    let first = world.play_sound_at(pop, Vector3::zeros());
+    let second = world.play_sound_at(pop, Vector3::new(1.0, 0.0, 0.0));
+    if !(first && second) {
+        // without an audio device nothing plays, which has to be reported
+        assert_eq!(world.audio.one_shot_count(), 0);
+        return;
+    }
+    assert_eq!(world.audio.one_shot_count(), 2);
+
+    std::thread::sleep(duration + Duration::from_millis(500));
+    world.update();
+    assert_eq!(world.audio.one_shot_count(), 0);
+}